@@ -2,12 +2,15 @@
 
 pub mod find_enrichment_table_records;
 pub mod get_enrichment_table_record;
+pub mod lookup;
 pub mod tables;
 
 #[cfg(test)]
 mod test_util;
 mod vrl_util;
 
+use std::net::IpAddr;
+
 use dyn_clone::DynClone;
 pub use tables::{TableRegistry, TableSearch};
 use vrl::compiler::Function;
@@ -16,7 +19,7 @@ use vrl::value::{ObjectMap, Value};
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct IndexHandle(pub usize);
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Condition<'a> {
     /// Condition exactly matches the field value.
     Equals { field: &'a str, value: Value },
@@ -36,6 +39,28 @@ pub enum Condition<'a> {
         field: &'a str,
         to: chrono::DateTime<chrono::Utc>,
     },
+    /// The field holds a CIDR block containing the IP address.
+    Cidr { field: &'a str, value: IpAddr },
+    /// The number is between the numbers in the `from` and `to` fields (inclusive).
+    NumericRange {
+        from: &'a str,
+        to: String,
+        value: f64,
+    },
+    /// The field holds a prefix of the value.
+    Prefix { field: &'a str, value: String },
+}
+
+/// A lookup structure a table can build to search its data with CIDR, numeric range or prefix
+/// conditions, rather than scanning all of it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LookupIndex {
+    /// Indexes the CIDR blocks of the field.
+    Cidr { field: String },
+    /// Indexes the ranges between the numbers in the `from` and `to` fields.
+    NumericRange { from: String, to: String },
+    /// Indexes the prefixes held by the field.
+    Prefix { field: String },
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -83,6 +108,22 @@ pub trait Table: DynClone {
     /// Returns a list of the field names that are in each index
     fn index_fields(&self) -> Vec<(Case, Vec<String>)>;
 
+    /// Hints to the enrichment table that the data is going to be searched with CIDR, numeric range
+    /// or prefix conditions, to allow it to build a lookup structure in advance.
+    ///
+    /// Tables that don't build lookup structures are searched sequentially.
+    ///
+    /// # Errors
+    /// Errors if the fields are not in the table, or if the table doesn't support the conditions.
+    fn add_lookup_index(&mut self, _lookup: &LookupIndex) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Returns the lookup structures that have been built.
+    fn lookup_indexes(&self) -> Vec<LookupIndex> {
+        Vec::new()
+    }
+
     /// Returns true if the underlying data has changed and the table needs reloading.
     fn needs_reload(&self) -> bool;
 }
@@ -0,0 +1,330 @@
+//! Lookup structures used to search tables with CIDR, numeric range and prefix conditions without
+//! scanning all of their rows.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::IpAddr,
+};
+
+use vrl::value::Value;
+
+/// Parses a CIDR block, like `10.0.0.0/8` or `2001:db8::/32`, into its address and prefix length.
+///
+/// A plain IP address is parsed as the block holding only that address.
+pub fn parse_cidr(cidr: &str) -> Option<(IpAddr, u8)> {
+    let (address, length) = match cidr.split_once('/') {
+        Some((address, length)) => (
+            address.trim().parse::<IpAddr>().ok()?,
+            Some(length.trim().parse::<u8>().ok()?),
+        ),
+        None => (cidr.trim().parse::<IpAddr>().ok()?, None),
+    };
+    let address = address.to_canonical();
+    let width = address_width(address);
+    let length = length.unwrap_or(width);
+    (length <= width).then_some((address, length))
+}
+
+/// Returns whether the CIDR block contains the IP address.
+pub fn cidr_contains(cidr: &str, address: IpAddr) -> bool {
+    let address = address.to_canonical();
+    parse_cidr(cidr).is_some_and(|(network, length)| {
+        network.is_ipv4() == address.is_ipv4()
+            && network_bits(network, length) == network_bits(address, length)
+    })
+}
+
+/// Returns the number held by the value, parsing it from a string if needed.
+pub fn numeric_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(number) => Some(*number as f64),
+        Value::Float(number) => Some(number.into_inner()),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).ok()?.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+const fn address_width(address: IpAddr) -> u8 {
+    match address {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// Returns the bits of the network of the given prefix length holding the address.
+fn network_bits(address: IpAddr, length: u8) -> u128 {
+    let bits = match address {
+        IpAddr::V4(address) => u128::from(u32::from(address)),
+        IpAddr::V6(address) => u128::from(address),
+    };
+    match address_width(address) - length {
+        // Shifting by the full width of the integer would overflow.
+        128 => 0,
+        host_bits => bits & (u128::MAX << host_bits),
+    }
+}
+
+/// An index of items by the CIDR blocks they belong to.
+///
+/// The blocks are grouped by prefix length, so finding the blocks containing an address takes one
+/// hash lookup per distinct prefix length rather than a scan of all the blocks.
+#[derive(Clone, Debug)]
+pub struct CidrIndex<T> {
+    /// The items by network, for each IP version and prefix length.
+    blocks: BTreeMap<(bool, u8), HashMap<u128, Vec<T>>>,
+}
+
+impl<T> Default for CidrIndex<T> {
+    fn default() -> Self {
+        Self {
+            blocks: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: PartialEq> CidrIndex<T> {
+    /// Adds the item to the given CIDR block, returning `false` if the block is invalid.
+    pub fn insert(&mut self, cidr: &str, item: T) -> bool {
+        let Some((network, length)) = parse_cidr(cidr) else {
+            return false;
+        };
+        let items = self
+            .blocks
+            .entry((network.is_ipv4(), length))
+            .or_default()
+            .entry(network_bits(network, length))
+            .or_default();
+        if !items.contains(&item) {
+            items.push(item);
+        }
+        true
+    }
+
+    /// Removes the item from the given CIDR block.
+    pub fn remove(&mut self, cidr: &str, item: &T) {
+        let Some((network, length)) = parse_cidr(cidr) else {
+            return;
+        };
+        let key = (network.is_ipv4(), length);
+        let Some(networks) = self.blocks.get_mut(&key) else {
+            return;
+        };
+        let bits = network_bits(network, length);
+        if let Some(items) = networks.get_mut(&bits) {
+            items.retain(|existing| existing != item);
+            if items.is_empty() {
+                networks.remove(&bits);
+            }
+        }
+        if networks.is_empty() {
+            self.blocks.remove(&key);
+        }
+    }
+
+    /// Returns the items of the blocks containing the address, most specific blocks first.
+    pub fn find(&self, address: IpAddr) -> impl Iterator<Item = &T> {
+        let address = address.to_canonical();
+        self.blocks
+            .iter()
+            .rev()
+            .filter(move |((ipv4, _), _)| *ipv4 == address.is_ipv4())
+            .filter_map(move |((_, length), networks)| {
+                networks.get(&network_bits(address, *length))
+            })
+            .flatten()
+    }
+}
+
+/// An index of items by the numeric ranges they cover.
+///
+/// The ranges are sorted by their start, along with the greatest end of the ranges up to each of
+/// them, so finding the ranges containing a number is a binary search followed by a walk over the
+/// ranges overlapping it.
+#[derive(Clone, Debug)]
+pub struct RangeIndex<T> {
+    ranges: Vec<(f64, f64, T)>,
+    max_ends: Vec<f64>,
+}
+
+impl<T> RangeIndex<T> {
+    /// Creates an index of the given inclusive ranges, ignoring the empty ones.
+    pub fn new(ranges: impl IntoIterator<Item = (f64, f64, T)>) -> Self {
+        let mut ranges = ranges
+            .into_iter()
+            .filter(|(from, to, _)| from <= to)
+            .collect::<Vec<_>>();
+        ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let max_ends = ranges
+            .iter()
+            .scan(f64::NEG_INFINITY, |max_end, (_, to, _)| {
+                *max_end = max_end.max(*to);
+                Some(*max_end)
+            })
+            .collect();
+        Self { ranges, max_ends }
+    }
+
+    /// Returns the items of the ranges containing the number, by increasing start.
+    pub fn find(&self, value: f64) -> impl Iterator<Item = &T> {
+        let end = self.ranges.partition_point(|(from, _, _)| *from <= value);
+        // The greatest ends never decrease, so the ranges before `start` all end before the number.
+        let start = self.max_ends[..end].partition_point(|max_end| *max_end < value);
+        self.ranges[start..end]
+            .iter()
+            .filter(move |(_, to, _)| value <= *to)
+            .map(|(_, _, item)| item)
+    }
+}
+
+/// An index of items by prefix.
+///
+/// The prefixes are grouped by length, so finding the prefixes of a string takes one hash lookup
+/// per distinct prefix length rather than a scan of all the prefixes.
+#[derive(Clone, Debug)]
+pub struct PrefixIndex<T> {
+    prefixes: HashMap<String, Vec<T>>,
+    /// The number of prefixes of each length.
+    lengths: BTreeMap<usize, usize>,
+}
+
+impl<T> Default for PrefixIndex<T> {
+    fn default() -> Self {
+        Self {
+            prefixes: HashMap::new(),
+            lengths: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T: PartialEq> PrefixIndex<T> {
+    /// Adds the item to the given prefix.
+    pub fn insert(&mut self, prefix: &str, item: T) {
+        let items = self.prefixes.entry(prefix.to_owned()).or_insert_with(|| {
+            *self.lengths.entry(prefix.len()).or_default() += 1;
+            Vec::new()
+        });
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+
+    /// Removes the item from the given prefix.
+    pub fn remove(&mut self, prefix: &str, item: &T) {
+        let Some(items) = self.prefixes.get_mut(prefix) else {
+            return;
+        };
+        items.retain(|existing| existing != item);
+        if items.is_empty() {
+            self.prefixes.remove(prefix);
+            if let Some(count) = self.lengths.get_mut(&prefix.len()) {
+                *count -= 1;
+                if *count == 0 {
+                    self.lengths.remove(&prefix.len());
+                }
+            }
+        }
+    }
+
+    /// Returns the items of the prefixes of the value, longest prefixes first.
+    pub fn find<'a>(&'a self, value: &'a str) -> impl Iterator<Item = &'a T> {
+        self.lengths
+            .keys()
+            .rev()
+            .filter(move |length| value.is_char_boundary(**length))
+            .filter_map(move |length| self.prefixes.get(&value[..*length]))
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cidr_blocks() {
+        assert_eq!(
+            parse_cidr("10.0.0.0/8"),
+            Some(("10.0.0.0".parse().unwrap(), 8))
+        );
+        assert_eq!(
+            parse_cidr("2001:db8::/32"),
+            Some(("2001:db8::".parse().unwrap(), 32))
+        );
+        assert_eq!(
+            parse_cidr("192.168.1.1"),
+            Some(("192.168.1.1".parse().unwrap(), 32))
+        );
+        assert_eq!(parse_cidr("10.0.0.0/33"), None);
+        assert_eq!(parse_cidr("not a block"), None);
+    }
+
+    #[test]
+    fn checks_cidr_containment() {
+        let address = "10.1.2.3".parse().unwrap();
+        assert!(cidr_contains("10.0.0.0/8", address));
+        assert!(cidr_contains("0.0.0.0/0", address));
+        assert!(cidr_contains("::ffff:10.1.2.3", address));
+        assert!(!cidr_contains("10.2.0.0/16", address));
+        assert!(!cidr_contains("::/0", address));
+    }
+
+    #[test]
+    fn finds_cidr_blocks() {
+        let mut index = CidrIndex::default();
+        assert!(index.insert("10.0.0.0/8", 0));
+        assert!(index.insert("10.1.0.0/16", 1));
+        assert!(index.insert("10.2.0.0/16", 2));
+        assert!(index.insert("2001:db8::/32", 3));
+        assert!(!index.insert("invalid", 4));
+
+        let find = |index: &CidrIndex<i32>, address: &str| {
+            index
+                .find(address.parse().unwrap())
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(find(&index, "10.1.2.3"), [1, 0]);
+        assert_eq!(find(&index, "10.3.2.1"), [0]);
+        assert_eq!(find(&index, "2001:db8::1"), [3]);
+        assert!(find(&index, "192.168.0.1").is_empty());
+
+        index.remove("10.1.0.0/16", &1);
+        assert_eq!(find(&index, "10.1.2.3"), [0]);
+    }
+
+    #[test]
+    fn finds_numeric_ranges() {
+        let index = RangeIndex::new([
+            (100.0, 200.0, "a"),
+            (150.0, 160.0, "b"),
+            (0.0, 1000.0, "c"),
+            (300.0, 400.0, "d"),
+            (500.0, 400.0, "empty"),
+        ]);
+
+        let find = |value| index.find(value).copied().collect::<Vec<_>>();
+        assert_eq!(find(155.0), ["c", "a", "b"]);
+        assert_eq!(find(200.0), ["c", "a"]);
+        assert_eq!(find(350.0), ["c", "d"]);
+        assert_eq!(find(450.0), ["c"]);
+        assert!(find(-1.0).is_empty());
+    }
+
+    #[test]
+    fn finds_prefixes() {
+        let mut index = PrefixIndex::default();
+        index.insert("+1", "us");
+        index.insert("+44", "uk");
+        index.insert("+4420", "london");
+        index.insert("", "any");
+
+        let find =
+            |index: &PrefixIndex<&str>, value| index.find(value).copied().collect::<Vec<_>>();
+        assert_eq!(find(&index, "+442071234567"), ["london", "uk", "any"]);
+        assert_eq!(find(&index, "+15551234567"), ["us", "any"]);
+
+        index.remove("+4420", &"london");
+        index.remove("", &"any");
+        assert_eq!(find(&index, "+442071234567"), ["uk"]);
+    }
+}
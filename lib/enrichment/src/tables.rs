@@ -37,7 +37,7 @@ use std::{
 use arc_swap::ArcSwap;
 use vrl::value::{ObjectMap, Value};
 
-use super::{Condition, IndexHandle, Table};
+use super::{Condition, IndexHandle, LookupIndex, Table};
 use crate::Case;
 
 /// A hashmap of name => implementation of an enrichment table.
@@ -163,6 +163,25 @@ impl TableRegistry {
         }
     }
 
+    /// Adds a lookup structure to the given Enrichment Table.
+    ///
+    /// If we are in the reading stage, this function will error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Mutex is poisoned.
+    pub fn add_lookup_index(&mut self, table: &str, lookup: &LookupIndex) -> Result<(), String> {
+        let mut locked = self.loading.lock().unwrap();
+
+        match *locked {
+            None => Err("finish_load has been called".to_string()),
+            Some(ref mut tables) => match tables.get_mut(table) {
+                None => Err(format!("table '{table}' not loaded")),
+                Some(table) => table.add_lookup_index(lookup),
+            },
+        }
+    }
+
     /// Returns a cheaply clonable struct through that provides lock free read
     /// access to the enrichment tables.
     pub fn as_readonly(&self) -> TableSearch {
@@ -181,6 +200,18 @@ impl TableRegistry {
         }
     }
 
+    /// Returns the lookup structures that have been added to the given table.
+    /// If the table is reloaded we need these to reapply them to the new reloaded tables.
+    pub fn lookup_indexes(&self, table: &str) -> Vec<LookupIndex> {
+        match &**self.tables.load() {
+            Some(tables) => tables
+                .get(table)
+                .map(|table| table.lookup_indexes())
+                .unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+
     /// Checks if the table needs reloading.
     /// If in doubt (the table isn't in our list) we return true.
     pub fn needs_reload(&self, table: &str) -> bool {
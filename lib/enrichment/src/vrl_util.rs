@@ -1,7 +1,7 @@
 //! Utilities shared between both VRL functions.
 use std::collections::BTreeMap;
 
-use crate::{Case, Condition, IndexHandle, TableRegistry};
+use crate::{Case, Condition, IndexHandle, LookupIndex, TableRegistry};
 use vrl::diagnostic::{Label, Span};
 use vrl::prelude::*;
 
@@ -40,6 +40,37 @@ impl DiagnosticMessage for Error {
 /// Evaluates the condition object to search the enrichment tables with.
 pub(crate) fn evaluate_condition(key: &str, value: Value) -> ExpressionResult<Condition> {
     Ok(match value {
+        Value::Object(map) if map.contains_key("contains_ip") => Condition::Cidr {
+            field: key,
+            value: map
+                .get("contains_ip")
+                .expect("should contain contains_ip")
+                .as_str()
+                .and_then(|address| address.trim().parse().ok())
+                .ok_or("contains_ip in condition must be an IP address")?,
+        },
+        Value::Object(map) if map.contains_key("contains") => Condition::NumericRange {
+            from: key,
+            to: map
+                .get("to_field")
+                .and_then(Value::as_str)
+                .ok_or("to_field in condition must be a string")?
+                .into_owned(),
+            value: match map.get("contains").expect("should contain contains") {
+                Value::Integer(number) => *number as f64,
+                Value::Float(number) => number.into_inner(),
+                _ => return Err("contains in condition must be a number".into()),
+            },
+        },
+        Value::Object(map) if map.contains_key("prefix_of") => Condition::Prefix {
+            field: key,
+            value: map
+                .get("prefix_of")
+                .expect("should contain prefix_of")
+                .as_str()
+                .ok_or("prefix_of in condition must be a string")?
+                .into_owned(),
+        },
         Value::Object(map) if map.contains_key("from") && map.contains_key("to") => {
             Condition::BetweenDates {
                 field: key,
@@ -82,20 +113,44 @@ pub(crate) fn add_index(
     case: Case,
     condition: &BTreeMap<KeyString, expression::Expr>,
 ) -> std::result::Result<IndexHandle, ExpressionError> {
-    let fields = condition
-        .iter()
-        .filter_map(|(field, value)| match value {
+    let mut fields = Vec::new();
+    for (field, value) in condition {
+        match value {
             expression::Expr::Container(expression::Container {
                 variant: expression::Variant::Object(map),
-            }) if (map.contains_key("from") && map.contains_key("to"))
-                || map.contains_key("from")
-                || map.contains_key("to") =>
-            {
-                None
+            }) => {
+                let lookup = if map.contains_key("contains_ip") {
+                    Some(LookupIndex::Cidr {
+                        field: field.to_string(),
+                    })
+                } else if map.contains_key("prefix_of") {
+                    Some(LookupIndex::Prefix {
+                        field: field.to_string(),
+                    })
+                } else if map.contains_key("contains") {
+                    // The range can only be indexed when its end field is known upfront.
+                    match map.get("to_field") {
+                        Some(expression::Expr::Literal(expression::Literal::String(to))) => {
+                            Some(LookupIndex::NumericRange {
+                                from: field.to_string(),
+                                to: String::from_utf8_lossy(to).into_owned(),
+                            })
+                        }
+                        _ => None,
+                    }
+                } else if map.contains_key("from") || map.contains_key("to") {
+                    None
+                } else {
+                    fields.push(field.as_ref());
+                    None
+                };
+                if let Some(lookup) = lookup {
+                    registry.add_lookup_index(tablename, &lookup)?;
+                }
             }
-            _ => Some(field.as_ref()),
-        })
-        .collect::<Vec<_>>();
+            _ => fields.push(field.as_ref()),
+        }
+    }
     let index = registry.add_index(tablename, case, &fields)?;
 
     Ok(index)
@@ -127,6 +182,7 @@ mod tests {
     use std::sync::{Arc, Mutex};
 
     use chrono::{TimeZone, Utc};
+    use vrl::value;
 
     use super::*;
     use crate::test_util;
@@ -188,4 +244,64 @@ mod tests {
         let indexes = indexes.lock().unwrap();
         assert_eq!(vec![vec!["field1".to_string()]], *indexes);
     }
+
+    #[test]
+    fn add_indexes_with_lookups() {
+        let indexes = Arc::new(Mutex::new(Vec::new()));
+        let dummy = test_util::DummyEnrichmentTable::new_with_index(indexes.clone());
+
+        let mut registry =
+            test_util::get_table_registry_with_tables(vec![("dummy1".to_string(), dummy)]);
+
+        let conditions = BTreeMap::from([
+            ("field1".into(), (expression::Literal::from("value")).into()),
+            (
+                "subnet".into(),
+                (expression::Container::new(expression::Variant::Object(
+                    BTreeMap::from([(
+                        "contains_ip".into(),
+                        (expression::Literal::from("10.1.2.3")).into(),
+                    )])
+                    .into(),
+                )))
+                .into(),
+            ),
+        ]);
+
+        let index = add_index(&mut registry, "dummy1", Case::Sensitive, &conditions).unwrap();
+
+        assert_eq!(IndexHandle(0), index);
+
+        // Ensure only the exact match has been added as an index.
+        let indexes = indexes.lock().unwrap();
+        assert_eq!(vec![vec!["field1".to_string()]], *indexes);
+    }
+
+    #[test]
+    fn evaluate_lookup_conditions() {
+        assert_eq!(
+            evaluate_condition("subnet", value!({"contains_ip": "10.1.2.3"})).unwrap(),
+            Condition::Cidr {
+                field: "subnet",
+                value: "10.1.2.3".parse().unwrap(),
+            }
+        );
+        assert_eq!(
+            evaluate_condition("start", value!({"to_field": "end", "contains": 443})).unwrap(),
+            Condition::NumericRange {
+                from: "start",
+                to: "end".to_string(),
+                value: 443.0,
+            }
+        );
+        assert_eq!(
+            evaluate_condition("prefix", value!({"prefix_of": "+4420"})).unwrap(),
+            Condition::Prefix {
+                field: "prefix",
+                value: "+4420".to_string(),
+            }
+        );
+        assert!(evaluate_condition("subnet", value!({"contains_ip": "nope"})).is_err());
+        assert!(evaluate_condition("start", value!({"contains": 443})).is_err());
+    }
 }
@@ -3,7 +3,14 @@
 //! as it flows through transforms, being duplicated and merged, and
 //! then report its status when the last copy is delivered or dropped.
 
-use std::{cmp, future::Future, mem, pin::Pin, sync::Arc, task::Poll};
+use std::{
+    cmp,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::Poll,
+};
 
 use crossbeam_utils::atomic::AtomicCell;
 use futures::future::FutureExt;
@@ -86,6 +93,16 @@ impl EventFinalizers {
         }
     }
 
+    /// Records the details of a rejection on the batches of all event finalizers in the collection.
+    ///
+    /// This does not update the status of the event finalizers, and only batches created with
+    /// [`BatchNotifier::new_with_rejection_receiver`] keep the details.
+    pub fn record_rejection(&self, rejection: &Rejection) {
+        for finalizer in &self.0 {
+            finalizer.record_rejection(rejection);
+        }
+    }
+
     /// Consumes all event finalizers and updates their underlying batches immediately.
     pub fn update_sources(&mut self) {
         let finalizers = mem::take(&mut self.0);
@@ -139,6 +156,11 @@ impl EventFinalizer {
             .unwrap_or_else(|_| unreachable!());
     }
 
+    /// Records the details of a rejection on the underlying batch.
+    pub fn record_rejection(&self, rejection: &Rejection) {
+        self.batch.record_rejection(rejection);
+    }
+
     /// Updates the underlying batch status with the status of the event finalizer.
     ///
     /// In doing so, the event finalizer is marked as "recorded", which prevents any further updates to it.
@@ -188,6 +210,50 @@ impl BatchStatusReceiver {
     }
 }
 
+/// The details of the permanent failure to deliver events.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Rejection {
+    /// A description of the failure.
+    pub message: String,
+    /// The status returned by the destination, such as an HTTP status code.
+    pub status: Option<String>,
+    /// The response returned by the destination.
+    pub response: Option<String>,
+}
+
+impl Rejection {
+    /// Creates a new `Rejection` with the given message.
+    #[must_use]
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            status: None,
+            response: None,
+        }
+    }
+}
+
+/// A receiver for the finalization status of a batch, along with the details of its rejection.
+pub struct RejectionReceiver {
+    status: BatchStatusReceiver,
+    rejection: Arc<Mutex<Option<Rejection>>>,
+}
+
+impl RejectionReceiver {
+    /// Waits for the finalization status of the batch.
+    ///
+    /// The details of the first rejection recorded on the batch are returned along with it.
+    pub async fn recv(self) -> (BatchStatus, Option<Rejection>) {
+        let status = self.status.await;
+        let rejection = self
+            .rejection
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take();
+        (status, rejection)
+    }
+}
+
 /// A batch notifier contains the status of the current batch along with
 /// a one-shot notifier to send that status back to the source. It is
 /// shared among all events of a batch.
@@ -202,10 +268,29 @@ impl BatchNotifier {
         let notifier = OwnedBatchNotifier {
             status: AtomicCell::new(BatchStatus::Delivered),
             notifier: Some(sender),
+            rejection: None,
         };
         (Self(Arc::new(notifier)), BatchStatusReceiver(receiver))
     }
 
+    /// Creates a new `BatchNotifier` which keeps the details of its first rejection, along with
+    /// the receiver used to await its finalization status and these details.
+    #[must_use]
+    pub fn new_with_rejection_receiver() -> (Self, RejectionReceiver) {
+        let (sender, receiver) = oneshot::channel();
+        let rejection = Arc::new(Mutex::new(None));
+        let notifier = OwnedBatchNotifier {
+            status: AtomicCell::new(BatchStatus::Delivered),
+            notifier: Some(sender),
+            rejection: Some(Arc::clone(&rejection)),
+        };
+        let receiver = RejectionReceiver {
+            status: BatchStatusReceiver(receiver),
+            rejection,
+        };
+        (Self(Arc::new(notifier)), receiver)
+    }
+
     /// Optionally creates a new `BatchNotifier` along with the receiver used to await its finalization status.
     #[must_use]
     pub fn maybe_new_with_receiver(enabled: bool) -> (Option<Self>, Option<BatchStatusReceiver>) {
@@ -250,6 +335,18 @@ impl BatchNotifier {
                 .unwrap_or_else(|_| unreachable!());
         }
     }
+
+    /// Records the details of a rejection, unless one was already recorded.
+    fn record_rejection(&self, rejection: &Rejection) {
+        if let Some(slot) = &self.0.rejection {
+            let mut slot = slot
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if slot.is_none() {
+                *slot = Some(rejection.clone());
+            }
+        }
+    }
 }
 
 /// The non-shared data underlying the shared `BatchNotifier`
@@ -257,6 +354,7 @@ impl BatchNotifier {
 pub struct OwnedBatchNotifier {
     status: AtomicCell<BatchStatus>,
     notifier: Option<oneshot::Sender<BatchStatus>>,
+    rejection: Option<Arc<Mutex<Option<Rejection>>>>,
 }
 
 impl OwnedBatchNotifier {
@@ -507,6 +605,25 @@ mod tests {
         assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
     }
 
+    #[test]
+    fn records_first_rejection() {
+        let (batch, receiver) = BatchNotifier::new_with_rejection_receiver();
+        let fin = EventFinalizers::new(EventFinalizer::new(batch));
+        fin.update_status(EventStatus::Rejected);
+        fin.record_rejection(&Rejection {
+            status: Some("400".into()),
+            ..Rejection::new("Bad request.")
+        });
+        fin.record_rejection(&Rejection::new("Ignored."));
+        drop(fin);
+
+        let (status, rejection) = receiver.recv().now_or_never().unwrap();
+        assert_eq!(status, BatchStatus::Rejected);
+        let rejection = rejection.unwrap();
+        assert_eq!(rejection.message, "Bad request.");
+        assert_eq!(rejection.status.as_deref(), Some("400"));
+    }
+
     fn make_finalizer() -> (EventFinalizers, BatchStatusReceiver) {
         let (batch, receiver) = BatchNotifier::new_with_receiver();
         let finalizer = EventFinalizers::new(EventFinalizer::new(batch));
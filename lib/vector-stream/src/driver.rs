@@ -10,7 +10,7 @@ use vector_common::internal_event::{
     RegisteredEventCache, SharedString, TaggedEventsSent,
 };
 use vector_common::request_metadata::{GroupedCountByteSize, MetaDescriptive};
-use vector_core::event::{EventFinalizers, EventStatus, Finalizable};
+use vector_core::event::{EventFinalizers, EventStatus, Finalizable, Rejection};
 
 use super::FuturesUnorderedCount;
 
@@ -24,6 +24,12 @@ pub trait DriverResponse {
     fn bytes_sent(&self) -> Option<usize> {
         None
     }
+
+    /// Return the details of the failure when the response has the `Rejected` status, such as the
+    /// status and body returned by the destination.
+    fn rejection(&self) -> Option<Rejection> {
+        None
+    }
 }
 
 /// Drives the interaction between a stream of items and a service which processes them
@@ -205,8 +211,9 @@ where
     ) {
         match result {
             Err(error) => {
-                Self::emit_call_error(Some(error), request_id, event_count);
                 finalizers.update_status(EventStatus::Rejected);
+                finalizers.record_rejection(&Rejection::new(format!("{error:?}")));
+                Self::emit_call_error(Some(error), request_id, event_count);
             }
             Ok(response) => {
                 trace!(message = "Service call succeeded.", request_id);
@@ -224,6 +231,9 @@ where
                 } else if response.event_status() == EventStatus::Rejected {
                     Self::emit_call_error(None, request_id, event_count);
                     finalizers.update_status(EventStatus::Rejected);
+                    finalizers.record_rejection(&response.rejection().unwrap_or_else(|| {
+                        Rejection::new("Request was rejected by the destination.")
+                    }));
                 }
             }
         }
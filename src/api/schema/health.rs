@@ -1,8 +1,12 @@
-use async_graphql::{Object, SimpleObject, Subscription};
+use std::collections::BTreeMap;
+
+use async_graphql::{Enum, Object, SimpleObject, Subscription};
 use chrono::{DateTime, Utc};
 use tokio::time::Duration;
 use tokio_stream::{wrappers::IntervalStream, Stream, StreamExt};
 
+use crate::{event::MetricValue, internal_events, metrics::Controller};
+
 #[derive(SimpleObject)]
 pub struct Heartbeat {
     utc: DateTime<Utc>,
@@ -14,6 +18,32 @@ impl Heartbeat {
     }
 }
 
+#[derive(Debug, Enum, Eq, PartialEq, Copy, Clone, Ord, PartialOrd)]
+pub enum CircuitBreakerState {
+    Closed,
+    HalfOpen,
+    Open,
+}
+
+impl From<internal_events::CircuitBreakerState> for CircuitBreakerState {
+    fn from(state: internal_events::CircuitBreakerState) -> Self {
+        match state {
+            internal_events::CircuitBreakerState::Closed => Self::Closed,
+            internal_events::CircuitBreakerState::HalfOpen => Self::HalfOpen,
+            internal_events::CircuitBreakerState::Open => Self::Open,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct CircuitBreaker {
+    /// Sink component_id
+    component_id: String,
+
+    /// State of the circuit breaker of the sink's requests
+    state: CircuitBreakerState,
+}
+
 #[derive(Default)]
 pub(super) struct HealthQuery;
 
@@ -23,6 +53,40 @@ impl HealthQuery {
     async fn health(&self) -> bool {
         true
     }
+
+    /// Returns the state of the circuit breakers of the sinks. Sinks with several circuit breakers
+    /// report the least healthy one.
+    async fn circuit_breakers(&self) -> Vec<CircuitBreaker> {
+        let Ok(controller) = Controller::get() else {
+            return Vec::new();
+        };
+
+        let mut states = BTreeMap::new();
+        for metric in controller.capture_metrics() {
+            if metric.name() != "circuit_breaker_state" {
+                continue;
+            }
+            if let (Some(component_id), MetricValue::Gauge { value }) =
+                (metric.tag_value("component_id"), metric.value())
+            {
+                let state = internal_events::CircuitBreakerState::from_gauge(*value).into();
+                states
+                    .entry(component_id)
+                    .and_modify(|current: &mut CircuitBreakerState| {
+                        *current = (*current).max(state)
+                    })
+                    .or_insert(state);
+            }
+        }
+
+        states
+            .into_iter()
+            .map(|(component_id, state)| CircuitBreaker {
+                component_id,
+                state,
+            })
+            .collect()
+    }
 }
 
 #[derive(Default)]
@@ -3,7 +3,7 @@ use std::{collections::HashMap, fmt, net::SocketAddr};
 
 use bytes::Bytes;
 use headers::{authorization::Credentials, Authorization};
-use http::{header::AUTHORIZATION, HeaderMap, HeaderValue, StatusCode};
+use http::{header::AUTHORIZATION, HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
 use serde::{
     de::{Error, MapAccess, Visitor},
     Deserialize,
@@ -24,6 +24,11 @@ use vrl::{
 };
 
 use super::ErrorMessage;
+use crate::http::{hmac_signature, signature_timestamp, HmacAlgorithm};
+
+/// The maximum difference, in seconds, between the timestamp of a signed request and the time it
+/// is received at.
+const HMAC_MAX_CLOCK_SKEW_SECS: u64 = 300;
 
 /// Configuration of the authentication strategy for server mode sinks and sources.
 ///
@@ -58,6 +63,31 @@ pub enum HttpServerAuthConfig {
         /// The VRL boolean expression.
         source: String,
     },
+
+    /// HMAC request signature verification.
+    ///
+    /// The signature of each request is computed with the secret, over its timestamp, method, path
+    /// with the query string, if any, and body, each followed by a newline except for the body, and
+    /// compared to the hex-encoded signature of the request. Requests signed more than five minutes
+    /// away from the current time are rejected.
+    Hmac {
+        /// The secret requests are signed with.
+        #[configurable(metadata(docs::examples = "${SIGNING_SECRET}"))]
+        #[configurable(metadata(docs::examples = "SECRET[vault.signing_secret]"))]
+        secret: SensitiveString,
+
+        #[configurable(derived)]
+        #[serde(default)]
+        algorithm: HmacAlgorithm,
+
+        /// The header holding the signature.
+        #[serde(default = "crate::http::default_signature_header")]
+        signature_header: String,
+
+        /// The header holding the timestamp the request was signed at, in Unix seconds.
+        #[serde(default = "crate::http::default_timestamp_header")]
+        timestamp_header: String,
+    },
 }
 
 // Custom deserializer implementation to default `strategy` to `basic`
@@ -68,13 +98,22 @@ impl<'de> Deserialize<'de> for HttpServerAuthConfig {
     {
         struct HttpServerAuthConfigVisitor;
 
-        const FIELD_KEYS: [&str; 4] = ["strategy", "username", "password", "source"];
+        const FIELD_KEYS: [&str; 8] = [
+            "strategy",
+            "username",
+            "password",
+            "source",
+            "secret",
+            "algorithm",
+            "signature_header",
+            "timestamp_header",
+        ];
 
         impl<'de> Visitor<'de> for HttpServerAuthConfigVisitor {
             type Value = HttpServerAuthConfig;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("a valid authentication strategy (basic or custom)")
+                formatter.write_str("a valid authentication strategy (basic, custom or hmac)")
             }
 
             fn visit_map<A>(self, mut map: A) -> Result<HttpServerAuthConfig, A::Error>
@@ -119,7 +158,35 @@ impl<'de> Deserialize<'de> for HttpServerAuthConfig {
                             .ok_or_else(|| Error::missing_field("source"))?;
                         Ok(HttpServerAuthConfig::Custom { source })
                     }
-                    _ => Err(Error::unknown_variant(strategy, &["basic", "custom"])),
+                    "hmac" => {
+                        let secret = fields
+                            .remove("secret")
+                            .ok_or_else(|| Error::missing_field("secret"))?;
+                        let algorithm = match fields.remove("algorithm").as_deref() {
+                            None | Some("sha256") => HmacAlgorithm::Sha256,
+                            Some("sha512") => HmacAlgorithm::Sha512,
+                            Some(algorithm) => {
+                                return Err(Error::unknown_variant(
+                                    algorithm,
+                                    &["sha256", "sha512"],
+                                ))
+                            }
+                        };
+                        Ok(HttpServerAuthConfig::Hmac {
+                            secret: SensitiveString::from(secret),
+                            algorithm,
+                            signature_header: fields
+                                .remove("signature_header")
+                                .unwrap_or_else(crate::http::default_signature_header),
+                            timestamp_header: fields
+                                .remove("timestamp_header")
+                                .unwrap_or_else(crate::http::default_timestamp_header),
+                        })
+                    }
+                    _ => Err(Error::unknown_variant(
+                        strategy,
+                        &["basic", "custom", "hmac"],
+                    )),
                 }
             }
         }
@@ -175,6 +242,17 @@ impl HttpServerAuthConfig {
 
                 Ok(HttpServerAuthMatcher::Vrl { program })
             }
+            HttpServerAuthConfig::Hmac {
+                secret,
+                algorithm,
+                signature_header,
+                timestamp_header,
+            } => Ok(HttpServerAuthMatcher::Hmac {
+                secret: secret.clone(),
+                algorithm: *algorithm,
+                signature_header: HeaderName::try_from(signature_header.as_str())?,
+                timestamp_header: HeaderName::try_from(timestamp_header.as_str())?,
+            }),
         }
     }
 }
@@ -191,15 +269,29 @@ pub enum HttpServerAuthMatcher {
         /// Compiled VRL script
         program: Program,
     },
+    /// Matcher for verifying the HMAC signature of requests
+    Hmac {
+        /// Secret requests are signed with
+        secret: SensitiveString,
+        /// Hash function of the signature
+        algorithm: HmacAlgorithm,
+        /// Header holding the signature
+        signature_header: HeaderName,
+        /// Header holding the timestamp the request was signed at
+        timestamp_header: HeaderName,
+    },
 }
 
 impl HttpServerAuthMatcher {
-    /// Compares passed headers to the matcher
+    /// Compares passed request to the matcher
     pub fn handle_auth(
         &self,
         address: Option<&SocketAddr>,
+        method: &Method,
         headers: &HeaderMap<HeaderValue>,
         path: &str,
+        query: Option<&str>,
+        body: &[u8],
     ) -> Result<(), ErrorMessage> {
         match self {
             HttpServerAuthMatcher::AuthHeader(expected, err_message) => {
@@ -222,6 +314,53 @@ impl HttpServerAuthMatcher {
             HttpServerAuthMatcher::Vrl { program } => {
                 self.handle_vrl_auth(address, headers, path, program)
             }
+            HttpServerAuthMatcher::Hmac {
+                secret,
+                algorithm,
+                signature_header,
+                timestamp_header,
+            } => {
+                let unauthorized =
+                    |message: &str| ErrorMessage::new(StatusCode::UNAUTHORIZED, message.to_owned());
+                let (Some(signature), Some(timestamp)) =
+                    (headers.get(signature_header), headers.get(timestamp_header))
+                else {
+                    return Err(unauthorized("No signature header"));
+                };
+                let timestamp = timestamp
+                    .to_str()
+                    .map_err(|_| unauthorized("Invalid signature timestamp"))?;
+                let signed_at = timestamp
+                    .parse::<u64>()
+                    .map_err(|_| unauthorized("Invalid signature timestamp"))?;
+                if signed_at.abs_diff(signature_timestamp()) > HMAC_MAX_CLOCK_SKEW_SECS {
+                    return Err(unauthorized(
+                        "Signature timestamp is too far from current time",
+                    ));
+                }
+
+                let expected = hmac_signature(
+                    *algorithm,
+                    secret.inner().as_bytes(),
+                    timestamp,
+                    method.as_str(),
+                    path,
+                    query,
+                    body,
+                )
+                .map_err(|error| {
+                    warn!("Computing signature failed: {}", error);
+                    unauthorized("Auth failed")
+                })?;
+                let signature = signature.as_bytes().to_ascii_lowercase();
+                if signature.len() == expected.len()
+                    && openssl::memcmp::eq(&signature, expected.as_bytes())
+                {
+                    Ok(())
+                } else {
+                    Err(unauthorized("Invalid signature"))
+                }
+            }
         }
     }
 
@@ -298,7 +437,7 @@ mod tests {
                 HttpServerAuthMatcher::AuthHeader(header_value, error_message) => {
                     (header_value, error_message)
                 }
-                HttpServerAuthMatcher::Vrl { .. } => {
+                HttpServerAuthMatcher::Vrl { .. } | HttpServerAuthMatcher::Hmac { .. } => {
                     panic!("Expected HttpServerAuthMatcher::AuthHeader")
                 }
             }
@@ -443,7 +582,14 @@ mod tests {
 
         let matcher = basic_auth.build(&Default::default()).unwrap();
 
-        let result = matcher.handle_auth(Some(&next_addr()), &HeaderMap::new(), "/");
+        let result = matcher.handle_auth(
+            Some(&next_addr()),
+            &Method::POST,
+            &HeaderMap::new(),
+            "/",
+            None,
+            &[],
+        );
 
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -462,7 +608,8 @@ mod tests {
 
         let mut headers = HeaderMap::new();
         headers.insert(AUTHORIZATION, HeaderValue::from_static("Basic wrong"));
-        let result = matcher.handle_auth(Some(&next_addr()), &headers, "/");
+        let result =
+            matcher.handle_auth(Some(&next_addr()), &Method::POST, &headers, "/", None, &[]);
 
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -486,7 +633,8 @@ mod tests {
             AUTHORIZATION,
             Authorization::basic(&username, &password).0.encode(),
         );
-        let result = matcher.handle_auth(Some(&next_addr()), &headers, "/");
+        let result =
+            matcher.handle_auth(Some(&next_addr()), &Method::POST, &headers, "/", None, &[]);
 
         assert!(result.is_ok());
     }
@@ -501,7 +649,8 @@ mod tests {
 
         let mut headers = HeaderMap::new();
         headers.insert(AUTHORIZATION, HeaderValue::from_static("test"));
-        let result = matcher.handle_auth(Some(&next_addr()), &headers, "/");
+        let result =
+            matcher.handle_auth(Some(&next_addr()), &Method::POST, &headers, "/", None, &[]);
 
         assert!(result.is_ok());
     }
@@ -517,7 +666,8 @@ mod tests {
         let matcher = custom_auth.build(&Default::default()).unwrap();
 
         let headers = HeaderMap::new();
-        let result = matcher.handle_auth(Some(&next_addr()), &headers, "/");
+        let result =
+            matcher.handle_auth(Some(&next_addr()), &Method::POST, &headers, "/", None, &[]);
 
         assert!(result.is_ok());
     }
@@ -533,7 +683,7 @@ mod tests {
         let matcher = custom_auth.build(&Default::default()).unwrap();
 
         let headers = HeaderMap::new();
-        let result = matcher.handle_auth(None, &headers, "/");
+        let result = matcher.handle_auth(None, &Method::POST, &headers, "/", None, &[]);
 
         assert!(result.is_err());
     }
@@ -547,7 +697,14 @@ mod tests {
         let matcher = custom_auth.build(&Default::default()).unwrap();
 
         let headers = HeaderMap::new();
-        let result = matcher.handle_auth(Some(&next_addr()), &headers, "/ok");
+        let result = matcher.handle_auth(
+            Some(&next_addr()),
+            &Method::POST,
+            &headers,
+            "/ok",
+            None,
+            &[],
+        );
 
         assert!(result.is_ok());
     }
@@ -561,7 +718,14 @@ mod tests {
         let matcher = custom_auth.build(&Default::default()).unwrap();
 
         let headers = HeaderMap::new();
-        let result = matcher.handle_auth(Some(&next_addr()), &headers, "/bad");
+        let result = matcher.handle_auth(
+            Some(&next_addr()),
+            &Method::POST,
+            &headers,
+            "/bad",
+            None,
+            &[],
+        );
 
         assert!(result.is_err());
     }
@@ -576,7 +740,8 @@ mod tests {
 
         let mut headers = HeaderMap::new();
         headers.insert(AUTHORIZATION, HeaderValue::from_static("wrong value"));
-        let result = matcher.handle_auth(Some(&next_addr()), &headers, "/");
+        let result =
+            matcher.handle_auth(Some(&next_addr()), &Method::POST, &headers, "/", None, &[]);
 
         assert!(result.is_err());
         let error = result.unwrap_err();
@@ -594,11 +759,92 @@ mod tests {
 
         let mut headers = HeaderMap::new();
         headers.insert(AUTHORIZATION, HeaderValue::from_static("test"));
-        let result = matcher.handle_auth(Some(&next_addr()), &headers, "/");
+        let result =
+            matcher.handle_auth(Some(&next_addr()), &Method::POST, &headers, "/", None, &[]);
 
         assert!(result.is_err());
         let error = result.unwrap_err();
         assert_eq!(401, error.code());
         assert_eq!("Auth failed", error.message());
     }
+
+    #[test]
+    fn config_should_support_hmac_strategy() {
+        let config: HttpServerAuthConfig = serde_yaml::from_str(indoc! { r#"
+            strategy: hmac
+            secret: foo
+            algorithm: sha512
+            "#
+        })
+        .unwrap();
+
+        assert_eq!(
+            config,
+            HttpServerAuthConfig::Hmac {
+                secret: "foo".to_string().into(),
+                algorithm: HmacAlgorithm::Sha512,
+                signature_header: "X-Signature".to_string(),
+                timestamp_header: "X-Signature-Timestamp".to_string(),
+            }
+        );
+    }
+
+    fn hmac_headers(timestamp: u64, body: &[u8]) -> HeaderMap {
+        let timestamp = timestamp.to_string();
+        let signature = hmac_signature(
+            HmacAlgorithm::Sha256,
+            b"secret",
+            &timestamp,
+            "POST",
+            "/ingest",
+            None,
+            body,
+        )
+        .unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Signature", signature.parse().unwrap());
+        headers.insert("X-Signature-Timestamp", timestamp.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn hmac_auth_matcher_should_verify_signatures() {
+        let hmac_auth = HttpServerAuthConfig::Hmac {
+            secret: "secret".to_string().into(),
+            algorithm: HmacAlgorithm::Sha256,
+            signature_header: "X-Signature".to_string(),
+            timestamp_header: "X-Signature-Timestamp".to_string(),
+        };
+        let matcher = hmac_auth.build(&Default::default()).unwrap();
+        let now = signature_timestamp();
+
+        let headers = hmac_headers(now, b"body");
+        let result = matcher.handle_auth(None, &Method::POST, &headers, "/ingest", None, b"body");
+        assert!(result.is_ok());
+
+        let result = matcher.handle_auth(None, &Method::POST, &headers, "/ingest", None, b"other");
+        assert_eq!("Invalid signature", result.unwrap_err().message());
+
+        let result = matcher.handle_auth(
+            None,
+            &Method::POST,
+            &headers,
+            "/ingest",
+            Some("stream=a"),
+            b"body",
+        );
+        assert_eq!("Invalid signature", result.unwrap_err().message());
+
+        let headers = hmac_headers(now - 2 * HMAC_MAX_CLOCK_SKEW_SECS, b"body");
+        let result = matcher.handle_auth(None, &Method::POST, &headers, "/ingest", None, b"body");
+        assert_eq!(401, result.as_ref().unwrap_err().code());
+        assert_eq!(
+            "Signature timestamp is too far from current time",
+            result.unwrap_err().message()
+        );
+
+        let result =
+            matcher.handle_auth(None, &Method::POST, &HeaderMap::new(), "/ingest", None, b"");
+        assert_eq!("No signature header", result.unwrap_err().message());
+    }
 }
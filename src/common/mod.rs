@@ -25,6 +25,13 @@ pub mod mqtt;
 #[cfg(any(feature = "transforms-log_to_metric", feature = "sinks-loki"))]
 pub(crate) mod expansion;
 
+#[cfg(any(
+    feature = "enrichment-tables-memory",
+    feature = "transforms-impl-dedupe",
+    feature = "transforms-log_clustering"
+))]
+pub mod persistence;
+
 #[cfg(any(
     feature = "sources-utils-http-auth",
     feature = "sources-utils-http-error"
@@ -0,0 +1,219 @@
+//! Persisting the state of components across restarts.
+//!
+//! Components persisting their state write snapshots of it to the data directory periodically and
+//! on shutdown, and restore their state from the latest snapshot on startup.
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufWriter},
+    num::NonZeroU64,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use async_stream::stream;
+use futures::{Stream, StreamExt};
+use tokio::time::{interval_at, Instant};
+use vector_lib::configurable::configurable_component;
+
+use crate::{config::GlobalOptions, internal_events::SnapshotDiscarded};
+
+/// Configuration for persisting the state of the component across restarts.
+///
+/// Snapshots of the state are written to the data directory, and the state is restored from its
+/// latest snapshot on startup. A snapshot that can't be restored is moved aside with a `.corrupt`
+/// suffix, and the component starts with an empty state.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PersistenceConfig {
+    /// The directory used to persist the snapshots.
+    ///
+    /// By default, the [global `data_dir` option][global_data_dir] is used. Make sure the running
+    /// user has write permissions to this directory.
+    ///
+    /// [global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/var/lib/vector"))]
+    #[configurable(metadata(docs::human_name = "Data Directory"))]
+    pub data_dir: Option<PathBuf>,
+
+    /// The interval between snapshots, in seconds.
+    ///
+    /// A last snapshot is also written on shutdown.
+    #[serde(default = "default_snapshot_interval_secs")]
+    #[configurable(metadata(docs::human_name = "Snapshot Interval"))]
+    pub snapshot_interval_secs: NonZeroU64,
+}
+
+const fn default_snapshot_interval_secs() -> NonZeroU64 {
+    NonZeroU64::new(60).expect("static non-zero number")
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            data_dir: None,
+            snapshot_interval_secs: default_snapshot_interval_secs(),
+        }
+    }
+}
+
+impl PersistenceConfig {
+    /// Returns the path of the snapshot named `file_name` in the `subdir` of the data directory,
+    /// creating the subdirectory if needed.
+    pub fn snapshot_path(
+        &self,
+        globals: &GlobalOptions,
+        subdir: &str,
+        file_name: &str,
+    ) -> crate::Result<PathBuf> {
+        let dir = globals.resolve_and_make_data_subdir(self.data_dir.as_ref(), subdir)?;
+        Ok(dir.join(file_name))
+    }
+
+    pub const fn snapshot_interval(&self) -> Duration {
+        Duration::from_secs(self.snapshot_interval_secs.get())
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    path.into()
+}
+
+/// Restores the state of a component with `restore` from the snapshot at `path`, if there is one.
+///
+/// A snapshot that can't be restored is moved aside with a `.corrupt` suffix, and `None` is
+/// returned, so `restore` must leave the state untouched when failing.
+pub fn restore_snapshot<T, E: fmt::Display>(
+    path: &Path,
+    restore: impl FnOnce(&Path) -> Result<T, E>,
+) -> Option<T> {
+    if !path.exists() {
+        return None;
+    }
+    match restore(path) {
+        Ok(restored) => Some(restored),
+        Err(error) => {
+            let moved_to = with_suffix(path, ".corrupt");
+            let moved = fs::rename(path, &moved_to).is_ok();
+            emit!(SnapshotDiscarded {
+                error,
+                path,
+                moved_to: moved.then_some(moved_to.as_path()),
+            });
+            None
+        }
+    }
+}
+
+/// Writes a snapshot to `path` with `write`.
+///
+/// The snapshot is written to a temporary file first, then synced and renamed over the previous
+/// snapshot, so that an interrupted write never leaves a partial snapshot behind.
+pub fn write_snapshot<T>(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<T>,
+) -> io::Result<T> {
+    let temp_path = with_suffix(path, ".tmp");
+    let mut file = BufWriter::new(File::create(&temp_path)?);
+    let written = write(&mut file)?;
+    file.into_inner()
+        .map_err(|error| error.into_error())?
+        .sync_all()?;
+    fs::rename(&temp_path, path)?;
+    Ok(written)
+}
+
+/// The next step of a component persisting its state.
+pub enum SnapshotStep<T> {
+    /// Process the next input.
+    Input(T),
+    /// Write a snapshot of the state.
+    Snapshot,
+}
+
+/// Interleaves the input with a snapshot step every `interval`, and a last one once the input ends.
+pub fn with_snapshots<T>(
+    mut input: impl Stream<Item = T> + Unpin,
+    interval: Duration,
+) -> impl Stream<Item = SnapshotStep<T>> {
+    stream! {
+        let mut ticks = interval_at(Instant::now() + interval, interval);
+        loop {
+            let step = tokio::select! {
+                _ = ticks.tick() => Some(SnapshotStep::Snapshot),
+                input = input.next() => input.map(SnapshotStep::Input),
+            };
+            let Some(step) = step else {
+                break;
+            };
+            yield step;
+        }
+        yield SnapshotStep::Snapshot;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn writes_snapshots_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state");
+        fs::write(&path, "previous").unwrap();
+
+        let error = write_snapshot(&path, |file| {
+            file.write_all(b"partial")?;
+            Err::<(), _>(io::Error::other("interrupted"))
+        })
+        .unwrap_err();
+        assert_eq!("interrupted", error.to_string());
+        assert_eq!("previous", fs::read_to_string(&path).unwrap());
+
+        assert_eq!(
+            7,
+            write_snapshot(&path, |file| file.write_all(b"current").map(|()| 7)).unwrap()
+        );
+        assert_eq!("current", fs::read_to_string(&path).unwrap());
+        assert!(!dir.path().join("state.tmp").exists());
+    }
+
+    #[test]
+    fn moves_unreadable_snapshots_aside() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state");
+        assert_eq!(None, restore_snapshot(&path, |_| Ok::<_, String>(1)));
+
+        fs::write(&path, "state").unwrap();
+        assert_eq!(Some(1), restore_snapshot(&path, |_| Ok::<_, String>(1)));
+        assert!(path.exists());
+
+        assert_eq!(
+            None,
+            restore_snapshot(&path, |_| Err::<usize, _>("corrupt"))
+        );
+        assert!(!path.exists());
+        assert_eq!(
+            "state",
+            fs::read_to_string(dir.path().join("state.corrupt")).unwrap()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ends_with_a_snapshot() {
+        let steps = with_snapshots(futures::stream::iter([1, 2]), Duration::from_secs(60))
+            .map(|step| match step {
+                SnapshotStep::Input(input) => Some(input),
+                SnapshotStep::Snapshot => None,
+            })
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(vec![Some(1), Some(2), None], steps);
+    }
+}
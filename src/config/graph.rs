@@ -1,6 +1,6 @@
 use super::{
-    schema, ComponentKey, DataType, OutputId, SinkOuter, SourceOuter, SourceOutput, TransformOuter,
-    TransformOutput, WildcardMatching,
+    schema, sink::DROPPED_OUTPUT, ComponentKey, DataType, OutputId, SinkOuter, SourceOuter,
+    SourceOutput, TransformOuter, TransformOutput, WildcardMatching,
 };
 use indexmap::{set::IndexSet, IndexMap};
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -17,6 +17,7 @@ pub enum Node {
     },
     Sink {
         ty: DataType,
+        reroute_dropped: bool,
     },
 }
 
@@ -40,8 +41,15 @@ impl fmt::Display for Node {
                 }
                 Ok(())
             }
-            Node::Sink { ty } => {
-                write!(f, "component_kind: sink\n  types: {ty}")
+            Node::Sink {
+                ty,
+                reroute_dropped,
+            } => {
+                write!(f, "component_kind: sink\n  types: {ty}")?;
+                if *reroute_dropped {
+                    write!(f, "\n  outputs:\n    {DROPPED_OUTPUT}: {ty}")?;
+                }
+                Ok(())
             }
         }
     }
@@ -121,6 +129,7 @@ impl Graph {
                 id.clone(),
                 Node::Sink {
                     ty: config.inner.input().data_type(),
+                    reroute_dropped: config.reroute_dropped,
                 },
             );
         }
@@ -207,7 +216,7 @@ impl Graph {
         match self.nodes[key] {
             Node::Source { .. } => panic!("no inputs on sources"),
             Node::Transform { in_ty, .. } => in_ty,
-            Node::Sink { ty } => ty,
+            Node::Sink { ty, .. } => ty,
         }
     }
 
@@ -215,8 +224,8 @@ impl Graph {
     ///
     /// # Panics
     ///
-    /// Will panic if the given id is not present in the graph or identifies a sink, which can't
-    /// have inputs.
+    /// Will panic if the given id is not present in the graph or identifies an output that doesn't
+    /// exist.
     fn get_output_type(&self, id: &OutputId) -> DataType {
         match &self.nodes[&id.component] {
             Node::Source { outputs } => outputs
@@ -229,7 +238,16 @@ impl Graph {
                 .find(|output| output.port == id.port)
                 .map(|output| output.ty)
                 .expect("output didn't exist"),
-            Node::Sink { .. } => panic!("no outputs on sinks"),
+            Node::Sink {
+                ty,
+                reroute_dropped,
+            } => {
+                assert!(
+                    *reroute_dropped && id.port.as_deref() == Some(DROPPED_OUTPUT),
+                    "output didn't exist"
+                );
+                *ty
+            }
         }
     }
 
@@ -314,7 +332,15 @@ impl Graph {
         self.nodes
             .iter()
             .flat_map(|(key, node)| match node {
-                Node::Sink { .. } => vec![],
+                Node::Sink {
+                    reroute_dropped, ..
+                } => reroute_dropped
+                    .then(|| OutputId {
+                        component: key.clone(),
+                        port: Some(DROPPED_OUTPUT.to_owned()),
+                    })
+                    .into_iter()
+                    .collect(),
                 Node::Source { outputs } => outputs
                     .iter()
                     .map(|output| OutputId {
@@ -406,7 +432,7 @@ impl Graph {
             .into_iter()
             .filter(|path| {
                 if let Some(key) = path.last() {
-                    matches!(self.nodes.get(key), Some(Node::Sink { ty: _ }))
+                    matches!(self.nodes.get(key), Some(Node::Sink { .. }))
                 } else {
                     false
                 }
@@ -480,7 +506,13 @@ mod test {
         fn add_sink(&mut self, id: &str, ty: DataType, inputs: Vec<&str>) {
             let id = ComponentKey::from(id);
             let inputs = clean_inputs(inputs);
-            self.nodes.insert(id.clone(), Node::Sink { ty });
+            self.nodes.insert(
+                id.clone(),
+                Node::Sink {
+                    ty,
+                    reroute_dropped: false,
+                },
+            );
             for from in inputs {
                 self.edges.push(Edge {
                     from,
@@ -558,6 +590,40 @@ mod test {
         graph.check_for_cycles().unwrap();
     }
 
+    #[test]
+    fn routes_sink_dropped_output() {
+        let mut graph = Graph::default();
+        graph.add_source("in", DataType::Log);
+        graph.add_sink("out", DataType::Log, vec!["in"]);
+        graph.add_sink("dead_letter", DataType::Log, vec![]);
+
+        assert_eq!(
+            Err(
+                "Input \"out.dropped\" for sink \"dead_letter\" doesn't match any components."
+                    .into()
+            ),
+            graph.test_add_input("dead_letter", "out.dropped", WildcardMatching::Strict)
+        );
+
+        if let Some(Node::Sink {
+            reroute_dropped, ..
+        }) = graph.nodes.get_mut(&ComponentKey::from("out"))
+        {
+            *reroute_dropped = true;
+        }
+        graph
+            .test_add_input("dead_letter", "out.dropped", WildcardMatching::Strict)
+            .unwrap();
+        assert_eq!(Ok(()), graph.typecheck());
+        graph.check_for_cycles().unwrap();
+
+        graph.add_transform("retry", DataType::Log, DataType::Log, vec!["out.dropped"]);
+        graph
+            .test_add_input("out", "retry", WildcardMatching::Strict)
+            .unwrap();
+        assert!(graph.check_for_cycles().is_err());
+    }
+
     #[test]
     fn detects_type_mismatches() {
         let mut graph = Graph::default();
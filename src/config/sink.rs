@@ -24,6 +24,9 @@ use crate::sinks::{util::UriSerde, Healthcheck};
 
 pub type BoxedSink = Box<dyn SinkConfig>;
 
+/// The name of the output of a sink carrying the events it failed to deliver.
+pub const DROPPED_OUTPUT: &str = "dropped";
+
 impl Configurable for BoxedSink {
     fn referenceable_name() -> Option<&'static str> {
         Some("vector::sinks::Sinks")
@@ -84,6 +87,21 @@ where
     #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
     pub proxy: ProxyConfig,
 
+    /// Reroutes events that the sink failed to deliver to a named output.
+    ///
+    /// Events whose delivery failed permanently, such as requests that were rejected by the
+    /// destination or exhausted their retries, are normally dropped. When `reroute_dropped` is set
+    /// to `true`, they are instead forwarded to a specially-named output of the sink, `dropped`,
+    /// which can be used as an input by other components. The events are annotated with
+    /// additional fields describing the failure, such as the status and the response returned by
+    /// the destination.
+    ///
+    /// The output must be referenced by its full name, such as `my_sink.dropped`, as it is not
+    /// matched by wildcards in the inputs of other components.
+    #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
+    #[configurable(metadata(docs::advanced, docs::human_name = "Reroute Dropped Events"))]
+    pub reroute_dropped: bool,
+
     #[serde(flatten)]
     #[configurable(metadata(docs::hidden))]
     pub inner: BoxedSink,
@@ -105,6 +123,7 @@ where
             healthcheck_uri: None,
             inner: inner.into(),
             proxy: Default::default(),
+            reroute_dropped: false,
             graph: Default::default(),
         }
     }
@@ -162,6 +181,7 @@ where
             healthcheck: self.healthcheck,
             healthcheck_uri: self.healthcheck_uri,
             proxy: self.proxy,
+            reroute_dropped: self.reroute_dropped,
             graph: self.graph,
         }
     }
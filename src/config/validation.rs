@@ -7,7 +7,7 @@ use vector_lib::{buffers::config::DiskUsage, internal_event::DEFAULT_OUTPUT};
 
 use super::{
     builder::ConfigBuilder, transform::get_transform_output_ids, ComponentKey, Config, OutputId,
-    Resource,
+    Resource, DROPPED_OUTPUT,
 };
 
 /// Check that provide + topology config aren't present in the same builder, which is an error.
@@ -355,12 +355,18 @@ pub fn warnings(config: &Config) -> Vec<String> {
         .collect::<Vec<_>>()
     });
 
+    let sink_ids = config
+        .sinks
+        .iter()
+        .filter(|(_, sink)| sink.reroute_dropped)
+        .map(|(key, _)| ("sink", OutputId::from((key, DROPPED_OUTPUT.to_owned()))));
+
     let table_sinks = config
         .enrichment_tables
         .iter()
         .filter_map(|(key, table)| table.as_sink(key))
         .collect::<Vec<_>>();
-    for (input_type, id) in transform_ids.chain(source_ids) {
+    for (input_type, id) in transform_ids.chain(source_ids).chain(sink_ids) {
         if !config
             .transforms
             .iter()
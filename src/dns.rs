@@ -5,6 +5,8 @@ use std::{
 };
 
 use futures::{future::BoxFuture, FutureExt};
+#[cfg(feature = "sinks-utils-srv-discovery")]
+use hickory_resolver::{ResolveError, TokioResolver};
 use hyper::client::connect::dns::Name;
 use snafu::ResultExt;
 use tokio::task::spawn_blocking;
@@ -49,6 +51,48 @@ impl Resolver {
     }
 }
 
+/// A target of an SRV record.
+#[cfg(feature = "sinks-utils-srv-discovery")]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SrvTarget {
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+    pub target: String,
+}
+
+/// Resolves SRV records, such as `_http._tcp.example.com`, with the nameservers of the system
+/// configuration.
+#[cfg(feature = "sinks-utils-srv-discovery")]
+#[derive(Clone)]
+pub struct SrvResolver(TokioResolver);
+
+#[cfg(feature = "sinks-utils-srv-discovery")]
+impl SrvResolver {
+    pub fn new() -> Result<Self, DnsError> {
+        let resolver = TokioResolver::builder_tokio()
+            .context(ResolveSnafu)?
+            .build();
+        Ok(Self(resolver))
+    }
+
+    pub async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvTarget>, DnsError> {
+        match self.0.srv_lookup(name).await {
+            Ok(lookup) => Ok(lookup
+                .iter()
+                .map(|srv| SrvTarget {
+                    priority: srv.priority(),
+                    weight: srv.weight(),
+                    port: srv.port(),
+                    target: srv.target().to_utf8().trim_end_matches('.').to_owned(),
+                })
+                .collect()),
+            Err(error) if error.is_no_records_found() => Ok(Vec::new()),
+            Err(source) => Err(DnsError::Resolve { source }),
+        }
+    }
+}
+
 impl Iterator for LookupIp {
     type Item = IpAddr;
 
@@ -77,6 +121,9 @@ pub enum DnsError {
     UnableLookup { source: tokio::io::Error },
     #[snafu(display("Failed to join with resolving future: {}", source))]
     JoinError { source: tokio::task::JoinError },
+    #[cfg(feature = "sinks-utils-srv-discovery")]
+    #[snafu(display("Unable to resolve SRV record: {}", source))]
+    Resolve { source: ResolveError },
 }
 
 #[cfg(test)]
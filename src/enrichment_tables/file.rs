@@ -1,14 +1,33 @@
 //! Handles enrichment tables for `type = file`.
-use std::{collections::HashMap, fs, hash::Hasher, path::PathBuf, time::SystemTime};
-
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    hash::Hasher,
+    path::PathBuf,
+    sync::{Arc, Mutex, Weak},
+    time::{Duration, SystemTime},
+};
+
+use arc_swap::ArcSwap;
 use bytes::Bytes;
+use http::{header, Request, StatusCode, Uri};
+use hyper::{body::to_bytes as body_to_bytes, Body};
+use serde_with::serde_as;
+use tokio::time::MissedTickBehavior;
 use tracing::trace;
 use vector_lib::configurable::configurable_component;
-use vector_lib::enrichment::{Case, Condition, IndexHandle, Table};
+use vector_lib::enrichment::{
+    lookup::{cidr_contains, numeric_value, CidrIndex, PrefixIndex, RangeIndex},
+    Case, Condition, IndexHandle, LookupIndex, Table,
+};
 use vector_lib::{conversion::Conversion, TimeZone};
 use vrl::value::{ObjectMap, Value};
 
-use crate::config::EnrichmentTableConfig;
+use crate::{
+    config::EnrichmentTableConfig,
+    http::HttpClient,
+    internal_events::{FileEnrichmentTableRefreshError, FileEnrichmentTableRefreshed},
+};
 
 /// File encoding configuration.
 #[configurable_component]
@@ -33,6 +52,30 @@ pub enum Encoding {
         #[serde(default = "default_delimiter")]
         delimiter: char,
     },
+
+    /// Decodes the file as a [JSON][json] array of objects, each object being a row.
+    ///
+    /// The columns are the keys found in the objects. Rows missing a key have a `null` value in
+    /// its column.
+    ///
+    /// [json]: https://www.json.org/
+    Json,
+
+    /// Decodes the file as [newline-delimited JSON][ndjson], each line holding the object of a row.
+    ///
+    /// The columns are the keys found in the objects. Rows missing a key have a `null` value in
+    /// its column.
+    ///
+    /// [ndjson]: https://github.com/ndjson/ndjson-spec
+    Ndjson,
+
+    /// Decodes the file as an [Apache Parquet][parquet] file.
+    ///
+    /// The columns are the top-level fields of the file schema.
+    ///
+    /// [parquet]: https://parquet.apache.org/
+    #[cfg(feature = "enrichment-tables-file-parquet")]
+    Parquet,
 }
 
 impl Default for Encoding {
@@ -45,15 +88,35 @@ impl Default for Encoding {
 }
 
 /// File-specific settings.
+#[serde_as]
 #[configurable_component]
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
 pub struct FileSettings {
     /// The path of the enrichment table file.
     ///
-    /// Currently, only [CSV][csv] files are supported.
+    /// The file is reloaded when its modification time changes and the configuration is reloaded.
+    ///
+    /// Exactly one of `path` and `url` must be set.
+    #[configurable(metadata(docs::examples = "/etc/vector/table.csv"))]
+    pub path: Option<PathBuf>,
+
+    /// The HTTP(S) URL of the enrichment table file.
+    ///
+    /// The file is fetched again every `refresh_interval_secs`. Its `ETag` is sent in the
+    /// `If-None-Match` header, so it is only downloaded again once it changed. Lookups keep using
+    /// the previous data until the new data is completely loaded.
     ///
-    /// [csv]: https://en.wikipedia.org/wiki/Comma-separated_values
-    pub path: PathBuf,
+    /// Exactly one of `path` and `url` must be set.
+    #[configurable(metadata(docs::examples = "https://example.com/enrichment/table.csv"))]
+    pub url: Option<String>,
+
+    /// The interval between refreshes of a file fetched from a `url`, in seconds.
+    #[serde(default = "default_refresh_interval_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_refresh_interval_secs()"))]
+    #[configurable(metadata(docs::human_name = "Refresh Interval"))]
+    pub refresh_interval_secs: Duration,
 
     /// File encoding configuration.
     #[configurable(derived)]
@@ -72,6 +135,8 @@ pub struct FileConfig {
     ///
     /// This is used to coerce log fields from strings into their proper types. The available types are listed in the `Types` list below.
     ///
+    /// Values that are already typed, like the numbers and booleans of JSON or Parquet files, are coerced from their string representation.
+    ///
     /// Timestamp coercions need to be prefaced with `timestamp|`, for example `"timestamp|%F"`. Timestamp specifiers can use either of the following:
     ///
     /// 1. One of the built-in-formats listed in the `Timestamp Formats` table below.
@@ -122,6 +187,10 @@ const fn default_delimiter() -> char {
     ','
 }
 
+const fn default_refresh_interval_secs() -> Duration {
+    Duration::from_secs(300)
+}
+
 impl FileConfig {
     fn parse_column(
         &self,
@@ -176,59 +245,156 @@ impl FileConfig {
         })
     }
 
-    /// Load the configured file into memory. Required to create a new file enrichment table.
-    pub fn load_file(&self, timezone: TimeZone) -> crate::Result<FileData> {
-        let Encoding::Csv {
-            include_headers,
-            delimiter,
-        } = self.file.encoding;
-
-        let mut reader = csv::ReaderBuilder::new()
-            .has_headers(include_headers)
-            .delimiter(delimiter as u8)
-            .from_path(&self.file.path)?;
-
-        let first_row = reader.records().next();
-        let headers = if include_headers {
-            reader
-                .headers()?
-                .iter()
-                .map(|col| col.to_string())
-                .collect::<Vec<_>>()
-        } else {
-            // If there are no headers in the datafile we make headers as the numerical index of
-            // the column.
-            match first_row {
-                Some(Ok(ref row)) => (0..row.len()).map(|idx| idx.to_string()).collect(),
-                _ => Vec::new(),
-            }
+    /// Coerces the decoded value of a column into the type given by the schema.
+    ///
+    /// Strings are parsed, while other scalar values, like the numbers of JSON or Parquet files,
+    /// are parsed from their string representation. Timestamps are kept as they are when the
+    /// column is coerced into a date or a timestamp.
+    fn coerce_column(
+        &self,
+        timezone: TimeZone,
+        column: &str,
+        row: usize,
+        value: Value,
+    ) -> Result<Value, String> {
+        let Some(format) = self.schema.get(column) else {
+            return Ok(value);
         };
 
-        let data = first_row
+        match value {
+            Value::Null => Ok(Value::Null),
+            Value::Bytes(bytes) => {
+                let value = std::str::from_utf8(&bytes)
+                    .map_err(|_| format!("invalid utf found in row {row}"))?;
+                self.parse_column(timezone, column, row, value)
+            }
+            Value::Timestamp(_)
+                if matches!(
+                    format.split('|').next().map(str::trim),
+                    Some("date" | "timestamp")
+                ) =>
+            {
+                Ok(value)
+            }
+            Value::Boolean(_) | Value::Integer(_) | Value::Float(_) | Value::Timestamp(_) => {
+                self.parse_column(timezone, column, row, &value.to_string_lossy())
+            }
+            value => Err(format!("unable to coerce {value} found in row {row}")),
+        }
+    }
+
+    /// Decodes the rows of the file, along with the names of their columns.
+    fn decode(&self, contents: Bytes) -> crate::Result<(Vec<String>, Vec<Vec<Value>>)> {
+        match self.file.encoding {
+            Encoding::Csv {
+                include_headers,
+                delimiter,
+            } => decode_csv(&contents, include_headers, delimiter),
+            Encoding::Json => Ok(decode_objects(serde_json::from_slice(&contents)?)),
+            Encoding::Ndjson => {
+                let objects = contents
+                    .split(|byte| *byte == b'\n')
+                    .enumerate()
+                    .filter(|(_, line)| !line.trim_ascii().is_empty())
+                    .map(|(idx, line)| {
+                        serde_json::from_slice(line)
+                            .map_err(|error| format!("unable to parse line {}: {error}", idx + 1))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(decode_objects(objects))
+            }
+            #[cfg(feature = "enrichment-tables-file-parquet")]
+            Encoding::Parquet => decode_parquet(contents),
+        }
+    }
+
+    /// Decodes the contents of the file and coerces its columns as configured in the schema.
+    fn parse_file(
+        &self,
+        timezone: TimeZone,
+        contents: Bytes,
+        modified: SystemTime,
+    ) -> crate::Result<FileData> {
+        let (headers, rows) = self.decode(contents)?;
+
+        let data = rows
             .into_iter()
-            .chain(reader.records())
-            .map(|row| {
-                Ok(row?
+            .enumerate()
+            .map(|(row, values)| {
+                headers
                     .iter()
-                    .enumerate()
-                    .map(|(idx, col)| self.parse_column(timezone, &headers[idx], idx, col))
-                    .collect::<Result<Vec<_>, String>>()?)
+                    .zip(values)
+                    .map(|(column, value)| self.coerce_column(timezone, column, row, value))
+                    .collect::<Result<Vec<_>, String>>()
             })
-            .collect::<crate::Result<Vec<_>>>()?;
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(FileData {
+            headers,
+            data,
+            modified,
+        })
+    }
+
+    /// Load the configured file into memory. Required to create a new file enrichment table.
+    pub fn load_file(&self, timezone: TimeZone) -> crate::Result<FileData> {
+        let path = self
+            .file
+            .path
+            .as_ref()
+            .ok_or("no path is set for the enrichment table file")?;
+
+        // Get the modified time before reading, so changes made while reading trigger a reload.
+        let modified = fs::metadata(path)?.modified()?;
+        let data = self.parse_file(timezone, fs::read(path)?.into(), modified)?;
 
         trace!(
             "Loaded enrichment file {} with headers {:?}.",
-            self.file.path.to_str().unwrap_or("path with invalid utf"),
-            headers
+            path.to_str().unwrap_or("path with invalid utf"),
+            data.headers
         );
 
-        let file = reader.into_inner();
+        Ok(data)
+    }
 
-        Ok(FileData {
-            headers,
-            data,
-            modified: file.metadata()?.modified()?,
-        })
+    /// Fetches the configured file from its URL, returning `None` if it has not changed since it
+    /// was fetched with the given `ETag`.
+    async fn fetch_file(
+        &self,
+        client: &HttpClient,
+        url: &Uri,
+        timezone: TimeZone,
+        etag: Option<&str>,
+    ) -> crate::Result<Option<(FileData, Option<String>)>> {
+        let mut request = Request::get(url.clone());
+        if let Some(etag) = etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+
+        let response = client.send(request.body(Body::empty())?).await?;
+        let status = response.status();
+        if status == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(format!("unexpected response status {status}").into());
+        }
+
+        let etag = response
+            .headers()
+            .get(header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(ToOwned::to_owned);
+        let contents = body_to_bytes(response.into_body()).await?;
+        let data = self.parse_file(timezone, contents, SystemTime::now())?;
+
+        trace!(
+            "Fetched enrichment file {} with headers {:?}.",
+            url,
+            data.headers
+        );
+
+        Ok(Some((data, etag)))
     }
 }
 
@@ -237,15 +403,204 @@ impl EnrichmentTableConfig for FileConfig {
         &self,
         globals: &crate::config::GlobalOptions,
     ) -> crate::Result<Box<dyn Table + Send + Sync>> {
-        Ok(Box::new(File::new(
-            self.clone(),
-            self.load_file(globals.timezone())?,
-        )))
+        match (&self.file.path, &self.file.url) {
+            (Some(_), None) => Ok(Box::new(File::new(
+                self.clone(),
+                self.load_file(globals.timezone())?,
+            ))),
+            (None, Some(url)) => {
+                if self.file.refresh_interval_secs.is_zero() {
+                    return Err("`file.refresh_interval_secs` must be greater than zero".into());
+                }
+
+                let url = url.parse::<Uri>()?;
+                let timezone = globals.timezone();
+                let client = HttpClient::new(None, &globals.proxy)?;
+                let (data, etag) = self
+                    .fetch_file(&client, &url, timezone, None)
+                    .await?
+                    .ok_or("the enrichment table file was not returned")?;
+
+                let table = RemoteFile::new(File::new(self.clone(), data));
+                tokio::spawn(refresh_remote_file(
+                    Arc::downgrade(&table.state),
+                    self.clone(),
+                    client,
+                    url,
+                    timezone,
+                    etag,
+                ));
+                Ok(Box::new(table))
+            }
+            _ => Err("exactly one of `file.path` and `file.url` must be set".into()),
+        }
     }
 }
 
 impl_generate_config_from_default!(FileConfig);
 
+/// Decodes the rows of a CSV file, along with the names of their columns.
+fn decode_csv(
+    contents: &[u8],
+    include_headers: bool,
+    delimiter: char,
+) -> crate::Result<(Vec<String>, Vec<Vec<Value>>)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(include_headers)
+        .delimiter(delimiter as u8)
+        .from_reader(contents);
+
+    let first_row = reader.records().next();
+    let headers = if include_headers {
+        reader
+            .headers()?
+            .iter()
+            .map(|col| col.to_string())
+            .collect::<Vec<_>>()
+    } else {
+        // If there are no headers in the datafile we make headers as the numerical index of
+        // the column.
+        match first_row {
+            Some(Ok(ref row)) => (0..row.len()).map(|idx| idx.to_string()).collect(),
+            _ => Vec::new(),
+        }
+    };
+
+    let data = first_row
+        .into_iter()
+        .chain(reader.records())
+        .map(|row| Ok(row?.iter().map(Value::from).collect()))
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    Ok((headers, data))
+}
+
+/// Decodes the rows of the objects of a JSON file, with a column for each key found in them.
+fn decode_objects(
+    objects: Vec<serde_json::Map<String, serde_json::Value>>,
+) -> (Vec<String>, Vec<Vec<Value>>) {
+    let mut seen = HashSet::new();
+    let headers = objects
+        .iter()
+        .flat_map(|object| object.keys())
+        .filter(|key| seen.insert(*key))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let data = objects
+        .into_iter()
+        .map(|mut object| {
+            headers
+                .iter()
+                .map(|header| object.remove(header).map_or(Value::Null, Value::from))
+                .collect()
+        })
+        .collect();
+
+    (headers, data)
+}
+
+/// Decodes the rows of a Parquet file, with a column for each top-level field of its schema.
+#[cfg(feature = "enrichment-tables-file-parquet")]
+fn decode_parquet(contents: Bytes) -> crate::Result<(Vec<String>, Vec<Vec<Value>>)> {
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let reader = SerializedFileReader::new(contents)?;
+    let headers = reader
+        .metadata()
+        .file_metadata()
+        .schema_descr()
+        .root_schema()
+        .get_fields()
+        .iter()
+        .map(|field| field.name().to_string())
+        .collect();
+
+    let data = reader
+        .get_row_iter(None)?
+        .map(|row| {
+            Ok(row?
+                .into_columns()
+                .into_iter()
+                .map(|(_, field)| parquet_value(field))
+                .collect())
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+
+    Ok((headers, data))
+}
+
+#[cfg(feature = "enrichment-tables-file-parquet")]
+fn parquet_value(field: parquet::record::Field) -> Value {
+    use chrono::DateTime;
+    use parquet::record::Field;
+
+    match field {
+        Field::Bytes(bytes) => Value::Bytes(Bytes::copy_from_slice(bytes.data())),
+        Field::Date(days) => {
+            DateTime::from_timestamp(i64::from(days) * 86_400, 0).map_or(Value::Null, Value::from)
+        }
+        Field::TimestampMillis(millis) => {
+            DateTime::from_timestamp_millis(millis).map_or(Value::Null, Value::from)
+        }
+        Field::TimestampMicros(micros) => {
+            DateTime::from_timestamp_micros(micros).map_or(Value::Null, Value::from)
+        }
+        field => Value::from(field.to_json_value()),
+    }
+}
+
+/// Periodically fetches the file of a remote table, swapping its data once the file changed.
+///
+/// Stops once the table has been dropped.
+async fn refresh_remote_file(
+    state: Weak<RemoteState>,
+    config: FileConfig,
+    client: HttpClient,
+    url: Uri,
+    timezone: TimeZone,
+    mut etag: Option<String>,
+) {
+    let url_str = url.to_string();
+    let mut interval = tokio::time::interval(config.file.refresh_interval_secs);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // The first tick completes immediately, but the file has just been fetched.
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        if state.strong_count() == 0 {
+            break;
+        }
+
+        match config
+            .fetch_file(&client, &url, timezone, etag.as_deref())
+            .await
+        {
+            Ok(None) => {}
+            Ok(Some((data, new_etag))) => {
+                let Some(state) = state.upgrade() else {
+                    break;
+                };
+                match state.replace(File::new(config.clone(), data)) {
+                    Ok(()) => {
+                        etag = new_etag;
+                        emit!(FileEnrichmentTableRefreshed { url: &url_str });
+                    }
+                    Err(error) => emit!(FileEnrichmentTableRefreshError {
+                        url: &url_str,
+                        error: error.into(),
+                    }),
+                }
+            }
+            Err(error) => emit!(FileEnrichmentTableRefreshError {
+                url: &url_str,
+                error,
+            }),
+        }
+    }
+}
+
 /// The data resulting from loading a configured file.
 pub struct FileData {
     /// The ordered set of headers of the data columns.
@@ -256,18 +611,38 @@ pub struct FileData {
     pub modified: SystemTime,
 }
 
+/// A lookup structure over the rows of the data, to search them with CIDR, numeric range or prefix
+/// conditions.
+#[derive(Clone)]
+enum Lookup {
+    Cidr {
+        field: usize,
+        index: CidrIndex<usize>,
+    },
+    NumericRange {
+        from: usize,
+        to: usize,
+        index: RangeIndex<usize>,
+    },
+    Prefix {
+        field: usize,
+        index: PrefixIndex<usize>,
+    },
+}
+
 /// A struct that implements [vector_lib::enrichment::Table] to handle loading enrichment data from a CSV file.
 #[derive(Clone)]
 pub struct File {
     config: FileConfig,
     last_modified: SystemTime,
-    data: Vec<Vec<Value>>,
+    data: Arc<Vec<Vec<Value>>>,
     headers: Vec<String>,
     indexes: Vec<(
         Case,
         Vec<usize>,
         HashMap<u64, Vec<usize>, hash_hasher::HashBuildHasher>,
     )>,
+    lookups: Vec<Lookup>,
 }
 
 impl File {
@@ -276,9 +651,10 @@ impl File {
         Self {
             config,
             last_modified: data.modified,
-            data: data.data,
+            data: Arc::new(data.data),
             headers: data.headers,
             indexes: Vec::new(),
+            lookups: Vec::new(),
         }
     }
 
@@ -363,6 +739,88 @@ impl File {
                     _ => false,
                 },
             },
+            Condition::Cidr { field, value } => match self.column_index(field) {
+                None => false,
+                Some(idx) => match &row[idx] {
+                    Value::Bytes(cidr) => {
+                        std::str::from_utf8(cidr).is_ok_and(|cidr| cidr_contains(cidr, *value))
+                    }
+                    _ => false,
+                },
+            },
+            Condition::NumericRange { from, to, value } => {
+                match (self.column_index(from), self.column_index(to)) {
+                    (Some(from), Some(to)) => matches!(
+                        (numeric_value(&row[from]), numeric_value(&row[to])),
+                        (Some(from), Some(to)) if from <= *value && *value <= to
+                    ),
+                    _ => false,
+                }
+            }
+            Condition::Prefix { field, value } => match self.column_index(field) {
+                None => false,
+                Some(idx) => match (case, &row[idx]) {
+                    (Case::Sensitive, Value::Bytes(prefix)) => value.as_bytes().starts_with(prefix),
+                    (Case::Insensitive, Value::Bytes(prefix)) => std::str::from_utf8(prefix)
+                        .is_ok_and(|prefix| {
+                            value.to_lowercase().starts_with(&prefix.to_lowercase())
+                        }),
+                    _ => false,
+                },
+            },
+        })
+    }
+
+    fn lookup_column(&self, field: &str) -> Result<usize, String> {
+        self.column_index(field)
+            .ok_or_else(|| format!("field(s) '{field}' missing from dataset"))
+    }
+
+    /// Finds the rows that may match the conditions through the lookup structure of one of them,
+    /// if one has been built.
+    fn lookup_rows(&self, case: Case, condition: &[Condition]) -> Option<Vec<usize>> {
+        condition.iter().find_map(|condition| {
+            let mut rows = self
+                .lookups
+                .iter()
+                .find_map(|lookup| match (condition, lookup) {
+                    (
+                        Condition::Cidr { field, value },
+                        Lookup::Cidr {
+                            field: column,
+                            index,
+                        },
+                    ) if self.column_index(field) == Some(*column) => {
+                        Some(index.find(*value).copied().collect::<Vec<_>>())
+                    }
+                    (
+                        Condition::NumericRange { from, to, value },
+                        Lookup::NumericRange {
+                            from: from_column,
+                            to: to_column,
+                            index,
+                        },
+                    ) if self.column_index(from) == Some(*from_column)
+                        && self.column_index(to) == Some(*to_column) =>
+                    {
+                        Some(index.find(*value).copied().collect())
+                    }
+                    // The prefixes are indexed as they are, so they can only be searched with their case.
+                    (
+                        Condition::Prefix { field, value },
+                        Lookup::Prefix {
+                            field: column,
+                            index,
+                        },
+                    ) if case == Case::Sensitive && self.column_index(field) == Some(*column) => {
+                        Some(index.find(value).copied().collect())
+                    }
+                    _ => None,
+                })?;
+            // Keep the order of the data, like a sequential scan does.
+            rows.sort_unstable();
+            rows.dedup();
+            Some(rows)
         })
     }
 
@@ -568,6 +1026,17 @@ impl Table for File {
         wildcard: Option<&Value>,
         index: Option<IndexHandle>,
     ) -> Result<ObjectMap, String> {
+        if let Some(rows) = self.lookup_rows(case, condition) {
+            // Perform a sequential scan over the rows found by the lookup.
+            return single_or_err(self.sequential(
+                rows.into_iter().map(|idx| &self.data[idx]),
+                case,
+                condition,
+                select,
+                wildcard,
+            ));
+        }
+
         match index {
             None => {
                 // No index has been passed so we need to do a Sequential Scan.
@@ -597,6 +1066,19 @@ impl Table for File {
         wildcard: Option<&Value>,
         index: Option<IndexHandle>,
     ) -> Result<Vec<ObjectMap>, String> {
+        if let Some(rows) = self.lookup_rows(case, condition) {
+            // Perform a sequential scan over the rows found by the lookup.
+            return Ok(self
+                .sequential(
+                    rows.into_iter().map(|idx| &self.data[idx]),
+                    case,
+                    condition,
+                    select,
+                    wildcard,
+                )
+                .collect());
+        }
+
         match index {
             None => {
                 // No index has been passed so we need to do a Sequential Scan.
@@ -664,9 +1146,74 @@ impl Table for File {
             .collect::<Vec<_>>()
     }
 
+    fn add_lookup_index(&mut self, lookup: &LookupIndex) -> Result<(), String> {
+        if self.lookup_indexes().contains(lookup) {
+            // This lookup already exists
+            return Ok(());
+        }
+
+        let lookup = match lookup {
+            LookupIndex::Cidr { field } => {
+                let field = self.lookup_column(field)?;
+                let mut index = CidrIndex::default();
+                for (idx, row) in self.data.iter().enumerate() {
+                    if let Value::Bytes(cidr) = &row[field] {
+                        if let Ok(cidr) = std::str::from_utf8(cidr) {
+                            index.insert(cidr, idx);
+                        }
+                    }
+                }
+                Lookup::Cidr { field, index }
+            }
+            LookupIndex::NumericRange { from, to } => {
+                let (from, to) = (self.lookup_column(from)?, self.lookup_column(to)?);
+                let index =
+                    RangeIndex::new(self.data.iter().enumerate().filter_map(|(idx, row)| {
+                        Some((numeric_value(&row[from])?, numeric_value(&row[to])?, idx))
+                    }));
+                Lookup::NumericRange { from, to, index }
+            }
+            LookupIndex::Prefix { field } => {
+                let field = self.lookup_column(field)?;
+                let mut index = PrefixIndex::default();
+                for (idx, row) in self.data.iter().enumerate() {
+                    if let Value::Bytes(prefix) = &row[field] {
+                        if let Ok(prefix) = std::str::from_utf8(prefix) {
+                            index.insert(prefix, idx);
+                        }
+                    }
+                }
+                Lookup::Prefix { field, index }
+            }
+        };
+        self.lookups.push(lookup);
+        Ok(())
+    }
+
+    fn lookup_indexes(&self) -> Vec<LookupIndex> {
+        self.lookups
+            .iter()
+            .map(|lookup| match lookup {
+                Lookup::Cidr { field, .. } => LookupIndex::Cidr {
+                    field: self.headers[*field].clone(),
+                },
+                Lookup::NumericRange { from, to, .. } => LookupIndex::NumericRange {
+                    from: self.headers[*from].clone(),
+                    to: self.headers[*to].clone(),
+                },
+                Lookup::Prefix { field, .. } => LookupIndex::Prefix {
+                    field: self.headers[*field].clone(),
+                },
+            })
+            .collect()
+    }
+
     /// Checks the modified timestamp of the data file to see if data has changed.
     fn needs_reload(&self) -> bool {
-        matches!(fs::metadata(&self.config.file.path)
+        let Some(path) = &self.config.file.path else {
+            return false;
+        };
+        matches!(fs::metadata(path)
             .and_then(|metadata| metadata.modified()),
             Ok(modified) if modified > self.last_modified)
     }
@@ -683,11 +1230,125 @@ impl std::fmt::Debug for File {
     }
 }
 
+/// The data of a [RemoteFile], shared with the task refreshing it.
+struct RemoteState {
+    current: ArcSwap<File>,
+    /// Serializes the changes to the data, so the indexes added while the file is refreshed are
+    /// not lost.
+    writer: Mutex<()>,
+}
+
+impl RemoteState {
+    /// Applies the change to a copy of the current data, then swaps the copy in.
+    fn update<T>(&self, change: impl FnOnce(&mut File) -> Result<T, String>) -> Result<T, String> {
+        let _writer = self.writer.lock().expect("poisoned lock");
+        let mut file = File::clone(&self.current.load());
+        let result = change(&mut file)?;
+        self.current.store(Arc::new(file));
+        Ok(result)
+    }
+
+    /// Swaps in the refreshed data, once it has been indexed like the current data.
+    fn replace(&self, mut file: File) -> Result<(), String> {
+        let _writer = self.writer.lock().expect("poisoned lock");
+        let current = self.current.load();
+        // The indexes are added in the same order, so the handles to them stay valid.
+        for (case, fields) in current.index_fields() {
+            let fields = fields.iter().map(String::as_str).collect::<Vec<_>>();
+            file.add_index(case, &fields)?;
+        }
+        for lookup in current.lookup_indexes() {
+            file.add_lookup_index(&lookup)?;
+        }
+        self.current.store(Arc::new(file));
+        Ok(())
+    }
+}
+
+/// A [File] table fetched from a URL.
+///
+/// The data is refreshed in the background and swapped atomically, so lookups see either the
+/// previous or the new data, but never a partially loaded table.
+#[derive(Clone)]
+pub struct RemoteFile {
+    state: Arc<RemoteState>,
+}
+
+impl RemoteFile {
+    /// Creates a new [RemoteFile] with the initially fetched data.
+    fn new(file: File) -> Self {
+        Self {
+            state: Arc::new(RemoteState {
+                current: ArcSwap::from_pointee(file),
+                writer: Mutex::new(()),
+            }),
+        }
+    }
+}
+
+impl Table for RemoteFile {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<ObjectMap, String> {
+        self.state
+            .current
+            .load()
+            .find_table_row(case, condition, select, wildcard, index)
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<Vec<ObjectMap>, String> {
+        self.state
+            .current
+            .load()
+            .find_table_rows(case, condition, select, wildcard, index)
+    }
+
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        self.state.update(|file| file.add_index(case, fields))
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        self.state.current.load().index_fields()
+    }
+
+    fn add_lookup_index(&mut self, lookup: &LookupIndex) -> Result<(), String> {
+        self.state.update(|file| file.add_lookup_index(lookup))
+    }
+
+    fn lookup_indexes(&self) -> Vec<LookupIndex> {
+        self.state.current.load().lookup_indexes()
+    }
+
+    /// The data is refreshed in the background, so the table never needs to be reloaded.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for RemoteFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Remote{:?}", self.state.current.load())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::{TimeZone, Timelike};
 
     use super::*;
+    use crate::test_util::http::spawn_blackhole_http_server;
 
     #[test]
     fn parse_file_with_headers() {
@@ -697,11 +1358,12 @@ mod tests {
 
         let config = FileConfig {
             file: FileSettings {
-                path,
+                path: Some(path),
                 encoding: Encoding::Csv {
                     include_headers: true,
                     delimiter: default_delimiter(),
                 },
+                ..Default::default()
             },
             schema: HashMap::new(),
         };
@@ -726,11 +1388,12 @@ mod tests {
 
         let config = FileConfig {
             file: FileSettings {
-                path,
+                path: Some(path),
                 encoding: Encoding::Csv {
                     include_headers: false,
                     delimiter: default_delimiter(),
                 },
+                ..Default::default()
             },
             schema: HashMap::new(),
         };
@@ -1528,4 +2191,408 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn finds_rows_in_cidr_blocks() {
+        let mut file = File::new(
+            Default::default(),
+            FileData {
+                modified: SystemTime::now(),
+                data: vec![
+                    vec!["10.0.0.0/8".into(), "internal".into()],
+                    vec!["10.1.0.0/16".into(), "office".into()],
+                    vec!["2001:db8::/32".into(), "docs".into()],
+                ],
+                headers: vec!["subnet".to_string(), "zone".to_string()],
+            },
+        );
+
+        let condition = Condition::Cidr {
+            field: "subnet",
+            value: "10.1.2.3".parse().unwrap(),
+        };
+        let expected = vec![
+            ObjectMap::from([
+                ("subnet".into(), Value::from("10.0.0.0/8")),
+                ("zone".into(), Value::from("internal")),
+            ]),
+            ObjectMap::from([
+                ("subnet".into(), Value::from("10.1.0.0/16")),
+                ("zone".into(), Value::from("office")),
+            ]),
+        ];
+
+        assert_eq!(
+            Ok(expected.clone()),
+            file.find_table_rows(Case::Sensitive, &[condition.clone()], None, None, None)
+        );
+
+        file.add_lookup_index(&LookupIndex::Cidr {
+            field: "subnet".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            vec![LookupIndex::Cidr {
+                field: "subnet".to_string()
+            }],
+            file.lookup_indexes()
+        );
+        assert_eq!(
+            Ok(expected),
+            file.find_table_rows(Case::Sensitive, &[condition], None, None, None)
+        );
+    }
+
+    #[test]
+    fn finds_row_in_numeric_range() {
+        let mut file = File::new(
+            Default::default(),
+            FileData {
+                modified: SystemTime::now(),
+                data: vec![
+                    vec![Value::from(0), Value::from(1023), "system".into()],
+                    vec!["1024".into(), "49151".into(), "registered".into()],
+                    vec![Value::from(49152), Value::from(65535), "dynamic".into()],
+                ],
+                headers: vec!["from".to_string(), "to".to_string(), "category".to_string()],
+            },
+        );
+        file.add_lookup_index(&LookupIndex::NumericRange {
+            from: "from".to_string(),
+            to: "to".to_string(),
+        })
+        .unwrap();
+
+        let condition = Condition::NumericRange {
+            from: "from",
+            to: "to".to_string(),
+            value: 8080.0,
+        };
+
+        assert_eq!(
+            Ok(ObjectMap::from([
+                ("from".into(), Value::from("1024")),
+                ("to".into(), Value::from("49151")),
+                ("category".into(), Value::from("registered")),
+            ])),
+            file.find_table_row(Case::Sensitive, &[condition], None, None, None)
+        );
+
+        let condition = Condition::NumericRange {
+            from: "from",
+            to: "to".to_string(),
+            value: 70000.0,
+        };
+
+        assert_eq!(
+            Err("no rows found".to_string()),
+            file.find_table_row(Case::Sensitive, &[condition], None, None, None)
+        );
+    }
+
+    #[test]
+    fn finds_rows_with_prefix() {
+        let mut file = File::new(
+            Default::default(),
+            FileData {
+                modified: SystemTime::now(),
+                data: vec![
+                    vec!["/api".into(), "api".into()],
+                    vec!["/API/v2".into(), "api_v2".into()],
+                    vec!["/static".into(), "static".into()],
+                ],
+                headers: vec!["path".to_string(), "service".to_string()],
+            },
+        );
+        file.add_lookup_index(&LookupIndex::Prefix {
+            field: "path".to_string(),
+        })
+        .unwrap();
+
+        let condition = Condition::Prefix {
+            field: "path",
+            value: "/api/v2/users".to_string(),
+        };
+
+        assert_eq!(
+            Ok(vec![ObjectMap::from([
+                ("path".into(), Value::from("/api")),
+                ("service".into(), Value::from("api")),
+            ])]),
+            file.find_table_rows(Case::Sensitive, &[condition.clone()], None, None, None)
+        );
+        assert_eq!(
+            Ok(vec![
+                ObjectMap::from([
+                    ("path".into(), Value::from("/api")),
+                    ("service".into(), Value::from("api")),
+                ]),
+                ObjectMap::from([
+                    ("path".into(), Value::from("/API/v2")),
+                    ("service".into(), Value::from("api_v2")),
+                ]),
+            ]),
+            file.find_table_rows(Case::Insensitive, &[condition], None, None, None)
+        );
+    }
+
+    #[test]
+    fn errors_on_missing_lookup_columns() {
+        let mut file = File::new(
+            Default::default(),
+            FileData {
+                modified: SystemTime::now(),
+                data: Vec::new(),
+                headers: vec!["field1".to_string()],
+            },
+        );
+
+        assert_eq!(
+            Err("field(s) 'subnet' missing from dataset".to_string()),
+            file.add_lookup_index(&LookupIndex::Cidr {
+                field: "subnet".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_json_file() {
+        let dir = tempfile::tempdir().expect("Unable to create tempdir for enrichment table");
+        let path = dir.path().join("table.json");
+        fs::write(
+            path.clone(),
+            r#"[{"count": "1", "enabled": true, "name": "a"}, {"count": 2, "name": "b"}]"#,
+        )
+        .expect("Failed to write enrichment table");
+
+        let config = FileConfig {
+            file: FileSettings {
+                path: Some(path),
+                encoding: Encoding::Json,
+                ..Default::default()
+            },
+            schema: HashMap::from([
+                ("count".to_string(), "integer".to_string()),
+                ("enabled".to_string(), "string".to_string()),
+            ]),
+        };
+        let data = config
+            .load_file(Default::default())
+            .expect("Failed to parse json");
+        assert_eq!(vec!["count", "enabled", "name"], data.headers);
+        assert_eq!(
+            vec![
+                vec![Value::from(1), Value::from("true"), Value::from("a")],
+                vec![Value::from(2), Value::Null, Value::from("b")],
+            ],
+            data.data
+        );
+    }
+
+    #[test]
+    fn parse_ndjson_file() {
+        let dir = tempfile::tempdir().expect("Unable to create tempdir for enrichment table");
+        let path = dir.path().join("table.ndjson");
+        fs::write(
+            path.clone(),
+            "{\"code\": \"AT\", \"since\": \"2020-03-05\"}\n\n{\"code\": \"BE\"}\n",
+        )
+        .expect("Failed to write enrichment table");
+
+        let config = FileConfig {
+            file: FileSettings {
+                path: Some(path.clone()),
+                encoding: Encoding::Ndjson,
+                ..Default::default()
+            },
+            schema: HashMap::from([("since".to_string(), "date".to_string())]),
+        };
+        let data = config
+            .load_file(Default::default())
+            .expect("Failed to parse ndjson");
+        assert_eq!(vec!["code", "since"], data.headers);
+        assert_eq!(
+            vec![
+                vec![
+                    Value::from("AT"),
+                    Value::from(
+                        chrono::Utc
+                            .with_ymd_and_hms(2020, 3, 5, 0, 0, 0)
+                            .single()
+                            .expect("invalid timestamp")
+                    )
+                ],
+                vec![Value::from("BE"), Value::Null],
+            ],
+            data.data
+        );
+
+        fs::write(path, "{\"code\": \"AT\"}\nnot json\n")
+            .expect("Failed to write enrichment table");
+        let error = config.load_file(Default::default()).unwrap_err();
+        assert!(error.to_string().starts_with("unable to parse line 2"));
+    }
+
+    #[test]
+    fn coerce_typed_column() {
+        let config = FileConfig {
+            file: Default::default(),
+            schema: HashMap::from([
+                ("count".to_string(), "integer".to_string()),
+                ("ratio".to_string(), "float".to_string()),
+                ("seen".to_string(), "timestamp".to_string()),
+                ("tags".to_string(), "string".to_string()),
+            ]),
+        };
+        let seen = Value::from(
+            chrono::Utc
+                .with_ymd_and_hms(2020, 3, 5, 0, 0, 0)
+                .single()
+                .expect("invalid timestamp"),
+        );
+
+        assert_eq!(
+            Ok(Value::from(42)),
+            config.coerce_column(Default::default(), "count", 1, Value::from("42"))
+        );
+        assert_eq!(
+            Ok(Value::from(2.0)),
+            config.coerce_column(Default::default(), "ratio", 1, Value::from(2))
+        );
+        assert_eq!(
+            Ok(seen.clone()),
+            config.coerce_column(Default::default(), "seen", 1, seen.clone())
+        );
+        assert_eq!(
+            Ok(Value::Null),
+            config.coerce_column(Default::default(), "count", 1, Value::Null)
+        );
+        assert_eq!(
+            Ok(Value::from(true)),
+            config.coerce_column(Default::default(), "unknown", 1, Value::from(true))
+        );
+        assert!(config
+            .coerce_column(
+                Default::default(),
+                "tags",
+                1,
+                Value::from(vec![Value::from("a")])
+            )
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn errors_without_a_single_source() {
+        let globals = Default::default();
+        assert!(FileConfig::default().build(&globals).await.is_err());
+
+        let mut config = FileConfig::default();
+        config.file.path = Some("path/to/file".into());
+        config.file.url = Some("http://localhost/table.csv".to_string());
+        assert!(config.build(&globals).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn fetches_file_with_etag() {
+        let url = spawn_blackhole_http_server(|request: Request<Body>| async move {
+            let response = if request
+                .headers()
+                .get(header::IF_NONE_MATCH)
+                .is_some_and(|etag| etag == "\"v1\"")
+            {
+                http::Response::builder()
+                    .status(StatusCode::NOT_MODIFIED)
+                    .body(Body::empty())
+            } else {
+                http::Response::builder()
+                    .header(header::ETAG, "\"v1\"")
+                    .body(Body::from("code,name\nAT,Austria\n"))
+            };
+            Ok(response.unwrap())
+        })
+        .await;
+
+        let config = FileConfig {
+            file: FileSettings {
+                url: Some(url.to_string()),
+                ..Default::default()
+            },
+            schema: HashMap::new(),
+        };
+        let client: HttpClient = HttpClient::new(None, &Default::default()).unwrap();
+
+        let (data, etag) = config
+            .fetch_file(&client, &url, Default::default(), None)
+            .await
+            .expect("Failed to fetch file")
+            .expect("File was not returned");
+        assert_eq!(vec!["code", "name"], data.headers);
+        assert_eq!(
+            vec![vec![Value::from("AT"), Value::from("Austria")]],
+            data.data
+        );
+        assert_eq!(Some("\"v1\""), etag.as_deref());
+
+        assert!(config
+            .fetch_file(&client, &url, Default::default(), etag.as_deref())
+            .await
+            .expect("Failed to fetch file")
+            .is_none());
+    }
+
+    #[test]
+    fn refreshes_remote_file_with_indexes() {
+        let file = |data: Vec<Vec<Value>>| {
+            File::new(
+                Default::default(),
+                FileData {
+                    modified: SystemTime::now(),
+                    data,
+                    headers: vec!["code".to_string(), "subnet".to_string()],
+                },
+            )
+        };
+        let mut table = RemoteFile::new(file(vec![vec!["a".into(), "10.0.0.0/8".into()]]));
+        let handle = table.add_index(Case::Sensitive, &["code"]).unwrap();
+        table
+            .add_lookup_index(&LookupIndex::Cidr {
+                field: "subnet".to_string(),
+            })
+            .unwrap();
+
+        table
+            .state
+            .replace(file(vec![
+                vec!["a".into(), "192.168.0.0/16".into()],
+                vec!["b".into(), "10.0.0.0/8".into()],
+            ]))
+            .unwrap();
+
+        assert_eq!(
+            vec![(Case::Sensitive, vec!["code".to_string()])],
+            table.index_fields()
+        );
+        let condition = Condition::Equals {
+            field: "code",
+            value: Value::from("b"),
+        };
+        assert_eq!(
+            Ok(ObjectMap::from([
+                ("code".into(), Value::from("b")),
+                ("subnet".into(), Value::from("10.0.0.0/8")),
+            ])),
+            table.find_table_row(Case::Sensitive, &[condition], None, None, Some(handle))
+        );
+        let condition = Condition::Cidr {
+            field: "subnet",
+            value: "10.1.2.3".parse().unwrap(),
+        };
+        assert_eq!(
+            Ok(ObjectMap::from([
+                ("code".into(), Value::from("b")),
+                ("subnet".into(), Value::from("10.0.0.0/8")),
+            ])),
+            table.find_table_row(Case::Sensitive, &[condition], None, None, None)
+        );
+    }
 }
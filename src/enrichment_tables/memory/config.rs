@@ -1,4 +1,5 @@
 use std::num::NonZeroU64;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::sinks::Healthcheck;
@@ -15,9 +16,12 @@ use vector_lib::{configurable::configurable_component, sink::VectorSink};
 use vrl::path::OwnedTargetPath;
 use vrl::value::Kind;
 
-use crate::config::{EnrichmentTableConfig, SinkConfig, SourceConfig, SourceContext, SourceOutput};
+use crate::config::{
+    EnrichmentTableConfig, GlobalOptions, SinkConfig, SourceConfig, SourceContext, SourceOutput,
+};
 
 use super::internal_events::InternalMetricsConfig;
+use super::persistence::MemoryPersistenceConfig;
 use super::source::MemorySourceConfig;
 
 /// Configuration for the `memory` enrichment table.
@@ -61,6 +65,22 @@ pub struct MemoryConfig {
     #[configurable(derived)]
     #[serde(skip_serializing_if = "vector_lib::serde::is_default")]
     pub source_config: Option<MemorySourceConfig>,
+    /// Configuration for persisting the table across restarts.
+    ///
+    /// When set, snapshots of the table are written to the data directory, and the table is
+    /// restored from its latest snapshot on startup.
+    #[configurable(derived)]
+    #[serde(skip_serializing_if = "vector_lib::serde::is_default")]
+    pub persistence: Option<MemoryPersistenceConfig>,
+    /// The path of a file seeding the table on startup.
+    ///
+    /// The file holds a JSON object per line, with the `key` and `value` of an entry, and
+    /// optionally its remaining `ttl`. This is the shape of the events exported by the table when
+    /// used as a source, so the exported data of a table can be written to a file and used to
+    /// seed it. The seed file is ignored when the table is restored from a snapshot.
+    #[serde(skip_serializing_if = "vector_lib::serde::is_default")]
+    #[configurable(metadata(docs::examples = "/var/lib/vector/seed.ndjson"))]
+    pub seed_path: Option<PathBuf>,
 
     #[serde(skip)]
     memory: Arc<Mutex<Option<Box<Memory>>>>,
@@ -71,6 +91,8 @@ impl PartialEq for MemoryConfig {
         self.ttl == other.ttl
             && self.scan_interval == other.scan_interval
             && self.flush_interval == other.flush_interval
+            && self.persistence == other.persistence
+            && self.seed_path == other.seed_path
     }
 }
 impl Eq for MemoryConfig {}
@@ -85,6 +107,8 @@ impl Default for MemoryConfig {
             max_byte_size: None,
             log_namespace: None,
             source_config: None,
+            persistence: None,
+            seed_path: None,
             internal_metrics: InternalMetricsConfig::default(),
         }
     }
@@ -99,20 +123,39 @@ const fn default_scan_interval() -> NonZeroU64 {
 }
 
 impl MemoryConfig {
-    pub(super) async fn get_or_build_memory(&self) -> Memory {
+    pub(super) async fn get_or_build_memory(
+        &self,
+        globals: &GlobalOptions,
+    ) -> crate::Result<Memory> {
         let mut boxed_memory = self.memory.lock().await;
-        *boxed_memory
-            .get_or_insert_with(|| Box::new(Memory::new(self.clone())))
-            .clone()
+        if let Some(memory) = boxed_memory.as_ref() {
+            return Ok(*memory.clone());
+        }
+
+        let mut memory = Memory::new(self.clone());
+        let snapshot_path = self
+            .persistence
+            .as_ref()
+            .map(|persistence| persistence.snapshot_path(globals))
+            .transpose()?;
+        match (&snapshot_path, &self.seed_path) {
+            (Some(path), _) if path.exists() => memory.restore_snapshot(path),
+            (_, Some(path)) => {
+                let count = memory.load_file(path)?;
+                info!(message = "Seeded memory enrichment table.", path = ?path, count);
+            }
+            _ => {}
+        }
+        memory.snapshot_path = snapshot_path.map(Arc::new);
+
+        *boxed_memory = Some(Box::new(memory.clone()));
+        Ok(memory)
     }
 }
 
 impl EnrichmentTableConfig for MemoryConfig {
-    async fn build(
-        &self,
-        _globals: &crate::config::GlobalOptions,
-    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
-        Ok(Box::new(self.get_or_build_memory().await))
+    async fn build(&self, globals: &GlobalOptions) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        Ok(Box::new(self.get_or_build_memory(globals).await?))
     }
 
     fn sink_config(
@@ -139,8 +182,8 @@ impl EnrichmentTableConfig for MemoryConfig {
 #[async_trait]
 #[typetag::serde(name = "memory_enrichment_table")]
 impl SinkConfig for MemoryConfig {
-    async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let sink = VectorSink::from_event_streamsink(self.get_or_build_memory().await);
+    async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let sink = VectorSink::from_event_streamsink(self.get_or_build_memory(&cx.globals).await?);
 
         Ok((sink, future::ok(()).boxed()))
     }
@@ -158,7 +201,7 @@ impl SinkConfig for MemoryConfig {
 #[typetag::serde(name = "memory_enrichment_table")]
 impl SourceConfig for MemoryConfig {
     async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
-        let memory = self.get_or_build_memory().await;
+        let memory = self.get_or_build_memory(&cx.globals).await?;
 
         let log_namespace = cx.log_namespace(self.log_namespace);
 
@@ -196,6 +239,8 @@ impl std::fmt::Debug for MemoryConfig {
             .field("scan_interval", &self.scan_interval)
             .field("flush_interval", &self.flush_interval)
             .field("max_byte_size", &self.max_byte_size)
+            .field("persistence", &self.persistence)
+            .field("seed_path", &self.seed_path)
             .finish()
     }
 }
@@ -1,6 +1,6 @@
 use metrics::{counter, gauge};
 use vector_lib::configurable::configurable_component;
-use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type, InternalEvent};
 
 /// Configuration of internal metrics for enrichment memory table.
 #[configurable_component]
@@ -152,3 +152,46 @@ impl InternalEvent for MemoryEnrichmentTableInsertFailed<'_> {
         Some("MemoryEnrichmentTableInsertFailed")
     }
 }
+
+#[derive(Debug)]
+pub(crate) struct MemoryEnrichmentTableSnapshotWritten {
+    pub count: usize,
+}
+
+impl InternalEvent for MemoryEnrichmentTableSnapshotWritten {
+    fn emit(self) {
+        debug!(message = "Wrote memory enrichment table snapshot.", count = %self.count);
+        counter!("memory_enrichment_table_snapshots_total",).increment(1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("MemoryEnrichmentTableSnapshotWritten")
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MemoryEnrichmentTableSnapshotFailed {
+    pub error: std::io::Error,
+}
+
+impl InternalEvent for MemoryEnrichmentTableSnapshotFailed {
+    fn emit(self) {
+        error!(
+            message = "Failed writing memory enrichment table snapshot.",
+            error = %self.error,
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        Some("MemoryEnrichmentTableSnapshotFailed")
+    }
+}
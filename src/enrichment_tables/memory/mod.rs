@@ -2,6 +2,7 @@
 
 mod config;
 mod internal_events;
+mod persistence;
 mod source;
 mod table;
 
@@ -0,0 +1,294 @@
+//! Snapshots of the memory enrichment table, and seeding it from files.
+//!
+//! Snapshots and seed files hold a JSON object per line, with the `key`, `value` and remaining
+//! `ttl` of an entry. This is the shape of the events exported by the table when used as a source,
+//! so the output of the source can be written to a file and used to seed another table.
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use snafu::{ResultExt, Snafu};
+use vector_lib::configurable::configurable_component;
+use vrl::value::Value;
+
+use super::{Memory, MemoryEntry};
+use crate::common::persistence::{restore_snapshot, write_snapshot, PersistenceConfig};
+
+/// The subdirectory of the data directory holding the snapshots of memory enrichment tables.
+const SNAPSHOT_SUBDIR: &str = "memory_enrichment_table";
+
+/// Configuration for persisting memory enrichment tables across restarts.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct MemoryPersistenceConfig {
+    /// The name of the snapshot of the table.
+    ///
+    /// Snapshots are written to the `memory_enrichment_table` subdirectory of the data directory,
+    /// so this name must be unique among the memory enrichment tables sharing a data directory.
+    #[configurable(metadata(docs::examples = "sessions"))]
+    pub name: String,
+
+    #[serde(flatten)]
+    pub snapshots: PersistenceConfig,
+}
+
+impl MemoryPersistenceConfig {
+    /// Returns the path of the snapshot file, creating its directory if needed.
+    pub(super) fn snapshot_path(
+        &self,
+        globals: &crate::config::GlobalOptions,
+    ) -> crate::Result<PathBuf> {
+        self.snapshots
+            .snapshot_path(globals, SNAPSHOT_SUBDIR, &format!("{}.ndjson", self.name))
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub(super) enum LoadError {
+    #[snafu(display("Unable to read {}: {}", path.display(), source))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid entry on line {} of {}: {}", line, path.display(), reason))]
+    InvalidEntry {
+        path: PathBuf,
+        line: usize,
+        reason: String,
+    },
+}
+
+/// Parses an entry of a snapshot or seed file into its key and entry.
+///
+/// The `ttl` of the entry is optional, entries without one are given the full TTL of the table.
+fn parse_entry(line: &str, now: Instant, total_ttl: u64) -> Result<(String, MemoryEntry), String> {
+    let Value::Object(mut object) =
+        serde_json::from_str::<Value>(line).map_err(|error| error.to_string())?
+    else {
+        return Err("expected an object".to_string());
+    };
+    let key = match object.remove("key") {
+        Some(Value::Bytes(key)) => String::from_utf8_lossy(&key).into_owned(),
+        Some(_) => return Err("`key` must be a string".to_string()),
+        None => return Err("missing `key`".to_string()),
+    };
+    let value = object.remove("value").ok_or("missing `value`")?;
+    let ttl = match object.remove("ttl") {
+        Some(Value::Integer(ttl)) => u64::try_from(ttl).unwrap_or(0).min(total_ttl),
+        Some(_) => return Err("`ttl` must be an integer".to_string()),
+        None => total_ttl,
+    };
+
+    // The entry is as old as the part of its TTL already spent.
+    let age = Duration::from_secs(total_ttl - ttl);
+    let entry = MemoryEntry {
+        value: serde_json::to_string(&value).map_err(|error| error.to_string())?,
+        update_time: now.checked_sub(age).unwrap_or(now).into(),
+    };
+    Ok((key, entry))
+}
+
+impl Memory {
+    /// Loads the entries of a snapshot or seed file into the table, returning how many were read.
+    ///
+    /// The whole file is read before inserting any entry, so the table is left untouched when the
+    /// file can't be loaded.
+    pub(super) fn load_file(&self, path: &Path) -> Result<usize, LoadError> {
+        let file = File::open(path).context(ReadSnafu { path })?;
+        let now = Instant::now();
+
+        let mut entries = Vec::new();
+        for (idx, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.context(ReadSnafu { path })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = parse_entry(&line, now, self.config.ttl).map_err(|reason| {
+                LoadError::InvalidEntry {
+                    path: path.to_owned(),
+                    line: idx + 1,
+                    reason,
+                }
+            })?;
+            entries.push(entry);
+        }
+
+        let count = entries.len();
+        let mut writer = self.write_handle.lock().expect("mutex poisoned");
+        for (key, entry) in entries {
+            self.insert_entry(&mut writer, key, entry);
+        }
+        self.flush(writer);
+        Ok(count)
+    }
+
+    /// Restores the table from a snapshot.
+    ///
+    /// A snapshot that can't be loaded is moved aside, and the table starts empty.
+    pub(super) fn restore_snapshot(&self, path: &Path) {
+        if let Some(count) = restore_snapshot(path, |path| self.load_file(path)) {
+            info!(message = "Restored memory enrichment table snapshot.", path = ?path, count);
+        }
+    }
+
+    /// Writes a snapshot of the visible entries of the table, returning how many were written.
+    pub(super) fn write_snapshot(&self, path: &Path) -> std::io::Result<usize> {
+        write_snapshot(path, |file| {
+            let now = Instant::now();
+            let mut count = 0;
+            if let Some(reader) = self.get_read_handle().read() {
+                for (key, entry) in reader.iter() {
+                    let Some(entry) = entry.get_one() else {
+                        continue;
+                    };
+                    if entry.expired(now, self.config.ttl) {
+                        continue;
+                    }
+                    let Ok(row) = entry.as_object_map(now, self.config.ttl, key) else {
+                        continue;
+                    };
+                    serde_json::to_writer(&mut *file, &row)?;
+                    file.write_all(b"\n")?;
+                    count += 1;
+                }
+            }
+            Ok(count)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use vector_lib::enrichment::{Case, Condition, Table};
+    use vrl::value::ObjectMap;
+
+    use super::*;
+    use crate::{config::GlobalOptions, enrichment_tables::memory::MemoryConfig};
+
+    fn find(memory: &Memory, key: &str) -> Option<ObjectMap> {
+        let condition = Condition::Equals {
+            field: "key",
+            value: Value::from(key),
+        };
+        memory
+            .find_table_row(Case::Sensitive, &[condition], None, None, None)
+            .ok()
+    }
+
+    #[test]
+    fn restores_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.ndjson");
+
+        let memory = Memory::new(Default::default());
+        memory.handle_value(ObjectMap::from([
+            ("first".into(), Value::from(1)),
+            ("second".into(), Value::from("two")),
+        ]));
+        assert_eq!(2, memory.write_snapshot(&path).unwrap());
+        assert!(!dir.path().join("table.ndjson.tmp").exists());
+
+        let restored = Memory::new(Default::default());
+        assert_eq!(2, restored.load_file(&path).unwrap());
+        assert_eq!(find(&memory, "first"), find(&restored, "first"));
+        assert_eq!(find(&memory, "second"), find(&restored, "second"));
+    }
+
+    #[test]
+    fn seeds_from_exported_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("seed.ndjson");
+        fs::write(
+            &path,
+            concat!(
+                r#"{"key":"aged","value":{"a":1},"ttl":100,"source_type":"memory_enrichment_table"}"#,
+                "\n\n",
+                r#"{"key":"fresh","value":[1,2]}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let memory = Memory::new(Default::default());
+        assert_eq!(2, memory.load_file(&path).unwrap());
+
+        let aged = find(&memory, "aged").unwrap();
+        assert_eq!(Value::from(100), aged["ttl"]);
+        assert_eq!(
+            Value::from(ObjectMap::from([("a".into(), Value::from(1))])),
+            aged["value"]
+        );
+        assert_eq!(
+            Value::from(memory.config.ttl),
+            find(&memory, "fresh").unwrap()["ttl"]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("seed.ndjson");
+        fs::write(&path, "{\"key\":\"a\",\"value\":1}\n{\"value\":2}\n").unwrap();
+
+        let error = Memory::new(Default::default())
+            .load_file(&path)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("line 2"), "{error}");
+        assert!(error.contains("missing `key`"), "{error}");
+    }
+
+    #[test]
+    fn discards_invalid_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.ndjson");
+        let snapshot = "{\"key\":\"a\",\"value\":1}\n{\"key\":\"b\",\"val";
+        fs::write(&path, snapshot).unwrap();
+
+        let memory = Memory::new(Default::default());
+        memory.restore_snapshot(&path);
+        assert_eq!(None, find(&memory, "a"));
+        assert!(!path.exists());
+        assert_eq!(
+            snapshot,
+            fs::read_to_string(dir.path().join("table.ndjson.corrupt")).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn restores_table_from_data_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let globals = GlobalOptions {
+            data_dir: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let config = || {
+            let mut config = MemoryConfig::default();
+            config.persistence = Some(MemoryPersistenceConfig {
+                name: "sessions".to_string(),
+                snapshots: PersistenceConfig::default(),
+            });
+            config
+        };
+
+        let memory = config().get_or_build_memory(&globals).await.unwrap();
+        memory.handle_value(ObjectMap::from([("user".into(), Value::from("alice"))]));
+        memory.snapshot();
+        assert!(dir
+            .path()
+            .join("memory_enrichment_table/sessions.ndjson")
+            .exists());
+
+        let restored = config().get_or_build_memory(&globals).await.unwrap();
+        assert_eq!(
+            Some(Value::from("alice")),
+            find(&restored, "user").map(|row| row["value"].clone())
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use crate::enrichment_tables::memory::internal_events::{
     MemoryEnrichmentTableFlushed, MemoryEnrichmentTableInsertFailed, MemoryEnrichmentTableInserted,
-    MemoryEnrichmentTableRead, MemoryEnrichmentTableReadFailed, MemoryEnrichmentTableTtlExpired,
+    MemoryEnrichmentTableRead, MemoryEnrichmentTableReadFailed,
+    MemoryEnrichmentTableSnapshotFailed, MemoryEnrichmentTableSnapshotWritten,
+    MemoryEnrichmentTableTtlExpired,
 };
 use crate::enrichment_tables::memory::MemoryConfig;
 use crate::SourceSender;
-use std::sync::{Arc, Mutex, MutexGuard};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use std::time::{Duration, Instant};
 
 use evmap::shallow_copy::CopyValue;
@@ -21,7 +24,10 @@ use vector_lib::{ByteSizeOf, EstimatedJsonEncodedSizeOf};
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::stream::BoxStream;
-use vector_lib::enrichment::{Case, Condition, IndexHandle, Table};
+use vector_lib::enrichment::{
+    lookup::{cidr_contains, CidrIndex, PrefixIndex},
+    Case, Condition, IndexHandle, LookupIndex, Table,
+};
 use vector_lib::event::{Event, EventStatus, Finalizable};
 use vector_lib::internal_event::{
     ByteSize, BytesSent, CountByteSize, EventsSent, InternalEventHandle, Output, Protocol,
@@ -34,8 +40,8 @@ use super::source::MemorySource;
 /// Single memory entry containing the value and TTL
 #[derive(Clone, Eq, PartialEq, Hash, ShallowCopy)]
 pub struct MemoryEntry {
-    value: String,
-    update_time: CopyValue<Instant>,
+    pub(super) value: String,
+    pub(super) update_time: CopyValue<Instant>,
 }
 
 impl ByteSizeOf for MemoryEntry {
@@ -69,7 +75,7 @@ impl MemoryEntry {
         ]))
     }
 
-    fn expired(&self, now: Instant, ttl: u64) -> bool {
+    pub(super) fn expired(&self, now: Instant, ttl: u64) -> bool {
         now.duration_since(*self.update_time).as_secs() > ttl
     }
 }
@@ -85,12 +91,43 @@ pub(super) struct MemoryWriter {
     metadata: MemoryMetadata,
 }
 
+/// The lookup structures indexing the keys of the table, built once CIDR or prefix conditions are
+/// used to search it.
+#[derive(Default)]
+pub(super) struct KeyLookups {
+    cidr: Option<CidrIndex<String>>,
+    prefix: Option<PrefixIndex<String>>,
+}
+
+impl KeyLookups {
+    fn insert(&mut self, key: &str) {
+        if let Some(index) = &mut self.cidr {
+            index.insert(key, key.to_owned());
+        }
+        if let Some(index) = &mut self.prefix {
+            index.insert(key, key.to_owned());
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        let item = key.to_owned();
+        if let Some(index) = &mut self.cidr {
+            index.remove(key, &item);
+        }
+        if let Some(index) = &mut self.prefix {
+            index.remove(key, &item);
+        }
+    }
+}
+
 /// A struct that implements [vector_lib::enrichment::Table] to handle loading enrichment data from a memory structure.
 pub struct Memory {
     pub(super) read_handle_factory: evmap::ReadHandleFactory<String, MemoryEntry>,
     pub(super) read_handle: ThreadLocal<evmap::ReadHandle<String, MemoryEntry>>,
     pub(super) write_handle: Arc<Mutex<MemoryWriter>>,
+    pub(super) lookups: Arc<RwLock<KeyLookups>>,
     pub(super) config: MemoryConfig,
+    pub(super) snapshot_path: Option<Arc<PathBuf>>,
 }
 
 impl Memory {
@@ -105,6 +142,8 @@ impl Memory {
                 write_handle,
                 metadata: MemoryMetadata::default(),
             })),
+            lookups: Arc::new(RwLock::new(KeyLookups::default())),
+            snapshot_path: None,
         }
     }
 
@@ -113,7 +152,7 @@ impl Memory {
             .get_or(|| self.read_handle_factory.handle())
     }
 
-    fn handle_value(&self, value: ObjectMap) {
+    pub(super) fn handle_value(&self, value: ObjectMap) {
         let mut writer = self.write_handle.lock().expect("mutex poisoned");
         let now = Instant::now();
 
@@ -130,31 +169,7 @@ impl Memory {
                 value: v,
                 update_time: now.into(),
             };
-            let new_entry_size = new_entry_key.size_of() + new_entry.size_of();
-            if let Some(max_byte_size) = self.config.max_byte_size {
-                if writer
-                    .metadata
-                    .byte_size
-                    .saturating_add(new_entry_size as u64)
-                    > max_byte_size
-                {
-                    // Reject new entries
-                    emit!(MemoryEnrichmentTableInsertFailed {
-                        key: &new_entry_key,
-                        include_key_metric_tag: self.config.internal_metrics.include_key_tag
-                    });
-                    continue;
-                }
-            }
-            writer.metadata.byte_size = writer
-                .metadata
-                .byte_size
-                .saturating_add(new_entry_size as u64);
-            emit!(MemoryEnrichmentTableInserted {
-                key: &new_entry_key,
-                include_key_metric_tag: self.config.internal_metrics.include_key_tag
-            });
-            writer.write_handle.update(new_entry_key, new_entry);
+            self.insert_entry(&mut writer, new_entry_key, new_entry);
         }
 
         if self.config.flush_interval.is_none() {
@@ -162,6 +177,45 @@ impl Memory {
         }
     }
 
+    /// Inserts an entry, unless it would make the table bigger than its maximum size. The entry is
+    /// only visible after the next flush.
+    pub(super) fn insert_entry(
+        &self,
+        writer: &mut MutexGuard<'_, MemoryWriter>,
+        new_entry_key: String,
+        new_entry: MemoryEntry,
+    ) {
+        let new_entry_size = new_entry_key.size_of() + new_entry.size_of();
+        if let Some(max_byte_size) = self.config.max_byte_size {
+            if writer
+                .metadata
+                .byte_size
+                .saturating_add(new_entry_size as u64)
+                > max_byte_size
+            {
+                // Reject new entries
+                emit!(MemoryEnrichmentTableInsertFailed {
+                    key: &new_entry_key,
+                    include_key_metric_tag: self.config.internal_metrics.include_key_tag
+                });
+                return;
+            }
+        }
+        writer.metadata.byte_size = writer
+            .metadata
+            .byte_size
+            .saturating_add(new_entry_size as u64);
+        emit!(MemoryEnrichmentTableInserted {
+            key: &new_entry_key,
+            include_key_metric_tag: self.config.internal_metrics.include_key_tag
+        });
+        self.lookups
+            .write()
+            .expect("poisoned lock")
+            .insert(&new_entry_key);
+        writer.write_handle.update(new_entry_key, new_entry);
+    }
+
     fn scan_and_mark_for_deletion(&self, writer: &mut MutexGuard<'_, MemoryWriter>) -> bool {
         let now = Instant::now();
 
@@ -176,6 +230,7 @@ impl Memory {
                         // Byte size is not reduced at this point, because the actual deletion
                         // will only happen at refresh time
                         writer.write_handle.empty(k.clone());
+                        self.lookups.write().expect("poisoned lock").remove(k);
                         emit!(MemoryEnrichmentTableTtlExpired {
                             key: k,
                             include_key_metric_tag: self.config.internal_metrics.include_key_tag
@@ -196,7 +251,7 @@ impl Memory {
         }
     }
 
-    fn flush(&self, mut writer: MutexGuard<'_, MemoryWriter>) {
+    pub(super) fn flush(&self, mut writer: MutexGuard<'_, MemoryWriter>) {
         writer.write_handle.refresh();
         if let Some(reader) = self.get_read_handle().read() {
             let mut byte_size = 0;
@@ -211,6 +266,62 @@ impl Memory {
         }
     }
 
+    /// Returns the keys matching a lookup, through its lookup structure if one has been built, or
+    /// by scanning all the keys otherwise.
+    fn lookup_keys(
+        &self,
+        indexed: impl FnOnce(&KeyLookups) -> Option<Vec<String>>,
+        matches: impl Fn(&str) -> bool,
+    ) -> Vec<String> {
+        if let Some(keys) = indexed(&self.lookups.read().expect("poisoned lock")) {
+            return keys;
+        }
+        self.get_read_handle()
+            .read()
+            .map(|reader| {
+                reader
+                    .iter()
+                    .map(|(key, _)| key)
+                    .filter(|key| matches(key))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn read_rows(&self, lookup: &str, keys: Vec<String>) -> Result<Vec<ObjectMap>, String> {
+        let now = Instant::now();
+        let mut rows = Vec::new();
+        for key in keys {
+            // Keys are indexed as soon as they are written, before they are visible.
+            if let Some(row) = self.get_read_handle().get_one(key.as_str()) {
+                emit!(MemoryEnrichmentTableRead {
+                    key: &key,
+                    include_key_metric_tag: self.config.internal_metrics.include_key_tag
+                });
+                rows.push(row.as_object_map(now, self.config.ttl, &key)?);
+            }
+        }
+        if rows.is_empty() {
+            emit!(MemoryEnrichmentTableReadFailed {
+                key: lookup,
+                include_key_metric_tag: self.config.internal_metrics.include_key_tag
+            });
+        }
+        Ok(rows)
+    }
+
+    /// Writes a snapshot of the table, if it is persisted.
+    pub(super) fn snapshot(&self) {
+        let Some(path) = &self.snapshot_path else {
+            return;
+        };
+        match self.write_snapshot(path) {
+            Ok(count) => emit!(MemoryEnrichmentTableSnapshotWritten { count }),
+            Err(error) => emit!(MemoryEnrichmentTableSnapshotFailed { error }),
+        }
+    }
+
     pub(crate) fn as_source(
         &self,
         shutdown: ShutdownSignal,
@@ -232,7 +343,9 @@ impl Clone for Memory {
             read_handle_factory: self.read_handle_factory.clone(),
             read_handle: ThreadLocal::new(),
             write_handle: Arc::clone(&self.write_handle),
+            lookups: Arc::clone(&self.lookups),
             config: self.config.clone(),
+            snapshot_path: self.snapshot_path.clone(),
         }
     }
 }
@@ -285,7 +398,27 @@ impl Table for Memory {
                     }
                 }
             }
-            Some(_) => Err("Only equality condition is allowed".to_string()),
+            Some(Condition::Cidr { value, .. }) => {
+                let keys = self.lookup_keys(
+                    |lookups| {
+                        let index = lookups.cidr.as_ref()?;
+                        Some(index.find(*value).cloned().collect())
+                    },
+                    |key| cidr_contains(key, *value),
+                );
+                self.read_rows(&value.to_string(), keys)
+            }
+            Some(Condition::Prefix { value, .. }) => {
+                let keys = self.lookup_keys(
+                    |lookups| {
+                        let index = lookups.prefix.as_ref()?;
+                        Some(index.find(value).cloned().collect())
+                    },
+                    |key| value.starts_with(key),
+                );
+                self.read_rows(value, keys)
+            }
+            Some(_) => Err("Only equality, CIDR and prefix conditions are allowed".to_string()),
             None => Err("Key condition must be specified".to_string()),
         }
     }
@@ -303,6 +436,43 @@ impl Table for Memory {
         Vec::new()
     }
 
+    /// Indexes the keys, which are searched regardless of the field of the condition.
+    fn add_lookup_index(&mut self, lookup: &LookupIndex) -> Result<(), String> {
+        let mut lookups = self.lookups.write().expect("poisoned lock");
+        let keys = || {
+            self.get_read_handle()
+                .read()
+                .map(|reader| {
+                    reader
+                        .iter()
+                        .map(|(key, _)| key.clone())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default()
+        };
+        match lookup {
+            LookupIndex::Cidr { .. } if lookups.cidr.is_none() => {
+                let mut index = CidrIndex::default();
+                for key in keys() {
+                    index.insert(&key, key.clone());
+                }
+                lookups.cidr = Some(index);
+            }
+            LookupIndex::Prefix { .. } if lookups.prefix.is_none() => {
+                let mut index = PrefixIndex::default();
+                for key in keys() {
+                    index.insert(&key, key.clone());
+                }
+                lookups.prefix = Some(index);
+            }
+            LookupIndex::NumericRange { .. } => {
+                return Err("Numeric range conditions are not allowed".to_string());
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Doesn't need reload, data is written directly
     fn needs_reload(&self) -> bool {
         false
@@ -329,6 +499,13 @@ impl StreamSink<Event> for Memory {
         let mut scan_interval = IntervalStream::new(interval(Duration::from_secs(
             self.config.scan_interval.into(),
         )));
+        let mut snapshot_interval = IntervalStream::new(interval(
+            self.config
+                .persistence
+                .as_ref()
+                .map(|persistence| persistence.snapshots.snapshot_interval())
+                .unwrap_or(Duration::MAX),
+        ));
 
         loop {
             tokio::select! {
@@ -363,8 +540,19 @@ impl StreamSink<Event> for Memory {
                     let writer = self.write_handle.lock().expect("mutex poisoned");
                     self.scan(writer);
                 }
+
+                Some(_) = snapshot_interval.next() => {
+                    self.snapshot();
+                }
             }
         }
+
+        if self.snapshot_path.is_some() {
+            // Writes not yet flushed would otherwise be missing from the last snapshot.
+            let writer = self.write_handle.lock().expect("mutex poisoned");
+            self.flush(writer);
+            self.snapshot();
+        }
         Ok(())
     }
 }
@@ -496,6 +684,82 @@ mod tests {
             .is_none());
     }
 
+    #[test]
+    fn finds_rows_in_cidr_blocks() {
+        let mut memory = Memory::new(Default::default());
+        memory.handle_value(ObjectMap::from([
+            ("10.0.0.0/8".into(), Value::from("internal")),
+            ("10.1.0.0/16".into(), Value::from("office")),
+            ("192.168.0.0/16".into(), Value::from("home")),
+        ]));
+        memory
+            .add_lookup_index(&LookupIndex::Cidr {
+                field: "key".to_string(),
+            })
+            .unwrap();
+        // Keys written after the index is built are indexed too.
+        memory.handle_value(ObjectMap::from([(
+            "10.1.2.0/24".into(),
+            Value::from("lab"),
+        )]));
+
+        let condition = Condition::Cidr {
+            field: "key",
+            value: "10.1.2.3".parse().unwrap(),
+        };
+        let values = memory
+            .find_table_rows(Case::Sensitive, &[condition], None, None, None)
+            .unwrap()
+            .into_iter()
+            .map(|row| row["value"].clone())
+            .collect::<Vec<_>>();
+
+        // The most specific blocks come first.
+        assert_eq!(
+            vec![
+                Value::from("lab"),
+                Value::from("office"),
+                Value::from("internal")
+            ],
+            values
+        );
+    }
+
+    #[test]
+    fn finds_row_with_prefix() {
+        let memory = Memory::new(Default::default());
+        memory.handle_value(ObjectMap::from([
+            ("+44".into(), Value::from("uk")),
+            ("+1".into(), Value::from("us")),
+        ]));
+
+        let condition = Condition::Prefix {
+            field: "key",
+            value: "+442071234567".to_string(),
+        };
+
+        assert_eq!(
+            Ok(ObjectMap::from([
+                ("key".into(), Value::from("+44")),
+                ("ttl".into(), Value::from(memory.config.ttl)),
+                ("value".into(), Value::from("uk")),
+            ])),
+            memory.find_table_row(Case::Sensitive, &[condition], None, None, None)
+        );
+    }
+
+    #[test]
+    fn rejects_numeric_range_lookups() {
+        let mut memory = Memory::new(Default::default());
+
+        assert!(memory
+            .add_lookup_index(&LookupIndex::NumericRange {
+                from: "from".to_string(),
+                to: "to".to_string(),
+            })
+            .is_err());
+    }
+
     #[test]
     fn does_not_show_values_before_flush_interval() {
         let ttl = 100;
@@ -870,7 +1134,10 @@ mod tests {
             remove_after_export: false,
             source_key: "test".to_string(),
         });
-        let memory = memory_config.get_or_build_memory().await;
+        let memory = memory_config
+            .get_or_build_memory(&Default::default())
+            .await
+            .unwrap();
         memory.handle_value(ObjectMap::from([("test_key".into(), Value::from(5))]));
 
         let mut events: Vec<Event> = run_and_assert_source_compliance(
@@ -18,12 +18,22 @@ pub mod geoip;
 #[cfg(feature = "enrichment-tables-mmdb")]
 pub mod mmdb;
 
+#[cfg(feature = "enrichment-tables-redis")]
+pub mod redis;
+
+#[cfg(any(feature = "enrichment-tables-redis", feature = "enrichment-tables-sql"))]
+mod remote;
+
+#[cfg(feature = "enrichment-tables-sql")]
+pub mod sql;
+
 /// Configuration options for an [enrichment table](https://vector.dev/docs/reference/glossary/#enrichment-tables) to be used in a
 /// [`remap`](https://vector.dev/docs/reference/configuration/transforms/remap/) transform. Currently supported are:
 ///
-/// * [CSV](https://en.wikipedia.org/wiki/Comma-separated_values) files
+/// * [CSV](https://en.wikipedia.org/wiki/Comma-separated_values), JSON and Parquet files, read from disk or fetched over HTTP(S)
 /// * [MaxMind](https://www.maxmind.com/en/home) databases
 /// * In-memory storage
+/// * Redis, PostgreSQL and SQLite databases, queried on each lookup with a local cache
 ///
 /// For the lookup in the enrichment tables to be as performant as possible, the data is indexed according
 /// to the fields that are used in the search. Note that indices can only be created for fields for which an
@@ -32,6 +42,9 @@ pub mod mmdb;
 /// significantly provided that there are only a few possible rows returned by the exact matches in the
 /// condition. We don't recommend using a condition that uses only date range searches.
 ///
+/// CIDR (`contains_ip`), numeric range (`to_field` and `contains`) and prefix (`prefix_of`) searches
+/// use dedicated lookup structures instead, so they don't need a sequential scan of the data.
+///
 ///
 #[configurable_component(global_option("enrichment_tables"))]
 #[derive(Clone, Debug)]
@@ -63,14 +76,25 @@ pub enum EnrichmentTables {
     /// [maxmind]: https://www.maxmind.com/
     #[cfg(feature = "enrichment-tables-mmdb")]
     Mmdb(mmdb::MmdbConfig),
+
+    /// Looks up rows in [Redis][redis] hashes or string values by key.
+    ///
+    /// [redis]: https://redis.io/
+    #[cfg(feature = "enrichment-tables-redis")]
+    Redis(redis::RedisConfig),
+
+    /// Looks up rows with a query against a PostgreSQL or SQLite database.
+    #[cfg(feature = "enrichment-tables-sql")]
+    Sql(sql::SqlConfig),
 }
 
 impl GenerateConfig for EnrichmentTables {
     fn generate_config() -> toml::Value {
         toml::Value::try_from(Self::File(file::FileConfig {
             file: file::FileSettings {
-                path: "path/to/file".into(),
+                path: Some("path/to/file".into()),
                 encoding: file::Encoding::default(),
+                ..Default::default()
             },
             schema: Default::default(),
         }))
@@ -0,0 +1,174 @@
+//! Handles enrichment tables for `type = redis`.
+//!
+//! Rows are looked up in [Redis][redis] by key, as hashes or as string values.
+//!
+//! [redis]: https://redis.io/
+use std::{collections::HashMap, sync::Arc};
+
+use futures::{future::BoxFuture, FutureExt};
+use redis::{aio::ConnectionManager, AsyncCommands};
+use vector_lib::configurable::configurable_component;
+use vector_lib::enrichment::Table;
+use vrl::value::{ObjectMap, Value};
+
+use super::remote::{Fetch, LookupConfig, RemoteTable};
+use crate::config::{EnrichmentTableConfig, GenerateConfig};
+
+/// How the rows are stored in Redis.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DataType {
+    /// Each row is a hash, whose fields are the fields of the row.
+    #[default]
+    Hash,
+
+    /// Each row is a string value, found in the `value` field of the row.
+    String,
+}
+
+/// Configuration for the `redis` enrichment table.
+#[configurable_component(enrichment_table("redis"))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RedisConfig {
+    /// The URL of the Redis endpoint to connect to.
+    ///
+    /// The URL _must_ take the form of `protocol://server:port/db` where the protocol can either be
+    /// `redis` or `rediss` for connections secured via TLS.
+    #[configurable(metadata(docs::examples = "redis://127.0.0.1:6379/0"))]
+    pub endpoint: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub data_type: DataType,
+
+    /// The field of the lookup conditions holding the key of the row.
+    ///
+    /// Lookups need an equality condition on this field, and the key is added to the rows found
+    /// under this field.
+    #[serde(default = "default_key_field")]
+    #[configurable(metadata(docs::examples = "user_id"))]
+    pub key_field: String,
+
+    /// A prefix added to the keys looked up.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "users:"))]
+    pub key_prefix: String,
+
+    #[configurable(derived)]
+    #[serde(flatten)]
+    pub lookup: LookupConfig,
+}
+
+fn default_key_field() -> String {
+    "key".to_string()
+}
+
+impl GenerateConfig for RedisConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"endpoint = "redis://127.0.0.1:6379/0"
+            key_prefix = "users:"
+        "#,
+        )
+        .unwrap()
+    }
+}
+
+impl EnrichmentTableConfig for RedisConfig {
+    async fn build(
+        &self,
+        _: &crate::config::GlobalOptions,
+    ) -> crate::Result<Box<dyn Table + Send + Sync>> {
+        let client = redis::Client::open(self.endpoint.as_str())?;
+        let config = Arc::new(self.clone());
+
+        Ok(Box::new(
+            RemoteTable::spawn(
+                "redis",
+                vec![self.key_field.clone()],
+                self.lookup.clone(),
+                move || {
+                    async move {
+                        Ok(RedisFetcher {
+                            connection: client.get_connection_manager().await?,
+                            config,
+                        })
+                    }
+                    .boxed()
+                },
+            )
+            .await?,
+        ))
+    }
+}
+
+/// Fetches the rows of a Redis enrichment table.
+struct RedisFetcher {
+    connection: ConnectionManager,
+    config: Arc<RedisConfig>,
+}
+
+impl Fetch for RedisFetcher {
+    fn fetch(&self, key: Vec<Value>) -> BoxFuture<'static, crate::Result<Vec<ObjectMap>>> {
+        fetch_row(self.connection.clone(), Arc::clone(&self.config), key).boxed()
+    }
+}
+
+async fn fetch_row(
+    mut connection: ConnectionManager,
+    config: Arc<RedisConfig>,
+    mut key: Vec<Value>,
+) -> crate::Result<Vec<ObjectMap>> {
+    let key = key.pop().unwrap_or(Value::Null);
+    let redis_key = format!("{}{}", config.key_prefix, key.to_string_lossy());
+
+    let mut row = match config.data_type {
+        DataType::Hash => {
+            let fields: HashMap<String, Vec<u8>> = connection.hgetall(&redis_key).await?;
+            if fields.is_empty() {
+                return Ok(Vec::new());
+            }
+            fields
+                .into_iter()
+                .map(|(field, value)| (field.into(), Value::Bytes(value.into())))
+                .collect::<ObjectMap>()
+        }
+        DataType::String => match connection.get::<_, Option<Vec<u8>>>(&redis_key).await? {
+            Some(value) => ObjectMap::from([("value".into(), Value::Bytes(value.into()))]),
+            None => return Ok(Vec::new()),
+        },
+    };
+    row.insert(config.key_field.as_str().into(), key);
+    Ok(vec![row])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<RedisConfig>();
+    }
+
+    #[test]
+    fn parses_config() {
+        let config: RedisConfig = toml::from_str(
+            r#"
+            endpoint = "redis://127.0.0.1:6379/0"
+            data_type = "string"
+            key_field = "user_id"
+            timeout_ms = 50
+            negative_cache_ttl_secs = 0
+        "#,
+        )
+        .unwrap();
+
+        assert_eq!(DataType::String, config.data_type);
+        assert_eq!("user_id", config.key_field);
+        assert_eq!(50, config.lookup.timeout_ms.as_millis());
+        assert!(config.lookup.negative_cache_ttl_secs.is_zero());
+        assert_eq!(10_000, config.lookup.cache_size);
+    }
+}
@@ -0,0 +1,617 @@
+//! Shared implementation of the enrichment tables looking up rows in external databases.
+//!
+//! The `Table` lookups are synchronous, so the queries are sent to a dedicated thread running its
+//! own runtime, and the lookups wait for their results for a bounded time. Lookups timing out or
+//! failing find no rows rather than stalling the transform, and once several of them fail in a
+//! row, lookups find no rows right away for a while instead of each waiting for the timeout. The
+//! results are kept in a local LRU cache, including the lookups finding no rows.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{mpsc, Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use futures::future::BoxFuture;
+use lru::LruCache;
+use serde_with::serde_as;
+use tokio::sync::{mpsc as async_mpsc, oneshot};
+use vector_lib::configurable::configurable_component;
+use vector_lib::enrichment::{
+    lookup::{cidr_contains, numeric_value},
+    Case, Condition, IndexHandle, Table,
+};
+use vrl::value::{ObjectMap, Value};
+
+use crate::internal_events::{
+    RemoteEnrichmentTableLookupError, RemoteEnrichmentTableLookupTimeout,
+    RemoteEnrichmentTableLookupsSuspended,
+};
+
+/// Timeout and caching settings of the lookups.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug, Derivative, Eq, PartialEq)]
+#[derivative(Default)]
+pub struct LookupConfig {
+    /// The maximum time to wait for the result of a lookup, in milliseconds.
+    ///
+    /// Lookups timing out find no rows, so a slow database delays events by at most this long.
+    #[serde(default = "default_timeout_ms")]
+    #[serde_as(as = "serde_with::DurationMilliSeconds<u64>")]
+    #[derivative(Default(value = "default_timeout_ms()"))]
+    #[configurable(metadata(docs::human_name = "Lookup Timeout"))]
+    pub timeout_ms: Duration,
+
+    /// The maximum number of lookup results kept in the local cache.
+    ///
+    /// Set to `0` to disable the cache.
+    #[serde(default = "default_cache_size")]
+    #[derivative(Default(value = "default_cache_size()"))]
+    pub cache_size: usize,
+
+    /// How long the rows found by a lookup are cached, in seconds.
+    #[serde(default = "default_cache_ttl_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_cache_ttl_secs()"))]
+    #[configurable(metadata(docs::human_name = "Cache TTL"))]
+    pub cache_ttl_secs: Duration,
+
+    /// How long lookups finding no rows are cached, in seconds.
+    ///
+    /// Lookups that time out or fail are not cached.
+    #[serde(default = "default_negative_cache_ttl_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_negative_cache_ttl_secs()"))]
+    #[configurable(metadata(docs::human_name = "Negative Cache TTL"))]
+    pub negative_cache_ttl_secs: Duration,
+
+    /// How long lookups are suspended, in seconds, once several lookups in a row time out or fail.
+    ///
+    /// Suspended lookups find no rows without querying the database, so that an unresponsive
+    /// database doesn't delay every event by the lookup timeout. The next lookup once this
+    /// elapses queries the database again.
+    #[serde(default = "default_failure_backoff_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[derivative(Default(value = "default_failure_backoff_secs()"))]
+    #[configurable(metadata(docs::human_name = "Failure Backoff"))]
+    pub failure_backoff_secs: Duration,
+}
+
+const fn default_timeout_ms() -> Duration {
+    Duration::from_millis(100)
+}
+
+const fn default_cache_size() -> usize {
+    10_000
+}
+
+const fn default_cache_ttl_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+const fn default_negative_cache_ttl_secs() -> Duration {
+    Duration::from_secs(10)
+}
+
+const fn default_failure_backoff_secs() -> Duration {
+    Duration::from_secs(5)
+}
+
+/// How many lookups in a row time out or fail before the lookups are suspended.
+const FAILURE_THRESHOLD: usize = 5;
+
+/// How many lookups can wait to be sent to the database.
+const MAX_PENDING_LOOKUPS: usize = 1024;
+
+/// Fetches the rows matching the key of a lookup from an external database.
+pub trait Fetch: Send + Sync + 'static {
+    /// Fetches the rows matching the values of the key fields, in the order of the key fields.
+    fn fetch(&self, key: Vec<Value>) -> BoxFuture<'static, crate::Result<Vec<ObjectMap>>>;
+}
+
+/// A lookup sent to the thread querying the database.
+struct Request {
+    key: Vec<Value>,
+    reply: mpsc::SyncSender<crate::Result<Vec<ObjectMap>>>,
+}
+
+/// The cached result of a lookup.
+struct CacheEntry {
+    rows: Arc<Vec<ObjectMap>>,
+    expires_at: Instant,
+}
+
+/// Tracks the lookups failing in a row, to suspend the lookups while the database is unresponsive.
+#[derive(Default)]
+struct Failures {
+    consecutive: usize,
+    suspended_until: Option<Instant>,
+}
+
+/// A table looking up its rows in an external database by the values of its key fields.
+#[derive(Clone)]
+pub struct RemoteTable {
+    kind: &'static str,
+    key_fields: Arc<Vec<String>>,
+    config: LookupConfig,
+    requests: async_mpsc::Sender<Request>,
+    cache: Option<Arc<Mutex<LruCache<String, CacheEntry>>>>,
+    failures: Arc<Mutex<Failures>>,
+}
+
+impl RemoteTable {
+    /// Starts the thread querying the database with the fetcher returned by `connect`, and
+    /// returns the table once it has connected.
+    pub async fn spawn<C, F>(
+        kind: &'static str,
+        key_fields: Vec<String>,
+        config: LookupConfig,
+        connect: C,
+    ) -> crate::Result<Self>
+    where
+        C: FnOnce() -> BoxFuture<'static, crate::Result<F>> + Send + 'static,
+        F: Fetch,
+    {
+        let (requests, mut receiver) = async_mpsc::channel::<Request>(MAX_PENDING_LOOKUPS);
+        let (connected, connection) = oneshot::channel();
+        let timeout = config.timeout_ms;
+
+        std::thread::Builder::new()
+            .name(format!("{kind}-enrichment-table"))
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(error) => {
+                        _ = connected.send(Err(error.into()));
+                        return;
+                    }
+                };
+
+                runtime.block_on(async move {
+                    let fetcher = match connect().await {
+                        Ok(fetcher) => Arc::new(fetcher),
+                        Err(error) => {
+                            _ = connected.send(Err(error));
+                            return;
+                        }
+                    };
+                    _ = connected.send(Ok(()));
+
+                    // Runs until all the clones of the table have been dropped.
+                    while let Some(request) = receiver.recv().await {
+                        let fetch = fetcher.fetch(request.key);
+                        tokio::spawn(async move {
+                            let result = tokio::time::timeout(timeout, fetch)
+                                .await
+                                .unwrap_or_else(|_| Err("lookup timed out".into()));
+                            // The lookup may have stopped waiting for the result.
+                            _ = request.reply.send(result);
+                        });
+                    }
+                });
+            })?;
+
+        connection
+            .await
+            .map_err(|_| "the enrichment table thread stopped unexpectedly")??;
+
+        let cache = NonZeroUsize::new(config.cache_size)
+            .map(|size| Arc::new(Mutex::new(LruCache::new(size))));
+
+        Ok(Self {
+            kind,
+            key_fields: Arc::new(key_fields),
+            config,
+            requests,
+            cache,
+            failures: Arc::default(),
+        })
+    }
+
+    /// Returns the rows matching the key, from the cache or from the database.
+    ///
+    /// Lookups that time out or fail find no rows, as do the lookups while they are suspended.
+    fn lookup(&self, key: Vec<Value>) -> Arc<Vec<ObjectMap>> {
+        let cache_key = serde_json::to_string(&key).expect("values should serialize to JSON");
+        if let Some(cache) = &self.cache {
+            let mut cache = cache.lock().expect("poisoned lock");
+            let cached = cache
+                .get(&cache_key)
+                .filter(|entry| entry.expires_at > Instant::now())
+                .map(|entry| Arc::clone(&entry.rows));
+            match cached {
+                Some(rows) => return rows,
+                // Drops the expired entry, if any.
+                None => _ = cache.pop(&cache_key),
+            }
+        }
+
+        if self.is_suspended() {
+            return Arc::default();
+        }
+
+        let (reply, result) = mpsc::sync_channel(1);
+        let result = match self.requests.try_send(Request { key, reply }) {
+            Ok(()) => match result.recv_timeout(self.config.timeout_ms) {
+                Ok(result) => result,
+                Err(_) => {
+                    emit!(RemoteEnrichmentTableLookupTimeout { table: self.kind });
+                    self.record_failure();
+                    return Arc::default();
+                }
+            },
+            Err(async_mpsc::error::TrySendError::Full(_)) => {
+                Err("too many lookups are waiting for the database".into())
+            }
+            Err(async_mpsc::error::TrySendError::Closed(_)) => {
+                Err("the enrichment table thread has stopped".into())
+            }
+        };
+
+        match result {
+            Ok(rows) => {
+                self.record_success();
+                let ttl = if rows.is_empty() {
+                    self.config.negative_cache_ttl_secs
+                } else {
+                    self.config.cache_ttl_secs
+                };
+                let rows = Arc::new(rows);
+                if let Some(cache) = self.cache.as_ref().filter(|_| !ttl.is_zero()) {
+                    cache.lock().expect("poisoned lock").put(
+                        cache_key,
+                        CacheEntry {
+                            rows: Arc::clone(&rows),
+                            expires_at: Instant::now() + ttl,
+                        },
+                    );
+                }
+                rows
+            }
+            Err(error) => {
+                emit!(RemoteEnrichmentTableLookupError {
+                    table: self.kind,
+                    error,
+                });
+                self.record_failure();
+                Arc::default()
+            }
+        }
+    }
+
+    fn is_suspended(&self) -> bool {
+        let failures = self.failures.lock().expect("poisoned lock");
+        failures
+            .suspended_until
+            .is_some_and(|suspended_until| Instant::now() < suspended_until)
+    }
+
+    fn record_success(&self) {
+        *self.failures.lock().expect("poisoned lock") = Failures::default();
+    }
+
+    /// Suspends the lookups once enough of them failed in a row, and again whenever the first
+    /// lookup after a suspension fails.
+    fn record_failure(&self) {
+        let mut failures = self.failures.lock().expect("poisoned lock");
+        failures.consecutive += 1;
+        if failures.consecutive >= FAILURE_THRESHOLD {
+            failures.suspended_until = Some(Instant::now() + self.config.failure_backoff_secs);
+            emit!(RemoteEnrichmentTableLookupsSuspended {
+                table: self.kind,
+                duration: self.config.failure_backoff_secs,
+            });
+        }
+    }
+
+    /// Returns the values of the key fields from the equality conditions.
+    fn key<'a>(&self, condition: &'a [Condition<'a>]) -> Result<Vec<Value>, String> {
+        self.key_fields
+            .iter()
+            .map(|key_field| {
+                condition
+                    .iter()
+                    .find_map(|condition| match condition {
+                        Condition::Equals { field, value } if field == key_field => {
+                            Some(value.clone())
+                        }
+                        _ => None,
+                    })
+                    .ok_or_else(|| format!("an equality condition on '{key_field}' is required"))
+            })
+            .collect()
+    }
+
+    fn is_key_field(&self, field: &str) -> bool {
+        self.key_fields.iter().any(|key_field| key_field == field)
+    }
+}
+
+impl Table for RemoteTable {
+    fn find_table_row<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        index: Option<IndexHandle>,
+    ) -> Result<ObjectMap, String> {
+        let mut rows = self.find_table_rows(case, condition, select, wildcard, index)?;
+
+        match rows.pop() {
+            Some(row) if rows.is_empty() => Ok(row),
+            Some(_) => Err("more than one row found".to_string()),
+            None => Err("no rows found".to_string()),
+        }
+    }
+
+    fn find_table_rows<'a>(
+        &self,
+        case: Case,
+        condition: &'a [Condition<'a>],
+        select: Option<&'a [String]>,
+        wildcard: Option<&Value>,
+        _: Option<IndexHandle>,
+    ) -> Result<Vec<ObjectMap>, String> {
+        if case == Case::Insensitive {
+            return Err("case insensitive lookups are not supported".to_string());
+        }
+
+        let key = self.key(condition)?;
+        let mut rows = self.lookup(key);
+        if let Some(wildcard) = wildcard.filter(|_| rows.is_empty()) {
+            rows = self.lookup(vec![wildcard.clone(); self.key_fields.len()]);
+        }
+
+        // The key fields have been matched by the database, and may not be in the rows.
+        Ok(rows
+            .iter()
+            .filter(|row| {
+                condition.iter().all(|condition| match condition {
+                    Condition::Equals { field, .. } if self.is_key_field(field) => true,
+                    condition => row_matches(condition, row, wildcard),
+                })
+            })
+            .map(|row| match select {
+                Some(select) => row
+                    .iter()
+                    .filter(|(field, _)| select.iter().any(|selected| selected == field.as_str()))
+                    .map(|(field, value)| (field.clone(), value.clone()))
+                    .collect(),
+                None => row.clone(),
+            })
+            .collect())
+    }
+
+    fn add_index(&mut self, case: Case, fields: &[&str]) -> Result<IndexHandle, String> {
+        if case == Case::Insensitive {
+            return Err("case insensitive lookups are not supported".to_string());
+        }
+        match self
+            .key_fields
+            .iter()
+            .find(|key_field| !fields.contains(&key_field.as_str()))
+        {
+            Some(key_field) => Err(format!(
+                "an equality condition on '{key_field}' is required"
+            )),
+            None => Ok(IndexHandle(0)),
+        }
+    }
+
+    fn index_fields(&self) -> Vec<(Case, Vec<String>)> {
+        Vec::new()
+    }
+
+    /// The rows are looked up in the database, so the table never needs to be reloaded.
+    fn needs_reload(&self) -> bool {
+        false
+    }
+}
+
+impl std::fmt::Debug for RemoteTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} table keyed by {:?}", self.kind, self.key_fields)
+    }
+}
+
+/// Does the row match the condition?
+fn row_matches(condition: &Condition, row: &ObjectMap, wildcard: Option<&Value>) -> bool {
+    match condition {
+        Condition::Equals { field, value } => row
+            .get(*field)
+            .is_some_and(|current| current == value || Some(current) == wildcard),
+        Condition::BetweenDates { field, from, to } => {
+            matches!(row.get(*field), Some(Value::Timestamp(date)) if from <= date && date <= to)
+        }
+        Condition::FromDate { field, from } => {
+            matches!(row.get(*field), Some(Value::Timestamp(date)) if from <= date)
+        }
+        Condition::ToDate { field, to } => {
+            matches!(row.get(*field), Some(Value::Timestamp(date)) if date <= to)
+        }
+        Condition::Cidr { field, value } => matches!(
+            row.get(*field),
+            Some(Value::Bytes(cidr))
+                if std::str::from_utf8(cidr).is_ok_and(|cidr| cidr_contains(cidr, *value))
+        ),
+        Condition::NumericRange { from, to, value } => matches!(
+            (
+                row.get(*from).and_then(numeric_value),
+                row.get(to.as_str()).and_then(numeric_value),
+            ),
+            (Some(from), Some(to)) if from <= *value && *value <= to
+        ),
+        Condition::Prefix { field, value } => matches!(
+            row.get(*field),
+            Some(Value::Bytes(prefix)) if value.as_bytes().starts_with(prefix)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use futures::FutureExt;
+
+    use super::*;
+
+    /// Finds a row for the keys starting with `row`, after sleeping for the keys starting with
+    /// `slow`, and counts the fetches.
+    struct TestFetcher {
+        fetches: Arc<AtomicUsize>,
+    }
+
+    impl Fetch for TestFetcher {
+        fn fetch(&self, key: Vec<Value>) -> BoxFuture<'static, crate::Result<Vec<ObjectMap>>> {
+            self.fetches.fetch_add(1, Ordering::Relaxed);
+            let key = key[0].to_string_lossy().into_owned();
+            async move {
+                if key.starts_with("slow") {
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+                Ok(if key.starts_with("row") {
+                    vec![ObjectMap::from([
+                        ("id".into(), Value::from(key)),
+                        ("region".into(), Value::from("eu")),
+                    ])]
+                } else {
+                    Vec::new()
+                })
+            }
+            .boxed()
+        }
+    }
+
+    async fn table(fetches: &Arc<AtomicUsize>) -> RemoteTable {
+        let fetches = Arc::clone(fetches);
+        RemoteTable::spawn(
+            "test",
+            vec!["id".to_string()],
+            LookupConfig {
+                timeout_ms: Duration::from_millis(50),
+                ..Default::default()
+            },
+            move || async move { Ok(TestFetcher { fetches }) }.boxed(),
+        )
+        .await
+        .unwrap()
+    }
+
+    fn condition(id: &str) -> Vec<Condition<'static>> {
+        vec![Condition::Equals {
+            field: "id",
+            value: Value::from(id),
+        }]
+    }
+
+    #[tokio::test]
+    async fn finds_and_caches_rows() {
+        let fetches = Arc::default();
+        let table = table(&fetches).await;
+
+        for _ in 0..2 {
+            assert_eq!(
+                Ok(ObjectMap::from([("region".into(), Value::from("eu"))])),
+                table.find_table_row(
+                    Case::Sensitive,
+                    &condition("row1"),
+                    Some(&["region".to_string()]),
+                    None,
+                    None
+                )
+            );
+            assert_eq!(
+                Ok(Vec::new()),
+                table.find_table_rows(Case::Sensitive, &condition("missing"), None, None, None)
+            );
+        }
+        assert_eq!(2, fetches.load(Ordering::Relaxed));
+
+        let filtered = [
+            condition("row1").remove(0),
+            Condition::Equals {
+                field: "region",
+                value: Value::from("us"),
+            },
+        ];
+        assert_eq!(
+            Ok(Vec::new()),
+            table.find_table_rows(Case::Sensitive, &filtered, None, None, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn degrades_to_not_found_on_timeout() {
+        let fetches = Arc::default();
+        let table = table(&fetches).await;
+
+        let started = Instant::now();
+        assert_eq!(
+            Ok(Vec::new()),
+            table.find_table_rows(Case::Sensitive, &condition("slow-row"), None, None, None)
+        );
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // Timed out lookups are not cached.
+        _ = table.find_table_rows(Case::Sensitive, &condition("slow-row"), None, None, None);
+        assert_eq!(2, fetches.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn suspends_lookups_after_failures() {
+        let fetches = Arc::default();
+        let table = table(&fetches).await;
+
+        for index in 0..FAILURE_THRESHOLD {
+            _ = table.find_table_rows(
+                Case::Sensitive,
+                &condition(&format!("slow-row{index}")),
+                None,
+                None,
+                None,
+            );
+        }
+        assert_eq!(FAILURE_THRESHOLD, fetches.load(Ordering::Relaxed));
+
+        // The database isn't queried while the lookups are suspended.
+        let started = Instant::now();
+        assert_eq!(
+            Ok(Vec::new()),
+            table.find_table_rows(Case::Sensitive, &condition("row1"), None, None, None)
+        );
+        assert!(started.elapsed() < Duration::from_millis(50));
+        assert_eq!(FAILURE_THRESHOLD, fetches.load(Ordering::Relaxed));
+
+        // The first lookup once the suspension elapses queries the database again.
+        table.failures.lock().unwrap().suspended_until = Some(Instant::now());
+        assert_eq!(
+            1,
+            table
+                .find_table_rows(Case::Sensitive, &condition("row1"), None, None, None)
+                .unwrap()
+                .len()
+        );
+        assert_eq!(0, table.failures.lock().unwrap().consecutive);
+    }
+
+    #[tokio::test]
+    async fn requires_key_fields() {
+        let mut table = table(&Arc::default()).await;
+
+        assert_eq!(
+            Ok(IndexHandle(0)),
+            table.add_index(Case::Sensitive, &["id"])
+        );
+        assert!(table.add_index(Case::Sensitive, &["region"]).is_err());
+        assert!(table.add_index(Case::Insensitive, &["id"]).is_err());
+        assert!(table
+            .find_table_rows(Case::Sensitive, &[], None, None, None)
+            .is_err());
+    }
+}
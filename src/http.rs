@@ -2,8 +2,10 @@
 use futures::future::BoxFuture;
 use headers::{Authorization, HeaderMapExt};
 use http::{
-    header::HeaderValue, request::Builder, uri::InvalidUri, HeaderMap, Request, Response, Uri,
-    Version,
+    header::{HeaderName, HeaderValue},
+    request::Builder,
+    uri::InvalidUri,
+    HeaderMap, Request, Response, StatusCode, Uri, Version,
 };
 use hyper::{
     body::{Body, HttpBody},
@@ -38,9 +40,20 @@ use crate::aws::AwsAuthentication;
 use crate::{
     config::ProxyConfig,
     internal_events::{http_client, HttpServerRequestReceived, HttpServerResponseSent},
+    sinks::util::load_balance::LoadBalancer,
     tls::{tls_connector_builder, MaybeTlsSettings, TlsError},
 };
 
+pub use self::{
+    hmac::{hmac_signature, signature_timestamp, HmacAlgorithm},
+    oauth2::{OAuth2Error, OAuth2TokenSource},
+};
+
+pub(crate) use self::hmac::{default_signature_header, default_timestamp_header};
+
+mod hmac;
+mod oauth2;
+
 pub mod status {
     pub const FORBIDDEN: u16 = 403;
     pub const NOT_FOUND: u16 = 404;
@@ -60,6 +73,8 @@ pub enum HttpError {
     CallRequest { source: hyper::Error },
     #[snafu(display("Failed to build HTTP request: {}", source))]
     BuildRequest { source: http::Error },
+    #[snafu(display("Failed to authorize HTTP request: {}", source))]
+    Authorize { source: Box<OAuth2Error> },
 }
 
 impl HttpError {
@@ -67,6 +82,7 @@ impl HttpError {
         match self {
             HttpError::BuildRequest { .. } | HttpError::MakeProxyConnector { .. } => false,
             HttpError::CallRequest { .. }
+            | HttpError::Authorize { .. }
             | HttpError::BuildTlsConnector { .. }
             | HttpError::MakeHttpsConnector { .. } => true,
         }
@@ -80,6 +96,8 @@ pub struct HttpClient<B = Body> {
     client: Client<HttpProxyConnector, B>,
     user_agent: HeaderValue,
     proxy_connector: HttpProxyConnector,
+    load_balancer: Option<LoadBalancer>,
+    oauth2: Option<OAuth2TokenSource>,
 }
 
 impl<B> HttpClient<B>
@@ -112,9 +130,26 @@ where
             client,
             user_agent,
             proxy_connector,
+            load_balancer: None,
+            oauth2: None,
         })
     }
 
+    /// Distributes the requests sent through the client across the endpoints of a load balancer.
+    #[allow(clippy::missing_const_for_fn)] // constant functions cannot evaluate destructors
+    pub fn with_load_balancer(mut self, load_balancer: Option<LoadBalancer>) -> Self {
+        self.load_balancer = load_balancer;
+        self
+    }
+
+    /// Authenticates the requests sent through the client with the access tokens of an OAuth2
+    /// token source.
+    #[allow(clippy::missing_const_for_fn)] // constant functions cannot evaluate destructors
+    pub fn with_oauth2(mut self, oauth2: Option<OAuth2TokenSource>) -> Self {
+        self.oauth2 = oauth2;
+        self
+    }
+
     pub fn send(
         &self,
         mut request: Request<B>,
@@ -122,14 +157,35 @@ where
         let span = tracing::info_span!("http");
         let _enter = span.enter();
 
+        let endpoint = self
+            .load_balancer
+            .as_ref()
+            .and_then(|load_balancer| load_balancer.route(&mut request));
         default_request_headers(&mut request, &self.user_agent);
         self.maybe_add_proxy_headers(&mut request);
 
         emit!(http_client::AboutToSendHttpRequest { request: &request });
 
-        let response = self.client.request(request);
+        let client = self.client.clone();
+        let oauth2 = self.oauth2.clone();
 
         let fut = async move {
+            let authorization = match &oauth2 {
+                Some(oauth2) => {
+                    let authorization =
+                        oauth2
+                            .authorization()
+                            .await
+                            .map_err(|error| HttpError::Authorize {
+                                source: Box::new(error),
+                            })?;
+                    request.headers_mut().typed_insert(authorization.clone());
+                    Some(authorization)
+                }
+                None => None,
+            };
+            let response = client.request(request);
+
             // Capture the time right before we issue the request.
             // Request doesn't start the processing until we start polling it.
             let before = std::time::Instant::now();
@@ -141,6 +197,14 @@ where
             // the response or error.
             let roundtrip = before.elapsed();
 
+            if let Some(endpoint) = endpoint {
+                endpoint.record(
+                    response_result
+                        .as_ref()
+                        .is_ok_and(|response| !response.status().is_server_error()),
+                );
+            }
+
             // Handle the errors and extract the response.
             let response = response_result
                 .inspect_err(|error| {
@@ -149,6 +213,12 @@ where
                 })
                 .context(CallRequestSnafu)?;
 
+            if let (Some(oauth2), Some(authorization)) = (&oauth2, &authorization) {
+                if response.status() == StatusCode::UNAUTHORIZED {
+                    oauth2.invalidate(authorization).await;
+                }
+            }
+
             // Emit the response into the internal events system.
             emit!(http_client::GotHttpResponse {
                 response: &response,
@@ -253,6 +323,8 @@ impl<B> Clone for HttpClient<B> {
             client: self.client.clone(),
             user_agent: self.user_agent.clone(),
             proxy_connector: self.proxy_connector.clone(),
+            load_balancer: self.load_balancer.clone(),
+            oauth2: self.oauth2.clone(),
         }
     }
 }
@@ -262,6 +334,8 @@ impl<B> fmt::Debug for HttpClient<B> {
         f.debug_struct("HttpClient")
             .field("client", &self.client)
             .field("user_agent", &self.user_agent)
+            .field("load_balancer", &self.load_balancer)
+            .field("oauth2", &self.oauth2)
             .finish()
     }
 }
@@ -353,6 +427,240 @@ impl Auth {
     }
 }
 
+/// Configuration of the authentication strategy for HTTP requests.
+///
+/// HTTP authentication should be used with HTTPS only, as the authentication credentials are passed as an
+/// HTTP header without any additional encryption beyond what is provided by the transport itself.
+// Along with the strategies of `Auth`, this supports strategies with runtime state, which only the
+// components building an `HttpAuthenticator` accept.
+#[configurable_component]
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[serde(deny_unknown_fields, rename_all = "snake_case", tag = "strategy")]
+#[configurable(metadata(docs::enum_tag_description = "The authentication strategy to use."))]
+pub enum HttpClientAuth {
+    /// Basic authentication.
+    ///
+    /// The username and password are concatenated and encoded using [base64][base64].
+    ///
+    /// [base64]: https://en.wikipedia.org/wiki/Base64
+    Basic {
+        /// The basic authentication username.
+        #[configurable(metadata(docs::examples = "${USERNAME}"))]
+        #[configurable(metadata(docs::examples = "username"))]
+        user: String,
+
+        /// The basic authentication password.
+        #[configurable(metadata(docs::examples = "${PASSWORD}"))]
+        #[configurable(metadata(docs::examples = "password"))]
+        password: SensitiveString,
+    },
+
+    /// Bearer authentication.
+    ///
+    /// The bearer token value (OAuth2, JWT, etc.) is passed as-is.
+    Bearer {
+        /// The bearer authentication token.
+        token: SensitiveString,
+    },
+
+    /// OAuth2 client credentials authentication.
+    ///
+    /// An access token is requested from the token endpoint with the client credentials, and is
+    /// passed as a bearer token. The token is cached, and refreshed before it expires.
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        /// The URL of the token endpoint of the authorization server.
+        #[configurable(metadata(docs::examples = "https://auth.example.com/oauth2/token"))]
+        token_endpoint: String,
+
+        /// The client identifier.
+        #[configurable(metadata(docs::examples = "${CLIENT_ID}"))]
+        #[configurable(metadata(docs::examples = "SECRET[vault.client_id]"))]
+        client_id: String,
+
+        /// The client secret.
+        #[configurable(metadata(docs::examples = "${CLIENT_SECRET}"))]
+        #[configurable(metadata(docs::examples = "SECRET[vault.client_secret]"))]
+        client_secret: SensitiveString,
+
+        /// The scopes to request.
+        #[serde(default)]
+        #[configurable(metadata(docs::examples = "logs.write"))]
+        scopes: Vec<String>,
+
+        /// The audience to request the access token for, for authorization servers requiring one.
+        #[serde(default)]
+        #[configurable(metadata(docs::examples = "https://api.example.com"))]
+        audience: Option<String>,
+
+        /// How long before it expires the access token is refreshed.
+        #[serde(default = "default_oauth2_refresh_margin_secs")]
+        #[configurable(metadata(docs::type_unit = "seconds"))]
+        #[configurable(metadata(docs::human_name = "Refresh Margin"))]
+        refresh_margin_secs: u64,
+    },
+
+    /// HMAC request signing.
+    ///
+    /// Each request is signed with the secret, over its timestamp, method, path with the query
+    /// string, if any, and body, each followed by a newline except for the body. The hex-encoded
+    /// signature and the timestamp, in Unix seconds, are passed in headers.
+    Hmac {
+        /// The secret used to sign requests.
+        #[configurable(metadata(docs::examples = "${SIGNING_SECRET}"))]
+        #[configurable(metadata(docs::examples = "SECRET[vault.signing_secret]"))]
+        secret: SensitiveString,
+
+        #[configurable(derived)]
+        #[serde(default)]
+        algorithm: HmacAlgorithm,
+
+        /// The header holding the signature.
+        #[serde(default = "hmac::default_signature_header")]
+        signature_header: String,
+
+        /// The header holding the timestamp the request was signed at.
+        #[serde(default = "hmac::default_timestamp_header")]
+        timestamp_header: String,
+    },
+
+    #[cfg(feature = "aws-core")]
+    /// AWS authentication.
+    Aws {
+        /// The AWS authentication configuration.
+        auth: AwsAuthentication,
+
+        /// The AWS service name to use for signing.
+        service: String,
+    },
+}
+
+const fn default_oauth2_refresh_margin_secs() -> u64 {
+    60
+}
+
+impl HttpClientAuth {
+    /// Builds an authenticator for the strategy.
+    ///
+    /// The access tokens of `oauth2` are fetched as the requests are sent, by the client the token
+    /// source of the authenticator is attached to with [`HttpClient::with_oauth2`].
+    pub fn build_authenticator(
+        &self,
+        tls_settings: impl Into<MaybeTlsSettings>,
+        proxy_config: &ProxyConfig,
+    ) -> crate::Result<HttpAuthenticator> {
+        Ok(match self {
+            Self::Basic { user, password } => HttpAuthenticator::Static(Auth::Basic {
+                user: user.clone(),
+                password: password.clone(),
+            }),
+            Self::Bearer { token } => HttpAuthenticator::Static(Auth::Bearer {
+                token: token.clone(),
+            }),
+            Self::OAuth2 {
+                token_endpoint,
+                client_id,
+                client_secret,
+                scopes,
+                audience,
+                refresh_margin_secs,
+            } => {
+                let client = HttpClient::new(tls_settings, proxy_config)?;
+                let credentials = oauth2::ClientCredentials {
+                    token_endpoint,
+                    client_id,
+                    client_secret,
+                    scopes,
+                    audience: audience.as_deref(),
+                    refresh_margin: Duration::from_secs(*refresh_margin_secs),
+                };
+                HttpAuthenticator::OAuth2(OAuth2TokenSource::new(client, credentials)?)
+            }
+            Self::Hmac {
+                secret,
+                algorithm,
+                signature_header,
+                timestamp_header,
+            } => HttpAuthenticator::Hmac {
+                secret: secret.clone(),
+                algorithm: *algorithm,
+                signature_header: HeaderName::try_from(signature_header.as_str())?,
+                timestamp_header: HeaderName::try_from(timestamp_header.as_str())?,
+            },
+            #[cfg(feature = "aws-core")]
+            Self::Aws { auth, service } => HttpAuthenticator::Static(Auth::Aws {
+                auth: auth.clone(),
+                service: service.clone(),
+            }),
+        })
+    }
+}
+
+/// An authentication strategy, along with its runtime state.
+#[derive(Clone, Debug)]
+pub enum HttpAuthenticator {
+    /// A strategy applied from its configuration alone.
+    Static(Auth),
+
+    /// OAuth2 authentication with a cached access token.
+    OAuth2(OAuth2TokenSource),
+
+    /// HMAC request signing.
+    Hmac {
+        secret: SensitiveString,
+        algorithm: HmacAlgorithm,
+        signature_header: HeaderName,
+        timestamp_header: HeaderName,
+    },
+}
+
+impl HttpAuthenticator {
+    /// Returns the token source to attach to the client sending the requests, if any.
+    pub fn token_source(&self) -> Option<OAuth2TokenSource> {
+        match self {
+            Self::OAuth2(source) => Some(source.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn apply<B: AsRef<[u8]>>(&self, request: &mut Request<B>) {
+        match self {
+            Self::Static(auth) => auth.apply(request),
+            // The access token is added by the client sending the request.
+            Self::OAuth2(_) => {}
+            Self::Hmac {
+                secret,
+                algorithm,
+                signature_header,
+                timestamp_header,
+            } => {
+                let timestamp = signature_timestamp();
+                match hmac_signature(
+                    *algorithm,
+                    secret.inner().as_bytes(),
+                    &timestamp.to_string(),
+                    request.method().as_str(),
+                    request.uri().path(),
+                    request.uri().query(),
+                    request.body().as_ref(),
+                ) {
+                    Ok(signature) => {
+                        let headers = request.headers_mut();
+                        headers.insert(
+                            signature_header.clone(),
+                            HeaderValue::from_str(&signature).expect("hex signature"),
+                        );
+                        headers.insert(timestamp_header.clone(), HeaderValue::from(timestamp));
+                    }
+                    Err(error) => {
+                        error!(message = "Failed to sign request.", %error, internal_log_rate_limit = true)
+                    }
+                }
+            }
+        }
+    }
+}
+
 pub fn get_http_scheme_from_uri(uri: &Uri) -> &'static str {
     // If there's no scheme, we just use "http" since it provides the most semantic relevance without inadvertently
     // implying things it can't know i.e. returning "https" when we're not actually sure HTTPS was used.
@@ -682,6 +990,7 @@ pub type QueryParameters = HashMap<String, QueryParameterValue>;
 mod tests {
     use std::convert::Infallible;
 
+    use bytes::Bytes;
     use hyper::{server::conn::AddrStream, service::make_service_fn, Server};
     use proptest::prelude::*;
     use tower::ServiceBuilder;
@@ -690,6 +999,38 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn hmac_authenticator_signs_requests() {
+        let auth: HttpClientAuth = toml::from_str(
+            r#"
+                strategy = "hmac"
+                secret = "secret"
+            "#,
+        )
+        .unwrap();
+        let authenticator = auth
+            .build_authenticator(None, &ProxyConfig::default())
+            .unwrap();
+
+        let mut request = Request::post("http://localhost/ingest?stream=a")
+            .body(Bytes::from_static(br#"{"a":1}"#))
+            .unwrap();
+        authenticator.apply(&mut request);
+
+        let timestamp = request.headers()["X-Signature-Timestamp"].to_str().unwrap();
+        let expected = hmac_signature(
+            HmacAlgorithm::Sha256,
+            b"secret",
+            timestamp,
+            "POST",
+            "/ingest",
+            Some("stream=a"),
+            br#"{"a":1}"#,
+        )
+        .unwrap();
+        assert_eq!(request.headers()["X-Signature"], expected.as_str());
+    }
+
     #[test]
     fn test_default_request_headers_defaults() {
         let user_agent = HeaderValue::from_static("vector");
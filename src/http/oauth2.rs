@@ -0,0 +1,242 @@
+//! The OAuth2 client credentials grant, with the access token cached and refreshed before it
+//! expires.
+
+use std::{sync::Arc, time::Duration};
+
+use headers::{authorization::Bearer, Authorization};
+use http::{
+    header::{ACCEPT, CONTENT_TYPE},
+    uri::InvalidUri,
+    Request, StatusCode, Uri,
+};
+use hyper::Body;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use tokio::{
+    sync::Mutex,
+    time::{sleep, Instant},
+};
+use vector_lib::sensitive_string::SensitiveString;
+
+use super::{Auth, HttpClient, HttpError};
+
+/// How many times a token is fetched before failing the request needing it.
+const TOKEN_FETCH_ATTEMPTS: usize = 3;
+
+/// The initial delay before fetching a token again after failing to, doubled on each attempt.
+const TOKEN_ERROR_INITIAL_BACKOFF_MILLIS: u64 = 500;
+
+#[derive(Debug, Snafu)]
+pub enum OAuth2Error {
+    #[snafu(display("Invalid token endpoint: {}", source))]
+    InvalidTokenEndpoint { source: InvalidUri },
+    #[snafu(display("Failed to request an access token: {}", source))]
+    RequestToken { source: HttpError },
+    #[snafu(display("Failed to read the access token response: {}", source))]
+    ReadToken { source: hyper::Error },
+    #[snafu(display("Access token request failed with status {}: {}", status, body))]
+    TokenStatus { status: StatusCode, body: String },
+    #[snafu(display("Invalid access token response: {}", source))]
+    ParseToken { source: serde_json::Error },
+    #[snafu(display("Invalid access token: {}", source))]
+    InvalidToken {
+        source: headers::authorization::InvalidBearerToken,
+    },
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// The parameters of the client credentials grant.
+pub(super) struct ClientCredentials<'a> {
+    pub(super) token_endpoint: &'a str,
+    pub(super) client_id: &'a str,
+    pub(super) client_secret: &'a SensitiveString,
+    pub(super) scopes: &'a [String],
+    pub(super) audience: Option<&'a str>,
+    pub(super) refresh_margin: Duration,
+}
+
+/// A source of OAuth2 access tokens.
+///
+/// A token is fetched when one is first needed, and then again once it is due for a refresh, so
+/// that an unreachable authorization server does not prevent the component from starting.
+#[derive(Clone, Debug)]
+pub struct OAuth2TokenSource(Arc<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+    client: HttpClient,
+    token_endpoint: Uri,
+    client_auth: Auth,
+    form: String,
+    refresh_margin: Duration,
+    token: Mutex<Option<AccessToken>>,
+}
+
+#[derive(Debug)]
+struct AccessToken {
+    authorization: Authorization<Bearer>,
+    /// When the token is due for a refresh, if it expires.
+    refresh_at: Option<Instant>,
+    /// When the token expires, if it does.
+    expires_at: Option<Instant>,
+}
+
+impl AccessToken {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.refresh_at.is_some_and(|refresh_at| refresh_at <= now)
+    }
+}
+
+impl OAuth2TokenSource {
+    pub(super) fn new(
+        client: HttpClient,
+        credentials: ClientCredentials<'_>,
+    ) -> Result<Self, OAuth2Error> {
+        let mut form = url::form_urlencoded::Serializer::new(String::new());
+        form.append_pair("grant_type", "client_credentials");
+        if !credentials.scopes.is_empty() {
+            form.append_pair("scope", &credentials.scopes.join(" "));
+        }
+        if let Some(audience) = credentials.audience {
+            form.append_pair("audience", audience);
+        }
+
+        Ok(Self(Arc::new(Inner {
+            client,
+            token_endpoint: credentials
+                .token_endpoint
+                .parse()
+                .context(InvalidTokenEndpointSnafu)?,
+            // The client authenticates with HTTP basic authentication, as recommended by RFC 6749.
+            client_auth: Auth::Basic {
+                user: credentials.client_id.to_owned(),
+                password: credentials.client_secret.clone(),
+            },
+            form: form.finish(),
+            refresh_margin: credentials.refresh_margin,
+            token: Mutex::new(None),
+        })))
+    }
+
+    /// Returns the authorization header of the current access token.
+    ///
+    /// The token is fetched if there is none yet, or if it is due for a refresh. Failed fetches are
+    /// retried a few times with a backoff, unless the current token has not expired yet, in which
+    /// case it is used until the next request. The error of the last fetch is returned otherwise,
+    /// failing the request so that it is retried like any other.
+    pub async fn authorization(&self) -> Result<Authorization<Bearer>, OAuth2Error> {
+        // Holding the lock while fetching lets concurrent requests share a single fetch.
+        let mut token = self.0.token.lock().await;
+        let mut backoff = Duration::from_millis(TOKEN_ERROR_INITIAL_BACKOFF_MILLIS);
+        let mut attempt = 1;
+        loop {
+            let now = Instant::now();
+            if let Some(current) = token.as_ref().filter(|token| !token.is_due(now)) {
+                return Ok(current.authorization.clone());
+            }
+
+            match self.0.fetch_token().await {
+                Ok(fetched) => {
+                    let authorization = fetched.authorization.clone();
+                    *token = Some(fetched);
+                    return Ok(authorization);
+                }
+                Err(error) => {
+                    error!(
+                        message = "Failed to fetch OAuth2 access token.",
+                        %error,
+                        attempt,
+                        internal_log_rate_limit = true,
+                    );
+                    if let Some(current) = token.as_ref().filter(|token| !token.is_expired(now)) {
+                        return Ok(current.authorization.clone());
+                    }
+                    if attempt == TOKEN_FETCH_ATTEMPTS {
+                        return Err(error);
+                    }
+                }
+            }
+
+            sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+
+    /// Discards the access token a request was rejected with, so that the next request fetches a
+    /// new one.
+    ///
+    /// This covers the tokens revoked before they expire, and those without a lifetime, which are
+    /// not refreshed otherwise.
+    pub async fn invalidate(&self, rejected: &Authorization<Bearer>) {
+        let mut token = self.0.token.lock().await;
+        // The token may have been refreshed since the request was sent.
+        if token
+            .as_ref()
+            .is_some_and(|current| current.authorization.token() == rejected.token())
+        {
+            debug!("OAuth2 access token rejected, discarding it.");
+            *token = None;
+        }
+    }
+}
+
+impl Inner {
+    /// Fetches a new access token.
+    async fn fetch_token(&self) -> Result<AccessToken, OAuth2Error> {
+        debug!(
+            message = "Fetching OAuth2 access token.",
+            token_endpoint = %self.token_endpoint,
+        );
+        let mut request = Request::post(&self.token_endpoint)
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .header(ACCEPT, "application/json")
+            .body(Body::from(self.form.clone()))
+            .expect("valid token request");
+        self.client_auth.apply(&mut request);
+
+        let fetched_at = Instant::now();
+        let response = self.client.send(request).await.context(RequestTokenSnafu)?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadTokenSnafu)?;
+        if !status.is_success() {
+            return TokenStatusSnafu {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            }
+            .fail();
+        }
+
+        let response: TokenResponse = serde_json::from_slice(&body).context(ParseTokenSnafu)?;
+        let access_token = SensitiveString::from(response.access_token);
+        let authorization =
+            Authorization::bearer(access_token.inner()).context(InvalidTokenSnafu)?;
+        let expires_at = response
+            .expires_in
+            .map(|expires_in| fetched_at + Duration::from_secs(expires_in));
+        Ok(AccessToken {
+            authorization,
+            refresh_at: expires_at.map(|expires_at| {
+                // Tokens expiring within the margin are refreshed halfway through their lifetime.
+                let lifetime = expires_at - fetched_at;
+                let refresh_in = lifetime
+                    .checked_sub(self.refresh_margin)
+                    .unwrap_or(lifetime / 2);
+                fetched_at + refresh_in
+            }),
+            expires_at,
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use metrics::{counter, gauge, Gauge};
+use vector_lib::internal_event::InternalEvent;
+
+/// The state of the circuit breaker of a sink's requests.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CircuitBreakerState {
+    Closed,
+    HalfOpen,
+    Open,
+}
+
+impl CircuitBreakerState {
+    /// The value of the `circuit_breaker_state` gauge for this state.
+    pub const fn as_gauge(self) -> f64 {
+        match self {
+            Self::Closed => 0.0,
+            Self::HalfOpen => 1.0,
+            Self::Open => 2.0,
+        }
+    }
+
+    pub fn from_gauge(value: f64) -> Self {
+        if value >= 2.0 {
+            Self::Open
+        } else if value >= 1.0 {
+            Self::HalfOpen
+        } else {
+            Self::Closed
+        }
+    }
+}
+
+/// The gauge of the state of a circuit breaker, tagged with its endpoint when a sink has several.
+fn state_gauge(endpoint: Option<&str>) -> Gauge {
+    match endpoint {
+        Some(endpoint) => gauge!("circuit_breaker_state", "endpoint" => endpoint.to_owned()),
+        None => gauge!("circuit_breaker_state"),
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerCreated<'a> {
+    pub endpoint: Option<&'a str>,
+}
+
+impl InternalEvent for CircuitBreakerCreated<'_> {
+    fn emit(self) {
+        state_gauge(self.endpoint).set(CircuitBreakerState::Closed.as_gauge());
+    }
+}
+
+#[derive(Debug)]
+pub struct CircuitBreakerStateChanged<'a> {
+    pub state: CircuitBreakerState,
+    pub consecutive_failures: usize,
+    pub endpoint: Option<&'a str>,
+}
+
+impl InternalEvent for CircuitBreakerStateChanged<'_> {
+    fn emit(self) {
+        match self.state {
+            CircuitBreakerState::Closed => {
+                info!(
+                    message = "Circuit breaker closed.",
+                    endpoint = self.endpoint
+                );
+            }
+            CircuitBreakerState::HalfOpen => {
+                debug!(
+                    message = "Circuit breaker half-open, probing the endpoint.",
+                    endpoint = self.endpoint,
+                );
+            }
+            CircuitBreakerState::Open => {
+                warn!(
+                    message = "Circuit breaker opened.",
+                    consecutive_failures = self.consecutive_failures,
+                    endpoint = self.endpoint,
+                );
+                match self.endpoint {
+                    Some(endpoint) => {
+                        counter!("circuit_breaker_opened_total", "endpoint" => endpoint.to_owned())
+                            .increment(1)
+                    }
+                    None => counter!("circuit_breaker_opened_total").increment(1),
+                }
+            }
+        }
+        state_gauge(self.endpoint).set(self.state.as_gauge());
+    }
+}
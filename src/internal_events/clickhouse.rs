@@ -0,0 +1,67 @@
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type, ComponentEventsDropped, UNINTENTIONAL};
+
+#[derive(Debug)]
+pub struct ClickhouseConversionError<E> {
+    pub error: E,
+}
+
+impl<E: std::fmt::Display> InternalEvent for ClickhouseConversionError<E> {
+    fn emit(self) {
+        let reason = "Event could not be converted to the schema of the table.";
+        error!(
+            message = reason,
+            error = %self.error,
+            error_code = "invalid_value",
+            error_type = error_type::CONVERSION_FAILED,
+            stage = error_stage::PROCESSING,
+
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "invalid_value",
+            "error_type" => error_type::CONVERSION_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
+
+#[derive(Debug)]
+pub struct ClickhouseSchemaError<'a, E> {
+    pub error: E,
+    pub database: &'a str,
+    pub table: &'a str,
+    pub count: usize,
+}
+
+impl<E: std::fmt::Display> InternalEvent for ClickhouseSchemaError<'_, E> {
+    fn emit(self) {
+        let reason = "Failed to fetch the schema of the table.";
+        error!(
+            message = reason,
+            error = %self.error,
+            error_code = "schema_unavailable",
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::SENDING,
+            database = %self.database,
+            table = %self.table,
+
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "schema_unavailable",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::SENDING,
+        )
+        .increment(1);
+
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+            count: self.count,
+            reason
+        });
+    }
+}
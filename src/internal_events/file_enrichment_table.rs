@@ -0,0 +1,39 @@
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct FileEnrichmentTableRefreshed<'a> {
+    pub url: &'a str,
+}
+
+impl InternalEvent for FileEnrichmentTableRefreshed<'_> {
+    fn emit(self) {
+        debug!(message = "File enrichment table refreshed.", url = %self.url);
+        counter!("enrichment_table_refreshes_total").increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct FileEnrichmentTableRefreshError<'a> {
+    pub url: &'a str,
+    pub error: crate::Error,
+}
+
+impl InternalEvent for FileEnrichmentTableRefreshError<'_> {
+    fn emit(self) {
+        error!(
+            message = "File enrichment table refresh failed.",
+            url = %self.url,
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use metrics::counter;
+use vector_lib::internal_event::{error_stage, error_type, InternalEvent};
+
+#[derive(Debug)]
+pub struct LogClusteringSnapshotError<'a> {
+    pub error: std::io::Error,
+    pub path: &'a Path,
+}
+
+impl InternalEvent for LogClusteringSnapshotError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Failed writing log patterns snapshot.",
+            error = %self.error,
+            path = %self.path.display(),
+            error_type = error_type::WRITER_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::WRITER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
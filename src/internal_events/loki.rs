@@ -96,3 +96,25 @@ impl InternalEvent for LokiTimestampNonParsableEventsDropped {
         .increment(1);
     }
 }
+
+#[derive(Debug)]
+pub struct LokiPushRequestParseError<'a> {
+    pub error: &'a str,
+}
+
+impl InternalEvent for LokiPushRequestParseError<'_> {
+    fn emit(self) {
+        error!(
+            message = "Could not decode push request.",
+            error = %self.error,
+            error_type = error_type::PARSER_FAILED,
+            stage = error_stage::PROCESSING,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::PARSER_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
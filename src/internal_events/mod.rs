@@ -27,6 +27,9 @@ mod aws_kinesis_firehose;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 mod aws_sqs;
 mod batch;
+mod circuit_breaker;
+#[cfg(feature = "sinks-clickhouse")]
+mod clickhouse;
 mod codecs;
 mod common;
 mod conditions;
@@ -51,13 +54,18 @@ mod eventstoredb_metrics;
 mod exec;
 #[cfg(any(feature = "sources-file_descriptor", feature = "sources-stdin"))]
 mod file_descriptor;
+mod file_enrichment_table;
 #[cfg(feature = "transforms-filter")]
 mod filter;
 #[cfg(feature = "sources-fluent")]
 mod fluent;
 #[cfg(feature = "sources-gcp_pubsub")]
 mod gcp_pubsub;
-#[cfg(any(feature = "sources-vector", feature = "sources-opentelemetry"))]
+#[cfg(any(
+    feature = "sources-grpc_server",
+    feature = "sources-vector",
+    feature = "sources-opentelemetry"
+))]
 mod grpc;
 mod heartbeat;
 #[cfg(feature = "sources-host_metrics")]
@@ -76,10 +84,12 @@ mod journald;
 mod kafka;
 #[cfg(feature = "sources-kubernetes_logs")]
 mod kubernetes_logs;
+#[cfg(feature = "transforms-log_clustering")]
+mod log_clustering;
 #[cfg(feature = "transforms-log_to_metric")]
 mod log_to_metric;
 mod logplex;
-#[cfg(feature = "sinks-loki")]
+#[cfg(any(feature = "sinks-loki", feature = "sources-loki"))]
 mod loki;
 #[cfg(feature = "transforms-lua")]
 mod lua;
@@ -89,10 +99,20 @@ mod metric_to_log;
 mod mongodb_metrics;
 #[cfg(feature = "sinks-mqtt")]
 mod mqtt;
+#[cfg(feature = "sources-netflow")]
+mod netflow;
 #[cfg(feature = "sources-nginx_metrics")]
 mod nginx_metrics;
 mod open;
 mod parser;
+#[cfg(any(
+    feature = "enrichment-tables-memory",
+    feature = "transforms-impl-dedupe",
+    feature = "transforms-log_clustering"
+))]
+mod persistence;
+#[cfg(feature = "transforms-pii_redaction")]
+mod pii_redaction;
 #[cfg(feature = "sources-postgresql_metrics")]
 mod postgresql_metrics;
 mod process;
@@ -109,16 +129,28 @@ mod redis;
 #[cfg(feature = "transforms-impl-reduce")]
 mod reduce;
 mod remap;
+#[cfg(any(feature = "enrichment-tables-redis", feature = "enrichment-tables-sql"))]
+mod remote_enrichment_table;
 mod sample;
 #[cfg(feature = "sinks-sematext")]
 mod sematext_metrics;
+#[cfg(any(feature = "sources-snmp", feature = "sources-snmp_trap"))]
+mod snmp;
 mod socket;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 mod splunk_hec;
+#[cfg(any(
+    feature = "sinks-mysql",
+    feature = "sinks-postgres",
+    feature = "sinks-sqlite"
+))]
+mod sql;
 #[cfg(feature = "sinks-statsd")]
 mod statsd_sink;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 mod tag_cardinality_limit;
+#[cfg(feature = "transforms-tail_sampling")]
+mod tail_sampling;
 mod tcp;
 mod template;
 #[cfg(feature = "transforms-throttle")]
@@ -168,6 +200,8 @@ pub(crate) use self::aws_kinesis::*;
 pub(crate) use self::aws_kinesis_firehose::*;
 #[cfg(any(feature = "sources-aws_s3", feature = "sources-aws_sqs",))]
 pub(crate) use self::aws_sqs::*;
+#[cfg(feature = "sinks-clickhouse")]
+pub(crate) use self::clickhouse::*;
 pub(crate) use self::codecs::*;
 #[cfg(feature = "sources-datadog_agent")]
 pub(crate) use self::datadog_agent::*;
@@ -201,7 +235,11 @@ pub(crate) use self::filter::*;
 pub(crate) use self::fluent::*;
 #[cfg(feature = "sources-gcp_pubsub")]
 pub(crate) use self::gcp_pubsub::*;
-#[cfg(any(feature = "sources-vector", feature = "sources-opentelemetry"))]
+#[cfg(any(
+    feature = "sources-grpc_server",
+    feature = "sources-vector",
+    feature = "sources-opentelemetry"
+))]
 pub(crate) use self::grpc::*;
 #[cfg(feature = "sources-host_metrics")]
 pub(crate) use self::host_metrics::*;
@@ -217,11 +255,13 @@ pub(crate) use self::journald::*;
 pub(crate) use self::kafka::*;
 #[cfg(feature = "sources-kubernetes_logs")]
 pub(crate) use self::kubernetes_logs::*;
+#[cfg(feature = "transforms-log_clustering")]
+pub(crate) use self::log_clustering::*;
 #[cfg(feature = "transforms-log_to_metric")]
 pub(crate) use self::log_to_metric::*;
 #[cfg(feature = "sources-heroku_logs")]
 pub(crate) use self::logplex::*;
-#[cfg(feature = "sinks-loki")]
+#[cfg(any(feature = "sinks-loki", feature = "sources-loki"))]
 pub(crate) use self::loki::*;
 #[cfg(feature = "transforms-lua")]
 pub(crate) use self::lua::*;
@@ -229,10 +269,20 @@ pub(crate) use self::lua::*;
 pub(crate) use self::metric_to_log::*;
 #[cfg(feature = "sinks-mqtt")]
 pub(crate) use self::mqtt::*;
+#[cfg(feature = "sources-netflow")]
+pub(crate) use self::netflow::*;
 #[cfg(feature = "sources-nginx_metrics")]
 pub(crate) use self::nginx_metrics::*;
 #[allow(unused_imports)]
 pub(crate) use self::parser::*;
+#[cfg(any(
+    feature = "enrichment-tables-memory",
+    feature = "transforms-impl-dedupe",
+    feature = "transforms-log_clustering"
+))]
+pub(crate) use self::persistence::*;
+#[cfg(feature = "transforms-pii_redaction")]
+pub(crate) use self::pii_redaction::*;
 #[cfg(feature = "sources-postgresql_metrics")]
 pub(crate) use self::postgresql_metrics::*;
 #[cfg(any(
@@ -249,16 +299,28 @@ pub(crate) use self::redis::*;
 pub(crate) use self::reduce::*;
 #[cfg(feature = "transforms-remap")]
 pub(crate) use self::remap::*;
+#[cfg(any(feature = "enrichment-tables-redis", feature = "enrichment-tables-sql"))]
+pub(crate) use self::remote_enrichment_table::*;
 #[cfg(feature = "transforms-impl-sample")]
 pub(crate) use self::sample::*;
 #[cfg(feature = "sinks-sematext")]
 pub(crate) use self::sematext_metrics::*;
+#[cfg(any(feature = "sources-snmp", feature = "sources-snmp_trap"))]
+pub(crate) use self::snmp::*;
 #[cfg(any(feature = "sources-splunk_hec", feature = "sinks-splunk_hec"))]
 pub(crate) use self::splunk_hec::*;
+#[cfg(any(
+    feature = "sinks-mysql",
+    feature = "sinks-postgres",
+    feature = "sinks-sqlite"
+))]
+pub(crate) use self::sql::*;
 #[cfg(feature = "sinks-statsd")]
 pub(crate) use self::statsd_sink::*;
 #[cfg(feature = "transforms-tag_cardinality_limit")]
 pub(crate) use self::tag_cardinality_limit::*;
+#[cfg(feature = "transforms-tail_sampling")]
+pub(crate) use self::tail_sampling::*;
 #[cfg(feature = "transforms-throttle")]
 pub(crate) use self::throttle::*;
 #[cfg(unix)]
@@ -272,6 +334,7 @@ pub(crate) use self::window::*;
 #[cfg(windows)]
 pub(crate) use self::windows::*;
 pub use self::{
-    adaptive_concurrency::*, batch::*, common::*, conditions::*, encoding_transcode::*,
-    heartbeat::*, http::*, open::*, process::*, socket::*, tcp::*, template::*, udp::*,
+    adaptive_concurrency::*, batch::*, circuit_breaker::*, common::*, conditions::*,
+    encoding_transcode::*, file_enrichment_table::*, heartbeat::*, http::*, open::*, process::*,
+    socket::*, tcp::*, template::*, udp::*,
 };
@@ -0,0 +1,57 @@
+use std::net::IpAddr;
+
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type};
+
+#[derive(Debug)]
+pub(crate) struct NetflowParseError<E> {
+    pub error: E,
+    pub exporter: IpAddr,
+}
+
+impl<E: std::fmt::Display> InternalEvent for NetflowParseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Error occurred while parsing flow datagram.",
+            error = %self.error,
+            exporter = %self.exporter,
+            stage = error_stage::PROCESSING,
+            error_type = error_type::PARSER_FAILED,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "stage" => error_stage::PROCESSING,
+            "error_type" => error_type::PARSER_FAILED,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct NetflowTemplateNotFound {
+    pub template_id: u16,
+    pub exporter: IpAddr,
+}
+
+impl InternalEvent for NetflowTemplateNotFound {
+    fn emit(self) {
+        warn!(
+            message = "Discarding flow records referencing an unknown template.",
+            template_id = self.template_id,
+            exporter = %self.exporter,
+            error_code = "template_not_found",
+            stage = error_stage::PROCESSING,
+            error_type = error_type::PARSER_FAILED,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "template_not_found",
+            "stage" => error_stage::PROCESSING,
+            "error_type" => error_type::PARSER_FAILED,
+        )
+        .increment(1);
+    }
+}
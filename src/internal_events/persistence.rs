@@ -0,0 +1,21 @@
+use std::{fmt::Display, path::Path};
+
+use vector_lib::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct SnapshotDiscarded<'a, E> {
+    pub error: E,
+    pub path: &'a Path,
+    pub moved_to: Option<&'a Path>,
+}
+
+impl<E: Display> InternalEvent for SnapshotDiscarded<'_, E> {
+    fn emit(self) {
+        warn!(
+            message = "Discarded unreadable snapshot, starting with an empty state.",
+            error = %self.error,
+            path = %self.path.display(),
+            moved_to = ?self.moved_to,
+        );
+    }
+}
@@ -0,0 +1,20 @@
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+
+#[derive(Debug)]
+pub struct PiiRedactionDetected {
+    pub detector: &'static str,
+    pub count: usize,
+}
+
+impl InternalEvent for PiiRedactionDetected {
+    fn emit(self) {
+        trace!(
+            message = "Redacted sensitive values.",
+            detector = self.detector,
+            count = self.count,
+            internal_log_rate_limit = true,
+        );
+        counter!("pii_detections_total", "detector" => self.detector).increment(self.count as u64);
+    }
+}
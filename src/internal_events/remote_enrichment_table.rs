@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type};
+
+#[derive(Debug)]
+pub struct RemoteEnrichmentTableLookupError {
+    pub table: &'static str,
+    pub error: crate::Error,
+}
+
+impl InternalEvent for RemoteEnrichmentTableLookupError {
+    fn emit(self) {
+        error!(
+            message = "Enrichment table lookup failed.",
+            table = self.table,
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct RemoteEnrichmentTableLookupTimeout {
+    pub table: &'static str,
+}
+
+impl InternalEvent for RemoteEnrichmentTableLookupTimeout {
+    fn emit(self) {
+        warn!(
+            message = "Enrichment table lookup timed out, no rows were found.",
+            table = self.table,
+            internal_log_rate_limit = true,
+        );
+        counter!("enrichment_table_lookup_timeouts_total", "table" => self.table).increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct RemoteEnrichmentTableLookupsSuspended {
+    pub table: &'static str,
+    pub duration: Duration,
+}
+
+impl InternalEvent for RemoteEnrichmentTableLookupsSuspended {
+    fn emit(self) {
+        warn!(
+            message = "Enrichment table lookups keep failing, suspending them.",
+            table = self.table,
+            duration_secs = self.duration.as_secs(),
+            internal_log_rate_limit = true,
+        );
+        counter!("enrichment_table_lookup_suspensions_total", "table" => self.table).increment(1);
+    }
+}
@@ -0,0 +1,139 @@
+use std::net::SocketAddr;
+
+use metrics::counter;
+use vector_lib::internal_event::InternalEvent;
+use vector_lib::internal_event::{error_stage, error_type};
+use vector_lib::json_size::JsonSize;
+
+#[derive(Debug)]
+pub(crate) struct SnmpParseError<E> {
+    pub error: E,
+    pub peer: SocketAddr,
+}
+
+impl<E: std::fmt::Display> InternalEvent for SnmpParseError<E> {
+    fn emit(self) {
+        error!(
+            message = "Error occurred while parsing SNMP message.",
+            error = %self.error,
+            peer = %self.peer,
+            stage = error_stage::PROCESSING,
+            error_type = error_type::PARSER_FAILED,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "stage" => error_stage::PROCESSING,
+            "error_type" => error_type::PARSER_FAILED,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SnmpAuthenticationError<E> {
+    pub error: E,
+    pub peer: SocketAddr,
+}
+
+impl<E: std::fmt::Display> InternalEvent for SnmpAuthenticationError<E> {
+    fn emit(self) {
+        error!(
+            message = "Discarding SNMP message that failed authentication.",
+            error = %self.error,
+            peer = %self.peer,
+            error_code = "authentication_failed",
+            stage = error_stage::RECEIVING,
+            error_type = error_type::CONDITION_FAILED,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "authentication_failed",
+            "stage" => error_stage::RECEIVING,
+            "error_type" => error_type::CONDITION_FAILED,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SnmpResponseSendError<E> {
+    pub error: E,
+    pub peer: SocketAddr,
+}
+
+impl<E: std::fmt::Display> InternalEvent for SnmpResponseSendError<E> {
+    fn emit(self) {
+        error!(
+            message = "Error sending SNMP response.",
+            error = %self.error,
+            peer = %self.peer,
+            error_code = "response_send",
+            stage = error_stage::SENDING,
+            error_type = error_type::WRITER_FAILED,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "error_code" => "response_send",
+            "stage" => error_stage::SENDING,
+            "error_type" => error_type::WRITER_FAILED,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SnmpEventsReceived<'a> {
+    pub byte_size: JsonSize,
+    pub count: usize,
+    pub endpoint: &'a str,
+}
+
+impl InternalEvent for SnmpEventsReceived<'_> {
+    fn emit(self) {
+        trace!(
+            message = "Events received.",
+            byte_size = %self.byte_size,
+            count = %self.count,
+            endpoint = self.endpoint,
+        );
+        counter!(
+            "component_received_events_total",
+            "endpoint" => self.endpoint.to_owned(),
+        )
+        .increment(self.count as u64);
+        counter!(
+            "component_received_event_bytes_total",
+            "endpoint" => self.endpoint.to_owned(),
+        )
+        .increment(self.byte_size.get() as u64);
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SnmpRequestError<'a, E> {
+    pub error: E,
+    pub endpoint: &'a str,
+}
+
+impl<E: std::fmt::Display> InternalEvent for SnmpRequestError<'_, E> {
+    fn emit(self) {
+        error!(
+            message = "SNMP request error.",
+            endpoint = %self.endpoint,
+            error = %self.error,
+            error_type = error_type::REQUEST_FAILED,
+            stage = error_stage::RECEIVING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "endpoint" => self.endpoint.to_owned(),
+            "error_type" => error_type::REQUEST_FAILED,
+            "stage" => error_stage::RECEIVING,
+        )
+        .increment(1);
+    }
+}
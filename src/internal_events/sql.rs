@@ -0,0 +1,28 @@
+use metrics::counter;
+use vector_lib::internal_event::{error_stage, error_type};
+use vector_lib::internal_event::{ComponentEventsDropped, InternalEvent, UNINTENTIONAL};
+
+#[derive(Debug)]
+pub struct SqlInvalidTableError<'a> {
+    pub table: &'a str,
+}
+
+impl InternalEvent for SqlInvalidTableError<'_> {
+    fn emit(self) {
+        let reason = "Rendered table name is not a valid identifier.";
+        error!(
+            message = reason,
+            table = %self.table,
+            error_type = error_type::TEMPLATE_FAILED,
+            stage = error_stage::PROCESSING,
+            internal_log_rate_limit = true,
+        );
+        counter!(
+            "component_errors_total",
+            "error_type" => error_type::TEMPLATE_FAILED,
+            "stage" => error_stage::PROCESSING,
+        )
+        .increment(1);
+        emit!(ComponentEventsDropped::<UNINTENTIONAL> { count: 1, reason });
+    }
+}
@@ -0,0 +1,60 @@
+use metrics::counter;
+use vector_lib::internal_event::{ComponentEventsDropped, InternalEvent, INTENTIONAL};
+
+#[derive(Debug)]
+pub struct TailSamplingTraceSampled {
+    pub policy: &'static str,
+}
+
+impl InternalEvent for TailSamplingTraceSampled {
+    fn emit(self) {
+        counter!(
+            "tail_sampling_decisions_total",
+            "decision" => "sampled",
+            "policy" => self.policy,
+        )
+        .increment(1);
+    }
+}
+
+#[derive(Debug)]
+pub struct TailSamplingTraceDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for TailSamplingTraceDropped {
+    fn emit(self) {
+        counter!("tail_sampling_decisions_total", "decision" => "dropped").increment(1);
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: self.count,
+            reason: "Trace not sampled by any policy.",
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct TailSamplingLateEventsDropped {
+    pub count: usize,
+}
+
+impl InternalEvent for TailSamplingLateEventsDropped {
+    fn emit(self) {
+        emit!(ComponentEventsDropped::<INTENTIONAL> {
+            count: self.count,
+            reason: "Trace already dropped.",
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct TailSamplingTraceEvicted;
+
+impl InternalEvent for TailSamplingTraceEvicted {
+    fn emit(self) {
+        debug!(
+            message = "Buffer full, deciding oldest trace early.",
+            internal_log_rate_limit = true,
+        );
+        counter!("tail_sampling_traces_evicted_total").increment(1);
+    }
+}
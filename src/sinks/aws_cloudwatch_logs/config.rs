@@ -208,7 +208,7 @@ impl SinkConfig for CloudwatchLogsSinkConfig {
         let request_settings = self.request.tower.into_settings();
         let client = self.create_client(cx.proxy()).await?;
         let svc = ServiceBuilder::new()
-            .settings(request_settings, CloudwatchRetryLogic::new())
+            .partial_settings(request_settings, CloudwatchRetryLogic::new())
             .service(CloudwatchLogsPartitionSvc::new(
                 self.clone(),
                 client.clone(),
@@ -6,7 +6,7 @@ use aws_sdk_cloudwatchlogs::{
         put_log_events::{PutLogEventsError, PutLogEventsOutput},
         put_retention_policy::PutRetentionPolicyError,
     },
-    types::InputLogEvent,
+    types::{InputLogEvent, RejectedLogEventsInfo},
     Client as CloudwatchLogsClient,
 };
 use aws_smithy_runtime_api::client::{orchestrator::HttpResponse, result::SdkError};
@@ -20,9 +20,11 @@ use std::{
     task::{ready, Context, Poll},
 };
 use tokio::sync::oneshot;
+use vector_lib::request_metadata::GroupedCountByteSize;
 
+use crate::event::Rejection;
 use crate::sinks::aws_cloudwatch_logs::config::Retention;
-use crate::sinks::aws_cloudwatch_logs::service::CloudwatchError;
+use crate::sinks::aws_cloudwatch_logs::service::{CloudwatchError, CloudwatchResponse};
 
 pub struct CloudwatchFuture {
     client: Client,
@@ -31,6 +33,10 @@ pub struct CloudwatchFuture {
     create_missing_stream: bool,
     retention_enabled: bool,
     events: Vec<Vec<InputLogEvent>>,
+    /// The index of the first event of the batch being put, and the length of the batch.
+    batch: (usize, usize),
+    /// The error of each event of the request, if it was rejected.
+    record_errors: Vec<Option<Rejection>>,
     token_tx: Option<oneshot::Sender<Option<String>>>,
 }
 
@@ -82,8 +88,11 @@ impl CloudwatchFuture {
             tags,
         };
 
+        let record_errors = vec![None; events.iter().map(Vec::len).sum()];
+        let mut batch = (0, 0);
         let state = if let Some(token) = token {
-            State::Put(client.put_logs(Some(token), events.pop().expect("No Events to send")))
+            let events = pop_batch(&mut events, &mut batch).expect("No Events to send");
+            State::Put(client.put_logs(Some(token), events))
         } else {
             State::DescribeStream(client.describe_stream())
         };
@@ -93,6 +102,8 @@ impl CloudwatchFuture {
         Self {
             client,
             events,
+            batch,
+            record_errors,
             state,
             token_tx: Some(token_tx),
             create_missing_group,
@@ -102,8 +113,44 @@ impl CloudwatchFuture {
     }
 }
 
+/// Pops the next batch of events to put, recording the index of its first event among all the
+/// events of the request, and its length.
+fn pop_batch(
+    events: &mut Vec<Vec<InputLogEvent>>,
+    batch: &mut (usize, usize),
+) -> Option<Vec<InputLogEvent>> {
+    let events_batch = events.pop()?;
+    *batch = (events.iter().map(Vec::len).sum(), events_batch.len());
+    Some(events_batch)
+}
+
+/// Returns the reason each event of a batch was rejected for, by index in the batch.
+fn rejected_events(
+    info: &RejectedLogEventsInfo,
+    len: usize,
+) -> impl Iterator<Item = (usize, &'static str)> {
+    let index = |index: Option<i32>| index.and_then(|index| usize::try_from(index).ok());
+    let too_new = index(info.too_new_log_event_start_index()).unwrap_or(len);
+    let too_old = index(info.too_old_log_event_end_index()).map_or(0, |end| end + 1);
+    let expired = index(info.expired_log_event_end_index()).map_or(0, |end| end + 1);
+    (0..len).filter_map(move |index| {
+        if index >= too_new {
+            Some((index, "Log event is too new."))
+        } else if index < too_old {
+            Some((index, "Log event is too old."))
+        } else if index < expired {
+            Some((
+                index,
+                "Log event is older than the retention period of the log group.",
+            ))
+        } else {
+            None
+        }
+    })
+}
+
 impl Future for CloudwatchFuture {
-    type Output = Result<(), CloudwatchError>;
+    type Output = Result<CloudwatchResponse, CloudwatchError>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
         loop {
@@ -138,9 +185,8 @@ impl Future for CloudwatchFuture {
                     {
                         debug!(message = "Stream found.", stream = ?stream.log_stream_name);
 
-                        let events = self
-                            .events
-                            .pop()
+                        let this = &mut *self;
+                        let events = pop_batch(&mut this.events, &mut this.batch)
                             .expect("Token got called multiple times, self is a bug!");
 
                         let token = stream.upload_sequence_token;
@@ -208,12 +254,21 @@ impl Future for CloudwatchFuture {
                 }
 
                 State::Put(fut) => {
-                    let next_token = match ready!(fut.poll_unpin(cx)) {
-                        Ok(resp) => resp.next_sequence_token,
+                    let resp = match ready!(fut.poll_unpin(cx)) {
+                        Ok(resp) => resp,
                         Err(err) => return Poll::Ready(Err(CloudwatchError::Put(err))),
                     };
 
-                    if let Some(events) = self.events.pop() {
+                    let this = &mut *self;
+                    if let Some(info) = resp.rejected_log_events_info() {
+                        let (offset, len) = this.batch;
+                        for (index, reason) in rejected_events(info, len) {
+                            this.record_errors[offset + index] = Some(Rejection::new(reason));
+                        }
+                    }
+
+                    let next_token = resp.next_sequence_token;
+                    if let Some(events) = pop_batch(&mut this.events, &mut this.batch) {
                         debug!(message = "Putting logs.", next_token = ?next_token);
                         self.state = State::Put(self.client.put_logs(next_token, events));
                     } else {
@@ -225,7 +280,10 @@ impl Future for CloudwatchFuture {
                             .send(next_token)
                             .expect("CloudwatchLogsSvc was dropped unexpectedly");
 
-                        return Poll::Ready(Ok(()));
+                        return Poll::Ready(Ok(CloudwatchResponse {
+                            record_errors: std::mem::take(&mut self.record_errors),
+                            events_byte_size: GroupedCountByteSize::default(),
+                        }));
                     }
                 }
 
@@ -338,3 +396,31 @@ impl Client {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_events_out_of_range() {
+        let info = RejectedLogEventsInfo::builder()
+            .too_old_log_event_end_index(0)
+            .expired_log_event_end_index(1)
+            .too_new_log_event_start_index(4)
+            .build();
+        assert_eq!(
+            rejected_events(&info, 5).collect::<Vec<_>>(),
+            [
+                (0, "Log event is too old."),
+                (
+                    1,
+                    "Log event is older than the retention period of the log group."
+                ),
+                (4, "Log event is too new."),
+            ]
+        );
+
+        let info = RejectedLogEventsInfo::builder().build();
+        assert_eq!(rejected_events(&info, 5).count(), 0);
+    }
+}
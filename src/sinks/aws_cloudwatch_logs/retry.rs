@@ -6,7 +6,10 @@ use aws_sdk_cloudwatchlogs::operation::put_log_events::PutLogEventsError;
 use aws_smithy_runtime_api::client::result::SdkError;
 
 use crate::aws::is_retriable_error;
-use crate::sinks::{aws_cloudwatch_logs::service::CloudwatchError, util::retries::RetryLogic};
+use crate::sinks::{
+    aws_cloudwatch_logs::service::{CloudwatchError, CloudwatchResponse},
+    util::{partial::ItemOutcome, retries::RetryLogic},
+};
 
 #[derive(Debug)]
 pub struct CloudwatchRetryLogic<T> {
@@ -28,9 +31,9 @@ impl<T> Clone for CloudwatchRetryLogic<T> {
     }
 }
 
-impl<T: Send + Sync + 'static> RetryLogic for CloudwatchRetryLogic<T> {
+impl RetryLogic for CloudwatchRetryLogic<CloudwatchResponse> {
     type Error = CloudwatchError;
-    type Response = T;
+    type Response = CloudwatchResponse;
 
     // TODO this match may not be necessary given the logic in `is_retriable_error()`
     #[allow(clippy::cognitive_complexity)] // long, but just a hair over our limit
@@ -66,6 +69,19 @@ impl<T: Send + Sync + 'static> RetryLogic for CloudwatchRetryLogic<T> {
             _ => false,
         }
     }
+
+    fn item_outcomes(&self, response: &Self::Response) -> Option<Vec<ItemOutcome>> {
+        Some(
+            response
+                .record_errors
+                .iter()
+                .map(|error| match error {
+                    None => ItemOutcome::Delivered,
+                    Some(rejection) => ItemOutcome::Rejected(rejection.clone()),
+                })
+                .collect(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -78,13 +94,14 @@ mod test {
     use aws_smithy_types::body::SdkBody;
 
     use crate::sinks::aws_cloudwatch_logs::{
-        retry::CloudwatchRetryLogic, service::CloudwatchError,
+        retry::CloudwatchRetryLogic,
+        service::{CloudwatchError, CloudwatchResponse},
     };
     use crate::sinks::util::retries::RetryLogic;
 
     #[test]
     fn test_throttle_retry() {
-        let retry_logic: CloudwatchRetryLogic<()> = CloudwatchRetryLogic::new();
+        let retry_logic: CloudwatchRetryLogic<CloudwatchResponse> = CloudwatchRetryLogic::new();
 
         let meta_err = aws_smithy_types::error::ErrorMetadata::builder()
             .code("ThrottlingException")
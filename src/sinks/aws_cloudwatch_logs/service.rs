@@ -33,7 +33,7 @@ use tower::{
 };
 use vector_lib::stream::DriverResponse;
 use vector_lib::{
-    finalization::EventStatus,
+    finalization::{EventStatus, Rejection},
     request_metadata::{GroupedCountByteSize, MetaDescriptive},
 };
 
@@ -50,7 +50,7 @@ type Svc = Buffer<
     <ConcurrencyLimit<
         RateLimit<
             Retry<
-                FibonacciRetryPolicy<CloudwatchRetryLogic<()>>,
+                FibonacciRetryPolicy<CloudwatchRetryLogic<CloudwatchResponse>>,
                 Buffer<
                     Vec<InputLogEvent>,
                     <Timeout<CloudwatchLogsSvc> as Service<Vec<InputLogEvent>>>::Future,
@@ -107,7 +107,9 @@ impl From<SdkError<DescribeLogStreamsError, HttpResponse>> for CloudwatchError {
 
 #[derive(Debug)]
 pub struct CloudwatchResponse {
-    events_byte_size: GroupedCountByteSize,
+    /// The error of each event of the request, if it was rejected.
+    pub(super) record_errors: Vec<Option<Rejection>>,
+    pub(super) events_byte_size: GroupedCountByteSize,
 }
 
 impl crate::sinks::util::sink::Response for CloudwatchResponse {
@@ -176,22 +178,28 @@ impl Service<BatchCloudwatchRequest> for CloudwatchLogsPartitionSvc {
         Poll::Ready(Ok(()))
     }
 
-    fn call(&mut self, mut req: BatchCloudwatchRequest) -> Self::Future {
-        let metadata = std::mem::take(req.metadata_mut());
-        let events_byte_size = metadata.into_events_estimated_json_encoded_byte_size();
-
+    fn call(&mut self, req: BatchCloudwatchRequest) -> Self::Future {
         let key = req.key;
-        let events = req
-            .events
+
+        // Events are put in the order of their timestamps, and their errors are reported in the
+        // order of the request.
+        let mut events = req.events.into_iter().enumerate().collect::<Vec<_>>();
+        events.sort_by_key(|(_, event)| event.timestamp);
+        let (order, (sizes, events)): (Vec<_>, (Vec<_>, Vec<_>)) = events
             .into_iter()
-            .map(|req| {
-                InputLogEvent::builder()
-                    .message(req.message)
-                    .timestamp(req.timestamp)
+            .map(|(index, event)| {
+                let size = event
+                    .get_metadata()
+                    .events_estimated_json_encoded_byte_size()
+                    .clone();
+                let event = InputLogEvent::builder()
+                    .message(event.message)
+                    .timestamp(event.timestamp)
                     .build()
-                    .expect("all builder fields specified")
+                    .expect("all builder fields specified");
+                (index, (size, event))
             })
-            .collect();
+            .unzip();
 
         let svc = if let Some(svc) = &mut self.clients.get_mut(&key) {
             svc.clone()
@@ -222,7 +230,23 @@ impl Service<BatchCloudwatchRequest> for CloudwatchLogsPartitionSvc {
         };
 
         svc.oneshot(events)
-            .map_ok(move |_x| CloudwatchResponse { events_byte_size })
+            .map_ok(move |response| {
+                // Only the delivered events are reported as sent.
+                let mut events_byte_size = GroupedCountByteSize::default();
+                let mut record_errors = vec![None; order.len()];
+                for ((index, size), error) in
+                    order.into_iter().zip(sizes).zip(response.record_errors)
+                {
+                    if error.is_none() {
+                        events_byte_size += size;
+                    }
+                    record_errors[index] = error;
+                }
+                CloudwatchResponse {
+                    record_errors,
+                    events_byte_size,
+                }
+            })
             .map_err(Into::into)
             .boxed()
     }
@@ -303,7 +327,7 @@ impl CloudwatchLogsSvc {
 }
 
 impl Service<Vec<InputLogEvent>> for CloudwatchLogsSvc {
-    type Response = ();
+    type Response = CloudwatchResponse;
     type Error = CloudwatchError;
     type Future = request::CloudwatchFuture;
 
@@ -15,7 +15,7 @@ use crate::{
             request_builder::{CloudwatchRequest, CloudwatchRequestBuilder},
             CloudwatchKey,
         },
-        util::SinkBuilderExt,
+        util::{partial::PartialRequest, SinkBuilderExt},
     },
 };
 
@@ -75,7 +75,18 @@ pub struct BatchCloudwatchRequest {
 
 impl Finalizable for BatchCloudwatchRequest {
     fn take_finalizers(&mut self) -> EventFinalizers {
-        self.events.take_finalizers()
+        // The events are finalized individually by the retry policy.
+        EventFinalizers::default()
+    }
+}
+
+impl PartialRequest for BatchCloudwatchRequest {
+    fn retain_items(&mut self, mut f: impl FnMut(usize, &EventFinalizers) -> bool) {
+        let mut index = 0;
+        self.events.retain(|request| {
+            index += 1;
+            f(index - 1, &request.finalizers)
+        });
     }
 }
 
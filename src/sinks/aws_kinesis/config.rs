@@ -53,7 +53,9 @@ pub struct KinesisSinkBaseConfig {
     #[serde(default)]
     pub auth: AwsAuthentication,
 
-    /// Whether or not to retry successful requests containing partial failures.
+    /// Whether or not to retry the records that failed individually in successful requests.
+    ///
+    /// Only the failed records are retried. When disabled, the failed records are rejected.
     #[serde(default)]
     #[configurable(metadata(docs::advanced))]
     pub request_retry_partial: bool,
@@ -105,7 +107,7 @@ where
 
     let region = config.region.region();
     let service = ServiceBuilder::new()
-        .settings::<RT, BatchKinesisRequest<RR>>(request_limits, retry_logic)
+        .partial_settings::<RT, BatchKinesisRequest<RR>>(request_limits, retry_logic)
         .service(KinesisService::<C, R, E> {
             client,
             stream_name: config.stream_name.clone(),
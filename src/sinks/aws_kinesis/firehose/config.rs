@@ -6,7 +6,7 @@ use futures::FutureExt;
 use snafu::Snafu;
 use vector_lib::configurable::configurable_component;
 
-use crate::sinks::util::retries::RetryAction;
+use crate::sinks::util::partial::ItemOutcome;
 use crate::{
     aws::{create_client, is_retriable_error, ClientBuilder},
     config::{AcknowledgementsConfig, GenerateConfig, Input, ProxyConfig, SinkConfig, SinkContext},
@@ -187,12 +187,7 @@ impl RetryLogic for KinesisRetryLogic {
         is_retriable_error(error)
     }
 
-    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
-        if response.failure_count > 0 && self.retry_partial {
-            let msg = format!("partial error count {}", response.failure_count);
-            RetryAction::Retry(msg.into())
-        } else {
-            RetryAction::Successful
-        }
+    fn item_outcomes(&self, response: &Self::Response) -> Option<Vec<ItemOutcome>> {
+        Some(response.record_outcomes(self.retry_partial))
     }
 }
@@ -4,7 +4,7 @@ use aws_smithy_types::Blob;
 use bytes::Bytes;
 use tracing::Instrument;
 
-use crate::sinks::prelude::*;
+use crate::sinks::{aws_kinesis::service::record_error, prelude::*};
 
 use super::{KinesisClient, KinesisError, KinesisRecord, KinesisResponse, Record, SendRecord};
 
@@ -65,7 +65,11 @@ impl SendRecord for KinesisFirehoseClient {
             .instrument(info_span!("request").or_current())
             .await
             .map(|output: PutRecordBatchOutput| KinesisResponse {
-                failure_count: output.failed_put_count() as usize,
+                record_errors: output
+                    .request_responses()
+                    .iter()
+                    .map(|response| record_error(response.error_code(), response.error_message()))
+                    .collect(),
                 events_byte_size: CountByteSize(rec_count, JsonSize::new(total_size)).into(),
             })
     }
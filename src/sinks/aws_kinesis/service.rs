@@ -10,7 +10,10 @@ use super::{
     record::{Record, SendRecord},
     sink::BatchKinesisRequest,
 };
-use crate::{event::EventStatus, sinks::prelude::*};
+use crate::{
+    event::{EventStatus, Rejection},
+    sinks::{prelude::*, util::partial::ItemOutcome},
+};
 
 pub struct KinesisService<C, T, E> {
     pub client: C,
@@ -36,10 +39,41 @@ where
 }
 
 pub struct KinesisResponse {
-    pub(crate) failure_count: usize,
+    /// The error of each record of the request, if it failed.
+    pub(crate) record_errors: Vec<Option<Rejection>>,
     pub(crate) events_byte_size: GroupedCountByteSize,
 }
 
+impl KinesisResponse {
+    /// Returns the outcome of each record of the request. Failed records are retried when
+    /// `retry_partial` is enabled, and rejected otherwise.
+    pub(crate) fn record_outcomes(&self, retry_partial: bool) -> Vec<ItemOutcome> {
+        self.record_errors
+            .iter()
+            .map(|error| match error {
+                None => ItemOutcome::Delivered,
+                Some(rejection) if retry_partial => ItemOutcome::Retry(
+                    format!(
+                        "{}: {}",
+                        rejection.status.as_deref().unwrap_or_default(),
+                        rejection.message
+                    )
+                    .into(),
+                ),
+                Some(rejection) => ItemOutcome::Rejected(rejection.clone()),
+            })
+            .collect()
+    }
+}
+
+/// Returns the error of a record from the error code and message of its result, if it failed.
+pub(super) fn record_error(code: Option<&str>, message: Option<&str>) -> Option<Rejection> {
+    code.map(|code| Rejection {
+        status: Some(code.to_owned()),
+        ..Rejection::new(message.unwrap_or("Record failed."))
+    })
+}
+
 impl DriverResponse for KinesisResponse {
     fn event_status(&self) -> EventStatus {
         EventStatus::Delivered
@@ -67,15 +101,21 @@ where
     }
 
     // Emission of internal events for errors and dropped events is handled upstream by the caller.
-    fn call(&mut self, mut requests: BatchKinesisRequest<R>) -> Self::Future {
-        let metadata = std::mem::take(requests.metadata_mut());
-        let events_byte_size = metadata.into_events_estimated_json_encoded_byte_size();
-
-        let records = requests
+    fn call(&mut self, requests: BatchKinesisRequest<R>) -> Self::Future {
+        // Only the delivered records are reported as sent, along with the records delivered by the
+        // earlier attempts of a partially retried request.
+        let mut events_byte_size = requests.delivered;
+        let (sizes, records): (Vec<_>, _) = requests
             .events
             .into_iter()
-            .map(|req| req.record.get())
-            .collect();
+            .map(|req| {
+                let size = req
+                    .get_metadata()
+                    .events_estimated_json_encoded_byte_size()
+                    .clone();
+                (size, req.record.get())
+            })
+            .unzip();
 
         let client = self.client.clone();
         let stream_name = self.stream_name.clone();
@@ -83,6 +123,11 @@ where
         Box::pin(async move {
             client.send(records, stream_name).await.map(|mut r| {
                 // augment the response
+                for (size, error) in sizes.into_iter().zip(&r.record_errors) {
+                    if error.is_none() {
+                        events_byte_size += size;
+                    }
+                }
                 r.events_byte_size = events_byte_size;
                 r
             })
@@ -8,7 +8,11 @@ use crate::{
     internal_events::{AwsKinesisStreamNoPartitionKeyError, SinkRequestBuildError},
     sinks::{
         prelude::*,
-        util::{processed_event::ProcessedEvent, StreamSink},
+        util::{
+            partial::{ItemOutcome, PartialRequest},
+            processed_event::ProcessedEvent,
+            StreamSink,
+        },
     },
 };
 
@@ -70,7 +74,11 @@ where
                 let metadata = RequestMetadata::from_batch(
                     events.iter().map(|req| req.get_metadata().clone()),
                 );
-                BatchKinesisRequest { events, metadata }
+                BatchKinesisRequest {
+                    events,
+                    metadata,
+                    delivered: GroupedCountByteSize::default(),
+                }
             })
             .into_driver(self.service)
             .run()
@@ -141,6 +149,8 @@ where
 {
     pub events: Vec<KinesisRequest<R>>,
     metadata: RequestMetadata,
+    /// The size of the records delivered by the earlier attempts of the request.
+    pub delivered: GroupedCountByteSize,
 }
 
 impl<R> Clone for BatchKinesisRequest<R>
@@ -151,6 +161,7 @@ where
         Self {
             events: self.events.to_vec(),
             metadata: self.metadata.clone(),
+            delivered: self.delivered.clone(),
         }
     }
 }
@@ -160,7 +171,32 @@ where
     R: Record + Clone,
 {
     fn take_finalizers(&mut self) -> EventFinalizers {
-        self.events.take_finalizers()
+        // The records are finalized individually by the retry policy.
+        EventFinalizers::default()
+    }
+}
+
+impl<R> PartialRequest for BatchKinesisRequest<R>
+where
+    R: Record + Clone,
+{
+    fn retain_items(&mut self, mut f: impl FnMut(usize, &EventFinalizers) -> bool) {
+        let mut index = 0;
+        self.events.retain(|request| {
+            index += 1;
+            f(index - 1, &request.finalizers)
+        });
+    }
+
+    fn items_delivered(&mut self, outcomes: &[ItemOutcome]) {
+        for (index, request) in self.events.iter().enumerate() {
+            if matches!(outcomes.get(index), Some(ItemOutcome::Delivered) | None) {
+                self.delivered += request
+                    .get_metadata()
+                    .events_estimated_json_encoded_byte_size()
+                    .clone();
+            }
+        }
     }
 }
 
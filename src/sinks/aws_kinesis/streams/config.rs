@@ -5,7 +5,7 @@ use futures::FutureExt;
 use snafu::Snafu;
 use vector_lib::configurable::{component::GenerateConfig, configurable_component};
 
-use crate::sinks::util::retries::RetryAction;
+use crate::sinks::util::partial::ItemOutcome;
 use crate::{
     aws::{create_client, is_retriable_error, ClientBuilder},
     config::{AcknowledgementsConfig, Input, ProxyConfig, SinkConfig, SinkContext},
@@ -174,12 +174,8 @@ impl RetryLogic for KinesisRetryLogic {
 
     fn is_retriable_error(&self, error: &Self::Error) -> bool {
         if let SdkError::ServiceError(inner) = error {
-            // Note that if the request partially fails (records sent to one
-            // partition fail but the others do not, for example), Vector
-            // does not retry. This line only covers a failure for the entire
-            // request.
-            //
-            // https://github.com/vectordotdev/vector/issues/359
+            // This only covers a failure for the entire request, the records
+            // failing individually are reported by `item_outcomes`.
             if matches!(
                 inner.err(),
                 PutRecordsError::ProvisionedThroughputExceededException(_)
@@ -190,13 +186,8 @@ impl RetryLogic for KinesisRetryLogic {
         is_retriable_error(error)
     }
 
-    fn should_retry_response(&self, response: &Self::Response) -> RetryAction {
-        if response.failure_count > 0 && self.retry_partial {
-            let msg = format!("partial error count {}", response.failure_count);
-            RetryAction::Retry(msg.into())
-        } else {
-            RetryAction::Successful
-        }
+    fn item_outcomes(&self, response: &Self::Response) -> Option<Vec<ItemOutcome>> {
+        Some(response.record_outcomes(self.retry_partial))
     }
 }
 
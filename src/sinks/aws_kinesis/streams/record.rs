@@ -4,7 +4,7 @@ use aws_smithy_types::Blob;
 use bytes::Bytes;
 use tracing::Instrument;
 
-use crate::sinks::prelude::*;
+use crate::sinks::{aws_kinesis::service::record_error, prelude::*};
 
 use super::{KinesisClient, KinesisError, KinesisRecord, KinesisResponse, Record, SendRecord};
 
@@ -73,7 +73,11 @@ impl SendRecord for KinesisStreamClient {
             .instrument(info_span!("request").or_current())
             .await
             .map(|output: PutRecordsOutput| KinesisResponse {
-                failure_count: output.failed_record_count().unwrap_or(0) as usize,
+                record_errors: output
+                    .records()
+                    .iter()
+                    .map(|record| record_error(record.error_code(), record.error_message()))
+                    .collect(),
                 events_byte_size: CountByteSize(rec_count, JsonSize::new(total_size)).into(),
             })
     }
@@ -9,7 +9,7 @@ use vector_lib::sensitive_string::SensitiveString;
 use crate::{
     codecs::{EncodingConfigWithFraming, Transformer},
     config::{AcknowledgementsConfig, DataType, GenerateConfig, Input, SinkConfig, SinkContext},
-    http::Auth as HttpAuthConfig,
+    http::HttpClientAuth as HttpAuthConfig,
     sinks::{
         http::config::{HttpMethod, HttpSinkConfig},
         util::{
@@ -120,6 +120,7 @@ impl SinkConfig for AxiomConfig {
             method: HttpMethod::Post,
             tls: self.tls.clone(),
             request,
+            load_balance: None,
             acknowledgements: self.acknowledgements,
             batch: self.batch,
             headers: None,
@@ -0,0 +1,396 @@
+//! Encoding of rows in the `ArrowStream` format.
+//!
+//! Each batch is encoded as an Arrow IPC stream holding a single record batch.
+//!
+//! [format]: https://clickhouse.com/docs/en/interfaces/formats#arrowstream
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        ArrayRef, BinaryArray, BinaryDictionaryBuilder, BooleanArray, Date32Array, Decimal128Array,
+        Decimal256Array, FixedSizeBinaryArray, Float32Array, Float64Array, Int16Array, Int32Array,
+        Int64Array, Int8Array, ListArray, MapArray, StringArray, StructArray,
+        TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+        TimestampSecondArray, UInt16Array, UInt32Array, UInt64Array, UInt8Array,
+    },
+    buffer::{NullBuffer, OffsetBuffer},
+    datatypes::{i256, DataType, Field, Fields, Int32Type, Schema, TimeUnit},
+    error::ArrowError,
+    ipc::writer::StreamWriter,
+    record_batch::RecordBatch,
+};
+
+use super::{
+    rows::Cell,
+    schema::{ColumnType, TableSchema},
+};
+
+/// Encodes rows, as converted from events with the same schema, as an Arrow IPC stream.
+pub fn encode_rows(schema: &TableSchema, rows: &[Vec<Cell>]) -> Result<Vec<u8>, ArrowError> {
+    let fields = schema
+        .columns
+        .iter()
+        .map(|column| field(&column.name, &column.column_type))
+        .collect::<Vec<_>>();
+    let arrays = schema
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            let cells = rows.iter().map(|row| &row[index]).collect::<Vec<_>>();
+            build_array(&column.column_type, &cells)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(Arc::clone(&schema), arrays)?;
+    let mut writer = StreamWriter::try_new(Vec::new(), &schema)?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    writer.into_inner()
+}
+
+fn field(name: &str, column_type: &ColumnType) -> Field {
+    Field::new(name, data_type(column_type), column_type.is_nullable())
+}
+
+/// Returns the Arrow type ClickHouse reads into a column type.
+fn data_type(column_type: &ColumnType) -> DataType {
+    match column_type {
+        ColumnType::Nullable(inner) => data_type(inner),
+        ColumnType::LowCardinality(inner) => match strip_nullable(inner) {
+            ColumnType::String | ColumnType::FixedString(_) => {
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Binary))
+            }
+            inner => data_type(inner),
+        },
+        ColumnType::Bool => DataType::Boolean,
+        ColumnType::UInt8 => DataType::UInt8,
+        ColumnType::UInt16 | ColumnType::Date => DataType::UInt16,
+        ColumnType::UInt32 | ColumnType::DateTime | ColumnType::Ipv4 => DataType::UInt32,
+        ColumnType::UInt64 => DataType::UInt64,
+        ColumnType::Int8 | ColumnType::Enum8(_) => DataType::Int8,
+        ColumnType::Int16 | ColumnType::Enum16(_) => DataType::Int16,
+        ColumnType::Int32 => DataType::Int32,
+        ColumnType::Int64 => DataType::Int64,
+        ColumnType::Float32 => DataType::Float32,
+        ColumnType::Float64 => DataType::Float64,
+        ColumnType::String => DataType::Binary,
+        ColumnType::FixedString(length) => DataType::FixedSizeBinary(*length as i32),
+        ColumnType::Date32 => DataType::Date32,
+        ColumnType::DateTime64(precision) => DataType::Timestamp(time_unit(*precision).0, None),
+        // UUIDs are sent as text, which ClickHouse parses.
+        ColumnType::Uuid => DataType::Utf8,
+        ColumnType::Ipv6 => DataType::FixedSizeBinary(16),
+        ColumnType::Decimal { precision, scale } if *precision <= 38 => {
+            DataType::Decimal128(*precision as u8, *scale as i8)
+        }
+        ColumnType::Decimal { precision, scale } => {
+            DataType::Decimal256(*precision as u8, *scale as i8)
+        }
+        ColumnType::Array(inner) => DataType::List(Arc::new(field("item", inner))),
+        ColumnType::Map(key_type, value_type) => {
+            DataType::Map(Arc::new(map_entries_field(key_type, value_type)), false)
+        }
+        ColumnType::Tuple(types) => DataType::Struct(tuple_fields(types)),
+    }
+}
+
+fn strip_nullable(column_type: &ColumnType) -> &ColumnType {
+    match column_type {
+        ColumnType::Nullable(inner) => inner,
+        column_type => column_type,
+    }
+}
+
+/// Returns the unit of the timestamps of a `DateTime64` column, and the factor converting ticks of
+/// the column to that unit.
+fn time_unit(precision: u32) -> (TimeUnit, i64) {
+    match precision {
+        0 => (TimeUnit::Second, 1),
+        1..=3 => (TimeUnit::Millisecond, 10_i64.pow(3 - precision)),
+        4..=6 => (TimeUnit::Microsecond, 10_i64.pow(6 - precision)),
+        _ => (TimeUnit::Nanosecond, 10_i64.pow(9 - precision.min(9))),
+    }
+}
+
+fn map_entries_field(key_type: &ColumnType, value_type: &ColumnType) -> Field {
+    Field::new(
+        "entries",
+        DataType::Struct(Fields::from(vec![
+            Field::new("key", data_type(key_type), false),
+            field("value", value_type),
+        ])),
+        false,
+    )
+}
+
+fn tuple_fields(types: &[ColumnType]) -> Fields {
+    types
+        .iter()
+        .enumerate()
+        .map(|(index, item_type)| field(&(index + 1).to_string(), item_type))
+        .collect()
+}
+
+fn nulls(cells: &[&Cell]) -> Option<NullBuffer> {
+    cells
+        .iter()
+        .any(|cell| matches!(cell, Cell::Null))
+        .then(|| NullBuffer::from_iter(cells.iter().map(|cell| !matches!(cell, Cell::Null))))
+}
+
+fn build_array(column_type: &ColumnType, cells: &[&Cell]) -> Result<ArrayRef, ArrowError> {
+    macro_rules! primitive {
+        ($array:ty, $accessor:ident, $native:ty) => {
+            Arc::new(
+                cells
+                    .iter()
+                    .map(|cell| cell.$accessor().map(|value| value as $native))
+                    .collect::<$array>(),
+            )
+        };
+    }
+
+    Ok(match column_type {
+        ColumnType::Nullable(inner) => build_array(inner, cells)?,
+        ColumnType::LowCardinality(inner) => match strip_nullable(inner) {
+            ColumnType::String | ColumnType::FixedString(_) => {
+                let mut builder = BinaryDictionaryBuilder::<Int32Type>::new();
+                for cell in cells {
+                    match cell.as_bytes() {
+                        Some(bytes) => {
+                            builder.append(bytes)?;
+                        }
+                        None => builder.append_null(),
+                    }
+                }
+                Arc::new(builder.finish())
+            }
+            inner => build_array(inner, cells)?,
+        },
+        ColumnType::Bool => Arc::new(
+            cells
+                .iter()
+                .map(|cell| cell.as_bool())
+                .collect::<BooleanArray>(),
+        ),
+        ColumnType::UInt8 => primitive!(UInt8Array, as_u64, u8),
+        ColumnType::UInt16 => primitive!(UInt16Array, as_u64, u16),
+        ColumnType::Date => primitive!(UInt16Array, as_i64, u16),
+        ColumnType::UInt32 | ColumnType::Ipv4 => primitive!(UInt32Array, as_u64, u32),
+        ColumnType::DateTime => primitive!(UInt32Array, as_i64, u32),
+        ColumnType::UInt64 => primitive!(UInt64Array, as_u64, u64),
+        ColumnType::Int8 | ColumnType::Enum8(_) => primitive!(Int8Array, as_i64, i8),
+        ColumnType::Int16 | ColumnType::Enum16(_) => primitive!(Int16Array, as_i64, i16),
+        ColumnType::Int32 => primitive!(Int32Array, as_i64, i32),
+        ColumnType::Date32 => primitive!(Date32Array, as_i64, i32),
+        ColumnType::Int64 => primitive!(Int64Array, as_i64, i64),
+        ColumnType::Float32 => primitive!(Float32Array, as_f64, f32),
+        ColumnType::Float64 => primitive!(Float64Array, as_f64, f64),
+        ColumnType::DateTime64(precision) => {
+            let (unit, factor) = time_unit(*precision);
+            let ticks = cells
+                .iter()
+                .map(|cell| cell.as_i64().map(|ticks| ticks.saturating_mul(factor)));
+            match unit {
+                TimeUnit::Second => Arc::new(ticks.collect::<TimestampSecondArray>()),
+                TimeUnit::Millisecond => Arc::new(ticks.collect::<TimestampMillisecondArray>()),
+                TimeUnit::Microsecond => Arc::new(ticks.collect::<TimestampMicrosecondArray>()),
+                TimeUnit::Nanosecond => Arc::new(ticks.collect::<TimestampNanosecondArray>()),
+            }
+        }
+        ColumnType::String => Arc::new(
+            cells
+                .iter()
+                .map(|cell| cell.as_bytes())
+                .collect::<BinaryArray>(),
+        ),
+        ColumnType::FixedString(length) => {
+            Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+                cells.iter().map(|cell| cell.as_bytes()),
+                *length as i32,
+            )?)
+        }
+        ColumnType::Uuid => Arc::new(
+            cells
+                .iter()
+                .map(|cell| match cell {
+                    Cell::Uuid(uuid) => Some(uuid::Uuid::from_u128(*uuid).to_string()),
+                    _ => None,
+                })
+                .collect::<StringArray>(),
+        ),
+        ColumnType::Ipv6 => Arc::new(FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+            cells.iter().map(|cell| match cell {
+                Cell::Ipv6(octets) => Some(octets),
+                _ => None,
+            }),
+            16,
+        )?),
+        ColumnType::Decimal { precision, scale } => {
+            let decimals = cells.iter().map(|cell| match cell {
+                Cell::Decimal(decimal) => Some(*decimal),
+                _ => None,
+            });
+            if *precision <= 38 {
+                Arc::new(
+                    decimals
+                        .collect::<Decimal128Array>()
+                        .with_precision_and_scale(*precision as u8, *scale as i8)?,
+                )
+            } else {
+                Arc::new(
+                    decimals
+                        .map(|decimal| decimal.map(i256::from_i128))
+                        .collect::<Decimal256Array>()
+                        .with_precision_and_scale(*precision as u8, *scale as i8)?,
+                )
+            }
+        }
+        ColumnType::Array(inner) => {
+            let items = cells.iter().map(|cell| match cell {
+                Cell::Array(items) => items.as_slice(),
+                _ => &[],
+            });
+            let offsets = OffsetBuffer::from_lengths(items.clone().map(<[_]>::len));
+            let values = build_array(inner, &items.flatten().collect::<Vec<_>>())?;
+            Arc::new(ListArray::try_new(
+                Arc::new(field("item", inner)),
+                offsets,
+                values,
+                nulls(cells),
+            )?)
+        }
+        ColumnType::Map(key_type, value_type) => {
+            let entries = cells.iter().map(|cell| match cell {
+                Cell::Map(entries) => entries.as_slice(),
+                _ => &[],
+            });
+            let offsets = OffsetBuffer::from_lengths(entries.clone().map(<[_]>::len));
+            let keys = entries
+                .clone()
+                .flatten()
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>();
+            let values = entries
+                .flatten()
+                .map(|(_, value)| value)
+                .collect::<Vec<_>>();
+            let entries_field = map_entries_field(key_type, value_type);
+            let DataType::Struct(entry_fields) = entries_field.data_type().clone() else {
+                unreachable!("map entries are structs");
+            };
+            let entries = StructArray::try_new(
+                entry_fields,
+                vec![
+                    build_array(key_type, &keys)?,
+                    build_array(value_type, &values)?,
+                ],
+                None,
+            )?;
+            Arc::new(MapArray::try_new(
+                Arc::new(entries_field),
+                offsets,
+                entries,
+                nulls(cells),
+                false,
+            )?)
+        }
+        ColumnType::Tuple(types) => {
+            let arrays = types
+                .iter()
+                .enumerate()
+                .map(|(index, item_type)| {
+                    let items = cells
+                        .iter()
+                        .map(|cell| match cell {
+                            Cell::Tuple(items) => &items[index],
+                            _ => &Cell::Null,
+                        })
+                        .collect::<Vec<_>>();
+                    build_array(item_type, &items)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Arc::new(StructArray::try_new(
+                tuple_fields(types),
+                arrays,
+                nulls(cells),
+            )?)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use arrow::{
+        array::{Array, AsArray},
+        datatypes::{TimestampMillisecondType, UInt16Type},
+        ipc::reader::StreamReader,
+    };
+    use bytes::Bytes;
+
+    use super::*;
+
+    #[test]
+    fn encodes_stream() {
+        let schema = TableSchema::from_describe(
+            "logs",
+            br#"{"name":"timestamp","type":"DateTime64(3)"}
+{"name":"host","type":"LowCardinality(String)"}
+{"name":"status","type":"Nullable(UInt16)"}
+{"name":"tags","type":"Array(String)"}
+{"name":"labels","type":"Map(String, String)"}
+"#,
+        )
+        .unwrap();
+        let rows = vec![
+            vec![
+                Cell::Int(1_700_000_000_123),
+                Cell::Bytes(Bytes::from("a")),
+                Cell::UInt(200),
+                Cell::Array(vec![Cell::Bytes(Bytes::from("x"))]),
+                Cell::Map(vec![(
+                    Cell::Bytes(Bytes::from("k")),
+                    Cell::Bytes(Bytes::from("v")),
+                )]),
+            ],
+            vec![
+                Cell::Int(1_700_000_000_456),
+                Cell::Bytes(Bytes::from("a")),
+                Cell::Null,
+                Cell::Array(Vec::new()),
+                Cell::Map(Vec::new()),
+            ],
+        ];
+
+        let stream = encode_rows(&schema, &rows).unwrap();
+        let batches = StreamReader::try_new(Cursor::new(stream), None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+
+        let timestamps = batch.column(0).as_primitive::<TimestampMillisecondType>();
+        assert_eq!(timestamps.value(1), 1_700_000_000_456);
+
+        let hosts = batch.column(1).as_dictionary::<Int32Type>();
+        assert_eq!(hosts.values().len(), 1);
+
+        let statuses = batch.column(2).as_primitive::<UInt16Type>();
+        assert_eq!(statuses.value(0), 200);
+        assert!(statuses.is_null(1));
+        assert!(batch.schema().field(2).is_nullable());
+
+        let tags = batch.column(3).as_list::<i32>();
+        assert_eq!(tags.value_offsets(), [0, 1, 1]);
+
+        let labels = batch.column(4).as_map();
+        assert_eq!(labels.value_offsets(), [0, 1, 1]);
+    }
+}
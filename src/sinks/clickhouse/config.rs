@@ -1,9 +1,10 @@
 //! Configuration for the `Clickhouse` sink.
 
 use super::{
-    request_builder::ClickhouseRequestBuilder,
-    service::{ClickhouseRetryLogic, ClickhouseServiceRequestBuilder},
-    sink::{ClickhouseSink, PartitionKey},
+    request_builder::{ClickhouseEncoder, ClickhouseRequestBuilder},
+    schema::SchemaCache,
+    service::{ClickhouseRetryLogic, ClickhouseService, ClickhouseServiceRequestBuilder},
+    sink::ClickhouseSink,
 };
 use crate::{
     http::{Auth, HttpClient, MaybeAuth},
@@ -14,7 +15,7 @@ use crate::{
 };
 use http::{Request, StatusCode, Uri};
 use hyper::Body;
-use std::fmt;
+use std::{fmt, sync::Arc};
 use vector_lib::codecs::{encoding::Framer, JsonSerializerConfig, NewlineDelimitedEncoderConfig};
 
 /// Data format.
@@ -37,6 +38,26 @@ pub enum Format {
 
     /// JSONAsString.
     JsonAsString,
+
+    /// RowBinary.
+    ///
+    /// Events are converted to the types of the columns of the table, whose schema is fetched with
+    /// `DESCRIBE TABLE`, and encoded in the native binary format of ClickHouse, which is much cheaper
+    /// for ClickHouse to parse than JSON. Events that can't be converted are rejected.
+    RowBinary,
+
+    /// ArrowStream.
+    ///
+    /// Events are converted to the types of the columns of the table like with `row_binary`, and
+    /// each batch is encoded as an Arrow IPC stream.
+    ArrowStream,
+}
+
+impl Format {
+    /// Returns whether events are encoded following the schema of the table.
+    pub const fn uses_schema(self) -> bool {
+        matches!(self, Format::RowBinary | Format::ArrowStream)
+    }
 }
 
 impl fmt::Display for Format {
@@ -45,6 +66,8 @@ impl fmt::Display for Format {
             Format::JsonEachRow => write!(f, "JSONEachRow"),
             Format::JsonAsObject => write!(f, "JSONAsObject"),
             Format::JsonAsString => write!(f, "JSONAsString"),
+            Format::RowBinary => write!(f, "RowBinary"),
+            Format::ArrowStream => write!(f, "ArrowStream"),
         }
     }
 }
@@ -73,11 +96,14 @@ pub struct ClickhouseConfig {
 
     /// Sets `input_format_skip_unknown_fields`, allowing ClickHouse to discard fields not present in the table schema.
     ///
-    /// If left unspecified, use the default provided by the `ClickHouse` server.
+    /// If left unspecified, use the default provided by the `ClickHouse` server. Fields not present in
+    /// the table schema are always discarded by the `row_binary` and `arrow_stream` formats.
     #[serde(default)]
     pub skip_unknown_fields: Option<bool>,
 
     /// Sets `date_time_input_format` to `best_effort`, allowing ClickHouse to properly parse RFC3339/ISO 8601.
+    ///
+    /// The `row_binary` and `arrow_stream` formats always parse RFC 3339 timestamps.
     #[serde(default)]
     pub date_time_best_effort: bool,
 
@@ -196,11 +222,22 @@ impl SinkConfig for ClickhouseConfig {
             query_settings: self.query_settings,
         };
 
-        let service: HttpService<ClickhouseServiceRequestBuilder, PartitionKey> =
-            HttpService::new(client.clone(), clickhouse_service_request_builder);
-
         let request_limits = self.request.into_settings();
 
+        let schemas = self.format.uses_schema().then(|| {
+            Arc::new(SchemaCache::new(
+                client.clone(),
+                endpoint.clone(),
+                auth.clone(),
+                &request_limits,
+            ))
+        });
+
+        let service = ClickhouseService::new(
+            HttpService::new(client.clone(), clickhouse_service_request_builder),
+            schemas.clone(),
+        );
+
         let service = ServiceBuilder::new()
             .settings(request_limits, ClickhouseRetryLogic::default())
             .service(service);
@@ -215,15 +252,28 @@ impl SinkConfig for ClickhouseConfig {
 
         let request_builder = ClickhouseRequestBuilder {
             compression: self.compression,
-            encoding: (
-                self.encoding.clone(),
-                Encoder::<Framer>::new(
-                    NewlineDelimitedEncoderConfig.build().into(),
-                    JsonSerializerConfig::default().build().into(),
+            encoder: ClickhouseEncoder {
+                format: self.format,
+                json: (
+                    self.encoding.clone(),
+                    Encoder::<Framer>::new(
+                        NewlineDelimitedEncoderConfig.build().into(),
+                        JsonSerializerConfig::default().build().into(),
+                    ),
                 ),
-            ),
+            },
         };
 
+        // The schema of a static table is fetched by the healthcheck, so that unsupported types are
+        // reported at startup.
+        let static_table = (!database.is_dynamic() && !self.table.is_dynamic()).then(|| {
+            (
+                database.get_ref().to_owned(),
+                self.table.get_ref().to_owned(),
+            )
+        });
+        let healthcheck_schema = schemas.clone().zip(static_table);
+
         let sink = ClickhouseSink::new(
             batch_settings,
             service,
@@ -231,9 +281,11 @@ impl SinkConfig for ClickhouseConfig {
             self.table.clone(),
             self.format,
             request_builder,
+            schemas,
+            self.encoding.clone(),
         );
 
-        let healthcheck = Box::pin(healthcheck(client, endpoint, auth));
+        let healthcheck = Box::pin(healthcheck(client, endpoint, auth, healthcheck_schema));
 
         Ok((VectorSink::from_event_streamsink(sink), healthcheck))
     }
@@ -256,7 +308,12 @@ fn get_healthcheck_uri(endpoint: &Uri) -> String {
     uri
 }
 
-async fn healthcheck(client: HttpClient, endpoint: Uri, auth: Option<Auth>) -> crate::Result<()> {
+async fn healthcheck(
+    client: HttpClient,
+    endpoint: Uri,
+    auth: Option<Auth>,
+    schema: Option<(Arc<SchemaCache>, (String, String))>,
+) -> crate::Result<()> {
     let uri = get_healthcheck_uri(&endpoint);
     let mut request = Request::get(uri).body(Body::empty()).unwrap();
 
@@ -267,9 +324,14 @@ async fn healthcheck(client: HttpClient, endpoint: Uri, auth: Option<Auth>) -> c
     let response = client.send(request).await?;
 
     match response.status() {
-        StatusCode::OK => Ok(()),
-        status => Err(HealthcheckError::UnexpectedStatus { status }.into()),
+        StatusCode::OK => {}
+        status => return Err(HealthcheckError::UnexpectedStatus { status }.into()),
+    }
+
+    if let Some((schemas, (database, table))) = schema {
+        schemas.get(&database, &table).await?;
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -7,11 +7,19 @@
 //! `INSERT INTO my_db.my_table FORMAT JSONEachRow`. The event payload is encoded as new-line
 //! delimited JSON.
 //!
+//! With the `RowBinary` and `ArrowStream` formats, the schema of the table is fetched with
+//! `DESCRIBE TABLE`, and events are converted to rows of typed values before being encoded in the
+//! binary format.
+//!
 //! This sink only supports logs for now but could support metrics and traces as well in the future.
 
+mod arrow;
 pub mod config;
 #[cfg(all(test, feature = "clickhouse-integration-tests"))]
 mod integration_tests;
 mod request_builder;
+mod row_binary;
+mod rows;
+mod schema;
 mod service;
 mod sink;
@@ -1,19 +1,78 @@
 //! `RequestBuilder` implementation for the `Clickhouse` sink.
 
-use super::sink::PartitionKey;
-use crate::sinks::{prelude::*, util::http::HttpRequest};
-use bytes::Bytes;
+use std::{io, sync::Arc};
+
+use super::{
+    arrow,
+    config::Format,
+    row_binary,
+    rows::Cell,
+    schema::TableSchema,
+    sink::{ClickhouseBatch, PartitionKey},
+};
+use crate::sinks::{
+    prelude::*,
+    util::{encoding::Encoder as _, http::HttpRequest},
+};
+use bytes::{Bytes, BytesMut};
 use vector_lib::codecs::encoding::Framer;
 
 pub(super) struct ClickhouseRequestBuilder {
     pub(super) compression: Compression,
-    pub(super) encoding: (Transformer, Encoder<Framer>),
+    pub(super) encoder: ClickhouseEncoder,
+}
+
+/// The events of a request, or their rows when they follow the schema of the table.
+pub(super) enum ClickhouseEvents {
+    Json(Vec<Event>),
+    Rows {
+        schema: Arc<TableSchema>,
+        rows: Vec<Vec<Cell>>,
+        byte_size: GroupedCountByteSize,
+    },
+}
+
+pub(super) struct ClickhouseEncoder {
+    pub(super) format: Format,
+    pub(super) json: (Transformer, Encoder<Framer>),
+}
+
+impl encoding::Encoder<ClickhouseEvents> for ClickhouseEncoder {
+    fn encode_input(
+        &self,
+        input: ClickhouseEvents,
+        writer: &mut dyn io::Write,
+    ) -> io::Result<(usize, GroupedCountByteSize)> {
+        match input {
+            ClickhouseEvents::Json(events) => self.json.encode_input(events, writer),
+            ClickhouseEvents::Rows {
+                schema,
+                rows,
+                byte_size,
+            } => {
+                let payload = match self.format {
+                    Format::ArrowStream => {
+                        arrow::encode_rows(&schema, &rows).map_err(io::Error::other)?
+                    }
+                    _ => {
+                        let mut buffer = BytesMut::new();
+                        for row in &rows {
+                            row_binary::encode_row(&schema, row, &mut buffer);
+                        }
+                        buffer.to_vec()
+                    }
+                };
+                encoding::write_all(writer, rows.len(), &payload)?;
+                Ok((payload.len(), byte_size))
+            }
+        }
+    }
 }
 
-impl RequestBuilder<(PartitionKey, Vec<Event>)> for ClickhouseRequestBuilder {
+impl RequestBuilder<(PartitionKey, ClickhouseBatch)> for ClickhouseRequestBuilder {
     type Metadata = (PartitionKey, EventFinalizers);
-    type Events = Vec<Event>;
-    type Encoder = (Transformer, Encoder<Framer>);
+    type Events = ClickhouseEvents;
+    type Encoder = ClickhouseEncoder;
     type Payload = Bytes;
     type Request = HttpRequest<PartitionKey>;
     type Error = std::io::Error;
@@ -23,18 +82,40 @@ impl RequestBuilder<(PartitionKey, Vec<Event>)> for ClickhouseRequestBuilder {
     }
 
     fn encoder(&self) -> &Self::Encoder {
-        &self.encoding
+        &self.encoder
     }
 
     fn split_input(
         &self,
-        input: (PartitionKey, Vec<Event>),
+        input: (PartitionKey, ClickhouseBatch),
     ) -> (Self::Metadata, RequestMetadataBuilder, Self::Events) {
-        let (key, mut events) = input;
+        let (key, batch) = input;
 
-        let finalizers = events.take_finalizers();
-        let builder = RequestMetadataBuilder::from_events(&events);
-        ((key, finalizers), builder, events)
+        match batch {
+            ClickhouseBatch::Events(mut events) => {
+                let finalizers = events.take_finalizers();
+                let builder = RequestMetadataBuilder::from_events(&events);
+                ((key, finalizers), builder, ClickhouseEvents::Json(events))
+            }
+            ClickhouseBatch::Rows {
+                schema,
+                mut events,
+                rows,
+            } => {
+                let finalizers = events.take_finalizers();
+                let builder = RequestMetadataBuilder::from_events(&events);
+                let mut byte_size = telemetry().create_request_count_byte_size();
+                for event in &events {
+                    byte_size.add_event(event, event.estimated_json_encoded_size_of());
+                }
+                let events = ClickhouseEvents::Rows {
+                    schema,
+                    rows,
+                    byte_size,
+                };
+                ((key, finalizers), builder, events)
+            }
+        }
     }
 
     fn build_request(
@@ -44,15 +125,6 @@ impl RequestBuilder<(PartitionKey, Vec<Event>)> for ClickhouseRequestBuilder {
         payload: EncodeResult<Self::Payload>,
     ) -> Self::Request {
         let (key, finalizers) = metadata;
-        HttpRequest::new(
-            payload.into_payload(),
-            finalizers,
-            request_metadata,
-            PartitionKey {
-                database: key.database,
-                table: key.table,
-                format: key.format,
-            },
-        )
+        HttpRequest::new(payload.into_payload(), finalizers, request_metadata, key)
     }
 }
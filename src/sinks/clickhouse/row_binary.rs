@@ -0,0 +1,214 @@
+//! Encoding of rows in the `RowBinary` format.
+//!
+//! [format]: https://clickhouse.com/docs/en/interfaces/formats#rowbinary
+
+use bytes::{BufMut, BytesMut};
+
+use super::{
+    rows::Cell,
+    schema::{ColumnType, TableSchema},
+};
+
+/// Encodes a row, as converted from an event with the same schema.
+pub fn encode_row(schema: &TableSchema, row: &[Cell], buffer: &mut BytesMut) {
+    for (column, cell) in schema.columns.iter().zip(row) {
+        encode_cell(&column.column_type, cell, buffer);
+    }
+}
+
+fn encode_cell(column_type: &ColumnType, cell: &Cell, buffer: &mut BytesMut) {
+    match column_type {
+        ColumnType::Nullable(inner) => {
+            if matches!(cell, Cell::Null) {
+                buffer.put_u8(1);
+            } else {
+                buffer.put_u8(0);
+                encode_cell(inner, cell, buffer);
+            }
+        }
+        // Low cardinality columns are sent as their inner type, and encoded by ClickHouse.
+        ColumnType::LowCardinality(inner) => encode_cell(inner, cell, buffer),
+        ColumnType::Bool => buffer.put_u8(cell.as_bool().unwrap_or_default().into()),
+        ColumnType::UInt8 => buffer.put_u8(cell.as_u64().unwrap_or_default() as u8),
+        ColumnType::UInt16 => buffer.put_u16_le(cell.as_u64().unwrap_or_default() as u16),
+        ColumnType::UInt32 | ColumnType::Ipv4 => {
+            buffer.put_u32_le(cell.as_u64().unwrap_or_default() as u32)
+        }
+        ColumnType::UInt64 => buffer.put_u64_le(cell.as_u64().unwrap_or_default()),
+        ColumnType::Int8 | ColumnType::Enum8(_) => {
+            buffer.put_i8(cell.as_i64().unwrap_or_default() as i8)
+        }
+        ColumnType::Int16 | ColumnType::Enum16(_) => {
+            buffer.put_i16_le(cell.as_i64().unwrap_or_default() as i16)
+        }
+        ColumnType::Int32 | ColumnType::Date32 => {
+            buffer.put_i32_le(cell.as_i64().unwrap_or_default() as i32)
+        }
+        ColumnType::Int64 | ColumnType::DateTime64(_) => {
+            buffer.put_i64_le(cell.as_i64().unwrap_or_default())
+        }
+        ColumnType::Date => buffer.put_u16_le(cell.as_i64().unwrap_or_default() as u16),
+        ColumnType::DateTime => buffer.put_u32_le(cell.as_i64().unwrap_or_default() as u32),
+        ColumnType::Float32 => buffer.put_f32_le(cell.as_f64().unwrap_or_default() as f32),
+        ColumnType::Float64 => buffer.put_f64_le(cell.as_f64().unwrap_or_default()),
+        ColumnType::String => {
+            let bytes = cell.as_bytes().unwrap_or_default();
+            put_length(buffer, bytes.len());
+            buffer.put_slice(bytes);
+        }
+        // Fixed strings are padded to their length by the conversion.
+        ColumnType::FixedString(_) => buffer.put_slice(cell.as_bytes().unwrap_or_default()),
+        ColumnType::Uuid => {
+            // UUIDs are encoded as their two halves, each in little-endian order.
+            let uuid = match cell {
+                Cell::Uuid(uuid) => *uuid,
+                _ => 0,
+            };
+            buffer.put_u64_le((uuid >> 64) as u64);
+            buffer.put_u64_le(uuid as u64);
+        }
+        ColumnType::Ipv6 => match cell {
+            Cell::Ipv6(octets) => buffer.put_slice(octets),
+            _ => buffer.put_slice(&[0; 16]),
+        },
+        ColumnType::Decimal { precision, .. } => {
+            let decimal = match cell {
+                Cell::Decimal(decimal) => *decimal,
+                _ => 0,
+            };
+            match precision {
+                0..=9 => buffer.put_i32_le(decimal as i32),
+                10..=18 => buffer.put_i64_le(decimal as i64),
+                19..=38 => buffer.put_i128_le(decimal),
+                _ => {
+                    // Sign-extend to 256 bits.
+                    buffer.put_i128_le(decimal);
+                    buffer.put_i128_le(if decimal < 0 { -1 } else { 0 });
+                }
+            }
+        }
+        ColumnType::Array(inner) => {
+            let items = match cell {
+                Cell::Array(items) => items.as_slice(),
+                _ => &[],
+            };
+            put_length(buffer, items.len());
+            for item in items {
+                encode_cell(inner, item, buffer);
+            }
+        }
+        ColumnType::Map(key_type, value_type) => {
+            let entries = match cell {
+                Cell::Map(entries) => entries.as_slice(),
+                _ => &[],
+            };
+            put_length(buffer, entries.len());
+            for (key, value) in entries {
+                encode_cell(key_type, key, buffer);
+                encode_cell(value_type, value, buffer);
+            }
+        }
+        ColumnType::Tuple(types) => {
+            let items = match cell {
+                Cell::Tuple(items) => items.as_slice(),
+                _ => &[],
+            };
+            for (item_type, item) in types.iter().zip(items) {
+                encode_cell(item_type, item, buffer);
+            }
+        }
+    }
+}
+
+/// Writes a length as an unsigned LEB128 integer.
+fn put_length(buffer: &mut BytesMut, length: usize) {
+    let mut length = length as u64;
+    while length >= 0x80 {
+        buffer.put_u8((length as u8) | 0x80);
+        length >>= 7;
+    }
+    buffer.put_u8(length as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn encode(type_name: &str, cell: Cell) -> Vec<u8> {
+        let mut buffer = BytesMut::new();
+        encode_cell(&type_name.parse().unwrap(), &cell, &mut buffer);
+        buffer.to_vec()
+    }
+
+    #[test]
+    fn encodes_scalars() {
+        assert_eq!(encode("UInt16", Cell::UInt(0x0102)), [0x02, 0x01]);
+        assert_eq!(encode("Int8", Cell::Int(-1)), [0xff]);
+        assert_eq!(encode("Bool", Cell::Bool(true)), [1]);
+        assert_eq!(
+            encode("DateTime64(3)", Cell::Int(1)),
+            [1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(encode("Date", Cell::Int(10)), [10, 0]);
+        assert_eq!(encode("Float32", Cell::Float(1.0)), 1.0_f32.to_le_bytes());
+        assert_eq!(encode("IPv4", Cell::UInt(0xc0a8_0001)), [1, 0, 0xa8, 0xc0]);
+        assert_eq!(
+            encode("Decimal(10, 2)", Cell::Decimal(-150)),
+            (-150_i64).to_le_bytes()
+        );
+        assert_eq!(
+            encode(
+                "UUID",
+                Cell::Uuid(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10)
+            ),
+            [8, 7, 6, 5, 4, 3, 2, 1, 0x10, 0x0f, 0x0e, 0x0d, 0x0c, 0x0b, 0x0a, 9]
+        );
+    }
+
+    #[test]
+    fn encodes_strings() {
+        assert_eq!(
+            encode("String", Cell::Bytes(Bytes::from("abc"))),
+            b"\x03abc"
+        );
+        let long = "x".repeat(300);
+        let encoded = encode("String", Cell::Bytes(Bytes::from(long.clone())));
+        assert_eq!(&encoded[..2], [0xac, 0x02]);
+        assert_eq!(&encoded[2..], long.as_bytes());
+        assert_eq!(
+            encode(
+                "LowCardinality(Nullable(String))",
+                Cell::Bytes(Bytes::from("a"))
+            ),
+            b"\x00\x01a"
+        );
+        assert_eq!(encode("Nullable(String)", Cell::Null), [1]);
+    }
+
+    #[test]
+    fn encodes_composites() {
+        assert_eq!(
+            encode(
+                "Array(Nullable(UInt8))",
+                Cell::Array(vec![Cell::UInt(1), Cell::Null])
+            ),
+            [2, 0, 1, 1]
+        );
+        assert_eq!(
+            encode(
+                "Map(String, UInt8)",
+                Cell::Map(vec![(Cell::Bytes(Bytes::from("a")), Cell::UInt(7))])
+            ),
+            [1, 1, b'a', 7]
+        );
+        assert_eq!(
+            encode(
+                "Tuple(UInt8, String)",
+                Cell::Tuple(vec![Cell::UInt(7), Cell::Bytes(Bytes::from("b"))])
+            ),
+            [7, 1, b'b']
+        );
+    }
+}
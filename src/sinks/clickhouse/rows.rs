@@ -0,0 +1,611 @@
+//! Conversion of events to rows of typed values, following the schema of the destination table.
+
+use std::net::IpAddr;
+
+use bytes::Bytes;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use snafu::Snafu;
+use vector_lib::event::{Event, LogEvent, Value};
+use vrl::event_path;
+
+use super::schema::{ColumnType, TableSchema};
+
+/// The value of a column, converted to its type.
+///
+/// Integers are stored in 64 bits whatever their width, and dates and times as their number of
+/// days, seconds or ticks since the Unix epoch.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cell {
+    Null,
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Bytes(Bytes),
+    Decimal(i128),
+    Uuid(u128),
+    Ipv6([u8; 16]),
+    Array(Vec<Cell>),
+    Map(Vec<(Cell, Cell)>),
+    Tuple(Vec<Cell>),
+}
+
+impl Cell {
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub const fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub const fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::UInt(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub const fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// An event whose value can't be converted to the type of a column.
+#[derive(Debug, Snafu)]
+#[snafu(display("Invalid value for column `{column}` of type `{type_name}`: {reason}"))]
+pub struct ConversionError {
+    pub column: String,
+    pub type_name: String,
+    pub reason: String,
+}
+
+/// Converts an event to a row of the table.
+///
+/// Each column is populated from the top-level field with the same name. Missing fields and `null`
+/// values are inserted as `NULL` into nullable columns, and as the default value of the type, such
+/// as zero or an empty string, into the other columns.
+pub fn convert_event(schema: &TableSchema, event: &Event) -> Result<Vec<Cell>, ConversionError> {
+    let log = event.maybe_as_log();
+    schema
+        .columns
+        .iter()
+        .map(|column| {
+            let value = log.and_then(|log| field(log, &column.name));
+            convert(value, &column.column_type).map_err(|reason| ConversionError {
+                column: column.name.clone(),
+                type_name: column.type_name.clone(),
+                reason,
+            })
+        })
+        .collect()
+}
+
+fn field<'a>(log: &'a LogEvent, name: &str) -> Option<&'a Value> {
+    // Columns of nested fields are named after their path, such as `http.status`.
+    log.get(event_path!(name))
+        .or_else(|| log.parse_path_and_get_value(name).ok().flatten())
+}
+
+fn convert(value: Option<&Value>, column_type: &ColumnType) -> Result<Cell, String> {
+    let value = match value {
+        None | Some(Value::Null) => return Ok(default(column_type)),
+        Some(value) => value,
+    };
+
+    match column_type {
+        ColumnType::Nullable(inner) | ColumnType::LowCardinality(inner) => {
+            convert(Some(value), inner)
+        }
+        ColumnType::Bool => match value {
+            Value::Boolean(value) => Ok(Cell::Bool(*value)),
+            Value::Integer(value @ (0 | 1)) => Ok(Cell::Bool(*value == 1)),
+            Value::Bytes(bytes) => match bytes.as_ref() {
+                b"true" | b"1" => Ok(Cell::Bool(true)),
+                b"false" | b"0" => Ok(Cell::Bool(false)),
+                _ => Err(unexpected(value)),
+            },
+            _ => Err(unexpected(value)),
+        },
+        ColumnType::UInt8 => unsigned(value, u8::MAX.into()),
+        ColumnType::UInt16 => unsigned(value, u16::MAX.into()),
+        ColumnType::UInt32 => unsigned(value, u32::MAX.into()),
+        ColumnType::UInt64 => unsigned(value, u64::MAX),
+        ColumnType::Int8 => signed(value, i8::MIN.into(), i8::MAX.into()),
+        ColumnType::Int16 => signed(value, i16::MIN.into(), i16::MAX.into()),
+        ColumnType::Int32 => signed(value, i32::MIN.into(), i32::MAX.into()),
+        ColumnType::Int64 => signed(value, i64::MIN, i64::MAX),
+        ColumnType::Float32 | ColumnType::Float64 => match value {
+            Value::Float(value) => Ok(Cell::Float(value.into_inner())),
+            Value::Integer(value) => Ok(Cell::Float(*value as f64)),
+            Value::Bytes(bytes) => parse_text::<f64>(bytes).map(Cell::Float),
+            _ => Err(unexpected(value)),
+        },
+        ColumnType::String => Ok(Cell::Bytes(text(value))),
+        ColumnType::FixedString(length) => {
+            let bytes = text(value);
+            if bytes.len() > *length {
+                return Err(format!(
+                    "{} bytes exceed the length of the string",
+                    bytes.len()
+                ));
+            }
+            let mut padded = bytes.to_vec();
+            padded.resize(*length, 0);
+            Ok(Cell::Bytes(padded.into()))
+        }
+        ColumnType::Date => {
+            let days = days(value)?;
+            if !(0..=i64::from(u16::MAX)).contains(&days) {
+                return Err(format!("{days} days since the epoch are out of range"));
+            }
+            Ok(Cell::Int(days))
+        }
+        ColumnType::Date32 => {
+            let days = days(value)?;
+            if !(i64::from(i32::MIN)..=i64::from(i32::MAX)).contains(&days) {
+                return Err(format!("{days} days since the epoch are out of range"));
+            }
+            Ok(Cell::Int(days))
+        }
+        ColumnType::DateTime => {
+            let timestamp = timestamp(value)?;
+            let seconds = timestamp.timestamp();
+            if !(0..=i64::from(u32::MAX)).contains(&seconds) {
+                return Err(format!("{timestamp} is out of range"));
+            }
+            Ok(Cell::Int(seconds))
+        }
+        ColumnType::DateTime64(precision) => {
+            let timestamp = timestamp(value)?;
+            let ticks = timestamp
+                .timestamp()
+                .checked_mul(10_i64.pow(*precision))
+                .and_then(|ticks| {
+                    ticks.checked_add(i64::from(
+                        timestamp.timestamp_subsec_nanos() / 10_u32.pow(9 - precision),
+                    ))
+                })
+                .ok_or_else(|| format!("{timestamp} is out of range"))?;
+            Ok(Cell::Int(ticks))
+        }
+        ColumnType::Uuid => match value {
+            Value::Bytes(bytes) => {
+                parse_text::<uuid::Uuid>(bytes).map(|uuid| Cell::Uuid(uuid.as_u128()))
+            }
+            _ => Err(unexpected(value)),
+        },
+        ColumnType::Ipv4 => match value {
+            Value::Bytes(bytes) => {
+                parse_text::<std::net::Ipv4Addr>(bytes).map(|ip| Cell::UInt(u32::from(ip).into()))
+            }
+            Value::Integer(_) => unsigned(value, u32::MAX.into()),
+            _ => Err(unexpected(value)),
+        },
+        ColumnType::Ipv6 => match value {
+            Value::Bytes(bytes) => parse_text::<IpAddr>(bytes).map(|ip| {
+                let ip = match ip {
+                    IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+                    IpAddr::V6(ip) => ip,
+                };
+                Cell::Ipv6(ip.octets())
+            }),
+            _ => Err(unexpected(value)),
+        },
+        ColumnType::Decimal { precision, scale } => {
+            let decimal = decimal(value, *scale)?;
+            let limit = 10_i128.checked_pow(*precision).unwrap_or(i128::MAX);
+            if decimal.unsigned_abs() >= limit.unsigned_abs() {
+                return Err(format!("{value} exceeds the precision of the decimal"));
+            }
+            Ok(Cell::Decimal(decimal))
+        }
+        ColumnType::Enum8(values) => enum_value(value, values).map(Cell::Int),
+        ColumnType::Enum16(values) => enum_value(value, values).map(Cell::Int),
+        ColumnType::Array(inner) => match value {
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| {
+                    convert(Some(item), inner).map_err(|reason| format!("[{index}]: {reason}"))
+                })
+                .collect::<Result<_, _>>()
+                .map(Cell::Array),
+            _ => Err(unexpected(value)),
+        },
+        ColumnType::Map(key_type, value_type) => match value {
+            Value::Object(fields) => fields
+                .iter()
+                .map(|(key, item)| {
+                    let key_cell = convert(Some(&Value::from(key.as_str())), key_type)
+                        .map_err(|reason| format!("key {key}: {reason}"))?;
+                    let value_cell = convert(Some(item), value_type)
+                        .map_err(|reason| format!("[{key}]: {reason}"))?;
+                    Ok((key_cell, value_cell))
+                })
+                .collect::<Result<_, String>>()
+                .map(Cell::Map),
+            _ => Err(unexpected(value)),
+        },
+        ColumnType::Tuple(types) => match value {
+            Value::Array(items) if items.len() == types.len() => items
+                .iter()
+                .zip(types)
+                .enumerate()
+                .map(|(index, (item, item_type))| {
+                    convert(Some(item), item_type).map_err(|reason| format!("[{index}]: {reason}"))
+                })
+                .collect::<Result<_, _>>()
+                .map(Cell::Tuple),
+            _ => Err(format!(
+                "expected an array of {} elements, found {}",
+                types.len(),
+                value.kind_str()
+            )),
+        },
+    }
+}
+
+/// Returns the value of missing fields.
+fn default(column_type: &ColumnType) -> Cell {
+    match column_type {
+        ColumnType::Nullable(_) => Cell::Null,
+        ColumnType::LowCardinality(inner) => default(inner),
+        ColumnType::Bool => Cell::Bool(false),
+        ColumnType::UInt8
+        | ColumnType::UInt16
+        | ColumnType::UInt32
+        | ColumnType::UInt64
+        | ColumnType::Ipv4 => Cell::UInt(0),
+        ColumnType::Int8
+        | ColumnType::Int16
+        | ColumnType::Int32
+        | ColumnType::Int64
+        | ColumnType::Date
+        | ColumnType::Date32
+        | ColumnType::DateTime
+        | ColumnType::DateTime64(_) => Cell::Int(0),
+        ColumnType::Float32 | ColumnType::Float64 => Cell::Float(0.0),
+        ColumnType::String => Cell::Bytes(Bytes::new()),
+        ColumnType::FixedString(length) => Cell::Bytes(vec![0; *length].into()),
+        ColumnType::Uuid => Cell::Uuid(0),
+        ColumnType::Ipv6 => Cell::Ipv6([0; 16]),
+        ColumnType::Decimal { .. } => Cell::Decimal(0),
+        ColumnType::Enum8(values) => {
+            Cell::Int(values.first().map_or(0, |(_, value)| (*value).into()))
+        }
+        ColumnType::Enum16(values) => {
+            Cell::Int(values.first().map_or(0, |(_, value)| (*value).into()))
+        }
+        ColumnType::Array(_) => Cell::Array(Vec::new()),
+        ColumnType::Map(_, _) => Cell::Map(Vec::new()),
+        ColumnType::Tuple(types) => Cell::Tuple(types.iter().map(default).collect()),
+    }
+}
+
+fn unexpected(value: &Value) -> String {
+    format!("unexpected {} value", value.kind_str())
+}
+
+fn parse_text<T: std::str::FromStr>(bytes: &Bytes) -> Result<T, String> {
+    std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|text| text.trim().parse().ok())
+        .ok_or_else(|| format!("can't parse {:?}", String::from_utf8_lossy(bytes)))
+}
+
+fn integer(value: &Value) -> Result<i128, String> {
+    match value {
+        Value::Integer(value) => Ok((*value).into()),
+        Value::Boolean(value) => Ok((*value).into()),
+        Value::Float(float) if float.fract() == 0.0 && float.is_finite() => {
+            Ok(float.into_inner() as i128)
+        }
+        Value::Bytes(bytes) => parse_text(bytes),
+        _ => Err(unexpected(value)),
+    }
+}
+
+fn unsigned(value: &Value, max: u64) -> Result<Cell, String> {
+    let integer = integer(value)?;
+    u64::try_from(integer)
+        .ok()
+        .filter(|integer| *integer <= max)
+        .map(Cell::UInt)
+        .ok_or_else(|| format!("{integer} is out of range"))
+}
+
+fn signed(value: &Value, min: i64, max: i64) -> Result<Cell, String> {
+    let integer = integer(value)?;
+    i64::try_from(integer)
+        .ok()
+        .filter(|integer| (min..=max).contains(integer))
+        .map(Cell::Int)
+        .ok_or_else(|| format!("{integer} is out of range"))
+}
+
+/// Returns the text of a string column, serializing other values.
+fn text(value: &Value) -> Bytes {
+    match value {
+        Value::Bytes(bytes) => bytes.clone(),
+        Value::Timestamp(timestamp) => timestamp.to_rfc3339().into(),
+        Value::Object(_) | Value::Array(_) => serde_json::to_vec(value)
+            .map(Into::into)
+            .unwrap_or_default(),
+        value => value.to_string_lossy().into_owned().into(),
+    }
+}
+
+/// Parses timestamps from timestamps, RFC 3339 strings, `YYYY-MM-DD hh:mm:ss` strings in UTC, and
+/// numbers of seconds since the epoch.
+fn timestamp(value: &Value) -> Result<DateTime<Utc>, String> {
+    match value {
+        Value::Timestamp(timestamp) => Ok(*timestamp),
+        Value::Integer(seconds) => DateTime::from_timestamp(*seconds, 0)
+            .ok_or_else(|| format!("{seconds} is out of range")),
+        Value::Float(seconds) => {
+            let seconds = seconds.into_inner();
+            DateTime::from_timestamp(seconds.floor() as i64, (seconds.fract().abs() * 1e9) as u32)
+                .ok_or_else(|| format!("{seconds} is out of range"))
+        }
+        Value::Bytes(bytes) => {
+            let text = String::from_utf8_lossy(bytes);
+            DateTime::parse_from_rfc3339(text.trim())
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(text.trim(), "%Y-%m-%d %H:%M:%S%.f")
+                        .map(|timestamp| timestamp.and_utc())
+                })
+                .map_err(|_| format!("can't parse {text:?} as a timestamp"))
+        }
+        _ => Err(unexpected(value)),
+    }
+}
+
+/// Returns the number of days since the epoch of dates, timestamps, or numbers of days.
+fn days(value: &Value) -> Result<i64, String> {
+    match value {
+        Value::Integer(days) => Ok(*days),
+        Value::Bytes(bytes) => {
+            let text = String::from_utf8_lossy(bytes);
+            match NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d") {
+                Ok(date) => Ok(date.signed_duration_since(NaiveDate::default()).num_days()),
+                Err(_) => timestamp(value).map(|timestamp| timestamp.timestamp().div_euclid(86400)),
+            }
+        }
+        value => timestamp(value).map(|timestamp| timestamp.timestamp().div_euclid(86400)),
+    }
+}
+
+/// Returns the value of a decimal, scaled by `10^scale`.
+fn decimal(value: &Value, scale: u32) -> Result<i128, String> {
+    let out_of_range = || format!("{value} is out of range");
+    let factor = 10_i128.checked_pow(scale).ok_or_else(out_of_range)?;
+    match value {
+        Value::Integer(integer) => i128::from(*integer)
+            .checked_mul(factor)
+            .ok_or_else(out_of_range),
+        Value::Float(float) => {
+            let scaled = (float.into_inner() * factor as f64).round();
+            if scaled.is_finite() && scaled.abs() < i128::MAX as f64 {
+                Ok(scaled as i128)
+            } else {
+                Err(out_of_range())
+            }
+        }
+        Value::Bytes(bytes) => {
+            // Parse the digits exactly rather than going through floats.
+            let text = String::from_utf8_lossy(bytes);
+            let text = text.trim();
+            let (negative, digits) = match text.strip_prefix('-') {
+                Some(digits) => (true, digits),
+                None => (false, text.strip_prefix('+').unwrap_or(text)),
+            };
+            let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+            let invalid = || format!("can't parse {text:?} as a decimal");
+            if (integer.is_empty() && fraction.is_empty())
+                || !integer
+                    .chars()
+                    .chain(fraction.chars())
+                    .all(|c| c.is_ascii_digit())
+            {
+                return Err(invalid());
+            }
+            // Digits beyond the scale are truncated.
+            let fraction = &fraction[..fraction.len().min(scale as usize)];
+            let mut scaled = format!("{integer}{fraction}");
+            scaled.extend(std::iter::repeat('0').take(scale as usize - fraction.len()));
+            let scaled = if scaled.is_empty() {
+                0
+            } else {
+                scaled.parse::<i128>().map_err(|_| out_of_range())?
+            };
+            Ok(if negative { -scaled } else { scaled })
+        }
+        _ => Err(unexpected(value)),
+    }
+}
+
+fn enum_value<T: Copy + Into<i64>>(value: &Value, values: &[(String, T)]) -> Result<i64, String> {
+    match value {
+        Value::Bytes(bytes) => values
+            .iter()
+            .find(|(name, _)| name.as_bytes() == bytes.as_ref())
+            .map(|(_, value)| (*value).into())
+            .ok_or_else(|| {
+                format!(
+                    "{:?} is not a value of the enum",
+                    String::from_utf8_lossy(bytes)
+                )
+            }),
+        Value::Integer(integer) => values
+            .iter()
+            .map(|(_, value)| (*value).into())
+            .find(|value| value == integer)
+            .ok_or_else(|| format!("{integer} is not a value of the enum")),
+        _ => Err(unexpected(value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use vector_lib::event::LogEvent;
+    use vrl::value;
+
+    use super::*;
+
+    fn convert_value(value: Value, type_name: &str) -> Result<Cell, String> {
+        convert(Some(&value), &type_name.parse().unwrap())
+    }
+
+    #[test]
+    fn converts_scalars() {
+        assert_eq!(convert_value(value!(200), "UInt16"), Ok(Cell::UInt(200)));
+        assert_eq!(convert_value(value!("-12"), "Int8"), Ok(Cell::Int(-12)));
+        assert_eq!(convert_value(value!(2.0), "Int32"), Ok(Cell::Int(2)));
+        assert_eq!(convert_value(value!(1), "Float64"), Ok(Cell::Float(1.0)));
+        assert_eq!(convert_value(value!(true), "Bool"), Ok(Cell::Bool(true)));
+        assert_eq!(
+            convert_value(value!({"a": 1}), "String"),
+            Ok(Cell::Bytes(Bytes::from(r#"{"a":1}"#)))
+        );
+        assert_eq!(
+            convert_value(value!("ab"), "FixedString(4)"),
+            Ok(Cell::Bytes(Bytes::from_static(b"ab\0\0")))
+        );
+        assert_eq!(
+            convert_value(value!("192.168.0.1"), "IPv4"),
+            Ok(Cell::UInt(0xc0a8_0001))
+        );
+        assert_eq!(
+            convert_value(value!("b"), "Enum8('a' = 1, 'b' = 2)"),
+            Ok(Cell::Int(2))
+        );
+        assert_eq!(
+            convert_value(value!("-1.2345"), "Decimal(10, 3)"),
+            Ok(Cell::Decimal(-1234))
+        );
+        assert_eq!(
+            convert_value(value!(1.5), "Decimal(10, 2)"),
+            Ok(Cell::Decimal(150))
+        );
+        assert_eq!(
+            convert_value(value!("0b6ee6d2-8b8c-4a64-9f4a-0e5a3ed1bd53"), "UUID"),
+            Ok(Cell::Uuid(0x0b6ee6d2_8b8c_4a64_9f4a_0e5a3ed1bd53))
+        );
+    }
+
+    #[test]
+    fn converts_times() {
+        let timestamp = Utc.timestamp_opt(1_700_000_000, 123_456_789).unwrap();
+        assert_eq!(
+            convert_value(Value::Timestamp(timestamp), "DateTime64(3)"),
+            Ok(Cell::Int(1_700_000_000_123))
+        );
+        assert_eq!(
+            convert_value(Value::Timestamp(timestamp), "DateTime('UTC')"),
+            Ok(Cell::Int(1_700_000_000))
+        );
+        assert_eq!(
+            convert_value(value!("2023-11-14 22:13:20.5"), "DateTime64(1)"),
+            Ok(Cell::Int(17_000_000_005))
+        );
+        assert_eq!(
+            convert_value(value!("1970-01-11"), "Date"),
+            Ok(Cell::Int(10))
+        );
+        assert_eq!(
+            convert_value(value!("1969-12-31T00:00:00Z"), "Date32"),
+            Ok(Cell::Int(-1))
+        );
+    }
+
+    #[test]
+    fn converts_composites() {
+        assert_eq!(
+            convert_value(value!([1, null]), "Array(Nullable(UInt8))"),
+            Ok(Cell::Array(vec![Cell::UInt(1), Cell::Null]))
+        );
+        assert_eq!(
+            convert_value(value!({"a": "x"}), "Map(LowCardinality(String), String)"),
+            Ok(Cell::Map(vec![(
+                Cell::Bytes(Bytes::from("a")),
+                Cell::Bytes(Bytes::from("x"))
+            )]))
+        );
+        assert_eq!(
+            convert_value(value!(["x", 1]), "Tuple(String, Int64)"),
+            Ok(Cell::Tuple(vec![
+                Cell::Bytes(Bytes::from("x")),
+                Cell::Int(1)
+            ]))
+        );
+    }
+
+    #[test]
+    fn rejects_mismatches() {
+        assert_eq!(
+            convert_value(value!(300), "UInt8"),
+            Err("300 is out of range".to_owned())
+        );
+        assert_eq!(
+            convert_value(value!("abc"), "FixedString(2)"),
+            Err("3 bytes exceed the length of the string".to_owned())
+        );
+        assert_eq!(
+            convert_value(value!([1, "x"]), "Array(Int32)"),
+            Err("[1]: can't parse \"x\"".to_owned())
+        );
+        assert_eq!(
+            convert_value(value!("c"), "Enum8('a' = 1)"),
+            Err("\"c\" is not a value of the enum".to_owned())
+        );
+        assert_eq!(
+            convert_value(value!({}), "Float32"),
+            Err("unexpected object value".to_owned())
+        );
+    }
+
+    #[test]
+    fn converts_events() {
+        let schema = TableSchema::from_describe(
+            "logs",
+            br#"{"name":"message","type":"String"}
+{"name":"status","type":"Nullable(UInt16)"}
+{"name":"count","type":"UInt64"}
+"#,
+        )
+        .unwrap();
+
+        let mut log = LogEvent::from("hello");
+        log.insert("count", 2);
+        assert_eq!(
+            convert_event(&schema, &log.clone().into()).unwrap(),
+            vec![Cell::Bytes(Bytes::from("hello")), Cell::Null, Cell::UInt(2)]
+        );
+
+        log.insert("status", "teapot");
+        let error = convert_event(&schema, &log.into()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid value for column `status` of type `Nullable(UInt16)`: can't parse \"teapot\""
+        );
+    }
+}
@@ -0,0 +1,732 @@
+//! Table schemas, used to encode events in the binary formats of ClickHouse.
+//!
+//! Schemas are fetched with `DESCRIBE TABLE` and cached per table. A cached schema is dropped when
+//! an insert into its table fails with a schema or type error, so that changes to the table are
+//! picked up.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use http::{Request, StatusCode, Uri};
+use hyper::Body;
+use serde::Deserialize;
+use snafu::{ResultExt, Snafu};
+use tokio::time::sleep;
+
+use crate::{
+    http::{Auth, HttpClient, HttpError},
+    sinks::util::{retries::ExponentialBackoff, service::TowerRequestSettings},
+};
+
+#[derive(Debug, Snafu)]
+pub enum SchemaError {
+    #[snafu(display("Invalid type `{type_name}`: {reason}"))]
+    InvalidType { type_name: String, reason: String },
+
+    #[snafu(display("Unsupported type `{type_name}` of column `{column}`"))]
+    UnsupportedType { column: String, type_name: String },
+
+    #[snafu(display("Table {table} has no insertable columns"))]
+    NoColumns { table: String },
+
+    #[snafu(display("Invalid `DESCRIBE TABLE` response: {source}"))]
+    InvalidResponse { source: serde_json::Error },
+}
+
+#[derive(Debug, Snafu)]
+pub enum FetchSchemaError {
+    #[snafu(display("Failed to build the `DESCRIBE TABLE` request: {source}"))]
+    BuildRequest { source: http::Error },
+
+    #[snafu(display("Failed to send the `DESCRIBE TABLE` request: {source}"))]
+    SendRequest { source: HttpError },
+
+    #[snafu(display("Failed to read the `DESCRIBE TABLE` response: {source}"))]
+    ReadResponse { source: hyper::Error },
+
+    #[snafu(display("`DESCRIBE TABLE` failed with status {status}: {body}"))]
+    UnexpectedStatus { status: StatusCode, body: String },
+
+    #[snafu(display("{source}"))]
+    Schema { source: SchemaError },
+}
+
+impl FetchSchemaError {
+    /// Returns whether fetching the schema again may succeed, as when ClickHouse is unreachable or
+    /// overloaded.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Self::SendRequest { source } => source.is_retriable(),
+            Self::ReadResponse { .. } => true,
+            Self::UnexpectedStatus { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+            Self::BuildRequest { .. } | Self::Schema { .. } => false,
+        }
+    }
+}
+
+/// The codes of the ClickHouse errors caused by inserting rows that don't follow the schema of the
+/// table, such as after it was altered.
+const SCHEMA_ERROR_CODES: [u32; 9] = [
+    8,   // THERE_IS_NO_COLUMN
+    10,  // NOT_FOUND_COLUMN_IN_BLOCK
+    16,  // NO_SUCH_COLUMN_IN_TABLE
+    27,  // CANNOT_PARSE_INPUT_ASSERTION_FAILED
+    33,  // CANNOT_READ_ALL_DATA
+    47,  // UNKNOWN_IDENTIFIER
+    53,  // TYPE_MISMATCH
+    60,  // UNKNOWN_TABLE
+    117, // INCORRECT_DATA
+];
+
+/// Returns whether the body of a failed insert reports an error caused by a stale schema.
+pub fn is_schema_error(body: &[u8]) -> bool {
+    let Some(code) = body.strip_prefix(b"Code: ") else {
+        return false;
+    };
+    let digits = code.iter().take_while(|byte| byte.is_ascii_digit()).count();
+    std::str::from_utf8(&code[..digits])
+        .ok()
+        .and_then(|code| code.parse().ok())
+        .is_some_and(|code| SCHEMA_ERROR_CODES.contains(&code))
+}
+
+/// The type of a column, as far as encoding values is concerned.
+///
+/// Time zones are ignored, as date and time values are encoded as Unix timestamps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    Bool,
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    String,
+    FixedString(usize),
+    Date,
+    Date32,
+    DateTime,
+    DateTime64(u32),
+    Uuid,
+    Ipv4,
+    Ipv6,
+    Decimal { precision: u32, scale: u32 },
+    Enum8(Vec<(String, i8)>),
+    Enum16(Vec<(String, i16)>),
+    Nullable(Box<ColumnType>),
+    LowCardinality(Box<ColumnType>),
+    Array(Box<ColumnType>),
+    Map(Box<ColumnType>, Box<ColumnType>),
+    Tuple(Vec<ColumnType>),
+}
+
+impl ColumnType {
+    /// Returns whether the column accepts `NULL` values.
+    pub fn is_nullable(&self) -> bool {
+        match self {
+            Self::Nullable(_) => true,
+            Self::LowCardinality(inner) => inner.is_nullable(),
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for ColumnType {
+    type Err = SchemaError;
+
+    fn from_str(type_name: &str) -> Result<Self, Self::Err> {
+        let mut parser = TypeParser {
+            input: type_name,
+            position: 0,
+        };
+        let parsed = parser.parse_type().and_then(|parsed| {
+            parser.skip_whitespace();
+            if parser.position == type_name.len() {
+                Ok(parsed)
+            } else {
+                Err(format!("unexpected input at position {}", parser.position))
+            }
+        });
+        parsed.map_err(|reason| SchemaError::InvalidType {
+            type_name: type_name.to_owned(),
+            reason,
+        })
+    }
+}
+
+/// A recursive descent parser of ClickHouse type names.
+struct TypeParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl TypeParser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(expected) {
+            self.position += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(format!(
+                "expected `{expected}` at position {}",
+                self.position
+            ))
+        }
+    }
+
+    fn identifier(&mut self) -> Result<&str, String> {
+        self.skip_whitespace();
+        let length = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest().len());
+        if length == 0 {
+            return Err(format!("expected a name at position {}", self.position));
+        }
+        let start = self.position;
+        self.position += length;
+        Ok(&self.input[start..self.position])
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T, String> {
+        self.skip_whitespace();
+        let length = self
+            .rest()
+            .find(|c: char| !(c.is_ascii_digit() || c == '-'))
+            .unwrap_or(self.rest().len());
+        let start = self.position;
+        self.position += length;
+        self.input[start..self.position]
+            .parse()
+            .map_err(|_| format!("expected a number at position {start}"))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('\'')?;
+        let input = self.input;
+        let rest = &input[self.position..];
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some((_, escaped)) = chars.next() {
+                        value.push(escaped);
+                    }
+                }
+                '\'' if rest[index + 1..].starts_with('\'') => {
+                    chars.next();
+                    value.push('\'');
+                }
+                '\'' => {
+                    self.position += index + 1;
+                    return Ok(value);
+                }
+                c => value.push(c),
+            }
+        }
+        Err("unterminated string".to_owned())
+    }
+
+    fn parse_type(&mut self) -> Result<ColumnType, String> {
+        let name = self.identifier()?.to_owned();
+        self.parse_named_type(&name)
+    }
+
+    fn parse_named_type(&mut self, name: &str) -> Result<ColumnType, String> {
+        Ok(match name {
+            "Bool" | "Boolean" => ColumnType::Bool,
+            "UInt8" => ColumnType::UInt8,
+            "UInt16" => ColumnType::UInt16,
+            "UInt32" => ColumnType::UInt32,
+            "UInt64" => ColumnType::UInt64,
+            "Int8" => ColumnType::Int8,
+            "Int16" => ColumnType::Int16,
+            "Int32" => ColumnType::Int32,
+            "Int64" => ColumnType::Int64,
+            "Float32" => ColumnType::Float32,
+            "Float64" => ColumnType::Float64,
+            "String" => ColumnType::String,
+            "FixedString" => {
+                self.expect('(')?;
+                let length = self.number()?;
+                self.expect(')')?;
+                ColumnType::FixedString(length)
+            }
+            "Date" => ColumnType::Date,
+            "Date32" => ColumnType::Date32,
+            "DateTime" => {
+                if self.eat('(') {
+                    self.string()?;
+                    self.expect(')')?;
+                }
+                ColumnType::DateTime
+            }
+            "DateTime64" => {
+                self.expect('(')?;
+                let precision = self.number()?;
+                if precision > 9 {
+                    return Err(format!("invalid precision {precision}"));
+                }
+                if self.eat(',') {
+                    self.string()?;
+                }
+                self.expect(')')?;
+                ColumnType::DateTime64(precision)
+            }
+            "UUID" => ColumnType::Uuid,
+            "IPv4" => ColumnType::Ipv4,
+            "IPv6" => ColumnType::Ipv6,
+            "Decimal" => {
+                self.expect('(')?;
+                let precision = self.number()?;
+                let scale = if self.eat(',') { self.number()? } else { 0 };
+                self.expect(')')?;
+                decimal(precision, scale)?
+            }
+            "Decimal32" | "Decimal64" | "Decimal128" | "Decimal256" => {
+                self.expect('(')?;
+                let scale = self.number()?;
+                self.expect(')')?;
+                let precision = match name {
+                    "Decimal32" => 9,
+                    "Decimal64" => 18,
+                    "Decimal128" => 38,
+                    _ => 76,
+                };
+                decimal(precision, scale)?
+            }
+            "Enum8" => ColumnType::Enum8(self.enum_values()?),
+            "Enum16" => ColumnType::Enum16(self.enum_values()?),
+            "Nullable" | "LowCardinality" | "Array" => {
+                self.expect('(')?;
+                let inner = Box::new(self.parse_type()?);
+                self.expect(')')?;
+                match name {
+                    "Nullable" => ColumnType::Nullable(inner),
+                    "LowCardinality" => ColumnType::LowCardinality(inner),
+                    _ => ColumnType::Array(inner),
+                }
+            }
+            "Map" => {
+                self.expect('(')?;
+                let key = Box::new(self.parse_type()?);
+                self.expect(',')?;
+                let value = Box::new(self.parse_type()?);
+                self.expect(')')?;
+                ColumnType::Map(key, value)
+            }
+            "Tuple" => {
+                self.expect('(')?;
+                let mut elements = Vec::new();
+                loop {
+                    // Elements of named tuples are preceded by their name.
+                    let first = self.identifier()?.to_owned();
+                    self.skip_whitespace();
+                    let element = if self.rest().starts_with(|c: char| c.is_ascii_alphabetic()) {
+                        self.parse_type()?
+                    } else {
+                        self.parse_named_type(&first)?
+                    };
+                    elements.push(element);
+                    if !self.eat(',') {
+                        break;
+                    }
+                }
+                self.expect(')')?;
+                ColumnType::Tuple(elements)
+            }
+            name => return Err(format!("unknown type `{name}`")),
+        })
+    }
+
+    fn enum_values<T: FromStr>(&mut self) -> Result<Vec<(String, T)>, String> {
+        self.expect('(')?;
+        let mut values = Vec::new();
+        loop {
+            let name = self.string()?;
+            self.expect('=')?;
+            values.push((name, self.number()?));
+            if !self.eat(',') {
+                break;
+            }
+        }
+        self.expect(')')?;
+        Ok(values)
+    }
+}
+
+fn decimal(precision: u32, scale: u32) -> Result<ColumnType, String> {
+    if precision == 0 || precision > 76 || scale > precision {
+        return Err(format!("invalid precision {precision} and scale {scale}"));
+    }
+    Ok(ColumnType::Decimal { precision, scale })
+}
+
+/// A column values are inserted into.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Column {
+    pub name: String,
+    pub type_name: String,
+    pub column_type: ColumnType,
+}
+
+/// The columns of a table values are inserted into.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableSchema {
+    pub columns: Vec<Column>,
+}
+
+impl TableSchema {
+    /// Parses the `JSONEachRow` output of `DESCRIBE TABLE`.
+    ///
+    /// Materialized and alias columns are computed by ClickHouse, so they are skipped.
+    pub fn from_describe(table: &str, body: &[u8]) -> Result<Self, SchemaError> {
+        #[derive(Deserialize)]
+        struct DescribedColumn {
+            name: String,
+            r#type: String,
+            #[serde(default)]
+            default_type: String,
+        }
+
+        let mut columns = Vec::new();
+        for line in body.split(|&byte| byte == b'\n') {
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let column: DescribedColumn =
+                serde_json::from_slice(line).context(InvalidResponseSnafu)?;
+            if matches!(column.default_type.as_str(), "MATERIALIZED" | "ALIAS") {
+                continue;
+            }
+            let column_type = column.r#type.parse().map_err(|error| match error {
+                SchemaError::InvalidType { type_name, .. } => SchemaError::UnsupportedType {
+                    column: column.name.clone(),
+                    type_name,
+                },
+                error => error,
+            })?;
+            columns.push(Column {
+                name: column.name,
+                type_name: column.r#type,
+                column_type,
+            });
+        }
+
+        if columns.is_empty() {
+            return Err(SchemaError::NoColumns {
+                table: table.to_owned(),
+            });
+        }
+        Ok(Self { columns })
+    }
+
+    /// Returns the list of columns of the `INSERT` query.
+    pub fn column_list(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| format!("\"{}\"", column.name.replace('"', "\\\"")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Fetches and caches the schemas of tables.
+pub struct SchemaCache {
+    client: HttpClient,
+    endpoint: Uri,
+    auth: Option<Auth>,
+    retry_attempts: usize,
+    backoff: ExponentialBackoff,
+    schemas: Mutex<HashMap<(String, String), Arc<TableSchema>>>,
+}
+
+impl SchemaCache {
+    /// Creates a cache fetching the schemas with the retry settings of the sink requests.
+    pub fn new(
+        client: HttpClient,
+        endpoint: Uri,
+        auth: Option<Auth>,
+        request: &TowerRequestSettings,
+    ) -> Self {
+        // An exponential backoff starting from retry_initial_backoff and doubling every time up to
+        // retry_max_duration.
+        let backoff = ExponentialBackoff::from_millis(2)
+            .factor((request.retry_initial_backoff.as_millis() as u64 / 2).max(1))
+            .max_delay(request.retry_max_duration);
+        Self {
+            client,
+            endpoint,
+            auth,
+            retry_attempts: request.retry_attempts,
+            backoff,
+            schemas: Mutex::default(),
+        }
+    }
+
+    /// Returns the schema of a table, fetching it if it isn't cached, and retrying the fetches
+    /// failing on errors that may go away.
+    pub async fn get_with_retries(
+        &self,
+        database: &str,
+        table: &str,
+    ) -> Result<Arc<TableSchema>, FetchSchemaError> {
+        let mut backoff = self.backoff.clone();
+        let mut attempts = 1;
+        loop {
+            match self.get(database, table).await {
+                Err(error) if error.is_retriable() && attempts < self.retry_attempts => {
+                    warn!(
+                        message = "Retrying after error fetching table schema.",
+                        %error,
+                        database,
+                        table,
+                        internal_log_rate_limit = true,
+                    );
+                    sleep(backoff.next().unwrap()).await;
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Returns the schema of a table, fetching it if it isn't cached.
+    pub async fn get(
+        &self,
+        database: &str,
+        table: &str,
+    ) -> Result<Arc<TableSchema>, FetchSchemaError> {
+        let key = (database.to_owned(), table.to_owned());
+        if let Some(schema) = self.schemas.lock().expect("poisoned lock").get(&key) {
+            return Ok(Arc::clone(schema));
+        }
+
+        let schema = Arc::new(self.fetch(database, table).await?);
+        debug!(
+            message = "Fetched table schema.",
+            database,
+            table,
+            columns = schema.columns.len(),
+        );
+        self.schemas
+            .lock()
+            .expect("poisoned lock")
+            .insert(key, Arc::clone(&schema));
+        Ok(schema)
+    }
+
+    /// Drops the cached schema of a table, so that it's fetched again by the next batch.
+    pub fn invalidate(&self, database: &str, table: &str) {
+        self.schemas
+            .lock()
+            .expect("poisoned lock")
+            .remove(&(database.to_owned(), table.to_owned()));
+    }
+
+    async fn fetch(&self, database: &str, table: &str) -> Result<TableSchema, FetchSchemaError> {
+        let table_name = format!(
+            "\"{}\".\"{}\"",
+            database.replace('"', "\\\""),
+            table.replace('"', "\\\"")
+        );
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair(
+                "query",
+                &format!("DESCRIBE TABLE {table_name} FORMAT JSONEachRow"),
+            )
+            .finish();
+        let mut uri = self.endpoint.to_string();
+        if !uri.ends_with('/') {
+            uri.push('/');
+        }
+        uri.push('?');
+        uri.push_str(&query);
+
+        let mut request = Request::get(uri)
+            .body(Body::empty())
+            .context(BuildRequestSnafu)?;
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut request);
+        }
+
+        let response = self.client.send(request).await.context(SendRequestSnafu)?;
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body())
+            .await
+            .context(ReadResponseSnafu)?;
+        if status != StatusCode::OK {
+            return UnexpectedStatusSnafu {
+                status,
+                body: String::from_utf8_lossy(&body).trim().to_owned(),
+            }
+            .fail();
+        }
+
+        TableSchema::from_describe(&table_name, &body).context(SchemaSnafu)
+    }
+}
+
+impl fmt::Debug for SchemaCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SchemaCache")
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(type_name: &str) -> ColumnType {
+        type_name.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_types() {
+        assert_eq!(parse("UInt64"), ColumnType::UInt64);
+        assert_eq!(parse("DateTime('Europe/Paris')"), ColumnType::DateTime);
+        assert_eq!(parse("DateTime64(3, 'UTC')"), ColumnType::DateTime64(3));
+        assert_eq!(parse("FixedString(16)"), ColumnType::FixedString(16));
+        assert_eq!(
+            parse("Decimal(10, 2)"),
+            ColumnType::Decimal {
+                precision: 10,
+                scale: 2
+            }
+        );
+        assert_eq!(
+            parse("Decimal64(4)"),
+            ColumnType::Decimal {
+                precision: 18,
+                scale: 4
+            }
+        );
+        assert_eq!(
+            parse("LowCardinality(Nullable(String))"),
+            ColumnType::LowCardinality(Box::new(ColumnType::Nullable(Box::new(
+                ColumnType::String
+            ))))
+        );
+        assert_eq!(
+            parse("Map(LowCardinality(String), Array(Nullable(Int32)))"),
+            ColumnType::Map(
+                Box::new(ColumnType::LowCardinality(Box::new(ColumnType::String))),
+                Box::new(ColumnType::Array(Box::new(ColumnType::Nullable(Box::new(
+                    ColumnType::Int32
+                )))))
+            )
+        );
+        assert_eq!(
+            parse("Enum8('it''s' = 1, 'b' = -2)"),
+            ColumnType::Enum8(vec![("it's".to_owned(), 1), ("b".to_owned(), -2)])
+        );
+        assert_eq!(
+            parse("Tuple(a String, b Tuple(UInt8, IPv6))"),
+            ColumnType::Tuple(vec![
+                ColumnType::String,
+                ColumnType::Tuple(vec![ColumnType::UInt8, ColumnType::Ipv6])
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_types() {
+        for type_name in ["Object('json')", "Nullable(String", "DateTime64(12)", ""] {
+            assert!(
+                type_name.parse::<ColumnType>().is_err(),
+                "{type_name} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn parses_describe_output() {
+        let body = br#"{"name":"timestamp","type":"DateTime64(6)","default_type":"","default_expression":""}
+{"name":"message","type":"String","default_type":"DEFAULT","default_expression":"''"}
+{"name":"length","type":"UInt64","default_type":"MATERIALIZED","default_expression":"length(message)"}
+"#;
+        let schema = TableSchema::from_describe("logs", body).unwrap();
+        assert_eq!(
+            schema
+                .columns
+                .iter()
+                .map(|column| (column.name.as_str(), &column.column_type))
+                .collect::<Vec<_>>(),
+            vec![
+                ("timestamp", &ColumnType::DateTime64(6)),
+                ("message", &ColumnType::String)
+            ]
+        );
+        assert_eq!(schema.column_list(), "\"timestamp\", \"message\"");
+
+        let error = TableSchema::from_describe(
+            "logs",
+            br#"{"name":"data","type":"JSON","default_type":""}"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unsupported type `JSON` of column `data`"
+        );
+    }
+
+    #[test]
+    fn detects_schema_errors() {
+        assert!(is_schema_error(
+            b"Code: 16. DB::Exception: No such column level in table default.logs."
+        ));
+        assert!(is_schema_error(b"Code: 53. DB::Exception: Type mismatch."));
+        assert!(!is_schema_error(
+            b"Code: 202. DB::Exception: Too many simultaneous queries."
+        ));
+        assert!(!is_schema_error(b"Service Unavailable"));
+    }
+
+    #[test]
+    fn retries_transient_fetch_errors() {
+        let status = |status| FetchSchemaError::UnexpectedStatus {
+            status,
+            body: String::new(),
+        };
+        assert!(status(StatusCode::SERVICE_UNAVAILABLE).is_retriable());
+        assert!(status(StatusCode::TOO_MANY_REQUESTS).is_retriable());
+        assert!(!status(StatusCode::NOT_FOUND).is_retriable());
+        assert!(!FetchSchemaError::Schema {
+            source: SchemaError::NoColumns {
+                table: "logs".to_owned()
+            }
+        }
+        .is_retriable());
+    }
+}
@@ -1,6 +1,12 @@
 //! Service implementation for the `Clickhouse` sink.
 
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+};
+
 use super::config::QuerySettingsConfig;
+use super::schema::{is_schema_error, SchemaCache};
 use super::sink::PartitionKey;
 use crate::{
     http::{Auth, HttpError},
@@ -8,7 +14,9 @@ use crate::{
         clickhouse::config::Format,
         prelude::*,
         util::{
-            http::{HttpRequest, HttpResponse, HttpRetryLogic, HttpServiceRequestBuilder},
+            http::{
+                HttpRequest, HttpResponse, HttpRetryLogic, HttpService, HttpServiceRequestBuilder,
+            },
             retries::RetryAction,
         },
         HTTPRequestBuilderSnafu, UriParseSnafu,
@@ -60,6 +68,50 @@ impl RetryLogic for ClickhouseRetryLogic {
     }
 }
 
+/// Sends requests, and forgets the cached schema of a table when inserting into it fails with a
+/// schema or type error, so that it is fetched again in case the table was altered.
+#[derive(Clone)]
+pub(super) struct ClickhouseService {
+    inner: HttpService<ClickhouseServiceRequestBuilder, PartitionKey>,
+    schemas: Option<Arc<SchemaCache>>,
+}
+
+impl ClickhouseService {
+    pub(super) const fn new(
+        inner: HttpService<ClickhouseServiceRequestBuilder, PartitionKey>,
+        schemas: Option<Arc<SchemaCache>>,
+    ) -> Self {
+        Self { inner, schemas }
+    }
+}
+
+impl Service<HttpRequest<PartitionKey>> for ClickhouseService {
+    type Response = HttpResponse;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest<PartitionKey>) -> Self::Future {
+        let key = request.get_additional_metadata().clone();
+        let schemas = self.schemas.clone();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let result = future.await;
+            if let (Some(schemas), Ok(response)) = (schemas, &result) {
+                let response = &response.http_response;
+                if !response.status().is_success() && is_schema_error(response.body()) {
+                    schemas.invalidate(&key.database, &key.table);
+                }
+            }
+            result
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct ClickhouseServiceRequestBuilder {
     pub(super) auth: Option<Auth>,
@@ -83,6 +135,7 @@ impl HttpServiceRequestBuilder<PartitionKey> for ClickhouseServiceRequestBuilder
             &metadata.database,
             &metadata.table,
             metadata.format,
+            metadata.columns.as_deref(),
             self.skip_unknown_fields,
             self.date_time_best_effort,
             self.insert_random_shard,
@@ -91,10 +144,16 @@ impl HttpServiceRequestBuilder<PartitionKey> for ClickhouseServiceRequestBuilder
 
         let auth: Option<Auth> = self.auth.clone();
 
+        let content_type = if metadata.format.uses_schema() {
+            "application/octet-stream"
+        } else {
+            "application/x-ndjson"
+        };
+
         let payload = request.take_payload();
 
         let mut builder = Request::post(&uri)
-            .header(CONTENT_TYPE, "application/x-ndjson")
+            .header(CONTENT_TYPE, content_type)
             .header(CONTENT_LENGTH, payload.len());
         if let Some(ce) = self.compression.content_encoding() {
             builder = builder.header(CONTENT_ENCODING, ce);
@@ -127,18 +186,23 @@ fn set_uri_query(
     database: &str,
     table: &str,
     format: Format,
+    columns: Option<&str>,
     skip_unknown: Option<bool>,
     date_time_best_effort: bool,
     insert_random_shard: bool,
     query_settings: QuerySettingsConfig,
 ) -> crate::Result<Uri> {
+    let columns = columns
+        .map(|columns| format!(" ({columns})"))
+        .unwrap_or_default();
     let query = url::form_urlencoded::Serializer::new(String::new())
         .append_pair(
             "query",
             format!(
-                "INSERT INTO \"{}\".\"{}\" FORMAT {}",
+                "INSERT INTO \"{}\".\"{}\"{} FORMAT {}",
                 database,
                 table.replace('\"', "\\\""),
+                columns,
                 format
             )
             .as_str(),
@@ -209,6 +273,7 @@ mod tests {
             "my_database",
             "my_table",
             Format::JsonEachRow,
+            None,
             Some(false),
             true,
             false,
@@ -226,6 +291,7 @@ mod tests {
             "my_database",
             "my_\"table\"",
             Format::JsonEachRow,
+            None,
             Some(false),
             false,
             false,
@@ -242,6 +308,7 @@ mod tests {
             "my_database",
             "my_\"table\"",
             Format::JsonAsObject,
+            None,
             Some(true),
             true,
             false,
@@ -260,6 +327,7 @@ mod tests {
             "my_\"table\"",
             Format::JsonAsObject,
             None,
+            None,
             true,
             false,
             QuerySettingsConfig::default(),
@@ -276,6 +344,7 @@ mod tests {
             "my_\"table\"",
             Format::JsonAsObject,
             None,
+            None,
             true,
             false,
             QuerySettingsConfig {
@@ -295,6 +364,22 @@ mod tests {
                                      wait_for_async_insert=1&\
                                      wait_for_async_insert_timeout=500&\
                                      query=INSERT+INTO+%22my_database%22.%22my_%5C%22table%5C%22%22+FORMAT+JSONAsObject");
+
+        let uri = set_uri_query(
+            &"http://localhost:80".parse().unwrap(),
+            "my_database",
+            "my_table",
+            Format::RowBinary,
+            Some("\"id\", \"message\""),
+            None,
+            false,
+            false,
+            QuerySettingsConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(uri.to_string(), "http://localhost:80/?\
+                                     input_format_import_nested_json=1&\
+                                     query=INSERT+INTO+%22my_database%22.%22my_table%22+%28%22id%22%2C+%22message%22%29+FORMAT+RowBinary");
     }
 
     #[test]
@@ -304,6 +389,7 @@ mod tests {
             "my_database",
             "my_table",
             Format::JsonEachRow,
+            None,
             Some(false),
             false,
             false,
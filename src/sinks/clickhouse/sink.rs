@@ -1,7 +1,17 @@
 //! Implementation of the `clickhouse` sink.
 
-use super::{config::Format, request_builder::ClickhouseRequestBuilder};
-use crate::sinks::{prelude::*, util::http::HttpRequest};
+use std::sync::Arc;
+
+use super::{
+    config::Format,
+    request_builder::ClickhouseRequestBuilder,
+    rows::{convert_event, Cell},
+    schema::{SchemaCache, TableSchema},
+};
+use crate::{
+    internal_events::{ClickhouseConversionError, ClickhouseSchemaError},
+    sinks::{prelude::*, util::http::HttpRequest},
+};
 
 pub struct ClickhouseSink<S> {
     batch_settings: BatcherSettings,
@@ -10,6 +20,8 @@ pub struct ClickhouseSink<S> {
     table: Template,
     format: Format,
     request_builder: ClickhouseRequestBuilder,
+    schemas: Option<Arc<SchemaCache>>,
+    transformer: Transformer,
 }
 
 impl<S> ClickhouseSink<S>
@@ -19,6 +31,7 @@ where
     S::Response: DriverResponse + Send + 'static,
     S::Error: std::fmt::Debug + Into<crate::Error> + Send,
 {
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         batch_settings: BatcherSettings,
         service: S,
@@ -26,6 +39,8 @@ where
         table: Template,
         format: Format,
         request_builder: ClickhouseRequestBuilder,
+        schemas: Option<Arc<SchemaCache>>,
+        transformer: Transformer,
     ) -> Self {
         Self {
             batch_settings,
@@ -34,11 +49,15 @@ where
             table,
             format,
             request_builder,
+            schemas,
+            transformer,
         }
     }
 
     async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
         let batch_settings = self.batch_settings;
+        let schemas = self.schemas;
+        let transformer = self.transformer;
 
         input
             .batched_partitioned(
@@ -46,6 +65,16 @@ where
                 || batch_settings.as_byte_size_config(),
             )
             .filter_map(|(key, batch)| async move { key.map(move |k| (k, batch)) })
+            .filter_map(move |(key, events)| {
+                let schemas = schemas.clone();
+                let transformer = transformer.clone();
+                async move {
+                    match schemas {
+                        None => Some((key, ClickhouseBatch::Events(events))),
+                        Some(schemas) => into_rows(&schemas, &transformer, key, events).await,
+                    }
+                }
+            })
             .request_builder(
                 default_request_builder_concurrency_limit(),
                 self.request_builder,
@@ -81,12 +110,78 @@ where
     }
 }
 
+/// Converts a batch of events to rows following the schema of their table.
+///
+/// Events that can't be converted are rejected. Fetching the schema is retried like the requests
+/// of the sink, and the whole batch is dropped if it still can't be fetched.
+async fn into_rows(
+    schemas: &SchemaCache,
+    transformer: &Transformer,
+    mut key: PartitionKey,
+    mut events: Vec<Event>,
+) -> Option<(PartitionKey, ClickhouseBatch)> {
+    let schema = match schemas.get_with_retries(&key.database, &key.table).await {
+        Ok(schema) => schema,
+        Err(error) => {
+            emit!(ClickhouseSchemaError {
+                error: &error,
+                database: &key.database,
+                table: &key.table,
+                count: events.len(),
+            });
+            events.take_finalizers().update_status(EventStatus::Errored);
+            return None;
+        }
+    };
+
+    let mut rows = Vec::with_capacity(events.len());
+    events.retain_mut(|event| {
+        transformer.transform(event);
+        match convert_event(&schema, event) {
+            Ok(row) => {
+                rows.push(row);
+                true
+            }
+            Err(error) => {
+                emit!(ClickhouseConversionError { error });
+                event.take_finalizers().update_status(EventStatus::Rejected);
+                false
+            }
+        }
+    });
+    if events.is_empty() {
+        return None;
+    }
+
+    key.columns = Some(schema.column_list());
+    Some((
+        key,
+        ClickhouseBatch::Rows {
+            schema,
+            events,
+            rows,
+        },
+    ))
+}
+
+/// A batch of events, converted to rows when the format follows the schema of the table.
+pub(super) enum ClickhouseBatch {
+    Events(Vec<Event>),
+    Rows {
+        schema: Arc<TableSchema>,
+        events: Vec<Event>,
+        rows: Vec<Vec<Cell>>,
+    },
+}
+
 /// PartitionKey used to partition events by (database, table) pair.
 #[derive(Hash, Eq, PartialEq, Clone, Debug)]
 pub(super) struct PartitionKey {
     pub database: String,
     pub table: String,
     pub format: Format,
+    /// The columns inserted into, when they are set from the schema of the table.
+    pub columns: Option<String>,
 }
 
 /// KeyPartitioner that partitions events by (database, table) pair.
@@ -130,6 +225,7 @@ impl Partitioner for KeyPartitioner {
             database,
             table,
             format: self.format,
+            columns: None,
         })
     }
 }
@@ -208,6 +208,7 @@ impl ElasticsearchCommon {
                 doc_type,
                 suppress_type_name,
             },
+            retry_partial: config.request_retry_partial,
         };
 
         Ok(Self {
@@ -126,9 +126,10 @@ pub struct ElasticsearchConfig {
     )]
     pub suppress_type_name: bool,
 
-    /// Whether or not to retry successful requests containing partial failures.
+    /// Whether or not to retry the documents that failed individually in successful requests.
     ///
-    /// To avoid duplicates in Elasticsearch, please use option `id_key`.
+    /// Only the documents that failed with a retriable error are retried. When disabled, the
+    /// failed documents are rejected.
     #[serde(default)]
     #[configurable(metadata(docs::advanced))]
     pub request_retry_partial: bool,
@@ -574,7 +575,7 @@ impl SinkConfig for ElasticsearchConfig {
             health_config,
             ElasticsearchHealthLogic,
             1,
-        );
+        )?;
 
         let sink = ElasticsearchSink::new(&common, self, service)?;
 
@@ -1,6 +1,9 @@
 use bytes::Bytes;
-use vector_lib::EstimatedJsonEncodedSizeOf;
-use vector_lib::{json_size::JsonSize, request_metadata::RequestMetadata};
+use vector_lib::{config::telemetry, EstimatedJsonEncodedSizeOf};
+use vector_lib::{
+    json_size::JsonSize,
+    request_metadata::{GroupedCountByteSize, RequestMetadata},
+};
 
 use crate::{
     event::{EventFinalizers, Finalizable},
@@ -20,12 +23,17 @@ use crate::{
 pub struct ElasticsearchRequestBuilder {
     pub compression: Compression,
     pub encoder: ElasticsearchEncoder,
+    /// Whether the failed documents of the requests are retried on their own, which requires
+    /// keeping their events to encode them again.
+    pub retry_partial: bool,
 }
 
 pub struct Metadata {
-    finalizers: EventFinalizers,
+    finalizers: Vec<EventFinalizers>,
     batch_size: usize,
     events_byte_size: JsonSize,
+    item_sizes: Vec<GroupedCountByteSize>,
+    original_events: Option<Vec<ProcessedEvent>>,
 }
 
 impl RequestBuilder<Vec<ProcessedEvent>> for ElasticsearchRequestBuilder {
@@ -48,18 +56,20 @@ impl RequestBuilder<Vec<ProcessedEvent>> for ElasticsearchRequestBuilder {
         &self,
         mut events: Vec<ProcessedEvent>,
     ) -> (Self::Metadata, RequestMetadataBuilder, Self::Events) {
-        let events_byte_size = events
-            .iter()
-            .map(|x| x.log.estimated_json_encoded_size_of())
-            .reduce(|a, b| a + b)
-            .unwrap_or(JsonSize::zero());
+        let events_byte_size = events_byte_size(&events);
 
         let metadata_builder = RequestMetadataBuilder::from_events(&events);
 
         let es_metadata = Metadata {
-            finalizers: events.take_finalizers(),
+            finalizers: events
+                .iter_mut()
+                .map(Finalizable::take_finalizers)
+                .collect(),
             batch_size: events.len(),
             events_byte_size,
+            item_sizes: events.iter().map(item_byte_size).collect(),
+            // Kept to encode the failed items again when they are retried.
+            original_events: self.retry_partial.then(|| events.clone()),
         };
         (es_metadata, metadata_builder, events)
     }
@@ -76,6 +86,26 @@ impl RequestBuilder<Vec<ProcessedEvent>> for ElasticsearchRequestBuilder {
             batch_size: es_metadata.batch_size,
             events_byte_size: es_metadata.events_byte_size,
             metadata,
+            item_sizes: es_metadata.item_sizes,
+            original_events: es_metadata.original_events,
+            stale_payload: false,
+            delivered: GroupedCountByteSize::default(),
+            request_builder: self.clone(),
         }
     }
 }
+
+/// Returns the estimated JSON size of a document, grouped like the sizes of the requests.
+fn item_byte_size(event: &ProcessedEvent) -> GroupedCountByteSize {
+    let mut size = telemetry().create_request_count_byte_size();
+    size.add_event(event, event.estimated_json_encoded_size_of());
+    size
+}
+
+pub(super) fn events_byte_size(events: &[ProcessedEvent]) -> JsonSize {
+    events
+        .iter()
+        .map(|x| x.log.estimated_json_encoded_size_of())
+        .reduce(|a, b| a + b)
+        .unwrap_or(JsonSize::zero())
+}
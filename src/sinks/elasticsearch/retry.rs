@@ -2,39 +2,37 @@ use http::StatusCode;
 use serde::Deserialize;
 
 use crate::{
+    event::Rejection,
     http::HttpError,
     sinks::{
         elasticsearch::service::ElasticsearchResponse,
-        util::retries::{RetryAction, RetryLogic},
+        util::{
+            partial::ItemOutcome,
+            retries::{RetryAction, RetryLogic},
+        },
     },
 };
 
 #[derive(Deserialize, Debug)]
-struct EsResultResponse {
+pub(super) struct EsResultResponse {
     items: Vec<EsResultItem>,
 }
 
 impl EsResultResponse {
-    fn parse(body: &str) -> Result<Self, String> {
+    pub(super) fn parse(body: &str) -> Result<Self, String> {
         serde_json::from_str::<EsResultResponse>(body).map_err(|json_error| {
             format!("some messages failed, could not parse response, error: {json_error}")
         })
     }
 
-    /// Returns iterator over status codes for items and optional error details.
-    fn iter_status(&self) -> impl Iterator<Item = (StatusCode, Option<&EsErrorDetails>)> {
-        self.items.iter().filter_map(|item| {
-            item.result()
-                .status
-                .and_then(|status| StatusCode::from_u16(status).ok())
-                .map(|status| (status, item.result().error.as_ref()))
-        })
+    /// Returns whether each item was indexed.
+    pub(super) fn items_delivered(&self) -> impl Iterator<Item = bool> + '_ {
+        self.items
+            .iter()
+            .map(|item| item.status().is_none_or(|status| status.is_success()))
     }
 
     /// Selects the first error since logging all errors would be quite verbose and many are duplicates.
-    /// If partial retry is enabled and we don't retry, this is because there is no retriable error in the
-    /// response, thus all errors are equally interesting so logging the first is sufficient.
-    /// When partial retry is disabled, we don't retry on any error.
     fn get_error_reason(&self, body: &str) -> String {
         match self
             .items
@@ -66,6 +64,12 @@ impl EsResultItem {
             EsResultItem::Update(r) => r,
         }
     }
+
+    fn status(&self) -> Option<StatusCode> {
+        self.result()
+            .status
+            .and_then(|status| StatusCode::from_u16(status).ok())
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -118,32 +122,9 @@ impl RetryLogic for ElasticsearchRetryLogic {
                 let body = String::from_utf8_lossy(response.http_response.body());
 
                 if body.contains("\"errors\":true") {
+                    // The items of parsed responses are handled by `item_outcomes`.
                     match EsResultResponse::parse(&body) {
-                        Ok(resp) => {
-                            if self.retry_partial {
-                                // We will retry if there exists at least one item that
-                                // failed with a retriable error.
-                                // Those are backpressure and server errors.
-                                if let Some((status, error)) =
-                                    resp.iter_status().find(|(status, _)| {
-                                        *status == StatusCode::TOO_MANY_REQUESTS
-                                            || status.is_server_error()
-                                    })
-                                {
-                                    let msg = if let Some(error) = error {
-                                        format!(
-                                            "partial error, status: {}, error type: {}, reason: {}",
-                                            status, error.err_type, error.reason
-                                        )
-                                    } else {
-                                        format!("partial error, status: {status}")
-                                    };
-                                    return RetryAction::Retry(msg.into());
-                                }
-                            }
-
-                            RetryAction::DontRetry(resp.get_error_reason(&body).into())
-                        }
+                        Ok(resp) => RetryAction::DontRetry(resp.get_error_reason(&body).into()),
                         Err(msg) => RetryAction::DontRetry(msg.into()),
                     }
                 } else {
@@ -153,6 +134,46 @@ impl RetryLogic for ElasticsearchRetryLogic {
             _ => RetryAction::DontRetry(format!("response status: {status}").into()),
         }
     }
+
+    fn item_outcomes(&self, response: &ElasticsearchResponse) -> Option<Vec<ItemOutcome>> {
+        if !response.http_response.status().is_success() {
+            return None;
+        }
+        let body = String::from_utf8_lossy(response.http_response.body());
+        if !body.contains("\"errors\":true") {
+            return None;
+        }
+        let resp = EsResultResponse::parse(&body).ok()?;
+        Some(
+            resp.items
+                .iter()
+                .map(|item| match item.status() {
+                    None => ItemOutcome::Delivered,
+                    Some(status) if status.is_success() => ItemOutcome::Delivered,
+                    Some(status) => {
+                        let reason = match &item.result().error {
+                            Some(error) => format!(
+                                "status: {}, error type: {}, reason: {}",
+                                status, error.err_type, error.reason
+                            ),
+                            None => format!("status: {status}"),
+                        };
+                        // Backpressure and server errors are retriable.
+                        if self.retry_partial
+                            && (status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
+                        {
+                            ItemOutcome::Retry(format!("partial error, {reason}").into())
+                        } else {
+                            ItemOutcome::Rejected(Rejection {
+                                status: Some(status.as_u16().to_string()),
+                                ..Rejection::new(reason)
+                            })
+                        }
+                    }
+                })
+                .collect(),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -187,21 +208,40 @@ mod tests {
 
     #[test]
     fn handles_partial_error_response() {
-        let json = "{\"took\":34,\"errors\":true,\"items\":[{\"index\":{\"_index\":\"test-asjkf1234\",\"_type\":\"log_lines\",\"_id\":\"4Z3QLYEBT52RtoOEKz2H\",\"status\":429}}]}";
-        let response = Response::builder()
-            .status(StatusCode::OK)
-            .body(Bytes::from(json))
-            .unwrap();
+        let json = "{\"took\":34,\"errors\":true,\"items\":[{\"index\":{\"_index\":\"test-asjkf1234\",\"_type\":\"log_lines\",\"_id\":\"4Z3QLYEBT52RtoOEKz2H\",\"status\":429}},{\"index\":{\"_index\":\"test-asjkf1234\",\"_type\":\"log_lines\",\"_id\":\"5Z3QLYEBT52RtoOEKz2H\",\"status\":201}},{\"index\":{\"_index\":\"test-asjkf1234\",\"_type\":\"log_lines\",\"_id\":\"6Z3QLYEBT52RtoOEKz2H\",\"status\":400,\"error\":{\"type\":\"mapper_parsing_exception\",\"reason\":\"failed to parse\"}}}]}";
+        let response = || ElasticsearchResponse {
+            http_response: Response::builder()
+                .status(StatusCode::OK)
+                .body(Bytes::from(json))
+                .unwrap(),
+            event_status: EventStatus::Delivered,
+            events_byte_size: CountByteSize(1, JsonSize::new(1)).into(),
+        };
+        let rejected = ItemOutcome::Rejected(Rejection {
+            status: Some("400".to_owned()),
+            ..Rejection::new(
+                "status: 400 Bad Request, error type: mapper_parsing_exception, reason: failed to parse",
+            )
+        });
+
         let logic = ElasticsearchRetryLogic {
             retry_partial: true,
         };
+        assert_eq!(
+            logic.item_outcomes(&response()).unwrap(),
+            [
+                ItemOutcome::Retry("partial error, status: 429 Too Many Requests".into()),
+                ItemOutcome::Delivered,
+                rejected,
+            ]
+        );
+
+        let logic = ElasticsearchRetryLogic {
+            retry_partial: false,
+        };
         assert!(matches!(
-            logic.should_retry_response(&ElasticsearchResponse {
-                http_response: response,
-                event_status: EventStatus::Errored,
-                events_byte_size: CountByteSize(1, JsonSize::new(1)).into(),
-            }),
-            RetryAction::Retry(_)
+            logic.item_outcomes(&response()).unwrap()[0],
+            ItemOutcome::Rejected(_)
         ));
     }
 
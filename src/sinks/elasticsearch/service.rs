@@ -15,29 +15,67 @@ use vector_lib::{
     request_metadata::{GroupedCountByteSize, MetaDescriptive, RequestMetadata},
 };
 
-use super::{ElasticsearchCommon, ElasticsearchConfig};
+use super::{
+    encoder::ProcessedEvent,
+    request_builder::{events_byte_size, ElasticsearchRequestBuilder},
+    retry::EsResultResponse,
+    ElasticsearchCommon, ElasticsearchConfig,
+};
 use crate::{
-    event::{EventFinalizers, EventStatus, Finalizable},
+    event::{EventFinalizers, EventStatus, Finalizable, Rejection},
     http::HttpClient,
     sinks::util::{
         auth::Auth,
         http::{HttpBatchService, RequestConfig},
-        Compression, ElementCount,
+        metadata::RequestMetadataBuilder,
+        partial::{ItemOutcome, PartialRequest},
+        Compression, ElementCount, RequestBuilder,
     },
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone)]
 pub struct ElasticsearchRequest {
     pub payload: Bytes,
-    pub finalizers: EventFinalizers,
+    /// The finalizers of each document of the request.
+    pub finalizers: Vec<EventFinalizers>,
     pub batch_size: usize,
     pub events_byte_size: JsonSize,
     pub metadata: RequestMetadata,
+    /// The size of each document of the request.
+    pub item_sizes: Vec<GroupedCountByteSize>,
+    /// The events of the documents of the request, to encode them again when some are retried.
+    ///
+    /// These are only kept when partial retries are enabled.
+    pub original_events: Option<Vec<ProcessedEvent>>,
+    /// Whether documents were removed from the request since its payload was encoded.
+    pub stale_payload: bool,
+    /// The size of the documents delivered by the earlier attempts of the request.
+    pub delivered: GroupedCountByteSize,
+    pub request_builder: ElasticsearchRequestBuilder,
+}
+
+impl ElasticsearchRequest {
+    /// Encodes the payload again from the remaining documents, if some were removed from it.
+    fn encode(&mut self) -> std::io::Result<()> {
+        let Some(events) = self.original_events.as_ref().filter(|_| self.stale_payload) else {
+            return Ok(());
+        };
+        let metadata_builder = RequestMetadataBuilder::from_events(events);
+        let payload = self.request_builder.encode_events(events.clone())?;
+        self.batch_size = events.len();
+        self.events_byte_size = events_byte_size(events);
+        self.metadata = metadata_builder.build(&payload);
+        self.payload = payload.into_payload();
+        self.stale_payload = false;
+        Ok(())
+    }
 }
 
 impl ByteSizeOf for ElasticsearchRequest {
     fn allocated_bytes(&self) -> usize {
-        self.payload.allocated_bytes() + self.finalizers.allocated_bytes()
+        self.payload.allocated_bytes()
+            + self.finalizers.allocated_bytes()
+            + self.original_events.allocated_bytes()
     }
 }
 
@@ -49,7 +87,43 @@ impl ElementCount for ElasticsearchRequest {
 
 impl Finalizable for ElasticsearchRequest {
     fn take_finalizers(&mut self) -> EventFinalizers {
-        std::mem::take(&mut self.finalizers)
+        // The documents are finalized individually by the retry policy.
+        EventFinalizers::default()
+    }
+}
+
+impl PartialRequest for ElasticsearchRequest {
+    fn retain_items(&mut self, mut f: impl FnMut(usize, &EventFinalizers) -> bool) {
+        let retained = self
+            .finalizers
+            .iter()
+            .enumerate()
+            .map(|(index, finalizers)| f(index, finalizers))
+            .collect::<Vec<_>>();
+        if retained.iter().all(|retained| *retained) {
+            return;
+        }
+
+        if let Some(events) = &mut self.original_events {
+            let mut retained_events = retained.iter();
+            events.retain(|_| *retained_events.next().unwrap_or(&false));
+        }
+        let mut retained_sizes = retained.iter();
+        self.item_sizes
+            .retain(|_| *retained_sizes.next().unwrap_or(&false));
+        let mut retained_finalizers = retained.iter();
+        self.finalizers
+            .retain(|_| *retained_finalizers.next().unwrap_or(&false));
+        // The payload is encoded again when the request is sent, where failing to can be reported.
+        self.stale_payload = true;
+    }
+
+    fn items_delivered(&mut self, outcomes: &[ItemOutcome]) {
+        for (index, size) in self.item_sizes.iter().enumerate() {
+            if matches!(outcomes.get(index), Some(ItemOutcome::Delivered) | None) {
+                self.delivered += size.clone();
+            }
+        }
     }
 }
 
@@ -172,6 +246,15 @@ impl DriverResponse for ElasticsearchResponse {
     fn events_sent(&self) -> &GroupedCountByteSize {
         &self.events_byte_size
     }
+
+    fn rejection(&self) -> Option<Rejection> {
+        let status = self.http_response.status();
+        Some(Rejection {
+            status: Some(status.as_u16().to_string()),
+            response: Some(String::from_utf8_lossy(self.http_response.body()).into_owned()),
+            ..Rejection::new(format!("Request was rejected with status {status}."))
+        })
+    }
 }
 
 impl Service<ElasticsearchRequest> for ElasticsearchService {
@@ -189,11 +272,20 @@ impl Service<ElasticsearchRequest> for ElasticsearchService {
         let mut http_service = self.batch_service.clone();
         Box::pin(async move {
             http_service.ready().await?;
-            let events_byte_size =
-                std::mem::take(req.metadata_mut()).into_events_estimated_json_encoded_byte_size();
+            req.encode()?;
+            // Only the delivered documents are reported as sent, along with the documents
+            // delivered by the earlier attempts of a partially retried request.
+            let mut events_byte_size = std::mem::take(&mut req.delivered);
+            let sizes = std::mem::take(&mut req.item_sizes);
             let http_response = http_service.call(req).await?;
 
             let event_status = get_event_status(&http_response);
+            let delivered = delivered_items(&http_response, sizes.len());
+            for (size, delivered) in sizes.into_iter().zip(delivered) {
+                if delivered {
+                    events_byte_size += size;
+                }
+            }
             Ok(ElasticsearchResponse {
                 event_status,
                 http_response,
@@ -216,13 +308,33 @@ fn emit_bad_response_error(response: &Response<Bytes>) {
     );
 }
 
+/// Returns whether each of the `count` documents of a request was delivered, from its response.
+fn delivered_items(response: &Response<Bytes>, count: usize) -> Vec<bool> {
+    let body = String::from_utf8_lossy(response.body());
+    if !response.status().is_success() {
+        vec![false; count]
+    } else if !body.contains("\"errors\":true") {
+        vec![true; count]
+    } else {
+        EsResultResponse::parse(&body).map_or_else(
+            |_| vec![false; count],
+            |result| result.items_delivered().collect(),
+        )
+    }
+}
+
 fn get_event_status(response: &Response<Bytes>) -> EventStatus {
     let status = response.status();
     if status.is_success() {
         let body = String::from_utf8_lossy(response.body());
         if body.contains("\"errors\":true") {
             emit_bad_response_error(response);
-            EventStatus::Rejected
+            // The documents of the response are finalized individually by the retry policy.
+            if EsResultResponse::parse(&body).is_ok() {
+                EventStatus::Delivered
+            } else {
+                EventStatus::Rejected
+            }
         } else {
             EventStatus::Delivered
         }
@@ -38,8 +38,11 @@ use crate::{
 };
 
 mod bytes_path;
+mod rotation;
 
 use bytes_path::BytesPath;
+pub use rotation::RotationConfig;
+use rotation::Segment;
 
 /// Configuration for the `file` sink.
 #[serde_as]
@@ -89,6 +92,10 @@ pub struct FileSinkConfig {
     #[configurable(derived)]
     #[serde(default)]
     pub internal_metrics: FileInternalMetricsConfig,
+
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    pub rotation: RotationConfig,
 }
 
 impl GenerateConfig for FileSinkConfig {
@@ -101,6 +108,7 @@ impl GenerateConfig for FileSinkConfig {
             acknowledgements: Default::default(),
             timezone: Default::default(),
             internal_metrics: Default::default(),
+            rotation: Default::default(),
         })
         .unwrap()
     }
@@ -179,6 +187,24 @@ impl OutFile {
     }
 }
 
+struct OpenFile {
+    file: OutFile,
+    /// Set when files are rotated.
+    segment: Option<Segment>,
+}
+
+impl OpenFile {
+    /// Closes the file and, when files are rotated, renames it to its final name and applies
+    /// retention.
+    async fn close(&mut self, rotation: &RotationConfig) -> Result<(), std::io::Error> {
+        self.file.close().await?;
+        if let Some(segment) = &self.segment {
+            segment.finish(rotation).await?;
+        }
+        Ok(())
+    }
+}
+
 #[async_trait::async_trait]
 #[typetag::serde(name = "file")]
 impl SinkConfig for FileSinkConfig {
@@ -207,8 +233,9 @@ pub struct FileSink {
     transformer: Transformer,
     encoder: Encoder<Framer>,
     idle_timeout: Duration,
-    files: ExpiringHashMap<Bytes, OutFile>,
+    files: ExpiringHashMap<Bytes, OpenFile>,
     compression: Compression,
+    rotation: RotationConfig,
     events_sent: Registered<EventsSent>,
     include_file_metric_tag: bool,
 }
@@ -218,6 +245,7 @@ impl FileSink {
         let transformer = config.encoding.transformer();
         let (framer, serializer) = config.encoding.build(SinkType::StreamBased)?;
         let encoder = Encoder::<Framer>::new(framer, serializer);
+        config.rotation.validate()?;
 
         let offset = config
             .timezone
@@ -231,6 +259,7 @@ impl FileSink {
             idle_timeout: config.idle_timeout,
             files: ExpiringHashMap::default(),
             compression: config.compression,
+            rotation: config.rotation.clone(),
             events_sent: register!(EventsSent::from(Output(None))),
             include_file_metric_tag: config.internal_metrics.include_file_tag,
         })
@@ -273,7 +302,7 @@ impl FileSink {
                             // Close all the open files.
                             debug!(message = "Closing all the open files.");
                             for (path, file) in self.files.iter_mut() {
-                                if let Err(error) = file.close().await {
+                                if let Err(error) = file.close(&self.rotation).await {
                                     emit!(FileIoError {
                                         error,
                                         code: "failed_closing_file",
@@ -302,7 +331,7 @@ impl FileSink {
                         Some((mut expired_file, path)) => {
                             // We got an expired file. All we really want is to
                             // flush and close it.
-                            if let Err(error) = expired_file.close().await {
+                            if let Err(error) = expired_file.close(&self.rotation).await {
                                 emit!(FileIoError {
                                     error,
                                     code: "failed_closing_file",
@@ -337,6 +366,15 @@ impl FileSink {
             }
         };
 
+        let expired = self.files.get(&path).is_some_and(|file| {
+            file.segment
+                .as_ref()
+                .is_some_and(|segment| segment.is_expired(&self.rotation))
+        });
+        if expired {
+            self.rotate(&path).await;
+        }
+
         let next_deadline = self.deadline_at();
         trace!(message = "Computed next deadline.", next_deadline = ?next_deadline, path = ?path);
 
@@ -345,8 +383,15 @@ impl FileSink {
             file
         } else {
             trace!(message = "Opening new file.", ?path);
-            let file = match open_file(BytesPath::new(path.clone())).await {
-                Ok(file) => file,
+            let opened = if self.rotation.is_enabled() {
+                self.open_segment(&path).await
+            } else {
+                open_file(BytesPath::new(path.clone()))
+                    .await
+                    .map(|file| (file, None))
+            };
+            let (file, segment) = match opened {
+                Ok(opened) => opened,
                 Err(error) => {
                     // We couldn't open the file for this event.
                     // Maybe other events will work though! Just log
@@ -363,7 +408,10 @@ impl FileSink {
                 }
             };
 
-            let outfile = OutFile::new(file, self.compression);
+            let outfile = OpenFile {
+                file: OutFile::new(file, self.compression),
+                segment,
+            };
 
             self.files.insert_at(path.clone(), outfile, next_deadline);
             emit!(FileOpen {
@@ -375,8 +423,14 @@ impl FileSink {
         trace!(message = "Writing an event to file.", path = ?path);
         let event_size = event.estimated_json_encoded_size_of();
         let finalizers = event.take_finalizers();
-        match write_event_to_file(file, event, &self.transformer, &mut self.encoder).await {
+        let mut full = false;
+        match write_event_to_file(&mut file.file, event, &self.transformer, &mut self.encoder).await
+        {
             Ok(byte_size) => {
+                full = file
+                    .segment
+                    .as_mut()
+                    .is_some_and(|segment| segment.record_write(byte_size, &self.rotation));
                 finalizers.update_status(EventStatus::Delivered);
                 self.events_sent.emit(CountByteSize(1, event_size));
                 emit!(FileBytesSent {
@@ -396,6 +450,37 @@ impl FileSink {
                 });
             }
         }
+
+        if full {
+            self.rotate(&path).await;
+        }
+    }
+
+    /// Opens the next file of a rotated path.
+    async fn open_segment(&self, path: &Bytes) -> std::io::Result<(File, Option<Segment>)> {
+        let (segment, open_path) =
+            Segment::start(BytesPath::new(path.clone()).as_ref(), &self.rotation).await?;
+        let file = open_file(open_path).await?;
+        Ok((file, Some(segment)))
+    }
+
+    /// Closes the open file of a path, so that the next event starts a new one.
+    async fn rotate(&mut self, path: &Bytes) {
+        if let Some((mut file, _)) = self.files.remove(path) {
+            trace!(message = "Rotating file.", ?path);
+            if let Err(error) = file.close(&self.rotation).await {
+                emit!(FileIoError {
+                    error,
+                    code: "failed_closing_file",
+                    message: "Failed to close file.",
+                    path,
+                    dropped_events: 0,
+                });
+            }
+            emit!(FileOpen {
+                count: self.files.len()
+            });
+        }
     }
 }
 
@@ -482,6 +567,7 @@ mod tests {
             internal_metrics: FileInternalMetricsConfig {
                 include_file_tag: true,
             },
+            rotation: Default::default(),
         };
 
         let (input, _events) = random_lines_with_stream(100, 64, None);
@@ -508,6 +594,7 @@ mod tests {
             internal_metrics: FileInternalMetricsConfig {
                 include_file_tag: true,
             },
+            rotation: Default::default(),
         };
 
         let (input, _) = random_lines_with_stream(100, 64, None);
@@ -534,6 +621,7 @@ mod tests {
             internal_metrics: FileInternalMetricsConfig {
                 include_file_tag: true,
             },
+            rotation: Default::default(),
         };
 
         let (input, _) = random_lines_with_stream(100, 64, None);
@@ -565,6 +653,7 @@ mod tests {
             internal_metrics: FileInternalMetricsConfig {
                 include_file_tag: true,
             },
+            rotation: Default::default(),
         };
 
         let (mut input, _events) = random_events_with_stream(32, 8, None);
@@ -647,6 +736,7 @@ mod tests {
             internal_metrics: FileInternalMetricsConfig {
                 include_file_tag: true,
             },
+            rotation: Default::default(),
         };
 
         let (mut input, _events) = random_lines_with_stream(10, 64, None);
@@ -689,6 +779,49 @@ mod tests {
         sink_handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn log_rotation_by_size() {
+        let directory = temp_dir();
+        let template = directory.join("app.log");
+
+        let config = FileSinkConfig {
+            path: template.to_str().unwrap().try_into().unwrap(),
+            idle_timeout: default_idle_timeout(),
+            encoding: (None::<FramingConfig>, TextSerializerConfig::default()).into(),
+            compression: Compression::None,
+            acknowledgements: Default::default(),
+            timezone: Default::default(),
+            internal_metrics: Default::default(),
+            rotation: RotationConfig {
+                max_file_size: std::num::NonZeroU64::new(20),
+                atomic: true,
+                max_files: std::num::NonZeroUsize::new(2),
+                ..Default::default()
+            },
+        };
+
+        let input = (0..8)
+            .map(|index| format!("line {index}"))
+            .collect::<Vec<_>>();
+        run_assert_log_sink(&config, input.clone()).await;
+
+        // Files are rotated once they hold 3 lines of 7 bytes, and only the last 2 are kept.
+        let mut names = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["app.2.log".to_owned(), "app.3.log".to_owned()]);
+        assert_eq!(
+            lines_from_file(directory.join("app.2.log")),
+            input[3..6].to_vec()
+        );
+        assert_eq!(
+            lines_from_file(directory.join("app.3.log")),
+            input[6..].to_vec()
+        );
+    }
+
     #[tokio::test]
     async fn metric_single_partition() {
         let template = temp_file();
@@ -703,6 +836,7 @@ mod tests {
             internal_metrics: FileInternalMetricsConfig {
                 include_file_tag: true,
             },
+            rotation: Default::default(),
         };
 
         let (input, _events) = random_metrics_with_stream(100, None, None);
@@ -734,6 +868,7 @@ mod tests {
             internal_metrics: FileInternalMetricsConfig {
                 include_file_tag: true,
             },
+            rotation: Default::default(),
         };
 
         let metric_count = 3;
@@ -785,6 +920,7 @@ mod tests {
             internal_metrics: FileInternalMetricsConfig {
                 include_file_tag: true,
             },
+            rotation: Default::default(),
         };
 
         let (input, _events) = random_lines_with_stream(100, 64, None);
@@ -0,0 +1,389 @@
+//! Rotation and retention of the files written by the `file` sink.
+
+use std::{
+    io,
+    num::{NonZeroU64, NonZeroUsize},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use serde_with::serde_as;
+use tokio::fs;
+use vector_lib::configurable::configurable_component;
+
+/// File rotation and retention configuration.
+///
+/// When rotation is enabled, by setting a maximum size or age or by enabling `atomic`, each file is
+/// written with a sequence number inserted before its extensions, such as `app.3.log.gz` for the
+/// path `app.log.gz`. A new file, with the next sequence number, is started when the current file
+/// is rotated, and when events are written again after the current file was closed for idleness.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RotationConfig {
+    /// The size, in bytes, after which a file is rotated.
+    ///
+    /// The size is measured before compression.
+    #[serde(default)]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    #[configurable(metadata(docs::examples = 104857600))]
+    pub max_file_size: Option<NonZeroU64>,
+
+    /// The age after which a file is rotated.
+    ///
+    /// The age of a file is checked when events are written to it.
+    #[serde(default, rename = "max_age_secs")]
+    #[serde_as(as = "Option<serde_with::DurationSeconds<u64>>")]
+    #[configurable(metadata(docs::examples = 3600))]
+    #[configurable(metadata(docs::human_name = "Maximum Age"))]
+    pub max_age: Option<Duration>,
+
+    /// Whether files are written under a temporary name, and renamed once closed.
+    ///
+    /// The temporary name is the final name prefixed with `.` and suffixed with `.tmp`, so that
+    /// readers of the final names never see partially written files. Temporary files left by an
+    /// unclean shutdown are renamed to their final names when the next file of the path is started.
+    #[serde(default)]
+    pub atomic: bool,
+
+    /// The maximum number of files to keep for each path.
+    ///
+    /// The oldest files are deleted when a file is closed.
+    #[serde(default)]
+    pub max_files: Option<NonZeroUsize>,
+
+    /// The maximum total size, in bytes, of the files to keep for each path.
+    ///
+    /// The oldest files are deleted when a file is closed, but the newest file is always kept.
+    #[serde(default)]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_total_bytes: Option<NonZeroU64>,
+}
+
+impl RotationConfig {
+    /// Returns whether files are written with sequence numbers.
+    pub const fn is_enabled(&self) -> bool {
+        self.max_file_size.is_some() || self.max_age.is_some() || self.atomic
+    }
+
+    pub fn validate(&self) -> crate::Result<()> {
+        if !self.is_enabled() && (self.max_files.is_some() || self.max_total_bytes.is_some()) {
+            return Err(
+                "Retention requires rotation to be enabled with `max_file_size`, \
+                 `max_age_secs` or `atomic`."
+                    .into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Deletes the oldest files of a path beyond the retention limits.
+    async fn enforce_retention(&self, path: &RotatedPath) -> io::Result<()> {
+        if self.max_files.is_none() && self.max_total_bytes.is_none() {
+            return Ok(());
+        }
+
+        let mut kept = 0;
+        let mut total_bytes = 0;
+        for file in path.list().await?.iter().rev() {
+            kept += 1;
+            total_bytes += file.size;
+            let exceeded = self.max_files.is_some_and(|max| kept > max.get())
+                || self
+                    .max_total_bytes
+                    .is_some_and(|max| total_bytes > max.get());
+            if kept > 1 && exceeded {
+                debug!(message = "Deleting file beyond retention.", path = ?file.path);
+                fs::remove_file(&file.path).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A rendered path, whose files are told apart by sequence numbers.
+#[derive(Clone, Debug)]
+struct RotatedPath {
+    directory: PathBuf,
+    stem: String,
+    extensions: String,
+}
+
+/// A closed file of a rotated path.
+#[derive(Debug)]
+struct RotatedFile {
+    sequence: u64,
+    path: PathBuf,
+    size: u64,
+}
+
+impl RotatedPath {
+    fn new(path: &Path) -> Self {
+        let directory = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // Leading dots are kept in the stem, so that hidden files stay hidden.
+        let start = name.len() - name.trim_start_matches('.').len();
+        let (stem, extensions) = match name[start..].find('.') {
+            Some(index) => name.split_at(start + index),
+            None => (name.as_str(), ""),
+        };
+        Self {
+            directory,
+            stem: stem.to_owned(),
+            extensions: extensions.to_owned(),
+        }
+    }
+
+    fn file_name(&self, sequence: u64) -> String {
+        format!("{}.{sequence}{}", self.stem, self.extensions)
+    }
+
+    fn final_path(&self, sequence: u64) -> PathBuf {
+        self.directory.join(self.file_name(sequence))
+    }
+
+    fn temporary_path(&self, sequence: u64) -> PathBuf {
+        self.directory
+            .join(format!(".{}.tmp", self.file_name(sequence)))
+    }
+
+    /// Parses the sequence number of a file name, and whether it is a temporary file.
+    fn parse(&self, name: &str) -> Option<(u64, bool)> {
+        let (name, temporary) = match name
+            .strip_prefix('.')
+            .and_then(|name| name.strip_suffix(".tmp"))
+        {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+        let sequence = name
+            .strip_prefix(self.stem.as_str())?
+            .strip_suffix(self.extensions.as_str())?
+            .strip_prefix('.')?
+            .parse()
+            .ok()?;
+        Some((sequence, temporary))
+    }
+
+    async fn entries(&self) -> io::Result<Vec<(u64, bool, fs::DirEntry)>> {
+        let directory = if self.directory.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            &self.directory
+        };
+        let mut read_dir = match fs::read_dir(directory).await {
+            Ok(read_dir) => read_dir,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        let mut entries = Vec::new();
+        while let Some(entry) = read_dir.next_entry().await? {
+            if let Some((sequence, temporary)) = self.parse(&entry.file_name().to_string_lossy()) {
+                entries.push((sequence, temporary, entry));
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Returns the sequence number following those of the existing files, including temporary ones.
+    async fn next_sequence(&self) -> io::Result<u64> {
+        let entries = self.entries().await?;
+        Ok(entries
+            .iter()
+            .map(|(sequence, _, _)| sequence + 1)
+            .max()
+            .unwrap_or(1))
+    }
+
+    /// Renames the temporary files left by an unclean shutdown to their final names, so that their
+    /// data is kept and counted against the retention limits.
+    async fn finalize_temporaries(&self) -> io::Result<()> {
+        for (sequence, temporary, entry) in self.entries().await? {
+            if temporary && entry.file_type().await?.is_file() {
+                let final_path = self.final_path(sequence);
+                warn!(message = "Renaming file left by an unclean shutdown.", path = ?final_path);
+                fs::rename(entry.path(), final_path).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Lists the closed files, ordered by sequence number.
+    async fn list(&self) -> io::Result<Vec<RotatedFile>> {
+        let mut files = Vec::new();
+        for (sequence, temporary, entry) in self.entries().await? {
+            let metadata = entry.metadata().await?;
+            if !temporary && metadata.is_file() {
+                files.push(RotatedFile {
+                    sequence,
+                    path: entry.path(),
+                    size: metadata.len(),
+                });
+            }
+        }
+        files.sort_by_key(|file| file.sequence);
+        Ok(files)
+    }
+}
+
+/// An open file of a rotated path.
+#[derive(Debug)]
+pub struct Segment {
+    path: RotatedPath,
+    sequence: u64,
+    atomic: bool,
+    opened_at: Instant,
+    written: u64,
+}
+
+impl Segment {
+    /// Starts the next file of a rendered path, returning the path to write it to.
+    pub async fn start(path: &Path, config: &RotationConfig) -> io::Result<(Self, PathBuf)> {
+        let path = RotatedPath::new(path);
+        if config.atomic {
+            // Files of a path are started once the previous one is finished, so the temporary
+            // files remaining were never finished.
+            path.finalize_temporaries().await?;
+            config.enforce_retention(&path).await?;
+        }
+        let sequence = path.next_sequence().await?;
+        let open_path = if config.atomic {
+            path.temporary_path(sequence)
+        } else {
+            path.final_path(sequence)
+        };
+        let segment = Self {
+            path,
+            sequence,
+            atomic: config.atomic,
+            opened_at: Instant::now(),
+            written: 0,
+        };
+        Ok((segment, open_path))
+    }
+
+    /// Records bytes written to the file, returning whether it has reached its maximum size.
+    pub fn record_write(&mut self, bytes: usize, config: &RotationConfig) -> bool {
+        self.written += bytes as u64;
+        config
+            .max_file_size
+            .is_some_and(|max| self.written >= max.get())
+    }
+
+    /// Returns whether the file has reached its maximum age.
+    pub fn is_expired(&self, config: &RotationConfig) -> bool {
+        config
+            .max_age
+            .is_some_and(|max_age| self.opened_at.elapsed() >= max_age)
+    }
+
+    /// Finishes a closed file, renaming it to its final name and deleting the oldest files beyond
+    /// the retention limits.
+    pub async fn finish(&self, config: &RotationConfig) -> io::Result<()> {
+        if self.atomic {
+            fs::rename(
+                self.path.temporary_path(self.sequence),
+                self.path.final_path(self.sequence),
+            )
+            .await?;
+        }
+        config.enforce_retention(&self.path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn names_files() {
+        let path = RotatedPath::new(Path::new("/var/log/app-2024.log.gz"));
+        assert_eq!(
+            path.final_path(3),
+            PathBuf::from("/var/log/app-2024.3.log.gz")
+        );
+        assert_eq!(
+            path.temporary_path(3),
+            PathBuf::from("/var/log/.app-2024.3.log.gz.tmp")
+        );
+        assert_eq!(path.parse("app-2024.3.log.gz"), Some((3, false)));
+        assert_eq!(path.parse(".app-2024.12.log.gz.tmp"), Some((12, true)));
+        assert_eq!(path.parse("app-2024.log.gz"), None);
+        assert_eq!(path.parse("app-2024.x.log.gz"), None);
+
+        let hidden = RotatedPath::new(Path::new(".spool"));
+        assert_eq!(hidden.file_name(1), ".spool.1");
+        assert_eq!(hidden.parse("..spool.1.tmp"), Some((1, true)));
+    }
+
+    #[tokio::test]
+    async fn rotates_atomically_with_retention() {
+        let directory = temp_dir();
+        let config = RotationConfig {
+            atomic: true,
+            max_files: NonZeroUsize::new(2),
+            ..Default::default()
+        };
+        let path = directory.join("app.log");
+
+        for expected in 1..=3 {
+            let (segment, open_path) = Segment::start(&path, &config).await.unwrap();
+            assert_eq!(segment.sequence, expected);
+            std::fs::create_dir_all(&directory).unwrap();
+            std::fs::write(&open_path, "data").unwrap();
+            assert!(!directory.join(format!("app.{expected}.log")).exists());
+            segment.finish(&config).await.unwrap();
+            assert!(directory.join(format!("app.{expected}.log")).exists());
+        }
+
+        let mut names = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["app.2.log", "app.3.log"]);
+    }
+
+    #[tokio::test]
+    async fn finalizes_stale_temporary_files() {
+        let directory = temp_dir();
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("app.1.log"), "data").unwrap();
+        std::fs::write(directory.join(".app.2.log.tmp"), "data").unwrap();
+        std::fs::write(directory.join(".app.3.log.tmp"), "data").unwrap();
+        let config = RotationConfig {
+            atomic: true,
+            max_files: NonZeroUsize::new(2),
+            ..Default::default()
+        };
+
+        let (segment, _) = Segment::start(&directory.join("app.log"), &config)
+            .await
+            .unwrap();
+        assert_eq!(segment.sequence, 4);
+
+        let mut names = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, ["app.2.log", "app.3.log"]);
+    }
+
+    #[test]
+    fn rejects_retention_without_rotation() {
+        let config = RotationConfig {
+            max_files: NonZeroUsize::new(2),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
@@ -4,6 +4,7 @@
 use aws_config::meta::region::ProvideRegion;
 #[cfg(feature = "aws-core")]
 use aws_types::region::Region;
+use bytes::Bytes;
 use http::{header::AUTHORIZATION, HeaderName, HeaderValue, Method, Request, StatusCode};
 use hyper::Body;
 use indexmap::IndexMap;
@@ -25,11 +26,12 @@ use crate::aws::AwsAuthentication;
 use crate::sinks::util::http::SigV4Config;
 use crate::{
     codecs::{EncodingConfigWithFraming, SinkType},
-    http::{Auth, HttpClient, MaybeAuth},
+    http::{HttpAuthenticator, HttpClient, HttpClientAuth},
     sinks::{
         prelude::*,
         util::{
             http::{http_response_retry_logic, HttpService, RequestConfig},
+            load_balance::LoadBalanceConfig,
             RealtimeSizeBasedDefaultBatchSettings, UriSerde,
         },
     },
@@ -55,7 +57,7 @@ pub struct HttpSinkConfig {
     pub method: HttpMethod,
 
     #[configurable(derived)]
-    pub auth: Option<Auth>,
+    pub auth: Option<HttpClientAuth>,
 
     /// A list of custom headers to add to each request.
     #[configurable(deprecated = "This option has been deprecated, use `request.headers` instead.")]
@@ -97,6 +99,10 @@ pub struct HttpSinkConfig {
     #[serde(default)]
     pub request: RequestConfig,
 
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_balance: Option<LoadBalanceConfig>,
+
     #[configurable(derived)]
     pub tls: Option<TlsConfig>,
 
@@ -163,7 +169,12 @@ impl From<HttpMethod> for Method {
 impl HttpSinkConfig {
     fn build_http_client(&self, cx: &SinkContext) -> crate::Result<HttpClient> {
         let tls = TlsSettings::from_options(self.tls.as_ref())?;
-        Ok(HttpClient::new(tls, cx.proxy())?)
+        let load_balancer = self
+            .load_balance
+            .as_ref()
+            .map(LoadBalanceConfig::build)
+            .transpose()?;
+        Ok(HttpClient::new(tls, cx.proxy())?.with_load_balancer(load_balancer))
     }
 
     pub(super) fn build_encoder(&self) -> crate::Result<Encoder<Framer>> {
@@ -182,16 +193,24 @@ impl GenerateConfig for HttpSinkConfig {
     }
 }
 
-async fn healthcheck(uri: UriSerde, auth: Option<Auth>, client: HttpClient) -> crate::Result<()> {
-    let auth = auth.choose_one(&uri.auth)?;
+async fn healthcheck(
+    uri: UriSerde,
+    auth: Option<HttpAuthenticator>,
+    client: HttpClient,
+) -> crate::Result<()> {
+    if auth.is_some() && uri.auth.is_some() {
+        return Err("Two authorization credentials was provided.".into());
+    }
     let uri = uri.with_default_parts();
-    let mut request = Request::head(&uri.uri).body(Body::empty()).unwrap();
+    let mut request = Request::head(&uri.uri).body(Bytes::new()).unwrap();
 
-    if let Some(auth) = auth {
+    if let Some(auth) = &uri.auth {
+        auth.apply(&mut request);
+    } else if let Some(auth) = &auth {
         auth.apply(&mut request);
     }
 
-    let response = client.send(request).await?;
+    let response = client.send(request.map(Body::from)).await?;
 
     match response.status() {
         StatusCode::OK => Ok(()),
@@ -248,14 +267,29 @@ impl SinkConfig for HttpSinkConfig {
 
         let headers = validate_headers(&request.headers, self.auth.is_some())?;
 
+        #[cfg(feature = "aws-core")]
+        if self.load_balance.is_some() && matches!(self.auth, Some(HttpClientAuth::Aws { .. })) {
+            return Err("Load balancing can't be used with AWS authentication.".into());
+        }
+
         let (payload_prefix, payload_suffix) =
             validate_payload_wrapper(&self.payload_prefix, &self.payload_suffix, &encoder)?;
 
-        let client = self.build_http_client(&cx)?;
+        let tls = TlsSettings::from_options(self.tls.as_ref())?;
+        let authenticator = match &self.auth {
+            Some(auth) => Some(auth.build_authenticator(tls, cx.proxy())?),
+            None => None,
+        };
+
+        let client = self.build_http_client(&cx)?.with_oauth2(
+            authenticator
+                .as_ref()
+                .and_then(HttpAuthenticator::token_source),
+        );
 
         let healthcheck = match cx.healthcheck.uri {
             Some(healthcheck_uri) => {
-                healthcheck(healthcheck_uri, self.auth.clone(), client.clone()).boxed()
+                healthcheck(healthcheck_uri, authenticator.clone(), client.clone()).boxed()
             }
             None => future::ok(()).boxed(),
         };
@@ -287,7 +321,7 @@ impl SinkConfig for HttpSinkConfig {
 
         let http_sink_request_builder = HttpSinkRequestBuilder::new(
             self.method,
-            self.auth.clone(),
+            authenticator,
             headers,
             content_type,
             content_encoding,
@@ -295,7 +329,7 @@ impl SinkConfig for HttpSinkConfig {
 
         let service = match &self.auth {
             #[cfg(feature = "aws-core")]
-            Some(Auth::Aws { auth, service }) => {
+            Some(HttpClientAuth::Aws { auth, service }) => {
                 let default_region = crate::aws::region_provider(&ProxyConfig::default(), None)?
                     .region()
                     .await;
@@ -389,6 +423,7 @@ mod tests {
                 compression: Compression::default(),
                 batch: BatchConfig::default(),
                 request: RequestConfig::default(),
+                load_balance: None,
                 tls: None,
                 acknowledgements: AcknowledgementsConfig::default(),
                 payload_prefix: String::new(),
@@ -10,7 +10,7 @@ use http::{
 use indexmap::IndexMap;
 
 use crate::{
-    http::{Auth, MaybeAuth},
+    http::HttpAuthenticator,
     sinks::{
         util::{
             http::{HttpRequest, HttpServiceRequestBuilder},
@@ -27,7 +27,7 @@ use super::sink::PartitionKey;
 #[derive(Debug, Clone)]
 pub(super) struct HttpSinkRequestBuilder {
     method: HttpMethod,
-    auth: Option<Auth>,
+    auth: Option<HttpAuthenticator>,
     headers: IndexMap<HeaderName, HeaderValue>,
     content_type: Option<String>,
     content_encoding: Option<String>,
@@ -37,7 +37,7 @@ impl HttpSinkRequestBuilder {
     /// Creates a new `HttpSinkRequestBuilder`
     pub(super) const fn new(
         method: HttpMethod,
-        auth: Option<Auth>,
+        auth: Option<HttpAuthenticator>,
         headers: IndexMap<HeaderName, HeaderValue>,
         content_type: Option<String>,
         content_encoding: Option<String>,
@@ -62,7 +62,9 @@ impl HttpServiceRequestBuilder<PartitionKey> for HttpSinkRequestBuilder {
         let uri_auth = uri_serde.auth;
         let uri = uri_serde.uri;
 
-        let auth = self.auth.choose_one(&uri_auth)?;
+        if self.auth.is_some() && uri_auth.is_some() {
+            return Err("Two authorization credentials was provided.".into());
+        }
 
         let method: Method = self.method.into();
         let mut builder = Request::builder().method(method).uri(uri);
@@ -90,7 +92,9 @@ impl HttpServiceRequestBuilder<PartitionKey> for HttpSinkRequestBuilder {
             .context(HTTPRequestBuilderSnafu)
             .map_err(Into::<crate::Error>::into)?;
 
-        if let Some(auth) = auth {
+        if let Some(auth) = uri_auth {
+            auth.apply(&mut request);
+        } else if let Some(auth) = &self.auth {
             auth.apply(&mut request);
         }
 
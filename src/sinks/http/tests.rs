@@ -65,6 +65,7 @@ fn default_cfg(encoding: EncodingConfigWithFraming) -> HttpSinkConfig {
         payload_suffix: Default::default(),
         batch: Default::default(),
         request: Default::default(),
+        load_balance: Default::default(),
         tls: Default::default(),
         acknowledgements: Default::default(),
     }
@@ -6,7 +6,10 @@ use super::{healthcheck::healthcheck, sink::LokiSink};
 use crate::{
     http::{Auth, HttpClient, MaybeAuth},
     schema,
-    sinks::{prelude::*, util::UriSerde},
+    sinks::{
+        prelude::*,
+        util::{load_balance::LoadBalanceConfig, UriSerde},
+    },
 };
 
 const fn default_compression() -> Compression {
@@ -109,6 +112,10 @@ pub struct LokiConfig {
     #[serde(default)]
     pub batch: BatchConfig<LokiDefaultBatchSettings>,
 
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_balance: Option<LoadBalanceConfig>,
+
     #[configurable(derived)]
     pub tls: Option<TlsConfig>,
 
@@ -204,7 +211,12 @@ impl GenerateConfig for LokiConfig {
 impl LokiConfig {
     pub(super) fn build_client(&self, cx: SinkContext) -> crate::Result<HttpClient> {
         let tls = TlsSettings::from_options(self.tls.as_ref())?;
-        let client = HttpClient::new(tls, cx.proxy())?;
+        let load_balancer = self
+            .load_balance
+            .as_ref()
+            .map(LoadBalanceConfig::build)
+            .transpose()?;
+        let client = HttpClient::new(tls, cx.proxy())?.with_load_balancer(load_balancer);
         Ok(client)
     }
 }
@@ -78,6 +78,8 @@ pub mod loki;
 pub mod mezmo;
 #[cfg(feature = "sinks-mqtt")]
 pub mod mqtt;
+#[cfg(feature = "sinks-mysql")]
+pub mod mysql;
 #[cfg(feature = "sinks-nats")]
 pub mod nats;
 #[cfg(feature = "sinks-new_relic")]
@@ -104,6 +106,8 @@ pub mod sematext;
 pub mod socket;
 #[cfg(feature = "sinks-splunk_hec")]
 pub mod splunk_hec;
+#[cfg(feature = "sinks-sqlite")]
+pub mod sqlite;
 #[cfg(feature = "sinks-statsd")]
 pub mod statsd;
 #[cfg(feature = "sinks-vector")]
@@ -0,0 +1,101 @@
+use serde_json::Value as JsonValue;
+use sqlx::mysql::MySqlDatabaseError;
+use sqlx::{MySql, MySqlConnection, Pool};
+
+use crate::sinks::util::sql::{
+    database_error_code, is_connection_error,
+    values::{SqlValue, ValuesInsert},
+    Dialect,
+};
+
+/// The maximum number of parameters of a prepared statement.
+pub const MAX_PARAMETERS: usize = 65535;
+
+/// Writes batches to MySQL or MariaDB with multi-row `INSERT ... VALUES` statements.
+#[derive(Debug)]
+pub struct MySqlDialect {
+    insert: ValuesInsert,
+}
+
+impl MySqlDialect {
+    pub const fn new(insert: ValuesInsert) -> Self {
+        Self { insert }
+    }
+
+    async fn insert(
+        &self,
+        connection: &mut MySqlConnection,
+        table: &str,
+        rows: &[JsonValue],
+    ) -> Result<(), sqlx::Error> {
+        for chunk in self.insert.chunks(rows) {
+            let statement = self.insert.statement(table, chunk.len());
+            let mut query = sqlx::query(&statement);
+            for value in self.insert.values(chunk) {
+                query = match value {
+                    SqlValue::Null => query.bind(None::<String>),
+                    SqlValue::Bool(value) => query.bind(value),
+                    SqlValue::Integer(value) => query.bind(value),
+                    SqlValue::Float(value) => query.bind(value),
+                    SqlValue::Text(value) => query.bind(value),
+                };
+            }
+            query.execute(&mut *connection).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the MySQL error number of a database error.
+fn error_number(error: &sqlx::Error) -> Option<u16> {
+    match error {
+        sqlx::Error::Database(error) => error
+            .try_downcast_ref::<MySqlDatabaseError>()
+            .map(MySqlDatabaseError::number),
+        _ => None,
+    }
+}
+
+#[async_trait::async_trait]
+impl Dialect for MySqlDialect {
+    type Database = MySql;
+
+    const PROTOCOL: &'static str = "mysql";
+
+    async fn write_batch(
+        &self,
+        pool: &Pool<MySql>,
+        table: &str,
+        rows: &[JsonValue],
+    ) -> Result<(), sqlx::Error> {
+        // Batches split into several statements are written atomically, so that they can be retried.
+        let mut transaction = pool.begin().await?;
+        self.insert(&mut transaction, table, rows).await?;
+        transaction.commit().await
+    }
+
+    async fn insert_row(
+        &self,
+        connection: &mut MySqlConnection,
+        table: &str,
+        row: &JsonValue,
+    ) -> Result<(), sqlx::Error> {
+        self.insert(connection, table, std::slice::from_ref(row))
+            .await
+    }
+
+    fn is_retriable_error(&self, error: &sqlx::Error) -> bool {
+        is_connection_error(error)
+            // Connection exceptions, and transaction rollbacks such as deadlocks.
+            || database_error_code(error).is_some_and(|code| matches!(&code[..2], "08" | "40"))
+            // Lock wait timeouts and too many connections.
+            || matches!(error_number(error), Some(1205 | 1040))
+    }
+
+    fn is_data_error(&self, error: &sqlx::Error) -> bool {
+        // Cardinality violations, data exceptions and integrity constraint violations.
+        database_error_code(error).is_some_and(|code| matches!(&code[..2], "21" | "22" | "23"))
+            // Incorrect values for the type of a column, reported without a specific SQLSTATE.
+            || matches!(error_number(error), Some(1366))
+    }
+}
@@ -0,0 +1,4 @@
+mod config;
+mod dialect;
+
+pub use self::config::MySqlConfig;
@@ -46,6 +46,7 @@ impl Default for Protocol {
             payload_suffix: Default::default(),
             batch: Default::default(),
             request: Default::default(),
+            load_balance: Default::default(),
             tls: Default::default(),
             acknowledgements: Default::default(),
         })
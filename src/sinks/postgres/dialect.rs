@@ -0,0 +1,91 @@
+use serde_json::Value as JsonValue;
+use sqlx::types::Json;
+use sqlx::{PgConnection, Pool, Postgres};
+
+use super::{config::InsertMode, statement::StatementBuilder};
+use crate::sinks::util::sql::{database_error_code, is_connection_error, Dialect};
+
+/// Writes batches to PostgreSQL with a single `INSERT` statement taking the rows as a JSON array,
+/// or with `COPY`.
+#[derive(Debug)]
+pub struct PostgresDialect {
+    statements: StatementBuilder,
+    insert_mode: InsertMode,
+}
+
+impl PostgresDialect {
+    pub const fn new(statements: StatementBuilder, insert_mode: InsertMode) -> Self {
+        Self {
+            statements,
+            insert_mode,
+        }
+    }
+
+    async fn insert(
+        &self,
+        connection: &mut PgConnection,
+        table: &str,
+        rows: &[JsonValue],
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(&self.statements.insert(table))
+            .bind(Json(rows))
+            .execute(connection)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[async_trait::async_trait]
+impl Dialect for PostgresDialect {
+    type Database = Postgres;
+
+    const PROTOCOL: &'static str = "postgres";
+
+    async fn write_batch(
+        &self,
+        pool: &Pool<Postgres>,
+        table: &str,
+        rows: &[JsonValue],
+    ) -> Result<(), sqlx::Error> {
+        match self.insert_mode {
+            InsertMode::Insert => {
+                let mut connection = pool.acquire().await?;
+                self.insert(&mut connection, table, rows).await
+            }
+            InsertMode::Copy => {
+                let mut copy = pool.copy_in_raw(&self.statements.copy(table)).await?;
+                let sent = copy.send(self.statements.csv(rows)).await.map(|_| ());
+                if let Err(error) = sent {
+                    _ = copy.abort(error.to_string()).await;
+                    return Err(error);
+                }
+                copy.finish().await.map(|_| ())
+            }
+        }
+    }
+
+    async fn insert_row(
+        &self,
+        connection: &mut PgConnection,
+        table: &str,
+        row: &JsonValue,
+    ) -> Result<(), sqlx::Error> {
+        self.insert(connection, table, std::slice::from_ref(row))
+            .await
+    }
+
+    fn is_retriable_error(&self, error: &sqlx::Error) -> bool {
+        is_connection_error(error)
+            || database_error_code(error).is_some_and(|code| {
+                // Connection exceptions, transaction rollbacks such as serialization failures and
+                // deadlocks, insufficient resources and server shutdowns are transient.
+                matches!(&code[..2], "08" | "40" | "53")
+                    || matches!(&*code, "57P01" | "57P02" | "57P03")
+            })
+    }
+
+    fn is_data_error(&self, error: &sqlx::Error) -> bool {
+        // Cardinality violations, data exceptions and integrity constraint violations.
+        database_error_code(error).is_some_and(|code| matches!(&code[..2], "21" | "22" | "23"))
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     config::{SinkConfig, SinkContext},
     event::{ObjectMap, TraceEvent, Value},
     sinks::{postgres::PostgresConfig, util::test::load_sink},
+    template::Template,
     test_util::{
         components::{
             run_and_assert_sink_compliance, run_and_assert_sink_error, COMPONENT_ERROR_TAGS,
@@ -446,7 +447,7 @@ async fn insertion_fails_missing_table() {
 
     let table = "missing_table".to_string();
     let (mut config, _, _) = prepare_config().await;
-    config.table = table.clone();
+    config.table = Template::try_from(table.as_str()).unwrap();
 
     let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
     let (input_event, mut receiver) = create_event_with_notifier(0);
@@ -482,3 +483,181 @@ async fn insertion_fails_primary_key_violation() {
     )
     .await;
 }
+
+async fn prepare_config_with(extra: &str) -> (PostgresConfig, String, PgConnection) {
+    let table = random_table_name();
+    let endpoint = pg_url();
+    let config_str = format!(
+        r#"
+            endpoint = "{endpoint}"
+            table = "{table}"
+            {extra}
+        "#,
+    );
+    let (config, _) = load_sink::<PostgresConfig>(&config_str).unwrap();
+
+    let connection = PgConnection::connect(endpoint.as_str())
+        .await
+        .expect("Failed to connect to Postgres");
+
+    (config, table, connection)
+}
+
+#[tokio::test]
+async fn insert_mapped_columns_with_upsert() {
+    trace_init();
+
+    let (config, table, mut connection) = prepare_config_with(
+        r#"
+            columns = [
+              { name = "id" },
+              { name = "created_at", path = "timestamp", type = "timestamptz" },
+              { name = "body", path = "message" },
+              { name = "origin", path = "payload.host" },
+            ]
+            on_conflict.action = "update"
+            on_conflict.target = ["id"]
+            on_conflict.columns = ["body"]
+        "#,
+    )
+    .await;
+    let create_table_sql = format!(
+        "CREATE TABLE {table} (id BIGINT PRIMARY KEY, created_at TIMESTAMPTZ, body TEXT, origin TEXT)"
+    );
+    sqlx::query(&create_table_sql)
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    let mut update = create_event(1);
+    update.as_mut_log().insert("message", "updated log line");
+    let mut events = vec![create_event(0), create_event(1)];
+    let receiver = BatchNotifier::apply_to(&mut events);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &POSTGRES_SINK_TAGS).await;
+    assert_eq!(receiver.await, BatchStatus::Delivered);
+
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    run_and_assert_sink_compliance(sink, stream::once(ready(update)), &POSTGRES_SINK_TAGS).await;
+
+    let select_all_sql = format!("SELECT id, body, origin FROM {table} ORDER BY id");
+    let rows: Vec<(i64, String, String)> = sqlx::query_as(&select_all_sql)
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![
+            (0, "raw log line".to_owned(), "example.com".to_owned()),
+            (1, "updated log line".to_owned(), "example.com".to_owned()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn insert_with_copy() {
+    trace_init();
+
+    let (config, table, mut connection) = prepare_config_with(
+        r#"
+            insert_mode = "copy"
+            columns = [{ name = "id" }, { name = "message" }, { name = "payload" }]
+        "#,
+    )
+    .await;
+    let create_table_sql = format!("CREATE TABLE {table} (id BIGINT, message TEXT, payload JSONB)");
+    sqlx::query(&create_table_sql)
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    let (events, receiver) = create_events(10);
+    run_and_assert_sink_compliance(sink, stream::iter(events), &POSTGRES_SINK_TAGS).await;
+    assert_eq!(receiver.await, BatchStatus::Delivered);
+
+    let select_all_sql = format!("SELECT id, message, payload FROM {table} ORDER BY id");
+    let rows: Vec<(i64, String, serde_json::Value)> = sqlx::query_as(&select_all_sql)
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 10);
+    assert_eq!(rows[3].0, 3);
+    assert_eq!(rows[3].1, "raw log line");
+    assert_eq!(rows[3].2["host"], "example.com");
+}
+
+#[tokio::test]
+async fn insert_into_templated_tables() {
+    trace_init();
+
+    let prefix = random_table_name();
+    let (mut config, _, mut connection) = prepare_config_with("").await;
+    config.table = Template::try_from(format!("{prefix}_{{{{ host }}}}").as_str()).unwrap();
+    for suffix in ["a", "b"] {
+        sqlx::query(&format!(
+            "CREATE TABLE {prefix}_{suffix} (id BIGINT, host TEXT)"
+        ))
+        .execute(&mut connection)
+        .await
+        .unwrap();
+    }
+
+    let mut events = (0..4)
+        .map(|id| {
+            let mut event = create_event(id);
+            let host = if id % 2 == 0 { "a" } else { "b" };
+            event.as_mut_log().insert("host", host);
+            event
+        })
+        .collect::<Vec<_>>();
+    let receiver = BatchNotifier::apply_to(&mut events);
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    run_and_assert_sink_compliance(sink, stream::iter(events), &POSTGRES_SINK_TAGS).await;
+    assert_eq!(receiver.await, BatchStatus::Delivered);
+
+    for (suffix, expected) in [("a", vec![0, 2]), ("b", vec![1, 3])] {
+        let ids: Vec<(i64,)> =
+            sqlx::query_as(&format!("SELECT id FROM {prefix}_{suffix} ORDER BY id"))
+                .fetch_all(&mut connection)
+                .await
+                .unwrap();
+        assert_eq!(
+            ids.into_iter().map(|(id,)| id).collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
+
+#[tokio::test]
+async fn constraint_violations_only_drop_offending_rows() {
+    trace_init();
+
+    let (config, table, mut connection) = prepare_config_with("batch.max_events = 10").await;
+    let create_table_sql = format!(
+        "CREATE TABLE {table} (id BIGINT PRIMARY KEY, host TEXT, timestamp TIMESTAMPTZ, message TEXT, payload JSONB)"
+    );
+    sqlx::query(&create_table_sql)
+        .execute(&mut connection)
+        .await
+        .unwrap();
+
+    // The duplicated event violates the primary key, which fails the whole batch at first.
+    let mut events = vec![
+        create_event(0),
+        create_event(1),
+        create_event(1),
+        create_event(2),
+    ];
+    let receiver = BatchNotifier::apply_to(&mut events);
+    let (sink, _hc) = config.build(SinkContext::default()).await.unwrap();
+    run_and_assert_sink_compliance(sink, stream::iter(events), &POSTGRES_SINK_TAGS).await;
+    assert_eq!(receiver.await, BatchStatus::Delivered);
+
+    let select_all_sql = format!("SELECT id FROM {table} ORDER BY id");
+    let ids: Vec<(i64,)> = sqlx::query_as(&select_all_sql)
+        .fetch_all(&mut connection)
+        .await
+        .unwrap();
+    assert_eq!(ids, vec![(0,), (1,), (2,)]);
+}
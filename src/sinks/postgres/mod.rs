@@ -1,7 +1,7 @@
 mod config;
+mod dialect;
 #[cfg(all(test, feature = "postgres_sink-integration-tests"))]
 mod integration_tests;
-mod service;
-mod sink;
+mod statement;
 
 pub use self::config::PostgresConfig;
@@ -0,0 +1,261 @@
+//! Generation of the statements used to write events to PostgreSQL.
+
+use serde_json::Value as JsonValue;
+
+use crate::sinks::util::sql::{Column, ColumnConfig, OnConflictConfig};
+
+/// The expression extracting the value of a column from the JSON row `r.value`.
+fn cast_expression(column: &Column, cast: &str) -> String {
+    let name = quote_literal(&column.name);
+    // Extracting JSON types as text would turn them into strings.
+    let operator = if is_json_type(cast) { "->" } else { "->>" };
+    format!("CAST(r.value {operator} {name} AS {cast})")
+}
+
+/// Builds the statements used to write batches of events.
+#[derive(Debug)]
+pub struct StatementBuilder {
+    columns: Vec<Column>,
+    on_conflict: OnConflictConfig,
+}
+
+impl StatementBuilder {
+    pub fn new(columns: &[ColumnConfig], on_conflict: OnConflictConfig) -> Self {
+        Self {
+            columns: columns.iter().map(Column::from).collect(),
+            on_conflict,
+        }
+    }
+
+    /// Returns the `INSERT` statement for a table, taking the rows as a JSON array in `$1`.
+    pub fn insert(&self, table: &str) -> String {
+        let mut statement = if self.columns.is_empty() {
+            format!("INSERT INTO {table} SELECT * FROM jsonb_populate_recordset(NULL::{table}, $1)")
+        } else {
+            self.insert_columns(table)
+        };
+
+        match &self.on_conflict {
+            OnConflictConfig::Error => {}
+            OnConflictConfig::Ignore { target } => {
+                statement.push_str(" ON CONFLICT");
+                if !target.is_empty() {
+                    statement.push_str(&format!(" ({})", quote_identifiers(target)));
+                }
+                statement.push_str(" DO NOTHING");
+            }
+            OnConflictConfig::Update { target, columns } => {
+                let assignments = columns
+                    .iter()
+                    .map(|column| {
+                        let column = quote_identifier(column);
+                        format!("{column} = EXCLUDED.{column}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                statement.push_str(&format!(
+                    " ON CONFLICT ({}) DO UPDATE SET {assignments}",
+                    quote_identifiers(target)
+                ));
+            }
+        }
+        statement
+    }
+
+    fn insert_columns(&self, table: &str) -> String {
+        let identifiers = self.identifiers();
+
+        // Columns without an explicit cast are converted to the types of the table by populating a
+        // record of the table from the remaining fields of the row.
+        let cast_columns = self
+            .columns
+            .iter()
+            .filter(|column| column.cast.is_some())
+            .map(|column| quote_literal(&column.name))
+            .collect::<Vec<_>>();
+        let populates_record = cast_columns.len() < self.columns.len();
+
+        let expressions = self
+            .columns
+            .iter()
+            .map(|column| match &column.cast {
+                Some(cast) => cast_expression(column, cast),
+                None => format!("p.{}", quote_identifier(&column.name)),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut statement = format!(
+            "INSERT INTO {table} ({identifiers}) SELECT {expressions} FROM jsonb_array_elements($1) AS r(value)"
+        );
+        if populates_record {
+            let record = if cast_columns.is_empty() {
+                "r.value".to_owned()
+            } else {
+                format!("r.value - ARRAY[{}]::text[]", cast_columns.join(", "))
+            };
+            statement.push_str(&format!(
+                " CROSS JOIN LATERAL jsonb_populate_record(NULL::{table}, {record}) AS p"
+            ));
+        }
+        statement
+    }
+
+    fn identifiers(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| quote_identifier(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Returns the `COPY` statement for a table, taking the rows in CSV format.
+    pub fn copy(&self, table: &str) -> String {
+        let identifiers = self.identifiers();
+        format!("COPY {table} ({identifiers}) FROM STDIN WITH (FORMAT csv)")
+    }
+
+    /// Encodes rows, as built by [`RowBuilder::row`](crate::sinks::util::sql::RowBuilder::row), in the CSV format expected by [`Self::copy`].
+    pub fn csv(&self, rows: &[JsonValue]) -> Vec<u8> {
+        let mut csv = Vec::new();
+        for row in rows {
+            for (index, column) in self.columns.iter().enumerate() {
+                if index > 0 {
+                    csv.push(b',');
+                }
+                match row.get(&column.name).unwrap_or(&JsonValue::Null) {
+                    // An unquoted empty value is NULL.
+                    JsonValue::Null => {}
+                    JsonValue::Bool(value) => csv.extend_from_slice(value.to_string().as_bytes()),
+                    JsonValue::Number(value) => csv.extend_from_slice(value.to_string().as_bytes()),
+                    JsonValue::String(value) => push_csv_quoted(&mut csv, value),
+                    value => push_csv_quoted(&mut csv, &value.to_string()),
+                }
+            }
+            csv.push(b'\n');
+        }
+        csv
+    }
+}
+
+fn push_csv_quoted(csv: &mut Vec<u8>, value: &str) {
+    csv.push(b'"');
+    csv.extend_from_slice(value.replace('"', "\"\"").as_bytes());
+    csv.push(b'"');
+}
+
+fn is_json_type(cast: &str) -> bool {
+    matches!(cast.trim().to_ascii_lowercase().as_str(), "json" | "jsonb")
+}
+
+fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+fn quote_identifiers(identifiers: &[String]) -> String {
+    identifiers
+        .iter()
+        .map(|identifier| quote_identifier(identifier))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn quote_literal(literal: &str) -> String {
+    format!("'{}'", literal.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::event::{LogEvent, Value};
+    use vector_lib::lookup::lookup_v2::ConfigValuePath;
+
+    use super::*;
+    use crate::sinks::util::sql::RowBuilder;
+
+    fn column(name: &str, path: Option<&str>, cast: Option<&str>) -> ColumnConfig {
+        ColumnConfig {
+            name: name.to_owned(),
+            path: path.map(|path| ConfigValuePath::try_from(path.to_owned()).unwrap()),
+            r#type: cast.map(str::to_owned),
+        }
+    }
+
+    #[test]
+    fn insert_without_columns() {
+        let statements = StatementBuilder::new(&[], OnConflictConfig::Error);
+        assert_eq!(
+            statements.insert("logs"),
+            "INSERT INTO logs SELECT * FROM jsonb_populate_recordset(NULL::logs, $1)"
+        );
+    }
+
+    #[test]
+    fn insert_with_columns_and_upsert() {
+        let statements = StatementBuilder::new(
+            &[
+                column("id", None, None),
+                column("created_at", Some("timestamp"), Some("timestamptz")),
+                column("attributes", Some("attrs"), Some("jsonb")),
+            ],
+            OnConflictConfig::Update {
+                target: vec!["id".to_owned()],
+                columns: vec!["attributes".to_owned()],
+            },
+        );
+        assert_eq!(
+            statements.insert("logs"),
+            "INSERT INTO logs (\"id\", \"created_at\", \"attributes\") \
+             SELECT p.\"id\", CAST(r.value ->> 'created_at' AS timestamptz), CAST(r.value -> 'attributes' AS jsonb) \
+             FROM jsonb_array_elements($1) AS r(value) \
+             CROSS JOIN LATERAL jsonb_populate_record(NULL::logs, r.value - ARRAY['created_at', 'attributes']::text[]) AS p \
+             ON CONFLICT (\"id\") DO UPDATE SET \"attributes\" = EXCLUDED.\"attributes\""
+        );
+    }
+
+    #[test]
+    fn insert_ignoring_conflicts() {
+        let statements = StatementBuilder::new(
+            &[column("id", None, Some("int8"))],
+            OnConflictConfig::Ignore { target: Vec::new() },
+        );
+        assert_eq!(
+            statements.insert("logs"),
+            "INSERT INTO logs (\"id\") SELECT CAST(r.value ->> 'id' AS int8) \
+             FROM jsonb_array_elements($1) AS r(value) ON CONFLICT DO NOTHING"
+        );
+    }
+
+    #[test]
+    fn rows_and_csv() {
+        let columns = [
+            column("message", None, None),
+            column("status", Some("http.status"), None),
+            column("tags", None, None),
+            column("missing", None, None),
+        ];
+        let statements = StatementBuilder::new(&columns, OnConflictConfig::Error);
+        let mut log = LogEvent::from("say \"hi\", bye");
+        log.insert("http.status", 200);
+        log.insert("tags", Value::Array(vec!["a".into(), "b".into()]));
+
+        let row = RowBuilder::new(&columns).row(log.into()).unwrap();
+        assert_eq!(
+            row,
+            serde_json::json!({
+                "message": "say \"hi\", bye",
+                "status": 200,
+                "tags": ["a", "b"],
+                "missing": null,
+            })
+        );
+
+        assert_eq!(
+            String::from_utf8(statements.csv(&[row])).unwrap(),
+            "\"say \"\"hi\"\", bye\",200,\"[\"\"a\"\",\"\"b\"\"]\",\n"
+        );
+        assert_eq!(
+            statements.copy("logs"),
+            "COPY logs (\"message\", \"status\", \"tags\", \"missing\") FROM STDIN WITH (FORMAT csv)"
+        );
+    }
+}
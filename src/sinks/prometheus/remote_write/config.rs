@@ -6,7 +6,7 @@ use crate::{
     sinks::{
         prelude::*,
         prometheus::PrometheusRemoteWriteAuth,
-        util::{auth::Auth, http::http_response_retry_logic},
+        util::{auth::Auth, http::http_response_retry_logic, load_balance::LoadBalanceConfig},
         UriParseSnafu,
     },
 };
@@ -101,6 +101,10 @@ pub struct RemoteWriteConfig {
     #[configurable(metadata(docs::common = false, docs::required = false))]
     pub expire_metrics_secs: Option<f64>,
 
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_balance: Option<LoadBalanceConfig>,
+
     #[configurable(derived)]
     pub tls: Option<TlsConfig>,
 
@@ -148,7 +152,18 @@ impl SinkConfig for RemoteWriteConfig {
         let quantiles = self.quantiles.clone();
         let default_namespace = self.default_namespace.clone();
 
-        let client = HttpClient::new(tls_settings, cx.proxy())?;
+        #[cfg(feature = "aws-core")]
+        if self.load_balance.is_some()
+            && matches!(self.auth, Some(PrometheusRemoteWriteAuth::Aws(_)))
+        {
+            return Err("Load balancing can't be used with AWS authentication.".into());
+        }
+        let load_balancer = self
+            .load_balance
+            .as_ref()
+            .map(LoadBalanceConfig::build)
+            .transpose()?;
+        let client = HttpClient::new(tls_settings, cx.proxy())?.with_load_balancer(load_balancer);
 
         let auth = match &self.auth {
             Some(PrometheusRemoteWriteAuth::Basic { user, password }) => {
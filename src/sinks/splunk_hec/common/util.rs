@@ -9,6 +9,7 @@ use vector_lib::lookup::lookup_v2::{OptionalTargetPath, OptionalValuePath};
 use vector_lib::{config::proxy::ProxyConfig, event::EventRef};
 
 use super::{
+    acknowledgements::HecClientAcknowledgementsConfig,
     request::HecRequest,
     service::{HttpRequestBuilder, MetadataFields},
     EndpointTarget,
@@ -18,7 +19,11 @@ use crate::{
     internal_events::TemplateRenderingError,
     sinks::{
         self,
-        util::{http::HttpBatchService, SinkBatchSettings},
+        util::{
+            http::HttpBatchService,
+            load_balance::{LoadBalanceConfig, LoadBalancer},
+            SinkBatchSettings,
+        },
         UriParseSnafu,
     },
     template::Template,
@@ -50,6 +55,23 @@ pub fn create_client(
     Ok(HttpClient::new(tls_settings, proxy_config)?)
 }
 
+/// Builds the load balancer of the sink.
+///
+/// Load balancing can't be used along with indexer acknowledgements, as the status of the
+/// acknowledgements must be queried from the indexer that received the events.
+pub fn build_load_balancer(
+    load_balance: Option<&LoadBalanceConfig>,
+    acknowledgements: &HecClientAcknowledgementsConfig,
+) -> crate::Result<Option<LoadBalancer>> {
+    let Some(load_balance) = load_balance else {
+        return Ok(None);
+    };
+    if acknowledgements.indexer_acknowledgements_enabled {
+        return Err("`load_balance` requires `acknowledgements.indexer_acknowledgements_enabled` to be set to `false`.".into());
+    }
+    load_balance.build().map(Some)
+}
+
 // TODO: `HttpBatchService` has been deprecated for direct use in sinks.
 //       This sink should undergo a refactor to utilize the `HttpService`
 //       instead, which extracts much of the boilerplate code for `Service`.
@@ -12,11 +12,11 @@ use crate::{
         prelude::*,
         splunk_hec::common::{
             acknowledgements::HecClientAcknowledgementsConfig,
-            build_healthcheck, build_http_batch_service, create_client,
+            build_healthcheck, build_http_batch_service, build_load_balancer, create_client,
             service::{HecService, HttpRequestBuilder},
             EndpointTarget, SplunkHecDefaultBatchSettings,
         },
-        util::http::HttpRetryLogic,
+        util::{http::HttpRetryLogic, load_balance::LoadBalanceConfig},
     },
 };
 
@@ -114,6 +114,13 @@ pub struct HecLogsSinkConfig {
     #[configurable(derived)]
     pub tls: Option<TlsConfig>,
 
+    /// Client-side load balancing of the requests across Splunk HEC endpoints.
+    ///
+    /// Requires indexer acknowledgements to be disabled.
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_balance: Option<LoadBalanceConfig>,
+
     #[configurable(derived)]
     #[serde(default)]
     pub acknowledgements: HecClientAcknowledgementsConfig,
@@ -173,6 +180,7 @@ impl GenerateConfig for HecLogsSinkConfig {
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
             tls: None,
+            load_balance: None,
             acknowledgements: Default::default(),
             timestamp_nanos_key: None,
             timestamp_key: None,
@@ -191,7 +199,10 @@ impl SinkConfig for HecLogsSinkConfig {
             return Err("`auto_extract_timestamp` cannot be set for the `raw` endpoint.".into());
         }
 
-        let client = create_client(self.tls.as_ref(), cx.proxy())?;
+        let load_balancer =
+            build_load_balancer(self.load_balance.as_ref(), &self.acknowledgements)?;
+        let client =
+            create_client(self.tls.as_ref(), cx.proxy())?.with_load_balancer(load_balancer);
         let healthcheck = build_healthcheck(
             self.endpoint.clone(),
             self.default_token.inner().to_owned(),
@@ -128,6 +128,7 @@ async fn config(
         batch,
         request: TowerRequestConfig::default(),
         tls: None,
+        load_balance: None,
         acknowledgements: Default::default(),
         timestamp_nanos_key: None,
         timestamp_key: None,
@@ -232,6 +232,7 @@ async fn splunk_passthrough_token() {
         batch: Default::default(),
         request: Default::default(),
         tls: None,
+        load_balance: None,
         acknowledgements: Default::default(),
         timestamp_nanos_key: None,
         timestamp_key: None,
@@ -14,12 +14,14 @@ use crate::{
     sinks::{
         splunk_hec::common::{
             acknowledgements::HecClientAcknowledgementsConfig,
-            build_healthcheck, build_http_batch_service, config_host_key, create_client,
+            build_healthcheck, build_http_batch_service, build_load_balancer, config_host_key,
+            create_client,
             service::{HecService, HttpRequestBuilder},
             EndpointTarget, SplunkHecDefaultBatchSettings,
         },
         util::{
-            http::HttpRetryLogic, BatchConfig, Compression, ServiceBuilderExt, TowerRequestConfig,
+            http::HttpRetryLogic, load_balance::LoadBalanceConfig, BatchConfig, Compression,
+            ServiceBuilderExt, TowerRequestConfig,
         },
         Healthcheck,
     },
@@ -116,6 +118,13 @@ pub struct HecMetricsSinkConfig {
     #[configurable(derived)]
     pub tls: Option<TlsConfig>,
 
+    /// Client-side load balancing of the requests across Splunk HEC endpoints.
+    ///
+    /// Requires indexer acknowledgements to be disabled.
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load_balance: Option<LoadBalanceConfig>,
+
     #[configurable(derived)]
     #[serde(default)]
     pub acknowledgements: HecClientAcknowledgementsConfig,
@@ -135,6 +144,7 @@ impl GenerateConfig for HecMetricsSinkConfig {
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
             tls: None,
+            load_balance: None,
             acknowledgements: Default::default(),
         })
         .unwrap()
@@ -145,7 +155,10 @@ impl GenerateConfig for HecMetricsSinkConfig {
 #[typetag::serde(name = "splunk_hec_metrics")]
 impl SinkConfig for HecMetricsSinkConfig {
     async fn build(&self, cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
-        let client = create_client(self.tls.as_ref(), cx.proxy())?;
+        let load_balancer =
+            build_load_balancer(self.load_balance.as_ref(), &self.acknowledgements)?;
+        let client =
+            create_client(self.tls.as_ref(), cx.proxy())?.with_load_balancer(load_balancer);
         let healthcheck = build_healthcheck(
             self.endpoint.clone(),
             self.default_token.inner().to_owned(),
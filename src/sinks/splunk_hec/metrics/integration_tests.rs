@@ -45,6 +45,7 @@ async fn config() -> HecMetricsSinkConfig {
         batch,
         request: TowerRequestConfig::default(),
         tls: None,
+        load_balance: None,
         acknowledgements: Default::default(),
     }
 }
@@ -329,6 +329,7 @@ async fn splunk_passthrough_token() {
         batch: Default::default(),
         request: Default::default(),
         tls: None,
+        load_balance: None,
         acknowledgements: Default::default(),
         default_namespace: None,
     };
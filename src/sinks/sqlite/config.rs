@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use futures::FutureExt;
+use snafu::Snafu;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    Pool, Sqlite,
+};
+use tower::ServiceBuilder;
+use vector_lib::{
+    config::AcknowledgementsConfig,
+    configurable::{component::GenerateConfig, configurable_component},
+    sink::VectorSink,
+};
+
+use super::dialect::{SqliteDialect, MAX_PARAMETERS};
+use crate::{
+    config::{Input, SinkConfig, SinkContext},
+    sinks::{
+        util::{
+            sql::{
+                values::ValuesInsert, ColumnConfig, OnConflictConfig, RowBuilder, SqlRetryLogic,
+                SqlService, SqlSink,
+            },
+            BatchConfig, RealtimeSizeBasedDefaultBatchSettings, ServiceBuilderExt,
+            TowerRequestConfig,
+        },
+        Healthcheck,
+    },
+    template::Template,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("`columns` must not be empty"))]
+    NoColumns,
+
+    #[snafu(display("`on_conflict.target` must not be empty when updating conflicting rows"))]
+    UpdateWithoutTarget,
+}
+
+const fn default_pool_size() -> u32 {
+    1
+}
+
+/// Configuration for the `sqlite` sink.
+#[configurable_component(sink("sqlite", "Deliver log data to an SQLite database file."))]
+#[derive(Clone, Default, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SqliteConfig {
+    /// The path of the database file.
+    ///
+    /// The file is created if it does not exist, and is opened in write-ahead logging mode so that
+    /// other processes can query it while events are written.
+    #[configurable(metadata(docs::examples = "/var/lib/vector/events.db"))]
+    pub path: PathBuf,
+
+    /// The table that data is inserted into. The table must exist.
+    ///
+    /// The name is quoted. It can be qualified by a schema, such as `main.logs`.
+    ///
+    /// The table can be templated. Rendered table names must be identifiers, optionally qualified
+    /// by a schema, and events rendering any other table name are dropped.
+    #[configurable(metadata(docs::examples = "logs"))]
+    pub table: Template,
+
+    /// The columns that are populated from each event.
+    pub columns: Vec<ColumnConfig>,
+
+    /// How rows conflicting with existing rows are handled, using an `ON CONFLICT` clause.
+    #[serde(default, skip_serializing_if = "crate::serde::is_default")]
+    pub on_conflict: OnConflictConfig,
+
+    /// The number of connections to the database.
+    ///
+    /// SQLite allows a single writer at a time, so more connections rarely increase throughput.
+    #[serde(default = "default_pool_size")]
+    pub pool_size: u32,
+
+    /// Event batching behavior.
+    ///
+    /// Each batch is inserted within a single transaction. If it fails because of invalid data,
+    /// such as a single event triggering a unique constraint violation, the events of the batch are
+    /// inserted one by one within a transaction, and only the events the database rejects are dropped.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub batch: BatchConfig<RealtimeSizeBasedDefaultBatchSettings>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub request: TowerRequestConfig,
+
+    #[configurable(derived)]
+    #[serde(
+        default,
+        deserialize_with = "crate::serde::bool_or_struct",
+        skip_serializing_if = "crate::serde::is_default"
+    )]
+    pub acknowledgements: AcknowledgementsConfig,
+}
+
+impl GenerateConfig for SqliteConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"path = "/var/lib/vector/events.db"
+            table = "logs"
+            columns = [{ name = "message" }]
+        "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sqlite")]
+impl SinkConfig for SqliteConfig {
+    async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        let dialect = Arc::new(SqliteDialect::new(self.statements()?));
+
+        let options = SqliteConnectOptions::new()
+            .filename(&self.path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        let connection_pool = SqlitePoolOptions::new()
+            .max_connections(self.pool_size)
+            .connect_with(options)
+            .await?;
+
+        let healthcheck = healthcheck(connection_pool.clone()).boxed();
+
+        let batch_settings = self.batch.into_batcher_settings()?;
+        let request_settings = self.request.into_settings();
+
+        let service = SqlService::new(
+            connection_pool,
+            Arc::clone(&dialect),
+            Arc::new(RowBuilder::new(&self.columns)),
+            self.path.display().to_string(),
+        );
+        let service = ServiceBuilder::new()
+            .settings(request_settings, SqlRetryLogic::new(dialect))
+            .service(service);
+
+        let sink = SqlSink::new(service, self.table.clone(), batch_settings);
+
+        Ok((VectorSink::from_event_streamsink(sink), healthcheck))
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &self.acknowledgements
+    }
+}
+
+impl SqliteConfig {
+    fn statements(&self) -> Result<ValuesInsert, BuildError> {
+        if self.columns.is_empty() {
+            return Err(BuildError::NoColumns);
+        }
+
+        let insert = ValuesInsert::new(&self.columns, '"', MAX_PARAMETERS);
+        let suffix = match &self.on_conflict {
+            OnConflictConfig::Error => return Ok(insert),
+            OnConflictConfig::Ignore { target } if target.is_empty() => {
+                " ON CONFLICT DO NOTHING".to_owned()
+            }
+            OnConflictConfig::Ignore { target } => format!(
+                " ON CONFLICT ({}) DO NOTHING",
+                insert.quote_identifiers(target)
+            ),
+            OnConflictConfig::Update { target, .. } if target.is_empty() => {
+                return Err(BuildError::UpdateWithoutTarget)
+            }
+            OnConflictConfig::Update { target, columns } => {
+                let assignments = columns
+                    .iter()
+                    .map(|column| {
+                        let column = insert.quote_identifier(column);
+                        format!("{column} = excluded.{column}")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    " ON CONFLICT ({}) DO UPDATE SET {assignments}",
+                    insert.quote_identifiers(target)
+                )
+            }
+        };
+        Ok(insert.with_suffix(suffix))
+    }
+}
+
+async fn healthcheck(connection_pool: Pool<Sqlite>) -> crate::Result<()> {
+    sqlx::query("SELECT 1").execute(&connection_pool).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SqliteConfig>();
+    }
+
+    #[test]
+    fn conflict_clauses() {
+        let mut cfg = toml::from_str::<SqliteConfig>(
+            r#"
+            path = "/tmp/events.db"
+            table = "logs"
+            columns = [{ name = "id" }, { name = "message" }]
+            on_conflict.action = "ignore"
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            cfg.statements().unwrap().statement("logs", 1),
+            "INSERT INTO \"logs\" (\"id\", \"message\") VALUES (?, ?) ON CONFLICT DO NOTHING"
+        );
+
+        cfg.on_conflict = OnConflictConfig::Update {
+            target: Vec::new(),
+            columns: vec!["message".to_owned()],
+        };
+        assert!(matches!(
+            cfg.statements(),
+            Err(BuildError::UpdateWithoutTarget)
+        ));
+
+        cfg.on_conflict = OnConflictConfig::Update {
+            target: vec!["id".to_owned()],
+            columns: vec!["message".to_owned()],
+        };
+        assert_eq!(
+            cfg.statements().unwrap().statement("logs", 1),
+            "INSERT INTO \"logs\" (\"id\", \"message\") VALUES (?, ?) \
+             ON CONFLICT (\"id\") DO UPDATE SET \"message\" = excluded.\"message\""
+        );
+
+        cfg.columns.clear();
+        assert!(matches!(cfg.statements(), Err(BuildError::NoColumns)));
+    }
+}
@@ -0,0 +1,94 @@
+use serde_json::Value as JsonValue;
+use sqlx::{Pool, Sqlite, SqliteConnection};
+
+use crate::sinks::util::sql::{
+    database_error_code,
+    values::{SqlValue, ValuesInsert},
+    Dialect,
+};
+
+/// The maximum number of parameters of a prepared statement.
+pub const MAX_PARAMETERS: usize = 32766;
+
+/// Writes batches to SQLite with multi-row `INSERT ... VALUES` statements.
+#[derive(Debug)]
+pub struct SqliteDialect {
+    insert: ValuesInsert,
+}
+
+impl SqliteDialect {
+    pub const fn new(insert: ValuesInsert) -> Self {
+        Self { insert }
+    }
+
+    async fn insert(
+        &self,
+        connection: &mut SqliteConnection,
+        table: &str,
+        rows: &[JsonValue],
+    ) -> Result<(), sqlx::Error> {
+        for chunk in self.insert.chunks(rows) {
+            let statement = self.insert.statement(table, chunk.len());
+            let mut query = sqlx::query(&statement);
+            for value in self.insert.values(chunk) {
+                query = match value {
+                    SqlValue::Null => query.bind(None::<String>),
+                    SqlValue::Bool(value) => query.bind(value),
+                    SqlValue::Integer(value) => query.bind(value),
+                    SqlValue::Float(value) => query.bind(value),
+                    SqlValue::Text(value) => query.bind(value),
+                };
+            }
+            query.execute(&mut *connection).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the primary result code of a database error, dropping the extended part.
+fn primary_result_code(error: &sqlx::Error) -> Option<i32> {
+    database_error_code(error)
+        .and_then(|code| code.parse::<i32>().ok())
+        .map(|code| code & 0xff)
+}
+
+#[async_trait::async_trait]
+impl Dialect for SqliteDialect {
+    type Database = Sqlite;
+
+    const PROTOCOL: &'static str = "sqlite";
+
+    async fn write_batch(
+        &self,
+        pool: &Pool<Sqlite>,
+        table: &str,
+        rows: &[JsonValue],
+    ) -> Result<(), sqlx::Error> {
+        // Batches split into several statements are written atomically, so that they can be
+        // retried, and a single transaction is much faster than one per statement.
+        let mut transaction = pool.begin().await?;
+        self.insert(&mut transaction, table, rows).await?;
+        transaction.commit().await
+    }
+
+    async fn insert_row(
+        &self,
+        connection: &mut SqliteConnection,
+        table: &str,
+        row: &JsonValue,
+    ) -> Result<(), sqlx::Error> {
+        self.insert(connection, table, std::slice::from_ref(row))
+            .await
+    }
+
+    fn is_retriable_error(&self, error: &sqlx::Error) -> bool {
+        // The database being locked by another connection or process.
+        matches!(error, sqlx::Error::PoolTimedOut)
+            || matches!(primary_result_code(error), Some(5 | 6))
+    }
+
+    fn is_data_error(&self, error: &sqlx::Error) -> bool {
+        // Constraint violations, data type mismatches and values too large.
+        matches!(primary_result_code(error), Some(18 | 19 | 20))
+    }
+}
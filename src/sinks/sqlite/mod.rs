@@ -0,0 +1,6 @@
+mod config;
+mod dialect;
+#[cfg(test)]
+mod tests;
+
+pub use self::config::SqliteConfig;
@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use futures::stream;
+use sqlx::{Connection, SqliteConnection};
+use vector_lib::event::{BatchNotifier, BatchStatus, Event, LogEvent};
+
+use super::SqliteConfig;
+use crate::{
+    config::{SinkConfig, SinkContext},
+    sinks::util::test::load_sink,
+    test_util::{
+        components::{run_and_assert_sink_compliance, SINK_TAGS},
+        temp_dir, trace_init,
+    },
+};
+
+type Row = (i64, String, Option<i64>);
+
+fn create_event(id: i64, message: Option<&str>, status: i64) -> Event {
+    let mut event = LogEvent::default();
+    event.insert("id", id);
+    if let Some(message) = message {
+        event.insert("message", message);
+    }
+    event.insert("http.status", status);
+    event.into()
+}
+
+async fn prepare(options: &str) -> (SqliteConfig, SqliteConnection) {
+    let directory = temp_dir();
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("events.db");
+
+    let (config, _) = load_sink::<SqliteConfig>(&format!(
+        r#"
+            path = "{}"
+            table = "logs"
+            columns = [
+              {{ name = "id" }},
+              {{ name = "message" }},
+              {{ name = "status", path = "http.status" }},
+            ]
+            {options}
+        "#,
+        path.display()
+    ))
+    .unwrap();
+
+    let mut connection = connect(&path).await;
+    sqlx::query(
+        "CREATE TABLE logs (id INTEGER PRIMARY KEY, message TEXT NOT NULL, status INTEGER)",
+    )
+    .execute(&mut connection)
+    .await
+    .unwrap();
+
+    (config, connection)
+}
+
+async fn connect(path: &Path) -> SqliteConnection {
+    SqliteConnection::connect(&format!("sqlite://{}?mode=rwc", path.display()))
+        .await
+        .unwrap()
+}
+
+async fn select_rows(connection: &mut SqliteConnection) -> Vec<Row> {
+    sqlx::query_as("SELECT id, message, status FROM logs ORDER BY id")
+        .fetch_all(connection)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn inserts_events() {
+    trace_init();
+
+    let (config, mut connection) = prepare("").await;
+    let (sink, healthcheck) = config.build(SinkContext::default()).await.unwrap();
+    healthcheck.await.unwrap();
+
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    let events = (1..=3)
+        .map(|id| create_event(id, Some("hello"), 200).with_batch_notifier(&batch))
+        .collect::<Vec<_>>();
+    drop(batch);
+
+    run_and_assert_sink_compliance(sink, stream::iter(events), &SINK_TAGS).await;
+    assert_eq!(receiver.await, BatchStatus::Delivered);
+
+    assert_eq!(
+        select_rows(&mut connection).await,
+        vec![
+            (1, "hello".to_owned(), Some(200)),
+            (2, "hello".to_owned(), Some(200)),
+            (3, "hello".to_owned(), Some(200)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn skips_rejected_rows() {
+    trace_init();
+
+    let (config, mut connection) = prepare("").await;
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+
+    let (batch, receiver) = BatchNotifier::new_with_receiver();
+    let events = vec![
+        create_event(1, Some("first"), 200),
+        // Violates the `NOT NULL` constraint of `message`.
+        create_event(2, None, 500),
+        create_event(3, Some("third"), 404),
+    ]
+    .into_iter()
+    .map(|event| event.with_batch_notifier(&batch))
+    .collect::<Vec<_>>();
+    drop(batch);
+
+    sink.run_events(events).await.unwrap();
+    assert_eq!(receiver.await, BatchStatus::Delivered);
+
+    assert_eq!(
+        select_rows(&mut connection).await,
+        vec![
+            (1, "first".to_owned(), Some(200)),
+            (3, "third".to_owned(), Some(404)),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn updates_conflicting_rows() {
+    trace_init();
+
+    let (config, mut connection) = prepare(
+        r#"
+            on_conflict.action = "update"
+            on_conflict.target = ["id"]
+            on_conflict.columns = ["message"]
+        "#,
+    )
+    .await;
+    let (sink, _) = config.build(SinkContext::default()).await.unwrap();
+
+    sink.run_events(vec![
+        create_event(1, Some("first"), 200),
+        create_event(1, Some("updated"), 500),
+    ])
+    .await
+    .unwrap();
+
+    assert_eq!(
+        select_rows(&mut connection).await,
+        vec![(1, "updated".to_owned(), Some(200))]
+    );
+}
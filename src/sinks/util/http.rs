@@ -23,8 +23,8 @@ use std::{
 use tower::{Service, ServiceBuilder};
 use tower_http::decompression::DecompressionLayer;
 use vector_lib::{
-    configurable::configurable_component, stream::batcher::limiter::ItemBatchSize, ByteSizeOf,
-    EstimatedJsonEncodedSizeOf,
+    configurable::configurable_component, finalization::Rejection,
+    stream::batcher::limiter::ItemBatchSize, ByteSizeOf, EstimatedJsonEncodedSizeOf,
 };
 
 use super::{
@@ -744,6 +744,15 @@ impl DriverResponse for HttpResponse {
     fn bytes_sent(&self) -> Option<usize> {
         Some(self.raw_byte_size)
     }
+
+    fn rejection(&self) -> Option<Rejection> {
+        let status = self.http_response.status();
+        Some(Rejection {
+            status: Some(status.as_u16().to_string()),
+            response: Some(String::from_utf8_lossy(self.http_response.body()).into_owned()),
+            ..Rejection::new(format!("Request was rejected with status {status}."))
+        })
+    }
 }
 
 /// Creates a `RetryLogic` for use with `HttpResponse`.
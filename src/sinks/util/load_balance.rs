@@ -0,0 +1,794 @@
+//! Client-side load balancing of HTTP requests across multiple endpoints.
+//!
+//! A [`LoadBalancer`] is attached to an [`HttpClient`] with [`HttpClient::with_load_balancer`], so
+//! that each request sent through the client is routed to one of the endpoints, by rewriting the
+//! scheme and authority of its URI. Endpoints failing repeatedly are considered unhealthy, and
+//! are skipped until a backoff elapses.
+//!
+//! Sinks building a different request for each endpoint distribute them with a
+//! [`BalancedService`] instead, which shares the selection and health of the endpoints.
+//!
+//! [`HttpClient`]: crate::http::HttpClient
+//! [`HttpClient::with_load_balancer`]: crate::http::HttpClient::with_load_balancer
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    task::{Context, Poll},
+};
+#[cfg(feature = "sinks-utils-srv-discovery")]
+use std::{num::NonZeroU64, sync::Weak, time::Duration};
+
+use futures::future::BoxFuture;
+use http::{
+    header::{HeaderName, HOST},
+    uri::{Authority, PathAndQuery, Scheme},
+    HeaderValue, Request, Uri,
+};
+#[cfg(feature = "sinks-utils-srv-discovery")]
+use tokio::time::interval;
+use tokio::time::Instant;
+use tower::Service;
+use vector_lib::{configurable::configurable_component, emit};
+
+use super::{
+    retries::ExponentialBackoff,
+    service::{HealthConfig, HealthLogic},
+    uri::UriSerde,
+};
+#[cfg(feature = "sinks-utils-srv-discovery")]
+use crate::dns::{SrvResolver, SrvTarget};
+use crate::internal_events::EndpointsActive;
+
+const UNHEALTHY_AMOUNT_OF_ERRORS: usize = 5;
+
+/// The strategy used to pick the endpoint of each request.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadBalanceStrategy {
+    /// Requests are sent to each endpoint in turn.
+    RoundRobin,
+
+    /// Requests are sent to the endpoint with the fewest requests in flight.
+    #[default]
+    LeastOutstanding,
+
+    /// Requests with the same partition key are sent to the same endpoint.
+    ///
+    /// The partition key is made of the path and query of the request, along with the value of the
+    /// `hash_header` header, if set. Only the requests of an endpoint are moved to other endpoints
+    /// when it becomes unhealthy.
+    ConsistentHash,
+}
+
+/// Discovery of endpoints through DNS SRV records.
+#[cfg(feature = "sinks-utils-srv-discovery")]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SrvDiscoveryConfig {
+    /// The name of the SRV records.
+    ///
+    /// The targets with the lowest priority value are used as endpoints, and their weights are
+    /// ignored.
+    #[configurable(metadata(docs::examples = "_http._tcp.ingest.example.com"))]
+    pub name: String,
+
+    /// The scheme of the discovered endpoints.
+    #[serde(default = "default_srv_scheme")]
+    #[configurable(metadata(docs::examples = "https"))]
+    pub scheme: String,
+
+    /// The interval between lookups of the SRV records.
+    #[serde(default = "default_srv_refresh_interval_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Refresh Interval"))]
+    pub refresh_interval_secs: NonZeroU64,
+}
+
+#[cfg(feature = "sinks-utils-srv-discovery")]
+fn default_srv_scheme() -> String {
+    "http".to_owned()
+}
+
+#[cfg(feature = "sinks-utils-srv-discovery")]
+const fn default_srv_refresh_interval_secs() -> NonZeroU64 {
+    NonZeroU64::new(30).unwrap()
+}
+
+/// Client-side load balancing configuration.
+///
+/// Requests are distributed across the endpoints, of which only the scheme and authority are
+/// used: the path, query, headers and credentials of the requests are left untouched. As such,
+/// load balancing can't be used along with request signing, such as AWS authentication, which
+/// covers the host the request is sent to.
+#[configurable_component]
+#[derive(Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LoadBalanceConfig {
+    /// The endpoints to distribute the requests across.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "http://10.24.32.122:9000"))]
+    #[configurable(metadata(docs::examples = "https://ingest-2.example.com"))]
+    pub endpoints: Vec<UriSerde>,
+
+    /// Discovery of additional endpoints through DNS SRV records.
+    ///
+    /// Until endpoints are discovered, requests are sent to the static `endpoints`, or to the
+    /// endpoint of the sink if there are none.
+    #[cfg(feature = "sinks-utils-srv-discovery")]
+    #[configurable(derived)]
+    #[serde(default)]
+    pub srv: Option<SrvDiscoveryConfig>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub strategy: LoadBalanceStrategy,
+
+    /// The header whose value is part of the partition key of the `consistent_hash` strategy.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "X-Tenant-Id"))]
+    pub hash_header: Option<String>,
+
+    /// Options for determining the health of the endpoints.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub endpoint_health: Option<HealthConfig>,
+}
+
+impl LoadBalanceConfig {
+    pub fn build(&self) -> crate::Result<LoadBalancer> {
+        #[cfg(feature = "sinks-utils-srv-discovery")]
+        let discovery = self.srv.is_some();
+        #[cfg(not(feature = "sinks-utils-srv-discovery"))]
+        let discovery = false;
+        if self.endpoints.is_empty() && !discovery {
+            return Err("Load balancing requires `endpoints` or `srv` to be set.".into());
+        }
+        let hash_header = self
+            .hash_header
+            .as_deref()
+            .map(HeaderName::try_from)
+            .transpose()?;
+        let health = self
+            .endpoint_health
+            .clone()
+            .unwrap_or_else(HealthConfig::unset);
+
+        let endpoints = self
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                let uri = endpoint.with_default_parts().uri;
+                let parts = uri.into_parts();
+                Arc::new(Endpoint::new(
+                    parts.scheme.expect("default scheme"),
+                    parts.authority.expect("default authority"),
+                    &health,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        let inner = Arc::new(Inner {
+            strategy: self.strategy,
+            hash_header,
+            endpoints: RwLock::new(endpoints.clone()),
+            next: AtomicUsize::new(0),
+        });
+
+        #[cfg(feature = "sinks-utils-srv-discovery")]
+        if let Some(srv) = &self.srv {
+            let scheme = srv.scheme.parse::<Scheme>()?;
+            tokio::spawn(discover(
+                srv.clone(),
+                SrvResolver::new()?,
+                scheme,
+                endpoints,
+                health,
+                Arc::downgrade(&inner),
+            ));
+        }
+
+        Ok(LoadBalancer(inner))
+    }
+}
+
+/// Distributes requests across endpoints.
+#[derive(Clone, Debug)]
+pub struct LoadBalancer(Arc<Inner>);
+
+#[derive(Debug)]
+struct Inner {
+    strategy: LoadBalanceStrategy,
+    hash_header: Option<HeaderName>,
+    endpoints: RwLock<Vec<Arc<Endpoint>>>,
+    next: AtomicUsize,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    scheme: Scheme,
+    authority: Authority,
+    in_flight: AtomicUsize,
+    health: Mutex<EndpointHealth>,
+}
+
+#[derive(Debug)]
+struct EndpointHealth {
+    consecutive_errors: usize,
+    backoff: ExponentialBackoff,
+    unhealthy_until: Option<Instant>,
+}
+
+impl Endpoint {
+    fn new(scheme: Scheme, authority: Authority, health: &HealthConfig) -> Self {
+        Self {
+            scheme,
+            authority,
+            in_flight: AtomicUsize::new(0),
+            health: Mutex::new(EndpointHealth {
+                consecutive_errors: 0,
+                backoff: health.backoff(),
+                unhealthy_until: None,
+            }),
+        }
+    }
+
+    fn is_available(&self, now: Instant) -> bool {
+        let health = self.health.lock().expect("poisoned lock");
+        health.unhealthy_until.is_none_or(|until| until <= now)
+    }
+
+    /// Records the outcome of a request, returning whether the health of the endpoint changed.
+    fn record(&self, healthy: bool) -> bool {
+        let mut health = self.health.lock().expect("poisoned lock");
+        if healthy {
+            health.consecutive_errors = 0;
+            health.backoff.reset();
+            let recovered = health.unhealthy_until.take().is_some();
+            if recovered {
+                info!(message = "Endpoint is healthy.", endpoint = %self.authority);
+            }
+            recovered
+        } else {
+            health.consecutive_errors += 1;
+            if health.consecutive_errors < UNHEALTHY_AMOUNT_OF_ERRORS {
+                return false;
+            }
+            // Endpoints on probation are given a longer backoff each time they fail again.
+            let delay = health.backoff.next().unwrap_or_default();
+            let failed = health
+                .unhealthy_until
+                .replace(Instant::now() + delay)
+                .is_none();
+            if failed {
+                warn!(message = "Endpoint is unhealthy.", endpoint = %self.authority);
+            }
+            failed
+        }
+    }
+}
+
+impl Inner {
+    fn select(&self, key: Option<u64>) -> Option<Arc<Endpoint>> {
+        self.candidates(key).into_iter().next()
+    }
+
+    /// Returns the endpoints to send a request to, in order of preference.
+    fn candidates(&self, key: Option<u64>) -> Vec<Arc<Endpoint>> {
+        let endpoints = self.endpoints.read().expect("poisoned lock");
+        let now = Instant::now();
+        let mut candidates = endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_available(now))
+            .cloned()
+            .collect::<Vec<_>>();
+        // Requests are sent anyway when all the endpoints are unhealthy.
+        if candidates.is_empty() {
+            candidates = endpoints.clone();
+        }
+        if candidates.is_empty() {
+            return candidates;
+        }
+
+        match (self.strategy, key) {
+            (LoadBalanceStrategy::ConsistentHash, Some(key)) => {
+                candidates.sort_by_cached_key(|endpoint| {
+                    let mut hasher = DefaultHasher::new();
+                    key.hash(&mut hasher);
+                    endpoint.authority.as_str().hash(&mut hasher);
+                    std::cmp::Reverse(hasher.finish())
+                });
+            }
+            (LoadBalanceStrategy::LeastOutstanding, _) => {
+                // Ties are broken in turn, so that idle endpoints all get requests.
+                let start = self.next.fetch_add(1, Ordering::Relaxed) % candidates.len();
+                candidates.rotate_left(start);
+                candidates.sort_by_key(|endpoint| endpoint.in_flight.load(Ordering::Relaxed));
+            }
+            _ => {
+                let start = self.next.fetch_add(1, Ordering::Relaxed) % candidates.len();
+                candidates.rotate_left(start);
+            }
+        }
+        candidates
+    }
+
+    fn partition_key<B>(&self, request: &Request<B>) -> Option<u64> {
+        if self.strategy != LoadBalanceStrategy::ConsistentHash {
+            return None;
+        }
+        let mut hasher = DefaultHasher::new();
+        request
+            .uri()
+            .path_and_query()
+            .map(PathAndQuery::as_str)
+            .hash(&mut hasher);
+        if let Some(name) = &self.hash_header {
+            request
+                .headers()
+                .get(name)
+                .map(HeaderValue::as_bytes)
+                .hash(&mut hasher);
+        }
+        Some(hasher.finish())
+    }
+
+    fn emit_active_endpoints(&self) {
+        let endpoints = self.endpoints.read().expect("poisoned lock");
+        let now = Instant::now();
+        emit!(EndpointsActive {
+            count: endpoints
+                .iter()
+                .filter(|endpoint| endpoint.is_available(now))
+                .count(),
+        });
+    }
+}
+
+impl LoadBalancer {
+    /// Routes a request to one of the endpoints, returning the endpoint it was routed to.
+    ///
+    /// The request is left untouched if there are no endpoints yet.
+    pub fn route<B>(&self, request: &mut Request<B>) -> Option<RoutedEndpoint> {
+        let endpoint = self.0.select(self.0.partition_key(request))?;
+
+        let mut parts = request.uri().clone().into_parts();
+        parts.scheme = Some(endpoint.scheme.clone());
+        parts.authority = Some(endpoint.authority.clone());
+        if parts.path_and_query.is_none() {
+            parts.path_and_query = Some(PathAndQuery::from_static("/"));
+        }
+        *request.uri_mut() = Uri::from_parts(parts).expect("valid URI parts");
+        if let Some(host) = request.headers_mut().get_mut(HOST) {
+            *host = HeaderValue::from_str(endpoint.authority.as_str())
+                .expect("valid authority header value");
+        }
+
+        Some(RoutedEndpoint::new(&self.0, endpoint))
+    }
+}
+
+/// The endpoint a request was routed to, counted as in flight until dropped.
+#[derive(Debug)]
+pub struct RoutedEndpoint {
+    balancer: Arc<Inner>,
+    endpoint: Arc<Endpoint>,
+}
+
+impl RoutedEndpoint {
+    fn new(balancer: &Arc<Inner>, endpoint: Arc<Endpoint>) -> Self {
+        endpoint.in_flight.fetch_add(1, Ordering::Relaxed);
+        Self {
+            balancer: Arc::clone(balancer),
+            endpoint,
+        }
+    }
+
+    /// Records whether the request was handled by the endpoint, rather than failing with a
+    /// connection error or a server error.
+    pub fn record(&self, healthy: bool) {
+        if self.endpoint.record(healthy) {
+            self.balancer.emit_active_endpoints();
+        }
+    }
+}
+
+impl Drop for RoutedEndpoint {
+    fn drop(&mut self) {
+        self.endpoint.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Distributes requests across the services of several endpoints, sending each request to the
+/// ready service of the endpoint with the fewest requests in flight.
+///
+/// Unlike a [`LoadBalancer`], which only rewrites the URI of the requests, this lets sinks build a
+/// different request for each endpoint, such as a signed request with the path and credentials of
+/// the endpoint. The health of the endpoints is determined by a [`HealthLogic`].
+pub struct BalancedService<S, L> {
+    balancer: Arc<Inner>,
+    services: Vec<(Arc<Endpoint>, S)>,
+    logic: L,
+    ready: Option<usize>,
+}
+
+impl<S, L> BalancedService<S, L> {
+    /// Creates a service distributing requests across the given `(endpoint, service)` pairs.
+    pub fn new(services: Vec<(String, S)>, health: &HealthConfig, logic: L) -> crate::Result<Self> {
+        let services = services
+            .into_iter()
+            .map(|(endpoint, service)| {
+                let uri = endpoint.parse::<UriSerde>()?.with_default_parts().uri;
+                let parts = uri.into_parts();
+                let endpoint = Endpoint::new(
+                    parts.scheme.expect("default scheme"),
+                    parts.authority.expect("default authority"),
+                    health,
+                );
+                Ok::<_, crate::Error>((Arc::new(endpoint), service))
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        let balancer = Arc::new(Inner {
+            strategy: LoadBalanceStrategy::LeastOutstanding,
+            hash_header: None,
+            endpoints: RwLock::new(
+                services
+                    .iter()
+                    .map(|(endpoint, _)| Arc::clone(endpoint))
+                    .collect(),
+            ),
+            next: AtomicUsize::new(0),
+        });
+        balancer.emit_active_endpoints();
+
+        Ok(Self {
+            balancer,
+            services,
+            logic,
+            ready: None,
+        })
+    }
+}
+
+impl<S, L, Req> Service<Req> for BalancedService<S, L>
+where
+    S: Service<Req, Error = crate::Error>,
+    S::Response: Send + 'static,
+    S::Future: Send + 'static,
+    L: HealthLogic<Response = S::Response, Error = crate::Error>,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = BoxFuture<'static, Result<S::Response, crate::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        if self.ready.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        // The services that aren't ready, such as the ones with an open circuit breaker, are
+        // skipped in favor of the next endpoints.
+        for endpoint in self.balancer.candidates(None) {
+            let Some(index) = self
+                .services
+                .iter()
+                .position(|(candidate, _)| Arc::ptr_eq(candidate, &endpoint))
+            else {
+                continue;
+            };
+            if self.services[index].1.poll_ready(cx)?.is_ready() {
+                self.ready = Some(index);
+                return Poll::Ready(Ok(()));
+            }
+        }
+        Poll::Pending
+    }
+
+    fn call(&mut self, request: Req) -> Self::Future {
+        let index = self
+            .ready
+            .take()
+            .expect("poll_ready must be called before call");
+        let (endpoint, service) = &mut self.services[index];
+        let routed = RoutedEndpoint::new(&self.balancer, Arc::clone(endpoint));
+        let logic = self.logic.clone();
+        let future = service.call(request);
+
+        Box::pin(async move {
+            let result = future.await;
+            if let Some(healthy) = logic.is_healthy(&result) {
+                routed.record(healthy);
+            }
+            result
+        })
+    }
+}
+
+/// Periodically replaces the discovered endpoints with the targets of the SRV records, keeping the
+/// health of the endpoints that were already known.
+#[cfg(feature = "sinks-utils-srv-discovery")]
+async fn discover(
+    config: SrvDiscoveryConfig,
+    resolver: SrvResolver,
+    scheme: Scheme,
+    static_endpoints: Vec<Arc<Endpoint>>,
+    health: HealthConfig,
+    balancer: Weak<Inner>,
+) {
+    let mut interval = interval(Duration::from_secs(config.refresh_interval_secs.get()));
+    loop {
+        interval.tick().await;
+        let targets = resolver.lookup_srv(&config.name).await;
+        let Some(balancer) = balancer.upgrade() else {
+            break;
+        };
+        let targets = match targets {
+            Ok(targets) if !targets.is_empty() => targets,
+            Ok(_) => {
+                warn!(
+                    message = "No endpoints discovered; keeping the current endpoints.",
+                    name = %config.name,
+                    internal_log_rate_limit = true,
+                );
+                continue;
+            }
+            Err(error) => {
+                warn!(
+                    message = "Failed to discover endpoints; keeping the current endpoints.",
+                    name = %config.name,
+                    %error,
+                    internal_log_rate_limit = true,
+                );
+                continue;
+            }
+        };
+
+        let mut endpoints = balancer.endpoints.write().expect("poisoned lock");
+        let mut updated = static_endpoints.clone();
+        for authority in srv_authorities(&targets) {
+            if updated
+                .iter()
+                .any(|endpoint| endpoint.scheme == scheme && endpoint.authority == authority)
+            {
+                continue;
+            }
+            let endpoint = endpoints
+                .iter()
+                .find(|endpoint| endpoint.scheme == scheme && endpoint.authority == authority)
+                .cloned()
+                .unwrap_or_else(|| {
+                    debug!(message = "Discovered endpoint.", endpoint = %authority);
+                    Arc::new(Endpoint::new(scheme.clone(), authority, &health))
+                });
+            updated.push(endpoint);
+        }
+        *endpoints = updated;
+        drop(endpoints);
+        balancer.emit_active_endpoints();
+    }
+}
+
+/// Returns the authorities of the SRV targets with the lowest priority value.
+#[cfg(feature = "sinks-utils-srv-discovery")]
+fn srv_authorities(targets: &[SrvTarget]) -> Vec<Authority> {
+    let Some(priority) = targets.iter().map(|target| target.priority).min() else {
+        return Vec::new();
+    };
+    targets
+        .iter()
+        .filter(|target| target.priority == priority)
+        .filter_map(|target| {
+            match Authority::try_from(format!("{}:{}", target.target, target.port)) {
+                Ok(authority) => Some(authority),
+                Err(error) => {
+                    warn!(
+                        message = "Ignoring invalid SRV target.",
+                        target = %target.target,
+                        %error,
+                        internal_log_rate_limit = true,
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn balancer(strategy: LoadBalanceStrategy) -> LoadBalancer {
+        LoadBalanceConfig {
+            endpoints: vec![
+                "http://a:9000".parse().unwrap(),
+                "http://b:9000".parse().unwrap(),
+                "https://c".parse().unwrap(),
+            ],
+            strategy,
+            hash_header: Some("X-Tenant-Id".to_owned()),
+            ..Default::default()
+        }
+        .build()
+        .unwrap()
+    }
+
+    fn route(
+        balancer: &LoadBalancer,
+        path: &str,
+        tenant: Option<&str>,
+    ) -> (String, RoutedEndpoint) {
+        let mut builder = Request::post(format!("http://localhost{path}"));
+        if let Some(tenant) = tenant {
+            builder = builder.header("X-Tenant-Id", tenant);
+        }
+        let mut request = builder.body(()).unwrap();
+        let endpoint = balancer.route(&mut request).unwrap();
+        (request.uri().to_string(), endpoint)
+    }
+
+    #[tokio::test]
+    async fn round_robin_skips_unhealthy_endpoints() {
+        let balancer = balancer(LoadBalanceStrategy::RoundRobin);
+
+        let uris = (0..3)
+            .map(|_| route(&balancer, "/logs?x=1", None).0)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            uris,
+            [
+                "http://a:9000/logs?x=1",
+                "http://b:9000/logs?x=1",
+                "https://c/logs?x=1"
+            ]
+        );
+
+        let (_, endpoint) = route(&balancer, "/", None);
+        for _ in 0..UNHEALTHY_AMOUNT_OF_ERRORS {
+            endpoint.record(false);
+        }
+        let uris = (0..4)
+            .map(|_| route(&balancer, "/", None).0)
+            .collect::<Vec<_>>();
+        assert!(uris.iter().all(|uri| !uri.starts_with("http://a:9000")));
+    }
+
+    #[tokio::test]
+    async fn least_outstanding_prefers_idle_endpoints() {
+        let balancer = balancer(LoadBalanceStrategy::LeastOutstanding);
+
+        let (first, _first) = route(&balancer, "/", None);
+        let (second, _second) = route(&balancer, "/", None);
+        let (third, third_endpoint) = route(&balancer, "/", None);
+        let mut uris = vec![first, second, third.clone()];
+        uris.sort();
+        uris.dedup();
+        assert_eq!(uris.len(), 3);
+
+        // Only the endpoint whose request completed is idle.
+        drop(third_endpoint);
+        assert_eq!(route(&balancer, "/", None).0, third);
+    }
+
+    #[tokio::test]
+    async fn consistent_hash_is_stable() {
+        let balancer = balancer(LoadBalanceStrategy::ConsistentHash);
+
+        let (uri, _) = route(&balancer, "/logs", Some("tenant-1"));
+        for _ in 0..5 {
+            assert_eq!(route(&balancer, "/logs", Some("tenant-1")).0, uri);
+        }
+
+        let authorities = (0..32)
+            .map(|tenant| {
+                let (uri, _) = route(&balancer, "/logs", Some(&format!("tenant-{tenant}")));
+                uri.parse::<Uri>().unwrap().authority().unwrap().to_string()
+            })
+            .collect::<std::collections::HashSet<_>>();
+        assert!(authorities.len() > 1);
+    }
+
+    #[test]
+    fn endpoint_health_counts_consecutive_errors() {
+        let endpoint = Endpoint::new(
+            Scheme::HTTP,
+            Authority::from_static("a:9000"),
+            &HealthConfig::unset(),
+        );
+        let now = Instant::now();
+
+        assert!(!endpoint.record(true));
+        for _ in 1..UNHEALTHY_AMOUNT_OF_ERRORS {
+            assert!(!endpoint.record(false));
+        }
+        assert!(endpoint.is_available(now));
+
+        // A success in between errors resets the count.
+        assert!(!endpoint.record(true));
+        for _ in 1..UNHEALTHY_AMOUNT_OF_ERRORS {
+            assert!(!endpoint.record(false));
+        }
+        assert!(endpoint.record(false));
+        assert!(!endpoint.is_available(Instant::now()));
+
+        // Errors on probation keep the endpoint unhealthy without changing its health.
+        assert!(!endpoint.record(false));
+        assert!(!endpoint.is_available(Instant::now()));
+
+        assert!(endpoint.record(true));
+        assert!(endpoint.is_available(Instant::now()));
+        assert!(!endpoint.record(false));
+    }
+
+    #[derive(Clone)]
+    struct OutputHealthLogic;
+
+    impl HealthLogic for OutputHealthLogic {
+        type Error = crate::Error;
+        type Response = bool;
+
+        fn is_healthy(&self, response: &Result<bool, crate::Error>) -> Option<bool> {
+            response.as_ref().ok().copied()
+        }
+    }
+
+    #[tokio::test]
+    async fn balanced_service_skips_unhealthy_endpoints() {
+        let service = |healthy: bool| {
+            tower::service_fn(move |()| async move { Ok::<_, crate::Error>(healthy) })
+        };
+        let mut balanced = BalancedService::new(
+            vec![
+                ("http://a:9000".to_owned(), service(false)),
+                ("http://b:9000".to_owned(), service(true)),
+            ],
+            &HealthConfig::unset(),
+            OutputHealthLogic,
+        )
+        .unwrap();
+
+        let mut outputs = Vec::new();
+        for _ in 0..2 * UNHEALTHY_AMOUNT_OF_ERRORS {
+            outputs.push(balanced.ready().await.unwrap().call(()).await.unwrap());
+        }
+        // Idle endpoints get requests in turn.
+        assert_eq!(
+            outputs.iter().filter(|healthy| !**healthy).count(),
+            UNHEALTHY_AMOUNT_OF_ERRORS
+        );
+
+        for _ in 0..4 {
+            assert!(balanced.ready().await.unwrap().call(()).await.unwrap());
+        }
+    }
+
+    #[cfg(feature = "sinks-utils-srv-discovery")]
+    #[test]
+    fn uses_lowest_priority_srv_targets() {
+        let target = |priority, target: &str| SrvTarget {
+            priority,
+            weight: 10,
+            port: 8080,
+            target: target.to_owned(),
+        };
+        let authorities = srv_authorities(&[
+            target(20, "backup.example.com"),
+            target(10, "a.example.com"),
+            target(10, "b.example.com"),
+        ]);
+        assert_eq!(
+            authorities,
+            [
+                Authority::from_static("a.example.com:8080"),
+                Authority::from_static("b.example.com:8080")
+            ]
+        );
+    }
+}
@@ -9,8 +9,10 @@ pub mod compressor;
 pub mod datagram;
 pub mod encoding;
 pub mod http;
+pub mod load_balance;
 pub mod metadata;
 pub mod normalizer;
+pub mod partial;
 pub mod partitioner;
 pub mod processed_event;
 pub mod request_builder;
@@ -19,6 +21,12 @@ pub mod service;
 pub mod sink;
 pub mod snappy;
 pub mod socket_bytes_sink;
+#[cfg(any(
+    feature = "sinks-mysql",
+    feature = "sinks-postgres",
+    feature = "sinks-sqlite"
+))]
+pub mod sql;
 pub mod statistic;
 pub mod tcp;
 #[cfg(any(test, feature = "test-utils"))]
@@ -47,8 +55,8 @@ pub use compressor::Compressor;
 pub use normalizer::Normalizer;
 pub use request_builder::{IncrementalRequestBuilder, RequestBuilder};
 pub use service::{
-    Concurrency, ServiceBuilderExt, TowerBatchedSink, TowerPartitionSink, TowerRequestConfig,
-    TowerRequestLayer, TowerRequestSettings,
+    Concurrency, ServiceBuilderExt, TowerBatchedSink, TowerPartialRequestLayer,
+    TowerPartitionSink, TowerRequestConfig, TowerRequestLayer, TowerRequestSettings,
 };
 pub use sink::{BatchSink, PartitionBatchSink, StreamSink};
 use snafu::Snafu;
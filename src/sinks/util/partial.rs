@@ -0,0 +1,317 @@
+//! Per-item handling of the partial failures of bulk requests.
+//!
+//! Bulk APIs report the outcome of each item of a request, so a request can be partially
+//! successful. A sink opts into per-item handling by reporting these outcomes from
+//! [`RetryLogic::item_outcomes`], by implementing [`PartialRequest`] for its requests, and by
+//! building its service with [`ServiceBuilderExt::partial_settings`]. Each item is then finalized
+//! with its own status, and only the items that failed with a retriable error are sent again after
+//! the usual backoff, so that the items already delivered are not duplicated.
+//!
+//! [`ServiceBuilderExt::partial_settings`]: super::ServiceBuilderExt::partial_settings
+
+use std::borrow::Cow;
+
+use tower::retry::Policy;
+use vector_lib::{
+    finalization::{EventFinalizers, EventStatus, Rejection},
+    internal_event::{ComponentEventsDropped, UNINTENTIONAL},
+    stream::DriverResponse,
+};
+
+use super::retries::{FibonacciRetryPolicy, RetryAction, RetryLogic, RetryPolicyFuture};
+use crate::Error;
+
+/// The outcome of an item of a bulk request.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ItemOutcome {
+    /// The item was delivered.
+    Delivered,
+
+    /// The item failed with a retriable error, and should be sent again with the given reason.
+    Retry(Cow<'static, str>),
+
+    /// The item was rejected, and must not be sent again.
+    Rejected(Rejection),
+}
+
+/// A bulk request whose items are finalized individually.
+///
+/// The finalizers of the items stay in the request until the retry policy finalizes them, so the
+/// `Finalizable` implementation of such a request must not hand them over to the driver.
+pub trait PartialRequest: Clone {
+    /// Keeps only the items for which `f` returns `true`, preserving their order.
+    ///
+    /// `f` is given the index of each item in the request, along with its finalizers.
+    fn retain_items(&mut self, f: impl FnMut(usize, &EventFinalizers) -> bool);
+
+    /// Accounts for the items delivered by an attempt, before they are removed from the request.
+    ///
+    /// `outcomes` holds the outcome of each item of the attempt. Responses only report the events
+    /// sent by their own attempt, so requests keep track here of the items delivered by the
+    /// earlier attempts, to report them as sent along with the last one.
+    fn items_delivered(&mut self, _outcomes: &[ItemOutcome]) {}
+}
+
+/// Finalizes all the remaining items of a request, leaving it empty.
+fn finalize_items<Req: PartialRequest>(
+    request: &mut Req,
+    status: EventStatus,
+    rejection: Option<&Rejection>,
+) {
+    request.retain_items(|_, finalizers| {
+        finalizers.update_status(status);
+        if let Some(rejection) = rejection {
+            finalizers.record_rejection(rejection);
+        }
+        false
+    });
+}
+
+/// A retry policy for bulk requests, retrying only the items that failed with a retriable error.
+///
+/// Requests failing or succeeding as a whole, and responses without item outcomes, are handled
+/// like `FibonacciRetryPolicy` does, with which the backoff is shared.
+#[derive(Clone, Debug)]
+pub struct PartialRetryPolicy<L> {
+    inner: FibonacciRetryPolicy<L>,
+}
+
+impl<L: RetryLogic> PartialRetryPolicy<L> {
+    pub const fn new(inner: FibonacciRetryPolicy<L>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<Req, Res, L> Policy<Req, Res, Error> for PartialRetryPolicy<L>
+where
+    Req: PartialRequest + 'static,
+    Res: DriverResponse,
+    L: RetryLogic<Response = Res>,
+{
+    type Future = RetryPolicyFuture;
+
+    fn retry(
+        &mut self,
+        request: &mut Req,
+        result: &mut Result<Res, Error>,
+    ) -> Option<Self::Future> {
+        let outcomes = match result {
+            Ok(response) => self.inner.logic().item_outcomes(response),
+            Err(_) => None,
+        };
+        let Some(outcomes) = outcomes else {
+            let retry = Policy::<Req, Res, Error>::retry(&mut self.inner, request, result);
+            if retry.is_none() {
+                let (status, rejection) = match result {
+                    Ok(response) => match self.inner.logic().should_retry_response(response) {
+                        RetryAction::Successful => (response.event_status(), response.rejection()),
+                        RetryAction::Retry(reason) | RetryAction::DontRetry(reason) => {
+                            (EventStatus::Rejected, Some(Rejection::new(reason)))
+                        }
+                    },
+                    Err(error) => (
+                        EventStatus::Rejected,
+                        Some(Rejection::new(error.to_string())),
+                    ),
+                };
+                finalize_items(request, status, rejection.as_ref());
+            }
+            return retry;
+        };
+
+        request.items_delivered(&outcomes);
+        let mut rejected = 0;
+        let mut retried = 0;
+        request.retain_items(|index, finalizers| match outcomes.get(index) {
+            Some(ItemOutcome::Retry(_)) => {
+                retried += 1;
+                true
+            }
+            Some(ItemOutcome::Rejected(rejection)) => {
+                rejected += 1;
+                finalizers.update_status(EventStatus::Rejected);
+                finalizers.record_rejection(rejection);
+                false
+            }
+            // Items without an outcome are considered delivered, like successful responses.
+            Some(ItemOutcome::Delivered) | None => {
+                finalizers.update_status(EventStatus::Delivered);
+                false
+            }
+        });
+        if rejected > 0 {
+            emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+                count: rejected,
+                reason: "Items of the request were rejected.",
+            });
+        }
+
+        let Some(reason) = outcomes.iter().find_map(|outcome| match outcome {
+            ItemOutcome::Retry(reason) => Some(reason),
+            _ => None,
+        }) else {
+            return None;
+        };
+        if self.inner.is_exhausted() {
+            error!(
+                message = "Retries exhausted; dropping the failed items of the request.",
+                count = retried,
+                %reason,
+                internal_log_rate_limit = true,
+            );
+            emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+                count: retried,
+                reason: "Retries exhausted for items of the request.",
+            });
+            finalize_items(
+                request,
+                EventStatus::Rejected,
+                Some(&Rejection::new(reason.clone())),
+            );
+            return None;
+        }
+
+        warn!(
+            message = "Retrying failed items of the request.",
+            count = retried,
+            %reason,
+            internal_log_rate_limit = true,
+        );
+        Some(self.inner.build_retry())
+    }
+
+    fn clone_request(&mut self, request: &Req) -> Option<Req> {
+        Some(request.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time;
+    use tokio_test::{assert_ready_ok, task};
+    use tower::retry::RetryLayer;
+    use tower_test::mock;
+    use vector_lib::{
+        event::{BatchNotifier, BatchStatus, EventFinalizer},
+        request_metadata::GroupedCountByteSize,
+    };
+
+    use super::*;
+    use crate::{sinks::util::retries::JitterMode, test_util::trace_init};
+
+    #[derive(Clone)]
+    struct Items(Vec<(&'static str, EventFinalizers)>);
+
+    impl Items {
+        fn names(&self) -> Vec<&'static str> {
+            self.0.iter().map(|(name, _)| *name).collect()
+        }
+    }
+
+    impl PartialRequest for Items {
+        fn retain_items(&mut self, mut f: impl FnMut(usize, &EventFinalizers) -> bool) {
+            let mut index = 0;
+            self.0.retain(|(_, finalizers)| {
+                index += 1;
+                f(index - 1, finalizers)
+            });
+        }
+    }
+
+    struct Outcomes(Vec<ItemOutcome>, GroupedCountByteSize);
+
+    impl DriverResponse for Outcomes {
+        fn event_status(&self) -> EventStatus {
+            EventStatus::Delivered
+        }
+
+        fn events_sent(&self) -> &GroupedCountByteSize {
+            &self.1
+        }
+    }
+
+    #[derive(Clone)]
+    struct OutcomesRetryLogic;
+
+    impl RetryLogic for OutcomesRetryLogic {
+        type Error = std::io::Error;
+        type Response = Outcomes;
+
+        fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+            true
+        }
+
+        fn item_outcomes(&self, response: &Self::Response) -> Option<Vec<ItemOutcome>> {
+            Some(response.0.clone())
+        }
+    }
+
+    fn outcomes(outcomes: Vec<ItemOutcome>) -> Outcomes {
+        Outcomes(outcomes, GroupedCountByteSize::new_untagged())
+    }
+
+    #[tokio::test]
+    async fn retries_failed_items_only() {
+        trace_init();
+        time::pause();
+
+        let policy = PartialRetryPolicy::new(FibonacciRetryPolicy::new(
+            1,
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            OutcomesRetryLogic,
+            JitterMode::None,
+        ));
+        let (mut svc, mut handle) = mock::spawn_layer(RetryLayer::new(policy));
+
+        let mut receivers = Vec::new();
+        let mut items = Vec::new();
+        for name in ["a", "b", "c", "d"] {
+            let (batch, receiver) = BatchNotifier::new_with_receiver();
+            receivers.push(receiver);
+            items.push((name, EventFinalizers::new(EventFinalizer::new(batch))));
+        }
+
+        assert_ready_ok!(svc.poll_ready());
+        let mut fut = task::spawn(svc.call(Items(items)));
+
+        let (request, send) = handle.next_request().await.unwrap();
+        assert_eq!(request.names(), ["a", "b", "c", "d"]);
+        drop(request);
+        send.send_response(outcomes(vec![
+            ItemOutcome::Delivered,
+            ItemOutcome::Retry("throttled".into()),
+            ItemOutcome::Rejected(Rejection::new("invalid")),
+            ItemOutcome::Retry("throttled".into()),
+        ]));
+        assert!(fut.poll().is_pending());
+        time::advance(Duration::from_secs(2)).await;
+        assert!(fut.poll().is_pending());
+
+        let (request, send) = handle.next_request().await.unwrap();
+        assert_eq!(request.names(), ["b", "d"]);
+        drop(request);
+        send.send_response(outcomes(vec![
+            ItemOutcome::Delivered,
+            ItemOutcome::Retry("throttled".into()),
+        ]));
+        fut.await.unwrap();
+
+        let statuses = receivers
+            .into_iter()
+            .map(|mut receiver| receiver.try_recv().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [
+                BatchStatus::Delivered,
+                BatchStatus::Delivered,
+                BatchStatus::Rejected,
+                // Retries are exhausted after the second attempt.
+                BatchStatus::Rejected,
+            ]
+        );
+    }
+}
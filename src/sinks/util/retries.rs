@@ -12,6 +12,7 @@ use tokio::time::{sleep, Sleep};
 use tower::{retry::Policy, timeout::error::Elapsed};
 use vector_lib::configurable::configurable_component;
 
+use super::{partial::ItemOutcome, service::CircuitOpenError};
 use crate::Error;
 
 pub enum RetryAction {
@@ -44,6 +45,14 @@ pub trait RetryLogic: Clone + Send + Sync + 'static {
 
     /// Optional hook run when an error is determined to be retriable.
     fn on_retriable_error(&self, _error: &Self::Error) {}
+
+    /// When the Service call returns an `Ok` response from a bulk API, this function allows
+    /// implementors to report the outcome of each item of the request, in order. The outcomes are
+    /// only used by `PartialRetryPolicy`, which finalizes each item with its own outcome and only
+    /// retries the items that failed with a retriable error.
+    fn item_outcomes(&self, _response: &Self::Response) -> Option<Vec<ItemOutcome>> {
+        None
+    }
 }
 
 /// The jitter mode to use for retry backoff behavior.
@@ -123,7 +132,15 @@ impl<L: RetryLogic> FibonacciRetryPolicy<L> {
         self.current_jitter_duration = Self::add_full_jitter(next_duration);
     }
 
-    fn build_retry(&mut self) -> RetryPolicyFuture {
+    pub(super) const fn logic(&self) -> &L {
+        &self.logic
+    }
+
+    pub(super) const fn is_exhausted(&self) -> bool {
+        self.remaining_attempts == 0
+    }
+
+    pub(super) fn build_retry(&mut self) -> RetryPolicyFuture {
         self.advance();
         let delay = Box::pin(sleep(self.backoff()));
 
@@ -183,6 +200,12 @@ where
                         );
                         None
                     }
+                } else if error.downcast_ref::<CircuitOpenError>().is_some() {
+                    error!(
+                        message = "Circuit breaker is open; dropping the request.",
+                        internal_log_rate_limit = true,
+                    );
+                    None
                 } else if error.downcast_ref::<Elapsed>().is_some() {
                     warn!(
                         message = "Request timed out. If this happens often while the events are actually reaching their destination, try decreasing `batch.max_bytes` and/or using `compression` if applicable. Alternatively `request.timeout_secs` can be increased.",
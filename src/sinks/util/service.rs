@@ -1,58 +1,61 @@
-use std::{hash::Hash, marker::PhantomData, num::NonZeroU64, pin::Pin, sync::Arc, time::Duration};
+use std::{hash::Hash, marker::PhantomData, num::NonZeroU64, sync::Arc, time::Duration};
 
-use futures_util::stream::{self, BoxStream};
+use futures_util::future::BoxFuture;
 use serde_with::serde_as;
 use tower::{
-    balance::p2c::Balance,
     buffer::{Buffer, BufferLayer},
-    discover::Change,
     layer::{util::Stack, Layer},
     limit::RateLimit,
     retry::Retry,
     timeout::Timeout,
     Service, ServiceBuilder,
 };
-use vector_lib::configurable::configurable_component;
+use vector_lib::{configurable::configurable_component, stream::DriverResponse};
 
 pub use crate::sinks::util::service::{
+    circuit_breaker::{
+        CircuitBreaker, CircuitBreakerConfig, CircuitBreakerLayer, CircuitOpenError,
+    },
     concurrency::Concurrency,
-    health::{HealthConfig, HealthLogic, HealthService},
+    health::{HealthConfig, HealthLogic},
     map::Map,
 };
-use crate::{
-    internal_events::OpenGauge,
-    sinks::util::{
-        adaptive_concurrency::{
-            AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitLayer, AdaptiveConcurrencySettings,
-        },
-        retries::{FibonacciRetryPolicy, JitterMode, RetryLogic},
-        service::map::MapLayer,
-        sink::Response,
-        Batch, BatchSink, Partition, PartitionBatchSink,
+use crate::sinks::util::{
+    adaptive_concurrency::{
+        AdaptiveConcurrencyLimit, AdaptiveConcurrencyLimitLayer, AdaptiveConcurrencySettings,
     },
+    load_balance::BalancedService,
+    partial::{PartialRequest, PartialRetryPolicy},
+    retries::{FibonacciRetryPolicy, JitterMode, RetryLogic},
+    service::map::MapLayer,
+    sink::Response,
+    Batch, BatchSink, Partition, PartitionBatchSink,
 };
 
+mod circuit_breaker;
 mod concurrency;
 mod health;
 mod map;
 pub mod net;
 
-pub type Svc<S, L> =
-    RateLimit<AdaptiveConcurrencyLimit<Retry<FibonacciRetryPolicy<L>, Timeout<S>>, L>>;
+pub type Svc<S, L> = RateLimit<
+    AdaptiveConcurrencyLimit<Retry<FibonacciRetryPolicy<L>, CircuitBreaker<Timeout<S>, L>>, L>,
+>;
+pub type PartialSvc<S, L> = RateLimit<
+    AdaptiveConcurrencyLimit<Retry<PartialRetryPolicy<L>, CircuitBreaker<Timeout<S>, L>>, L>,
+>;
 pub type TowerBatchedSink<S, B, RL> = BatchSink<Svc<S, RL>, B>;
 pub type TowerPartitionSink<S, B, RL, K> = PartitionBatchSink<Svc<S, RL>, B, K>;
 
 // Distributed service types
-pub type DistributedService<S, RL, HL, K, Req> = RateLimit<
+pub type DistributedService<S, RL, Req> = RateLimit<
     Retry<
-        FibonacciRetryPolicy<RL>,
-        Buffer<Req, <Balance<DiscoveryService<S, RL, HL, K>, Req> as Service<Req>>::Future>,
+        PartialRetryPolicy<RL>,
+        Buffer<Req, BoxFuture<'static, Result<<S as Service<Req>>::Response, crate::Error>>>,
     >,
 >;
-pub type DiscoveryService<S, RL, HL, K> =
-    BoxStream<'static, Result<Change<K, SingleDistributedService<S, RL, HL>>, crate::Error>>;
-pub type SingleDistributedService<S, RL, HL> =
-    AdaptiveConcurrencyLimit<HealthService<Timeout<S>, HL>, RL>;
+pub type SingleDistributedService<S, RL> =
+    AdaptiveConcurrencyLimit<CircuitBreaker<Timeout<S>, RL>, RL>;
 
 pub trait ServiceBuilderExt<L> {
     fn map<R1, R2, F>(self, f: F) -> ServiceBuilder<Stack<MapLayer<R1, R2>, L>>
@@ -64,6 +67,12 @@ pub trait ServiceBuilderExt<L> {
         settings: TowerRequestSettings,
         retry_logic: RL,
     ) -> ServiceBuilder<Stack<TowerRequestLayer<RL, Request>, L>>;
+
+    fn partial_settings<RL, Request>(
+        self,
+        settings: TowerRequestSettings,
+        retry_logic: RL,
+    ) -> ServiceBuilder<Stack<TowerPartialRequestLayer<RL, Request>, L>>;
 }
 
 impl<L> ServiceBuilderExt<L> for ServiceBuilder<L> {
@@ -85,6 +94,18 @@ impl<L> ServiceBuilderExt<L> for ServiceBuilder<L> {
             _pd: std::marker::PhantomData,
         })
     }
+
+    fn partial_settings<RL, Request>(
+        self,
+        settings: TowerRequestSettings,
+        retry_logic: RL,
+    ) -> ServiceBuilder<Stack<TowerPartialRequestLayer<RL, Request>, L>> {
+        self.layer(TowerPartialRequestLayer {
+            settings,
+            retry_logic,
+            _pd: std::marker::PhantomData,
+        })
+    }
 }
 
 pub trait TowerRequestConfigDefaults {
@@ -165,6 +186,10 @@ pub struct TowerRequestConfig<D: TowerRequestConfigDefaults = GlobalTowerRequest
     #[serde(default)]
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
 
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+
     #[serde(skip)]
     pub _d: PhantomData<D>,
 }
@@ -213,6 +238,7 @@ impl<D: TowerRequestConfigDefaults> Default for TowerRequestConfig<D> {
             retry_initial_backoff_secs: default_retry_initial_backoff_secs::<D>(),
             adaptive_concurrency: AdaptiveConcurrencySettings::default(),
             retry_jitter_mode: JitterMode::default(),
+            circuit_breaker: None,
 
             _d: PhantomData,
         }
@@ -232,6 +258,7 @@ impl<D: TowerRequestConfigDefaults> TowerRequestConfig<D> {
             retry_initial_backoff: Duration::from_secs(self.retry_initial_backoff_secs.get()),
             adaptive_concurrency: self.adaptive_concurrency,
             retry_jitter_mode: self.retry_jitter_mode,
+            circuit_breaker: self.circuit_breaker,
         }
     }
 }
@@ -247,6 +274,7 @@ pub struct TowerRequestSettings {
     pub retry_initial_backoff: Duration,
     pub adaptive_concurrency: AdaptiveConcurrencySettings,
     pub retry_jitter_mode: JitterMode,
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
 }
 
 impl TowerRequestSettings {
@@ -260,6 +288,10 @@ impl TowerRequestSettings {
         )
     }
 
+    pub fn partial_retry_policy<L: RetryLogic>(&self, logic: L) -> PartialRetryPolicy<L> {
+        PartialRetryPolicy::new(self.retry_policy(logic))
+    }
+
     /// Note: This has been deprecated, please do not use when creating new Sinks.
     pub fn partition_sink<B, RL, S, K>(
         &self,
@@ -308,7 +340,11 @@ impl TowerRequestSettings {
         BatchSink::new(service, batch, batch_timeout)
     }
 
-    /// Distributes requests to services [(Endpoint, service, healthcheck)]
+    /// Distributes requests to services [(Endpoint, service)]
+    ///
+    /// Requests are bulk requests, retrying only their failed items like
+    /// [`ServiceBuilderExt::partial_settings`] does. The endpoints are selected and their health
+    /// tracked like the ones of a [`LoadBalancer`](super::load_balance::LoadBalancer).
     ///
     /// [BufferLayer] suggests that the `buffer_bound` should be at least equal to
     /// the number of the callers of the service. For sinks, this should typically be 1.
@@ -319,52 +355,49 @@ impl TowerRequestSettings {
         health_config: HealthConfig,
         health_logic: HL,
         buffer_bound: usize,
-    ) -> DistributedService<S, RL, HL, usize, Req>
+    ) -> crate::Result<DistributedService<S, RL, Req>>
     where
-        Req: Clone + Send + 'static,
+        Req: PartialRequest + Send + 'static,
         RL: RetryLogic<Response = S::Response>,
         HL: HealthLogic<Response = S::Response, Error = crate::Error>,
         S: Service<Req> + Clone + Send + 'static,
         S::Error: Into<crate::Error> + Send + Sync + 'static,
-        S::Response: Send,
+        S::Response: DriverResponse + Send + 'static,
         S::Future: Send + 'static,
     {
-        let policy = self.retry_policy(retry_logic.clone());
+        let policy = self.partial_retry_policy(retry_logic.clone());
 
         // Build services
-        let open = OpenGauge::new();
         let services = services
             .into_iter()
             .map(|(endpoint, inner)| {
                 // Build individual service
-                ServiceBuilder::new()
+                let service: SingleDistributedService<S, RL> = ServiceBuilder::new()
                     .layer(AdaptiveConcurrencyLimitLayer::new(
                         self.concurrency,
                         self.adaptive_concurrency,
                         retry_logic.clone(),
                     ))
-                    .service(
-                        health_config.build(
-                            health_logic.clone(),
-                            ServiceBuilder::new().timeout(self.timeout).service(inner),
-                            open.clone(),
-                            endpoint,
-                        ), // NOTE: there is a version conflict for crate `tracing` between `tracing_tower` crate
-                           // and Vector. Once that is resolved, this can be used instead of passing endpoint everywhere.
-                           // .trace_service(|_| info_span!("endpoint", %endpoint)),
+                    // Each endpoint has its own circuit breaker, so that the requests are
+                    // balanced to the other endpoints while it is open.
+                    .layer(
+                        CircuitBreakerLayer::new(self.circuit_breaker, retry_logic.clone())
+                            .endpoint(endpoint.clone()),
                     )
+                    .timeout(self.timeout)
+                    .service(inner);
+                (endpoint, service)
             })
-            .enumerate()
-            .map(|(i, service)| Ok::<_, S::Error>(Change::Insert(i, service)))
             .collect::<Vec<_>>();
+        let balanced = BalancedService::new(services, &health_config, health_logic)?;
 
         // Build sink service
-        ServiceBuilder::new()
+        Ok(ServiceBuilder::new()
             .rate_limit(self.rate_limit_num, self.rate_limit_duration)
             .retry(policy)
-            // [Balance] must be wrapped with a [BufferLayer] so that the overall service implements Clone.
+            // [BalancedService] must be wrapped with a [BufferLayer] so that the overall service implements Clone.
             .layer(BufferLayer::new(buffer_bound))
-            .service(Balance::new(Box::pin(stream::iter(services)) as Pin<Box<_>>))
+            .service(balanced))
     }
 }
 
@@ -399,6 +432,51 @@ where
                 self.retry_logic.clone(),
             ))
             .retry(policy)
+            .layer(CircuitBreakerLayer::new(
+                self.settings.circuit_breaker,
+                self.retry_logic.clone(),
+            ))
+            .timeout(self.settings.timeout)
+            .service(inner)
+    }
+}
+
+/// Like `TowerRequestLayer`, but retrying only the failed items of bulk requests.
+#[derive(Debug, Clone)]
+pub struct TowerPartialRequestLayer<L, Request> {
+    settings: TowerRequestSettings,
+    retry_logic: L,
+    _pd: PhantomData<Request>,
+}
+
+impl<S, RL, Request> Layer<S> for TowerPartialRequestLayer<RL, Request>
+where
+    S: Service<Request> + Send + 'static,
+    S::Response: DriverResponse + Send + 'static,
+    S::Error: Into<crate::Error> + Send + Sync + 'static,
+    S::Future: Send + 'static,
+    RL: RetryLogic<Response = S::Response> + Send + 'static,
+    Request: PartialRequest + Send + 'static,
+{
+    type Service = PartialSvc<S, RL>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let policy = self.settings.partial_retry_policy(self.retry_logic.clone());
+        ServiceBuilder::new()
+            .rate_limit(
+                self.settings.rate_limit_num,
+                self.settings.rate_limit_duration,
+            )
+            .layer(AdaptiveConcurrencyLimitLayer::new(
+                self.settings.concurrency,
+                self.settings.adaptive_concurrency,
+                self.retry_logic.clone(),
+            ))
+            .retry(policy)
+            .layer(CircuitBreakerLayer::new(
+                self.settings.circuit_breaker,
+                self.retry_logic.clone(),
+            ))
             .timeout(self.settings.timeout)
             .service(inner)
     }
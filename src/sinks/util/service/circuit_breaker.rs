@@ -0,0 +1,701 @@
+use std::{
+    fmt,
+    future::Future,
+    num::{NonZeroU64, NonZeroUsize},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use pin_project::pin_project;
+use tokio::{
+    sync::Notify,
+    time::{sleep_until, Instant},
+};
+use tower::{Layer, Service};
+use vector_lib::configurable::configurable_component;
+
+use crate::{
+    internal_events::{CircuitBreakerCreated, CircuitBreakerState, CircuitBreakerStateChanged},
+    sinks::util::retries::RetryLogic,
+};
+
+const fn default_failure_threshold() -> NonZeroUsize {
+    NonZeroUsize::new(5).unwrap()
+}
+
+const fn default_reset_timeout_secs() -> NonZeroU64 {
+    NonZeroU64::new(30).unwrap()
+}
+
+/// Configuration of the circuit breaker of outbound requests.
+///
+/// The circuit breaker opens after a number of consecutive failed requests, stopping the requests to
+/// the endpoint so that they are not retried in vain. Once the reset timeout has elapsed, the
+/// circuit breaker is half-open: a single request is sent to probe the endpoint, and the circuit
+/// breaker closes if it succeeds, or opens again otherwise.
+///
+/// Requests are failed when they return an error that would be retried, or a response that would be
+/// retried.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitBreakerConfig {
+    /// The number of consecutive failed requests after which the circuit breaker opens.
+    #[serde(default = "default_failure_threshold")]
+    #[configurable(metadata(docs::type_unit = "requests"))]
+    pub failure_threshold: NonZeroUsize,
+
+    /// The time the circuit breaker stays open before probing the endpoint.
+    #[serde(default = "default_reset_timeout_secs")]
+    #[configurable(metadata(docs::type_unit = "seconds"))]
+    #[configurable(metadata(docs::human_name = "Reset Timeout"))]
+    pub reset_timeout_secs: NonZeroU64,
+
+    /// Whether requests are rejected while the circuit breaker is open, instead of waiting for it to
+    /// close.
+    ///
+    /// The events of the rejected requests can be sent to a failover sink by enabling
+    /// `reroute_dropped` on this sink, and using its `dropped` output as an input of the failover
+    /// sink.
+    #[serde(default)]
+    pub reject_when_open: bool,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: default_failure_threshold(),
+            reset_timeout_secs: default_reset_timeout_secs(),
+            reject_when_open: false,
+        }
+    }
+}
+
+/// The error of the requests rejected while the circuit breaker is open.
+#[derive(Debug)]
+pub struct CircuitOpenError;
+
+impl fmt::Display for CircuitOpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("circuit breaker is open")
+    }
+}
+
+impl std::error::Error for CircuitOpenError {}
+
+#[derive(Clone, Copy, Debug)]
+enum Circuit {
+    Closed,
+    Open { until: Instant },
+    HalfOpen { probing: bool },
+}
+
+impl Circuit {
+    const fn state(self) -> CircuitBreakerState {
+        match self {
+            Self::Closed => CircuitBreakerState::Closed,
+            Self::Open { .. } => CircuitBreakerState::Open,
+            Self::HalfOpen { .. } => CircuitBreakerState::HalfOpen,
+        }
+    }
+}
+
+struct BreakerState {
+    circuit: Circuit,
+    consecutive_failures: usize,
+}
+
+/// The state of a circuit breaker, shared by the clones of its service.
+struct Shared {
+    /// The endpoint of the circuit breaker, when the requests are distributed to several.
+    endpoint: Option<String>,
+    failure_threshold: usize,
+    reset_timeout: Duration,
+    reject_when_open: bool,
+    state: Mutex<BreakerState>,
+    /// Notified when a probe is done.
+    probed: Notify,
+}
+
+impl Shared {
+    fn transition(&self, state: &mut BreakerState, circuit: Circuit) {
+        let changed = state.circuit.state() != circuit.state();
+        state.circuit = circuit;
+        if changed {
+            emit!(CircuitBreakerStateChanged {
+                state: circuit.state(),
+                consecutive_failures: state.consecutive_failures,
+                endpoint: self.endpoint.as_deref(),
+            });
+        }
+    }
+
+    fn record(&self, failed: bool, probe: bool) {
+        let mut state = self.state.lock().expect("circuit breaker state poisoned");
+        if failed {
+            state.consecutive_failures += 1;
+            let trip = probe
+                || (matches!(state.circuit, Circuit::Closed)
+                    && state.consecutive_failures >= self.failure_threshold);
+            if trip {
+                let until = Instant::now() + self.reset_timeout;
+                self.transition(&mut state, Circuit::Open { until });
+            }
+        } else {
+            state.consecutive_failures = 0;
+            self.transition(&mut state, Circuit::Closed);
+        }
+        if probe {
+            if let Circuit::HalfOpen { probing } = &mut state.circuit {
+                *probing = false;
+            }
+            drop(state);
+            self.probed.notify_waiters();
+        }
+    }
+
+    fn release_probe(&self) {
+        let mut state = self.state.lock().expect("circuit breaker state poisoned");
+        if let Circuit::HalfOpen { probing } = &mut state.circuit {
+            *probing = false;
+        }
+        drop(state);
+        self.probed.notify_waiters();
+    }
+}
+
+/// Records the outcome of a request, releasing the probe of a half-open circuit breaker if the
+/// request is dropped before completing.
+struct Outcome {
+    shared: Arc<Shared>,
+    probe: bool,
+}
+
+impl Outcome {
+    fn record(mut self, failed: bool) {
+        self.shared.record(failed, self.probe);
+        self.probe = false;
+    }
+}
+
+impl Drop for Outcome {
+    fn drop(&mut self) {
+        if self.probe {
+            self.shared.release_probe();
+        }
+    }
+}
+
+/// A layer adding a circuit breaker to a service, if configured.
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerLayer<L> {
+    config: Option<CircuitBreakerConfig>,
+    logic: L,
+    endpoint: Option<String>,
+}
+
+impl<L> CircuitBreakerLayer<L> {
+    pub const fn new(config: Option<CircuitBreakerConfig>, logic: L) -> Self {
+        Self {
+            config,
+            logic,
+            endpoint: None,
+        }
+    }
+
+    /// Tags the internal events of the circuit breaker with its endpoint, when the requests are
+    /// distributed to several endpoints with a circuit breaker each.
+    pub fn endpoint(mut self, endpoint: String) -> Self {
+        self.endpoint = Some(endpoint);
+        self
+    }
+}
+
+impl<S, L: Clone> Layer<S> for CircuitBreakerLayer<L> {
+    type Service = CircuitBreaker<S, L>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let shared = self.config.map(|config| {
+            emit!(CircuitBreakerCreated {
+                endpoint: self.endpoint.as_deref(),
+            });
+            Arc::new(Shared {
+                endpoint: self.endpoint.clone(),
+                failure_threshold: config.failure_threshold.get(),
+                reset_timeout: Duration::from_secs(config.reset_timeout_secs.get()),
+                reject_when_open: config.reject_when_open,
+                state: Mutex::new(BreakerState {
+                    circuit: Circuit::Closed,
+                    consecutive_failures: 0,
+                }),
+                probed: Notify::new(),
+            })
+        });
+        CircuitBreaker {
+            inner,
+            logic: self.logic.clone(),
+            shared,
+            wait: None,
+            probe: false,
+            reject: false,
+        }
+    }
+}
+
+/// A service stopping requests to an endpoint after consecutive failures.
+///
+/// The service passes all requests through when the circuit breaker is not configured. Otherwise,
+/// the service is not ready while the circuit breaker is open, unless it rejects requests instead.
+pub struct CircuitBreaker<S, L> {
+    inner: S,
+    logic: L,
+    shared: Option<Arc<Shared>>,
+    wait: Option<BoxFuture<'static, ()>>,
+    /// Whether the next request is the probe of the half-open circuit breaker.
+    probe: bool,
+    /// Whether the next request is rejected.
+    reject: bool,
+}
+
+impl<S: Clone, L: Clone> Clone for CircuitBreaker<S, L> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            logic: self.logic.clone(),
+            shared: self.shared.clone(),
+            wait: None,
+            probe: false,
+            reject: false,
+        }
+    }
+}
+
+impl<S, L> Drop for CircuitBreaker<S, L> {
+    fn drop(&mut self) {
+        if let (true, Some(shared)) = (self.probe, &self.shared) {
+            shared.release_probe();
+        }
+    }
+}
+
+impl<S, L, Req> Service<Req> for CircuitBreaker<S, L>
+where
+    S: Service<Req>,
+    S::Error: Into<crate::Error>,
+    L: RetryLogic<Response = S::Response>,
+{
+    type Response = S::Response;
+    type Error = crate::Error;
+    type Future = CircuitBreakerFuture<S::Future, L>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let Some(shared) = &self.shared else {
+            return self.inner.poll_ready(cx).map_err(Into::into);
+        };
+
+        if !self.probe {
+            loop {
+                if let Some(wait) = self.wait.as_mut() {
+                    ready!(wait.as_mut().poll(cx));
+                    self.wait = None;
+                }
+
+                let mut state = shared.state.lock().expect("circuit breaker state poisoned");
+                let mut wait = match state.circuit {
+                    Circuit::Closed => break,
+                    Circuit::Open { until } if until <= Instant::now() => {
+                        shared.transition(&mut state, Circuit::HalfOpen { probing: false });
+                        continue;
+                    }
+                    Circuit::HalfOpen { probing: false } => {
+                        state.circuit = Circuit::HalfOpen { probing: true };
+                        self.probe = true;
+                        break;
+                    }
+                    _ if shared.reject_when_open => {
+                        self.reject = true;
+                        return Poll::Ready(Ok(()));
+                    }
+                    Circuit::Open { until } => sleep_until(until).boxed(),
+                    Circuit::HalfOpen { probing: true } => {
+                        let shared = Arc::clone(shared);
+                        async move { shared.probed.notified().await }.boxed()
+                    }
+                };
+                // The waiter is registered while the state is locked, so that the end of the probe
+                // can't be missed.
+                if wait.as_mut().poll(cx).is_pending() {
+                    self.wait = Some(wait);
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        self.reject = false;
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, request: Req) -> Self::Future {
+        if std::mem::take(&mut self.reject) {
+            return CircuitBreakerFuture {
+                inner: None,
+                logic: self.logic.clone(),
+                outcome: None,
+            };
+        }
+
+        let outcome = self.shared.as_ref().map(|shared| Outcome {
+            shared: Arc::clone(shared),
+            probe: std::mem::take(&mut self.probe),
+        });
+        CircuitBreakerFuture {
+            inner: Some(self.inner.call(request)),
+            logic: self.logic.clone(),
+            outcome,
+        }
+    }
+}
+
+/// Future for `CircuitBreaker`.
+#[pin_project]
+pub struct CircuitBreakerFuture<F, L> {
+    #[pin]
+    inner: Option<F>,
+    logic: L,
+    outcome: Option<Outcome>,
+}
+
+impl<F, Res, E, L> Future for CircuitBreakerFuture<F, L>
+where
+    F: Future<Output = Result<Res, E>>,
+    E: Into<crate::Error>,
+    L: RetryLogic<Response = Res>,
+{
+    type Output = Result<Res, crate::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let Some(inner) = this.inner.as_pin_mut() else {
+            return Poll::Ready(Err(Box::new(CircuitOpenError)));
+        };
+        let output = ready!(inner.poll(cx)).map_err(Into::into);
+
+        if let Some(outcome) = this.outcome.take() {
+            let failed = match &output {
+                Ok(response) => this.logic.should_retry_response(response).is_retryable(),
+                Err(error) => error
+                    .downcast_ref::<L::Error>()
+                    .is_none_or(|error| this.logic.is_retriable_error(error)),
+            };
+            outcome.record(failed);
+        }
+
+        Poll::Ready(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time;
+    use tokio_test::{assert_pending, assert_ready_ok, task};
+    use tower_test::{assert_request_eq, mock};
+    use vector_lib::{event::MetricValue, metrics::Controller};
+
+    use super::*;
+    use crate::test_util::trace_init;
+
+    #[derive(Clone)]
+    struct TestRetryLogic;
+
+    impl RetryLogic for TestRetryLogic {
+        type Error = std::io::Error;
+        type Response = &'static str;
+
+        fn is_retriable_error(&self, _error: &Self::Error) -> bool {
+            true
+        }
+    }
+
+    fn io_error() -> std::io::Error {
+        std::io::Error::other("connection refused")
+    }
+
+    fn layer(failure_threshold: usize) -> CircuitBreakerLayer<TestRetryLogic> {
+        CircuitBreakerLayer::new(
+            Some(CircuitBreakerConfig {
+                failure_threshold: NonZeroUsize::new(failure_threshold).unwrap(),
+                reset_timeout_secs: NonZeroU64::new(10).unwrap(),
+                reject_when_open: false,
+            }),
+            TestRetryLogic,
+        )
+    }
+
+    fn state<S, L>(breaker: &CircuitBreaker<S, L>) -> CircuitBreakerState {
+        let shared = breaker.shared.as_ref().unwrap();
+        let state = shared.state.lock().unwrap();
+        state.circuit.state()
+    }
+
+    fn state_gauge(endpoint: &str) -> Option<f64> {
+        Controller::get()
+            .unwrap()
+            .capture_metrics()
+            .into_iter()
+            .find(|metric| {
+                metric.name() == "circuit_breaker_state"
+                    && metric.tag_value("endpoint").as_deref() == Some(endpoint)
+            })
+            .and_then(|metric| match metric.value() {
+                MetricValue::Gauge { value } => Some(*value),
+                _ => None,
+            })
+    }
+
+    #[tokio::test]
+    async fn opens_after_consecutive_failures() {
+        trace_init();
+        time::pause();
+
+        let layer = CircuitBreakerLayer::new(
+            Some(CircuitBreakerConfig {
+                failure_threshold: NonZeroUsize::new(2).unwrap(),
+                reset_timeout_secs: NonZeroU64::new(10).unwrap(),
+                reject_when_open: false,
+            }),
+            TestRetryLogic,
+        );
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        for _ in 0..2 {
+            assert_ready_ok!(svc.poll_ready());
+            let fut = task::spawn(svc.call("hello"));
+            assert_request_eq!(handle, "hello").send_error(io_error());
+            assert!(fut.await.is_err());
+        }
+
+        // The circuit breaker is open until the reset timeout elapses.
+        assert_pending!(svc.poll_ready());
+        time::advance(Duration::from_secs(11)).await;
+
+        // A single probe is sent while half-open.
+        assert_ready_ok!(svc.poll_ready());
+        let fut = task::spawn(svc.call("probe"));
+        let mut other = mock::Spawn::new(svc.get_ref().clone());
+        assert_pending!(other.poll_ready());
+
+        assert_request_eq!(handle, "probe").send_response("ok");
+        assert_eq!(fut.await.unwrap(), "ok");
+        assert_ready_ok!(svc.poll_ready());
+    }
+
+    #[tokio::test]
+    async fn rejects_when_open() {
+        trace_init();
+
+        let layer = CircuitBreakerLayer::new(
+            Some(CircuitBreakerConfig {
+                failure_threshold: NonZeroUsize::new(1).unwrap(),
+                reject_when_open: true,
+                ..Default::default()
+            }),
+            TestRetryLogic,
+        );
+        let (mut svc, mut handle) = mock::spawn_layer(layer);
+
+        assert_ready_ok!(svc.poll_ready());
+        let fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_error(io_error());
+        assert!(fut.await.is_err());
+
+        assert_ready_ok!(svc.poll_ready());
+        let error = svc.call("hello").await.unwrap_err();
+        assert!(error.downcast_ref::<CircuitOpenError>().is_some());
+    }
+
+    #[tokio::test]
+    async fn closes_after_successful_probe() {
+        trace_init();
+        time::pause();
+
+        let (mut svc, mut handle) = mock::spawn_layer(layer(2));
+
+        for _ in 0..2 {
+            assert_ready_ok!(svc.poll_ready());
+            let fut = task::spawn(svc.call("hello"));
+            assert_request_eq!(handle, "hello").send_error(io_error());
+            assert!(fut.await.is_err());
+        }
+        assert_eq!(CircuitBreakerState::Open, state(svc.get_ref()));
+
+        time::advance(Duration::from_secs(11)).await;
+        assert_ready_ok!(svc.poll_ready());
+        assert_eq!(CircuitBreakerState::HalfOpen, state(svc.get_ref()));
+
+        let fut = task::spawn(svc.call("probe"));
+        assert_request_eq!(handle, "probe").send_response("ok");
+        assert_eq!(fut.await.unwrap(), "ok");
+        assert_eq!(CircuitBreakerState::Closed, state(svc.get_ref()));
+
+        // The failures before the probe are forgotten, so a single failure doesn't open it again.
+        assert_ready_ok!(svc.poll_ready());
+        let fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_error(io_error());
+        assert!(fut.await.is_err());
+        assert_eq!(CircuitBreakerState::Closed, state(svc.get_ref()));
+        assert_ready_ok!(svc.poll_ready());
+    }
+
+    #[tokio::test]
+    async fn reopens_after_failed_probe() {
+        trace_init();
+        time::pause();
+
+        let (mut svc, mut handle) = mock::spawn_layer(layer(2));
+
+        for _ in 0..2 {
+            assert_ready_ok!(svc.poll_ready());
+            let fut = task::spawn(svc.call("hello"));
+            assert_request_eq!(handle, "hello").send_error(io_error());
+            assert!(fut.await.is_err());
+        }
+
+        time::advance(Duration::from_secs(11)).await;
+        assert_ready_ok!(svc.poll_ready());
+        let fut = task::spawn(svc.call("probe"));
+        let mut other = mock::Spawn::new(svc.get_ref().clone());
+        assert_pending!(other.poll_ready());
+
+        // A failed probe opens the circuit breaker again, whatever the failure threshold.
+        assert_request_eq!(handle, "probe").send_error(io_error());
+        assert!(fut.await.is_err());
+        assert_eq!(CircuitBreakerState::Open, state(svc.get_ref()));
+        assert!(other.is_woken());
+        assert_pending!(other.poll_ready());
+        assert_pending!(svc.poll_ready());
+
+        time::advance(Duration::from_secs(11)).await;
+        assert_ready_ok!(svc.poll_ready());
+        assert_eq!(CircuitBreakerState::HalfOpen, state(svc.get_ref()));
+    }
+
+    #[tokio::test]
+    async fn reports_state_gauge() {
+        trace_init();
+        time::pause();
+
+        let endpoint = "http://localhost:9000/";
+        let (mut svc, mut handle) = mock::spawn_layer(layer(1).endpoint(endpoint.to_owned()));
+        assert_eq!(Some(0.0), state_gauge(endpoint));
+
+        assert_ready_ok!(svc.poll_ready());
+        let fut = task::spawn(svc.call("hello"));
+        assert_request_eq!(handle, "hello").send_error(io_error());
+        assert!(fut.await.is_err());
+        assert_eq!(Some(2.0), state_gauge(endpoint));
+
+        time::advance(Duration::from_secs(11)).await;
+        assert_ready_ok!(svc.poll_ready());
+        assert_eq!(Some(1.0), state_gauge(endpoint));
+
+        let fut = task::spawn(svc.call("probe"));
+        assert_request_eq!(handle, "probe").send_response("ok");
+        assert_eq!(fut.await.unwrap(), "ok");
+        assert_eq!(Some(0.0), state_gauge(endpoint));
+    }
+
+    #[cfg(feature = "api")]
+    #[tokio::test]
+    async fn reports_state_through_graphql() {
+        use tracing::Instrument;
+
+        trace_init();
+
+        let span = info_span!("sink", component_id = "primary");
+        let (mut svc, mut handle) = span.in_scope(|| mock::spawn_layer(layer(1)));
+        async {
+            assert_ready_ok!(svc.poll_ready());
+            let fut = task::spawn(svc.call("hello"));
+            assert_request_eq!(handle, "hello").send_error(io_error());
+            assert!(fut.await.is_err());
+        }
+        .instrument(span)
+        .await;
+
+        let response = crate::api::build_schema()
+            .finish()
+            .execute("{ circuitBreakers { componentId state } }")
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            serde_json::json!({
+                "circuitBreakers": [{ "componentId": "primary", "state": "OPEN" }]
+            }),
+            response.data.into_json().unwrap()
+        );
+    }
+
+    #[cfg(feature = "sinks-http")]
+    #[tokio::test]
+    async fn reroutes_rejected_requests_to_dropped_output() {
+        use futures::StreamExt;
+
+        use crate::{
+            config::{Config, SinkOuter},
+            event::{into_event_stream, Event, LogEvent},
+            sinks::http::config::HttpSinkConfig,
+            test_util::{
+                mock::{basic_sink, basic_source},
+                next_addr, start_topology,
+            },
+        };
+
+        trace_init();
+
+        // Nothing listens on the address, so the first request fails and opens the circuit
+        // breaker, and its retry is rejected.
+        let http: HttpSinkConfig = toml::from_str(&format!(
+            r#"
+            uri = "http://{}/"
+            encoding.codec = "json"
+            batch.max_events = 1
+            request.circuit_breaker.failure_threshold = 1
+            request.circuit_breaker.reject_when_open = true
+            "#,
+            next_addr()
+        ))
+        .unwrap();
+
+        let (mut in1, source1) = basic_source();
+        let (out1, sink1) = basic_sink(10);
+
+        let mut primary = SinkOuter::new(["in1".to_owned()], http);
+        primary.reroute_dropped = true;
+
+        let mut config = Config::builder();
+        config.add_source("in1", source1);
+        config.add_sink_outer("primary", primary);
+        config.add_sink("failover", &["primary.dropped"], sink1);
+
+        let (topology, _) = start_topology(config.build().unwrap(), false).await;
+
+        in1.send_event(Event::Log(LogEvent::from("this")))
+            .await
+            .unwrap();
+
+        topology.stop().await;
+
+        let res = out1.flat_map(into_event_stream).collect::<Vec<_>>().await;
+        assert_eq!(res.len(), 1);
+        let log = res[0].as_log();
+        assert_eq!(log["message"], "this".into());
+        assert_eq!(log["metadata.dropped.reason"], "rejected".into());
+        assert_eq!(log["metadata.dropped.component_id"], "primary".into());
+        assert!(log["metadata.dropped.message"]
+            .to_string_lossy()
+            .contains("CircuitOpenError"));
+    }
+}
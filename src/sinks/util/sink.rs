@@ -60,7 +60,7 @@ use super::{
     service::{Map, ServiceBuilderExt},
     EncodedEvent,
 };
-use crate::event::EventStatus;
+use crate::event::{EventStatus, Rejection};
 
 // === BatchSink ===
 
@@ -454,6 +454,15 @@ where
                         // TODO: Emit a BytesSent event here too
                     }
                     EventStatus::Rejected => {
+                        let rejection = match &result {
+                            Ok(response) => Rejection {
+                                response: Some(format!("{response:?}")),
+                                ..Rejection::new("Response failed.")
+                            },
+                            Err(error) => Rejection::new(error.to_string()),
+                        };
+                        finalizers.record_rejection(&rejection);
+
                         // Emit the `Error` and `EventsDropped` internal events.
                         // This scenario occurs after retries have been attempted.
                         let error = result.err().unwrap_or_else(|| "Response failed.".into());
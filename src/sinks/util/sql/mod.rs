@@ -0,0 +1,248 @@
+//! Shared implementation of the sinks writing events to SQL databases with `sqlx`.
+//!
+//! Batching, request building, delivery and retries are shared, while each database provides a
+//! [`Dialect`] generating its statements and classifying its errors.
+
+use std::borrow::Cow;
+
+use serde_json::Value as JsonValue;
+use sqlx::{Database, Pool};
+use vector_lib::codecs::{JsonSerializer, JsonSerializerConfig};
+use vector_lib::configurable::configurable_component;
+use vector_lib::event::{Event, Value};
+use vector_lib::lookup::{
+    lookup_v2::{ConfigValuePath, OwnedSegment},
+    OwnedValuePath,
+};
+
+mod service;
+mod sink;
+#[cfg(any(feature = "sinks-mysql", feature = "sinks-sqlite"))]
+pub mod values;
+
+pub use self::service::{SqlRequest, SqlResponse, SqlRetryLogic, SqlService, SqlServiceError};
+pub use self::sink::SqlSink;
+
+/// A column of the destination table and the event field it is populated from.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ColumnConfig {
+    /// The name of the column.
+    #[configurable(metadata(docs::examples = "created_at"))]
+    pub name: String,
+
+    /// The path of the event field holding the value of the column.
+    ///
+    /// If not set, the top-level field with the same name as the column is used.
+    #[configurable(metadata(docs::examples = "timestamp"))]
+    #[configurable(metadata(docs::examples = "http.status_code"))]
+    pub path: Option<ConfigValuePath>,
+
+    /// A type the value is explicitly cast to, such as `timestamptz` or `int8` in PostgreSQL.
+    ///
+    /// If not set, the value is converted to the type of the column by the database. Casts are not
+    /// applied by the `copy` insert mode of the `postgres` sink, where values are always parsed by
+    /// the type of the column.
+    #[configurable(metadata(docs::examples = "timestamptz"))]
+    pub r#type: Option<String>,
+}
+
+/// How rows conflicting with existing rows are handled.
+#[configurable_component]
+#[derive(Clone, Debug, Default, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+#[configurable(metadata(
+    docs::enum_tag_description = "The action taken for rows conflicting with existing rows."
+))]
+pub enum OnConflictConfig {
+    /// Conflicts are errors, and the conflicting events are dropped.
+    #[default]
+    Error,
+
+    /// Conflicting rows are skipped.
+    Ignore {
+        /// The columns of the unique index conflicts are checked against.
+        ///
+        /// If empty, conflicts with any unique index are ignored. MySQL always checks all unique
+        /// indexes, so this must be empty there.
+        #[serde(default)]
+        target: Vec<String>,
+    },
+
+    /// Existing rows are updated with the values of conflicting rows.
+    Update {
+        /// The columns of the unique index conflicts are checked against.
+        ///
+        /// MySQL always checks all unique indexes, so this must be empty there.
+        #[serde(default)]
+        target: Vec<String>,
+
+        /// The columns that are updated.
+        columns: Vec<String>,
+    },
+}
+
+/// A column populated from an event field.
+#[derive(Debug)]
+pub struct Column {
+    pub name: String,
+    pub cast: Option<String>,
+    path: OwnedValuePath,
+}
+
+/// Converts events to rows.
+#[derive(Debug)]
+pub struct RowBuilder {
+    columns: Vec<Column>,
+    serializer: JsonSerializer,
+}
+
+impl From<&ColumnConfig> for Column {
+    fn from(column: &ColumnConfig) -> Self {
+        Self {
+            name: column.name.clone(),
+            cast: column.r#type.clone(),
+            path: column.path.as_ref().map_or_else(
+                || vec![OwnedSegment::field(&column.name)].into(),
+                |path| path.0.clone(),
+            ),
+        }
+    }
+}
+
+impl RowBuilder {
+    pub fn new(columns: &[ColumnConfig]) -> Self {
+        let columns = columns.iter().map(Column::from).collect();
+        Self {
+            columns,
+            serializer: JsonSerializerConfig::default().build(),
+        }
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    /// Converts an event to a JSON row.
+    ///
+    /// Without explicit columns, the whole event is the row. Otherwise, the row maps the name of each
+    /// column to the value of its field.
+    pub fn row(&self, event: Event) -> vector_common::Result<JsonValue> {
+        let json = self.serializer.to_json_value(event)?;
+        if self.columns.is_empty() {
+            return Ok(json);
+        }
+
+        let event = Value::from(json);
+        let row = self
+            .columns
+            .iter()
+            .map(|column| {
+                let value = event
+                    .get(&column.path)
+                    .map(serde_json::to_value)
+                    .transpose()?
+                    .unwrap_or(JsonValue::Null);
+                Ok((column.name.clone(), value))
+            })
+            .collect::<Result<serde_json::Map<_, _>, serde_json::Error>>()?;
+        Ok(JsonValue::Object(row))
+    }
+}
+
+/// The database specific part of a SQL sink.
+#[async_trait::async_trait]
+pub trait Dialect: Send + Sync + 'static {
+    type Database: Database;
+
+    /// The protocol reported in the `protocol` tag of the sink's telemetry.
+    const PROTOCOL: &'static str;
+
+    /// Writes a batch of rows, as built by [`RowBuilder::row`], to a table.
+    async fn write_batch(
+        &self,
+        pool: &Pool<Self::Database>,
+        table: &str,
+        rows: &[JsonValue],
+    ) -> Result<(), sqlx::Error>;
+
+    /// Inserts a single row into a table using the given connection.
+    async fn insert_row(
+        &self,
+        connection: &mut <Self::Database as Database>::Connection,
+        table: &str,
+        row: &JsonValue,
+    ) -> Result<(), sqlx::Error>;
+
+    /// Returns whether an error is transient, so that the batch can be retried.
+    fn is_retriable_error(&self, error: &sqlx::Error) -> bool;
+
+    /// Returns whether the database rejected the data of at least one row, for example because it
+    /// violates a constraint or can't be converted to the type of a column.
+    ///
+    /// The other rows of a batch failing with such an error can still be written.
+    fn is_data_error(&self, error: &sqlx::Error) -> bool;
+}
+
+/// Returns whether an error is a transient connection error, whatever the database.
+pub fn is_connection_error(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut)
+}
+
+/// Returns the code of a database error, which is the SQLSTATE for databases reporting one.
+pub fn database_error_code(error: &sqlx::Error) -> Option<Cow<'_, str>> {
+    match error {
+        sqlx::Error::Database(error) => error.code(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::event::LogEvent;
+
+    use super::*;
+
+    #[test]
+    fn builds_rows_from_columns() {
+        let columns = [
+            ColumnConfig {
+                name: "message".to_owned(),
+                path: None,
+                r#type: None,
+            },
+            ColumnConfig {
+                name: "status".to_owned(),
+                path: Some(ConfigValuePath::try_from("http.status".to_owned()).unwrap()),
+                r#type: Some("int8".to_owned()),
+            },
+            ColumnConfig {
+                name: "missing".to_owned(),
+                path: None,
+                r#type: None,
+            },
+        ];
+        let mut log = LogEvent::from("hello");
+        log.insert("http.status", 200);
+        log.insert("other", "ignored");
+
+        let row = RowBuilder::new(&columns).row(log.into()).unwrap();
+        assert_eq!(
+            row,
+            serde_json::json!({ "message": "hello", "status": 200, "missing": null })
+        );
+    }
+
+    #[test]
+    fn builds_rows_from_events() {
+        let mut log = LogEvent::from("hello");
+        log.insert("http.status", 200);
+
+        let row = RowBuilder::new(&[]).row(log.into()).unwrap();
+        assert_eq!(
+            row,
+            serde_json::json!({ "message": "hello", "http": { "status": 200 } })
+        );
+    }
+}
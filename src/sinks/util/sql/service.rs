@@ -0,0 +1,232 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::future::BoxFuture;
+use serde_json::Value as JsonValue;
+use snafu::{ResultExt, Snafu};
+use sqlx::{Acquire, Pool};
+use tower::Service;
+use vector_lib::event::{Event, EventFinalizers, EventStatus, Finalizable};
+use vector_lib::internal_event::{ComponentEventsDropped, UNINTENTIONAL};
+use vector_lib::request_metadata::{GroupedCountByteSize, MetaDescriptive, RequestMetadata};
+use vector_lib::stream::DriverResponse;
+use vector_lib::EstimatedJsonEncodedSizeOf;
+
+use super::{Dialect, RowBuilder};
+use crate::internal_events::EndpointBytesSent;
+use crate::sinks::prelude::{RequestMetadataBuilder, RetryLogic};
+
+pub struct SqlRetryLogic<D> {
+    dialect: Arc<D>,
+}
+
+impl<D> SqlRetryLogic<D> {
+    pub const fn new(dialect: Arc<D>) -> Self {
+        Self { dialect }
+    }
+}
+
+impl<D> Clone for SqlRetryLogic<D> {
+    fn clone(&self) -> Self {
+        Self {
+            dialect: Arc::clone(&self.dialect),
+        }
+    }
+}
+
+impl<D: Dialect> RetryLogic for SqlRetryLogic<D> {
+    type Error = SqlServiceError;
+    type Response = SqlResponse;
+
+    fn is_retriable_error(&self, error: &Self::Error) -> bool {
+        match error {
+            SqlServiceError::Database { source } => self.dialect.is_retriable_error(source),
+            SqlServiceError::VectorCommon { .. } => false,
+        }
+    }
+}
+
+pub struct SqlService<D: Dialect> {
+    connection_pool: Pool<D::Database>,
+    dialect: Arc<D>,
+    rows: Arc<RowBuilder>,
+    endpoint: String,
+}
+
+impl<D: Dialect> Clone for SqlService<D> {
+    fn clone(&self) -> Self {
+        Self {
+            connection_pool: self.connection_pool.clone(),
+            dialect: Arc::clone(&self.dialect),
+            rows: Arc::clone(&self.rows),
+            endpoint: self.endpoint.clone(),
+        }
+    }
+}
+
+impl<D: Dialect> SqlService<D> {
+    pub const fn new(
+        connection_pool: Pool<D::Database>,
+        dialect: Arc<D>,
+        rows: Arc<RowBuilder>,
+        endpoint: String,
+    ) -> Self {
+        Self {
+            connection_pool,
+            dialect,
+            rows,
+            endpoint,
+        }
+    }
+
+    /// Inserts the rows one by one within a transaction, skipping the rows the database rejects.
+    ///
+    /// Each row is inserted within a savepoint, so that a rejected row does not abort the
+    /// transaction. Any other error rolls back the whole transaction, so the batch can be retried
+    /// without writing rows twice.
+    async fn insert_rows(&self, table: &str, rows: &[JsonValue]) -> Result<usize, sqlx::Error> {
+        let mut transaction = self.connection_pool.begin().await?;
+        let mut rejected = 0;
+        for row in rows {
+            let mut savepoint = transaction.begin().await?;
+            match self.dialect.insert_row(&mut *savepoint, table, row).await {
+                Ok(()) => savepoint.commit().await?,
+                Err(error) if self.dialect.is_data_error(&error) => {
+                    warn!(
+                        message = "Row rejected by the database.",
+                        %error,
+                        internal_log_rate_limit = true,
+                    );
+                    savepoint.rollback().await?;
+                    rejected += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        transaction.commit().await?;
+        Ok(rejected)
+    }
+}
+
+#[derive(Clone)]
+pub struct SqlRequest {
+    pub table: String,
+    pub events: Vec<Event>,
+    pub finalizers: EventFinalizers,
+    pub metadata: RequestMetadata,
+}
+
+impl SqlRequest {
+    pub fn new(table: String, mut events: Vec<Event>) -> Result<Self, String> {
+        let finalizers = events.take_finalizers();
+        let metadata_builder = RequestMetadataBuilder::from_events(&events);
+        let events_size = NonZeroUsize::new(events.estimated_json_encoded_size_of().get())
+            .ok_or("payload should never be zero length")?;
+        let metadata = metadata_builder.with_request_size(events_size);
+        Ok(SqlRequest {
+            table,
+            events,
+            finalizers,
+            metadata,
+        })
+    }
+}
+
+impl Finalizable for SqlRequest {
+    fn take_finalizers(&mut self) -> EventFinalizers {
+        self.finalizers.take_finalizers()
+    }
+}
+
+impl MetaDescriptive for SqlRequest {
+    fn get_metadata(&self) -> &RequestMetadata {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut RequestMetadata {
+        &mut self.metadata
+    }
+}
+
+pub struct SqlResponse {
+    metadata: RequestMetadata,
+}
+
+impl DriverResponse for SqlResponse {
+    fn event_status(&self) -> EventStatus {
+        EventStatus::Delivered
+    }
+
+    fn events_sent(&self) -> &GroupedCountByteSize {
+        self.metadata.events_estimated_json_encoded_byte_size()
+    }
+
+    fn bytes_sent(&self) -> Option<usize> {
+        Some(self.metadata.request_encoded_size())
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum SqlServiceError {
+    #[snafu(display("Database error: {source}"))]
+    Database { source: sqlx::Error },
+
+    #[snafu(display("Serialization error: {source}"))]
+    VectorCommon { source: vector_common::Error },
+}
+
+impl<D: Dialect> Service<SqlRequest> for SqlService<D> {
+    type Response = SqlResponse;
+    type Error = SqlServiceError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: SqlRequest) -> Self::Future {
+        let service = self.clone();
+        let future = async move {
+            let table = request.table;
+            let metadata = request.metadata;
+            let rows = request
+                .events
+                .into_iter()
+                .map(|event| service.rows.row(event))
+                .collect::<Result<Vec<_>, _>>()
+                .context(VectorCommonSnafu)?;
+
+            match service
+                .dialect
+                .write_batch(&service.connection_pool, &table, &rows)
+                .await
+            {
+                Ok(()) => {}
+                Err(error) if rows.len() > 1 && service.dialect.is_data_error(&error) => {
+                    let rejected = service
+                        .insert_rows(&table, &rows)
+                        .await
+                        .context(DatabaseSnafu)?;
+                    if rejected > 0 {
+                        emit!(ComponentEventsDropped::<UNINTENTIONAL> {
+                            count: rejected,
+                            reason: "Rows rejected by the database.",
+                        });
+                    }
+                }
+                Err(error) => return Err(error).context(DatabaseSnafu),
+            }
+
+            emit!(EndpointBytesSent {
+                byte_size: metadata.request_encoded_size(),
+                protocol: D::PROTOCOL,
+                endpoint: &service.endpoint,
+            });
+
+            Ok(SqlResponse { metadata })
+        };
+
+        Box::pin(future)
+    }
+}
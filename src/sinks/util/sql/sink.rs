@@ -0,0 +1,137 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::{Dialect, SqlRequest, SqlRetryLogic, SqlService};
+use crate::{internal_events::SqlInvalidTableError, sinks::prelude::*};
+
+/// The table names templates may render to: an identifier, optionally qualified by a schema.
+///
+/// Table names are interpolated into statements, so names rendered from event fields are
+/// restricted to plain identifiers.
+static TABLE_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*(\.[A-Za-z_][A-Za-z0-9_]*)?$").expect("valid regex")
+});
+
+pub struct SqlSink<D: Dialect> {
+    service: Svc<SqlService<D>, SqlRetryLogic<D>>,
+    table: Template,
+    batch_settings: BatcherSettings,
+}
+
+impl<D: Dialect> SqlSink<D> {
+    pub const fn new(
+        service: Svc<SqlService<D>, SqlRetryLogic<D>>,
+        table: Template,
+        batch_settings: BatcherSettings,
+    ) -> Self {
+        Self {
+            service,
+            table,
+            batch_settings,
+        }
+    }
+
+    async fn run_inner(self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        let batch_settings = self.batch_settings;
+
+        input
+            .batched_partitioned(TablePartitioner::new(self.table), || {
+                batch_settings.as_byte_size_config()
+            })
+            .filter_map(|(table, events)| async move {
+                match SqlRequest::new(table?, events) {
+                    Ok(request) => Some(request),
+                    Err(e) => {
+                        warn!(
+                            message = "Error creating SQL sink's request.",
+                            protocol = D::PROTOCOL,
+                            error = %e,
+                            internal_log_rate_limit=true
+                        );
+                        None
+                    }
+                }
+            })
+            .into_driver(self.service)
+            .run()
+            .await
+    }
+}
+
+#[async_trait::async_trait]
+impl<D: Dialect> StreamSink<Event> for SqlSink<D> {
+    async fn run(mut self: Box<Self>, input: BoxStream<'_, Event>) -> Result<(), ()> {
+        self.run_inner(input).await
+    }
+}
+
+/// Partitions events by the table they are inserted into.
+struct TablePartitioner {
+    table: Template,
+}
+
+impl TablePartitioner {
+    const fn new(table: Template) -> Self {
+        Self { table }
+    }
+}
+
+impl Partitioner for TablePartitioner {
+    type Item = Event;
+    type Key = Option<String>;
+
+    fn partition(&self, item: &Self::Item) -> Self::Key {
+        let table = self
+            .table
+            .render_string(item)
+            .map_err(|error| {
+                emit!(TemplateRenderingError {
+                    error,
+                    field: Some("table"),
+                    drop_event: true,
+                });
+            })
+            .ok()?;
+        if self.table.is_dynamic() && !TABLE_NAME.is_match(&table) {
+            emit!(SqlInvalidTableError { table: &table });
+            return None;
+        }
+        Some(table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::event::LogEvent;
+
+    use super::*;
+
+    fn partition(table: &str, service: &str) -> Option<String> {
+        let mut log = LogEvent::from("hello");
+        log.insert("service", service);
+        TablePartitioner::new(Template::try_from(table).unwrap()).partition(&log.into())
+    }
+
+    #[test]
+    fn partitions_by_rendered_table() {
+        assert_eq!(
+            partition("logs_{{ service }}", "api"),
+            Some("logs_api".to_owned())
+        );
+        assert_eq!(
+            partition("{{ service }}.logs", "audit"),
+            Some("audit.logs".to_owned())
+        );
+        // Static names are used as configured.
+        assert_eq!(partition("\"Logs\"", "api"), Some("\"Logs\"".to_owned()));
+    }
+
+    #[test]
+    fn drops_events_rendering_invalid_tables() {
+        assert_eq!(partition("logs_{{ service }}", "x; DROP TABLE logs"), None);
+        assert_eq!(partition("logs_{{ service }}", "a.b.c"), None);
+        assert_eq!(partition("{{ service }}", "1logs"), None);
+        assert_eq!(partition("logs_{{ service }}", "\"x\""), None);
+    }
+}
@@ -0,0 +1,210 @@
+//! Generation of multi-row `INSERT ... VALUES` statements with positional `?` parameters, as used
+//! by MySQL and SQLite.
+
+use serde_json::Value as JsonValue;
+
+use super::{Column, ColumnConfig};
+
+/// A value bound to a parameter of a statement.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SqlValue {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl From<&JsonValue> for SqlValue {
+    fn from(value: &JsonValue) -> Self {
+        match value {
+            JsonValue::Null => Self::Null,
+            JsonValue::Bool(value) => Self::Bool(*value),
+            JsonValue::Number(number) => match (number.as_i64(), number.as_f64()) {
+                (Some(value), _) => Self::Integer(value),
+                // Integers too large for `i64` are left for the database to convert.
+                _ if number.is_u64() => Self::Text(number.to_string()),
+                (None, Some(value)) => Self::Float(value),
+                (None, None) => Self::Text(number.to_string()),
+            },
+            JsonValue::String(value) => Self::Text(value.clone()),
+            // Objects and arrays are stored as JSON.
+            value => Self::Text(value.to_string()),
+        }
+    }
+}
+
+/// Builds `INSERT ... VALUES` statements inserting rows into explicit columns.
+#[derive(Debug)]
+pub struct ValuesInsert {
+    columns: Vec<Column>,
+    quote: char,
+    verb: &'static str,
+    suffix: String,
+    rows_per_statement: usize,
+}
+
+impl ValuesInsert {
+    /// Creates a builder quoting identifiers with `quote`, whose statements bind at most
+    /// `max_parameters` parameters.
+    pub fn new(columns: &[ColumnConfig], quote: char, max_parameters: usize) -> Self {
+        Self {
+            columns: columns.iter().map(Column::from).collect(),
+            quote,
+            verb: "INSERT INTO",
+            suffix: String::new(),
+            rows_per_statement: (max_parameters / columns.len().max(1)).max(1),
+        }
+    }
+
+    /// Replaces the leading `INSERT INTO`, for example with `INSERT IGNORE INTO`.
+    pub const fn with_verb(mut self, verb: &'static str) -> Self {
+        self.verb = verb;
+        self
+    }
+
+    /// Appends a clause to the statements, such as an `ON CONFLICT` clause.
+    pub fn with_suffix(mut self, suffix: String) -> Self {
+        self.suffix = suffix;
+        self
+    }
+
+    /// Quotes an identifier, such as a column name.
+    pub fn quote_identifier(&self, identifier: &str) -> String {
+        let quote = self.quote.to_string();
+        format!(
+            "{quote}{}{quote}",
+            identifier.replace(self.quote, &quote.repeat(2))
+        )
+    }
+
+    /// Quotes a list of identifiers, separated by commas.
+    pub fn quote_identifiers(&self, identifiers: &[String]) -> String {
+        identifiers
+            .iter()
+            .map(|identifier| self.quote_identifier(identifier))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Quotes a table name, quoting the schema or database it is qualified by separately.
+    pub fn quote_table(&self, table: &str) -> String {
+        table
+            .split('.')
+            .map(|part| self.quote_identifier(part))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Splits rows into the chunks inserted by a single statement each, so that statements don't
+    /// bind more parameters than the database supports.
+    pub fn chunks<'a>(&self, rows: &'a [JsonValue]) -> std::slice::Chunks<'a, JsonValue> {
+        rows.chunks(self.rows_per_statement)
+    }
+
+    /// Returns the statement inserting `rows` rows into a table.
+    pub fn statement(&self, table: &str, rows: usize) -> String {
+        let identifiers = self
+            .columns
+            .iter()
+            .map(|column| self.quote_identifier(&column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let placeholders = self
+            .columns
+            .iter()
+            .map(|column| match &column.cast {
+                Some(cast) => format!("CAST(? AS {cast})"),
+                None => "?".to_owned(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let values = vec![format!("({placeholders})"); rows].join(", ");
+        format!(
+            "{} {} ({identifiers}) VALUES {values}{}",
+            self.verb,
+            self.quote_table(table),
+            self.suffix
+        )
+    }
+
+    /// Returns the values bound to the parameters of the statement inserting `rows`, in order.
+    pub fn values<'a>(&'a self, rows: &'a [JsonValue]) -> impl Iterator<Item = SqlValue> + 'a {
+        rows.iter().flat_map(move |row| {
+            self.columns
+                .iter()
+                .map(move |column| row.get(&column.name).map_or(SqlValue::Null, SqlValue::from))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use vector_lib::lookup::lookup_v2::ConfigValuePath;
+
+    use super::*;
+
+    fn columns() -> Vec<ColumnConfig> {
+        vec![
+            ColumnConfig {
+                name: "message".to_owned(),
+                path: None,
+                r#type: None,
+            },
+            ColumnConfig {
+                name: "status".to_owned(),
+                path: Some(ConfigValuePath::try_from("http.status".to_owned()).unwrap()),
+                r#type: Some("SIGNED".to_owned()),
+            },
+        ]
+    }
+
+    #[test]
+    fn statement() {
+        let insert = ValuesInsert::new(&columns(), '`', 65535)
+            .with_verb("INSERT IGNORE INTO")
+            .with_suffix(" ON DUPLICATE KEY UPDATE `status` = VALUES(`status`)".to_owned());
+        assert_eq!(
+            insert.statement("logs", 2),
+            "INSERT IGNORE INTO `logs` (`message`, `status`) \
+             VALUES (?, CAST(? AS SIGNED)), (?, CAST(? AS SIGNED)) \
+             ON DUPLICATE KEY UPDATE `status` = VALUES(`status`)"
+        );
+        assert_eq!(insert.quote_identifier("a`b"), "`a``b`");
+        assert_eq!(insert.quote_table("app.lo`gs"), "`app`.`lo``gs`");
+        assert_eq!(
+            ValuesInsert::new(&columns(), '"', 32766).quote_table("main.logs"),
+            "\"main\".\"logs\""
+        );
+    }
+
+    #[test]
+    fn values() {
+        let insert = ValuesInsert::new(&columns(), '"', 32766);
+        let rows = [
+            json!({ "message": "hello", "status": 200 }),
+            json!({ "message": { "nested": true }, "status": 1.5 }),
+            json!({ "status": u64::MAX }),
+        ];
+        assert_eq!(
+            insert.values(&rows).collect::<Vec<_>>(),
+            vec![
+                SqlValue::Text("hello".to_owned()),
+                SqlValue::Integer(200),
+                SqlValue::Text("{\"nested\":true}".to_owned()),
+                SqlValue::Float(1.5),
+                SqlValue::Null,
+                SqlValue::Text(u64::MAX.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn chunks_rows_by_parameters() {
+        let insert = ValuesInsert::new(&columns(), '"', 5);
+        let rows = vec![json!({}); 5];
+        let chunks = insert.chunks(&rows).map(<[_]>::len).collect::<Vec<_>>();
+        assert_eq!(chunks, vec![2, 2, 1]);
+    }
+}
@@ -20,8 +20,8 @@ use crate::{
     proto::vector as proto,
     sinks::{
         util::{
-            retries::RetryLogic, BatchConfig, RealtimeEventBasedDefaultBatchSettings,
-            ServiceBuilderExt, TowerRequestConfig,
+            load_balance::LoadBalanceConfig, retries::RetryLogic, BatchConfig,
+            RealtimeEventBasedDefaultBatchSettings, ServiceBuilderExt, TowerRequestConfig,
         },
         Healthcheck, VectorSink as VectorSinkType,
     },
@@ -70,6 +70,10 @@ pub struct VectorConfig {
     #[serde(default)]
     tls: Option<TlsEnableableConfig>,
 
+    #[configurable(derived)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    load_balance: Option<LoadBalanceConfig>,
+
     #[configurable(derived)]
     #[serde(
         default,
@@ -101,6 +105,7 @@ fn default_config(address: &str) -> VectorConfig {
         batch: BatchConfig::default(),
         request: TowerRequestConfig::default(),
         tls: None,
+        load_balance: None,
         acknowledgements: Default::default(),
     }
 }
@@ -120,9 +125,14 @@ impl SinkConfig for VectorConfig {
             .clone()
             .map(|uri| uri.uri)
             .unwrap_or_else(|| uri.clone());
-        let healthcheck_client = VectorService::new(client.clone(), healthcheck_uri, false);
+        let healthcheck_client = VectorService::new(client.clone(), healthcheck_uri, None, false);
         let healthcheck = healthcheck(healthcheck_client, cx.healthcheck);
-        let service = VectorService::new(client, uri, self.compression);
+        let load_balancer = self
+            .load_balance
+            .as_ref()
+            .map(LoadBalanceConfig::build)
+            .transpose()?;
+        let service = VectorService::new(client, uri, load_balancer, self.compression);
         let request_settings = self.request.into_settings();
         let batch_settings = self.batch.into_batcher_settings()?;
 
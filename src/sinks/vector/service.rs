@@ -16,7 +16,7 @@ use crate::{
     event::{EventFinalizers, EventStatus, Finalizable},
     internal_events::EndpointBytesSent,
     proto::vector as proto_vector,
-    sinks::util::uri,
+    sinks::util::{load_balance::LoadBalancer, uri},
     Error,
 };
 
@@ -68,12 +68,14 @@ impl VectorService {
     pub fn new(
         hyper_client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
         uri: Uri,
+        load_balancer: Option<LoadBalancer>,
         compression: bool,
     ) -> Self {
         let (protocol, endpoint) = uri::protocol_endpoint(uri.clone());
         let mut proto_client = proto_vector::Client::new(HyperSvc {
             uri,
             client: hyper_client,
+            load_balancer,
         });
 
         if compression {
@@ -134,6 +136,7 @@ impl Service<VectorRequest> for VectorService {
 pub struct HyperSvc {
     uri: Uri,
     client: hyper::Client<ProxyConnector<HttpsConnector<HttpConnector>>, BoxBody>,
+    load_balancer: Option<LoadBalancer>,
 }
 
 impl Service<hyper::Request<BoxBody>> for HyperSvc {
@@ -157,6 +160,22 @@ impl Service<hyper::Request<BoxBody>> for HyperSvc {
 
         *req.uri_mut() = uri;
 
-        Box::pin(self.client.request(req))
+        let endpoint = self
+            .load_balancer
+            .as_ref()
+            .and_then(|load_balancer| load_balancer.route(&mut req));
+        let response = self.client.request(req);
+
+        Box::pin(async move {
+            let response = response.await;
+            if let Some(endpoint) = endpoint {
+                endpoint.record(
+                    response
+                        .as_ref()
+                        .is_ok_and(|response| !response.status().is_server_error()),
+                );
+            }
+            response
+        })
     }
 }
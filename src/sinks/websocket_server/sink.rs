@@ -214,7 +214,14 @@ impl WebSocketListenerSink {
                 ));
                 return Ok(response);
             };
-            match auth.handle_auth(Some(&addr), req.headers(), req.uri().path()) {
+            match auth.handle_auth(
+                Some(&addr),
+                req.method(),
+                req.headers(),
+                req.uri().path(),
+                req.uri().query(),
+                &[],
+            ) {
                 Ok(_) => {
                     extra_tags.append(&mut Self::extract_extra_tags(
                         &extra_tags_config,
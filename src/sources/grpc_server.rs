@@ -0,0 +1,694 @@
+//! The `grpc_server` source. See [GrpcServerConfig].
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::Future,
+    net::SocketAddr,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::{Buf, BufMut, Bytes};
+use chrono::Utc;
+use futures::{StreamExt, TryFutureExt};
+use hyper::Body;
+use prost_reflect::{DescriptorPool, MethodDescriptor};
+use snafu::{ResultExt, Snafu};
+use tonic::{
+    body::BoxBody,
+    codec::{Codec, CompressionEncoding, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    server::{ClientStreamingService, Grpc, UnaryService},
+    transport::server::Routes,
+    Request, Response, Status, Streaming,
+};
+use tower::Service;
+use vector_lib::codecs::decoding::{
+    format::Deserializer as _, ProtobufDeserializer, ProtobufDeserializerConfig,
+};
+use vector_lib::configurable::configurable_component;
+use vector_lib::internal_event::{CountByteSize, InternalEventHandle as _, Registered};
+use vector_lib::lookup::owned_value_path;
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    event::{BatchNotifier, BatchStatus, BatchStatusReceiver, Event},
+    EstimatedJsonEncodedSizeOf,
+};
+use vrl::{path, value::Kind};
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Resource, SourceAcknowledgementsConfig, SourceConfig,
+        SourceContext, SourceOutput,
+    },
+    internal_events::{DecoderDeserializeError, EventsReceived, StreamClosedError},
+    serde::bool_or_struct,
+    sources::{util::grpc::run_grpc_server_with_routes, Source},
+    tls::{MaybeTlsSettings, TlsEnableableConfig},
+    SourceSender,
+};
+
+#[derive(Debug, Snafu)]
+enum BuildError {
+    #[snafu(display("Could not read descriptor set file {:?}: {}", path, source))]
+    ReadDescriptorSet {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Invalid descriptor set file {:?}: {}", path, source))]
+    DecodeDescriptorSet {
+        path: PathBuf,
+        source: prost_reflect::DescriptorError,
+    },
+
+    #[snafu(display("Method {:?} is not of the form `package.Service/Method`", method))]
+    InvalidMethodName { method: String },
+
+    #[snafu(display("Service {:?} is not defined in the descriptor set", service))]
+    UnknownService { service: String },
+
+    #[snafu(display("Method {:?} is not defined in the descriptor set", method))]
+    UnknownMethod { method: String },
+
+    #[snafu(display(
+        "Method {:?} streams responses, only unary and client-streaming methods are supported",
+        method
+    ))]
+    ServerStreaming { method: String },
+}
+
+/// Configuration for the `grpc_server` source.
+#[configurable_component(source(
+    "grpc_server",
+    "Receive protobuf messages sent to user-defined gRPC methods."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct GrpcServerConfig {
+    /// The socket address to listen for connections on.
+    ///
+    /// It _must_ include a port.
+    #[configurable(metadata(docs::examples = "0.0.0.0:50051"))]
+    address: SocketAddr,
+
+    /// The path to the protobuf descriptor set file describing the services.
+    ///
+    /// This file is the output of `protoc -I <include path> --include_imports -o <desc output path> <proto>`.
+    #[configurable(metadata(docs::examples = "/etc/vector/ingest.desc"))]
+    desc_file: PathBuf,
+
+    /// The methods to expose, given as `package.Service/Method`.
+    ///
+    /// Only unary and client-streaming methods are supported. Each request message is decoded
+    /// into an event, and the response, the default instance of the method's output message, is
+    /// only sent once the events have been acknowledged.
+    #[configurable(metadata(docs::examples = "ingest.v1.IngestService/Push"))]
+    methods: Vec<String>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    log_namespace: Option<bool>,
+}
+
+impl GenerateConfig for GrpcServerConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            address: "0.0.0.0:50051".parse().unwrap(),
+            desc_file: PathBuf::from("/etc/vector/ingest.desc"),
+            methods: vec!["ingest.v1.IngestService/Push".to_owned()],
+            tls: None,
+            acknowledgements: Default::default(),
+            log_namespace: None,
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "grpc_server")]
+impl SourceConfig for GrpcServerConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<Source> {
+        let tls_settings = MaybeTlsSettings::from_config(self.tls.as_ref(), true)?;
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        let pool = load_descriptor_pool(&self.desc_file)?;
+        let mut methods = HashMap::new();
+        let mut services = Vec::new();
+        for name in &self.methods {
+            let method = find_method(&pool, name)?;
+            let service = method.parent_service().full_name().to_owned();
+            if !services.contains(&service) {
+                services.push(service);
+            }
+            methods.insert(
+                format!("/{name}"),
+                MethodHandler {
+                    path: format!("/{name}"),
+                    deserializer: ProtobufDeserializer::new(method.input()),
+                    client_streaming: method.is_client_streaming(),
+                },
+            );
+        }
+
+        let service = GrpcService {
+            methods: Arc::new(methods),
+            pipeline: cx.out,
+            acknowledgements: cx.do_acknowledgements(self.acknowledgements),
+            log_namespace,
+            events_received: register!(EventsReceived),
+        };
+
+        // Services are only known at runtime, so they can't be added as `NamedService`s.
+        let router = services.iter().fold(axum::Router::new(), |router, name| {
+            router.route_service(&format!("/{name}/*rest"), service.clone())
+        });
+
+        let source = run_grpc_server_with_routes(
+            self.address,
+            tls_settings,
+            Routes::from(router),
+            cx.shutdown,
+        )
+        .map_err(|error| {
+            error!(message = "Source future failed.", %error);
+        });
+
+        Ok(Box::pin(source))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+
+        let schema_definition = ProtobufDeserializerConfig::default()
+            .schema_definition(log_namespace)
+            .with_standard_vector_source_metadata()
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::InsertIfEmpty(owned_value_path!("grpc_method"))),
+                &owned_value_path!("method"),
+                Kind::bytes(),
+                None,
+            );
+
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+fn load_descriptor_pool(path: &PathBuf) -> Result<DescriptorPool, BuildError> {
+    let bytes = std::fs::read(path).context(ReadDescriptorSetSnafu { path })?;
+    DescriptorPool::decode(bytes.as_slice()).context(DecodeDescriptorSetSnafu { path })
+}
+
+fn find_method(pool: &DescriptorPool, name: &str) -> Result<MethodDescriptor, BuildError> {
+    let (service, method_name) =
+        name.split_once('/')
+            .ok_or_else(|| BuildError::InvalidMethodName {
+                method: name.to_owned(),
+            })?;
+    let service = pool
+        .get_service_by_name(service)
+        .ok_or_else(|| BuildError::UnknownService {
+            service: service.to_owned(),
+        })?;
+    let method = service
+        .methods()
+        .find(|method| method.name() == method_name)
+        .ok_or_else(|| BuildError::UnknownMethod {
+            method: name.to_owned(),
+        })?;
+    if method.is_server_streaming() {
+        return Err(BuildError::ServerStreaming {
+            method: name.to_owned(),
+        });
+    }
+    Ok(method)
+}
+
+/// A configured method, keyed by its request path.
+#[derive(Clone, Debug)]
+struct MethodHandler {
+    path: String,
+    deserializer: ProtobufDeserializer,
+    client_streaming: bool,
+}
+
+/// Serves all configured methods, dispatching requests on their path.
+#[derive(Clone)]
+struct GrpcService {
+    methods: Arc<HashMap<String, MethodHandler>>,
+    pipeline: SourceSender,
+    acknowledgements: bool,
+    log_namespace: LogNamespace,
+    events_received: Registered<EventsReceived>,
+}
+
+impl Service<http::Request<Body>> for GrpcService {
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            let Some(method) = service.methods.get(request.uri().path()).cloned() else {
+                return Ok(Status::unimplemented("Method is not exposed by this source").to_http());
+            };
+
+            let mut grpc = Grpc::new(RawCodec)
+                .accept_compressed(CompressionEncoding::Gzip)
+                // Tonic added a default of 4MB in 0.9. This replaces the old behavior.
+                .max_decoding_message_size(usize::MAX);
+            let handler = RequestHandler { service, method };
+            let response = if handler.method.client_streaming {
+                grpc.client_streaming(handler, request).await
+            } else {
+                grpc.unary(handler, request).await
+            };
+            Ok(response)
+        })
+    }
+}
+
+/// Handles a single request to a method.
+#[derive(Clone)]
+struct RequestHandler {
+    service: GrpcService,
+    method: MethodHandler,
+}
+
+impl RequestHandler {
+    fn decode(&self, message: Bytes) -> Result<Vec<Event>, Status> {
+        let events = self
+            .method
+            .deserializer
+            .parse(message, self.service.log_namespace)
+            .map_err(|error| {
+                emit!(DecoderDeserializeError { error: &error });
+                Status::invalid_argument(format!("Invalid request message: {error}"))
+            })?;
+
+        let now = Utc::now();
+        Ok(events
+            .into_iter()
+            .map(|mut event| {
+                if let Event::Log(log) = &mut event {
+                    self.service
+                        .log_namespace
+                        .insert_standard_vector_source_metadata(log, GrpcServerConfig::NAME, now);
+                    self.service.log_namespace.insert_source_metadata(
+                        GrpcServerConfig::NAME,
+                        log,
+                        Some(LegacyKey::InsertIfEmpty(path!("grpc_method"))),
+                        path!("method"),
+                        self.method.path.clone(),
+                    );
+                }
+                event
+            })
+            .collect())
+    }
+
+    async fn send(&self, events: Vec<Event>) -> Result<(), Status> {
+        let count = events.len();
+        let byte_size = events.estimated_json_encoded_size_of();
+        self.service
+            .events_received
+            .emit(CountByteSize(count, byte_size));
+
+        self.service
+            .pipeline
+            .clone()
+            .send_batch(events)
+            .await
+            .map_err(|error| {
+                emit!(StreamClosedError { count });
+                Status::unavailable(error.to_string())
+            })
+    }
+}
+
+impl UnaryService<Bytes> for RequestHandler {
+    type Response = Bytes;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Bytes>, Status>> + Send>>;
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let handler = self.clone();
+        Box::pin(async move {
+            let mut events = handler.decode(request.into_inner())?;
+            let receiver =
+                BatchNotifier::maybe_apply_to(handler.service.acknowledgements, &mut events);
+            handler.send(events).await?;
+            handle_batch_status(receiver).await?;
+            Ok(Response::new(empty_response()))
+        })
+    }
+}
+
+impl ClientStreamingService<Bytes> for RequestHandler {
+    type Response = Bytes;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Bytes>, Status>> + Send>>;
+
+    fn call(&mut self, request: Request<Streaming<Bytes>>) -> Self::Future {
+        let handler = self.clone();
+        Box::pin(async move {
+            // All messages of a stream share one notifier, so the response reflects all of them.
+            let (batch, receiver) =
+                BatchNotifier::maybe_new_with_receiver(handler.service.acknowledgements);
+            let mut stream = request.into_inner();
+            while let Some(message) = stream.next().await {
+                let events = handler
+                    .decode(message?)?
+                    .into_iter()
+                    .map(|event| event.with_batch_notifier_option(&batch))
+                    .collect();
+                handler.send(events).await?;
+            }
+            drop(batch);
+
+            handle_batch_status(receiver).await?;
+            Ok(Response::new(empty_response()))
+        })
+    }
+}
+
+/// The response to every request is the default instance of the output message, which always
+/// encodes to an empty payload.
+const fn empty_response() -> Bytes {
+    Bytes::new()
+}
+
+async fn handle_batch_status(receiver: Option<BatchStatusReceiver>) -> Result<(), Status> {
+    let status = match receiver {
+        Some(receiver) => receiver.await,
+        None => BatchStatus::Delivered,
+    };
+
+    match status {
+        BatchStatus::Errored => Err(Status::internal("Delivery error")),
+        BatchStatus::Rejected => Err(Status::data_loss("Delivery failed")),
+        BatchStatus::Delivered => Ok(()),
+    }
+}
+
+/// A codec passing messages through as raw bytes, leaving decoding to the deserializer.
+#[derive(Clone, Copy, Debug, Default)]
+struct RawCodec;
+
+impl Codec for RawCodec {
+    type Encode = Bytes;
+    type Decode = Bytes;
+    type Encoder = Self;
+    type Decoder = Self;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        *self
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        *self
+    }
+}
+
+impl Encoder for RawCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put(item);
+        Ok(())
+    }
+}
+
+impl Decoder for RawCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::uri::PathAndQuery;
+    use prost::Message as _;
+    use prost_reflect::DynamicMessage;
+    use prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+        MethodDescriptorProto, ServiceDescriptorProto,
+    };
+    use tonic::transport::Channel;
+    use vector_lib::{config::log_schema, event::EventStatus};
+
+    use super::*;
+    use crate::test_util::{
+        collect_n,
+        components::{assert_source_compliance, SOURCE_TAGS},
+        next_addr, temp_file, wait_for_tcp,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<GrpcServerConfig>();
+    }
+
+    fn method(name: &str, client_streaming: bool, server_streaming: bool) -> MethodDescriptorProto {
+        MethodDescriptorProto {
+            name: Some(name.to_owned()),
+            input_type: Some(".test.Entry".to_owned()),
+            output_type: Some(".test.Ack".to_owned()),
+            client_streaming: Some(client_streaming),
+            server_streaming: Some(server_streaming),
+            ..Default::default()
+        }
+    }
+
+    fn descriptor_set() -> FileDescriptorSet {
+        let field = |name: &str, number, r#type: Type| FieldDescriptorProto {
+            name: Some(name.to_owned()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(r#type as i32),
+            json_name: Some(name.to_owned()),
+            ..Default::default()
+        };
+        FileDescriptorSet {
+            file: vec![FileDescriptorProto {
+                name: Some("test.proto".to_owned()),
+                package: Some("test".to_owned()),
+                syntax: Some("proto3".to_owned()),
+                message_type: vec![
+                    DescriptorProto {
+                        name: Some("Entry".to_owned()),
+                        field: vec![
+                            field("message", 1, Type::String),
+                            field("level", 2, Type::Int32),
+                        ],
+                        ..Default::default()
+                    },
+                    DescriptorProto {
+                        name: Some("Ack".to_owned()),
+                        ..Default::default()
+                    },
+                ],
+                service: vec![ServiceDescriptorProto {
+                    name: Some("Ingest".to_owned()),
+                    method: vec![
+                        method("Push", false, false),
+                        method("PushStream", true, false),
+                        method("Subscribe", false, true),
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn config(address: SocketAddr) -> GrpcServerConfig {
+        let desc_file = temp_file();
+        std::fs::write(&desc_file, descriptor_set().encode_to_vec()).unwrap();
+        GrpcServerConfig {
+            address,
+            desc_file,
+            methods: vec![
+                "test.Ingest/Push".to_owned(),
+                "test.Ingest/PushStream".to_owned(),
+            ],
+            tls: None,
+            acknowledgements: Default::default(),
+            log_namespace: None,
+        }
+    }
+
+    fn entry(message: &str, level: i32) -> Bytes {
+        let pool = DescriptorPool::decode(descriptor_set().encode_to_vec().as_slice()).unwrap();
+        let mut entry = DynamicMessage::new(pool.get_message_by_name("test.Entry").unwrap());
+        entry.set_field_by_name("message", prost_reflect::Value::String(message.to_owned()));
+        entry.set_field_by_name("level", prost_reflect::Value::I32(level));
+        entry.encode_to_vec().into()
+    }
+
+    async fn client(address: SocketAddr) -> tonic::client::Grpc<Channel> {
+        let channel = Channel::from_shared(format!("http://{address}"))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut client = tonic::client::Grpc::new(channel);
+        client.ready().await.unwrap();
+        client
+    }
+
+    #[tokio::test]
+    async fn receives_unary_and_streaming_requests() {
+        assert_source_compliance(&SOURCE_TAGS, async {
+            let address = next_addr();
+            let (tx, rx) = SourceSender::new_test();
+            let source = config(address)
+                .build(SourceContext::new_test(tx, None))
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            wait_for_tcp(address).await;
+
+            let mut client = client(address).await;
+            let response = client
+                .unary(
+                    Request::new(entry("first", 1)),
+                    PathAndQuery::from_static("/test.Ingest/Push"),
+                    RawCodec,
+                )
+                .await
+                .unwrap();
+            assert!(response.into_inner().is_empty());
+
+            client.ready().await.unwrap();
+            client
+                .client_streaming(
+                    Request::new(futures::stream::iter([
+                        entry("second", 2),
+                        entry("third", 3),
+                    ])),
+                    PathAndQuery::from_static("/test.Ingest/PushStream"),
+                    RawCodec,
+                )
+                .await
+                .unwrap();
+
+            let events = collect_n(rx, 3).await;
+            let messages = events
+                .iter()
+                .map(|event| event.as_log()["message"].to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            assert_eq!(messages, ["first", "second", "third"]);
+
+            let log = events[2].as_log();
+            assert_eq!(log["level"], 3.into());
+            assert_eq!(log["grpc_method"], "/test.Ingest/PushStream".into());
+            assert_eq!(
+                log[log_schema().source_type_key().unwrap().to_string()],
+                "grpc_server".into()
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_methods_and_invalid_messages() {
+        let address = next_addr();
+        let (tx, _rx) = SourceSender::new_test();
+        let source = config(address)
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        wait_for_tcp(address).await;
+
+        let mut client = client(address).await;
+        let status = client
+            .unary(
+                Request::new(entry("ignored", 0)),
+                PathAndQuery::from_static("/test.Ingest/Subscribe"),
+                RawCodec,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unimplemented);
+
+        client.ready().await.unwrap();
+        let status = client
+            .unary(
+                Request::new(Bytes::from_static(&[0xff, 0xff])),
+                PathAndQuery::from_static("/test.Ingest/Push"),
+                RawCodec,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn responds_with_delivery_status() {
+        let address = next_addr();
+        let (tx, rx) = SourceSender::new_test_finalize(EventStatus::Rejected);
+        let mut config = config(address);
+        config.acknowledgements = true.into();
+        let source = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+        wait_for_tcp(address).await;
+
+        let mut client = client(address).await;
+        let status = client
+            .unary(
+                Request::new(entry("rejected", 1)),
+                PathAndQuery::from_static("/test.Ingest/Push"),
+                RawCodec,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::DataLoss);
+        assert_eq!(collect_n(rx, 1).await.len(), 1);
+    }
+
+    #[test]
+    fn rejects_server_streaming_methods() {
+        let mut config = config(next_addr());
+        config.methods = vec!["test.Ingest/Subscribe".to_owned()];
+        let (tx, _rx) = SourceSender::new_test();
+        let error = futures::executor::block_on(config.build(SourceContext::new_test(tx, None)))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("streams responses"));
+    }
+}
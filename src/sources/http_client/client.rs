@@ -16,7 +16,7 @@ use crate::sources::util::http_client;
 use crate::{
     codecs::{Decoder, DecodingConfig},
     config::{SourceConfig, SourceContext},
-    http::Auth,
+    http::HttpClientAuth,
     serde::{default_decoding, default_framing_message_based},
     sources,
     sources::util::{
@@ -120,7 +120,7 @@ pub struct HttpClientConfig {
 
     /// HTTP Authentication.
     #[configurable(derived)]
-    pub auth: Option<Auth>,
+    pub auth: Option<HttpClientAuth>,
 
     /// The namespace to use for logs. This overrides the global setting.
     #[configurable(metadata(docs::hidden))]
@@ -333,13 +333,18 @@ impl SourceConfig for HttpClientConfig {
 
         warn_if_interval_too_low(self.timeout, self.interval);
 
+        let auth = match &self.auth {
+            Some(auth) => Some(auth.build_authenticator(tls.clone(), &cx.proxy)?),
+            None => None,
+        };
+
         let inputs = GenericHttpClientInputs {
             urls,
             interval: self.interval,
             timeout: self.timeout,
             headers: self.headers.clone(),
             content_type,
-            auth: self.auth.clone(),
+            auth,
             tls,
             proxy: cx.proxy.clone(),
             shutdown: cx.shutdown,
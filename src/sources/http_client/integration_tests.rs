@@ -8,7 +8,7 @@ use tokio::time::{Duration, Instant};
 use crate::sources::util::http::HttpMethod;
 use crate::{
     config::{ComponentKey, SourceConfig, SourceContext},
-    http::Auth,
+    http::HttpClientAuth,
     serde::default_decoding,
     serde::default_framing_message_based,
     tls,
@@ -203,7 +203,7 @@ async fn unauthorized_wrong_auth() {
         headers: HashMap::new(),
         method: HttpMethod::Get,
         tls: None,
-        auth: Some(Auth::Basic {
+        auth: Some(HttpClientAuth::Basic {
             user: "white_rabbit".to_string(),
             password: "morpheus".to_string().into(),
         }),
@@ -225,7 +225,7 @@ async fn authorized() {
         headers: HashMap::new(),
         method: HttpMethod::Get,
         tls: None,
-        auth: Some(Auth::Basic {
+        auth: Some(HttpClientAuth::Basic {
             user: "user".to_string(),
             password: "pass".to_string().into(),
         }),
@@ -0,0 +1,587 @@
+use std::{collections::HashMap, iter, net::SocketAddr};
+
+use bytes::Bytes;
+use chrono::{DateTime, TimeZone, Utc};
+use loki_logproto::logproto;
+use prost::Message;
+use serde::Deserialize;
+use vector_lib::codecs::BytesDeserializerConfig;
+use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::{owned_value_path, path};
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    schema::Definition,
+};
+use vrl::value::{kind::Collection, Kind, ObjectMap, Value};
+use warp::http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+
+use crate::{
+    common::http::{server_auth::HttpServerAuthConfig, ErrorMessage},
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceAcknowledgementsConfig, SourceConfig,
+        SourceContext, SourceOutput,
+    },
+    event::{Event, LogEvent},
+    http::KeepaliveConfig,
+    internal_events::LokiPushRequestParseError,
+    serde::bool_or_struct,
+    sources::{
+        self,
+        util::{decode, http::HttpMethod, HttpSource},
+    },
+    tls::TlsEnableableConfig,
+};
+
+/// The path of the Loki push API.
+const PUSH_PATH: &str = "/loki/api/v1/push";
+
+/// The header holding the tenant of a request, in multi-tenant Loki deployments.
+const TENANT_HEADER: &str = "X-Scope-OrgID";
+
+const LABELS: &str = "labels";
+const STRUCTURED_METADATA: &str = "structured_metadata";
+const TENANT_ID: &str = "tenant_id";
+
+/// Configuration for the `loki` source.
+#[configurable_component(source("loki", "Receive logs sent to the Loki push API."))]
+#[derive(Clone, Debug)]
+pub struct LokiConfig {
+    /// The socket address to accept connections on.
+    ///
+    /// The address _must_ include a port.
+    #[configurable(metadata(docs::examples = "0.0.0.0:3100"))]
+    address: SocketAddr,
+
+    #[configurable(derived)]
+    tls: Option<TlsEnableableConfig>,
+
+    #[configurable(derived)]
+    #[configurable(metadata(docs::advanced))]
+    auth: Option<HttpServerAuthConfig>,
+
+    #[configurable(derived)]
+    #[serde(default, deserialize_with = "bool_or_struct")]
+    acknowledgements: SourceAcknowledgementsConfig,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[configurable(metadata(docs::hidden))]
+    #[serde(default)]
+    log_namespace: Option<bool>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    keepalive: KeepaliveConfig,
+}
+
+impl LokiConfig {
+    /// Builds the `schema::Definition` for this source using the provided `LogNamespace`.
+    fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
+        BytesDeserializerConfig
+            .schema_definition(log_namespace)
+            .with_standard_vector_source_metadata()
+            .with_source_metadata(
+                Self::NAME,
+                log_schema()
+                    .timestamp_key()
+                    .cloned()
+                    .map(LegacyKey::Overwrite),
+                &owned_value_path!("timestamp"),
+                Kind::timestamp(),
+                Some("timestamp"),
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!(LABELS))),
+                &owned_value_path!(LABELS),
+                Kind::object(Collection::empty().with_unknown(Kind::bytes())),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!(STRUCTURED_METADATA))),
+                &owned_value_path!(STRUCTURED_METADATA),
+                Kind::object(Collection::empty().with_unknown(Kind::bytes())).or_undefined(),
+                None,
+            )
+            .with_source_metadata(
+                Self::NAME,
+                Some(LegacyKey::Overwrite(owned_value_path!(TENANT_ID))),
+                &owned_value_path!(TENANT_ID),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+    }
+}
+
+impl Default for LokiConfig {
+    fn default() -> Self {
+        Self {
+            address: "0.0.0.0:3100".parse().unwrap(),
+            tls: None,
+            auth: None,
+            acknowledgements: SourceAcknowledgementsConfig::default(),
+            log_namespace: None,
+            keepalive: KeepaliveConfig::default(),
+        }
+    }
+}
+
+impl GenerateConfig for LokiConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self::default()).unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "loki")]
+impl SourceConfig for LokiConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<sources::Source> {
+        let source = LokiSource {
+            log_namespace: cx.log_namespace(self.log_namespace),
+        };
+        source.run(
+            self.address,
+            PUSH_PATH,
+            HttpMethod::Post,
+            StatusCode::NO_CONTENT,
+            true,
+            self.tls.as_ref(),
+            self.auth.as_ref(),
+            cx,
+            self.acknowledgements,
+            self.keepalive.clone(),
+        )
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let schema_definition =
+            self.schema_definition(global_log_namespace.merge(self.log_namespace));
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            schema_definition,
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![Resource::tcp(self.address)]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        true
+    }
+}
+
+/// A push request in the JSON format of the Loki push API.
+#[derive(Deserialize)]
+struct JsonPushRequest {
+    streams: Vec<JsonStream>,
+}
+
+#[derive(Deserialize)]
+struct JsonStream {
+    #[serde(default)]
+    stream: HashMap<String, String>,
+    values: Vec<JsonEntry>,
+}
+
+/// An entry, made of its timestamp in nanoseconds, its line, and its optional structured metadata.
+#[derive(Deserialize)]
+struct JsonEntry(String, String, #[serde(default)] HashMap<String, String>);
+
+#[derive(Clone)]
+struct LokiSource {
+    log_namespace: LogNamespace,
+}
+
+impl LokiSource {
+    fn decode_protobuf(
+        &self,
+        body: Bytes,
+        tenant_id: Option<&str>,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        // Protobuf push requests are always compressed with snappy.
+        let body = decode(Some("snappy"), body)?;
+        let request = logproto::PushRequest::decode(body)
+            .map_err(|error| parse_error(format!("Could not decode push request: {error}")))?;
+
+        let now = Utc::now();
+        let mut events = Vec::new();
+        for stream in request.streams {
+            let labels = parse_labels(&stream.labels)
+                .ok_or_else(|| parse_error(format!("Invalid stream labels: {}", stream.labels)))?;
+            for entry in stream.entries {
+                let timestamp = entry
+                    .timestamp
+                    .and_then(|timestamp| {
+                        Utc.timestamp_opt(timestamp.seconds, timestamp.nanos as u32)
+                            .single()
+                    })
+                    .ok_or_else(|| parse_error("Invalid entry timestamp.".to_owned()))?;
+                let structured_metadata = entry
+                    .structured_metadata
+                    .into_iter()
+                    .map(|pair| (pair.name.into(), pair.value.into()))
+                    .collect();
+                events.push(self.build_event(
+                    entry.line,
+                    timestamp,
+                    &labels,
+                    structured_metadata,
+                    tenant_id,
+                    now,
+                ));
+            }
+        }
+        Ok(events)
+    }
+
+    fn decode_json(
+        &self,
+        body: Bytes,
+        tenant_id: Option<&str>,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        let request: JsonPushRequest = serde_json::from_slice(&body)
+            .map_err(|error| parse_error(format!("Could not decode push request: {error}")))?;
+
+        let now = Utc::now();
+        let mut events = Vec::new();
+        for stream in request.streams {
+            let labels = into_object(stream.stream);
+            for JsonEntry(timestamp, line, structured_metadata) in stream.values {
+                let timestamp = timestamp
+                    .parse::<i64>()
+                    .map(|nanos| Utc.timestamp_nanos(nanos))
+                    .map_err(|_| parse_error(format!("Invalid entry timestamp: {timestamp}")))?;
+                events.push(self.build_event(
+                    line,
+                    timestamp,
+                    &labels,
+                    into_object(structured_metadata),
+                    tenant_id,
+                    now,
+                ));
+            }
+        }
+        Ok(events)
+    }
+
+    fn build_event(
+        &self,
+        line: String,
+        timestamp: DateTime<Utc>,
+        labels: &ObjectMap,
+        structured_metadata: ObjectMap,
+        tenant_id: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Event {
+        let mut log = match self.log_namespace {
+            LogNamespace::Vector => LogEvent::from(Value::from(line)),
+            LogNamespace::Legacy => {
+                let mut log = LogEvent::default();
+                log.maybe_insert(log_schema().message_key_target_path(), line);
+                log
+            }
+        };
+
+        self.log_namespace.insert_source_metadata(
+            LokiConfig::NAME,
+            &mut log,
+            log_schema().timestamp_key().map(LegacyKey::Overwrite),
+            path!("timestamp"),
+            timestamp,
+        );
+        self.log_namespace.insert_source_metadata(
+            LokiConfig::NAME,
+            &mut log,
+            Some(LegacyKey::Overwrite(path!(LABELS))),
+            path!(LABELS),
+            labels.clone(),
+        );
+        if !structured_metadata.is_empty() {
+            self.log_namespace.insert_source_metadata(
+                LokiConfig::NAME,
+                &mut log,
+                Some(LegacyKey::Overwrite(path!(STRUCTURED_METADATA))),
+                path!(STRUCTURED_METADATA),
+                structured_metadata,
+            );
+        }
+        if let Some(tenant_id) = tenant_id {
+            self.log_namespace.insert_source_metadata(
+                LokiConfig::NAME,
+                &mut log,
+                Some(LegacyKey::Overwrite(path!(TENANT_ID))),
+                path!(TENANT_ID),
+                tenant_id.to_owned(),
+            );
+        }
+        self.log_namespace
+            .insert_standard_vector_source_metadata(&mut log, LokiConfig::NAME, now);
+
+        log.into()
+    }
+}
+
+impl HttpSource for LokiSource {
+    fn build_events(
+        &self,
+        body: Bytes,
+        header_map: &HeaderMap,
+        _query_parameters: &HashMap<String, String>,
+        _full_path: &str,
+    ) -> Result<Vec<Event>, ErrorMessage> {
+        let tenant_id = header_map
+            .get(TENANT_HEADER)
+            .and_then(|value| value.to_str().ok());
+        // Like Loki, anything but JSON is decoded as protobuf.
+        let is_json = header_map
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|content_type| content_type.split(';').next())
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"));
+
+        if is_json {
+            self.decode_json(body, tenant_id)
+        } else {
+            self.decode_protobuf(body, tenant_id)
+        }
+    }
+}
+
+fn parse_error(message: String) -> ErrorMessage {
+    emit!(LokiPushRequestParseError { error: &message });
+    ErrorMessage::new(StatusCode::BAD_REQUEST, message)
+}
+
+fn into_object(map: HashMap<String, String>) -> ObjectMap {
+    map.into_iter()
+        .map(|(name, value)| (name.into(), value.into()))
+        .collect()
+}
+
+/// Parses the labels of a stream, serialized like `{name="value", ...}`.
+fn parse_labels(labels: &str) -> Option<ObjectMap> {
+    let mut chars = labels
+        .trim()
+        .strip_prefix('{')?
+        .strip_suffix('}')?
+        .chars()
+        .peekable();
+    let mut map = ObjectMap::new();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Some(map);
+        }
+
+        let name = iter::from_fn(|| chars.next_if(|&c| c != '=' && !c.is_whitespace()))
+            .collect::<String>();
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if name.is_empty() || chars.next() != Some('=') {
+            return None;
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.next() != Some('"') {
+            return None;
+        }
+
+        let mut value = String::new();
+        loop {
+            match chars.next()? {
+                '"' => break,
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    c => value.push(c),
+                },
+                c => value.push(c),
+            }
+        }
+        map.insert(name.into(), value.into());
+
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.next() {
+            Some(',') | None => {}
+            Some(_) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use loki_logproto::util::{Batch, Entry, Stream};
+    use vector_lib::event::EventStatus;
+    use vrl::value;
+
+    use super::*;
+    use crate::{
+        test_util::{
+            components::{assert_source_compliance, HTTP_PUSH_SOURCE_TAGS},
+            next_addr, spawn_collect_n, wait_for_tcp,
+        },
+        SourceSender,
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LokiConfig>();
+    }
+
+    #[test]
+    fn parses_labels() {
+        assert_eq!(
+            parse_labels(r#"{app="api", msg="say \"hi\"\n", path="C:\\logs"}"#),
+            Some(
+                value!({"app": "api", "msg": "say \"hi\"\n", "path": "C:\\logs"})
+                    .into_object()
+                    .unwrap()
+            )
+        );
+        assert_eq!(parse_labels("{}"), Some(ObjectMap::new()));
+        assert_eq!(parse_labels(r#"{app="api""#), None);
+        assert_eq!(parse_labels(r#"{app=api}"#), None);
+        assert_eq!(parse_labels(r#"{app="api" env="prod"}"#), None);
+    }
+
+    async fn source(
+        log_namespace: bool,
+    ) -> (impl futures::Stream<Item = Event> + Unpin, SocketAddr) {
+        let (sender, recv) = SourceSender::new_test_finalize(EventStatus::Delivered);
+        let address = next_addr();
+        let context = SourceContext::new_test(sender, None);
+        tokio::spawn(async move {
+            LokiConfig {
+                address,
+                acknowledgements: true.into(),
+                log_namespace: Some(log_namespace),
+                ..Default::default()
+            }
+            .build(context)
+            .await
+            .unwrap()
+            .await
+            .unwrap()
+        });
+        wait_for_tcp(address).await;
+        (recv, address)
+    }
+
+    async fn push(address: SocketAddr, content_type: &str, body: Vec<u8>) -> u16 {
+        reqwest::Client::new()
+            .post(format!("http://{address}{PUSH_PATH}"))
+            .header(CONTENT_TYPE.as_str(), content_type)
+            .header(TENANT_HEADER, "team-a")
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn receives_protobuf_push_requests() {
+        assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+            let (rx, address) = source(false).await;
+            let timestamp = Utc.timestamp_opt(1_700_000_000, 5).single().unwrap();
+            let labels = HashMap::from([("app".to_owned(), "api".to_owned())]);
+            let body = Batch(vec![Stream(
+                labels,
+                vec![
+                    Entry(
+                        timestamp.timestamp_nanos_opt().unwrap(),
+                        "hello".to_owned(),
+                        vec![("trace_id".to_owned(), "abc".to_owned())],
+                    ),
+                    Entry(
+                        timestamp.timestamp_nanos_opt().unwrap(),
+                        "world".to_owned(),
+                        vec![],
+                    ),
+                ],
+            )])
+            .encode();
+            let body = snap::raw::Encoder::new().compress_vec(&body).unwrap();
+
+            let events = spawn_collect_n(
+                async move { assert_eq!(204, push(address, "application/x-protobuf", body).await) },
+                rx,
+                2,
+            )
+            .await;
+
+            let log = events[0].as_log();
+            assert_eq!(*log.get_message().unwrap(), "hello".into());
+            assert_eq!(*log.get_timestamp().unwrap(), timestamp.into());
+            assert_eq!(log[LABELS], value!({"app": "api"}));
+            assert_eq!(log[STRUCTURED_METADATA], value!({"trace_id": "abc"}));
+            assert_eq!(log[TENANT_ID], "team-a".into());
+            assert_eq!(*log.get_source_type().unwrap(), "loki".into());
+
+            let log = events[1].as_log();
+            assert_eq!(*log.get_message().unwrap(), "world".into());
+            assert!(log.get(STRUCTURED_METADATA).is_none());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn receives_json_push_requests() {
+        assert_source_compliance(&HTTP_PUSH_SOURCE_TAGS, async {
+            let (rx, address) = source(true).await;
+            let body = br#"{"streams": [{"stream": {"app": "api"}, "values": [
+                ["1700000000000000005", "hello", {"trace_id": "abc"}],
+                ["1700000000000000006", "world"]
+            ]}]}"#;
+
+            let events =
+                spawn_collect_n(
+                    async move {
+                        assert_eq!(204, push(address, "application/json", body.to_vec()).await)
+                    },
+                    rx,
+                    2,
+                )
+                .await;
+
+            let log = events[0].as_log();
+            assert_eq!(*log.value(), "hello".into());
+            let metadata = log.metadata().value();
+            assert_eq!(
+                metadata.get(path!(LokiConfig::NAME, "timestamp")),
+                Some(&Utc.timestamp_nanos(1_700_000_000_000_000_005).into())
+            );
+            assert_eq!(
+                metadata.get(path!(LokiConfig::NAME, LABELS)),
+                Some(&value!({"app": "api"}))
+            );
+            assert_eq!(
+                metadata.get(path!(LokiConfig::NAME, STRUCTURED_METADATA)),
+                Some(&value!({"trace_id": "abc"}))
+            );
+            assert_eq!(
+                metadata.get(path!(LokiConfig::NAME, TENANT_ID)),
+                Some(&"team-a".into())
+            );
+
+            assert_eq!(*events[1].as_log().value(), "world".into());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_push_requests() {
+        let (_rx, address) = source(false).await;
+        assert_eq!(
+            400,
+            push(address, "application/json", b"{\"streams\": 1}".to_vec()).await
+        );
+        let body = snap::raw::Encoder::new()
+            .compress_vec(b"not protobuf")
+            .unwrap();
+        assert_eq!(400, push(address, "application/x-protobuf", body).await);
+        assert_eq!(
+            422,
+            push(address, "application/x-protobuf", b"not snappy".to_vec()).await
+        );
+    }
+}
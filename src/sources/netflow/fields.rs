@@ -0,0 +1,129 @@
+//! Information element definitions shared by NetFlow v9 and IPFIX.
+//!
+//! NetFlow v9 field types 1-127 share their numbering with the IANA IPFIX information elements, so
+//! a single table is used for both protocols. Names are the IANA names converted to snake case.
+
+/// How the raw bytes of a field are interpreted.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FieldKind {
+    /// Big-endian unsigned integer, possibly using reduced-size encoding.
+    Unsigned,
+    /// IPv4 address.
+    Ipv4,
+    /// IPv6 address.
+    Ipv6,
+    /// 48-bit MAC address.
+    Mac,
+    /// UTF-8 string.
+    String,
+    /// Seconds since the UNIX epoch.
+    Seconds,
+    /// Milliseconds since the UNIX epoch.
+    Milliseconds,
+    /// Opaque octets, rendered as a hexadecimal string.
+    Octets,
+}
+
+/// A known information element.
+#[derive(Clone, Copy, Debug)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub kind: FieldKind,
+}
+
+const fn info(name: &'static str, kind: FieldKind) -> Option<FieldInfo> {
+    Some(FieldInfo { name, kind })
+}
+
+/// Returns the IANA information element with the given identifier, if it is known.
+pub const fn lookup(id: u16) -> Option<FieldInfo> {
+    use FieldKind::*;
+
+    match id {
+        1 => info("octet_delta_count", Unsigned),
+        2 => info("packet_delta_count", Unsigned),
+        3 => info("delta_flow_count", Unsigned),
+        4 => info("protocol_identifier", Unsigned),
+        5 => info("ip_class_of_service", Unsigned),
+        6 => info("tcp_control_bits", Unsigned),
+        7 => info("source_transport_port", Unsigned),
+        8 => info("source_ipv4_address", Ipv4),
+        9 => info("source_ipv4_prefix_length", Unsigned),
+        10 => info("ingress_interface", Unsigned),
+        11 => info("destination_transport_port", Unsigned),
+        12 => info("destination_ipv4_address", Ipv4),
+        13 => info("destination_ipv4_prefix_length", Unsigned),
+        14 => info("egress_interface", Unsigned),
+        15 => info("ip_next_hop_ipv4_address", Ipv4),
+        16 => info("bgp_source_as_number", Unsigned),
+        17 => info("bgp_destination_as_number", Unsigned),
+        18 => info("bgp_next_hop_ipv4_address", Ipv4),
+        19 => info("post_mcast_packet_delta_count", Unsigned),
+        20 => info("post_mcast_octet_delta_count", Unsigned),
+        21 => info("flow_end_sys_up_time", Unsigned),
+        22 => info("flow_start_sys_up_time", Unsigned),
+        23 => info("post_octet_delta_count", Unsigned),
+        24 => info("post_packet_delta_count", Unsigned),
+        25 => info("minimum_ip_total_length", Unsigned),
+        26 => info("maximum_ip_total_length", Unsigned),
+        27 => info("source_ipv6_address", Ipv6),
+        28 => info("destination_ipv6_address", Ipv6),
+        29 => info("source_ipv6_prefix_length", Unsigned),
+        30 => info("destination_ipv6_prefix_length", Unsigned),
+        31 => info("flow_label_ipv6", Unsigned),
+        32 => info("icmp_type_code_ipv4", Unsigned),
+        33 => info("igmp_type", Unsigned),
+        34 => info("sampling_interval", Unsigned),
+        35 => info("sampling_algorithm", Unsigned),
+        36 => info("flow_active_timeout", Unsigned),
+        37 => info("flow_idle_timeout", Unsigned),
+        38 => info("engine_type", Unsigned),
+        39 => info("engine_id", Unsigned),
+        40 => info("exported_octet_total_count", Unsigned),
+        41 => info("exported_message_total_count", Unsigned),
+        42 => info("exported_flow_record_total_count", Unsigned),
+        48 => info("sampler_id", Unsigned),
+        49 => info("sampler_mode", Unsigned),
+        50 => info("sampler_random_interval", Unsigned),
+        52 => info("minimum_ttl", Unsigned),
+        53 => info("maximum_ttl", Unsigned),
+        54 => info("fragment_identification", Unsigned),
+        55 => info("post_ip_class_of_service", Unsigned),
+        56 => info("source_mac_address", Mac),
+        57 => info("post_destination_mac_address", Mac),
+        58 => info("vlan_id", Unsigned),
+        59 => info("post_vlan_id", Unsigned),
+        60 => info("ip_version", Unsigned),
+        61 => info("flow_direction", Unsigned),
+        62 => info("ip_next_hop_ipv6_address", Ipv6),
+        63 => info("bgp_next_hop_ipv6_address", Ipv6),
+        64 => info("ipv6_extension_headers", Unsigned),
+        70 => info("mpls_top_label_stack_section", Octets),
+        80 => info("destination_mac_address", Mac),
+        81 => info("post_source_mac_address", Mac),
+        82 => info("interface_name", String),
+        83 => info("interface_description", String),
+        85 => info("octet_total_count", Unsigned),
+        86 => info("packet_total_count", Unsigned),
+        89 => info("forwarding_status", Unsigned),
+        95 => info("application_id", Octets),
+        96 => info("application_name", String),
+        136 => info("flow_end_reason", Unsigned),
+        148 => info("flow_id", Unsigned),
+        150 => info("flow_start_seconds", Seconds),
+        151 => info("flow_end_seconds", Seconds),
+        152 => info("flow_start_milliseconds", Milliseconds),
+        153 => info("flow_end_milliseconds", Milliseconds),
+        176 => info("icmp_type_ipv4", Unsigned),
+        177 => info("icmp_code_ipv4", Unsigned),
+        178 => info("icmp_type_ipv6", Unsigned),
+        179 => info("icmp_code_ipv6", Unsigned),
+        225 => info("post_nat_source_ipv4_address", Ipv4),
+        226 => info("post_nat_destination_ipv4_address", Ipv4),
+        227 => info("post_napt_source_transport_port", Unsigned),
+        228 => info("post_napt_destination_transport_port", Unsigned),
+        234 => info("ingress_vrf_id", Unsigned),
+        235 => info("egress_vrf_id", Unsigned),
+        _ => None,
+    }
+}
@@ -0,0 +1,473 @@
+use std::{
+    collections::BTreeMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+
+use bytes::BytesMut;
+use chrono::Utc;
+use listenfd::ListenFd;
+use serde_with::serde_as;
+use vector_lib::configurable::configurable_component;
+use vector_lib::internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol};
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path, path};
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    metric_tags,
+    schema::Definition,
+    EstimatedJsonEncodedSizeOf,
+};
+use vrl::value::{kind::Collection, Kind};
+
+use self::parser::{parse_datagram, FlowRecord, RecordType, TemplateCache};
+use super::util::net::{try_bind_udp_socket, SocketListenAddr};
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext, SourceOutput,
+    },
+    event::{Event, LogEvent, Metric, MetricKind, MetricValue, Value},
+    internal_events::{
+        NetflowParseError, NetflowTemplateNotFound, SocketBindError, SocketEventsReceived,
+        SocketMode, SocketReceiveError, StreamClosedError,
+    },
+    net,
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+mod fields;
+pub mod parser;
+
+/// Configuration for the `netflow` source.
+#[serde_as]
+#[configurable_component(source(
+    "netflow",
+    "Collect network flow records from NetFlow v5, NetFlow v9 and IPFIX exporters."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct NetflowConfig {
+    #[configurable(derived)]
+    address: SocketListenAddr,
+
+    /// The maximum size of an incoming datagram.
+    ///
+    /// Datagrams larger than this are truncated, and are likely to fail decoding.
+    #[serde(default = "default_max_length")]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    max_length: usize,
+
+    /// The size of the receive buffer used for the listening socket.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    receive_buffer_bytes: Option<usize>,
+
+    /// How long a NetFlow v9 or IPFIX template is kept after it was last announced by its exporter.
+    ///
+    /// Data records referencing an expired (or never received) template cannot be decoded and are
+    /// discarded until the exporter announces the template again.
+    #[serde(default = "default_template_timeout_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Template Timeout"))]
+    template_timeout_secs: Duration,
+
+    /// Overrides the name of the log field used to add the exporter's address to each event.
+    ///
+    /// By default, the [global `log_schema.host_key` option][global_host_key] is used.
+    ///
+    /// Set to `""` to suppress this key.
+    ///
+    /// [global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+    host_key: Option<OptionalValuePath>,
+
+    /// Whether or not to additionally emit per-interface byte and packet counters.
+    ///
+    /// When enabled, the `netflow_interface_bytes_total` and `netflow_interface_packets_total`
+    /// counters are emitted for every datagram, tagged with the exporter address, the interface
+    /// index and the direction (`ingress` or `egress`) of the flows.
+    #[serde(default)]
+    interface_metrics: bool,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    log_namespace: Option<bool>,
+}
+
+const fn default_max_length() -> usize {
+    65535
+}
+
+const fn default_template_timeout_secs() -> Duration {
+    Duration::from_secs(1800)
+}
+
+impl NetflowConfig {
+    pub fn from_address(address: SocketListenAddr) -> Self {
+        Self {
+            address,
+            max_length: default_max_length(),
+            receive_buffer_bytes: None,
+            template_timeout_secs: default_template_timeout_secs(),
+            host_key: None,
+            interface_metrics: false,
+            log_namespace: None,
+        }
+    }
+
+    fn host_key(&self) -> OptionalValuePath {
+        self.host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().cloned().into())
+    }
+
+    fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
+        Definition::new_with_default_metadata(Kind::object(Collection::empty()), [log_namespace])
+            .with_standard_vector_source_metadata()
+            .with_source_metadata(
+                Self::NAME,
+                self.host_key().path.map(LegacyKey::InsertIfEmpty),
+                &owned_value_path!("host"),
+                Kind::bytes(),
+                Some("host"),
+            )
+            .with_event_field(&owned_value_path!("version"), Kind::integer(), None)
+            .with_event_field(&owned_value_path!("record_type"), Kind::bytes(), None)
+            .with_event_field(&owned_value_path!("export_time"), Kind::timestamp(), None)
+            .with_event_field(&owned_value_path!("sequence_number"), Kind::integer(), None)
+            .with_event_field(
+                &owned_value_path!("observation_domain_id"),
+                Kind::integer().or_undefined(),
+                None,
+            )
+            .with_event_field(
+                &owned_value_path!("template_id"),
+                Kind::integer().or_undefined(),
+                None,
+            )
+            .unknown_fields(Kind::any())
+    }
+}
+
+impl GenerateConfig for NetflowConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self::from_address(SocketListenAddr::SocketAddr(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 2055)),
+        )))
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "netflow")]
+impl SourceConfig for NetflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+        Ok(Box::pin(netflow_udp(
+            self.clone(),
+            cx.shutdown,
+            cx.out,
+            log_namespace,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        let ty = if self.interface_metrics {
+            DataType::Log | DataType::Metric
+        } else {
+            DataType::Log
+        };
+
+        vec![SourceOutput::new_maybe_logs(
+            ty,
+            self.schema_definition(log_namespace),
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![self.address.as_udp_resource()]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+async fn netflow_udp(
+    config: NetflowConfig,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    log_namespace: LogNamespace,
+) -> Result<(), ()> {
+    let listenfd = ListenFd::from_env();
+    let socket = try_bind_udp_socket(config.address, listenfd)
+        .await
+        .map_err(|error| {
+            emit!(SocketBindError {
+                mode: SocketMode::Udp,
+                error,
+            })
+        })?;
+
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = net::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(message = "Listening.", address = %config.address);
+
+    let bytes_received = register!(BytesReceived::from(Protocol::UDP));
+    let host_key = config.host_key();
+    let mut templates = TemplateCache::new(config.template_timeout_secs);
+    let mut evict_interval =
+        tokio::time::interval(config.template_timeout_secs.max(Duration::from_secs(1)));
+    let mut buf = BytesMut::with_capacity(config.max_length);
+
+    loop {
+        buf.resize(config.max_length, 0);
+        tokio::select! {
+            recv = socket.recv_from(&mut buf) => {
+                let (byte_size, address) = recv.map_err(|error| {
+                    emit!(SocketReceiveError {
+                        mode: SocketMode::Udp,
+                        error,
+                    })
+                })?;
+                bytes_received.emit(ByteSize(byte_size));
+
+                let exporter = address.ip();
+                let parsed = match parse_datagram(&buf[..byte_size], exporter, &mut templates) {
+                    Ok(parsed) => parsed,
+                    Err(error) => {
+                        emit!(NetflowParseError { error, exporter });
+                        continue;
+                    }
+                };
+                for template_id in parsed.missing_templates {
+                    emit!(NetflowTemplateNotFound { template_id, exporter });
+                }
+                if parsed.records.is_empty() {
+                    continue;
+                }
+
+                let now = Utc::now();
+                let mut events = if config.interface_metrics {
+                    interface_metrics(&parsed.records, exporter)
+                } else {
+                    Vec::new()
+                };
+                events.extend(parsed.records.into_iter().map(|record| {
+                    Event::Log(record_to_log(record, exporter, &host_key, now, log_namespace))
+                }));
+
+                let count = events.len();
+                emit!(SocketEventsReceived {
+                    mode: SocketMode::Udp,
+                    byte_size: events.estimated_json_encoded_size_of(),
+                    count,
+                });
+
+                tokio::select! {
+                    result = out.send_batch(events) => {
+                        if result.is_err() {
+                            emit!(StreamClosedError { count });
+                            return Ok(());
+                        }
+                    }
+                    _ = &mut shutdown => return Ok(()),
+                }
+            }
+            _ = evict_interval.tick() => templates.evict_expired(),
+            _ = &mut shutdown => return Ok(()),
+        }
+    }
+}
+
+fn record_to_log(
+    record: FlowRecord,
+    exporter: IpAddr,
+    host_key: &OptionalValuePath,
+    now: chrono::DateTime<Utc>,
+    log_namespace: LogNamespace,
+) -> LogEvent {
+    let mut log = LogEvent::from_map(record.fields, Default::default());
+    log.insert("version", record.version);
+    log.insert("record_type", record.record_type.as_str());
+    log.insert("export_time", record.export_time);
+    log.insert("sequence_number", record.sequence_number);
+    if let Some(observation_domain_id) = record.observation_domain_id {
+        log.insert("observation_domain_id", observation_domain_id);
+    }
+    if let Some(template_id) = record.template_id {
+        log.insert("template_id", template_id);
+    }
+
+    log_namespace.insert_standard_vector_source_metadata(&mut log, NetflowConfig::NAME, now);
+    log_namespace.insert_source_metadata(
+        NetflowConfig::NAME,
+        &mut log,
+        host_key.path.as_ref().map(LegacyKey::InsertIfEmpty),
+        path!("host"),
+        exporter.to_string(),
+    );
+
+    log
+}
+
+/// Sums the bytes and packets of the flow records in a datagram per interface and direction.
+fn interface_metrics(records: &[FlowRecord], exporter: IpAddr) -> Vec<Event> {
+    let mut totals = BTreeMap::<(i64, &'static str), (f64, f64)>::new();
+    for record in records {
+        if record.record_type != RecordType::Flow {
+            continue;
+        }
+
+        let counter = |name: &str| {
+            record
+                .fields
+                .get(name)
+                .and_then(Value::as_integer)
+                .unwrap_or(0) as f64
+        };
+        let bytes = counter("octet_delta_count");
+        let packets = counter("packet_delta_count");
+
+        for (direction, field) in [
+            ("ingress", "ingress_interface"),
+            ("egress", "egress_interface"),
+        ] {
+            if let Some(interface) = record.fields.get(field).and_then(Value::as_integer) {
+                let total = totals.entry((interface, direction)).or_default();
+                total.0 += bytes;
+                total.1 += packets;
+            }
+        }
+    }
+
+    let now = Utc::now();
+    let exporter = exporter.to_string();
+    totals
+        .into_iter()
+        .flat_map(|((interface, direction), (bytes, packets))| {
+            let tags = metric_tags!(
+                "exporter" => exporter.clone(),
+                "interface" => interface.to_string(),
+                "direction" => direction,
+            );
+            [
+                ("interface_bytes_total", bytes),
+                ("interface_packets_total", packets),
+            ]
+            .map(|(name, value)| {
+                Event::Metric(
+                    Metric::new(
+                        name,
+                        MetricKind::Incremental,
+                        MetricValue::Counter { value },
+                    )
+                    .with_namespace(Some(NetflowConfig::NAME))
+                    .with_tags(Some(tags.clone()))
+                    .with_timestamp(Some(now)),
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use tokio::{net::UdpSocket, time::sleep};
+    use vector_lib::config::ComponentKey;
+
+    use super::*;
+    use crate::test_util::{
+        collect_ready,
+        components::{assert_source_compliance, SOCKET_PUSH_SOURCE_TAGS},
+        next_addr,
+    };
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests/data/netflow")
+            .join(name);
+        fs::read(path).unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<NetflowConfig>();
+    }
+
+    async fn send_and_collect(config: NetflowConfig, datagrams: &[&str]) -> Vec<Event> {
+        let in_addr = match config.address {
+            SocketListenAddr::SocketAddr(addr) => addr,
+            SocketListenAddr::SystemdFd(_) => unreachable!(),
+        };
+        let (tx, rx) = SourceSender::new_test();
+        let (cx, shutdown) = SourceContext::new_shutdown(&ComponentKey::from("netflow"), tx);
+        let source = config.build(cx).await.unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_millis(250)).await;
+
+        let socket = UdpSocket::bind(next_addr()).await.unwrap();
+        for name in datagrams {
+            socket.send_to(&fixture(name), in_addr).await.unwrap();
+            sleep(Duration::from_millis(10)).await;
+        }
+        sleep(Duration::from_millis(250)).await;
+        shutdown
+            .shutdown_all(Some(
+                tokio::time::Instant::now() + Duration::from_millis(100),
+            ))
+            .await;
+
+        collect_ready(rx).await
+    }
+
+    #[tokio::test]
+    async fn receives_v9_flows() {
+        assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let config = NetflowConfig::from_address(next_addr().into());
+            let events = send_and_collect(config, &["v9.bin", "v9-data-only.bin"]).await;
+            assert_eq!(events.len(), 4);
+
+            let log = events[0].as_log();
+            assert_eq!(log["version"], 9.into());
+            assert_eq!(log["record_type"], "flow".into());
+            assert_eq!(log["source_ipv4_address"], "10.1.0.1".into());
+            assert_eq!(log["host"], "127.0.0.1".into());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn emits_interface_metrics() {
+        let mut config = NetflowConfig::from_address(next_addr().into());
+        config.interface_metrics = true;
+        let events = send_and_collect(config, &["v5.bin"]).await;
+
+        let metrics = events
+            .into_iter()
+            .filter_map(Event::try_into_metric)
+            .collect::<Vec<_>>();
+        // Two flows in opposite directions over interfaces 1 and 2.
+        assert_eq!(metrics.len(), 8);
+
+        let ingress_bytes = metrics
+            .iter()
+            .find(|metric| {
+                metric.name() == "interface_bytes_total"
+                    && metric.tag_value("interface").as_deref() == Some("1")
+                    && metric.tag_value("direction").as_deref() == Some("ingress")
+            })
+            .unwrap();
+        assert_eq!(ingress_bytes.namespace(), Some("netflow"));
+        assert_eq!(
+            ingress_bytes.value(),
+            &MetricValue::Counter { value: 1500.0 }
+        );
+    }
+}
@@ -0,0 +1,791 @@
+//! Decoding of NetFlow v5, NetFlow v9 and IPFIX datagrams.
+//!
+//! NetFlow v9 and IPFIX are template based: exporters periodically announce the layout of their
+//! data records, and data records can only be decoded once the matching template has been seen.
+//! Templates are cached per exporter address and observation domain (the v9 "source ID") in a
+//! [`TemplateCache`], which is owned by the receiving task.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use snafu::Snafu;
+
+use super::fields::{self, FieldKind};
+use crate::event::{ObjectMap, Value};
+
+const NETFLOW_V5: u16 = 5;
+const NETFLOW_V9: u16 = 9;
+const IPFIX: u16 = 10;
+
+const V5_HEADER_LENGTH: usize = 24;
+const V5_RECORD_LENGTH: usize = 48;
+const IPFIX_HEADER_LENGTH: usize = 16;
+
+/// Field length announcing a variable-length IPFIX field.
+const VARIABLE_LENGTH: u16 = 65535;
+
+/// Errors that can occur while decoding a datagram.
+#[derive(Debug, Eq, PartialEq, Snafu)]
+pub enum ParseError {
+    #[snafu(display(
+        "Datagram truncated: needed {} bytes, only {} available",
+        needed,
+        available
+    ))]
+    Truncated { needed: usize, available: usize },
+
+    #[snafu(display("Unsupported flow protocol version {}", version))]
+    UnsupportedVersion { version: u16 },
+
+    #[snafu(display("Invalid set length {} for set {}", length, set_id))]
+    InvalidSetLength { set_id: u16, length: usize },
+
+    #[snafu(display("Invalid template {}: {}", template_id, reason))]
+    InvalidTemplate {
+        template_id: u16,
+        reason: &'static str,
+    },
+}
+
+/// The kind of data record a [`FlowRecord`] was decoded from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RecordType {
+    /// A regular flow record.
+    Flow,
+    /// A record described by an options template, such as sampler or interface information.
+    Options,
+}
+
+impl RecordType {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Flow => "flow",
+            Self::Options => "options",
+        }
+    }
+}
+
+/// A single decoded data record, along with the header information of the datagram carrying it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowRecord {
+    pub version: u16,
+    pub export_time: DateTime<Utc>,
+    pub sequence_number: u32,
+    pub observation_domain_id: Option<u32>,
+    pub template_id: Option<u16>,
+    pub record_type: RecordType,
+    pub fields: ObjectMap,
+}
+
+/// The result of decoding a single datagram.
+#[derive(Debug, Default)]
+pub struct ParsedDatagram {
+    /// The decoded data records.
+    pub records: Vec<FlowRecord>,
+
+    /// IDs of the templates referenced by data sets that could not be decoded because the template
+    /// has not been received yet (or has expired).
+    pub missing_templates: Vec<u16>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct TemplateField {
+    id: u16,
+    enterprise: Option<u32>,
+    length: u16,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Template {
+    fields: Vec<TemplateField>,
+    scope_field_count: usize,
+    options: bool,
+}
+
+impl Template {
+    /// The smallest number of bytes a record using this template can occupy.
+    fn min_record_length(&self) -> usize {
+        self.fields
+            .iter()
+            .map(|field| match field.length {
+                VARIABLE_LENGTH => 1,
+                length => length as usize,
+            })
+            .sum()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+struct TemplateKey {
+    exporter: IpAddr,
+    observation_domain_id: u32,
+    template_id: u16,
+}
+
+/// Templates announced by exporters, keyed by exporter address, observation domain and template ID.
+///
+/// Templates that have not been refreshed within the configured timeout are discarded, so that an
+/// exporter that is reconfigured (or replaced) does not have its data decoded with a stale layout.
+#[derive(Debug)]
+pub struct TemplateCache {
+    templates: HashMap<TemplateKey, (Template, Instant)>,
+    timeout: Duration,
+}
+
+impl TemplateCache {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            templates: HashMap::new(),
+            timeout,
+        }
+    }
+
+    /// The number of templates currently cached.
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    fn insert(&mut self, key: TemplateKey, template: Template) {
+        let now = Instant::now();
+        self.templates.insert(key, (template, now));
+    }
+
+    fn remove(&mut self, key: &TemplateKey) {
+        self.templates.remove(key);
+    }
+
+    fn get(&self, key: &TemplateKey) -> Option<&Template> {
+        self.templates
+            .get(key)
+            .filter(|(_, updated)| updated.elapsed() < self.timeout)
+            .map(|(template, _)| template)
+    }
+
+    /// Drops all templates that have not been refreshed within the timeout.
+    pub fn evict_expired(&mut self) {
+        let timeout = self.timeout;
+        self.templates
+            .retain(|_, (_, updated)| updated.elapsed() < timeout);
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    const fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    const fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if self.remaining() < n {
+            return Err(ParseError::Truncated {
+                needed: n,
+                available: self.remaining(),
+            });
+        }
+        let bytes = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, ParseError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ParseError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+/// Decodes a NetFlow v5, NetFlow v9 or IPFIX datagram received from `exporter`.
+///
+/// Templates contained in the datagram are added to `cache` before any data sets that follow them
+/// are decoded.
+pub fn parse_datagram(
+    bytes: &[u8],
+    exporter: IpAddr,
+    cache: &mut TemplateCache,
+) -> Result<ParsedDatagram, ParseError> {
+    let mut reader = Reader::new(bytes);
+    match reader.u16()? {
+        NETFLOW_V5 => parse_v5(reader),
+        NETFLOW_V9 => parse_v9(reader, exporter, cache),
+        IPFIX => parse_ipfix(bytes, reader, exporter, cache),
+        version => Err(ParseError::UnsupportedVersion { version }),
+    }
+}
+
+fn integer(value: impl Into<i64>) -> Value {
+    Value::Integer(value.into())
+}
+
+fn timestamp(secs: u32, nsecs: u32) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs as i64, nsecs).unwrap_or_default()
+}
+
+fn parse_v5(mut reader: Reader<'_>) -> Result<ParsedDatagram, ParseError> {
+    let count = reader.u16()? as usize;
+    let sys_uptime = reader.u32()?;
+    let unix_secs = reader.u32()?;
+    let unix_nsecs = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let engine_type = reader.u8()?;
+    let engine_id = reader.u8()?;
+    let sampling = reader.u16()?;
+
+    let needed = count * V5_RECORD_LENGTH;
+    if reader.remaining() < needed {
+        return Err(ParseError::Truncated {
+            needed: V5_HEADER_LENGTH + needed,
+            available: V5_HEADER_LENGTH + reader.remaining(),
+        });
+    }
+
+    let export_time = timestamp(unix_secs, unix_nsecs);
+    let mut records = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut record = Reader::new(reader.take(V5_RECORD_LENGTH)?);
+        let mut fields = ObjectMap::new();
+
+        let ipv4 = |bytes: &[u8]| {
+            Value::from(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string())
+        };
+
+        fields.insert("source_ipv4_address".into(), ipv4(record.take(4)?));
+        fields.insert("destination_ipv4_address".into(), ipv4(record.take(4)?));
+        fields.insert("ip_next_hop_ipv4_address".into(), ipv4(record.take(4)?));
+        fields.insert("ingress_interface".into(), integer(record.u16()?));
+        fields.insert("egress_interface".into(), integer(record.u16()?));
+        fields.insert("packet_delta_count".into(), integer(record.u32()?));
+        fields.insert("octet_delta_count".into(), integer(record.u32()?));
+        fields.insert("flow_start_sys_up_time".into(), integer(record.u32()?));
+        fields.insert("flow_end_sys_up_time".into(), integer(record.u32()?));
+        fields.insert("source_transport_port".into(), integer(record.u16()?));
+        fields.insert("destination_transport_port".into(), integer(record.u16()?));
+        record.take(1)?; // pad1
+        fields.insert("tcp_control_bits".into(), integer(record.u8()?));
+        fields.insert("protocol_identifier".into(), integer(record.u8()?));
+        fields.insert("ip_class_of_service".into(), integer(record.u8()?));
+        fields.insert("bgp_source_as_number".into(), integer(record.u16()?));
+        fields.insert("bgp_destination_as_number".into(), integer(record.u16()?));
+        fields.insert("source_ipv4_prefix_length".into(), integer(record.u8()?));
+        fields.insert(
+            "destination_ipv4_prefix_length".into(),
+            integer(record.u8()?),
+        );
+
+        fields.insert("system_up_time".into(), integer(sys_uptime));
+        fields.insert("engine_type".into(), integer(engine_type));
+        fields.insert("engine_id".into(), integer(engine_id));
+        fields.insert("sampling_algorithm".into(), integer(sampling >> 14));
+        fields.insert("sampling_interval".into(), integer(sampling & 0x3fff));
+
+        records.push(FlowRecord {
+            version: NETFLOW_V5,
+            export_time,
+            sequence_number,
+            observation_domain_id: None,
+            template_id: None,
+            record_type: RecordType::Flow,
+            fields,
+        });
+    }
+
+    Ok(ParsedDatagram {
+        records,
+        missing_templates: Vec::new(),
+    })
+}
+
+/// Header information shared by all records of a v9 or IPFIX datagram.
+struct Header {
+    version: u16,
+    export_time: DateTime<Utc>,
+    sequence_number: u32,
+    observation_domain_id: u32,
+    exporter: IpAddr,
+}
+
+impl Header {
+    const fn key(&self, template_id: u16) -> TemplateKey {
+        TemplateKey {
+            exporter: self.exporter,
+            observation_domain_id: self.observation_domain_id,
+            template_id,
+        }
+    }
+}
+
+fn parse_v9(
+    mut reader: Reader<'_>,
+    exporter: IpAddr,
+    cache: &mut TemplateCache,
+) -> Result<ParsedDatagram, ParseError> {
+    let _count = reader.u16()?;
+    let _sys_uptime = reader.u32()?;
+    let unix_secs = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let source_id = reader.u32()?;
+
+    let header = Header {
+        version: NETFLOW_V9,
+        export_time: timestamp(unix_secs, 0),
+        sequence_number,
+        observation_domain_id: source_id,
+        exporter,
+    };
+
+    parse_sets(reader, &header, cache)
+}
+
+fn parse_ipfix<'a>(
+    bytes: &'a [u8],
+    mut reader: Reader<'a>,
+    exporter: IpAddr,
+    cache: &mut TemplateCache,
+) -> Result<ParsedDatagram, ParseError> {
+    let length = reader.u16()? as usize;
+    let export_time = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let observation_domain_id = reader.u32()?;
+
+    if length < IPFIX_HEADER_LENGTH || length > bytes.len() {
+        return Err(ParseError::Truncated {
+            needed: length.max(IPFIX_HEADER_LENGTH),
+            available: bytes.len(),
+        });
+    }
+
+    let header = Header {
+        version: IPFIX,
+        export_time: timestamp(export_time, 0),
+        sequence_number,
+        observation_domain_id,
+        exporter,
+    };
+
+    // Anything past the length announced in the message header is not part of the message.
+    let sets = Reader::new(&bytes[IPFIX_HEADER_LENGTH..length]);
+    parse_sets(sets, &header, cache)
+}
+
+fn parse_sets(
+    mut reader: Reader<'_>,
+    header: &Header,
+    cache: &mut TemplateCache,
+) -> Result<ParsedDatagram, ParseError> {
+    let (template_set, options_template_set) = match header.version {
+        NETFLOW_V9 => (0, 1),
+        _ => (2, 3),
+    };
+
+    let mut parsed = ParsedDatagram::default();
+    while reader.remaining() >= 4 {
+        let set_id = reader.u16()?;
+        let length = reader.u16()? as usize;
+        if length < 4 || length - 4 > reader.remaining() {
+            return Err(ParseError::InvalidSetLength { set_id, length });
+        }
+        let body = Reader::new(reader.take(length - 4)?);
+
+        if set_id == template_set {
+            parse_templates(body, header, cache, false)?;
+        } else if set_id == options_template_set {
+            parse_templates(body, header, cache, true)?;
+        } else if set_id >= 256 {
+            match cache.get(&header.key(set_id)) {
+                Some(template) => parse_data_set(body, header, set_id, template, &mut parsed)?,
+                None => parsed.missing_templates.push(set_id),
+            }
+        }
+        // Set IDs between the template sets and 256 are reserved, and skipped.
+    }
+
+    Ok(parsed)
+}
+
+fn parse_template_field(
+    reader: &mut Reader<'_>,
+    version: u16,
+) -> Result<TemplateField, ParseError> {
+    let id = reader.u16()?;
+    let length = reader.u16()?;
+    if version == IPFIX && id & 0x8000 != 0 {
+        let enterprise = reader.u32()?;
+        Ok(TemplateField {
+            id: id & 0x7fff,
+            enterprise: Some(enterprise),
+            length,
+        })
+    } else {
+        Ok(TemplateField {
+            id,
+            enterprise: None,
+            length,
+        })
+    }
+}
+
+fn parse_templates(
+    mut reader: Reader<'_>,
+    header: &Header,
+    cache: &mut TemplateCache,
+    options: bool,
+) -> Result<(), ParseError> {
+    // Each template record header is at least four bytes long; anything shorter is padding.
+    while reader.remaining() >= 4 {
+        let template_id = reader.u16()?;
+        if template_id < 256 {
+            if template_id == 0 && reader.buf[reader.pos..].iter().all(|b| *b == 0) {
+                // Zero padding at the end of the set.
+                break;
+            }
+            return Err(ParseError::InvalidTemplate {
+                template_id,
+                reason: "template IDs must be at least 256",
+            });
+        }
+
+        let (field_count, scope_field_count) = match (header.version, options) {
+            (NETFLOW_V9, false) => (reader.u16()? as usize, 0),
+            (NETFLOW_V9, true) => {
+                let scope_length = reader.u16()? as usize;
+                let option_length = reader.u16()? as usize;
+                ((scope_length + option_length) / 4, scope_length / 4)
+            }
+            (_, false) => (reader.u16()? as usize, 0),
+            (_, true) => {
+                let field_count = reader.u16()? as usize;
+                if field_count == 0 {
+                    (0, 0)
+                } else {
+                    (field_count, reader.u16()? as usize)
+                }
+            }
+        };
+
+        if field_count == 0 {
+            // IPFIX template withdrawal.
+            cache.remove(&header.key(template_id));
+            continue;
+        }
+        if scope_field_count > field_count {
+            return Err(ParseError::InvalidTemplate {
+                template_id,
+                reason: "more scope fields than fields",
+            });
+        }
+
+        let fields = (0..field_count)
+            .map(|_| parse_template_field(&mut reader, header.version))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if header.version == NETFLOW_V9 && fields.iter().any(|f| f.length == VARIABLE_LENGTH) {
+            return Err(ParseError::InvalidTemplate {
+                template_id,
+                reason: "variable length fields are not supported by NetFlow v9",
+            });
+        }
+
+        let template = Template {
+            fields,
+            scope_field_count,
+            options,
+        };
+        if template.min_record_length() == 0 {
+            return Err(ParseError::InvalidTemplate {
+                template_id,
+                reason: "records must not be empty",
+            });
+        }
+        cache.insert(header.key(template_id), template);
+
+        if options && header.version == NETFLOW_V9 {
+            // NetFlow v9 options template flowsets carry a single template followed by padding.
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_data_set(
+    mut reader: Reader<'_>,
+    header: &Header,
+    template_id: u16,
+    template: &Template,
+    parsed: &mut ParsedDatagram,
+) -> Result<(), ParseError> {
+    let min_length = template.min_record_length();
+    // Trailing bytes shorter than a record are padding.
+    while reader.remaining() >= min_length {
+        let mut fields = ObjectMap::new();
+        for (index, field) in template.fields.iter().enumerate() {
+            let length = match field.length {
+                VARIABLE_LENGTH => match reader.u8()? {
+                    255 => reader.u16()? as usize,
+                    length => length as usize,
+                },
+                length => length as usize,
+            };
+            let bytes = reader.take(length)?;
+            let scope = index < template.scope_field_count;
+            let (name, value) = decode_field(field, bytes, header.version, scope);
+            fields.insert(name.into(), value);
+        }
+
+        parsed.records.push(FlowRecord {
+            version: header.version,
+            export_time: header.export_time,
+            sequence_number: header.sequence_number,
+            observation_domain_id: Some(header.observation_domain_id),
+            template_id: Some(template_id),
+            record_type: if template.options {
+                RecordType::Options
+            } else {
+                RecordType::Flow
+            },
+            fields,
+        });
+    }
+
+    Ok(())
+}
+
+/// Names of the NetFlow v9 option scope field types.
+const fn v9_scope_name(id: u16) -> Option<&'static str> {
+    match id {
+        1 => Some("scope_system"),
+        2 => Some("scope_interface"),
+        3 => Some("scope_line_card"),
+        4 => Some("scope_cache"),
+        5 => Some("scope_template"),
+        _ => None,
+    }
+}
+
+fn decode_field(field: &TemplateField, bytes: &[u8], version: u16, scope: bool) -> (String, Value) {
+    if let Some(enterprise) = field.enterprise {
+        let name = format!("enterprise_{}_{}", enterprise, field.id);
+        return (name, decode_value(FieldKind::Octets, bytes));
+    }
+
+    if scope && version == NETFLOW_V9 {
+        let name = v9_scope_name(field.id)
+            .map(str::to_owned)
+            .unwrap_or_else(|| format!("scope_{}", field.id));
+        return (name, decode_value(FieldKind::Unsigned, bytes));
+    }
+
+    match fields::lookup(field.id) {
+        Some(info) => (info.name.to_owned(), decode_value(info.kind, bytes)),
+        None => (
+            format!("field_{}", field.id),
+            decode_value(FieldKind::Unsigned, bytes),
+        ),
+    }
+}
+
+fn decode_value(kind: FieldKind, bytes: &[u8]) -> Value {
+    match (kind, bytes.len()) {
+        (FieldKind::Unsigned, 1..=8) => {
+            let value = bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+            Value::from(i64::try_from(value).unwrap_or(i64::MAX))
+        }
+        (FieldKind::Ipv4, 4) => {
+            Value::from(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]).to_string())
+        }
+        (FieldKind::Ipv6, 16) => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(bytes);
+            Value::from(Ipv6Addr::from(octets).to_string())
+        }
+        (FieldKind::Mac, 6) => Value::from(
+            bytes
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<Vec<_>>()
+                .join(":"),
+        ),
+        (FieldKind::String, _) => {
+            let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+            Value::from(String::from_utf8_lossy(&bytes[..end]).into_owned())
+        }
+        (FieldKind::Seconds, 4) => {
+            let secs = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            Value::from(timestamp(secs, 0))
+        }
+        (FieldKind::Milliseconds, 8) => {
+            let mut millis = [0u8; 8];
+            millis.copy_from_slice(bytes);
+            let millis = i64::try_from(u64::from_be_bytes(millis)).unwrap_or(i64::MAX);
+            Value::from(DateTime::from_timestamp_millis(millis).unwrap_or_default())
+        }
+        _ => Value::from(bytes.iter().map(|b| format!("{b:02x}")).collect::<String>()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::*;
+
+    const EXPORTER: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests/data/netflow")
+            .join(name);
+        fs::read(path).unwrap()
+    }
+
+    fn cache() -> TemplateCache {
+        TemplateCache::new(Duration::from_secs(60))
+    }
+
+    #[test]
+    fn parses_v5() {
+        let parsed = parse_datagram(&fixture("v5.bin"), EXPORTER, &mut cache()).unwrap();
+        assert_eq!(parsed.records.len(), 2);
+
+        let record = &parsed.records[0];
+        assert_eq!(record.version, 5);
+        assert_eq!(record.sequence_number, 1000);
+        assert_eq!(record.export_time.timestamp(), 1_700_000_000);
+        assert_eq!(record.fields["source_ipv4_address"], "10.0.0.1".into());
+        assert_eq!(record.fields["destination_ipv4_address"], "10.0.0.2".into());
+        assert_eq!(record.fields["ingress_interface"], 1.into());
+        assert_eq!(record.fields["egress_interface"], 2.into());
+        assert_eq!(record.fields["packet_delta_count"], 10.into());
+        assert_eq!(record.fields["octet_delta_count"], 1500.into());
+        assert_eq!(record.fields["source_transport_port"], 40000.into());
+        assert_eq!(record.fields["destination_transport_port"], 443.into());
+        assert_eq!(record.fields["protocol_identifier"], 6.into());
+        assert_eq!(record.fields["sampling_interval"], 100.into());
+    }
+
+    #[test]
+    fn rejects_truncated_v5() {
+        let bytes = fixture("v5.bin");
+        let error = parse_datagram(&bytes[..bytes.len() - 1], EXPORTER, &mut cache()).unwrap_err();
+        assert!(matches!(error, ParseError::Truncated { .. }));
+    }
+
+    #[test]
+    fn parses_v9_template_and_data() {
+        let mut cache = cache();
+        let parsed = parse_datagram(&fixture("v9.bin"), EXPORTER, &mut cache).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert!(parsed.missing_templates.is_empty());
+        assert_eq!(parsed.records.len(), 2);
+
+        let record = &parsed.records[1];
+        assert_eq!(record.version, 9);
+        assert_eq!(record.observation_domain_id, Some(42));
+        assert_eq!(record.template_id, Some(256));
+        assert_eq!(record.record_type, RecordType::Flow);
+        assert_eq!(record.fields["source_ipv4_address"], "10.1.0.3".into());
+        assert_eq!(record.fields["destination_ipv4_address"], "10.1.0.4".into());
+        assert_eq!(record.fields["octet_delta_count"], 3000.into());
+        assert_eq!(record.fields["packet_delta_count"], 20.into());
+        assert_eq!(record.fields["ingress_interface"], 3.into());
+        assert_eq!(record.fields["egress_interface"], 4.into());
+    }
+
+    #[test]
+    fn v9_data_without_template() {
+        let mut cache = cache();
+        let parsed = parse_datagram(&fixture("v9-data-only.bin"), EXPORTER, &mut cache).unwrap();
+        assert!(parsed.records.is_empty());
+        assert_eq!(parsed.missing_templates, vec![256]);
+
+        // Once the template is known, data-only datagrams decode.
+        parse_datagram(&fixture("v9.bin"), EXPORTER, &mut cache).unwrap();
+        let parsed = parse_datagram(&fixture("v9-data-only.bin"), EXPORTER, &mut cache).unwrap();
+        assert_eq!(parsed.records.len(), 2);
+    }
+
+    #[test]
+    fn templates_are_scoped_to_exporter() {
+        let mut cache = cache();
+        parse_datagram(&fixture("v9.bin"), EXPORTER, &mut cache).unwrap();
+
+        let other = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+        let parsed = parse_datagram(&fixture("v9-data-only.bin"), other, &mut cache).unwrap();
+        assert_eq!(parsed.missing_templates, vec![256]);
+    }
+
+    #[test]
+    fn templates_expire() {
+        let mut cache = TemplateCache::new(Duration::ZERO);
+        parse_datagram(&fixture("v9.bin"), EXPORTER, &mut cache).unwrap();
+        let parsed = parse_datagram(&fixture("v9-data-only.bin"), EXPORTER, &mut cache).unwrap();
+        assert_eq!(parsed.missing_templates, vec![256]);
+
+        cache.evict_expired();
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn parses_ipfix() {
+        let mut cache = cache();
+        let parsed = parse_datagram(&fixture("ipfix.bin"), EXPORTER, &mut cache).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(parsed.records.len(), 2);
+
+        let flow = &parsed.records[0];
+        assert_eq!(flow.version, 10);
+        assert_eq!(flow.observation_domain_id, Some(7));
+        assert_eq!(flow.record_type, RecordType::Flow);
+        assert_eq!(flow.fields["source_ipv6_address"], "2001:db8::1".into());
+        assert_eq!(
+            flow.fields["destination_ipv6_address"],
+            "2001:db8::2".into()
+        );
+        assert_eq!(flow.fields["octet_delta_count"], 123_456.into());
+        assert_eq!(flow.fields["interface_name"], "eth0".into());
+        assert_eq!(flow.fields["enterprise_9_1"], "beef".into());
+        assert_eq!(
+            flow.fields["flow_start_milliseconds"],
+            Value::from(DateTime::from_timestamp_millis(1_700_000_000_123).unwrap())
+        );
+
+        let options = &parsed.records[1];
+        assert_eq!(options.record_type, RecordType::Options);
+        assert_eq!(options.template_id, Some(257));
+        assert_eq!(options.fields["sampler_id"], 1.into());
+        assert_eq!(options.fields["sampling_interval"], 1000.into());
+    }
+
+    #[test]
+    fn ipfix_template_withdrawal() {
+        let mut cache = cache();
+        parse_datagram(&fixture("ipfix.bin"), EXPORTER, &mut cache).unwrap();
+        parse_datagram(&fixture("ipfix-withdrawal.bin"), EXPORTER, &mut cache).unwrap();
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let error = parse_datagram(&[0, 7, 0, 0], EXPORTER, &mut cache()).unwrap_err();
+        assert_eq!(error, ParseError::UnsupportedVersion { version: 7 });
+    }
+}
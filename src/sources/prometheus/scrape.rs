@@ -15,7 +15,7 @@ use crate::sources::util::http::HttpMethod;
 use crate::sources::util::http_client::{default_timeout, warn_if_interval_too_low};
 use crate::{
     config::{GenerateConfig, SourceConfig, SourceContext, SourceOutput},
-    http::Auth,
+    http::HttpClientAuth,
     internal_events::PrometheusParseError,
     sources::{
         self,
@@ -102,7 +102,7 @@ pub struct PrometheusScrapeConfig {
 
     #[configurable(derived)]
     #[configurable(metadata(docs::advanced))]
-    auth: Option<Auth>,
+    auth: Option<HttpClientAuth>,
 }
 
 fn query_example() -> serde_json::Value {
@@ -151,13 +151,18 @@ impl SourceConfig for PrometheusScrapeConfig {
 
         warn_if_interval_too_low(self.timeout, self.interval);
 
+        let auth = match &self.auth {
+            Some(auth) => Some(auth.build_authenticator(tls.clone(), &cx.proxy)?),
+            None => None,
+        };
+
         let inputs = GenericHttpClientInputs {
             urls,
             interval: self.interval,
             timeout: self.timeout,
             headers: HashMap::new(),
             content_type: "text/plain".to_string(),
-            auth: self.auth.clone(),
+            auth,
             tls,
             proxy: cx.proxy.clone(),
             shutdown: cx.shutdown,
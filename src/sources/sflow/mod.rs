@@ -0,0 +1,497 @@
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+use bytes::Bytes;
+use chrono::Utc;
+use smallvec::SmallVec;
+use vector_lib::codecs::{
+    decoding::{self, Deserializer, Framer},
+    BytesDecoder,
+};
+use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path};
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    metric_tags,
+    schema::Definition,
+};
+use vrl::value::{kind::Collection, Kind};
+
+use self::parser::{
+    parse_datagram, CounterRecord, CountersSample, Datagram, EthernetInterfaceCounters, FlowSample,
+    GenericInterfaceCounters, Sample,
+};
+use super::{
+    socket::udp::{udp, UdpConfig},
+    util::net::SocketListenAddr,
+};
+use crate::{
+    codecs::Decoder,
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext, SourceOutput,
+    },
+    event::{Event, LogEvent, Metric, MetricKind, MetricTags, MetricValue},
+};
+
+pub mod parser;
+
+/// Configuration for the `sflow` source.
+#[configurable_component(source(
+    "sflow",
+    "Collect flow and counter samples from sFlow v5 agents."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SflowConfig {
+    #[configurable(derived)]
+    address: SocketListenAddr,
+
+    /// The maximum size of an incoming datagram.
+    ///
+    /// Datagrams larger than this are discarded.
+    #[serde(default = "default_max_length")]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    max_length: usize,
+
+    /// The size of the receive buffer used for the listening socket.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    receive_buffer_bytes: Option<usize>,
+
+    /// Overrides the name of the log field used to add the address of the peer that sent the
+    /// datagram to each flow sample.
+    ///
+    /// The sFlow agent address reported inside the datagram is always added as `agent_address`.
+    ///
+    /// By default, the [global `log_schema.host_key` option][global_host_key] is used.
+    ///
+    /// Set to `""` to suppress this key.
+    ///
+    /// [global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+    host_key: Option<OptionalValuePath>,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    log_namespace: Option<bool>,
+}
+
+const fn default_max_length() -> usize {
+    65535
+}
+
+impl SflowConfig {
+    pub const fn from_address(address: SocketListenAddr) -> Self {
+        Self {
+            address,
+            max_length: default_max_length(),
+            receive_buffer_bytes: None,
+            host_key: None,
+            log_namespace: None,
+        }
+    }
+
+    fn host_key(&self) -> OptionalValuePath {
+        self.host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().cloned().into())
+    }
+
+    fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
+        Definition::new_with_default_metadata(Kind::object(Collection::empty()), [log_namespace])
+            .with_standard_vector_source_metadata()
+            .with_source_metadata(
+                Self::NAME,
+                self.host_key().path.map(LegacyKey::InsertIfEmpty),
+                &owned_value_path!("host"),
+                Kind::bytes(),
+                Some("host"),
+            )
+            .with_source_metadata(
+                Self::NAME,
+                None,
+                &owned_value_path!("port"),
+                Kind::integer(),
+                None,
+            )
+            .with_event_field(&owned_value_path!("agent_address"), Kind::bytes(), None)
+            .with_event_field(&owned_value_path!("sub_agent_id"), Kind::integer(), None)
+            .with_event_field(&owned_value_path!("sampling_rate"), Kind::integer(), None)
+            .with_event_field(&owned_value_path!("input_interface"), Kind::integer(), None)
+            .with_event_field(
+                &owned_value_path!("output_interface"),
+                Kind::integer(),
+                None,
+            )
+            .unknown_fields(Kind::any())
+    }
+}
+
+impl GenerateConfig for SflowConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self::from_address(SocketListenAddr::SocketAddr(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 6343)),
+        )))
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "sflow")]
+impl SourceConfig for SflowConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+
+        let mut udp_config = UdpConfig::from_address(self.address);
+        udp_config
+            .set_max_length(self.max_length)
+            .set_receive_buffer_bytes(self.receive_buffer_bytes)
+            .set_host_key(Some(self.host_key()))
+            .set_port_key(OptionalValuePath::none())
+            .set_log_namespace(self.log_namespace);
+
+        // Each datagram is decoded as a whole.
+        let decoder = Decoder::new(
+            Framer::Bytes(BytesDecoder::new()),
+            Deserializer::Boxed(Box::new(SflowDeserializer)),
+        )
+        .with_log_namespace(log_namespace);
+
+        Ok(udp(
+            udp_config,
+            decoder,
+            cx.shutdown,
+            cx.out,
+            log_namespace,
+            Self::NAME,
+        ))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log | DataType::Metric,
+            self.schema_definition(log_namespace),
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![self.address.as_udp_resource()]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// Turns each sFlow datagram into a log event per flow sample and metrics per counter sample.
+#[derive(Clone, Debug)]
+struct SflowDeserializer;
+
+impl decoding::format::Deserializer for SflowDeserializer {
+    fn parse(
+        &self,
+        bytes: Bytes,
+        log_namespace: LogNamespace,
+    ) -> crate::Result<SmallVec<[Event; 1]>> {
+        let datagram = parse_datagram(&bytes)?;
+        let now = Utc::now();
+
+        let mut events = SmallVec::new();
+        for sample in &datagram.samples {
+            match sample {
+                Sample::Flow(sample) => events.push(Event::Log(flow_sample_to_log(
+                    &datagram,
+                    sample,
+                    log_namespace,
+                ))),
+                Sample::Counters(sample) => events.extend(
+                    counters_sample_to_metrics(&datagram, sample)
+                        .into_iter()
+                        .map(|metric| Event::Metric(metric.with_timestamp(Some(now)))),
+                ),
+            }
+        }
+        Ok(events)
+    }
+}
+
+fn flow_sample_to_log(
+    datagram: &Datagram,
+    sample: &FlowSample,
+    log_namespace: LogNamespace,
+) -> LogEvent {
+    let mut data = sample.fields.clone();
+    let mut insert = |key: &str, value: i64| {
+        data.insert(key.into(), value.into());
+    };
+    insert("sub_agent_id", datagram.sub_agent_id.into());
+    insert("datagram_sequence_number", datagram.sequence_number.into());
+    insert("uptime", datagram.uptime.into());
+    insert("sequence_number", sample.sequence_number.into());
+    insert("source_id_type", sample.source_id_type.into());
+    insert("source_id_index", sample.source_id_index.into());
+    insert("sampling_rate", sample.sampling_rate.into());
+    insert("sample_pool", sample.sample_pool.into());
+    insert("drops", sample.drops.into());
+    insert("input_interface", sample.input_interface.into());
+    insert("output_interface", sample.output_interface.into());
+    data.insert(
+        "agent_address".into(),
+        datagram.agent_address.to_string().into(),
+    );
+
+    log_namespace.new_log_from_data(data)
+}
+
+fn counters_sample_to_metrics(datagram: &Datagram, sample: &CountersSample) -> Vec<Metric> {
+    let mut tags = metric_tags!(
+        "agent_address" => datagram.agent_address.to_string(),
+        "sub_agent_id" => datagram.sub_agent_id.to_string(),
+    );
+
+    let mut metrics = Vec::new();
+    for record in &sample.records {
+        match record {
+            CounterRecord::GenericInterface(counters) => {
+                tags.replace("if_index".into(), counters.if_index.to_string());
+                tags.replace("if_type".into(), counters.if_type.to_string());
+                generic_interface_metrics(counters, &tags, &mut metrics);
+            }
+            CounterRecord::EthernetInterface(counters) => {
+                // Ethernet counters don't carry their own interface index, they apply to the data
+                // source of the sample.
+                tags.replace("if_index".into(), sample.source_id_index.to_string());
+                ethernet_interface_metrics(counters, &tags, &mut metrics);
+            }
+        }
+    }
+    metrics
+}
+
+fn counter(name: &str, value: impl Into<f64>, tags: &MetricTags) -> Metric {
+    Metric::new(
+        name,
+        MetricKind::Absolute,
+        MetricValue::Counter {
+            value: value.into(),
+        },
+    )
+    .with_namespace(Some(SflowConfig::NAME))
+    .with_tags(Some(tags.clone()))
+}
+
+fn gauge(name: &str, value: impl Into<f64>, tags: &MetricTags) -> Metric {
+    Metric::new(
+        name,
+        MetricKind::Absolute,
+        MetricValue::Gauge {
+            value: value.into(),
+        },
+    )
+    .with_namespace(Some(SflowConfig::NAME))
+    .with_tags(Some(tags.clone()))
+}
+
+fn generic_interface_metrics(
+    counters: &GenericInterfaceCounters,
+    tags: &MetricTags,
+    metrics: &mut Vec<Metric>,
+) {
+    metrics.extend([
+        gauge("interface_speed_bits", counters.if_speed as f64, tags),
+        gauge("interface_admin_up", counters.if_status & 1, tags),
+        gauge("interface_oper_up", (counters.if_status >> 1) & 1, tags),
+        counter(
+            "interface_in_octets_total",
+            counters.if_in_octets as f64,
+            tags,
+        ),
+        counter(
+            "interface_in_unicast_packets_total",
+            counters.if_in_ucast_pkts,
+            tags,
+        ),
+        counter(
+            "interface_in_multicast_packets_total",
+            counters.if_in_multicast_pkts,
+            tags,
+        ),
+        counter(
+            "interface_in_broadcast_packets_total",
+            counters.if_in_broadcast_pkts,
+            tags,
+        ),
+        counter("interface_in_discards_total", counters.if_in_discards, tags),
+        counter("interface_in_errors_total", counters.if_in_errors, tags),
+        counter(
+            "interface_in_unknown_protocols_total",
+            counters.if_in_unknown_protos,
+            tags,
+        ),
+        counter(
+            "interface_out_octets_total",
+            counters.if_out_octets as f64,
+            tags,
+        ),
+        counter(
+            "interface_out_unicast_packets_total",
+            counters.if_out_ucast_pkts,
+            tags,
+        ),
+        counter(
+            "interface_out_multicast_packets_total",
+            counters.if_out_multicast_pkts,
+            tags,
+        ),
+        counter(
+            "interface_out_broadcast_packets_total",
+            counters.if_out_broadcast_pkts,
+            tags,
+        ),
+        counter(
+            "interface_out_discards_total",
+            counters.if_out_discards,
+            tags,
+        ),
+        counter("interface_out_errors_total", counters.if_out_errors, tags),
+    ]);
+}
+
+fn ethernet_interface_metrics(
+    counters: &EthernetInterfaceCounters,
+    tags: &MetricTags,
+    metrics: &mut Vec<Metric>,
+) {
+    metrics.extend(
+        [
+            ("alignment_errors", counters.alignment_errors),
+            ("fcs_errors", counters.fcs_errors),
+            ("single_collision_frames", counters.single_collision_frames),
+            (
+                "multiple_collision_frames",
+                counters.multiple_collision_frames,
+            ),
+            ("sqe_test_errors", counters.sqe_test_errors),
+            ("deferred_transmissions", counters.deferred_transmissions),
+            ("late_collisions", counters.late_collisions),
+            ("excessive_collisions", counters.excessive_collisions),
+            (
+                "internal_mac_transmit_errors",
+                counters.internal_mac_transmit_errors,
+            ),
+            ("carrier_sense_errors", counters.carrier_sense_errors),
+            ("frame_too_longs", counters.frame_too_longs),
+            (
+                "internal_mac_receive_errors",
+                counters.internal_mac_receive_errors,
+            ),
+            ("symbol_errors", counters.symbol_errors),
+        ]
+        .into_iter()
+        .map(|(name, value)| counter(&format!("ethernet_{name}_total"), value, tags)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf, time::Duration};
+
+    use tokio::{net::UdpSocket, time::sleep};
+    use vector_lib::config::ComponentKey;
+
+    use super::*;
+    use crate::{
+        test_util::{
+            collect_ready,
+            components::{assert_source_compliance, SOCKET_PUSH_SOURCE_TAGS},
+            next_addr,
+        },
+        SourceSender,
+    };
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests/data/sflow")
+            .join(name);
+        fs::read(path).unwrap()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SflowConfig>();
+    }
+
+    #[test]
+    fn counters_become_metrics() {
+        let events = SflowDeserializer
+            .parse(
+                Bytes::from(fixture("counters-sample.bin")),
+                LogNamespace::Legacy,
+            )
+            .unwrap();
+        assert_eq!(events.len(), 16 + 13);
+
+        let metric = events
+            .iter()
+            .map(Event::as_metric)
+            .find(|metric| metric.name() == "interface_in_octets_total")
+            .unwrap();
+        assert_eq!(metric.namespace(), Some("sflow"));
+        assert_eq!(metric.kind(), MetricKind::Absolute);
+        assert_eq!(
+            metric.value(),
+            &MetricValue::Counter {
+                value: 5_000_000_000.0
+            }
+        );
+        assert_eq!(metric.tag_value("if_index").as_deref(), Some("3"));
+        assert_eq!(
+            metric.tag_value("agent_address").as_deref(),
+            Some("192.0.2.10")
+        );
+
+        let oper_up = events
+            .iter()
+            .map(Event::as_metric)
+            .find(|metric| metric.name() == "interface_oper_up")
+            .unwrap();
+        assert_eq!(oper_up.value(), &MetricValue::Gauge { value: 1.0 });
+    }
+
+    #[tokio::test]
+    async fn receives_flow_samples() {
+        assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let in_addr = next_addr();
+            let (tx, rx) = SourceSender::new_test();
+            let (cx, shutdown) = SourceContext::new_shutdown(&ComponentKey::from("sflow"), tx);
+            let source = SflowConfig::from_address(in_addr.into())
+                .build(cx)
+                .await
+                .unwrap();
+            tokio::spawn(source);
+            sleep(Duration::from_millis(250)).await;
+
+            let socket = UdpSocket::bind(next_addr()).await.unwrap();
+            socket
+                .send_to(&fixture("flow-sample.bin"), in_addr)
+                .await
+                .unwrap();
+            sleep(Duration::from_millis(250)).await;
+            shutdown
+                .shutdown_all(Some(
+                    tokio::time::Instant::now() + Duration::from_millis(100),
+                ))
+                .await;
+
+            let events = collect_ready(rx).await;
+            assert_eq!(events.len(), 1);
+
+            let log = events[0].as_log();
+            assert_eq!(log["agent_address"], "192.0.2.10".into());
+            assert_eq!(log["sampling_rate"], 1024.into());
+            assert_eq!(log["dst_port"], 443.into());
+            assert_eq!(log["host"], "127.0.0.1".into());
+            assert!(log.get("port").is_none());
+        })
+        .await;
+    }
+}
@@ -0,0 +1,591 @@
+//! Decoding of sFlow version 5 datagrams.
+//!
+//! See <https://sflow.org/sflow_version_5.txt> for the datagram layout. All structures are XDR
+//! encoded: big endian, with opaque data padded to a multiple of four bytes. Sample and record
+//! formats that are not understood are skipped using their length prefix.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use snafu::Snafu;
+
+use crate::event::{ObjectMap, Value};
+
+const SFLOW_V5: u32 = 5;
+
+// Sample formats, enterprise 0.
+const FLOW_SAMPLE: u32 = 1;
+const COUNTERS_SAMPLE: u32 = 2;
+const FLOW_SAMPLE_EXPANDED: u32 = 3;
+const COUNTERS_SAMPLE_EXPANDED: u32 = 4;
+
+// Flow record formats, enterprise 0.
+const SAMPLED_HEADER: u32 = 1;
+const EXTENDED_SWITCH: u32 = 1001;
+const EXTENDED_ROUTER: u32 = 1002;
+
+// Counter record formats, enterprise 0.
+const GENERIC_INTERFACE_COUNTERS: u32 = 1;
+const ETHERNET_INTERFACE_COUNTERS: u32 = 2;
+
+// Sampled header protocols.
+const HEADER_PROTOCOL_ETHERNET: u32 = 1;
+const HEADER_PROTOCOL_IPV4: u32 = 11;
+const HEADER_PROTOCOL_IPV6: u32 = 12;
+
+const ETHER_TYPE_IPV4: u16 = 0x0800;
+const ETHER_TYPE_IPV6: u16 = 0x86dd;
+const ETHER_TYPE_VLAN: u16 = 0x8100;
+
+/// Errors that can occur while decoding a datagram.
+#[derive(Debug, Eq, PartialEq, Snafu)]
+pub enum ParseError {
+    #[snafu(display(
+        "Datagram truncated: needed {} bytes, only {} available",
+        needed,
+        available
+    ))]
+    Truncated { needed: usize, available: usize },
+
+    #[snafu(display("Unsupported sFlow version {}", version))]
+    UnsupportedVersion { version: u32 },
+
+    #[snafu(display("Unsupported agent address type {}", address_type))]
+    UnsupportedAddressType { address_type: u32 },
+}
+
+/// A decoded sFlow datagram.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Datagram {
+    pub agent_address: IpAddr,
+    pub sub_agent_id: u32,
+    pub sequence_number: u32,
+    pub uptime: u32,
+    pub samples: Vec<Sample>,
+}
+
+/// A single sample carried by a datagram.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sample {
+    Flow(FlowSample),
+    Counters(CountersSample),
+}
+
+/// A packet sample, along with the fields decoded from its flow records.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowSample {
+    pub sequence_number: u32,
+    pub source_id_type: u32,
+    pub source_id_index: u32,
+    pub sampling_rate: u32,
+    pub sample_pool: u32,
+    pub drops: u32,
+    pub input_interface: u32,
+    pub output_interface: u32,
+    pub fields: ObjectMap,
+}
+
+/// A counters sample taken from a single data source, usually an interface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CountersSample {
+    pub sequence_number: u32,
+    pub source_id_type: u32,
+    pub source_id_index: u32,
+    pub records: Vec<CounterRecord>,
+}
+
+/// The counter records understood by the source.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CounterRecord {
+    GenericInterface(GenericInterfaceCounters),
+    EthernetInterface(EthernetInterfaceCounters),
+}
+
+/// Generic interface counters, mirroring the IF-MIB `ifTable`/`ifXTable` entries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GenericInterfaceCounters {
+    pub if_index: u32,
+    pub if_type: u32,
+    pub if_speed: u64,
+    pub if_direction: u32,
+    pub if_status: u32,
+    pub if_in_octets: u64,
+    pub if_in_ucast_pkts: u32,
+    pub if_in_multicast_pkts: u32,
+    pub if_in_broadcast_pkts: u32,
+    pub if_in_discards: u32,
+    pub if_in_errors: u32,
+    pub if_in_unknown_protos: u32,
+    pub if_out_octets: u64,
+    pub if_out_ucast_pkts: u32,
+    pub if_out_multicast_pkts: u32,
+    pub if_out_broadcast_pkts: u32,
+    pub if_out_discards: u32,
+    pub if_out_errors: u32,
+    pub if_promiscuous_mode: u32,
+}
+
+/// Ethernet interface counters, mirroring the EtherLike-MIB `dot3StatsTable` entries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EthernetInterfaceCounters {
+    pub alignment_errors: u32,
+    pub fcs_errors: u32,
+    pub single_collision_frames: u32,
+    pub multiple_collision_frames: u32,
+    pub sqe_test_errors: u32,
+    pub deferred_transmissions: u32,
+    pub late_collisions: u32,
+    pub excessive_collisions: u32,
+    pub internal_mac_transmit_errors: u32,
+    pub carrier_sense_errors: u32,
+    pub frame_too_longs: u32,
+    pub internal_mac_receive_errors: u32,
+    pub symbol_errors: u32,
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    const fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    const fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        if self.remaining() < n {
+            return Err(ParseError::Truncated {
+                needed: n,
+                available: self.remaining(),
+            });
+        }
+        let bytes = &self.buf[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(bytes)
+    }
+
+    /// Takes XDR opaque data of length `n`, skipping the padding that follows it.
+    fn take_opaque(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        let bytes = self.take(n)?;
+        let padding = (4 - n % 4) % 4;
+        self.take(padding.min(self.remaining()))?;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, ParseError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, ParseError> {
+        Ok(((self.u32()? as u64) << 32) | self.u32()? as u64)
+    }
+
+    fn address(&mut self) -> Result<IpAddr, ParseError> {
+        match self.u32()? {
+            1 => {
+                let b = self.take(4)?;
+                Ok(Ipv4Addr::new(b[0], b[1], b[2], b[3]).into())
+            }
+            2 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(self.take(16)?);
+                Ok(Ipv6Addr::from(octets).into())
+            }
+            address_type => Err(ParseError::UnsupportedAddressType { address_type }),
+        }
+    }
+
+    /// Reads a `(format, length)` prefixed structure, returning its format and a reader over its
+    /// contents.
+    fn structure(&mut self) -> Result<(u32, Reader<'a>), ParseError> {
+        let format = self.u32()?;
+        let length = self.u32()? as usize;
+        Ok((format, Reader::new(self.take_opaque(length)?)))
+    }
+}
+
+/// Decodes an sFlow v5 datagram.
+pub fn parse_datagram(bytes: &[u8]) -> Result<Datagram, ParseError> {
+    let mut reader = Reader::new(bytes);
+    let version = reader.u32()?;
+    if version != SFLOW_V5 {
+        return Err(ParseError::UnsupportedVersion { version });
+    }
+
+    let agent_address = reader.address()?;
+    let sub_agent_id = reader.u32()?;
+    let sequence_number = reader.u32()?;
+    let uptime = reader.u32()?;
+    let sample_count = reader.u32()?;
+
+    let mut samples = Vec::new();
+    for _ in 0..sample_count {
+        let (format, mut sample) = reader.structure()?;
+        match format {
+            FLOW_SAMPLE => samples.push(Sample::Flow(parse_flow_sample(&mut sample, false)?)),
+            FLOW_SAMPLE_EXPANDED => {
+                samples.push(Sample::Flow(parse_flow_sample(&mut sample, true)?))
+            }
+            COUNTERS_SAMPLE => {
+                samples.push(Sample::Counters(parse_counters_sample(&mut sample, false)?))
+            }
+            COUNTERS_SAMPLE_EXPANDED => {
+                samples.push(Sample::Counters(parse_counters_sample(&mut sample, true)?))
+            }
+            // Enterprise specific or unknown sample formats.
+            _ => {}
+        }
+    }
+
+    Ok(Datagram {
+        agent_address,
+        sub_agent_id,
+        sequence_number,
+        uptime,
+        samples,
+    })
+}
+
+/// Reads a data source ID, which the compact sample formats pack into a single word.
+fn source_id(reader: &mut Reader<'_>, expanded: bool) -> Result<(u32, u32), ParseError> {
+    if expanded {
+        Ok((reader.u32()?, reader.u32()?))
+    } else {
+        let source_id = reader.u32()?;
+        Ok((source_id >> 24, source_id & 0x00ff_ffff))
+    }
+}
+
+/// Reads an interface, which the compact sample formats pack into a single word.
+fn interface(reader: &mut Reader<'_>, expanded: bool) -> Result<u32, ParseError> {
+    if expanded {
+        let _format = reader.u32()?;
+        reader.u32()
+    } else {
+        Ok(reader.u32()? & 0x3fff_ffff)
+    }
+}
+
+fn parse_flow_sample(reader: &mut Reader<'_>, expanded: bool) -> Result<FlowSample, ParseError> {
+    let sequence_number = reader.u32()?;
+    let (source_id_type, source_id_index) = source_id(reader, expanded)?;
+    let sampling_rate = reader.u32()?;
+    let sample_pool = reader.u32()?;
+    let drops = reader.u32()?;
+    let input_interface = interface(reader, expanded)?;
+    let output_interface = interface(reader, expanded)?;
+    let record_count = reader.u32()?;
+
+    let mut fields = ObjectMap::new();
+    for _ in 0..record_count {
+        let (format, mut record) = reader.structure()?;
+        match format {
+            SAMPLED_HEADER => parse_sampled_header(&mut record, &mut fields)?,
+            EXTENDED_SWITCH => {
+                insert(&mut fields, "src_vlan", record.u32()?);
+                insert(&mut fields, "src_priority", record.u32()?);
+                insert(&mut fields, "dst_vlan", record.u32()?);
+                insert(&mut fields, "dst_priority", record.u32()?);
+            }
+            EXTENDED_ROUTER => {
+                let next_hop = record.address()?;
+                fields.insert("next_hop".into(), next_hop.to_string().into());
+                insert(&mut fields, "src_mask_len", record.u32()?);
+                insert(&mut fields, "dst_mask_len", record.u32()?);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(FlowSample {
+        sequence_number,
+        source_id_type,
+        source_id_index,
+        sampling_rate,
+        sample_pool,
+        drops,
+        input_interface,
+        output_interface,
+        fields,
+    })
+}
+
+fn insert(fields: &mut ObjectMap, name: &str, value: impl Into<i64>) {
+    fields.insert(name.into(), Value::Integer(value.into()));
+}
+
+fn parse_sampled_header(reader: &mut Reader<'_>, fields: &mut ObjectMap) -> Result<(), ParseError> {
+    let protocol = reader.u32()?;
+    let frame_length = reader.u32()?;
+    let stripped = reader.u32()?;
+    let header_length = reader.u32()? as usize;
+    let header = reader.take_opaque(header_length)?;
+
+    insert(fields, "header_protocol", protocol);
+    insert(fields, "frame_length", frame_length);
+    insert(fields, "stripped", stripped);
+
+    // The sampled header is a truncated copy of the packet, so decoding stops silently at
+    // whatever layer the snapshot ends.
+    let mut packet = Reader::new(header);
+    match protocol {
+        HEADER_PROTOCOL_ETHERNET => decode_ethernet(&mut packet, fields),
+        HEADER_PROTOCOL_IPV4 => decode_ipv4(&mut packet, fields),
+        HEADER_PROTOCOL_IPV6 => decode_ipv6(&mut packet, fields),
+        _ => None,
+    };
+
+    Ok(())
+}
+
+fn mac(bytes: &[u8]) -> Value {
+    Value::from(
+        bytes
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<Vec<_>>()
+            .join(":"),
+    )
+}
+
+fn read_u16(packet: &mut Reader<'_>) -> Option<u16> {
+    let bytes = packet.take(2).ok()?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn decode_ethernet(packet: &mut Reader<'_>, fields: &mut ObjectMap) -> Option<()> {
+    let dst_mac = packet.take(6).ok()?;
+    let src_mac = packet.take(6).ok()?;
+    fields.insert("dst_mac".into(), mac(dst_mac));
+    fields.insert("src_mac".into(), mac(src_mac));
+
+    let mut ether_type = read_u16(packet)?;
+    if ether_type == ETHER_TYPE_VLAN {
+        let tci = read_u16(packet)?;
+        insert(fields, "vlan", tci & 0x0fff);
+        ether_type = read_u16(packet)?;
+    }
+    insert(fields, "ether_type", ether_type);
+
+    match ether_type {
+        ETHER_TYPE_IPV4 => decode_ipv4(packet, fields),
+        ETHER_TYPE_IPV6 => decode_ipv6(packet, fields),
+        _ => None,
+    }
+}
+
+fn decode_ipv4(packet: &mut Reader<'_>, fields: &mut ObjectMap) -> Option<()> {
+    let header = packet.take(20).ok()?;
+    let header_length = ((header[0] & 0x0f) as usize) * 4;
+    insert(fields, "ip_version", 4);
+    insert(fields, "ip_tos", header[1]);
+    insert(
+        fields,
+        "ip_total_length",
+        u16::from_be_bytes([header[2], header[3]]),
+    );
+    insert(fields, "ip_ttl", header[8]);
+    insert(fields, "ip_protocol", header[9]);
+    let src = Ipv4Addr::new(header[12], header[13], header[14], header[15]);
+    let dst = Ipv4Addr::new(header[16], header[17], header[18], header[19]);
+    fields.insert("src_ip".into(), src.to_string().into());
+    fields.insert("dst_ip".into(), dst.to_string().into());
+
+    // Skip IP options.
+    packet.take(header_length.checked_sub(20)?).ok()?;
+    decode_transport(header[9], packet, fields)
+}
+
+fn decode_ipv6(packet: &mut Reader<'_>, fields: &mut ObjectMap) -> Option<()> {
+    let header = packet.take(40).ok()?;
+    let mut src = [0u8; 16];
+    let mut dst = [0u8; 16];
+    src.copy_from_slice(&header[8..24]);
+    dst.copy_from_slice(&header[24..40]);
+    insert(fields, "ip_version", 6);
+    insert(
+        fields,
+        "ip_tos",
+        ((header[0] & 0x0f) << 4) | (header[1] >> 4),
+    );
+    insert(
+        fields,
+        "ip_total_length",
+        u32::from(u16::from_be_bytes([header[4], header[5]])) + 40,
+    );
+    insert(fields, "ip_protocol", header[6]);
+    insert(fields, "ip_ttl", header[7]);
+    fields.insert("src_ip".into(), Ipv6Addr::from(src).to_string().into());
+    fields.insert("dst_ip".into(), Ipv6Addr::from(dst).to_string().into());
+
+    decode_transport(header[6], packet, fields)
+}
+
+fn decode_transport(protocol: u8, packet: &mut Reader<'_>, fields: &mut ObjectMap) -> Option<()> {
+    match protocol {
+        // TCP, UDP
+        6 | 17 => {
+            insert(fields, "src_port", read_u16(packet)?);
+            insert(fields, "dst_port", read_u16(packet)?);
+            if protocol == 6 {
+                let rest = packet.take(10).ok()?;
+                insert(fields, "tcp_flags", rest[9]);
+            }
+        }
+        // ICMP
+        1 | 58 => {
+            let bytes = packet.take(2).ok()?;
+            insert(fields, "icmp_type", bytes[0]);
+            insert(fields, "icmp_code", bytes[1]);
+        }
+        _ => {}
+    }
+    Some(())
+}
+
+fn parse_counters_sample(
+    reader: &mut Reader<'_>,
+    expanded: bool,
+) -> Result<CountersSample, ParseError> {
+    let sequence_number = reader.u32()?;
+    let (source_id_type, source_id_index) = source_id(reader, expanded)?;
+    let record_count = reader.u32()?;
+
+    let mut records = Vec::new();
+    for _ in 0..record_count {
+        let (format, mut record) = reader.structure()?;
+        match format {
+            GENERIC_INTERFACE_COUNTERS => {
+                records.push(CounterRecord::GenericInterface(GenericInterfaceCounters {
+                    if_index: record.u32()?,
+                    if_type: record.u32()?,
+                    if_speed: record.u64()?,
+                    if_direction: record.u32()?,
+                    if_status: record.u32()?,
+                    if_in_octets: record.u64()?,
+                    if_in_ucast_pkts: record.u32()?,
+                    if_in_multicast_pkts: record.u32()?,
+                    if_in_broadcast_pkts: record.u32()?,
+                    if_in_discards: record.u32()?,
+                    if_in_errors: record.u32()?,
+                    if_in_unknown_protos: record.u32()?,
+                    if_out_octets: record.u64()?,
+                    if_out_ucast_pkts: record.u32()?,
+                    if_out_multicast_pkts: record.u32()?,
+                    if_out_broadcast_pkts: record.u32()?,
+                    if_out_discards: record.u32()?,
+                    if_out_errors: record.u32()?,
+                    if_promiscuous_mode: record.u32()?,
+                }))
+            }
+            ETHERNET_INTERFACE_COUNTERS => records.push(CounterRecord::EthernetInterface(
+                EthernetInterfaceCounters {
+                    alignment_errors: record.u32()?,
+                    fcs_errors: record.u32()?,
+                    single_collision_frames: record.u32()?,
+                    multiple_collision_frames: record.u32()?,
+                    sqe_test_errors: record.u32()?,
+                    deferred_transmissions: record.u32()?,
+                    late_collisions: record.u32()?,
+                    excessive_collisions: record.u32()?,
+                    internal_mac_transmit_errors: record.u32()?,
+                    carrier_sense_errors: record.u32()?,
+                    frame_too_longs: record.u32()?,
+                    internal_mac_receive_errors: record.u32()?,
+                    symbol_errors: record.u32()?,
+                },
+            )),
+            _ => {}
+        }
+    }
+
+    Ok(CountersSample {
+        sequence_number,
+        source_id_type,
+        source_id_index,
+        records,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use super::*;
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap())
+            .join("tests/data/sflow")
+            .join(name);
+        fs::read(path).unwrap()
+    }
+
+    #[test]
+    fn parses_flow_sample() {
+        let datagram = parse_datagram(&fixture("flow-sample.bin")).unwrap();
+        assert_eq!(datagram.agent_address, IpAddr::from([192, 0, 2, 10]));
+        assert_eq!(datagram.sequence_number, 17);
+        assert_eq!(datagram.samples.len(), 1);
+
+        let Sample::Flow(sample) = &datagram.samples[0] else {
+            panic!("expected a flow sample");
+        };
+        assert_eq!(sample.sampling_rate, 1024);
+        assert_eq!(sample.input_interface, 3);
+        assert_eq!(sample.output_interface, 7);
+        assert_eq!(sample.fields["src_mac"], "00:11:22:33:44:55".into());
+        assert_eq!(sample.fields["vlan"], 10.into());
+        assert_eq!(sample.fields["src_ip"], "198.51.100.1".into());
+        assert_eq!(sample.fields["dst_ip"], "203.0.113.7".into());
+        assert_eq!(sample.fields["ip_protocol"], 6.into());
+        assert_eq!(sample.fields["src_port"], 51000.into());
+        assert_eq!(sample.fields["dst_port"], 443.into());
+        assert_eq!(sample.fields["tcp_flags"], 0x18.into());
+        assert_eq!(sample.fields["src_vlan"], 10.into());
+        assert_eq!(sample.fields["dst_vlan"], 20.into());
+    }
+
+    #[test]
+    fn parses_counters_sample() {
+        let datagram = parse_datagram(&fixture("counters-sample.bin")).unwrap();
+        assert_eq!(datagram.samples.len(), 1);
+
+        let Sample::Counters(sample) = &datagram.samples[0] else {
+            panic!("expected a counters sample");
+        };
+        assert_eq!(sample.source_id_index, 3);
+        assert_eq!(sample.records.len(), 2);
+
+        let CounterRecord::GenericInterface(generic) = &sample.records[0] else {
+            panic!("expected generic interface counters");
+        };
+        assert_eq!(generic.if_index, 3);
+        assert_eq!(generic.if_speed, 10_000_000_000);
+        assert_eq!(generic.if_in_octets, 5_000_000_000);
+        assert_eq!(generic.if_out_octets, 7_000_000_000);
+        assert_eq!(generic.if_in_errors, 2);
+
+        let CounterRecord::EthernetInterface(ethernet) = &sample.records[1] else {
+            panic!("expected ethernet interface counters");
+        };
+        assert_eq!(ethernet.fcs_errors, 4);
+    }
+
+    #[test]
+    fn rejects_truncated_datagram() {
+        let bytes = fixture("flow-sample.bin");
+        let error = parse_datagram(&bytes[..bytes.len() - 8]).unwrap_err();
+        assert!(matches!(error, ParseError::Truncated { .. }));
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let error = parse_datagram(&[0, 0, 0, 4]).unwrap_err();
+        assert_eq!(error, ParseError::UnsupportedVersion { version: 4 });
+    }
+}
@@ -0,0 +1,408 @@
+use std::{
+    net::SocketAddr,
+    time::{Duration, Instant},
+};
+
+use snafu::{ResultExt, Snafu};
+use tokio::net::UdpSocket;
+
+use crate::sources::util::snmp::{
+    message::{
+        AnyPdu, CommunityMessage, Message, MessageError, Pdu, PduType, ScopedPdu, ScopedPduData,
+        UsmSecurityParameters, V3Message, Value, VarBind, Version, FLAG_REPORTABLE,
+    },
+    oid::Oid,
+    usm::{self, LocalizedKeys, UsmError, UsmUser},
+};
+
+/// The maximum message size advertised in SNMPv3 requests.
+const MAX_MESSAGE_SIZE: i64 = 65507;
+/// The number of variable bindings requested at once by `get`.
+const MAX_GET_VARBINDS: usize = 32;
+/// `usmStatsNotInTimeWindows.0`.
+const USM_STATS_NOT_IN_TIME_WINDOWS: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 1, 2, 0];
+
+#[derive(Debug, Snafu)]
+pub enum ClientError {
+    #[snafu(display("I/O error: {}", source))]
+    Io { source: std::io::Error },
+
+    #[snafu(display("Request timed out"))]
+    Timeout,
+
+    #[snafu(display("Malformed response: {}", source))]
+    Decode { source: MessageError },
+
+    #[snafu(display("{}", source))]
+    Usm { source: UsmError },
+
+    #[snafu(display(
+        "Agent returned error status {} for variable binding {}",
+        status,
+        index
+    ))]
+    ErrorStatus { status: i64, index: i64 },
+
+    #[snafu(display("Agent returned a report for {}", oid))]
+    Report { oid: String },
+
+    #[snafu(display("Agent clock is not synchronized"))]
+    NotInTimeWindow,
+}
+
+impl From<UsmError> for ClientError {
+    fn from(source: UsmError) -> Self {
+        Self::Usm { source }
+    }
+}
+
+/// How requests to an agent are secured.
+#[derive(Clone)]
+pub enum Security {
+    Community(Vec<u8>),
+    Usm {
+        user: UsmUser,
+        context_name: Vec<u8>,
+    },
+}
+
+/// The authoritative engine of an SNMPv3 agent, as discovered from its reports.
+struct Engine {
+    engine_id: Vec<u8>,
+    boots: u32,
+    time: u32,
+    discovered_at: Instant,
+    keys: Option<LocalizedKeys>,
+}
+
+impl Engine {
+    fn security_parameters(&self, user_name: &[u8]) -> UsmSecurityParameters {
+        UsmSecurityParameters {
+            engine_id: self.engine_id.clone(),
+            engine_boots: self.boots,
+            engine_time: self
+                .time
+                .saturating_add(self.discovered_at.elapsed().as_secs() as u32),
+            user_name: user_name.to_vec(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A client issuing SNMP requests to a single agent.
+pub struct SnmpClient {
+    socket: UdpSocket,
+    security: Security,
+    engine: Option<Engine>,
+    timeout: Duration,
+    retries: usize,
+    max_repetitions: u32,
+    next_request_id: i32,
+    /// The number of bytes received since the counter was last taken.
+    received_bytes: usize,
+}
+
+impl SnmpClient {
+    pub async fn connect(
+        address: SocketAddr,
+        security: Security,
+        timeout: Duration,
+        retries: usize,
+        max_repetitions: u32,
+    ) -> Result<Self, ClientError> {
+        let bind_address: SocketAddr = if address.is_ipv4() {
+            ([0, 0, 0, 0], 0).into()
+        } else {
+            ([0u16; 8], 0).into()
+        };
+        let socket = UdpSocket::bind(bind_address).await.context(IoSnafu)?;
+        socket.connect(address).await.context(IoSnafu)?;
+        Ok(Self {
+            socket,
+            security,
+            engine: None,
+            timeout,
+            retries,
+            max_repetitions: max_repetitions.max(1),
+            next_request_id: rand::random::<i32>() & 0x7fff_ffff,
+            received_bytes: 0,
+        })
+    }
+
+    /// Returns the number of bytes received since the last call.
+    pub fn take_received_bytes(&mut self) -> usize {
+        std::mem::take(&mut self.received_bytes)
+    }
+
+    /// Fetches the given objects, omitting those the agent does not know.
+    pub async fn get(&mut self, oids: &[Oid]) -> Result<Vec<VarBind>, ClientError> {
+        let mut varbinds = Vec::with_capacity(oids.len());
+        for chunk in oids.chunks(MAX_GET_VARBINDS) {
+            let request = chunk
+                .iter()
+                .map(|oid| VarBind::new(oid.clone(), Value::Null))
+                .collect::<Vec<_>>();
+            let response = self.request(PduType::GetRequest, 0, 0, request).await?;
+            varbinds.extend(
+                response
+                    .varbinds
+                    .into_iter()
+                    .filter(|varbind| !varbind.value.is_exception()),
+            );
+        }
+        Ok(varbinds)
+    }
+
+    /// Retrieves all objects below `root`.
+    pub async fn walk(&mut self, root: &Oid) -> Result<Vec<VarBind>, ClientError> {
+        let mut varbinds = Vec::new();
+        let mut current = root.clone();
+        loop {
+            let request = vec![VarBind::new(current.clone(), Value::Null)];
+            let response = self
+                .request(
+                    PduType::GetBulkRequest,
+                    0,
+                    self.max_repetitions as i64,
+                    request,
+                )
+                .await?;
+            if response.varbinds.is_empty() {
+                return Ok(varbinds);
+            }
+
+            for varbind in response.varbinds {
+                // Agents must return increasing OIDs; guard against ones that loop.
+                if varbind.value == Value::EndOfMibView
+                    || !varbind.oid.starts_with(root)
+                    || varbind.oid <= current
+                {
+                    return Ok(varbinds);
+                }
+                current = varbind.oid.clone();
+                varbinds.push(varbind);
+            }
+        }
+    }
+
+    async fn request(
+        &mut self,
+        pdu_type: PduType,
+        non_repeaters: i64,
+        max_repetitions: i64,
+        varbinds: Vec<VarBind>,
+    ) -> Result<Pdu, ClientError> {
+        let mut attempt = 0;
+        loop {
+            let pdu = Pdu {
+                pdu_type,
+                request_id: self.request_id(),
+                error_status: non_repeaters,
+                error_index: max_repetitions,
+                varbinds: varbinds.clone(),
+            };
+            let result = if matches!(self.security, Security::Community(_)) {
+                self.exchange_community(&pdu).await
+            } else {
+                self.exchange_usm(&pdu).await
+            };
+            match result {
+                Ok(response) if response.error_status != 0 => {
+                    return Err(ClientError::ErrorStatus {
+                        status: response.error_status,
+                        index: response.error_index,
+                    })
+                }
+                Ok(response) => return Ok(response),
+                Err(ClientError::Timeout | ClientError::NotInTimeWindow)
+                    if attempt < self.retries =>
+                {
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn request_id(&mut self) -> i32 {
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1) & 0x7fff_ffff;
+        request_id
+    }
+
+    async fn exchange_community(&mut self, pdu: &Pdu) -> Result<Pdu, ClientError> {
+        let Security::Community(community) = &self.security else {
+            unreachable!("community exchange requires community security");
+        };
+        let request = CommunityMessage::encode(Version::V2c, community, pdu);
+        self.socket.send(&request).await.context(IoSnafu)?;
+
+        receive(
+            &self.socket,
+            self.timeout,
+            &mut self.received_bytes,
+            |raw| match Message::decode(raw) {
+                Ok(Message::Community(CommunityMessage {
+                    pdu: AnyPdu::Standard(response),
+                    ..
+                })) if response.pdu_type == PduType::Response
+                    && response.request_id == pdu.request_id =>
+                {
+                    Some(Ok(response))
+                }
+                // Late responses to earlier attempts are skipped.
+                Ok(_) => None,
+                Err(source) => Some(Err(ClientError::Decode { source })),
+            },
+        )
+        .await
+    }
+
+    async fn exchange_usm(&mut self, pdu: &Pdu) -> Result<Pdu, ClientError> {
+        if self.engine.is_none() {
+            self.engine = Some(self.discover().await?);
+        }
+        let (Security::Usm { user, context_name }, Some(engine)) = (&self.security, &self.engine)
+        else {
+            unreachable!("usm exchange requires usm security and a discovered engine");
+        };
+
+        let msg_id = pdu.request_id as i64;
+        let scoped = ScopedPdu::encode(&engine.engine_id, context_name, pdu);
+        let request = usm::seal(
+            msg_id,
+            MAX_MESSAGE_SIZE,
+            FLAG_REPORTABLE,
+            engine.security_parameters(&user.name),
+            engine.keys.as_ref(),
+            &scoped,
+        )?;
+        self.socket.send(&request).await.context(IoSnafu)?;
+
+        let keys = engine.keys.as_ref();
+        let (response, usm) = receive(
+            &self.socket,
+            self.timeout,
+            &mut self.received_bytes,
+            |raw| match Message::decode(raw) {
+                Ok(Message::V3(message)) if message.msg_id == msg_id => Some(
+                    usm::open(raw, &message, keys)
+                        .map(|scoped| (scoped.pdu, message.usm))
+                        .map_err(Into::into),
+                ),
+                Ok(_) => None,
+                Err(source) => Some(Err(ClientError::Decode { source })),
+            },
+        )
+        .await?;
+
+        match response {
+            AnyPdu::Standard(response) if response.pdu_type == PduType::Response => Ok(response),
+            AnyPdu::Standard(report) if report.pdu_type == PduType::Report => {
+                let oid = report
+                    .varbinds
+                    .first()
+                    .map(|varbind| varbind.oid.clone())
+                    .unwrap_or_default();
+                if oid.components() == USM_STATS_NOT_IN_TIME_WINDOWS {
+                    // Resynchronize with the clock of the agent before retrying.
+                    if let Some(engine) = self.engine.as_mut() {
+                        engine.boots = usm.engine_boots;
+                        engine.time = usm.engine_time;
+                        engine.discovered_at = Instant::now();
+                    }
+                    Err(ClientError::NotInTimeWindow)
+                } else {
+                    // The agent may have been replaced or reconfigured, so discover it again on
+                    // the next request.
+                    self.engine = None;
+                    Err(ClientError::Report {
+                        oid: oid.to_string(),
+                    })
+                }
+            }
+            other => Err(ClientError::Report {
+                oid: other.pdu_type().as_str().to_owned(),
+            }),
+        }
+    }
+
+    /// Discovers the engine ID, boots and time of the agent (RFC 3414 section 4).
+    async fn discover(&mut self) -> Result<Engine, ClientError> {
+        let mut attempt = 0;
+        loop {
+            let request_id = self.request_id();
+            let msg_id = request_id as i64;
+            let scoped = ScopedPdu::encode(
+                &[],
+                &[],
+                &Pdu::new(PduType::GetRequest, request_id, Vec::new()),
+            );
+            let (request, _) = V3Message::encode(
+                msg_id,
+                MAX_MESSAGE_SIZE,
+                FLAG_REPORTABLE,
+                &UsmSecurityParameters::default(),
+                &scoped,
+            );
+            self.socket.send(&request).await.context(IoSnafu)?;
+
+            let result = receive(
+                &self.socket,
+                self.timeout,
+                &mut self.received_bytes,
+                |raw| match Message::decode(raw) {
+                    Ok(Message::V3(message)) if message.msg_id == msg_id => {
+                        let is_report = matches!(
+                            &message.data,
+                            ScopedPduData::Plaintext(scoped)
+                                if scoped.pdu.pdu_type() == PduType::Report
+                        );
+                        is_report.then_some(Ok(message.usm))
+                    }
+                    Ok(_) => None,
+                    Err(source) => Some(Err(ClientError::Decode { source })),
+                },
+            )
+            .await;
+
+            match result {
+                Ok(usm) => {
+                    let Security::Usm { user, .. } = &self.security else {
+                        unreachable!("discovery requires usm security");
+                    };
+                    return Ok(Engine {
+                        keys: user.localize(&usm.engine_id)?,
+                        engine_id: usm.engine_id,
+                        boots: usm.engine_boots,
+                        time: usm.engine_time,
+                        discovered_at: Instant::now(),
+                    });
+                }
+                Err(ClientError::Timeout) if attempt < self.retries => attempt += 1,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+/// Receives datagrams until `accept` produces a result or the timeout expires.
+async fn receive<T>(
+    socket: &UdpSocket,
+    timeout: Duration,
+    received_bytes: &mut usize,
+    mut accept: impl FnMut(&[u8]) -> Option<Result<T, ClientError>>,
+) -> Result<T, ClientError> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut buf = vec![0; 65535];
+    loop {
+        let size = tokio::time::timeout_at(deadline, socket.recv(&mut buf))
+            .await
+            .map_err(|_| ClientError::Timeout)?
+            .context(IoSnafu)?;
+        *received_bytes += size;
+        if let Some(result) = accept(&buf[..size]) {
+            return result;
+        }
+    }
+}
@@ -0,0 +1,874 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use chrono::Utc;
+use futures::{future::join_all, StreamExt};
+use serde_with::serde_as;
+use snafu::Snafu;
+use tokio::time;
+use tokio_stream::wrappers::IntervalStream;
+use vector_lib::configurable::configurable_component;
+use vector_lib::{
+    config::LogNamespace, metric_tags, sensitive_string::SensitiveString,
+    EstimatedJsonEncodedSizeOf,
+};
+
+use self::client::{ClientError, Security, SnmpClient};
+use super::util::snmp::{
+    message::{Value, VarBind},
+    mib::OidNames,
+    oid::Oid,
+    to_event_value,
+    usm::{UsmUser, UsmUserConfig},
+};
+use crate::{
+    config::{GenerateConfig, SourceConfig, SourceContext, SourceOutput},
+    event::metric::{Metric, MetricKind, MetricTags, MetricValue},
+    internal_events::{
+        CollectionCompleted, EndpointBytesReceived, SnmpEventsReceived, SnmpRequestError,
+        StreamClosedError,
+    },
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+mod client;
+
+/// The standard SNMP agent port.
+const DEFAULT_PORT: u16 = 161;
+
+#[derive(Debug, Snafu)]
+enum SnmpBuildError {
+    #[snafu(display(
+        "Unknown object {:?}; use a numeric OID or add a MIB mapping file",
+        oid
+    ))]
+    UnknownObject { oid: String },
+
+    #[snafu(display("Target {:?} uses SNMPv3 but does not configure a user", address))]
+    MissingUser { address: String },
+}
+
+/// Configuration for the `snmp` source.
+#[serde_as]
+#[configurable_component(source("snmp", "Collect metrics from SNMP agents."))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpConfig {
+    /// The agents to poll.
+    targets: Vec<TargetConfig>,
+
+    /// The interval between scrapes.
+    ///
+    /// Can be overridden for each target.
+    #[serde(default = "default_scrape_interval_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Scrape Interval"))]
+    scrape_interval_secs: Duration,
+
+    /// The timeout for each SNMP request.
+    #[serde(default = "default_timeout_secs")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    #[configurable(metadata(docs::human_name = "Request Timeout"))]
+    timeout_secs: Duration,
+
+    /// The number of times a request is retried after timing out.
+    #[serde(default = "default_retries")]
+    retries: usize,
+
+    /// The number of table rows requested at once when walking tables.
+    #[serde(default = "default_max_repetitions")]
+    max_repetitions: u32,
+
+    /// A list of files mapping OIDs to object names.
+    ///
+    /// Each line contains an object name and its OID, as produced by `snmptranslate -Tz -On`
+    /// for a set of MIBs. Names from these files can be used in place of numeric OIDs. Common
+    /// objects from SNMPv2-MIB and IF-MIB are always known.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/etc/vector/mibs/oids.txt"))]
+    mib_files: Vec<PathBuf>,
+
+    /// Overrides the default namespace for the metrics emitted by the source.
+    ///
+    /// If set to an empty string, no namespace is added to the metrics.
+    ///
+    /// By default, `snmp` is used.
+    #[serde(default = "default_namespace")]
+    namespace: String,
+}
+
+/// An agent to poll.
+#[serde_as]
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TargetConfig {
+    /// The address of the agent.
+    ///
+    /// If no port is given, the standard port 161 is used.
+    #[configurable(metadata(docs::examples = "192.0.2.1"))]
+    #[configurable(metadata(docs::examples = "switch01.example.com:161"))]
+    address: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    version: SnmpVersion,
+
+    /// The community used for SNMPv2c requests.
+    #[serde(default = "default_community")]
+    #[configurable(metadata(docs::examples = "SECRET[secret_backend.snmp_community]"))]
+    community: SensitiveString,
+
+    /// The user used for SNMPv3 requests.
+    #[configurable(derived)]
+    user: Option<UsmUserConfig>,
+
+    /// The context name used for SNMPv3 requests.
+    context_name: Option<String>,
+
+    /// Overrides the interval between scrapes of this agent.
+    #[serde_as(as = "Option<serde_with::DurationSeconds<u64>>")]
+    #[configurable(metadata(docs::human_name = "Scrape Interval"))]
+    scrape_interval_secs: Option<Duration>,
+
+    /// Scalar objects to fetch.
+    #[serde(default)]
+    objects: Vec<ObjectConfig>,
+
+    /// Tables to walk.
+    #[serde(default)]
+    tables: Vec<TableConfig>,
+}
+
+/// The SNMP version used to poll an agent.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SnmpVersion {
+    /// SNMPv2c, using a community for authentication.
+    #[default]
+    #[serde(rename = "2c")]
+    V2c,
+
+    /// SNMPv3, using the user-based security model.
+    #[serde(rename = "3")]
+    V3,
+}
+
+/// An object whose value is emitted as a metric.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectConfig {
+    /// The name of the metric.
+    #[configurable(metadata(docs::examples = "interface_in_octets_total"))]
+    name: String,
+
+    /// The OID of the object, either numeric or as an object name.
+    ///
+    /// For tables, this is the OID of the column.
+    #[configurable(metadata(docs::examples = "1.3.6.1.2.1.1.3.0"))]
+    #[configurable(metadata(docs::examples = "ifHCInOctets"))]
+    oid: String,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    r#type: MetricType,
+}
+
+/// The type of metric emitted for an object.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricType {
+    /// A counter for `Counter32` and `Counter64` values, and a gauge for all others.
+    #[default]
+    Auto,
+
+    /// A counter.
+    Counter,
+
+    /// A gauge.
+    Gauge,
+}
+
+/// A table whose rows are emitted as metrics.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TableConfig {
+    /// The name of the tag holding the index of each row.
+    #[serde(default = "default_index_tag")]
+    index_tag: String,
+
+    /// The columns whose values are emitted as metrics.
+    metrics: Vec<ObjectConfig>,
+
+    /// The columns whose values are added as tags to the metrics of the same row.
+    ///
+    /// Columns of other tables sharing the same index, such as `ifName` from `ifXTable` for
+    /// rows of `ifTable`, can be used.
+    #[serde(default)]
+    tags: Vec<TagConfig>,
+}
+
+/// A column whose values are used as tags.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TagConfig {
+    /// The name of the tag.
+    #[configurable(metadata(docs::examples = "interface"))]
+    name: String,
+
+    /// The OID of the column, either numeric or as an object name.
+    #[configurable(metadata(docs::examples = "ifName"))]
+    oid: String,
+}
+
+const fn default_scrape_interval_secs() -> Duration {
+    Duration::from_secs(60)
+}
+
+const fn default_timeout_secs() -> Duration {
+    Duration::from_secs(5)
+}
+
+const fn default_retries() -> usize {
+    1
+}
+
+const fn default_max_repetitions() -> u32 {
+    25
+}
+
+fn default_namespace() -> String {
+    "snmp".to_string()
+}
+
+fn default_community() -> SensitiveString {
+    "public".to_string().into()
+}
+
+fn default_index_tag() -> String {
+    "index".to_string()
+}
+
+impl GenerateConfig for SnmpConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self {
+            targets: vec![TargetConfig {
+                address: "192.0.2.1".to_owned(),
+                version: SnmpVersion::V2c,
+                community: default_community(),
+                user: None,
+                context_name: None,
+                scrape_interval_secs: None,
+                objects: vec![ObjectConfig {
+                    name: "uptime_ticks".to_owned(),
+                    oid: "sysUpTime.0".to_owned(),
+                    r#type: MetricType::Gauge,
+                }],
+                tables: vec![TableConfig {
+                    index_tag: "if_index".to_owned(),
+                    metrics: vec![
+                        ObjectConfig {
+                            name: "interface_in_octets_total".to_owned(),
+                            oid: "ifHCInOctets".to_owned(),
+                            r#type: MetricType::Auto,
+                        },
+                        ObjectConfig {
+                            name: "interface_out_octets_total".to_owned(),
+                            oid: "ifHCOutOctets".to_owned(),
+                            r#type: MetricType::Auto,
+                        },
+                    ],
+                    tags: vec![TagConfig {
+                        name: "interface".to_owned(),
+                        oid: "ifName".to_owned(),
+                    }],
+                }],
+            }],
+            scrape_interval_secs: default_scrape_interval_secs(),
+            timeout_secs: default_timeout_secs(),
+            retries: default_retries(),
+            max_repetitions: default_max_repetitions(),
+            mib_files: Vec::new(),
+            namespace: default_namespace(),
+        })
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp")]
+impl SourceConfig for SnmpConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let names = OidNames::load(&self.mib_files)?;
+        let namespace = Some(self.namespace.clone()).filter(|namespace| !namespace.is_empty());
+        let targets = self
+            .targets
+            .iter()
+            .map(|target| {
+                SnmpTarget::new(
+                    self,
+                    target,
+                    &names,
+                    namespace.clone(),
+                    cx.shutdown.clone(),
+                    cx.out.clone(),
+                )
+            })
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(Box::pin(async move {
+            join_all(targets.into_iter().map(SnmpTarget::run))
+                .await
+                .into_iter()
+                .collect()
+        }))
+    }
+
+    fn outputs(&self, _global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        vec![SourceOutput::new_metrics()]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+/// A metric column of a table.
+struct Column {
+    name: String,
+    oid: Oid,
+    r#type: MetricType,
+}
+
+/// A table resolved from its configuration.
+struct Table {
+    index_tag: String,
+    metrics: Vec<Column>,
+    tags: Vec<(String, Oid)>,
+}
+
+/// The polling state of a single agent.
+struct SnmpTarget {
+    address: String,
+    security: Security,
+    client: Option<SnmpClient>,
+    interval: Duration,
+    timeout: Duration,
+    retries: usize,
+    max_repetitions: u32,
+    objects: Vec<Column>,
+    tables: Vec<Table>,
+    namespace: Option<String>,
+    tags: MetricTags,
+    shutdown: ShutdownSignal,
+    out: SourceSender,
+}
+
+impl SnmpTarget {
+    fn new(
+        config: &SnmpConfig,
+        target: &TargetConfig,
+        names: &OidNames,
+        namespace: Option<String>,
+        shutdown: ShutdownSignal,
+        out: SourceSender,
+    ) -> crate::Result<Self> {
+        let resolve = |oid: &str| {
+            names
+                .resolve(oid)
+                .ok_or_else(|| SnmpBuildError::UnknownObject {
+                    oid: oid.to_owned(),
+                })
+        };
+        let column = |object: &ObjectConfig| {
+            Ok::<_, SnmpBuildError>(Column {
+                name: object.name.clone(),
+                oid: resolve(&object.oid)?,
+                r#type: object.r#type,
+            })
+        };
+
+        let security = match target.version {
+            SnmpVersion::V2c => Security::Community(target.community.inner().as_bytes().to_vec()),
+            SnmpVersion::V3 => {
+                let user = target
+                    .user
+                    .as_ref()
+                    .ok_or_else(|| SnmpBuildError::MissingUser {
+                        address: target.address.clone(),
+                    })?;
+                Security::Usm {
+                    user: UsmUser::from_config(user)?,
+                    context_name: target.context_name.clone().unwrap_or_default().into_bytes(),
+                }
+            }
+        };
+
+        let objects = target
+            .objects
+            .iter()
+            .map(column)
+            .collect::<Result<Vec<_>, _>>()?;
+        let tables = target
+            .tables
+            .iter()
+            .map(|table| {
+                Ok::<_, SnmpBuildError>(Table {
+                    index_tag: table.index_tag.clone(),
+                    metrics: table.metrics.iter().map(column).collect::<Result<_, _>>()?,
+                    tags: table
+                        .tags
+                        .iter()
+                        .map(|tag| Ok((tag.name.clone(), resolve(&tag.oid)?)))
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            address: target.address.clone(),
+            security,
+            client: None,
+            interval: target
+                .scrape_interval_secs
+                .unwrap_or(config.scrape_interval_secs),
+            timeout: config.timeout_secs,
+            retries: config.retries,
+            max_repetitions: config.max_repetitions,
+            objects,
+            tables,
+            namespace,
+            tags: metric_tags!("host" => target.address.clone()),
+            shutdown,
+            out,
+        })
+    }
+
+    async fn run(mut self) -> Result<(), ()> {
+        let mut interval =
+            IntervalStream::new(time::interval(self.interval)).take_until(self.shutdown.clone());
+        while interval.next().await.is_some() {
+            let start = Instant::now();
+            let metrics = self.collect().await;
+            emit!(CollectionCompleted {
+                start,
+                end: Instant::now()
+            });
+
+            let count = metrics.len();
+            if self.out.send_batch(metrics).await.is_err() {
+                emit!(StreamClosedError { count });
+                return Err(());
+            }
+        }
+        Ok(())
+    }
+
+    async fn collect(&mut self) -> Vec<Metric> {
+        let (up_value, mut metrics) = match self.collect_metrics().await {
+            Ok(metrics) => (1.0, metrics),
+            Err(error) => {
+                emit!(SnmpRequestError {
+                    error,
+                    endpoint: &self.address,
+                });
+                // Start over with a new socket and engine discovery on the next scrape.
+                self.client = None;
+                (0.0, Vec::new())
+            }
+        };
+
+        let byte_size = metrics.estimated_json_encoded_size_of();
+        metrics.push(self.create_metric(
+            "up",
+            MetricValue::Gauge { value: up_value },
+            self.tags.clone(),
+        ));
+
+        emit!(SnmpEventsReceived {
+            byte_size,
+            count: metrics.len(),
+            endpoint: &self.address,
+        });
+
+        metrics
+    }
+
+    async fn client(&mut self) -> Result<&mut SnmpClient, ClientError> {
+        if self.client.is_none() {
+            let address = resolve_address(&self.address).await?;
+            let client = SnmpClient::connect(
+                address,
+                self.security.clone(),
+                self.timeout,
+                self.retries,
+                self.max_repetitions,
+            )
+            .await?;
+            self.client = Some(client);
+        }
+        Ok(self.client.as_mut().expect("client was just created"))
+    }
+
+    async fn collect_metrics(&mut self) -> Result<Vec<Metric>, ClientError> {
+        let object_oids = self
+            .objects
+            .iter()
+            .map(|object| object.oid.clone())
+            .collect::<Vec<_>>();
+        let mut walk_oids = self
+            .tables
+            .iter()
+            .flat_map(|table| {
+                table
+                    .metrics
+                    .iter()
+                    .map(|column| column.oid.clone())
+                    .chain(table.tags.iter().map(|(_, oid)| oid.clone()))
+            })
+            .collect::<Vec<_>>();
+        walk_oids.sort();
+        walk_oids.dedup();
+
+        let client = self.client().await?;
+        let scalars = if object_oids.is_empty() {
+            Vec::new()
+        } else {
+            client.get(&object_oids).await?
+        };
+        let mut columns = HashMap::with_capacity(walk_oids.len());
+        for oid in walk_oids {
+            let rows = client.walk(&oid).await?;
+            columns.insert(oid, rows);
+        }
+        let byte_size = client.take_received_bytes();
+        emit!(EndpointBytesReceived {
+            byte_size,
+            protocol: "udp",
+            endpoint: &self.address,
+        });
+
+        let mut metrics = Vec::new();
+        for VarBind { oid, value } in &scalars {
+            let Some(object) = self.objects.iter().find(|object| &object.oid == oid) else {
+                continue;
+            };
+            if let Some(value) = metric_value(value, object.r#type) {
+                metrics.push(self.create_metric(&object.name, value, self.tags.clone()));
+            }
+        }
+
+        for table in &self.tables {
+            // Tag values of each row, keyed by the row index.
+            let mut row_tags = BTreeMap::<&[u32], Vec<(&str, String)>>::new();
+            for (name, column) in &table.tags {
+                for varbind in columns.get(column).into_iter().flatten() {
+                    if let Some(index) = varbind.oid.suffix(column) {
+                        row_tags
+                            .entry(index)
+                            .or_default()
+                            .push((name.as_str(), tag_value(&varbind.value)));
+                    }
+                }
+            }
+
+            for column in &table.metrics {
+                for varbind in columns.get(&column.oid).into_iter().flatten() {
+                    let (Some(index), Some(value)) = (
+                        varbind.oid.suffix(&column.oid),
+                        metric_value(&varbind.value, column.r#type),
+                    ) else {
+                        continue;
+                    };
+
+                    let mut tags = self.tags.clone();
+                    tags.replace(table.index_tag.clone(), format_index(index));
+                    for (name, value) in row_tags.get(index).into_iter().flatten() {
+                        tags.replace((*name).to_owned(), value.clone());
+                    }
+                    metrics.push(self.create_metric(&column.name, value, tags));
+                }
+            }
+        }
+
+        Ok(metrics)
+    }
+
+    fn create_metric(&self, name: &str, value: MetricValue, tags: MetricTags) -> Metric {
+        Metric::new(name, MetricKind::Absolute, value)
+            .with_namespace(self.namespace.clone())
+            .with_tags(Some(tags))
+            .with_timestamp(Some(Utc::now()))
+    }
+}
+
+/// Resolves a target address, which may omit the port.
+async fn resolve_address(address: &str) -> Result<SocketAddr, ClientError> {
+    if let Ok(address) = address.parse::<SocketAddr>() {
+        return Ok(address);
+    }
+    if let Ok(ip) = address.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, DEFAULT_PORT));
+    }
+
+    let has_port = address
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok());
+    let lookup = if has_port {
+        tokio::net::lookup_host(address).await
+    } else {
+        tokio::net::lookup_host((address, DEFAULT_PORT)).await
+    };
+    lookup
+        .map_err(|source| ClientError::Io { source })?
+        .next()
+        .ok_or_else(|| ClientError::Io {
+            source: std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("no addresses found for {address:?}"),
+            ),
+        })
+}
+
+/// Converts a numeric value to a metric value of the given type.
+fn metric_value(value: &Value, r#type: MetricType) -> Option<MetricValue> {
+    let (number, is_counter) = match value {
+        Value::Integer(value) => (*value as f64, false),
+        Value::Gauge32(value) | Value::TimeTicks(value) => (*value as f64, false),
+        Value::Counter32(value) => (*value as f64, true),
+        Value::Counter64(value) => (*value as f64, true),
+        _ => return None,
+    };
+    let counter = match r#type {
+        MetricType::Auto => is_counter,
+        MetricType::Counter => true,
+        MetricType::Gauge => false,
+    };
+    Some(if counter {
+        MetricValue::Counter { value: number }
+    } else {
+        MetricValue::Gauge { value: number }
+    })
+}
+
+fn tag_value(value: &Value) -> String {
+    match to_event_value(value) {
+        crate::event::Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        value => value.to_string_lossy().into_owned(),
+    }
+}
+
+fn format_index(index: &[u32]) -> String {
+    Oid::from(index.to_vec()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::UdpSocket;
+
+    use super::*;
+    use crate::{
+        sources::util::snmp::message::{AnyPdu, CommunityMessage, Message, Pdu, PduType, Version},
+        test_util::{
+            collect_ready,
+            components::{run_and_assert_source_compliance, PULL_SOURCE_TAGS},
+            next_addr,
+        },
+    };
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpConfig>();
+    }
+
+    fn agent_objects() -> BTreeMap<Oid, Value> {
+        let mut objects = BTreeMap::new();
+        let mut insert = |oid: &str, value| {
+            objects.insert(oid.parse().unwrap(), value);
+        };
+        insert("1.3.6.1.2.1.1.3.0", Value::TimeTicks(123456));
+        for (index, name, octets) in [(1, "lo", 1000), (2, "eth0", 2000), (3, "eth1", 3000)] {
+            insert(&format!("1.3.6.1.2.1.2.2.1.8.{index}"), Value::Integer(1));
+            insert(
+                &format!("1.3.6.1.2.1.31.1.1.1.1.{index}"),
+                Value::OctetString(name.as_bytes().to_vec()),
+            );
+            insert(
+                &format!("1.3.6.1.2.1.31.1.1.1.6.{index}"),
+                Value::Counter64(octets),
+            );
+        }
+        objects
+    }
+
+    /// Answers `get` and `get-bulk` requests from a fixed set of objects.
+    fn respond(objects: &BTreeMap<Oid, Value>, request: &Pdu) -> Pdu {
+        let varbinds = match request.pdu_type {
+            PduType::GetRequest => request
+                .varbinds
+                .iter()
+                .map(|varbind| {
+                    let value = objects
+                        .get(&varbind.oid)
+                        .cloned()
+                        .unwrap_or(Value::NoSuchInstance);
+                    VarBind::new(varbind.oid.clone(), value)
+                })
+                .collect(),
+            PduType::GetBulkRequest => {
+                let start = &request.varbinds[0].oid;
+                let mut varbinds = objects
+                    .range(start.clone()..)
+                    .filter(|(oid, _)| *oid > start)
+                    .take(2)
+                    .map(|(oid, value)| VarBind::new(oid.clone(), value.clone()))
+                    .collect::<Vec<_>>();
+                if varbinds.is_empty() {
+                    varbinds.push(VarBind::new(start.clone(), Value::EndOfMibView));
+                }
+                varbinds
+            }
+            _ => unreachable!(),
+        };
+        Pdu::new(PduType::Response, request.request_id, varbinds)
+    }
+
+    async fn run_agent(socket: UdpSocket) {
+        let objects = agent_objects();
+        let mut buf = vec![0; 65535];
+        loop {
+            let (size, peer) = socket.recv_from(&mut buf).await.unwrap();
+            let Ok(Message::Community(message)) = Message::decode(&buf[..size]) else {
+                continue;
+            };
+            if message.community != b"public" {
+                continue;
+            }
+            let AnyPdu::Standard(request) = message.pdu else {
+                continue;
+            };
+            let response = CommunityMessage::encode(
+                Version::V2c,
+                &message.community,
+                &respond(&objects, &request),
+            );
+            socket.send_to(&response, peer).await.unwrap();
+        }
+    }
+
+    fn config(address: SocketAddr) -> SnmpConfig {
+        let mut config: SnmpConfig = toml::from_str(&format!(
+            r#"
+            scrape_interval_secs = 1
+
+            [[targets]]
+            address = "{address}"
+            objects = [{{ name = "uptime_ticks", oid = "sysUpTime.0" }}]
+
+            [[targets.tables]]
+            index_tag = "if_index"
+            metrics = [
+              {{ name = "interface_in_octets_total", oid = "ifHCInOctets" }},
+              {{ name = "interface_oper_status", oid = "1.3.6.1.2.1.2.2.1.8" }},
+            ]
+            tags = [{{ name = "interface", oid = "ifName" }}]
+            "#
+        ))
+        .unwrap();
+        config.max_repetitions = 2;
+        config
+    }
+
+    #[tokio::test]
+    async fn polls_objects_and_tables() {
+        let address = next_addr();
+        let socket = UdpSocket::bind(address).await.unwrap();
+        tokio::spawn(run_agent(socket));
+
+        let events = run_and_assert_source_compliance(
+            config(address),
+            Duration::from_secs(1),
+            &PULL_SOURCE_TAGS,
+        )
+        .await;
+        let metrics = events
+            .into_iter()
+            .map(|event| event.into_metric())
+            .collect::<Vec<_>>();
+
+        let find = |name: &str, index: Option<&str>| {
+            metrics
+                .iter()
+                .find(|metric| {
+                    metric.name() == name && metric.tag_value("if_index").as_deref() == index
+                })
+                .unwrap_or_else(|| panic!("metric {name} not found"))
+        };
+
+        let up = find("up", None);
+        assert_eq!(up.namespace(), Some("snmp"));
+        assert_eq!(up.value(), &MetricValue::Gauge { value: 1.0 });
+
+        let uptime = find("uptime_ticks", None);
+        assert_eq!(uptime.value(), &MetricValue::Gauge { value: 123456.0 });
+
+        let octets = find("interface_in_octets_total", Some("2"));
+        assert_eq!(octets.value(), &MetricValue::Counter { value: 2000.0 });
+        assert_eq!(octets.tag_value("interface").as_deref(), Some("eth0"));
+        assert_eq!(
+            octets.tag_value("host").as_deref(),
+            Some(address.to_string().as_str())
+        );
+
+        let status = find("interface_oper_status", Some("3"));
+        assert_eq!(status.value(), &MetricValue::Gauge { value: 1.0 });
+        assert_eq!(status.tag_value("interface").as_deref(), Some("eth1"));
+    }
+
+    #[tokio::test]
+    async fn reports_unreachable_targets() {
+        // Nothing listens on this address, so every request times out.
+        let mut config = config(next_addr());
+        config.timeout_secs = Duration::from_secs(1);
+        config.retries = 0;
+
+        let (tx, rx) = SourceSender::new_test();
+        let source = config
+            .build(SourceContext::new_test(tx, None))
+            .await
+            .unwrap();
+        tokio::spawn(source);
+
+        time::sleep(Duration::from_millis(1500)).await;
+
+        let metrics = collect_ready(rx)
+            .await
+            .into_iter()
+            .map(|event| event.into_metric())
+            .collect::<Vec<_>>();
+        assert!(!metrics.is_empty());
+        for metric in metrics {
+            assert_eq!(metric.name(), "up");
+            assert_eq!(metric.value(), &MetricValue::Gauge { value: 0.0 });
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_objects() {
+        let mut config = config(next_addr());
+        config.targets[0].objects[0].oid = "notAnObject.0".to_owned();
+        let (tx, _rx) = SourceSender::new_test();
+        let cx = SourceContext::new_test(tx, None);
+        let error = futures::executor::block_on(config.build(cx)).err().unwrap();
+        assert!(error.to_string().contains("notAnObject.0"));
+    }
+}
@@ -0,0 +1,968 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    path::PathBuf,
+    time::Instant,
+};
+
+use bytes::BytesMut;
+use chrono::Utc;
+use listenfd::ListenFd;
+use snafu::Snafu;
+use vector_lib::configurable::configurable_component;
+use vector_lib::internal_event::{ByteSize, BytesReceived, InternalEventHandle as _, Protocol};
+use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path, path};
+use vector_lib::{
+    config::{LegacyKey, LogNamespace},
+    schema::Definition,
+    sensitive_string::SensitiveString,
+    EstimatedJsonEncodedSizeOf,
+};
+use vrl::value::{kind::Collection, Kind};
+
+use super::util::{
+    net::{try_bind_udp_socket, SocketListenAddr},
+    snmp::{
+        message::{
+            AnyPdu, CommunityMessage, Message, MessageError, Pdu, PduType, ScopedPdu,
+            ScopedPduData, UsmSecurityParameters, V3Message, Value as SnmpValue, VarBind, Version,
+            FLAG_AUTH, FLAG_REPORTABLE,
+        },
+        mib::OidNames,
+        oid::Oid,
+        to_event_value,
+        usm::{self, LocalizedKeys, UsmError, UsmUser, UsmUserConfig},
+    },
+};
+use crate::{
+    config::{
+        log_schema, DataType, GenerateConfig, Resource, SourceConfig, SourceContext, SourceOutput,
+    },
+    event::{Event, LogEvent, ObjectMap, Value},
+    internal_events::{
+        SnmpAuthenticationError, SnmpParseError, SnmpResponseSendError, SocketBindError,
+        SocketEventsReceived, SocketMode, SocketReceiveError, StreamClosedError,
+    },
+    net,
+    shutdown::ShutdownSignal,
+    SourceSender,
+};
+
+/// `sysUpTime.0`, the first variable binding of SNMPv2 notifications.
+const SYS_UPTIME: &[u32] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+/// `snmpTrapOID.0`, the second variable binding of SNMPv2 notifications.
+const SNMP_TRAP_OID: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0];
+/// `snmpTraps`, the parent of the generic traps defined by SNMPv2-MIB.
+const SNMP_TRAPS: &[u32] = &[1, 3, 6, 1, 6, 3, 1, 1, 5];
+/// `usmStatsNotInTimeWindows.0`.
+const USM_STATS_NOT_IN_TIME_WINDOWS: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 1, 2, 0];
+/// `usmStatsUnknownEngineIDs.0`.
+const USM_STATS_UNKNOWN_ENGINE_IDS: &[u32] = &[1, 3, 6, 1, 6, 3, 15, 1, 1, 4, 0];
+
+/// The maximum message size advertised in SNMPv3 responses.
+const MAX_MESSAGE_SIZE: i64 = 65507;
+/// The time window, in seconds, within which authenticated informs are accepted (RFC 3414).
+const TIME_WINDOW_SECS: u32 = 150;
+
+/// Configuration for the `snmp_trap` source.
+#[configurable_component(source(
+    "snmp_trap",
+    "Receive SNMP traps and informs from network devices."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct SnmpTrapConfig {
+    #[configurable(derived)]
+    address: SocketListenAddr,
+
+    /// The maximum size of an incoming datagram.
+    ///
+    /// Datagrams larger than this are truncated, and are likely to fail decoding.
+    #[serde(default = "default_max_length")]
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    max_length: usize,
+
+    /// The size of the receive buffer used for the listening socket.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    receive_buffer_bytes: Option<usize>,
+
+    /// The communities accepted from SNMPv1 and SNMPv2c agents.
+    ///
+    /// If empty, messages with any community are accepted.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "public"))]
+    #[configurable(metadata(docs::examples = "SECRET[secret_backend.snmp_community]"))]
+    communities: Vec<SensitiveString>,
+
+    /// The users accepted from SNMPv3 agents.
+    ///
+    /// SNMPv3 messages from users that are not listed here are discarded.
+    #[serde(default)]
+    users: Vec<UsmUserConfig>,
+
+    /// The SNMP engine ID of this receiver, as a hex string.
+    ///
+    /// SNMPv3 agents sending informs authenticate against this engine ID. If not set, a random
+    /// engine ID is generated on startup, which requires agents to rediscover it after each
+    /// restart.
+    #[configurable(metadata(docs::examples = "80001f8880e9630000d61ff449"))]
+    engine_id: Option<String>,
+
+    /// A list of files mapping OIDs to object names.
+    ///
+    /// Each line contains an object name and its OID, as produced by `snmptranslate -Tz -On`
+    /// for a set of MIBs. These names are used to populate the `name` of variable bindings
+    /// and the `trap_name` of notifications. Common objects from SNMPv2-MIB and IF-MIB are
+    /// always known.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "/etc/vector/mibs/oids.txt"))]
+    mib_files: Vec<PathBuf>,
+
+    /// Overrides the name of the log field used to add the agent's address to each event.
+    ///
+    /// By default, the [global `log_schema.host_key` option][global_host_key] is used.
+    ///
+    /// Set to `""` to suppress this key.
+    ///
+    /// [global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+    host_key: Option<OptionalValuePath>,
+
+    /// The namespace to use for logs. This overrides the global setting.
+    #[serde(default)]
+    #[configurable(metadata(docs::hidden))]
+    log_namespace: Option<bool>,
+}
+
+const fn default_max_length() -> usize {
+    65535
+}
+
+impl SnmpTrapConfig {
+    pub fn from_address(address: SocketListenAddr) -> Self {
+        Self {
+            address,
+            max_length: default_max_length(),
+            receive_buffer_bytes: None,
+            communities: Vec::new(),
+            users: Vec::new(),
+            engine_id: None,
+            mib_files: Vec::new(),
+            host_key: None,
+            log_namespace: None,
+        }
+    }
+
+    fn host_key(&self) -> OptionalValuePath {
+        self.host_key
+            .clone()
+            .unwrap_or_else(|| log_schema().host_key().cloned().into())
+    }
+
+    fn engine_id(&self) -> crate::Result<Vec<u8>> {
+        let Some(engine_id) = &self.engine_id else {
+            // RFC 3411 format 5 (administratively assigned octets) with random contents.
+            let mut engine_id = vec![0x80, 0x00, 0x00, 0x00, 0x05];
+            engine_id.extend_from_slice(&rand::random::<[u8; 8]>());
+            return Ok(engine_id);
+        };
+        let engine_id = hex::decode(engine_id)
+            .map_err(|error| format!("Invalid engine ID {engine_id:?}: {error}"))?;
+        if !(5..=32).contains(&engine_id.len()) {
+            return Err("The engine ID must be between 5 and 32 bytes long.".into());
+        }
+        Ok(engine_id)
+    }
+
+    fn schema_definition(&self, log_namespace: LogNamespace) -> Definition {
+        Definition::new_with_default_metadata(Kind::object(Collection::empty()), [log_namespace])
+            .with_standard_vector_source_metadata()
+            .with_source_metadata(
+                Self::NAME,
+                self.host_key().path.map(LegacyKey::InsertIfEmpty),
+                &owned_value_path!("host"),
+                Kind::bytes(),
+                Some("host"),
+            )
+            .with_event_field(&owned_value_path!("version"), Kind::bytes(), None)
+            .with_event_field(&owned_value_path!("pdu_type"), Kind::bytes(), None)
+            .with_event_field(
+                &owned_value_path!("request_id"),
+                Kind::integer().or_undefined(),
+                None,
+            )
+            .with_event_field(
+                &owned_value_path!("uptime"),
+                Kind::integer().or_undefined(),
+                None,
+            )
+            .with_event_field(
+                &owned_value_path!("trap_oid"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_event_field(
+                &owned_value_path!("trap_name"),
+                Kind::bytes().or_undefined(),
+                None,
+            )
+            .with_event_field(
+                &owned_value_path!("varbinds"),
+                Kind::array(Collection::any()),
+                None,
+            )
+            .unknown_fields(Kind::any())
+    }
+}
+
+impl GenerateConfig for SnmpTrapConfig {
+    fn generate_config() -> toml::Value {
+        toml::Value::try_from(Self::from_address(SocketListenAddr::SocketAddr(
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 162)),
+        )))
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "snmp_trap")]
+impl SourceConfig for SnmpTrapConfig {
+    async fn build(&self, cx: SourceContext) -> crate::Result<super::Source> {
+        let log_namespace = cx.log_namespace(self.log_namespace);
+        let users = self
+            .users
+            .iter()
+            .map(|config| {
+                Ok((
+                    config.user_name.as_bytes().to_vec(),
+                    UsmUser::from_config(config)?,
+                ))
+            })
+            .collect::<Result<_, UsmError>>()?;
+        let receiver = TrapReceiver {
+            communities: self
+                .communities
+                .iter()
+                .map(|community| community.inner().as_bytes().to_vec())
+                .collect(),
+            users,
+            localized: HashMap::new(),
+            engine_id: self.engine_id()?,
+            started: Instant::now(),
+            unknown_engine_ids: 0,
+        };
+        let names = OidNames::load(&self.mib_files)?;
+
+        Ok(Box::pin(snmp_trap_udp(
+            self.clone(),
+            receiver,
+            names,
+            cx.shutdown,
+            cx.out,
+            log_namespace,
+        )))
+    }
+
+    fn outputs(&self, global_log_namespace: LogNamespace) -> Vec<SourceOutput> {
+        let log_namespace = global_log_namespace.merge(self.log_namespace);
+        vec![SourceOutput::new_maybe_logs(
+            DataType::Log,
+            self.schema_definition(log_namespace),
+        )]
+    }
+
+    fn resources(&self) -> Vec<Resource> {
+        vec![self.address.as_udp_resource()]
+    }
+
+    fn can_acknowledge(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Snafu)]
+enum ReceiveError {
+    #[snafu(display("{}", source))]
+    Decode { source: MessageError },
+
+    #[snafu(display("Unexpected {} PDU", pdu_type.as_str()))]
+    UnexpectedPdu { pdu_type: PduType },
+
+    #[snafu(display("Unknown community"))]
+    UnknownCommunity,
+
+    #[snafu(display("Unknown user {:?}", user))]
+    UnknownUser { user: String },
+
+    #[snafu(display("Inform is not addressed to this engine"))]
+    UnknownEngineId,
+
+    #[snafu(display("{}", source))]
+    Usm { source: UsmError },
+}
+
+impl ReceiveError {
+    const fn is_authentication_error(&self) -> bool {
+        !matches!(self, Self::Decode { .. } | Self::UnexpectedPdu { .. })
+    }
+}
+
+impl From<UsmError> for ReceiveError {
+    fn from(source: UsmError) -> Self {
+        match source {
+            UsmError::MalformedScopedPdu { source } => Self::Decode { source },
+            source => Self::Usm { source },
+        }
+    }
+}
+
+/// A trap or inform accepted by the receiver.
+#[derive(Debug)]
+struct Notification {
+    version: Version,
+    pdu: AnyPdu,
+    user_name: Option<String>,
+    context_name: Option<String>,
+}
+
+/// The result of processing a single datagram.
+#[derive(Debug, Default)]
+struct Received {
+    notification: Option<Notification>,
+    reply: Option<Vec<u8>>,
+}
+
+/// Authenticates received messages and builds replies to informs and SNMPv3 discovery.
+struct TrapReceiver {
+    communities: Vec<Vec<u8>>,
+    users: HashMap<Vec<u8>, UsmUser>,
+    /// Keys localized per user and authoritative engine ID.
+    localized: HashMap<(Vec<u8>, Vec<u8>), Option<LocalizedKeys>>,
+    engine_id: Vec<u8>,
+    started: Instant,
+    unknown_engine_ids: u32,
+}
+
+impl TrapReceiver {
+    fn engine_time(&self) -> u32 {
+        self.started.elapsed().as_secs() as u32
+    }
+
+    fn receive(&mut self, raw: &[u8]) -> Result<Received, ReceiveError> {
+        match Message::decode(raw).map_err(|source| ReceiveError::Decode { source })? {
+            Message::Community(message) => self.receive_community(message),
+            Message::V3(message) => self.receive_v3(raw, message),
+        }
+    }
+
+    fn receive_community(&self, message: CommunityMessage) -> Result<Received, ReceiveError> {
+        if !self.communities.is_empty() && !self.communities.contains(&message.community) {
+            return Err(ReceiveError::UnknownCommunity);
+        }
+
+        let reply = match &message.pdu {
+            AnyPdu::Standard(pdu) if pdu.pdu_type == PduType::InformRequest => Some(
+                CommunityMessage::encode(message.version, &message.community, &response(pdu)),
+            ),
+            AnyPdu::Standard(pdu) if pdu.pdu_type != PduType::TrapV2 => {
+                return Err(ReceiveError::UnexpectedPdu {
+                    pdu_type: pdu.pdu_type,
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Received {
+            notification: Some(Notification {
+                version: message.version,
+                pdu: message.pdu,
+                user_name: None,
+                context_name: None,
+            }),
+            reply,
+        })
+    }
+
+    fn receive_v3(&mut self, raw: &[u8], message: V3Message) -> Result<Received, ReceiveError> {
+        let usm = &message.usm;
+        if usm.engine_id.is_empty() {
+            // Engine ID discovery (RFC 3414 section 4), sent by agents before their first inform.
+            if message.flags & FLAG_REPORTABLE == 0 {
+                return Err(ReceiveError::UnknownEngineId);
+            }
+            self.unknown_engine_ids = self.unknown_engine_ids.wrapping_add(1);
+            let request_id = match &message.data {
+                ScopedPduData::Plaintext(scoped) => request_id(&scoped.pdu),
+                ScopedPduData::Encrypted(_) => 0,
+            };
+            let reply = self.report(
+                &message,
+                None,
+                request_id,
+                b"",
+                USM_STATS_UNKNOWN_ENGINE_IDS,
+                self.unknown_engine_ids,
+            )?;
+            return Ok(Received {
+                notification: None,
+                reply: Some(reply),
+            });
+        }
+
+        let user_name = String::from_utf8_lossy(&usm.user_name).into_owned();
+        let user = self
+            .users
+            .get(&usm.user_name)
+            .ok_or_else(|| ReceiveError::UnknownUser {
+                user: user_name.clone(),
+            })?;
+        let keys = match self
+            .localized
+            .entry((usm.user_name.clone(), usm.engine_id.clone()))
+        {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => entry.insert(user.localize(&usm.engine_id)?).clone(),
+        };
+
+        let scoped = usm::open(raw, &message, keys.as_ref())?;
+        let context_name = String::from_utf8_lossy(&scoped.context_name).into_owned();
+
+        let reply = match &scoped.pdu {
+            AnyPdu::Standard(pdu) if pdu.pdu_type == PduType::InformRequest => {
+                // This receiver is the authoritative engine for informs.
+                if usm.engine_id != self.engine_id {
+                    return Err(ReceiveError::UnknownEngineId);
+                }
+                let authenticated = message.flags & FLAG_AUTH != 0;
+                let time = self.engine_time();
+                if authenticated
+                    && (usm.engine_boots != 1 || usm.engine_time.abs_diff(time) > TIME_WINDOW_SECS)
+                {
+                    let reply = self.report(
+                        &message,
+                        keys.as_ref(),
+                        pdu.request_id,
+                        &scoped.context_name,
+                        USM_STATS_NOT_IN_TIME_WINDOWS,
+                        1,
+                    )?;
+                    return Ok(Received {
+                        notification: None,
+                        reply: Some(reply),
+                    });
+                }
+
+                let scoped_response =
+                    ScopedPdu::encode(&self.engine_id, &scoped.context_name, &response(pdu));
+                Some(usm::seal(
+                    message.msg_id,
+                    MAX_MESSAGE_SIZE,
+                    0,
+                    self.security_parameters(&usm.user_name),
+                    keys.as_ref(),
+                    &scoped_response,
+                )?)
+            }
+            AnyPdu::Standard(pdu) if pdu.pdu_type != PduType::TrapV2 => {
+                return Err(ReceiveError::UnexpectedPdu {
+                    pdu_type: pdu.pdu_type,
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Received {
+            notification: Some(Notification {
+                version: Version::V3,
+                pdu: scoped.pdu,
+                user_name: Some(user_name),
+                context_name: Some(context_name),
+            }),
+            reply,
+        })
+    }
+
+    fn security_parameters(&self, user_name: &[u8]) -> UsmSecurityParameters {
+        UsmSecurityParameters {
+            engine_id: self.engine_id.clone(),
+            // Engine boots are not persisted, so every instance reports its first boot.
+            engine_boots: 1,
+            engine_time: self.engine_time(),
+            user_name: user_name.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn report(
+        &self,
+        message: &V3Message,
+        keys: Option<&LocalizedKeys>,
+        request_id: i32,
+        context_name: &[u8],
+        counter: &[u32],
+        value: u32,
+    ) -> Result<Vec<u8>, ReceiveError> {
+        let report = Pdu::new(
+            PduType::Report,
+            request_id,
+            vec![VarBind::new(
+                Oid::from(counter.to_vec()),
+                SnmpValue::Counter32(value),
+            )],
+        );
+        let scoped = ScopedPdu::encode(&self.engine_id, context_name, &report);
+        Ok(usm::seal(
+            message.msg_id,
+            MAX_MESSAGE_SIZE,
+            0,
+            self.security_parameters(&message.usm.user_name),
+            keys,
+            &scoped,
+        )?)
+    }
+}
+
+fn request_id(pdu: &AnyPdu) -> i32 {
+    match pdu {
+        AnyPdu::Standard(pdu) => pdu.request_id,
+        AnyPdu::TrapV1(_) => 0,
+    }
+}
+
+/// Builds the response acknowledging an inform, which echoes its variable bindings.
+fn response(inform: &Pdu) -> Pdu {
+    Pdu::new(
+        PduType::Response,
+        inform.request_id,
+        inform.varbinds.clone(),
+    )
+}
+
+async fn snmp_trap_udp(
+    config: SnmpTrapConfig,
+    mut receiver: TrapReceiver,
+    names: OidNames,
+    mut shutdown: ShutdownSignal,
+    mut out: SourceSender,
+    log_namespace: LogNamespace,
+) -> Result<(), ()> {
+    let listenfd = ListenFd::from_env();
+    let socket = try_bind_udp_socket(config.address, listenfd)
+        .await
+        .map_err(|error| {
+            emit!(SocketBindError {
+                mode: SocketMode::Udp,
+                error,
+            })
+        })?;
+
+    if let Some(receive_buffer_bytes) = config.receive_buffer_bytes {
+        if let Err(error) = net::set_receive_buffer_size(&socket, receive_buffer_bytes) {
+            warn!(message = "Failed configuring receive buffer size on UDP socket.", %error);
+        }
+    }
+
+    info!(message = "Listening.", address = %config.address);
+
+    let bytes_received = register!(BytesReceived::from(Protocol::UDP));
+    let host_key = config.host_key();
+    let mut buf = BytesMut::with_capacity(config.max_length);
+
+    loop {
+        buf.resize(config.max_length, 0);
+        tokio::select! {
+            recv = socket.recv_from(&mut buf) => {
+                let (byte_size, peer) = recv.map_err(|error| {
+                    emit!(SocketReceiveError {
+                        mode: SocketMode::Udp,
+                        error,
+                    })
+                })?;
+                bytes_received.emit(ByteSize(byte_size));
+
+                let received = match receiver.receive(&buf[..byte_size]) {
+                    Ok(received) => received,
+                    Err(error) if error.is_authentication_error() => {
+                        emit!(SnmpAuthenticationError { error, peer });
+                        continue;
+                    }
+                    Err(error) => {
+                        emit!(SnmpParseError { error, peer });
+                        continue;
+                    }
+                };
+
+                if let Some(reply) = received.reply {
+                    if let Err(error) = socket.send_to(&reply, peer).await {
+                        emit!(SnmpResponseSendError { error, peer });
+                    }
+                }
+                let Some(notification) = received.notification else {
+                    continue;
+                };
+
+                let event = Event::Log(notification_to_log(
+                    notification,
+                    peer,
+                    &names,
+                    &host_key,
+                    log_namespace,
+                ));
+                emit!(SocketEventsReceived {
+                    mode: SocketMode::Udp,
+                    byte_size: event.estimated_json_encoded_size_of(),
+                    count: 1,
+                });
+
+                tokio::select! {
+                    result = out.send_event(event) => {
+                        if result.is_err() {
+                            emit!(StreamClosedError { count: 1 });
+                            return Ok(());
+                        }
+                    }
+                    _ = &mut shutdown => return Ok(()),
+                }
+            }
+            _ = &mut shutdown => return Ok(()),
+        }
+    }
+}
+
+fn notification_to_log(
+    notification: Notification,
+    peer: SocketAddr,
+    names: &OidNames,
+    host_key: &OptionalValuePath,
+    log_namespace: LogNamespace,
+) -> LogEvent {
+    let mut fields = ObjectMap::new();
+    fields.insert("version".into(), notification.version.as_str().into());
+    fields.insert(
+        "pdu_type".into(),
+        notification.pdu.pdu_type().as_str().into(),
+    );
+    if let Some(user_name) = notification.user_name {
+        fields.insert("user_name".into(), user_name.into());
+    }
+    if let Some(context_name) = notification.context_name {
+        fields.insert("context_name".into(), context_name.into());
+    }
+
+    let mut trap_oid = None;
+    let varbinds = match &notification.pdu {
+        AnyPdu::TrapV1(trap) => {
+            fields.insert("enterprise".into(), trap.enterprise.to_string().into());
+            fields.insert(
+                "agent_address".into(),
+                trap.agent_address.to_string().into(),
+            );
+            fields.insert("generic_trap".into(), trap.generic_trap.into());
+            fields.insert("specific_trap".into(), trap.specific_trap.into());
+            fields.insert("uptime".into(), (trap.timestamp as i64).into());
+            // The SNMPv2 notification OID of an SNMPv1 trap, as defined by RFC 3584.
+            trap_oid = Some(if (0..6).contains(&trap.generic_trap) {
+                Oid::from(SNMP_TRAPS.to_vec()).child(&[trap.generic_trap as u32 + 1])
+            } else {
+                trap.enterprise.child(&[0, trap.specific_trap as u32])
+            });
+            &trap.varbinds[..]
+        }
+        AnyPdu::Standard(pdu) => {
+            fields.insert("request_id".into(), (pdu.request_id as i64).into());
+            let mut varbinds = &pdu.varbinds[..];
+            // The first two variable bindings of a notification are its uptime and identity.
+            if let [VarBind {
+                oid,
+                value: SnmpValue::TimeTicks(uptime),
+            }, rest @ ..] = varbinds
+            {
+                if oid.components() == SYS_UPTIME {
+                    fields.insert("uptime".into(), (*uptime as i64).into());
+                    varbinds = rest;
+                }
+            }
+            if let [VarBind {
+                oid,
+                value: SnmpValue::ObjectIdentifier(notification_oid),
+            }, rest @ ..] = varbinds
+            {
+                if oid.components() == SNMP_TRAP_OID {
+                    trap_oid = Some(notification_oid.clone());
+                    varbinds = rest;
+                }
+            }
+            varbinds
+        }
+    };
+
+    if let Some(trap_oid) = trap_oid {
+        if let Some(name) = names.translate(&trap_oid) {
+            fields.insert("trap_name".into(), name.into());
+        }
+        fields.insert("trap_oid".into(), trap_oid.to_string().into());
+    }
+
+    let varbinds = varbinds
+        .iter()
+        .map(|varbind| {
+            let mut object = ObjectMap::new();
+            object.insert("oid".into(), varbind.oid.to_string().into());
+            if let Some(name) = names.translate(&varbind.oid) {
+                object.insert("name".into(), name.into());
+            }
+            object.insert("type".into(), varbind.value.type_name().into());
+            object.insert("value".into(), to_event_value(&varbind.value));
+            Value::Object(object)
+        })
+        .collect::<Vec<_>>();
+    fields.insert("varbinds".into(), Value::Array(varbinds));
+
+    let mut log = LogEvent::from_map(fields, Default::default());
+    log_namespace.insert_standard_vector_source_metadata(
+        &mut log,
+        SnmpTrapConfig::NAME,
+        Utc::now(),
+    );
+    log_namespace.insert_source_metadata(
+        SnmpTrapConfig::NAME,
+        &mut log,
+        host_key.path.as_ref().map(LegacyKey::InsertIfEmpty),
+        path!("host"),
+        peer.ip().to_string(),
+    );
+
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::{net::UdpSocket, time::sleep};
+    use vector_lib::config::ComponentKey;
+
+    use super::*;
+    use crate::{
+        sources::util::snmp::{
+            message::TrapV1Pdu,
+            usm::{AuthProtocol, PrivacyProtocol},
+        },
+        test_util::{
+            collect_ready,
+            components::{assert_source_compliance, SOCKET_PUSH_SOURCE_TAGS},
+            next_addr,
+        },
+    };
+
+    const ENGINE_ID: &str = "8000000005a1b2c3d4e5f60718";
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<SnmpTrapConfig>();
+    }
+
+    fn link_down(pdu_type: PduType, request_id: i32) -> Pdu {
+        Pdu::new(
+            pdu_type,
+            request_id,
+            vec![
+                VarBind::new(Oid::from(SYS_UPTIME.to_vec()), SnmpValue::TimeTicks(360000)),
+                VarBind::new(
+                    Oid::from(SNMP_TRAP_OID.to_vec()),
+                    SnmpValue::ObjectIdentifier("1.3.6.1.6.3.1.1.5.3".parse().unwrap()),
+                ),
+                VarBind::new(
+                    "1.3.6.1.2.1.2.2.1.1.3".parse().unwrap(),
+                    SnmpValue::Integer(3),
+                ),
+                VarBind::new(
+                    "1.3.6.1.2.1.2.2.1.7.3".parse().unwrap(),
+                    SnmpValue::Integer(2),
+                ),
+            ],
+        )
+    }
+
+    fn config(address: SocketAddr) -> SnmpTrapConfig {
+        let mut config = SnmpTrapConfig::from_address(address.into());
+        config.communities = vec!["public".to_owned().into()];
+        config.engine_id = Some(ENGINE_ID.to_owned());
+        config.users = vec![UsmUserConfig {
+            user_name: "monitor".to_owned(),
+            auth_protocol: Some(AuthProtocol::Sha256),
+            auth_password: Some("authpassword".to_owned().into()),
+            privacy_protocol: Some(PrivacyProtocol::Aes),
+            privacy_password: Some("privpassword".to_owned().into()),
+        }];
+        config
+    }
+
+    async fn exchange(socket: &UdpSocket, address: SocketAddr, datagram: &[u8]) -> Vec<u8> {
+        socket.send_to(datagram, address).await.unwrap();
+        let mut buf = vec![0; 65535];
+        let (size, _) = tokio::time::timeout(Duration::from_secs(2), socket.recv_from(&mut buf))
+            .await
+            .expect("no response received")
+            .unwrap();
+        buf.truncate(size);
+        buf
+    }
+
+    async fn run<F, Fut>(config: SnmpTrapConfig, agent: F) -> Vec<Event>
+    where
+        F: FnOnce(UdpSocket, SocketAddr) -> Fut,
+        Fut: std::future::Future<Output = ()>,
+    {
+        let address = match config.address {
+            SocketListenAddr::SocketAddr(address) => address,
+            SocketListenAddr::SystemdFd(_) => unreachable!(),
+        };
+        let (tx, rx) = SourceSender::new_test();
+        let (cx, shutdown) = SourceContext::new_shutdown(&ComponentKey::from("snmp_trap"), tx);
+        let source = config.build(cx).await.unwrap();
+        tokio::spawn(source);
+        sleep(Duration::from_millis(250)).await;
+
+        let socket = UdpSocket::bind(next_addr()).await.unwrap();
+        agent(socket, address).await;
+        sleep(Duration::from_millis(250)).await;
+        shutdown
+            .shutdown_all(Some(
+                tokio::time::Instant::now() + Duration::from_millis(100),
+            ))
+            .await;
+
+        collect_ready(rx).await
+    }
+
+    #[tokio::test]
+    async fn receives_v2c_traps() {
+        assert_source_compliance(&SOCKET_PUSH_SOURCE_TAGS, async {
+            let events = run(config(next_addr()), |socket, address| async move {
+                let trap = link_down(PduType::TrapV2, 7);
+                for community in [b"public".as_slice(), b"private".as_slice()] {
+                    let datagram = CommunityMessage::encode(Version::V2c, community, &trap);
+                    socket.send_to(&datagram, address).await.unwrap();
+                }
+            })
+            .await;
+
+            // The trap with an unknown community is discarded.
+            assert_eq!(events.len(), 1);
+            let log = events[0].as_log();
+            assert_eq!(log["version"], "2c".into());
+            assert_eq!(log["pdu_type"], "trap_v2".into());
+            assert_eq!(log["uptime"], 360000.into());
+            assert_eq!(log["trap_oid"], "1.3.6.1.6.3.1.1.5.3".into());
+            assert_eq!(log["trap_name"], "linkDown".into());
+            assert_eq!(log["varbinds[0].name"], "ifIndex.3".into());
+            assert_eq!(log["varbinds[0].type"], "integer".into());
+            assert_eq!(log["varbinds[1].value"], 2.into());
+            assert_eq!(log["host"], "127.0.0.1".into());
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn acknowledges_v2c_informs() {
+        let events = run(config(next_addr()), |socket, address| async move {
+            let inform = link_down(PduType::InformRequest, 42);
+            let datagram = CommunityMessage::encode(Version::V2c, b"public", &inform);
+            let reply = exchange(&socket, address, &datagram).await;
+
+            let Message::Community(reply) = Message::decode(&reply).unwrap() else {
+                panic!("expected a community message");
+            };
+            let AnyPdu::Standard(pdu) = reply.pdu else {
+                panic!("expected a response PDU");
+            };
+            assert_eq!(pdu.pdu_type, PduType::Response);
+            assert_eq!(pdu.request_id, 42);
+            assert_eq!(pdu.varbinds, inform.varbinds);
+        })
+        .await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_log()["pdu_type"], "inform_request".into());
+    }
+
+    #[tokio::test]
+    async fn acknowledges_v3_informs() {
+        let events = run(config(next_addr()), |socket, address| async move {
+            // Discover the engine ID and time of the receiver.
+            let discovery = ScopedPdu::encode(b"", b"", &Pdu::new(PduType::GetRequest, 1, vec![]));
+            let (datagram, _) = V3Message::encode(
+                1,
+                MAX_MESSAGE_SIZE,
+                FLAG_REPORTABLE,
+                &UsmSecurityParameters::default(),
+                &discovery,
+            );
+            let reply = exchange(&socket, address, &datagram).await;
+            let Message::V3(report) = Message::decode(&reply).unwrap() else {
+                panic!("expected a v3 message");
+            };
+            assert_eq!(hex::encode(&report.usm.engine_id), ENGINE_ID);
+
+            let user = UsmUser::from_config(&config(next_addr()).users[0]).unwrap();
+            let keys = user.localize(&report.usm.engine_id).unwrap();
+            let usm = UsmSecurityParameters {
+                user_name: b"monitor".to_vec(),
+                ..report.usm.clone()
+            };
+            let inform = link_down(PduType::InformRequest, 43);
+            let scoped = ScopedPdu::encode(&report.usm.engine_id, b"", &inform);
+            let datagram = usm::seal(
+                2,
+                MAX_MESSAGE_SIZE,
+                FLAG_REPORTABLE,
+                usm,
+                keys.as_ref(),
+                &scoped,
+            )
+            .unwrap();
+
+            let reply = exchange(&socket, address, &datagram).await;
+            let Message::V3(response) = Message::decode(&reply).unwrap() else {
+                panic!("expected a v3 message");
+            };
+            let scoped = usm::open(&reply, &response, keys.as_ref()).unwrap();
+            assert_eq!(scoped.pdu.pdu_type(), PduType::Response);
+            assert_eq!(request_id(&scoped.pdu), 43);
+        })
+        .await;
+
+        assert_eq!(events.len(), 1);
+        let log = events[0].as_log();
+        assert_eq!(log["version"], "3".into());
+        assert_eq!(log["user_name"], "monitor".into());
+        assert_eq!(log["trap_name"], "linkDown".into());
+    }
+
+    #[test]
+    fn translates_v1_traps() {
+        let notification = Notification {
+            version: Version::V1,
+            pdu: AnyPdu::TrapV1(TrapV1Pdu {
+                enterprise: "1.3.6.1.4.1.8072.2.3.1".parse().unwrap(),
+                agent_address: Ipv4Addr::new(192, 0, 2, 1),
+                generic_trap: 6,
+                specific_trap: 17,
+                timestamp: 5500,
+                varbinds: Vec::new(),
+            }),
+            user_name: None,
+            context_name: None,
+        };
+        let log = notification_to_log(
+            notification,
+            "192.0.2.1:50000".parse().unwrap(),
+            &OidNames::default(),
+            &OptionalValuePath::none(),
+            LogNamespace::Legacy,
+        );
+        assert_eq!(log["trap_oid"], "1.3.6.1.4.1.8072.2.3.1.0.17".into());
+        assert_eq!(log["agent_address"], "192.0.2.1".into());
+        assert_eq!(log["uptime"], 5500.into());
+    }
+}
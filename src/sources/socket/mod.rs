@@ -162,6 +162,7 @@ impl SourceConfig for SocketConfig {
                     cx.shutdown,
                     cx.out,
                     log_namespace,
+                    SocketConfig::NAME,
                 ))
             }
             #[cfg(unix)]
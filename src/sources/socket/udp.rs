@@ -29,7 +29,6 @@ use crate::{
     serde::default_decoding,
     shutdown::ShutdownSignal,
     sources::{
-        socket::SocketConfig,
         util::net::{try_bind_udp_socket, SocketListenAddr},
         Source,
     },
@@ -151,14 +150,39 @@ impl UdpConfig {
         self.log_namespace = val;
         self
     }
+
+    pub const fn set_max_length(&mut self, val: usize) -> &mut Self {
+        self.max_length = val;
+        self
+    }
+
+    pub const fn set_receive_buffer_bytes(&mut self, val: Option<usize>) -> &mut Self {
+        self.receive_buffer_bytes = val;
+        self
+    }
+
+    pub fn set_host_key(&mut self, val: Option<OptionalValuePath>) -> &mut Self {
+        self.host_key = val;
+        self
+    }
+
+    pub fn set_port_key(&mut self, val: OptionalValuePath) -> &mut Self {
+        self.port_key = val;
+        self
+    }
 }
 
-pub(super) fn udp(
+/// Receives datagrams on the configured socket, decoding each one with `decoder`.
+///
+/// Log events are annotated with the peer's address and port as source metadata of `source_name`,
+/// other event types are forwarded as-is.
+pub(crate) fn udp(
     config: UdpConfig,
     decoder: Decoder,
     mut shutdown: ShutdownSignal,
     mut out: SourceSender,
     log_namespace: LogNamespace,
+    source_name: &'static str,
 ) -> Source {
     Box::pin(async move {
         let listenfd = ListenFd::from_env();
@@ -278,7 +302,7 @@ pub(super) fn udp(
                                     if let Event::Log(log) = event {
                                         log_namespace.insert_standard_vector_source_metadata(
                                             log,
-                                            SocketConfig::NAME,
+                                            source_name,
                                             now,
                                         );
 
@@ -289,7 +313,7 @@ pub(super) fn udp(
                                             .path;
 
                                         log_namespace.insert_source_metadata(
-                                            SocketConfig::NAME,
+                                            source_name,
                                             log,
                                             legacy_host_key.as_ref().map(LegacyKey::InsertIfEmpty),
                                             path!("host"),
@@ -299,7 +323,7 @@ pub(super) fn udp(
                                         let legacy_port_key = config.port_key.clone().path;
 
                                         log_namespace.insert_source_metadata(
-                                            SocketConfig::NAME,
+                                            source_name,
                                             log,
                                             legacy_port_key.as_ref().map(LegacyKey::InsertIfEmpty),
                                             path!("port"),
@@ -1391,6 +1391,7 @@ mod tests {
             batch: BatchConfig::default(),
             request: TowerRequestConfig::default(),
             tls: None,
+            load_balance: None,
             acknowledgements: Default::default(),
             timestamp_nanos_key: None,
             timestamp_key: None,
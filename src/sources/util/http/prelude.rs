@@ -17,7 +17,7 @@ use warp::{
         path::{FullPath, Tail},
         BoxedFilter,
     },
-    http::{HeaderMap, StatusCode},
+    http::{HeaderMap, Method, StatusCode},
     reject::Rejection,
     Filter,
 };
@@ -115,17 +115,21 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                 })
                 .untuple_one()
                 .and(warp::path::full())
+                .and(warp::method())
                 .and(warp::header::optional::<String>("content-encoding"))
                 .and(warp::header::headers_cloned())
                 .and(warp::body::bytes())
                 .and(warp::query::<HashMap<String, String>>())
+                .and(warp::query::raw().or(warp::any().map(String::new)).unify())
                 .and(warp::filters::ext::optional())
                 .and_then(
                     move |path: FullPath,
+                          method: Method,
                           encoding_header: Option<String>,
                           headers: HeaderMap,
                           body: Bytes,
                           query_parameters: HashMap<String, String>,
+                          raw_query: String,
                           addr: Option<PeerAddr>| {
                         debug!(message = "Handling HTTP request.", headers = ?headers);
                         let http_path = path.as_str();
@@ -135,8 +139,11 @@ pub trait HttpSource: Clone + Send + Sync + 'static {
                             .map_or(Ok(()), |a| {
                                 a.handle_auth(
                                     addr.as_ref().map(|a| a.0).as_ref(),
+                                    &method,
                                     &headers,
                                     path.as_str(),
+                                    Some(raw_query.as_str()),
+                                    &body,
                                 )
                             })
                             .and_then(|()| self.decode(encoding_header.as_deref(), body))
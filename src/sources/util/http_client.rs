@@ -19,7 +19,7 @@ use vector_lib::json_size::JsonSize;
 
 use crate::http::{QueryParameterValue, QueryParameters};
 use crate::{
-    http::{Auth, HttpClient},
+    http::{HttpAuthenticator, HttpClient},
     internal_events::{
         EndpointBytesReceived, HttpClientEventsReceived, HttpClientHttpError,
         HttpClientHttpResponseError, StreamClosedError,
@@ -43,7 +43,7 @@ pub(crate) struct GenericHttpClientInputs {
     pub headers: HashMap<String, Vec<String>>,
     /// Content type of the HTTP request, determined by the source.
     pub content_type: String,
-    pub auth: Option<Auth>,
+    pub auth: Option<HttpAuthenticator>,
     pub tls: TlsSettings,
     pub proxy: ProxyConfig,
     pub shutdown: ShutdownSignal,
@@ -149,8 +149,14 @@ pub(crate) async fn call<
 ) -> Result<(), ()> {
     // Building the HttpClient should not fail as it is just setting up the client with the
     // proxy and tls settings.
-    let client =
-        HttpClient::new(inputs.tls.clone(), &inputs.proxy).expect("Building HTTP client failed");
+    let client = HttpClient::new(inputs.tls.clone(), &inputs.proxy)
+        .expect("Building HTTP client failed")
+        .with_oauth2(
+            inputs
+                .auth
+                .as_ref()
+                .and_then(HttpAuthenticator::token_source),
+        );
     let mut stream = IntervalStream::new(tokio::time::interval(inputs.interval))
         .take_until(inputs.shutdown)
         .map(move |_| stream::iter(inputs.urls.clone()))
@@ -188,13 +194,13 @@ pub(crate) async fn call<
             }
 
             // building an empty request should be infallible
-            let mut request = builder.body(Body::empty()).expect("error creating request");
+            let mut request = builder.body(Bytes::new()).expect("error creating request");
 
             if let Some(auth) = &inputs.auth {
                 auth.apply(&mut request);
             }
 
-            tokio::time::timeout(inputs.timeout, client.send(request))
+            tokio::time::timeout(inputs.timeout, client.send(request.map(Body::from)))
                 .then(move |result| async move {
                     match result {
                         Ok(Ok(response)) => Ok(response),
@@ -0,0 +1,366 @@
+//! Encoding and decoding of the subset of ASN.1 BER used by SNMP.
+
+use snafu::Snafu;
+
+use super::oid::Oid;
+
+pub const INTEGER: u8 = 0x02;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+
+pub const IP_ADDRESS: u8 = 0x40;
+pub const COUNTER32: u8 = 0x41;
+pub const GAUGE32: u8 = 0x42;
+pub const TIMETICKS: u8 = 0x43;
+pub const OPAQUE: u8 = 0x44;
+pub const COUNTER64: u8 = 0x46;
+
+pub const NO_SUCH_OBJECT: u8 = 0x80;
+pub const NO_SUCH_INSTANCE: u8 = 0x81;
+pub const END_OF_MIB_VIEW: u8 = 0x82;
+
+/// Errors that can occur while decoding BER data.
+#[derive(Debug, Eq, PartialEq, Snafu)]
+pub enum BerError {
+    #[snafu(display("Unexpected end of data"))]
+    Truncated,
+
+    #[snafu(display("Expected tag {:#04x}, found {:#04x}", expected, found))]
+    UnexpectedTag { expected: u8, found: u8 },
+
+    #[snafu(display("Unsupported length encoding"))]
+    InvalidLength,
+
+    #[snafu(display("Integer does not fit in 64 bits"))]
+    IntegerOverflow,
+
+    #[snafu(display("Invalid object identifier encoding"))]
+    InvalidOid,
+}
+
+/// A cursor over BER encoded data.
+///
+/// Readers created for the contents of a TLV keep track of their absolute offset in the outermost
+/// buffer, which SNMPv3 needs to locate the authentication parameters within a message.
+#[derive(Clone, Debug)]
+pub struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    base: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub const fn new(buf: &'a [u8]) -> Self {
+        Self {
+            buf,
+            pos: 0,
+            base: 0,
+        }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    /// The absolute offset of the next unread byte.
+    pub const fn offset(&self) -> usize {
+        self.base + self.pos
+    }
+
+    /// The remaining, unread bytes.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.buf[self.pos..]
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.buf.get(self.pos).copied()
+    }
+
+    fn byte(&mut self) -> Result<u8, BerError> {
+        let byte = *self.buf.get(self.pos).ok_or(BerError::Truncated)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn length(&mut self) -> Result<usize, BerError> {
+        let first = self.byte()?;
+        if first & 0x80 == 0 {
+            return Ok(first as usize);
+        }
+
+        let octets = (first & 0x7f) as usize;
+        if octets == 0 || octets > 4 {
+            // Indefinite lengths are not allowed in SNMP.
+            return Err(BerError::InvalidLength);
+        }
+        (0..octets).try_fold(
+            0usize,
+            |length, _| Ok((length << 8) | self.byte()? as usize),
+        )
+    }
+
+    /// Reads a TLV, returning its tag and a reader over its contents.
+    pub fn read_tlv(&mut self) -> Result<(u8, Reader<'a>), BerError> {
+        let tag = self.byte()?;
+        let length = self.length()?;
+        let start = self.pos;
+        let end = start.checked_add(length).ok_or(BerError::InvalidLength)?;
+        if end > self.buf.len() {
+            return Err(BerError::Truncated);
+        }
+        self.pos = end;
+        Ok((
+            tag,
+            Reader {
+                buf: &self.buf[start..end],
+                pos: 0,
+                base: self.base + start,
+            },
+        ))
+    }
+
+    /// Reads a TLV with the given tag, returning a reader over its contents.
+    pub fn read_expected(&mut self, expected: u8) -> Result<Reader<'a>, BerError> {
+        let (found, contents) = self.read_tlv()?;
+        if found != expected {
+            return Err(BerError::UnexpectedTag { expected, found });
+        }
+        Ok(contents)
+    }
+
+    pub fn read_integer(&mut self) -> Result<i64, BerError> {
+        decode_integer(self.read_expected(INTEGER)?.remaining())
+    }
+
+    pub fn read_octet_string(&mut self) -> Result<&'a [u8], BerError> {
+        Ok(self.read_expected(OCTET_STRING)?.remaining())
+    }
+
+    pub fn read_oid(&mut self) -> Result<Oid, BerError> {
+        decode_oid(self.read_expected(OBJECT_IDENTIFIER)?.remaining())
+    }
+}
+
+pub fn decode_integer(bytes: &[u8]) -> Result<i64, BerError> {
+    if bytes.is_empty() {
+        return Ok(0);
+    }
+    if bytes.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    let initial = if bytes[0] & 0x80 != 0 { -1i64 } else { 0 };
+    Ok(bytes
+        .iter()
+        .fold(initial, |value, byte| (value << 8) | *byte as i64))
+}
+
+pub fn decode_unsigned(bytes: &[u8]) -> Result<u64, BerError> {
+    // Unsigned values may carry a leading zero octet to keep the sign bit clear.
+    let bytes = match bytes {
+        [0, rest @ ..] => rest,
+        _ => bytes,
+    };
+    if bytes.len() > 8 {
+        return Err(BerError::IntegerOverflow);
+    }
+    Ok(bytes
+        .iter()
+        .fold(0u64, |value, byte| (value << 8) | *byte as u64))
+}
+
+pub fn decode_oid(bytes: &[u8]) -> Result<Oid, BerError> {
+    let mut components = Vec::with_capacity(bytes.len() + 1);
+    let mut value = 0u64;
+    for (index, byte) in bytes.iter().enumerate() {
+        value = (value << 7) | (byte & 0x7f) as u64;
+        if value > u32::MAX as u64 {
+            return Err(BerError::InvalidOid);
+        }
+        if byte & 0x80 != 0 {
+            if index == bytes.len() - 1 {
+                return Err(BerError::InvalidOid);
+            }
+            continue;
+        }
+        if components.is_empty() {
+            // The first sub-identifier packs the first two arcs.
+            let first = (value / 40).min(2);
+            components.push(first as u32);
+            components.push((value - first * 40) as u32);
+        } else {
+            components.push(value as u32);
+        }
+        value = 0;
+    }
+    Ok(Oid::from(components))
+}
+
+fn encode_length(length: usize, out: &mut Vec<u8>) {
+    if length < 0x80 {
+        out.push(length as u8);
+    } else {
+        let bytes = (length as u32).to_be_bytes();
+        let skip = bytes.iter().take_while(|b| **b == 0).count();
+        out.push(0x80 | (4 - skip) as u8);
+        out.extend_from_slice(&bytes[skip..]);
+    }
+}
+
+/// Encodes a TLV with the given tag and contents.
+pub fn encode_tlv(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(contents.len() + 6);
+    out.push(tag);
+    encode_length(contents.len(), &mut out);
+    out.extend_from_slice(contents);
+    out
+}
+
+/// Encodes a constructed TLV whose contents are the concatenation of `parts`.
+pub fn encode_sequence(tag: u8, parts: &[Vec<u8>]) -> Vec<u8> {
+    encode_tlv(tag, &parts.concat())
+}
+
+pub fn encode_integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    // Strip redundant leading octets, keeping the sign bit intact.
+    let mut start = 0;
+    while start < 7 {
+        let redundant = (bytes[start] == 0 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xff && bytes[start + 1] & 0x80 != 0);
+        if !redundant {
+            break;
+        }
+        start += 1;
+    }
+    encode_tlv(INTEGER, &bytes[start..])
+}
+
+pub fn encode_unsigned(tag: u8, value: u64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let skip = bytes.iter().take_while(|b| **b == 0).count().min(7);
+    let mut contents = Vec::with_capacity(9);
+    if bytes[skip] & 0x80 != 0 {
+        contents.push(0);
+    }
+    contents.extend_from_slice(&bytes[skip..]);
+    encode_tlv(tag, &contents)
+}
+
+pub fn encode_octet_string(value: &[u8]) -> Vec<u8> {
+    encode_tlv(OCTET_STRING, value)
+}
+
+pub fn encode_null() -> Vec<u8> {
+    encode_tlv(NULL, &[])
+}
+
+pub fn encode_oid(oid: &Oid) -> Vec<u8> {
+    let components = oid.components();
+    let mut contents = Vec::with_capacity(components.len() + 4);
+    let mut push = |value: u32| {
+        let mut chunks = [0u8; 5];
+        let mut count = 0;
+        let mut value = value;
+        loop {
+            chunks[count] = (value & 0x7f) as u8;
+            count += 1;
+            value >>= 7;
+            if value == 0 {
+                break;
+            }
+        }
+        for index in (0..count).rev() {
+            let continuation = if index == 0 { 0 } else { 0x80 };
+            contents.push(chunks[index] | continuation);
+        }
+    };
+
+    match components {
+        [] => {}
+        [first] => push(first * 40),
+        [first, second, rest @ ..] => {
+            push(first * 40 + second);
+            rest.iter().copied().for_each(&mut push);
+        }
+    }
+    encode_tlv(OBJECT_IDENTIFIER, &contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integer_roundtrip() {
+        for value in [
+            0,
+            1,
+            127,
+            128,
+            255,
+            256,
+            -1,
+            -128,
+            -129,
+            i32::MAX as i64,
+            i64::MIN,
+        ] {
+            let encoded = encode_integer(value);
+            assert_eq!(Reader::new(&encoded).read_integer().unwrap(), value);
+        }
+        assert_eq!(encode_integer(128), vec![INTEGER, 2, 0x00, 0x80]);
+        assert_eq!(encode_integer(-128), vec![INTEGER, 1, 0x80]);
+    }
+
+    #[test]
+    fn unsigned_roundtrip() {
+        for value in [0, 1, 0x80, u32::MAX as u64, u64::MAX] {
+            let encoded = encode_unsigned(COUNTER64, value);
+            let mut reader = Reader::new(&encoded);
+            let contents = reader.read_expected(COUNTER64).unwrap();
+            assert_eq!(decode_unsigned(contents.remaining()).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn oid_roundtrip() {
+        let oid: Oid = "1.3.6.1.4.1.2636.3.1.13.1.8.9.1.0.0".parse().unwrap();
+        let encoded = encode_oid(&oid);
+        assert_eq!(&encoded[..4], &[OBJECT_IDENTIFIER, 15, 0x2b, 0x06]);
+        assert_eq!(Reader::new(&encoded).read_oid().unwrap(), oid);
+    }
+
+    #[test]
+    fn long_lengths() {
+        let contents = vec![0xaa; 300];
+        let encoded = encode_octet_string(&contents);
+        assert_eq!(&encoded[..4], &[OCTET_STRING, 0x82, 0x01, 0x2c]);
+        assert_eq!(
+            Reader::new(&encoded).read_octet_string().unwrap(),
+            &contents[..]
+        );
+    }
+
+    #[test]
+    fn tracks_absolute_offsets() {
+        let encoded = encode_sequence(
+            SEQUENCE,
+            &[encode_integer(3), encode_octet_string(b"secret")],
+        );
+        let mut outer = Reader::new(&encoded);
+        let mut inner = outer.read_expected(SEQUENCE).unwrap();
+        inner.read_integer().unwrap();
+        let string = inner.read_expected(OCTET_STRING).unwrap();
+        assert_eq!(string.offset(), 7);
+        assert_eq!(&encoded[7..], b"secret");
+    }
+
+    #[test]
+    fn rejects_truncated_data() {
+        let encoded = encode_octet_string(b"truncated");
+        let error = Reader::new(&encoded[..5]).read_octet_string().unwrap_err();
+        assert_eq!(error, BerError::Truncated);
+    }
+}
@@ -0,0 +1,724 @@
+//! SNMP message and PDU definitions (RFC 1157, RFC 3416 and RFC 3412).
+
+use std::net::Ipv4Addr;
+
+use snafu::Snafu;
+
+use super::{
+    ber::{self, BerError, Reader},
+    oid::Oid,
+};
+
+/// Errors that can occur while decoding an SNMP message.
+#[derive(Debug, Snafu)]
+pub enum MessageError {
+    #[snafu(display("Malformed message: {}", source))]
+    Malformed { source: BerError },
+
+    #[snafu(display("Unsupported SNMP version {}", version))]
+    UnsupportedVersion { version: i64 },
+
+    #[snafu(display("Unsupported security model {}", model))]
+    UnsupportedSecurityModel { model: i64 },
+
+    #[snafu(display("Unknown PDU type {:#04x}", tag))]
+    UnknownPduType { tag: u8 },
+}
+
+impl From<BerError> for MessageError {
+    fn from(source: BerError) -> Self {
+        Self::Malformed { source }
+    }
+}
+
+/// The USM security model, the only one supported for SNMPv3.
+pub const SECURITY_MODEL_USM: i64 = 3;
+
+pub const FLAG_AUTH: u8 = 0x01;
+pub const FLAG_PRIV: u8 = 0x02;
+pub const FLAG_REPORTABLE: u8 = 0x04;
+
+/// The protocol version of an SNMP message.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Version {
+    V1,
+    V2c,
+    V3,
+}
+
+impl Version {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::V1 => "1",
+            Self::V2c => "2c",
+            Self::V3 => "3",
+        }
+    }
+
+    const fn wire(self) -> i64 {
+        match self {
+            Self::V1 => 0,
+            Self::V2c => 1,
+            Self::V3 => 3,
+        }
+    }
+
+    fn from_wire(version: i64) -> Result<Self, MessageError> {
+        match version {
+            0 => Ok(Self::V1),
+            1 => Ok(Self::V2c),
+            3 => Ok(Self::V3),
+            version => Err(MessageError::UnsupportedVersion { version }),
+        }
+    }
+}
+
+/// The value of a variable binding.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Null,
+    ObjectIdentifier(Oid),
+    IpAddress(Ipv4Addr),
+    Counter32(u32),
+    Gauge32(u32),
+    TimeTicks(u32),
+    Opaque(Vec<u8>),
+    Counter64(u64),
+    NoSuchObject,
+    NoSuchInstance,
+    EndOfMibView,
+}
+
+impl Value {
+    /// The name of the value's type, as used in MIB definitions.
+    pub const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::OctetString(_) => "octet_string",
+            Self::Null => "null",
+            Self::ObjectIdentifier(_) => "object_identifier",
+            Self::IpAddress(_) => "ip_address",
+            Self::Counter32(_) => "counter32",
+            Self::Gauge32(_) => "gauge32",
+            Self::TimeTicks(_) => "timeticks",
+            Self::Opaque(_) => "opaque",
+            Self::Counter64(_) => "counter64",
+            Self::NoSuchObject => "no_such_object",
+            Self::NoSuchInstance => "no_such_instance",
+            Self::EndOfMibView => "end_of_mib_view",
+        }
+    }
+
+    /// Returns `true` for the exception values a response may carry in place of a value.
+    pub const fn is_exception(&self) -> bool {
+        matches!(
+            self,
+            Self::NoSuchObject | Self::NoSuchInstance | Self::EndOfMibView
+        )
+    }
+
+    fn decode(tag: u8, bytes: &[u8]) -> Result<Self, BerError> {
+        let unsigned32 = |bytes| {
+            ber::decode_unsigned(bytes)
+                .and_then(|value| u32::try_from(value).map_err(|_| BerError::IntegerOverflow))
+        };
+        Ok(match tag {
+            ber::INTEGER => Self::Integer(ber::decode_integer(bytes)?),
+            ber::OCTET_STRING => Self::OctetString(bytes.to_vec()),
+            ber::NULL => Self::Null,
+            ber::OBJECT_IDENTIFIER => Self::ObjectIdentifier(ber::decode_oid(bytes)?),
+            ber::IP_ADDRESS => {
+                let octets: [u8; 4] = bytes.try_into().map_err(|_| BerError::InvalidLength)?;
+                Self::IpAddress(Ipv4Addr::from(octets))
+            }
+            ber::COUNTER32 => Self::Counter32(unsigned32(bytes)?),
+            ber::GAUGE32 => Self::Gauge32(unsigned32(bytes)?),
+            ber::TIMETICKS => Self::TimeTicks(unsigned32(bytes)?),
+            ber::COUNTER64 => Self::Counter64(ber::decode_unsigned(bytes)?),
+            ber::NO_SUCH_OBJECT => Self::NoSuchObject,
+            ber::NO_SUCH_INSTANCE => Self::NoSuchInstance,
+            ber::END_OF_MIB_VIEW => Self::EndOfMibView,
+            // Treat anything else as opaque so that a single unknown type does not cause the whole
+            // message to be discarded.
+            _ => Self::Opaque(bytes.to_vec()),
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        match self {
+            Self::Integer(value) => ber::encode_integer(*value),
+            Self::OctetString(value) => ber::encode_octet_string(value),
+            Self::Null => ber::encode_null(),
+            Self::ObjectIdentifier(oid) => ber::encode_oid(oid),
+            Self::IpAddress(address) => ber::encode_tlv(ber::IP_ADDRESS, &address.octets()),
+            Self::Counter32(value) => ber::encode_unsigned(ber::COUNTER32, *value as u64),
+            Self::Gauge32(value) => ber::encode_unsigned(ber::GAUGE32, *value as u64),
+            Self::TimeTicks(value) => ber::encode_unsigned(ber::TIMETICKS, *value as u64),
+            Self::Opaque(value) => ber::encode_tlv(ber::OPAQUE, value),
+            Self::Counter64(value) => ber::encode_unsigned(ber::COUNTER64, *value),
+            Self::NoSuchObject => ber::encode_tlv(ber::NO_SUCH_OBJECT, &[]),
+            Self::NoSuchInstance => ber::encode_tlv(ber::NO_SUCH_INSTANCE, &[]),
+            Self::EndOfMibView => ber::encode_tlv(ber::END_OF_MIB_VIEW, &[]),
+        }
+    }
+}
+
+/// A single variable binding.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VarBind {
+    pub oid: Oid,
+    pub value: Value,
+}
+
+impl VarBind {
+    pub const fn new(oid: Oid, value: Value) -> Self {
+        Self { oid, value }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, BerError> {
+        let mut contents = reader.read_expected(ber::SEQUENCE)?;
+        let oid = contents.read_oid()?;
+        let (tag, value) = contents.read_tlv()?;
+        Ok(Self {
+            oid,
+            value: Value::decode(tag, value.remaining())?,
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        ber::encode_sequence(
+            ber::SEQUENCE,
+            &[ber::encode_oid(&self.oid), self.value.encode()],
+        )
+    }
+}
+
+/// The type of a PDU, identified by its context-specific tag.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PduType {
+    GetRequest,
+    GetNextRequest,
+    Response,
+    SetRequest,
+    TrapV1,
+    GetBulkRequest,
+    InformRequest,
+    TrapV2,
+    Report,
+}
+
+impl PduType {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::GetRequest => "get_request",
+            Self::GetNextRequest => "get_next_request",
+            Self::Response => "response",
+            Self::SetRequest => "set_request",
+            Self::TrapV1 => "trap",
+            Self::GetBulkRequest => "get_bulk_request",
+            Self::InformRequest => "inform_request",
+            Self::TrapV2 => "trap_v2",
+            Self::Report => "report",
+        }
+    }
+
+    const fn tag(self) -> u8 {
+        match self {
+            Self::GetRequest => 0xa0,
+            Self::GetNextRequest => 0xa1,
+            Self::Response => 0xa2,
+            Self::SetRequest => 0xa3,
+            Self::TrapV1 => 0xa4,
+            Self::GetBulkRequest => 0xa5,
+            Self::InformRequest => 0xa6,
+            Self::TrapV2 => 0xa7,
+            Self::Report => 0xa8,
+        }
+    }
+
+    const fn from_tag(tag: u8) -> Option<Self> {
+        Some(match tag {
+            0xa0 => Self::GetRequest,
+            0xa1 => Self::GetNextRequest,
+            0xa2 => Self::Response,
+            0xa3 => Self::SetRequest,
+            0xa4 => Self::TrapV1,
+            0xa5 => Self::GetBulkRequest,
+            0xa6 => Self::InformRequest,
+            0xa7 => Self::TrapV2,
+            0xa8 => Self::Report,
+            _ => return None,
+        })
+    }
+}
+
+/// A PDU in the common request/response format.
+///
+/// For `GetBulkRequest` PDUs, `error_status` and `error_index` hold the non-repeaters and
+/// max-repetitions fields respectively.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pdu {
+    pub pdu_type: PduType,
+    pub request_id: i32,
+    pub error_status: i64,
+    pub error_index: i64,
+    pub varbinds: Vec<VarBind>,
+}
+
+impl Pdu {
+    pub const fn new(pdu_type: PduType, request_id: i32, varbinds: Vec<VarBind>) -> Self {
+        Self {
+            pdu_type,
+            request_id,
+            error_status: 0,
+            error_index: 0,
+            varbinds,
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let varbinds = self
+            .varbinds
+            .iter()
+            .map(VarBind::encode)
+            .collect::<Vec<_>>();
+        ber::encode_sequence(
+            self.pdu_type.tag(),
+            &[
+                ber::encode_integer(self.request_id as i64),
+                ber::encode_integer(self.error_status),
+                ber::encode_integer(self.error_index),
+                ber::encode_sequence(ber::SEQUENCE, &varbinds),
+            ],
+        )
+    }
+}
+
+/// An SNMPv1 Trap-PDU.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrapV1Pdu {
+    pub enterprise: Oid,
+    pub agent_address: Ipv4Addr,
+    pub generic_trap: i64,
+    pub specific_trap: i64,
+    pub timestamp: u32,
+    pub varbinds: Vec<VarBind>,
+}
+
+/// Any PDU carried by an SNMP message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnyPdu {
+    Standard(Pdu),
+    TrapV1(TrapV1Pdu),
+}
+
+impl AnyPdu {
+    pub const fn pdu_type(&self) -> PduType {
+        match self {
+            Self::Standard(pdu) => pdu.pdu_type,
+            Self::TrapV1(_) => PduType::TrapV1,
+        }
+    }
+
+    pub fn varbinds(&self) -> &[VarBind] {
+        match self {
+            Self::Standard(pdu) => &pdu.varbinds,
+            Self::TrapV1(trap) => &trap.varbinds,
+        }
+    }
+
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, MessageError> {
+        let (tag, mut contents) = reader.read_tlv()?;
+        let pdu_type = PduType::from_tag(tag).ok_or(MessageError::UnknownPduType { tag })?;
+
+        if pdu_type == PduType::TrapV1 {
+            let enterprise = contents.read_oid()?;
+            let address = contents.read_expected(ber::IP_ADDRESS)?;
+            let octets: [u8; 4] = address
+                .remaining()
+                .try_into()
+                .map_err(|_| BerError::InvalidLength)?;
+            let generic_trap = contents.read_integer()?;
+            let specific_trap = contents.read_integer()?;
+            let timestamp =
+                ber::decode_unsigned(contents.read_expected(ber::TIMETICKS)?.remaining())?;
+            let varbinds = decode_varbinds(&mut contents)?;
+            return Ok(Self::TrapV1(TrapV1Pdu {
+                enterprise,
+                agent_address: Ipv4Addr::from(octets),
+                generic_trap,
+                specific_trap,
+                timestamp: timestamp as u32,
+                varbinds,
+            }));
+        }
+
+        let request_id = contents.read_integer()? as i32;
+        let error_status = contents.read_integer()?;
+        let error_index = contents.read_integer()?;
+        let varbinds = decode_varbinds(&mut contents)?;
+        Ok(Self::Standard(Pdu {
+            pdu_type,
+            request_id,
+            error_status,
+            error_index,
+            varbinds,
+        }))
+    }
+}
+
+fn decode_varbinds(reader: &mut Reader<'_>) -> Result<Vec<VarBind>, BerError> {
+    let mut list = reader.read_expected(ber::SEQUENCE)?;
+    let mut varbinds = Vec::new();
+    while !list.is_empty() {
+        varbinds.push(VarBind::decode(&mut list)?);
+    }
+    Ok(varbinds)
+}
+
+/// An SNMPv1 or SNMPv2c message, using community based security.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommunityMessage {
+    pub version: Version,
+    pub community: Vec<u8>,
+    pub pdu: AnyPdu,
+}
+
+impl CommunityMessage {
+    /// Encodes the message. Only standard PDUs can be sent.
+    pub fn encode(version: Version, community: &[u8], pdu: &Pdu) -> Vec<u8> {
+        ber::encode_sequence(
+            ber::SEQUENCE,
+            &[
+                ber::encode_integer(version.wire()),
+                ber::encode_octet_string(community),
+                pdu.encode(),
+            ],
+        )
+    }
+}
+
+/// The USM security parameters of an SNMPv3 message (RFC 3414).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsmSecurityParameters {
+    pub engine_id: Vec<u8>,
+    pub engine_boots: u32,
+    pub engine_time: u32,
+    pub user_name: Vec<u8>,
+    pub auth_params: Vec<u8>,
+    pub priv_params: Vec<u8>,
+}
+
+/// The scoped PDU of an SNMPv3 message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScopedPdu {
+    pub context_engine_id: Vec<u8>,
+    pub context_name: Vec<u8>,
+    pub pdu: AnyPdu,
+}
+
+impl ScopedPdu {
+    pub fn decode(bytes: &[u8]) -> Result<Self, MessageError> {
+        Self::read(&mut Reader::new(bytes))
+    }
+
+    fn read(reader: &mut Reader<'_>) -> Result<Self, MessageError> {
+        let mut contents = reader.read_expected(ber::SEQUENCE)?;
+        Ok(Self {
+            context_engine_id: contents.read_octet_string()?.to_vec(),
+            context_name: contents.read_octet_string()?.to_vec(),
+            pdu: AnyPdu::decode(&mut contents)?,
+        })
+    }
+
+    /// Encodes the scoped PDU. Only standard PDUs can be sent.
+    pub fn encode(context_engine_id: &[u8], context_name: &[u8], pdu: &Pdu) -> Vec<u8> {
+        ber::encode_sequence(
+            ber::SEQUENCE,
+            &[
+                ber::encode_octet_string(context_engine_id),
+                ber::encode_octet_string(context_name),
+                pdu.encode(),
+            ],
+        )
+    }
+}
+
+/// The data of an SNMPv3 message, which is encrypted when privacy is in use.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScopedPduData {
+    Plaintext(ScopedPdu),
+    Encrypted(Vec<u8>),
+}
+
+/// An SNMPv3 message using the user-based security model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct V3Message {
+    pub msg_id: i64,
+    pub max_size: i64,
+    pub flags: u8,
+    pub usm: UsmSecurityParameters,
+    pub data: ScopedPduData,
+    /// The offset of the authentication parameters within the encoded message, used to compute
+    /// and verify its digest.
+    pub auth_params_offset: usize,
+}
+
+impl V3Message {
+    /// Encodes the message, returning it along with the offset of the authentication parameters.
+    ///
+    /// `scoped_pdu` is either an encoded `ScopedPdu` or, when privacy is in use, the encrypted
+    /// form of one.
+    pub fn encode(
+        msg_id: i64,
+        max_size: i64,
+        flags: u8,
+        usm: &UsmSecurityParameters,
+        scoped_pdu: &[u8],
+    ) -> (Vec<u8>, usize) {
+        let header = ber::encode_sequence(
+            ber::SEQUENCE,
+            &[
+                ber::encode_integer(msg_id),
+                ber::encode_integer(max_size),
+                ber::encode_octet_string(&[flags]),
+                ber::encode_integer(SECURITY_MODEL_USM),
+            ],
+        );
+
+        let leading = [
+            ber::encode_octet_string(&usm.engine_id),
+            ber::encode_integer(usm.engine_boots as i64),
+            ber::encode_integer(usm.engine_time as i64),
+            ber::encode_octet_string(&usm.user_name),
+        ];
+        let auth_params = ber::encode_octet_string(&usm.auth_params);
+        let priv_params = ber::encode_octet_string(&usm.priv_params);
+        let usm_contents_len =
+            leading.iter().map(Vec::len).sum::<usize>() + auth_params.len() + priv_params.len();
+        let usm_sequence = ber::encode_sequence(
+            ber::SEQUENCE,
+            &[leading.concat(), auth_params.clone(), priv_params],
+        );
+        let security_params = ber::encode_octet_string(&usm_sequence);
+
+        let version = ber::encode_integer(Version::V3.wire());
+        let data = if flags & FLAG_PRIV != 0 {
+            ber::encode_octet_string(scoped_pdu)
+        } else {
+            scoped_pdu.to_vec()
+        };
+        let contents_len = version.len() + header.len() + security_params.len() + data.len();
+        let message = ber::encode_sequence(
+            ber::SEQUENCE,
+            &[
+                version.clone(),
+                header.clone(),
+                security_params.clone(),
+                data,
+            ],
+        );
+
+        // Walk down through the TLV headers to the contents of the authentication parameters.
+        let offset = (message.len() - contents_len)
+            + version.len()
+            + header.len()
+            + (security_params.len() - usm_sequence.len())
+            + (usm_sequence.len() - usm_contents_len)
+            + leading.iter().map(Vec::len).sum::<usize>()
+            + (auth_params.len() - usm.auth_params.len());
+        (message, offset)
+    }
+}
+
+/// A decoded SNMP message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    Community(CommunityMessage),
+    V3(V3Message),
+}
+
+impl Message {
+    pub fn decode(bytes: &[u8]) -> Result<Self, MessageError> {
+        let mut reader = Reader::new(bytes);
+        let mut message = reader.read_expected(ber::SEQUENCE)?;
+        let version = Version::from_wire(message.read_integer()?)?;
+
+        if version != Version::V3 {
+            let community = message.read_octet_string()?.to_vec();
+            let pdu = AnyPdu::decode(&mut message)?;
+            return Ok(Self::Community(CommunityMessage {
+                version,
+                community,
+                pdu,
+            }));
+        }
+
+        let mut header = message.read_expected(ber::SEQUENCE)?;
+        let msg_id = header.read_integer()?;
+        let max_size = header.read_integer()?;
+        let flags = header.read_octet_string()?.first().copied().unwrap_or(0);
+        let model = header.read_integer()?;
+        if model != SECURITY_MODEL_USM {
+            return Err(MessageError::UnsupportedSecurityModel { model });
+        }
+
+        let mut security_params = message.read_expected(ber::OCTET_STRING)?;
+        let mut usm_params = security_params.read_expected(ber::SEQUENCE)?;
+        let engine_id = usm_params.read_octet_string()?.to_vec();
+        let engine_boots = usm_params.read_integer()? as u32;
+        let engine_time = usm_params.read_integer()? as u32;
+        let user_name = usm_params.read_octet_string()?.to_vec();
+        let auth = usm_params.read_expected(ber::OCTET_STRING)?;
+        let auth_params_offset = auth.offset();
+        let auth_params = auth.remaining().to_vec();
+        let priv_params = usm_params.read_octet_string()?.to_vec();
+
+        let data = match message.peek_tag() {
+            Some(ber::OCTET_STRING) => {
+                ScopedPduData::Encrypted(message.read_octet_string()?.to_vec())
+            }
+            _ => ScopedPduData::Plaintext(ScopedPdu::read(&mut message)?),
+        };
+
+        Ok(Self::V3(V3Message {
+            msg_id,
+            max_size,
+            flags,
+            usm: UsmSecurityParameters {
+                engine_id,
+                engine_boots,
+                engine_time,
+                user_name,
+                auth_params,
+                priv_params,
+            },
+            data,
+            auth_params_offset,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_varbinds() -> Vec<VarBind> {
+        vec![
+            VarBind::new(
+                "1.3.6.1.2.1.1.3.0".parse().unwrap(),
+                Value::TimeTicks(123456),
+            ),
+            VarBind::new(
+                "1.3.6.1.6.3.1.1.4.1.0".parse().unwrap(),
+                Value::ObjectIdentifier("1.3.6.1.6.3.1.1.5.3".parse().unwrap()),
+            ),
+            VarBind::new("1.3.6.1.2.1.2.2.1.1.3".parse().unwrap(), Value::Integer(3)),
+            VarBind::new(
+                "1.3.6.1.2.1.2.2.1.2.3".parse().unwrap(),
+                Value::OctetString(b"eth0".to_vec()),
+            ),
+            VarBind::new(
+                "1.3.6.1.2.1.31.1.1.1.6.3".parse().unwrap(),
+                Value::Counter64(u64::MAX),
+            ),
+            VarBind::new(
+                "1.3.6.1.2.1.4.20.1.1.10.0.0.1".parse().unwrap(),
+                Value::IpAddress(Ipv4Addr::new(10, 0, 0, 1)),
+            ),
+            VarBind::new("1.3.6.1.2.1.1.9.0".parse().unwrap(), Value::NoSuchInstance),
+        ]
+    }
+
+    #[test]
+    fn community_message_roundtrip() {
+        let pdu = Pdu::new(PduType::InformRequest, -42, sample_varbinds());
+        let encoded = CommunityMessage::encode(Version::V2c, b"public", &pdu);
+        let Message::Community(message) = Message::decode(&encoded).unwrap() else {
+            panic!("expected a community message");
+        };
+        assert_eq!(message.version, Version::V2c);
+        assert_eq!(message.community, b"public");
+        assert_eq!(message.pdu, AnyPdu::Standard(pdu));
+    }
+
+    #[test]
+    fn decodes_v1_trap() {
+        let trap = ber::encode_sequence(
+            0xa4,
+            &[
+                ber::encode_oid(&"1.3.6.1.4.1.8072.2.3.1".parse().unwrap()),
+                ber::encode_tlv(ber::IP_ADDRESS, &[192, 0, 2, 1]),
+                ber::encode_integer(6),
+                ber::encode_integer(17),
+                ber::encode_unsigned(ber::TIMETICKS, 5500),
+                ber::encode_sequence(ber::SEQUENCE, &[]),
+            ],
+        );
+        let encoded = ber::encode_sequence(
+            ber::SEQUENCE,
+            &[
+                ber::encode_integer(0),
+                ber::encode_octet_string(b"public"),
+                trap,
+            ],
+        );
+        let Message::Community(message) = Message::decode(&encoded).unwrap() else {
+            panic!("expected a community message");
+        };
+        assert_eq!(message.version, Version::V1);
+        let AnyPdu::TrapV1(trap) = message.pdu else {
+            panic!("expected a v1 trap");
+        };
+        assert_eq!(trap.agent_address, Ipv4Addr::new(192, 0, 2, 1));
+        assert_eq!(trap.generic_trap, 6);
+        assert_eq!(trap.specific_trap, 17);
+        assert_eq!(trap.timestamp, 5500);
+    }
+
+    #[test]
+    fn v3_message_roundtrip() {
+        let pdu = Pdu::new(PduType::TrapV2, 7, sample_varbinds());
+        let scoped = ScopedPdu::encode(b"engine", b"", &pdu);
+        let usm = UsmSecurityParameters {
+            engine_id: b"engine".to_vec(),
+            engine_boots: 1,
+            engine_time: 300,
+            user_name: b"monitor".to_vec(),
+            auth_params: vec![0xab; 12],
+            priv_params: Vec::new(),
+        };
+        let (encoded, offset) = V3Message::encode(99, 65507, FLAG_AUTH, &usm, &scoped);
+        assert_eq!(&encoded[offset..offset + 12], &[0xab; 12]);
+
+        let Message::V3(message) = Message::decode(&encoded).unwrap() else {
+            panic!("expected a v3 message");
+        };
+        assert_eq!(message.msg_id, 99);
+        assert_eq!(message.flags, FLAG_AUTH);
+        assert_eq!(message.usm, usm);
+        assert_eq!(message.auth_params_offset, offset);
+        assert_eq!(
+            message.data,
+            ScopedPduData::Plaintext(ScopedPdu {
+                context_engine_id: b"engine".to_vec(),
+                context_name: Vec::new(),
+                pdu: AnyPdu::Standard(pdu),
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        let encoded = ber::encode_sequence(
+            ber::SEQUENCE,
+            &[ber::encode_integer(2), ber::encode_octet_string(b"public")],
+        );
+        assert!(matches!(
+            Message::decode(&encoded),
+            Err(MessageError::UnsupportedVersion { version: 2 })
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+//! Translation of numeric OIDs to names derived from MIB definitions.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use snafu::{ResultExt, Snafu};
+
+use super::oid::Oid;
+
+/// Errors that can occur while loading an OID name mapping file.
+#[derive(Debug, Snafu)]
+pub enum MibError {
+    #[snafu(display("Could not read OID mapping file {:?}: {}", path, source))]
+    Read {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Invalid entry on line {} of OID mapping file {:?}", line, path))]
+    InvalidEntry { path: PathBuf, line: usize },
+}
+
+/// Well known objects that are always translated, even without any mapping files.
+const BUILTIN: &[(&str, &str)] = &[
+    ("system", "1.3.6.1.2.1.1"),
+    ("sysDescr", "1.3.6.1.2.1.1.1"),
+    ("sysObjectID", "1.3.6.1.2.1.1.2"),
+    ("sysUpTime", "1.3.6.1.2.1.1.3"),
+    ("sysContact", "1.3.6.1.2.1.1.4"),
+    ("sysName", "1.3.6.1.2.1.1.5"),
+    ("sysLocation", "1.3.6.1.2.1.1.6"),
+    ("ifNumber", "1.3.6.1.2.1.2.1"),
+    ("ifTable", "1.3.6.1.2.1.2.2"),
+    ("ifEntry", "1.3.6.1.2.1.2.2.1"),
+    ("ifIndex", "1.3.6.1.2.1.2.2.1.1"),
+    ("ifDescr", "1.3.6.1.2.1.2.2.1.2"),
+    ("ifType", "1.3.6.1.2.1.2.2.1.3"),
+    ("ifMtu", "1.3.6.1.2.1.2.2.1.4"),
+    ("ifSpeed", "1.3.6.1.2.1.2.2.1.5"),
+    ("ifPhysAddress", "1.3.6.1.2.1.2.2.1.6"),
+    ("ifAdminStatus", "1.3.6.1.2.1.2.2.1.7"),
+    ("ifOperStatus", "1.3.6.1.2.1.2.2.1.8"),
+    ("ifLastChange", "1.3.6.1.2.1.2.2.1.9"),
+    ("ifInOctets", "1.3.6.1.2.1.2.2.1.10"),
+    ("ifInUcastPkts", "1.3.6.1.2.1.2.2.1.11"),
+    ("ifInDiscards", "1.3.6.1.2.1.2.2.1.13"),
+    ("ifInErrors", "1.3.6.1.2.1.2.2.1.14"),
+    ("ifInUnknownProtos", "1.3.6.1.2.1.2.2.1.15"),
+    ("ifOutOctets", "1.3.6.1.2.1.2.2.1.16"),
+    ("ifOutUcastPkts", "1.3.6.1.2.1.2.2.1.17"),
+    ("ifOutDiscards", "1.3.6.1.2.1.2.2.1.19"),
+    ("ifOutErrors", "1.3.6.1.2.1.2.2.1.20"),
+    ("ifXTable", "1.3.6.1.2.1.31.1.1"),
+    ("ifXEntry", "1.3.6.1.2.1.31.1.1.1"),
+    ("ifName", "1.3.6.1.2.1.31.1.1.1.1"),
+    ("ifInMulticastPkts", "1.3.6.1.2.1.31.1.1.1.2"),
+    ("ifInBroadcastPkts", "1.3.6.1.2.1.31.1.1.1.3"),
+    ("ifOutMulticastPkts", "1.3.6.1.2.1.31.1.1.1.4"),
+    ("ifOutBroadcastPkts", "1.3.6.1.2.1.31.1.1.1.5"),
+    ("ifHCInOctets", "1.3.6.1.2.1.31.1.1.1.6"),
+    ("ifHCInUcastPkts", "1.3.6.1.2.1.31.1.1.1.7"),
+    ("ifHCInMulticastPkts", "1.3.6.1.2.1.31.1.1.1.8"),
+    ("ifHCInBroadcastPkts", "1.3.6.1.2.1.31.1.1.1.9"),
+    ("ifHCOutOctets", "1.3.6.1.2.1.31.1.1.1.10"),
+    ("ifHCOutUcastPkts", "1.3.6.1.2.1.31.1.1.1.11"),
+    ("ifHCOutMulticastPkts", "1.3.6.1.2.1.31.1.1.1.12"),
+    ("ifHCOutBroadcastPkts", "1.3.6.1.2.1.31.1.1.1.13"),
+    ("ifHighSpeed", "1.3.6.1.2.1.31.1.1.1.15"),
+    ("ifAlias", "1.3.6.1.2.1.31.1.1.1.18"),
+    ("snmpTrapOID", "1.3.6.1.6.3.1.1.4.1"),
+    ("snmpTrapEnterprise", "1.3.6.1.6.3.1.1.4.3"),
+    ("snmpTrapAddress", "1.3.6.1.6.3.18.1.3"),
+    ("snmpTrapCommunity", "1.3.6.1.6.3.18.1.4"),
+    ("coldStart", "1.3.6.1.6.3.1.1.5.1"),
+    ("warmStart", "1.3.6.1.6.3.1.1.5.2"),
+    ("linkDown", "1.3.6.1.6.3.1.1.5.3"),
+    ("linkUp", "1.3.6.1.6.3.1.1.5.4"),
+    ("authenticationFailure", "1.3.6.1.6.3.1.1.5.5"),
+];
+
+/// A mapping of OIDs to object names.
+///
+/// Mapping files contain one `name OID` pair per line, in the format produced by
+/// `snmptranslate -Tz -On`. Names and OIDs may be quoted and lines starting with `#` are ignored.
+#[derive(Clone, Debug)]
+pub struct OidNames {
+    names: HashMap<Vec<u32>, String>,
+}
+
+impl Default for OidNames {
+    fn default() -> Self {
+        let names = BUILTIN
+            .iter()
+            .map(|(name, oid)| {
+                let oid: Oid = oid.parse().expect("builtin OIDs are valid");
+                (oid.components().to_vec(), (*name).to_owned())
+            })
+            .collect();
+        Self { names }
+    }
+}
+
+impl OidNames {
+    /// Loads the builtin names extended with the given mapping files.
+    ///
+    /// Entries in later files take precedence over earlier ones.
+    pub fn load(paths: &[PathBuf]) -> Result<Self, MibError> {
+        let mut names = Self::default();
+        for path in paths {
+            let contents = std::fs::read_to_string(path).context(ReadSnafu { path })?;
+            names.extend_from_str(path, &contents)?;
+        }
+        Ok(names)
+    }
+
+    fn extend_from_str(&mut self, path: &Path, contents: &str) -> Result<(), MibError> {
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || MibError::InvalidEntry {
+                path: path.to_owned(),
+                line: index + 1,
+            };
+            let mut parts = line.split_whitespace().map(|part| part.trim_matches('"'));
+            let (Some(name), Some(oid), None) = (parts.next(), parts.next(), parts.next()) else {
+                return Err(invalid());
+            };
+            let oid: Oid = oid.parse().map_err(|_| invalid())?;
+            self.names
+                .insert(oid.components().to_vec(), name.to_owned());
+        }
+        Ok(())
+    }
+
+    /// Returns the name of the object, without any instance suffix, if it is known exactly.
+    pub fn name(&self, oid: &Oid) -> Option<&str> {
+        self.names.get(oid.components()).map(String::as_str)
+    }
+
+    /// Resolves an OID given either in numeric form or as an object name optionally followed by
+    /// numeric sub-identifiers, for example `sysUpTime.0`.
+    pub fn resolve(&self, oid: &str) -> Option<Oid> {
+        if let Ok(oid) = oid.parse() {
+            return Some(oid);
+        }
+
+        let (name, suffix) = oid.split_once('.').unwrap_or((oid, ""));
+        let prefix = self
+            .names
+            .iter()
+            .find_map(|(components, known)| (known == name).then_some(components))?;
+        let suffix = suffix
+            .split('.')
+            .filter(|component| !component.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<u32>, _>>()
+            .ok()?;
+        Some(Oid::from(prefix.clone()).child(&suffix))
+    }
+
+    /// Translates an OID using its longest known prefix, for example `ifInOctets.3`.
+    ///
+    /// Returns `None` if no prefix of the OID is known.
+    pub fn translate(&self, oid: &Oid) -> Option<String> {
+        let components = oid.components();
+        (1..=components.len()).rev().find_map(|length| {
+            let name = self.names.get(&components[..length])?;
+            let mut translated = name.clone();
+            for component in &components[length..] {
+                translated.push('.');
+                translated.push_str(&component.to_string());
+            }
+            Some(translated)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_with_longest_prefix() {
+        let names = OidNames::default();
+        let oid: Oid = "1.3.6.1.2.1.2.2.1.10.3".parse().unwrap();
+        assert_eq!(names.translate(&oid).as_deref(), Some("ifInOctets.3"));
+        let oid: Oid = "1.3.6.1.6.3.1.1.5.3".parse().unwrap();
+        assert_eq!(names.name(&oid), Some("linkDown"));
+        let oid: Oid = "1.2.3".parse().unwrap();
+        assert_eq!(names.translate(&oid), None);
+    }
+
+    #[test]
+    fn resolves_names() {
+        let names = OidNames::default();
+        let expected: Oid = "1.3.6.1.2.1.1.3.0".parse().unwrap();
+        assert_eq!(names.resolve("sysUpTime.0"), Some(expected.clone()));
+        assert_eq!(names.resolve("1.3.6.1.2.1.1.3.0"), Some(expected));
+        assert_eq!(
+            names.resolve("ifHCInOctets"),
+            Some("1.3.6.1.2.1.31.1.1.1.6".parse().unwrap())
+        );
+        assert_eq!(names.resolve("unknownObject"), None);
+        assert_eq!(names.resolve("sysUpTime.x"), None);
+    }
+
+    #[test]
+    fn loads_mapping_files() {
+        let mut names = OidNames::default();
+        let contents = r#"
+# Generated with snmptranslate -Tz -On
+"enterprises"		"1.3.6.1.4.1"
+"jnxMibs"		"1.3.6.1.4.1.2636.3"
+linkDown 1.3.6.1.6.3.1.1.5.99
+"#;
+        names
+            .extend_from_str(Path::new("juniper.txt"), contents)
+            .unwrap();
+
+        let oid: Oid = "1.3.6.1.4.1.2636.3.1.13".parse().unwrap();
+        assert_eq!(names.translate(&oid).as_deref(), Some("jnxMibs.1.13"));
+        let oid: Oid = "1.3.6.1.4.1.9".parse().unwrap();
+        assert_eq!(names.translate(&oid).as_deref(), Some("enterprises.9"));
+        let oid: Oid = "1.3.6.1.6.3.1.1.5.99".parse().unwrap();
+        assert_eq!(names.name(&oid), Some("linkDown"));
+    }
+
+    #[test]
+    fn rejects_invalid_entries() {
+        let error = OidNames::default()
+            .extend_from_str(Path::new("broken.txt"), "ok 1.3.6\nbroken\n")
+            .unwrap_err();
+        assert!(matches!(error, MibError::InvalidEntry { line: 2, .. }));
+    }
+}
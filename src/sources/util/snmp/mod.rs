@@ -0,0 +1,83 @@
+//! Shared SNMP protocol support for the SNMP sources.
+
+pub mod ber;
+pub mod message;
+pub mod mib;
+pub mod oid;
+pub mod usm;
+
+use ordered_float::NotNan;
+
+use self::message::Value;
+use crate::event::Value as EventValue;
+
+/// Converts a variable binding value to an event value.
+///
+/// Octet strings are kept as text when they are valid UTF-8 without control characters, and are
+/// otherwise rendered as colon separated hex, which is how binary values such as MAC addresses are
+/// conventionally displayed.
+pub fn to_event_value(value: &Value) -> EventValue {
+    match value {
+        Value::Integer(value) => EventValue::from(*value),
+        Value::OctetString(bytes) => octet_string(bytes),
+        Value::ObjectIdentifier(oid) => EventValue::from(oid.to_string()),
+        Value::IpAddress(address) => EventValue::from(address.to_string()),
+        Value::Counter32(value) | Value::Gauge32(value) | Value::TimeTicks(value) => {
+            EventValue::from(*value as i64)
+        }
+        Value::Counter64(value) => match i64::try_from(*value) {
+            Ok(value) => EventValue::from(value),
+            Err(_) => EventValue::Float(
+                NotNan::new(*value as f64).expect("u64 conversions are never NaN"),
+            ),
+        },
+        Value::Opaque(bytes) => EventValue::from(hex::encode(bytes)),
+        Value::Null | Value::NoSuchObject | Value::NoSuchInstance | Value::EndOfMibView => {
+            EventValue::Null
+        }
+    }
+}
+
+fn octet_string(bytes: &[u8]) -> EventValue {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => {
+            // Agents commonly include the C string terminator.
+            EventValue::from(text.trim_end_matches('\0'))
+        }
+        _ => EventValue::from(
+            bytes
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(":"),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_octet_strings() {
+        assert_eq!(
+            to_event_value(&Value::OctetString(b"ge-0/0/1\0".to_vec())),
+            EventValue::from("ge-0/0/1")
+        );
+        assert_eq!(
+            to_event_value(&Value::OctetString(vec![
+                0x00, 0x1b, 0x21, 0x3c, 0x9d, 0xf8
+            ])),
+            EventValue::from("00:1b:21:3c:9d:f8")
+        );
+    }
+
+    #[test]
+    fn converts_large_counters() {
+        assert_eq!(to_event_value(&Value::Counter64(42)), EventValue::from(42));
+        assert!(matches!(
+            to_event_value(&Value::Counter64(u64::MAX)),
+            EventValue::Float(_)
+        ));
+    }
+}
@@ -0,0 +1,98 @@
+use std::{fmt, str::FromStr};
+
+/// An SNMP object identifier.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Oid(Vec<u32>);
+
+impl Oid {
+    pub fn components(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Returns `true` if `prefix` is a (non-strict) prefix of this OID.
+    pub fn starts_with(&self, prefix: &Oid) -> bool {
+        self.0.starts_with(&prefix.0)
+    }
+
+    /// Returns the components following `prefix`, if this OID starts with it.
+    pub fn suffix(&self, prefix: &Oid) -> Option<&[u32]> {
+        self.0.strip_prefix(prefix.0.as_slice())
+    }
+
+    /// Returns a new OID with `components` appended.
+    pub fn child(&self, components: &[u32]) -> Oid {
+        let mut oid = self.0.clone();
+        oid.extend_from_slice(components);
+        Oid(oid)
+    }
+}
+
+impl From<Vec<u32>> for Oid {
+    fn from(components: Vec<u32>) -> Self {
+        Self(components)
+    }
+}
+
+impl fmt::Display for Oid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, component) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{component}")?;
+        }
+        Ok(())
+    }
+}
+
+/// An error parsing an OID from its dotted decimal representation.
+#[derive(Debug, Eq, PartialEq)]
+pub struct InvalidOid(pub String);
+
+impl fmt::Display for InvalidOid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid object identifier {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidOid {}
+
+impl FromStr for Oid {
+    type Err = InvalidOid;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim().trim_start_matches('.');
+        if trimmed.is_empty() {
+            return Err(InvalidOid(s.to_owned()));
+        }
+        trimmed
+            .split('.')
+            .map(|component| component.parse::<u32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map(Oid)
+            .map_err(|_| InvalidOid(s.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let oid: Oid = ".1.3.6.1.2.1.1.3.0".parse().unwrap();
+        assert_eq!(oid.components(), &[1, 3, 6, 1, 2, 1, 1, 3, 0]);
+        assert_eq!(oid.to_string(), "1.3.6.1.2.1.1.3.0");
+        assert!("1.3.x".parse::<Oid>().is_err());
+        assert!("".parse::<Oid>().is_err());
+    }
+
+    #[test]
+    fn prefixes() {
+        let table: Oid = "1.3.6.1.2.1.2.2.1".parse().unwrap();
+        let oid: Oid = "1.3.6.1.2.1.2.2.1.10.3".parse().unwrap();
+        assert!(oid.starts_with(&table));
+        assert_eq!(oid.suffix(&table), Some(&[10, 3][..]));
+        assert_eq!(table.child(&[10, 3]), oid);
+    }
+}
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use futures_util::{stream::BoxStream, FutureExt, StreamExt};
+use vector_lib::configurable::configurable_component;
+use vector_lib::{
+    config::{AcknowledgementsConfig, Input},
+    event::{EventArray, EventStatus, Finalizable, Rejection},
+    sink::{StreamSink, VectorSink},
+};
+
+use crate::{
+    config::{SinkConfig, SinkContext},
+    sinks::Healthcheck,
+};
+
+/// Configuration for the `test_reject` sink.
+#[configurable_component(sink("test_reject", "Test (reject)."))]
+#[derive(Clone, Debug, Default)]
+pub struct RejectSinkConfig {}
+
+impl_generate_config_from_default!(RejectSinkConfig);
+
+#[async_trait]
+#[typetag::serde(name = "test_reject")]
+impl SinkConfig for RejectSinkConfig {
+    async fn build(&self, _cx: SinkContext) -> crate::Result<(VectorSink, Healthcheck)> {
+        Ok((
+            VectorSink::Stream(Box::new(RejectSink)),
+            futures_util::future::ok(()).boxed(),
+        ))
+    }
+
+    fn input(&self) -> Input {
+        Input::all()
+    }
+
+    fn acknowledgements(&self) -> &AcknowledgementsConfig {
+        &AcknowledgementsConfig::DEFAULT
+    }
+}
+
+/// A sink rejecting all of the events it receives.
+struct RejectSink;
+
+#[async_trait]
+impl StreamSink<EventArray> for RejectSink {
+    async fn run(self: Box<Self>, mut input: BoxStream<'_, EventArray>) -> Result<(), ()> {
+        while let Some(mut events) = input.next().await {
+            let finalizers = events.take_finalizers();
+            finalizers.update_status(EventStatus::Rejected);
+            finalizers.record_rejection(&Rejection::new("Events were rejected by the test sink."));
+        }
+        Ok(())
+    }
+}
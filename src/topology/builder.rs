@@ -34,6 +34,7 @@ use vector_lib::{
 };
 
 use super::{
+    dropped::DroppedTracker,
     fanout::{self, Fanout},
     schema,
     task::{Task, TaskOutput, TaskResult},
@@ -43,6 +44,7 @@ use crate::{
     config::{
         ComponentKey, Config, DataType, EnrichmentTableConfig, Input, Inputs, OutputId,
         ProxyConfig, SinkContext, SourceContext, TransformContext, TransformOuter, TransformOutput,
+        DROPPED_OUTPUT,
     },
     event::{EventArray, EventContainer},
     extra_context::ExtraContext,
@@ -165,7 +167,10 @@ impl<'a> Builder<'a> {
                 let indexes = if !self.diff.enrichment_tables.is_added(name) {
                     // If this is an existing enrichment table, we need to store the indexes to reapply
                     // them again post load.
-                    Some(ENRICHMENT_TABLES.index_fields(&table_name))
+                    Some((
+                        ENRICHMENT_TABLES.index_fields(&table_name),
+                        ENRICHMENT_TABLES.lookup_indexes(&table_name),
+                    ))
                 } else {
                     None
                 };
@@ -179,7 +184,15 @@ impl<'a> Builder<'a> {
                     }
                 };
 
-                if let Some(indexes) = indexes {
+                if let Some((indexes, lookups)) = indexes {
+                    for lookup in lookups {
+                        if let Err(error) = table.add_lookup_index(&lookup) {
+                            error!(message = "Unable to add lookup index to reloaded enrichment table.",
+                                table = ?name.to_string(),
+                                %error);
+                            continue 'tables;
+                        }
+                    }
                     for (case, index) in indexes {
                         match table
                             .add_index(case, &index.iter().map(|s| s.as_ref()).collect::<Vec<_>>())
@@ -551,6 +564,7 @@ impl<'a> Builder<'a> {
 
             let typetag = sink.inner.get_component_name();
             let input_type = sink.inner.input().data_type();
+            let reroute_dropped = sink.reroute_dropped;
 
             let span = error_span!(
                 "sink",
@@ -618,6 +632,22 @@ impl<'a> Builder<'a> {
                 Ok(built) => built,
             };
 
+            // Sinks rerouting the events they fail to deliver get an output, fed by a pump running
+            // alongside the sink.
+            let (tracker, dropped_pump) = if reroute_dropped {
+                let (tracker, pump, control) = DroppedTracker::new(key, typetag);
+                self.outputs.insert(
+                    OutputId {
+                        component: key.clone(),
+                        port: Some(DROPPED_OUTPUT.to_owned()),
+                    },
+                    control,
+                );
+                (Some(tracker), Some(pump.instrument(span.clone())))
+            } else {
+                (None, None)
+            };
+
             let (trigger, tripwire) = Tripwire::new();
 
             let utilization_sender = self
@@ -642,7 +672,7 @@ impl<'a> Builder<'a> {
                 let mut rx = wrap(utilization_sender, component_key.clone(), rx);
 
                 let events_received = register!(EventsReceived);
-                sink.run(
+                let run = sink.run(
                     rx.by_ref()
                         .filter(|events: &EventArray| ready(filter_events_type(events, input_type)))
                         .inspect(|events| {
@@ -651,17 +681,31 @@ impl<'a> Builder<'a> {
                                 events.estimated_json_encoded_size_of(),
                             ))
                         })
+                        .then(move |mut events| {
+                            let tracker = tracker.clone();
+                            async move {
+                                if let Some(tracker) = tracker {
+                                    tracker.track(&mut events).await;
+                                }
+                                events
+                            }
+                        })
                         .take_until_if(tripwire),
-                )
-                .await
-                .map(|_| {
-                    debug!("Sink finished normally.");
-                    TaskOutput::Sink(rx)
-                })
-                .map_err(|_| {
-                    debug!("Sink finished with an error.");
-                    TaskError::Opaque
-                })
+                );
+                let result = match dropped_pump {
+                    // The pump finishes once the sink has released all of the events it received.
+                    Some(pump) => futures::future::join(run, pump).await.0,
+                    None => run.await,
+                };
+                result
+                    .map(|_| {
+                        debug!("Sink finished normally.");
+                        TaskOutput::Sink(rx)
+                    })
+                    .map_err(|_| {
+                        debug!("Sink finished with an error.");
+                        TaskError::Opaque
+                    })
             };
 
             let task = Task::new(key.clone(), typetag, sink);
@@ -0,0 +1,304 @@
+//! Rerouting of the events a sink failed to deliver to its `dropped` output.
+
+use std::{collections::BTreeMap, future::Future, sync::Arc};
+
+use futures::{stream::FuturesUnordered, FutureExt, StreamExt};
+use tokio::sync::mpsc;
+use vector_lib::{
+    config::LogNamespace,
+    event::{
+        BatchNotifier, BatchStatus, Event, EventArray, EventFinalizer, EventMutRef, EventStatus,
+        Finalizable, LogEvent, Metric, ObjectMap, Rejection, RejectionReceiver, TraceEvent, Value,
+    },
+    lookup::{metadata_path, owned_value_path, PathPrefix},
+    schema::Definition,
+};
+use vrl::{path, value::Kind};
+
+use super::fanout::{self, Fanout};
+use crate::config::{log_schema, ComponentKey, OutputId, DROPPED_OUTPUT};
+
+/// The number of tracked events waiting to be picked up by the pump, beyond which the sink stops
+/// receiving events.
+///
+/// The pump stops picking up events while the consumers of the `dropped` output apply
+/// back-pressure, which then propagates to the sink instead of queueing copies of its events.
+const TRACKED_EVENTS_CAPACITY: usize = 1000;
+
+/// The number of events picked up by the pump the sink has yet to finalize, beyond which the pump
+/// stops picking up events, so that the sink stops receiving events until it finalizes some.
+const PENDING_EVENTS_LIMIT: usize = 100_000;
+
+/// Annotates the schema definition of the events entering a sink with the fields describing why
+/// they were dropped, giving the schema definition of its `dropped` output.
+pub(super) fn definition(definition: Definition) -> Definition {
+    let fields = Kind::object(BTreeMap::from([
+        ("reason".into(), Kind::bytes()),
+        ("message".into(), Kind::bytes()),
+        ("status".into(), Kind::bytes().or_undefined()),
+        ("response".into(), Kind::bytes().or_undefined()),
+        ("component_id".into(), Kind::bytes()),
+        ("component_type".into(), Kind::bytes()),
+        ("component_kind".into(), Kind::bytes()),
+    ]));
+
+    Definition::combine_log_namespaces(
+        definition.log_namespaces(),
+        definition.clone().with_event_field(
+            &log_schema()
+                .metadata_key()
+                .expect("valid metadata key")
+                .concat(path!("dropped")),
+            fields.clone(),
+            None,
+        ),
+        definition.clone().with_metadata_field(
+            &owned_value_path!("vector", "dropped"),
+            fields,
+            None,
+        ),
+    )
+}
+
+/// Tracks the events entering a sink, so that the events it fails to deliver are forwarded to its
+/// `dropped` output.
+///
+/// Each tracked event is given its own batch notifier, while a copy of the event keeps its
+/// original finalizers. Once the sink is done with the event, the copy is either discarded, if it
+/// was delivered, or annotated with the details of the failure and sent to the `dropped` output, if
+/// it was rejected. The acknowledgement of rerouted events is left to the components consuming the
+/// `dropped` output.
+///
+/// The copies of log and trace events share their data with the tracked events, which is only
+/// duplicated if the sink modifies the events, so that delivering events doesn't copy them.
+#[derive(Clone)]
+pub(super) struct DroppedTracker {
+    sender: mpsc::Sender<(Event, RejectionReceiver)>,
+}
+
+impl DroppedTracker {
+    /// Creates a tracker for the given sink, along with the future forwarding the rejected events
+    /// and the control channel of the fanout of the `dropped` output.
+    pub(super) fn new(
+        key: &ComponentKey,
+        component_type: &'static str,
+    ) -> (
+        Self,
+        impl Future<Output = ()> + Send + 'static,
+        fanout::ControlChannel,
+    ) {
+        let (sender, receiver) = mpsc::channel(TRACKED_EVENTS_CAPACITY);
+        let (fanout, control) = Fanout::new();
+        let pump = DroppedPump {
+            receiver,
+            fanout,
+            output_id: Arc::new(OutputId {
+                component: key.clone(),
+                port: Some(DROPPED_OUTPUT.to_owned()),
+            }),
+            component_id: key.id().to_owned(),
+            component_type,
+        };
+        (Self { sender }, pump.run(), control)
+    }
+
+    /// Tracks the events entering the sink, waiting for the pump to catch up when too many events
+    /// are waiting to be tracked.
+    pub(super) async fn track(&self, events: &mut EventArray) {
+        for mut event in events.iter_events_mut() {
+            // Log and trace events are copied on write, so the copy only holds a reference to the
+            // data of the event until either is modified.
+            let copy = match event {
+                EventMutRef::Log(ref log) => Event::from(LogEvent::clone(log)),
+                EventMutRef::Metric(ref metric) => Event::from(Metric::clone(metric)),
+                EventMutRef::Trace(ref trace) => Event::from(TraceEvent::clone(trace)),
+            };
+            let (batch, receiver) = BatchNotifier::new_with_rejection_receiver();
+            let metadata = event.metadata_mut();
+            drop(metadata.take_finalizers());
+            metadata.add_finalizer(EventFinalizer::new(batch));
+            // The pump only stops once the tracker is dropped.
+            _ = self.sender.send((copy, receiver)).await;
+        }
+    }
+}
+
+struct DroppedPump {
+    receiver: mpsc::Receiver<(Event, RejectionReceiver)>,
+    fanout: Fanout,
+    output_id: Arc<OutputId>,
+    component_id: String,
+    component_type: &'static str,
+}
+
+impl DroppedPump {
+    async fn run(mut self) {
+        debug!("Dropped events pump starting.");
+
+        let mut pending = FuturesUnordered::new();
+        loop {
+            tokio::select! {
+                Some((event, receiver)) = self.receiver.recv(), if pending.len() < PENDING_EVENTS_LIMIT => {
+                    pending.push(receiver.recv().map(move |result| (event, result)));
+                }
+                Some((mut event, (status, rejection))) = pending.next(), if !pending.is_empty() => {
+                    match status {
+                        BatchStatus::Delivered => {}
+                        BatchStatus::Errored => {
+                            event.take_finalizers().update_status(EventStatus::Errored);
+                        }
+                        BatchStatus::Rejected => {
+                            self.annotate(&mut event, rejection.unwrap_or_else(|| {
+                                Rejection::new("Events were rejected by the sink.")
+                            }));
+                            event.metadata_mut().set_upstream_id(Arc::clone(&self.output_id));
+                            if let Err(error) = self.fanout.send(event.into(), None).await {
+                                debug!(message = "Dropped events pump finished with an error.", %error);
+                                return;
+                            }
+                        }
+                    }
+                }
+                else => break,
+            }
+        }
+
+        debug!("Dropped events pump finished normally.");
+    }
+
+    fn dropped_data(&self, rejection: Rejection) -> Value {
+        let mut data = ObjectMap::new();
+        data.insert("reason".into(), "rejected".into());
+        data.insert("message".into(), rejection.message.into());
+        if let Some(status) = rejection.status {
+            data.insert("status".into(), status.into());
+        }
+        if let Some(response) = rejection.response {
+            data.insert("response".into(), response.into());
+        }
+        data.insert("component_id".into(), self.component_id.clone().into());
+        data.insert("component_type".into(), self.component_type.into());
+        data.insert("component_kind".into(), "sink".into());
+        Value::Object(data)
+    }
+
+    fn annotate(&self, event: &mut Event, rejection: Rejection) {
+        match event {
+            Event::Log(log) => match log.namespace() {
+                LogNamespace::Legacy => {
+                    if let Some(metadata_key) = log_schema().metadata_key() {
+                        log.insert(
+                            (PathPrefix::Event, metadata_key.concat(path!("dropped"))),
+                            self.dropped_data(rejection),
+                        );
+                    }
+                }
+                LogNamespace::Vector => {
+                    log.insert(
+                        metadata_path!("vector", "dropped"),
+                        self.dropped_data(rejection),
+                    );
+                }
+            },
+            Event::Metric(metric) => {
+                if let Some(metadata_key) = log_schema().metadata_key() {
+                    let tags = [
+                        ("reason", Some("rejected".to_owned())),
+                        ("message", Some(rejection.message)),
+                        ("status", rejection.status),
+                        ("component_id", Some(self.component_id.clone())),
+                        ("component_type", Some(self.component_type.to_owned())),
+                        ("component_kind", Some("sink".to_owned())),
+                    ];
+                    for (name, value) in tags {
+                        if let Some(value) = value {
+                            metric.replace_tag(format!("{metadata_key}.dropped.{name}"), value);
+                        }
+                    }
+                }
+            }
+            Event::Trace(trace) => {
+                trace.maybe_insert(log_schema().metadata_key_target_path(), || {
+                    self.dropped_data(rejection)
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use tracing::Span;
+    use vector_lib::{
+        buffers::{topology::builder::TopologyBuilder, WhenFull},
+        event::EventContainer,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reroutes_rejected_events() {
+        let (tracker, pump, control) = DroppedTracker::new(&ComponentKey::from("out"), "http");
+        let (tx, rx) = TopologyBuilder::standalone_memory(
+            NonZeroUsize::new(10).unwrap(),
+            WhenFull::Block,
+            &Span::current(),
+        )
+        .await;
+        control
+            .send(fanout::ControlMessage::Add("dead_letter".into(), tx))
+            .unwrap();
+
+        let (batch, mut receiver) = BatchNotifier::new_with_receiver();
+        let mut delivered =
+            EventArray::from(LogEvent::from("delivered").with_batch_notifier(&batch));
+        let mut rejected = EventArray::from(LogEvent::from("rejected").with_batch_notifier(&batch));
+        drop(batch);
+        tracker.track(&mut delivered).await;
+        tracker.track(&mut rejected).await;
+        drop(tracker);
+
+        delivered
+            .take_finalizers()
+            .update_status(EventStatus::Delivered);
+        let finalizers = rejected.take_finalizers();
+        finalizers.update_status(EventStatus::Rejected);
+        finalizers.record_rejection(&Rejection {
+            status: Some("400".into()),
+            ..Rejection::new("Request was rejected with status 400 Bad Request.")
+        });
+        drop(finalizers);
+        pump.await;
+
+        let mut arrays = rx.into_stream().collect::<Vec<_>>().await;
+        assert_eq!(arrays.len(), 1);
+        let log = arrays.remove(0).into_events().next().unwrap().into_log();
+        assert_eq!(log["message"], "rejected".into());
+        assert_eq!(log["metadata.dropped.reason"], "rejected".into());
+        assert_eq!(log["metadata.dropped.status"], "400".into());
+        assert_eq!(log["metadata.dropped.component_id"], "out".into());
+        assert_eq!(log["metadata.dropped.component_kind"], "sink".into());
+
+        // The source is only acknowledged once the rerouted event is.
+        assert!(receiver.try_recv().is_err());
+        drop(log);
+        assert_eq!(receiver.try_recv(), Ok(BatchStatus::Delivered));
+    }
+
+    #[tokio::test]
+    async fn shares_the_data_of_tracked_events() {
+        let (sender, mut receiver) = mpsc::channel(1);
+        let tracker = DroppedTracker { sender };
+
+        let mut events = EventArray::from(LogEvent::from("tracked"));
+        tracker.track(&mut events).await;
+        let (copy, _) = receiver.recv().await.unwrap();
+        let tracked = events.into_events().next().unwrap();
+        assert!(std::ptr::eq(
+            copy.as_log().value(),
+            tracked.as_log().value()
+        ));
+    }
+}
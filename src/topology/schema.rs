@@ -7,7 +7,7 @@ pub(super) use crate::schema::Definition;
 
 use crate::{
     config::{ComponentKey, Config, OutputId, SinkOuter, TransformOutput},
-    topology,
+    topology::{self, dropped},
 };
 
 #[derive(Debug, Snafu)]
@@ -90,6 +90,21 @@ pub fn possible_definitions(
 
             definitions.append(&mut transform_definition);
         }
+
+        // If the input is the `dropped` output of a sink, the events entering the sink are
+        // annotated and merged into the top-level schema.
+        if let Some(inputs) = config.sink_dropped_inputs(key) {
+            let input_definitions =
+                possible_definitions(inputs, config, enrichment_tables.clone(), cache)?;
+
+            definitions.append(
+                &mut input.with_definitions(
+                    input_definitions
+                        .into_iter()
+                        .map(|(_, definition)| dropped::definition(definition)),
+                ),
+            );
+        }
     }
 
     Ok(definitions)
@@ -193,6 +208,19 @@ pub(super) fn expanded_definitions(
             // Append whatever number of additional pipelines we created to the existing
             // pipeline definitions.
             definitions.append(&mut transform_definition);
+
+        // The `dropped` output of a sink carries the annotated events entering the sink.
+        } else if let Some(inputs) = config.sink_dropped_inputs(key) {
+            let input_definitions =
+                possible_definitions(inputs, config, enrichment_tables.clone(), &mut merged_cache)?;
+
+            definitions.append(
+                &mut input.with_definitions(
+                    input_definitions
+                        .into_iter()
+                        .map(|(_, definition)| dropped::definition(definition)),
+                ),
+            );
         }
     }
 
@@ -283,6 +311,21 @@ pub(crate) fn input_definitions(
 
             definitions.append(&mut transform_definitions);
         }
+
+        // If the input is the `dropped` output of a sink we recurse to the inputs of the sink,
+        // whose events are forwarded with annotations.
+        if let Some(inputs) = config.sink_dropped_inputs(key) {
+            let sink_definitions =
+                input_definitions(inputs, config, enrichment_tables.clone(), cache)?;
+
+            definitions.append(
+                &mut input.with_definitions(
+                    sink_definitions
+                        .into_iter()
+                        .map(|(_, definition)| dropped::definition(definition)),
+                ),
+            );
+        }
     }
 
     Ok(definitions)
@@ -356,6 +399,12 @@ pub trait ComponentContainer {
         input_definitions: &[(OutputId, Definition)],
     ) -> Option<Vec<TransformOutput>>;
 
+    /// Gets the inputs of the sink with the given key, if it reroutes the events it fails to
+    /// deliver to its `dropped` output.
+    fn sink_dropped_inputs(&self, _key: &ComponentKey) -> Option<&[OutputId]> {
+        None
+    }
+
     /// Gets the transform output for the given port.
     ///
     /// Returns Err(()) if there is no transform with the given key
@@ -435,6 +484,12 @@ impl ComponentContainer for Config {
             )
         })
     }
+
+    fn sink_dropped_inputs(&self, key: &ComponentKey) -> Option<&[OutputId]> {
+        self.sink(key)
+            .filter(|sink| sink.reroute_dropped)
+            .map(|sink| &sink.inputs[..])
+    }
 }
 
 #[cfg(test)]
@@ -14,7 +14,7 @@ use crate::{
         mock::{
             basic_sink, basic_sink_failing_healthcheck, basic_sink_with_data, basic_source,
             basic_source_with_data, basic_source_with_event_counter, basic_transform,
-            error_definition_transform,
+            error_definition_transform, reject_sink,
         },
         start_topology, trace_init,
     },
@@ -165,6 +165,49 @@ async fn topology_source_and_sink() {
     assert_eq!(vec![event], res);
 }
 
+#[tokio::test]
+async fn topology_reroutes_rejected_events() {
+    trace_init();
+
+    let (mut in1, source1) = basic_source();
+    let (out1, sink1) = basic_sink(10);
+
+    let mut rejecting = SinkOuter::new(["in1".to_owned()], reject_sink());
+    rejecting.reroute_dropped = true;
+
+    let mut config = Config::builder();
+    config.add_source("in1", source1);
+    config.add_sink_outer("rejecting", rejecting);
+    config.add_sink("dead_letter", &["rejecting.dropped"], sink1);
+
+    let (topology, _) = start_topology(config.build().unwrap(), false).await;
+
+    in1.send_event(Event::Log(LogEvent::from("this")))
+        .await
+        .unwrap();
+
+    topology.stop().await;
+
+    let res = out1.flat_map(into_event_stream).collect::<Vec<_>>().await;
+    assert_eq!(res.len(), 1);
+    let log = res[0].as_log();
+    assert_eq!(log["message"], "this".into());
+    assert_eq!(log["metadata.dropped.reason"], "rejected".into());
+    assert_eq!(
+        log["metadata.dropped.message"],
+        "Events were rejected by the test sink.".into()
+    );
+    assert_eq!(log["metadata.dropped.component_id"], "rejecting".into());
+    assert_eq!(log["metadata.dropped.component_type"], "test_reject".into());
+    assert_eq!(
+        res[0].metadata().upstream_id(),
+        Some(&OutputId::from((
+            &ComponentKey::from("rejecting"),
+            "dropped".to_owned()
+        )))
+    );
+}
+
 #[tokio::test]
 async fn topology_multiple_sources() {
     trace_init();
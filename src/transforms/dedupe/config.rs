@@ -44,11 +44,19 @@ impl GenerateConfig for DedupeConfig {
 #[async_trait::async_trait]
 #[typetag::serde(name = "dedupe")]
 impl TransformConfig for DedupeConfig {
-    async fn build(&self, _context: &TransformContext) -> crate::Result<Transform> {
-        Ok(Transform::event_task(Dedupe::new(
-            self.cache.num_events,
-            fill_default_fields_match(self.fields.as_ref()),
-        )))
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        let mut dedupe = Dedupe::new(&self.cache, fill_default_fields_match(self.fields.as_ref()));
+        if let Some(persistence) = &self.cache.persistence {
+            let key = context
+                .key
+                .as_ref()
+                .ok_or("Persisting the dedupe cache requires a component key")?;
+            dedupe = dedupe.persisted(
+                persistence.snapshot_path(&context.globals, key.id(), "dedupe_cache")?,
+                persistence.snapshot_interval(),
+            );
+        }
+        Ok(Transform::event_task(dedupe))
     }
 
     fn input(&self) -> Input {
@@ -83,7 +91,10 @@ mod tests {
         event::{Event, LogEvent, ObjectMap, Value},
         test_util::components::assert_transform_compliance,
         transforms::{
-            dedupe::config::{CacheConfig, DedupeConfig, FieldMatchConfig},
+            dedupe::{
+                common::default_cache_config,
+                config::{CacheConfig, DedupeConfig, FieldMatchConfig},
+            },
             test::create_topology,
         },
     };
@@ -93,13 +104,14 @@ mod tests {
         crate::test_util::test_generate_config::<DedupeConfig>();
     }
 
-    const fn make_match_transform_config(
+    fn make_match_transform_config(
         num_events: usize,
         fields: Vec<ConfigTargetPath>,
     ) -> DedupeConfig {
         DedupeConfig {
             cache: CacheConfig {
                 num_events: std::num::NonZeroUsize::new(num_events).expect("non-zero num_events"),
+                ..default_cache_config()
             },
             fields: Some(FieldMatchConfig::MatchFields(fields)),
         }
@@ -116,6 +128,7 @@ mod tests {
         DedupeConfig {
             cache: CacheConfig {
                 num_events: std::num::NonZeroUsize::new(num_events).expect("non-zero num_events"),
+                ..default_cache_config()
             },
             fields: Some(FieldMatchConfig::IgnoreFields(fields)),
         }
@@ -6,11 +6,11 @@ pub mod transform;
 
 #[cfg(feature = "transforms-impl-dedupe")]
 pub mod common {
-    use std::num::NonZeroUsize;
+    use std::num::{NonZeroU64, NonZeroUsize};
 
     use vector_lib::{configurable::configurable_component, lookup::lookup_v2::ConfigTargetPath};
 
-    use crate::config::log_schema;
+    use crate::{common::persistence::PersistenceConfig, config::log_schema};
 
     /// Caching configuration for deduplication.
     #[configurable_component]
@@ -19,11 +19,52 @@ pub mod common {
     pub struct CacheConfig {
         /// Number of events to cache and use for comparing incoming events to previously seen events.
         pub num_events: NonZeroUsize,
+
+        /// The time-to-live of cached events, in seconds.
+        ///
+        /// When set, events are only dropped if a duplicate was seen within this period. Seeing a
+        /// duplicate again restarts the period.
+        ///
+        /// By default, events stay cached until they are evicted by newer events.
+        #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
+        #[configurable(metadata(docs::examples = 600))]
+        #[configurable(metadata(docs::human_name = "Time-to-live"))]
+        pub ttl_secs: Option<NonZeroU64>,
+
+        /// The maximum size of the cache, in bytes.
+        ///
+        /// The size of the cache is estimated from the size of the matched field values. The least
+        /// recently seen events are evicted once the cache grows over this size.
+        ///
+        /// By default, the cache is only bounded by `num_events`.
+        #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
+        #[configurable(metadata(docs::examples = 10485760))]
+        pub max_bytes: Option<NonZeroUsize>,
+
+        /// Whether to cache a 64-bit fingerprint of the matched fields instead of their values.
+        ///
+        /// This keeps the cache small regardless of the size of the matched fields, at the cost of
+        /// a negligible chance of two different events being considered duplicates.
+        #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
+        pub fingerprint: bool,
+
+        /// Configuration for persisting the cache across restarts.
+        ///
+        /// When set, snapshots of the cache are written to the data directory, and the cache is
+        /// restored from its latest snapshot on startup, so that events redelivered after a restart
+        /// are still deduplicated. Persisted caches always hold fingerprints of the matched fields.
+        #[configurable(derived)]
+        #[serde(default, skip_serializing_if = "vector_lib::serde::is_default")]
+        pub persistence: Option<PersistenceConfig>,
     }
 
     pub const fn default_cache_config() -> CacheConfig {
         CacheConfig {
             num_events: NonZeroUsize::new(5000).expect("static non-zero number"),
+            ttl_secs: None,
+            max_bytes: None,
+            fingerprint: false,
+            persistence: None,
         }
     }
 
@@ -1,23 +1,56 @@
-use std::{future::ready, num::NonZeroUsize, pin::Pin};
+use std::{
+    fs,
+    future::ready,
+    hash::Hasher,
+    io::Write,
+    mem::size_of,
+    path::{Path, PathBuf},
+    pin::Pin,
+    time::{Duration, Instant},
+};
 
 use bytes::Bytes;
 use futures::{Stream, StreamExt};
 use lru::LruCache;
+use seahash::SeaHasher;
 use vector_lib::lookup::lookup_v2::ConfigTargetPath;
 use vrl::path::OwnedTargetPath;
 
 use crate::{
+    common::persistence::{restore_snapshot, with_snapshots, write_snapshot, SnapshotStep},
     event::{Event, Value},
-    internal_events::DedupeEventsDropped,
+    internal_events::{DedupeCacheSnapshotError, DedupeEventsDropped},
     transforms::TaskTransform,
 };
 
-use super::common::FieldMatchConfig;
+use super::common::{CacheConfig, FieldMatchConfig};
+
+/// The size of a snapshot record: the fingerprint of an entry followed by its age in milliseconds.
+const SNAPSHOT_RECORD_SIZE: usize = 16;
 
 #[derive(Clone)]
 pub struct Dedupe {
     fields: FieldMatchConfig,
-    cache: LruCache<CacheEntry, bool>,
+    cache: LruCache<CacheEntry, Seen>,
+    ttl: Option<Duration>,
+    max_bytes: Option<usize>,
+    byte_size: usize,
+    fingerprint: bool,
+    snapshot: Option<Snapshot>,
+}
+
+/// When a cached event was last seen, and the estimated size of its entry.
+#[derive(Clone, Copy)]
+struct Seen {
+    at: Instant,
+    byte_size: usize,
+}
+
+/// Where and how often the cache is persisted.
+#[derive(Clone)]
+struct Snapshot {
+    path: PathBuf,
+    interval: Duration,
 }
 
 type TypeId = u8;
@@ -45,10 +78,75 @@ type TypeId = u8;
 /// iterating over the fields of the incoming Events, we know that the
 /// CacheEntries for 2 equivalent events will always contain the fields in the
 /// same order.
+///
+/// When fingerprinting is enabled, either form is replaced by a stable 64-bit
+/// hash of its contents, which is also the form persisted in snapshots.
 #[derive(Clone, PartialEq, Eq, Hash)]
 enum CacheEntry {
     Match(Vec<Option<(TypeId, Bytes)>>),
     Ignore(Vec<(OwnedTargetPath, TypeId, Bytes)>),
+    Fingerprint(u64),
+}
+
+impl CacheEntry {
+    /// Hashes the entry into a fingerprint that is stable across restarts, by feeding each of its
+    /// parts to the hasher with an explicit tag or length.
+    fn fingerprint(&self) -> u64 {
+        fn write_bytes(hasher: &mut SeaHasher, bytes: &[u8]) {
+            hasher.write(&(bytes.len() as u64).to_le_bytes());
+            hasher.write(bytes);
+        }
+
+        let mut hasher = SeaHasher::new();
+        match self {
+            Self::Match(values) => {
+                hasher.write_u8(0);
+                for value in values {
+                    match value {
+                        Some((type_id, bytes)) => {
+                            hasher.write_u8(1);
+                            hasher.write_u8(*type_id);
+                            write_bytes(&mut hasher, bytes);
+                        }
+                        None => hasher.write_u8(0),
+                    }
+                }
+            }
+            Self::Ignore(values) => {
+                hasher.write_u8(1);
+                for (path, type_id, bytes) in values {
+                    write_bytes(&mut hasher, path.to_string().as_bytes());
+                    hasher.write_u8(*type_id);
+                    write_bytes(&mut hasher, bytes);
+                }
+            }
+            Self::Fingerprint(fingerprint) => return *fingerprint,
+        }
+        hasher.finish()
+    }
+
+    /// Estimates the memory used by the entry.
+    fn byte_size(&self) -> usize {
+        size_of::<Self>()
+            + match self {
+                Self::Match(values) => values
+                    .iter()
+                    .map(|value| {
+                        size_of::<Option<(TypeId, Bytes)>>()
+                            + value.as_ref().map_or(0, |(_, bytes)| bytes.len())
+                    })
+                    .sum(),
+                Self::Ignore(values) => values
+                    .iter()
+                    .map(|(path, _, bytes)| {
+                        size_of::<(OwnedTargetPath, TypeId, Bytes)>()
+                            + path.to_string().len()
+                            + bytes.len()
+                    })
+                    .sum(),
+                Self::Fingerprint(_) => 0,
+            }
+    }
 }
 
 /// Assigns a unique number to each of the types supported by Event::Value.
@@ -67,22 +165,138 @@ const fn type_id_for_value(val: &Value) -> TypeId {
 }
 
 impl Dedupe {
-    pub fn new(num_entries: NonZeroUsize, fields: FieldMatchConfig) -> Self {
+    pub fn new(cache: &CacheConfig, fields: FieldMatchConfig) -> Self {
         Self {
             fields,
-            cache: LruCache::new(num_entries),
+            cache: LruCache::new(cache.num_events),
+            ttl: cache.ttl_secs.map(|ttl| Duration::from_secs(ttl.get())),
+            max_bytes: cache.max_bytes.map(|max_bytes| max_bytes.get()),
+            byte_size: 0,
+            fingerprint: cache.fingerprint || cache.persistence.is_some(),
+            snapshot: None,
         }
     }
 
+    /// Persists the cache to `path` every `interval`, restoring it from the latest snapshot
+    /// written there, if any.
+    ///
+    /// A snapshot that can't be restored is moved aside, and the cache starts empty.
+    pub fn persisted(mut self, path: PathBuf, interval: Duration) -> Self {
+        restore_snapshot(&path, |path| self.restore(path));
+        self.snapshot = Some(Snapshot { path, interval });
+        self
+    }
+
     pub fn transform_one(&mut self, event: Event) -> Option<Event> {
-        let cache_entry = build_cache_entry(&event, &self.fields);
-        if self.cache.put(cache_entry, true).is_some() {
+        let mut cache_entry = build_cache_entry(&event, &self.fields);
+        if self.fingerprint {
+            cache_entry = CacheEntry::Fingerprint(cache_entry.fingerprint());
+        }
+
+        let now = Instant::now();
+        self.evict_expired(now);
+        if let Some(seen) = self.cache.get_mut(&cache_entry) {
+            seen.at = now;
             emit!(DedupeEventsDropped { count: 1 });
             None
         } else {
+            self.insert(cache_entry, now);
             Some(event)
         }
     }
+
+    fn insert(&mut self, cache_entry: CacheEntry, at: Instant) {
+        let byte_size = if self.max_bytes.is_some() {
+            cache_entry.byte_size()
+        } else {
+            0
+        };
+        if let Some((_, evicted)) = self.cache.push(cache_entry, Seen { at, byte_size }) {
+            self.byte_size -= evicted.byte_size;
+        }
+        self.byte_size += byte_size;
+
+        if let Some(max_bytes) = self.max_bytes {
+            while self.byte_size > max_bytes {
+                let Some((_, evicted)) = self.cache.pop_lru() else {
+                    break;
+                };
+                self.byte_size -= evicted.byte_size;
+            }
+        }
+    }
+
+    /// Evicts the entries seen longer than the TTL ago. As seeing an entry makes it the most
+    /// recently used one, these are always the least recently used entries.
+    fn evict_expired(&mut self, now: Instant) {
+        let Some(ttl) = self.ttl else {
+            return;
+        };
+        while self
+            .cache
+            .peek_lru()
+            .is_some_and(|(_, seen)| now.duration_since(seen.at) >= ttl)
+        {
+            if let Some((_, evicted)) = self.cache.pop_lru() {
+                self.byte_size -= evicted.byte_size;
+            }
+        }
+    }
+
+    /// Restores the cache from a snapshot, skipping the entries that expired since.
+    ///
+    /// The cache is left untouched when the snapshot can't be read.
+    fn restore(&mut self, path: &Path) -> crate::Result<()> {
+        let data = fs::read(path)?;
+        if data.len() % SNAPSHOT_RECORD_SIZE != 0 {
+            return Err(format!("Truncated dedupe cache snapshot {}", path.display()).into());
+        }
+
+        let now = Instant::now();
+        for record in data.chunks_exact(SNAPSHOT_RECORD_SIZE) {
+            let (fingerprint, age) = record.split_at(8);
+            let fingerprint = u64::from_le_bytes(fingerprint.try_into().expect("8 bytes"));
+            let age = Duration::from_millis(u64::from_le_bytes(age.try_into().expect("8 bytes")));
+            if self.ttl.is_some_and(|ttl| age >= ttl) {
+                continue;
+            }
+            self.insert(
+                CacheEntry::Fingerprint(fingerprint),
+                now.checked_sub(age).unwrap_or(now),
+            );
+        }
+        Ok(())
+    }
+
+    /// Writes a snapshot of the cache, if it is persisted.
+    fn write_snapshot(&self) {
+        let Some(snapshot) = &self.snapshot else {
+            return;
+        };
+        if let Err(error) = self.try_write_snapshot(&snapshot.path) {
+            emit!(DedupeCacheSnapshotError {
+                error,
+                path: &snapshot.path,
+            });
+        }
+    }
+
+    /// Writes the fingerprints of the cache with their age, from the least to the most recently
+    /// seen.
+    fn try_write_snapshot(&self, path: &Path) -> std::io::Result<()> {
+        write_snapshot(path, |file| {
+            let now = Instant::now();
+            for (entry, seen) in self.cache.iter().rev() {
+                let CacheEntry::Fingerprint(fingerprint) = entry else {
+                    continue;
+                };
+                let age = now.saturating_duration_since(seen.at).as_millis();
+                file.write_all(&fingerprint.to_le_bytes())?;
+                file.write_all(&u64::try_from(age).unwrap_or(u64::MAX).to_le_bytes())?;
+            }
+            Ok(())
+        })
+    }
 }
 
 /// Takes in an Event and returns a CacheEntry to place into the LRU cache
@@ -134,6 +348,149 @@ impl TaskTransform<Event> for Dedupe {
         Self: 'static,
     {
         let mut inner = self;
-        Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))))
+        let Some(snapshot_interval) = inner.snapshot.as_ref().map(|snapshot| snapshot.interval)
+        else {
+            return Box::pin(task.filter_map(move |v| ready(inner.transform_one(v))));
+        };
+
+        Box::pin(
+            with_snapshots(task, snapshot_interval).filter_map(move |step| {
+                ready(match step {
+                    SnapshotStep::Input(event) => inner.transform_one(event),
+                    SnapshotStep::Snapshot => {
+                        inner.write_snapshot();
+                        None
+                    }
+                })
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::{NonZeroU64, NonZeroUsize};
+
+    use super::*;
+    use crate::{
+        common::persistence::PersistenceConfig, event::LogEvent,
+        transforms::dedupe::common::default_cache_config,
+    };
+
+    fn event(value: &str) -> Event {
+        let mut event = Event::Log(LogEvent::from("message"));
+        event.as_mut_log().insert("matched", value);
+        event
+    }
+
+    fn dedupe(cache: CacheConfig) -> Dedupe {
+        Dedupe::new(
+            &cache,
+            FieldMatchConfig::MatchFields(vec!["matched".into()]),
+        )
+    }
+
+    #[test]
+    fn fingerprints_are_stable() {
+        let entry = CacheEntry::Match(vec![Some((0, Bytes::from("value"))), None]);
+        let same = CacheEntry::Match(vec![Some((0, Bytes::from("value"))), None]);
+        let different = CacheEntry::Match(vec![None, Some((0, Bytes::from("value")))]);
+
+        assert_eq!(entry.fingerprint(), same.fingerprint());
+        assert_ne!(entry.fingerprint(), different.fingerprint());
+        assert_eq!(
+            CacheEntry::Fingerprint(42).fingerprint(),
+            CacheEntry::Fingerprint(42).fingerprint()
+        );
+    }
+
+    #[test]
+    fn drops_duplicates_within_ttl() {
+        let mut dedupe = dedupe(CacheConfig {
+            ttl_secs: NonZeroU64::new(60),
+            ..default_cache_config()
+        });
+
+        assert!(dedupe.transform_one(event("a")).is_some());
+        assert!(dedupe.transform_one(event("a")).is_none());
+
+        // Entries seen longer than the TTL ago are evicted.
+        for (_, seen) in dedupe.cache.iter_mut() {
+            seen.at = seen.at.checked_sub(Duration::from_secs(61)).unwrap();
+        }
+        assert!(dedupe.transform_one(event("a")).is_some());
+        assert_eq!(1, dedupe.cache.len());
+    }
+
+    #[test]
+    fn evicts_entries_over_max_bytes() {
+        let entry_size = CacheEntry::Match(vec![Some((0, Bytes::from("a")))]).byte_size();
+        let mut dedupe = dedupe(CacheConfig {
+            max_bytes: NonZeroUsize::new(entry_size * 2),
+            ..default_cache_config()
+        });
+
+        assert!(dedupe.transform_one(event("a")).is_some());
+        assert!(dedupe.transform_one(event("b")).is_some());
+        assert!(dedupe.transform_one(event("c")).is_some());
+        assert_eq!(2, dedupe.cache.len());
+        assert_eq!(entry_size * 2, dedupe.byte_size);
+
+        // The least recently seen event was evicted.
+        assert!(dedupe.transform_one(event("a")).is_some());
+        assert!(dedupe.transform_one(event("c")).is_none());
+    }
+
+    #[test]
+    fn restores_cache_from_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dedupe_cache");
+        let cache = CacheConfig {
+            persistence: Some(PersistenceConfig::default()),
+            ..default_cache_config()
+        };
+
+        let mut first = dedupe(cache.clone()).persisted(path.clone(), Duration::from_secs(60));
+        assert!(first.transform_one(event("a")).is_some());
+        assert!(first.transform_one(event("b")).is_some());
+        first.write_snapshot();
+        assert_eq!(
+            2 * SNAPSHOT_RECORD_SIZE,
+            fs::metadata(&path).unwrap().len() as usize
+        );
+
+        let mut restored = dedupe(cache).persisted(path, Duration::from_secs(60));
+        assert!(restored.transform_one(event("a")).is_none());
+        assert!(restored.transform_one(event("b")).is_none());
+        assert!(restored.transform_one(event("c")).is_some());
+    }
+
+    #[test]
+    fn discards_truncated_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dedupe_cache");
+        let mut snapshot = 42u64.to_le_bytes().to_vec();
+        snapshot.extend_from_slice(&[0; 3]);
+        fs::write(&path, &snapshot).unwrap();
+
+        let mut dedupe = dedupe(CacheConfig {
+            persistence: Some(PersistenceConfig::default()),
+            ..default_cache_config()
+        })
+        .persisted(path.clone(), Duration::from_secs(60));
+        assert_eq!(0, dedupe.cache.len());
+        assert!(!path.exists());
+        assert_eq!(
+            snapshot,
+            fs::read(dir.path().join("dedupe_cache.corrupt")).unwrap()
+        );
+
+        // The next snapshot is written in place of the discarded one.
+        assert!(dedupe.transform_one(event("a")).is_some());
+        dedupe.write_snapshot();
+        assert_eq!(
+            SNAPSHOT_RECORD_SIZE,
+            fs::metadata(&path).unwrap().len() as usize
+        );
     }
 }
@@ -0,0 +1,241 @@
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::time::Duration;
+
+use vector_lib::config::LogNamespace;
+use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::{lookup_v2::ConfigTargetPath, PathPrefix};
+use vrl::value::kind::{Collection, Kind};
+
+use crate::{
+    common::persistence::PersistenceConfig,
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    schema,
+    transforms::Transform,
+};
+
+use super::{drain::Drain, transform::LogClustering};
+
+/// Configuration for the `log_clustering` transform.
+#[configurable_component(transform(
+    "log_clustering",
+    "Learn the templates of log messages and annotate events with their pattern."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LogClusteringConfig {
+    /// The field holding the message to cluster.
+    ///
+    /// By default, the message of the event is used. Events without a string in this field are
+    /// forwarded without a pattern.
+    #[configurable(metadata(docs::examples = "message"))]
+    pub field: Option<ConfigTargetPath>,
+
+    /// The field the pattern of the message is written to.
+    ///
+    /// The pattern is an object with the `id` of the pattern, its `template`, in which the variable
+    /// tokens are replaced with `<*>`, and the `variables` of the message, the tokens matching the
+    /// `<*>` of the template. The ID of a pattern is derived from the first message of the pattern,
+    /// and stays the same as the template is generalized.
+    #[serde(default = "default_target_field")]
+    #[configurable(metadata(docs::examples = "pattern"))]
+    pub target_field: ConfigTargetPath,
+
+    /// The depth of the parse tree, which routes messages by their first `depth - 2` tokens.
+    ///
+    /// Must be at least 3.
+    #[serde(default = "default_depth")]
+    pub depth: usize,
+
+    /// The share of tokens a message must have in common with a template to match it.
+    #[serde(default = "default_similarity_threshold")]
+    #[configurable(validation(range(min = 0.0, max = 1.0)))]
+    pub similarity_threshold: f64,
+
+    /// The maximum number of children of a node of the parse tree.
+    ///
+    /// Tokens of nodes with this many children are routed to the wildcard child.
+    #[serde(default = "default_max_children")]
+    pub max_children: NonZeroUsize,
+
+    /// The maximum number of patterns kept.
+    ///
+    /// When this limit is reached, the least recently matched pattern is forgotten.
+    #[serde(default = "default_max_clusters")]
+    pub max_clusters: NonZeroUsize,
+
+    /// The interval between the emission of pattern counts, in seconds.
+    ///
+    /// When set, an incremental `log_pattern_events` counter is emitted for each pattern matched
+    /// during the interval, tagged with the `pattern_id` and `template` of the pattern, alongside the
+    /// log events.
+    #[configurable(metadata(docs::human_name = "Metrics Interval"))]
+    pub metrics_interval_secs: Option<NonZeroU64>,
+
+    /// Configuration for persisting the learned patterns across restarts.
+    ///
+    /// When set, snapshots of the patterns are written to the data directory, and the patterns are
+    /// restored from their latest snapshot on startup.
+    #[configurable(derived)]
+    pub persistence: Option<PersistenceConfig>,
+}
+
+fn default_target_field() -> ConfigTargetPath {
+    ConfigTargetPath::try_from("pattern".to_string()).expect("valid path")
+}
+
+const fn default_depth() -> usize {
+    4
+}
+
+const fn default_similarity_threshold() -> f64 {
+    0.4
+}
+
+const fn default_max_children() -> NonZeroUsize {
+    NonZeroUsize::new(100).expect("static non-zero number")
+}
+
+const fn default_max_clusters() -> NonZeroUsize {
+    NonZeroUsize::new(1000).expect("static non-zero number")
+}
+
+impl LogClusteringConfig {
+    fn validate_options(&self) -> crate::Result<()> {
+        if self.depth < 3 {
+            return Err(format!("`depth` must be at least 3, value: {}", self.depth).into());
+        }
+        if !(0.0..=1.0).contains(&self.similarity_threshold) {
+            return Err(format!(
+                "`similarity_threshold` must be between 0 and 1, value: {}",
+                self.similarity_threshold
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+impl GenerateConfig for LogClusteringConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"target_field = "pattern"
+            similarity_threshold = 0.4
+        "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "log_clustering")]
+impl TransformConfig for LogClusteringConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        self.validate_options()?;
+        let drain = Drain::new(
+            self.depth,
+            self.similarity_threshold,
+            self.max_children,
+            self.max_clusters,
+        );
+        let mut transform = LogClustering::new(
+            drain,
+            self.field.clone().map(|field| field.0),
+            self.target_field.0.clone(),
+            self.metrics_interval_secs
+                .map(|interval| Duration::from_secs(interval.get())),
+        );
+        if let Some(persistence) = &self.persistence {
+            let key = context
+                .key
+                .as_ref()
+                .ok_or("Persisting the patterns requires a component key")?;
+            transform = transform.persisted(
+                persistence.snapshot_path(&context.globals, key.id(), "log_clustering_patterns")?,
+                persistence.snapshot_interval(),
+            );
+        }
+        Ok(Transform::event_task(transform))
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn validate(&self, _: &schema::Definition) -> Result<(), Vec<String>> {
+        self.validate_options()
+            .map_err(|error| vec![error.to_string()])
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        let kind = Kind::object(
+            Collection::empty()
+                .with_known("id", Kind::bytes())
+                .with_known("template", Kind::bytes())
+                .with_known(
+                    "variables",
+                    Kind::array(Collection::empty().with_unknown(Kind::bytes())),
+                ),
+        )
+        .or_undefined();
+        let target = &self.target_field.0;
+
+        let data_type = if self.metrics_interval_secs.is_some() {
+            DataType::Log | DataType::Metric
+        } else {
+            DataType::Log
+        };
+        vec![TransformOutput::new(
+            data_type,
+            input_definitions
+                .iter()
+                .map(|(output, definition)| {
+                    let definition = match target.prefix {
+                        PathPrefix::Event => {
+                            definition
+                                .clone()
+                                .with_event_field(&target.path, kind.clone(), None)
+                        }
+                        PathPrefix::Metadata => {
+                            definition
+                                .clone()
+                                .with_metadata_field(&target.path, kind.clone(), None)
+                        }
+                    };
+                    (output.clone(), definition)
+                })
+                .collect(),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vector_lib::lookup::owned_value_path;
+
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<LogClusteringConfig>();
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        let mut config = toml::from_str::<LogClusteringConfig>("depth = 2").unwrap();
+        assert!(config.validate_options().is_err());
+
+        config.depth = 4;
+        config.similarity_threshold = 1.5;
+        assert!(config.validate_options().is_err());
+
+        assert_eq!(config.target_field.0.path, owned_value_path!("pattern"));
+    }
+}
@@ -0,0 +1,320 @@
+//! Online clustering of log messages into templates, following the [Drain][drain] algorithm.
+//!
+//! Messages are split into tokens and routed through a fixed depth tree, by token count and then
+//! by their first tokens, to a leaf holding candidate clusters. A message joins the most similar
+//! candidate when similar enough, replacing the tokens that differ in the template of the cluster
+//! with wildcards, or starts a new cluster otherwise.
+//!
+//! [drain]: https://jiemingzhu.github.io/pub/pjhe_icws2017.pdf
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    num::NonZeroUsize,
+    path::Path,
+};
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+
+use crate::common::persistence::write_snapshot;
+
+/// The token standing for the variable parts of templates.
+pub(super) const WILDCARD: &str = "<*>";
+
+/// A cluster of messages sharing a template.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(super) struct Cluster {
+    /// The ID of the cluster, derived from the first message of the cluster so that it stays the
+    /// same as the template is generalized.
+    pub(super) id: String,
+    pub(super) tokens: Vec<String>,
+    pub(super) count: u64,
+}
+
+impl Cluster {
+    pub(super) fn template(&self) -> String {
+        self.tokens.join(" ")
+    }
+
+    /// The share of the tokens of a message equal to the tokens of the template.
+    fn similarity(&self, tokens: &[&str]) -> f64 {
+        let same = self
+            .tokens
+            .iter()
+            .zip(tokens)
+            .filter(|(template, token)| template.as_str() != WILDCARD && template == *token)
+            .count();
+        same as f64 / tokens.len().max(1) as f64
+    }
+}
+
+/// The pattern a message was assigned to.
+#[derive(Debug, PartialEq)]
+pub(super) struct Pattern {
+    pub(super) id: String,
+    pub(super) template: String,
+    /// The tokens of the message matching the wildcards of the template.
+    pub(super) variables: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: HashMap<String, Node>,
+    /// The keys of the clusters of a leaf, which may have been evicted since.
+    clusters: Vec<u64>,
+}
+
+#[derive(Debug)]
+pub(super) struct Drain {
+    /// The number of tokens routing messages through the tree.
+    prefix_tokens: usize,
+    similarity_threshold: f64,
+    max_children: usize,
+    /// The first level of the tree, by token count.
+    root: HashMap<usize, Node>,
+    clusters: LruCache<u64, Cluster>,
+    next_key: u64,
+}
+
+/// Finds the leaf of a message, creating the nodes on the way.
+///
+/// Tokens with digits are likely variables, so they are routed to the wildcard child, as are the
+/// tokens of nodes which already have the maximum number of children.
+fn leaf<'a>(
+    root: &'a mut HashMap<usize, Node>,
+    tokens: &[&str],
+    prefix_tokens: usize,
+    max_children: usize,
+) -> &'a mut Node {
+    let mut node = root.entry(tokens.len()).or_default();
+    for token in tokens.iter().take(prefix_tokens) {
+        let key = if token.chars().any(|c| c.is_ascii_digit())
+            || (!node.children.contains_key(*token) && node.children.len() >= max_children)
+        {
+            WILDCARD
+        } else {
+            *token
+        };
+        node = node.children.entry(key.to_string()).or_default();
+    }
+    node
+}
+
+impl Drain {
+    /// Creates an empty set of clusters.
+    ///
+    /// The depth of the tree counts the root and the leaves, so messages are routed by their first
+    /// `depth - 2` tokens.
+    pub(super) fn new(
+        depth: usize,
+        similarity_threshold: f64,
+        max_children: NonZeroUsize,
+        max_clusters: NonZeroUsize,
+    ) -> Self {
+        Self {
+            prefix_tokens: depth.saturating_sub(2),
+            similarity_threshold,
+            max_children: max_children.get(),
+            root: HashMap::new(),
+            clusters: LruCache::new(max_clusters),
+            next_key: 0,
+        }
+    }
+
+    /// Adds a message to its cluster, returning the pattern of the message.
+    pub(super) fn add(&mut self, message: &str) -> Pattern {
+        let tokens = message.split_whitespace().collect::<Vec<_>>();
+        let node = leaf(
+            &mut self.root,
+            &tokens,
+            self.prefix_tokens,
+            self.max_children,
+        );
+
+        let clusters = &self.clusters;
+        node.clusters.retain(|key| clusters.contains(key));
+        let best = node
+            .clusters
+            .iter()
+            .filter_map(|key| {
+                let cluster = clusters.peek(key)?;
+                (cluster.tokens.len() == tokens.len()).then(|| (*key, cluster.similarity(&tokens)))
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .filter(|(_, similarity)| *similarity >= self.similarity_threshold);
+
+        let cluster: &Cluster = match best {
+            Some((key, _)) => {
+                let cluster = self.clusters.get_mut(&key).expect("cluster was found");
+                for (template, token) in cluster.tokens.iter_mut().zip(&tokens) {
+                    if template.as_str() != *token {
+                        *template = WILDCARD.to_string();
+                    }
+                }
+                cluster.count += 1;
+                cluster
+            }
+            None => {
+                let key = self.next_key;
+                self.next_key += 1;
+                node.clusters.push(key);
+                self.clusters.push(
+                    key,
+                    Cluster {
+                        id: format!("{:016x}", seahash::hash(tokens.join(" ").as_bytes())),
+                        tokens: tokens.iter().map(ToString::to_string).collect(),
+                        count: 1,
+                    },
+                );
+                self.clusters.peek(&key).expect("cluster was inserted")
+            }
+        };
+
+        Pattern {
+            id: cluster.id.clone(),
+            template: cluster.template(),
+            variables: cluster
+                .tokens
+                .iter()
+                .zip(&tokens)
+                .filter(|(template, _)| template.as_str() == WILDCARD)
+                .map(|(_, token)| token.to_string())
+                .collect(),
+        }
+    }
+
+    /// Inserts a cluster, routed by its template.
+    fn insert(&mut self, cluster: Cluster) {
+        let key = self.next_key;
+        self.next_key += 1;
+        let tokens = cluster
+            .tokens
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        leaf(
+            &mut self.root,
+            &tokens,
+            self.prefix_tokens,
+            self.max_children,
+        )
+        .clusters
+        .push(key);
+        self.clusters.push(key, cluster);
+    }
+
+    /// The clusters, from the least to the most recently matched.
+    pub(super) fn clusters(&self) -> impl Iterator<Item = &Cluster> {
+        self.clusters.iter().rev().map(|(_, cluster)| cluster)
+    }
+
+    /// Restores the clusters of a snapshot.
+    ///
+    /// The whole snapshot is read before restoring any cluster, so the clusters are left
+    /// untouched when the snapshot can't be read.
+    pub(super) fn restore(&mut self, path: &Path) -> crate::Result<()> {
+        let mut clusters = Vec::new();
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                clusters.push(serde_json::from_str(&line)?);
+            }
+        }
+        for cluster in clusters {
+            self.insert(cluster);
+        }
+        Ok(())
+    }
+
+    /// Writes a snapshot of the clusters, a JSON object per line from the least to the most
+    /// recently matched.
+    pub(super) fn write_snapshot(&self, path: &Path) -> std::io::Result<()> {
+        write_snapshot(path, |file| {
+            for cluster in self.clusters() {
+                serde_json::to_writer(&mut *file, cluster)?;
+                file.write_all(b"\n")?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(max_clusters: usize) -> Drain {
+        Drain::new(
+            4,
+            0.4,
+            NonZeroUsize::new(100).unwrap(),
+            NonZeroUsize::new(max_clusters).unwrap(),
+        )
+    }
+
+    #[test]
+    fn learns_templates() {
+        let mut drain = drain(100);
+        let first = drain.add("user login succeeded for alice from 10.0.0.1");
+        assert_eq!(
+            first.template,
+            "user login succeeded for alice from 10.0.0.1"
+        );
+        assert!(first.variables.is_empty());
+
+        let second = drain.add("user login succeeded for bob from 10.0.0.2");
+        assert_eq!(second.id, first.id);
+        assert_eq!(second.template, "user login succeeded for <*> from <*>");
+        assert_eq!(second.variables, ["bob", "10.0.0.2"]);
+
+        let other = drain.add("disk full on /dev/sda1");
+        assert_ne!(other.id, first.id);
+        assert_eq!(
+            drain
+                .add("user login succeeded for carol from 10.0.0.3")
+                .variables,
+            ["carol", "10.0.0.3"]
+        );
+        assert_eq!(
+            drain.clusters().map(|cluster| cluster.count).sum::<u64>(),
+            4
+        );
+    }
+
+    #[test]
+    fn evicts_least_recently_matched_clusters() {
+        let mut drain = drain(2);
+        let first = drain.add("connection opened");
+        drain.add("cache miss for key a");
+        drain.add("queue is empty");
+
+        assert_eq!(drain.clusters().count(), 2);
+        let relearned = drain.add("connection opened");
+        assert_eq!(relearned.id, first.id);
+        assert_eq!(
+            drain.clusters().map(Cluster::template).collect::<Vec<_>>(),
+            ["queue is empty", "connection opened"]
+        );
+    }
+
+    #[test]
+    fn restores_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("clusters");
+
+        let mut first = drain(100);
+        first.add("request took 12 ms");
+        let pattern = first.add("request took 15 ms");
+        first.add("shutting down");
+        first.write_snapshot(&path).unwrap();
+
+        let mut restored = drain(100);
+        restored.restore(&path).unwrap();
+        assert_eq!(
+            first.clusters().collect::<Vec<_>>(),
+            restored.clusters().collect::<Vec<_>>()
+        );
+        assert_eq!(restored.add("request took 20 ms").id, pattern.id);
+    }
+}
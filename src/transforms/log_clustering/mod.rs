@@ -0,0 +1,3 @@
+pub mod config;
+mod drain;
+pub mod transform;
@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+use async_stream::stream;
+use chrono::Utc;
+use futures::{Stream, StreamExt};
+use tokio::time::{interval_at, Instant, Interval};
+use vector_lib::metric_tags;
+use vrl::path::OwnedTargetPath;
+use vrl::value::{ObjectMap, Value};
+
+use crate::{
+    common::persistence::{restore_snapshot, with_snapshots, SnapshotStep},
+    event::{Event, Metric, MetricKind, MetricValue},
+    internal_events::LogClusteringSnapshotError,
+    transforms::TaskTransform,
+};
+
+use super::drain::Drain;
+
+/// The name of the counters of events by pattern.
+const PATTERN_EVENTS_METRIC: &str = "log_pattern_events";
+
+/// Ticks every `period`, starting one period from now, or never.
+fn interval(period: Option<Duration>) -> Option<Interval> {
+    period.map(|period| interval_at(Instant::now() + period, period))
+}
+
+async fn tick(interval: &mut Option<Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Where and how often the patterns are persisted.
+struct Snapshot {
+    path: PathBuf,
+    interval: Duration,
+}
+
+pub struct LogClustering {
+    drain: Drain,
+    field: Option<OwnedTargetPath>,
+    target_field: OwnedTargetPath,
+    metrics_interval: Option<Duration>,
+    /// The template and number of events of the patterns matched since the last emitted counts.
+    pattern_counts: HashMap<String, (String, u64)>,
+    snapshot: Option<Snapshot>,
+}
+
+impl LogClustering {
+    pub(super) fn new(
+        drain: Drain,
+        field: Option<OwnedTargetPath>,
+        target_field: OwnedTargetPath,
+        metrics_interval: Option<Duration>,
+    ) -> Self {
+        Self {
+            drain,
+            field,
+            target_field,
+            metrics_interval,
+            pattern_counts: HashMap::new(),
+            snapshot: None,
+        }
+    }
+
+    /// Persists the patterns to `path` every `interval`, restoring them from the latest snapshot
+    /// if there is one.
+    ///
+    /// A snapshot that can't be restored is moved aside, and the patterns are learned anew.
+    pub(super) fn persisted(mut self, path: PathBuf, interval: Duration) -> Self {
+        restore_snapshot(&path, |path| self.drain.restore(path));
+        self.snapshot = Some(Snapshot { path, interval });
+        self
+    }
+
+    fn transform_one(&mut self, mut event: Event) -> Event {
+        let log = event.as_mut_log();
+        let message = match &self.field {
+            Some(field) => log.get(field),
+            None => log.get_message(),
+        };
+        let Some(Value::Bytes(message)) = message else {
+            return event;
+        };
+
+        let pattern = self.drain.add(&String::from_utf8_lossy(message));
+        if self.metrics_interval.is_some() {
+            let (template, count) = self.pattern_counts.entry(pattern.id.clone()).or_default();
+            template.clone_from(&pattern.template);
+            *count += 1;
+        }
+
+        log.insert(
+            &self.target_field,
+            ObjectMap::from([
+                ("id".into(), Value::from(pattern.id)),
+                ("template".into(), Value::from(pattern.template)),
+                (
+                    "variables".into(),
+                    pattern
+                        .variables
+                        .into_iter()
+                        .map(Value::from)
+                        .collect::<Vec<_>>()
+                        .into(),
+                ),
+            ]),
+        );
+        event
+    }
+
+    /// Takes the counts of events by pattern since the last call.
+    fn pattern_metrics(&mut self) -> Vec<Event> {
+        let timestamp = Utc::now();
+        self.pattern_counts
+            .drain()
+            .map(|(id, (template, count))| {
+                Metric::new(
+                    PATTERN_EVENTS_METRIC,
+                    MetricKind::Incremental,
+                    MetricValue::Counter {
+                        value: count as f64,
+                    },
+                )
+                .with_tags(Some(
+                    metric_tags!("pattern_id" => id, "template" => template),
+                ))
+                .with_timestamp(Some(timestamp))
+                .into()
+            })
+            .collect()
+    }
+
+    /// Writes a snapshot of the patterns, if they are persisted.
+    fn write_snapshot(&self) {
+        let Some(snapshot) = &self.snapshot else {
+            return;
+        };
+        if let Err(error) = self.drain.write_snapshot(&snapshot.path) {
+            emit!(LogClusteringSnapshotError {
+                error,
+                path: &snapshot.path,
+            });
+        }
+    }
+}
+
+impl TaskTransform<Event> for LogClustering {
+    fn transform(
+        self: Box<Self>,
+        task: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let mut inner = self;
+        let snapshot_interval = inner.snapshot.as_ref().map(|snapshot| snapshot.interval);
+        if snapshot_interval.is_none() && inner.metrics_interval.is_none() {
+            return Box::pin(task.map(move |event| inner.transform_one(event)));
+        }
+
+        let mut input = match snapshot_interval {
+            Some(snapshot_interval) => with_snapshots(task, snapshot_interval).boxed(),
+            None => task.map(SnapshotStep::Input).boxed(),
+        };
+        let mut metrics_interval = interval(inner.metrics_interval);
+        Box::pin(stream! {
+            let mut done = false;
+            while !done {
+                let mut output = Vec::new();
+                tokio::select! {
+                    _ = tick(&mut metrics_interval) => output = inner.pattern_metrics(),
+                    step = input.next() => match step {
+                        Some(SnapshotStep::Input(event)) => output.push(inner.transform_one(event)),
+                        Some(SnapshotStep::Snapshot) => inner.write_snapshot(),
+                        None => {
+                            output = inner.pattern_metrics();
+                            done = true;
+                        }
+                    },
+                }
+                for event in output {
+                    yield event;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use vrl::owned_event_path;
+
+    use super::*;
+    use crate::event::LogEvent;
+
+    fn clustering(metrics_interval: Option<Duration>) -> LogClustering {
+        LogClustering::new(
+            Drain::new(
+                4,
+                0.4,
+                NonZeroUsize::new(100).unwrap(),
+                NonZeroUsize::new(100).unwrap(),
+            ),
+            None,
+            owned_event_path!("pattern"),
+            metrics_interval,
+        )
+    }
+
+    #[tokio::test]
+    async fn annotates_events_and_counts_patterns() {
+        let transform = clustering(Some(Duration::from_secs(3600)));
+        let events = vec![
+            LogEvent::from("GET /users/1 took 12 ms").into(),
+            LogEvent::from("GET /users/2 took 15 ms").into(),
+            Event::from(LogEvent::from(ObjectMap::from([(
+                "other".into(),
+                Value::from(1),
+            )]))),
+        ];
+        let output = Box::new(transform)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect::<Vec<_>>()
+            .await;
+
+        let logs = output
+            .iter()
+            .filter_map(Event::maybe_as_log)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(logs.len(), 3);
+        assert_eq!(
+            logs[1]["pattern.template"],
+            Value::from("GET <*> took <*> ms")
+        );
+        assert_eq!(
+            logs[1]["pattern.variables"],
+            Value::from(vec![Value::from("/users/2"), Value::from("15")])
+        );
+        assert_eq!(logs[0]["pattern.id"], logs[1]["pattern.id"]);
+        assert!(logs[2].get("pattern").is_none());
+
+        let metrics = output
+            .into_iter()
+            .filter_map(Event::try_into_metric)
+            .collect::<Vec<_>>();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].name(), PATTERN_EVENTS_METRIC);
+        assert_eq!(metrics[0].value(), &MetricValue::Counter { value: 2.0 });
+        assert_eq!(
+            metrics[0].tag_value("pattern_id"),
+            logs[0]["pattern.id"].as_str().map(|id| id.into_owned())
+        );
+    }
+
+    #[test]
+    fn restores_patterns_from_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("patterns");
+
+        let mut first = clustering(None).persisted(path.clone(), Duration::from_secs(60));
+        let event = first.transform_one(LogEvent::from("worker 1 started").into());
+        first.write_snapshot();
+
+        let mut restored = clustering(None).persisted(path, Duration::from_secs(60));
+        let restored_event = restored.transform_one(LogEvent::from("worker 2 started").into());
+        assert_eq!(
+            event.as_log()["pattern.id"],
+            restored_event.as_log()["pattern.id"]
+        );
+        assert_eq!(
+            restored_event.as_log()["pattern.template"],
+            Value::from("worker <*> started")
+        );
+    }
+
+    #[test]
+    fn discards_invalid_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("patterns");
+        std::fs::write(&path, "{\"id\":").unwrap();
+
+        let mut transform = clustering(None).persisted(path.clone(), Duration::from_secs(60));
+        assert!(!path.exists());
+        assert!(dir.path().join("patterns.corrupt").exists());
+
+        let event = transform.transform_one(LogEvent::from("worker 1 started").into());
+        assert_eq!(
+            event.as_log()["pattern.template"],
+            Value::from("worker 1 started")
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use openssl::{error::ErrorStack, hash::MessageDigest, pkey::PKey, pkey::Private, sign::Signer};
+use vector_lib::config::{clone_input_definitions, LogNamespace};
+use vector_lib::configurable::configurable_component;
+use vector_lib::lookup::lookup_v2::ConfigTargetPath;
+use vector_lib::sensitive_string::SensitiveString;
+use vrl::path::OwnedTargetPath;
+use vrl::value::Value;
+
+use crate::{
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    event::Event,
+    internal_events::PiiRedactionDetected,
+    schema,
+    transforms::{FunctionTransform, OutputBuffer, Transform},
+};
+
+mod detectors;
+
+pub use detectors::Detector;
+use detectors::{detect, Detection};
+
+/// The number of bytes of the HMAC of a value kept by the `hash` action.
+const HASH_BYTES: usize = 16;
+
+/// What to do with the sensitive values found.
+#[configurable_component]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Replaces the sensitive values with `mask`.
+    #[default]
+    Mask,
+
+    /// Replaces the sensitive values with their keyed hash, so that values can still be joined
+    /// on without being revealed.
+    ///
+    /// Values are replaced with `[<detector>:<hash>]`, where the hash is the first 16 bytes of the
+    /// HMAC-SHA256 of the value with `hash_key`, hex-encoded.
+    Hash,
+
+    /// Removes the fields holding sensitive values.
+    Drop,
+}
+
+/// Configuration for the `pii_redaction` transform.
+#[configurable_component(transform(
+    "pii_redaction",
+    "Detect and redact personally identifiable information and secrets in log events."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PiiRedactionConfig {
+    /// The detectors of sensitive values to run.
+    ///
+    /// By default, all detectors are run.
+    #[serde(default = "default_detectors")]
+    pub detectors: Vec<Detector>,
+
+    /// The fields to scan.
+    ///
+    /// By default, all string fields of the event are scanned. The objects and arrays in these
+    /// fields are scanned recursively.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = "message"))]
+    #[configurable(metadata(docs::examples = "user.address"))]
+    pub fields: Vec<ConfigTargetPath>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub action: Action,
+
+    /// The text replacing sensitive values with the `mask` action.
+    #[serde(default = "default_mask")]
+    pub mask: String,
+
+    /// The key of the HMAC of sensitive values with the `hash` action.
+    ///
+    /// Use a [secret][secrets] to keep the key out of the configuration.
+    ///
+    /// [secrets]: https://vector.dev/docs/reference/configuration/global-options/#secret
+    #[configurable(metadata(docs::examples = "SECRET[backend.pii_hash_key]"))]
+    pub hash_key: Option<SensitiveString>,
+}
+
+fn default_detectors() -> Vec<Detector> {
+    Detector::ALL.to_vec()
+}
+
+fn default_mask() -> String {
+    "[REDACTED]".to_string()
+}
+
+impl GenerateConfig for PiiRedactionConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"detectors = ["credit_card", "email", "iban"]
+            action = "mask"
+        "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "pii_redaction")]
+impl TransformConfig for PiiRedactionConfig {
+    async fn build(&self, _: &TransformContext) -> crate::Result<Transform> {
+        PiiRedaction::new(self).map(Transform::function)
+    }
+
+    fn input(&self) -> Input {
+        Input::log()
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        vec![TransformOutput::new(
+            DataType::Log,
+            clone_input_definitions(input_definitions),
+        )]
+    }
+
+    fn enable_concurrency(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone)]
+enum Replacement {
+    Mask(String),
+    Hash(PKey<Private>),
+    Drop,
+}
+
+#[derive(Clone)]
+pub struct PiiRedaction {
+    detectors: Vec<Detector>,
+    fields: Vec<OwnedTargetPath>,
+    replacement: Replacement,
+}
+
+/// Computes the hex-encoded, truncated HMAC-SHA256 of a value.
+fn hmac_hex(key: &PKey<Private>, value: &str) -> Result<String, ErrorStack> {
+    let mut signer = Signer::new(MessageDigest::sha256(), key)?;
+    signer.update(value.as_bytes())?;
+    Ok(signer.sign_to_vec()?[..HASH_BYTES]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+impl PiiRedaction {
+    pub fn new(config: &PiiRedactionConfig) -> crate::Result<Self> {
+        let replacement = match config.action {
+            Action::Mask => Replacement::Mask(config.mask.clone()),
+            Action::Hash => {
+                let key = config
+                    .hash_key
+                    .as_ref()
+                    .ok_or("`hash_key` is required by the `hash` action")?;
+                Replacement::Hash(PKey::hmac(key.inner().as_bytes())?)
+            }
+            Action::Drop => Replacement::Drop,
+        };
+        Ok(Self {
+            detectors: config.detectors.clone(),
+            fields: config.fields.iter().map(|field| field.0.clone()).collect(),
+            replacement,
+        })
+    }
+
+    /// Replaces the sensitive values of a string.
+    fn replace(&self, text: &str, detections: &[Detection]) -> String {
+        let mut redacted = String::with_capacity(text.len());
+        let mut last = 0;
+        for detection in detections {
+            redacted.push_str(&text[last..detection.start]);
+            match &self.replacement {
+                Replacement::Mask(mask) => redacted.push_str(mask),
+                Replacement::Hash(key) => {
+                    let name = detection.detector.name();
+                    // Values are never left in place, even when they cannot be hashed.
+                    match hmac_hex(key, &text[detection.start..detection.end]) {
+                        Ok(hash) => redacted.push_str(&format!("[{name}:{hash}]")),
+                        Err(_) => redacted.push_str(&format!("[{name}]")),
+                    }
+                }
+                Replacement::Drop => {}
+            }
+            last = detection.end;
+        }
+        redacted.push_str(&text[last..]);
+        redacted
+    }
+
+    /// Redacts the sensitive values of a value, returning whether the value must be removed.
+    fn redact(&self, value: &mut Value, hits: &mut [usize; Detector::ALL.len()]) -> bool {
+        match value {
+            Value::Bytes(bytes) => {
+                let redacted = {
+                    let text = String::from_utf8_lossy(bytes);
+                    let detections = detect(&self.detectors, &text);
+                    if detections.is_empty() {
+                        return false;
+                    }
+                    for detection in &detections {
+                        hits[detection.detector as usize] += 1;
+                    }
+                    if matches!(self.replacement, Replacement::Drop) {
+                        return true;
+                    }
+                    self.replace(&text, &detections)
+                };
+                *value = Value::from(redacted);
+                false
+            }
+            Value::Object(object) => {
+                object.retain(|_, value| !self.redact(value, hits));
+                false
+            }
+            Value::Array(array) => {
+                array.retain_mut(|value| !self.redact(value, hits));
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FunctionTransform for PiiRedaction {
+    fn transform(&mut self, output: &mut OutputBuffer, mut event: Event) {
+        let log = event.as_mut_log();
+        let mut hits = [0; Detector::ALL.len()];
+        if self.fields.is_empty() {
+            self.redact(log.value_mut(), &mut hits);
+        } else {
+            for field in &self.fields {
+                let remove = log
+                    .get_mut(field)
+                    .is_some_and(|value| self.redact(value, &mut hits));
+                if remove {
+                    log.remove(field);
+                }
+            }
+        }
+
+        for (detector, count) in Detector::ALL.iter().zip(hits) {
+            if count > 0 {
+                emit!(PiiRedactionDetected {
+                    detector: detector.name(),
+                    count,
+                });
+            }
+        }
+        output.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use vrl::btreemap;
+
+    use super::*;
+    use crate::{event::LogEvent, transforms::test::transform_one};
+
+    fn redaction(config: &str) -> PiiRedaction {
+        PiiRedaction::new(&toml::from_str(config).unwrap()).unwrap()
+    }
+
+    fn event() -> Event {
+        LogEvent::from(btreemap! {
+            "message" => "login from jane@example.com at 10.0.0.1",
+            "user" => btreemap! {
+                "card" => "4111-1111-1111-1111",
+                "tags" => vec!["vip", "jane@example.com"],
+                "age" => 42,
+            },
+        })
+        .into()
+    }
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<PiiRedactionConfig>();
+    }
+
+    #[test]
+    fn masks_all_string_fields() {
+        let mut transform = redaction("");
+        let event = transform_one(&mut transform, event()).unwrap();
+        let log = event.as_log();
+        assert_eq!(
+            log["message"],
+            Value::from("login from [REDACTED] at [REDACTED]")
+        );
+        assert_eq!(log["user.card"], Value::from("[REDACTED]"));
+        assert_eq!(
+            log["user.tags"],
+            Value::from(vec![Value::from("vip"), Value::from("[REDACTED]")])
+        );
+        assert_eq!(log["user.age"], Value::from(42));
+    }
+
+    #[test]
+    fn hashes_selected_fields() {
+        let mut transform = redaction(
+            r#"
+            detectors = ["email"]
+            fields = ["message"]
+            action = "hash"
+            hash_key = "secret"
+            "#,
+        );
+        let event = transform_one(&mut transform, event()).unwrap();
+        let log = event.as_log();
+
+        let hash = hmac_hex(&PKey::hmac(b"secret").unwrap(), "jane@example.com").unwrap();
+        assert_eq!(hash.len(), 2 * HASH_BYTES);
+        assert_eq!(
+            log["message"],
+            Value::from(format!("login from [email:{hash}] at 10.0.0.1"))
+        );
+        assert_eq!(
+            log["user.tags"],
+            Value::from(vec![Value::from("vip"), Value::from("jane@example.com")])
+        );
+
+        assert!(PiiRedaction::new(&toml::from_str(r#"action = "hash""#).unwrap()).is_err());
+    }
+
+    #[test]
+    fn drops_fields_with_sensitive_values() {
+        let mut transform = redaction(r#"action = "drop""#);
+        let event = transform_one(&mut transform, event()).unwrap();
+        let log = event.as_log();
+        assert!(log.get("message").is_none());
+        assert!(log.get("user.card").is_none());
+        assert_eq!(log["user.tags"], Value::from(vec![Value::from("vip")]));
+        assert_eq!(log["user.age"], Value::from(42));
+    }
+}
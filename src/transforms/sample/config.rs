@@ -1,4 +1,6 @@
+use serde_with::serde_as;
 use snafu::Snafu;
+use std::time::Duration;
 use vector_lib::config::{LegacyKey, LogNamespace};
 use vector_lib::configurable::configurable_component;
 use vector_lib::lookup::{lookup_v2::OptionalValuePath, owned_value_path};
@@ -29,12 +31,21 @@ pub enum SampleError {
     InvalidRate,
 
     #[snafu(display(
-        "Exactly one value must be provided for either 'rate' or 'ratio', but not both"
+        "Only positive, non-zero numbers are allowed values for `target_events_per_sec`, value: {target}"
+    ))]
+    InvalidTarget { target: f64 },
+
+    #[snafu(display("`target_window_secs` must be non-zero"))]
+    InvalidTargetWindow,
+
+    #[snafu(display(
+        "Exactly one value must be provided for either 'rate', 'ratio' or 'target_events_per_sec'"
     ))]
     InvalidConfiguration,
 }
 
 /// Configuration for the `sample` transform.
+#[serde_as]
 #[configurable_component(transform(
     "sample",
     "Sample events from an event stream based on supplied criteria and at a configurable rate."
@@ -60,6 +71,23 @@ pub struct SampleConfig {
     #[configurable(validation(range(min = 0.0, max = 1.0)))]
     pub ratio: Option<f64>,
 
+    /// The total number of events per second to forward, sampling events dynamically.
+    ///
+    /// The events of each `group_by` bucket are counted over `target_window_secs`, and this target is
+    /// then shared between the buckets for the next window. Rare buckets are forwarded in full, while
+    /// the buckets with the most events are sampled the hardest. The effective rate of the bucket of
+    /// each forwarded event, expressed as `N` for `1/N`, is stored in `sample_rate_key` so that counts
+    /// can be re-weighted downstream. It is an error to provide a value for both this and `rate` or
+    /// `ratio`.
+    #[configurable(metadata(docs::examples = 100.0))]
+    pub target_events_per_sec: Option<f64>,
+
+    /// The window over which events are counted to adjust the rates of dynamic sampling, in seconds.
+    #[serde(default = "default_target_window_secs")]
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[configurable(metadata(docs::human_name = "Target Window"))]
+    pub target_window_secs: Duration,
+
     /// The name of the field whose value is hashed to determine if the event should be
     /// sampled.
     ///
@@ -95,15 +123,24 @@ pub struct SampleConfig {
 
 impl SampleConfig {
     fn sample_rate(&self) -> Result<SampleMode, SampleError> {
-        match (self.rate, self.ratio) {
-            (None, Some(ratio)) => {
+        match (self.rate, self.ratio, self.target_events_per_sec) {
+            (None, None, Some(target)) => {
+                if !target.is_finite() || target <= 0.0 {
+                    Err(SampleError::InvalidTarget { target })
+                } else if self.target_window_secs.is_zero() {
+                    Err(SampleError::InvalidTargetWindow)
+                } else {
+                    Ok(SampleMode::new_dynamic(target, self.target_window_secs))
+                }
+            }
+            (None, Some(ratio), None) => {
                 if ratio <= 0.0 {
                     Err(SampleError::InvalidRatio { ratio })
                 } else {
                     Ok(SampleMode::new_ratio(ratio))
                 }
             }
-            (Some(rate), None) => {
+            (Some(rate), None, None) => {
                 if rate == 0 {
                     Err(SampleError::InvalidRate)
                 } else {
@@ -124,6 +161,8 @@ impl GenerateConfig for SampleConfig {
             group_by: None,
             exclude: None::<AnyCondition>,
             sample_rate_key: default_sample_rate_key(),
+            target_events_per_sec: None,
+            target_window_secs: default_target_window_secs(),
         })
         .unwrap()
     }
@@ -183,6 +222,10 @@ impl TransformConfig for SampleConfig {
     }
 }
 
+pub const fn default_target_window_secs() -> Duration {
+    Duration::from_secs(10)
+}
+
 pub fn default_sample_rate_key() -> OptionalValuePath {
     OptionalValuePath::from(owned_value_path!("sample_rate"))
 }
@@ -8,11 +8,12 @@ use crate::{
     config::log_schema,
     event::{Event, LogEvent, TraceEvent},
     test_util::random_lines,
-    transforms::sample::config::default_sample_rate_key,
-    transforms::sample::transform::{Sample, SampleMode},
+    transforms::sample::config::{default_sample_rate_key, default_target_window_secs},
+    transforms::sample::transform::{DynamicSampler, Sample, SampleMode},
     transforms::test::transform_one,
 };
 use approx::assert_relative_eq;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
 use vector_lib::lookup::lookup_v2::OptionalValuePath;
@@ -28,6 +29,8 @@ async fn emits_internal_events() {
             group_by: None,
             exclude: None,
             sample_rate_key: default_sample_rate_key(),
+            target_events_per_sec: None,
+            target_window_secs: default_target_window_secs(),
         };
         let (tx, rx) = mpsc::channel(1);
         let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
@@ -322,6 +325,72 @@ fn sample_at_rates_higher_then_half() {
     }
 }
 
+#[test]
+fn dynamic_sampling_keeps_rare_keys() {
+    let mut sampler = DynamicSampler::new(10.0, Duration::from_secs(1));
+    let debug = Some("debug".to_string());
+    let error = Some("error".to_string());
+
+    // Nothing is sampled until the first window is over.
+    for _ in 0..100 {
+        assert!(sampler.sample(&debug, None));
+    }
+    for _ in 0..2 {
+        assert!(sampler.sample(&error, None));
+    }
+    sampler.roll_window(Instant::now() + Duration::from_secs(2));
+
+    // The budget of 10 events is shared between both keys, rare keys keeping all of their events.
+    assert_relative_eq!(sampler.ratio(&error), 1.0);
+    assert_relative_eq!(sampler.ratio(&debug), 0.08);
+    assert_eq!(sampler.sample_rate(&error), "1");
+    assert_eq!(sampler.sample_rate(&debug), "12.5");
+
+    let passed = |sampler: &mut DynamicSampler, key: &Option<String>, n: usize| {
+        (0..n).filter(|_| sampler.sample(key, None)).count()
+    };
+    assert_eq!(passed(&mut sampler, &debug, 100), 8);
+    assert_eq!(passed(&mut sampler, &error, 2), 2);
+}
+
+#[test]
+fn dynamic_sampling_adds_effective_rate_to_event() {
+    let mut sampler = Sample::new(
+        "sample".to_string(),
+        SampleMode::new_dynamic(1000.0, Duration::from_secs(10)),
+        None,
+        None,
+        None,
+        default_sample_rate_key(),
+    );
+    let event = transform_one(&mut sampler, Event::Log(LogEvent::from("hello"))).unwrap();
+    assert_eq!(event.as_log()["sample_rate"], "1".into());
+}
+
+#[tokio::test]
+async fn rejects_invalid_dynamic_configurations() {
+    use crate::config::{TransformConfig, TransformContext};
+
+    let config = |ratio, target| SampleConfig {
+        rate: None,
+        ratio,
+        key_field: None,
+        group_by: None,
+        exclude: None,
+        sample_rate_key: default_sample_rate_key(),
+        target_events_per_sec: target,
+        target_window_secs: default_target_window_secs(),
+    };
+    let context = TransformContext::default();
+    assert!(config(None, Some(100.0)).build(&context).await.is_ok());
+    assert!(config(Some(0.5), Some(100.0))
+        .build(&context)
+        .await
+        .is_err());
+    assert!(config(None, Some(0.0)).build(&context).await.is_err());
+    assert!(config(None, Some(f64::NAN)).build(&context).await.is_err());
+}
+
 fn condition_contains(key: &str, needle: &str) -> Condition {
     let vrl_config = VrlConfig {
         source: format!(r#"contains!(."{key}", "{needle}")"#),
@@ -1,4 +1,9 @@
-use std::{borrow::Cow, collections::HashMap, fmt};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    time::{Duration, Instant},
+};
 use vector_lib::config::LegacyKey;
 
 use crate::{
@@ -26,6 +31,7 @@ pub enum SampleMode {
         values: HashMap<Option<String>, f64>,
         hash_ratio_threshold: u64,
     },
+    Dynamic(DynamicSampler),
 }
 
 impl SampleMode {
@@ -47,10 +53,14 @@ impl SampleMode {
             // To do one option would be to convert the hash to a number between 0 and 1 and compare
             // to the ratio. However to address issues with precision, here the ratio is scaled to
             // meet the width of the type of the hash.
-            hash_ratio_threshold: (ratio * (u64::MAX as u128) as f64) as u64,
+            hash_ratio_threshold: hash_ratio_threshold(ratio),
         }
     }
 
+    pub fn new_dynamic(target_events_per_sec: f64, window: Duration) -> Self {
+        Self::Dynamic(DynamicSampler::new(target_events_per_sec, window))
+    }
+
     fn increment(&mut self, group_by_key: &Option<String>, value: &Option<Cow<'_, str>>) -> bool {
         let threshold_exceeded = match self {
             Self::Rate { rate, counters } => {
@@ -69,6 +79,9 @@ impl SampleMode {
                 };
                 increment >= 1.0
             }
+            Self::Dynamic(sampler) => {
+                return sampler.sample(group_by_key, value.as_ref().map(|value| value.as_bytes()));
+            }
         };
         if let Some(value) = value {
             self.hash_within_ratio(value.as_bytes())
@@ -85,8 +98,111 @@ impl SampleMode {
                 hash_ratio_threshold,
                 ..
             } => hash <= *hash_ratio_threshold,
+            Self::Dynamic(_) => {
+                unreachable!("dynamic sampling hashes values with the rate of their key")
+            }
         }
     }
+
+    /// Returns the value stored in the `sample_rate_key` of the events sampled for a key.
+    fn sample_rate(&self, group_by_key: &Option<String>) -> String {
+        match self {
+            Self::Dynamic(sampler) => sampler.sample_rate(group_by_key),
+            mode => mode.to_string(),
+        }
+    }
+}
+
+/// Scales a ratio to the width of the hashes of values, to compare them without losing precision.
+fn hash_ratio_threshold(ratio: f64) -> u64 {
+    (ratio * (u64::MAX as u128) as f64) as u64
+}
+
+/// Samples events to target a total number of output events per second.
+///
+/// The events of each key are counted over a window. At the end of each window, the budget of
+/// events of the next window is shared between the keys seen: keys with fewer events than their
+/// share keep all of them, and the rest of the budget is shared between the other keys, which are
+/// sampled down to their share. Keys not seen in the previous window are kept at full rate.
+#[derive(Clone, Debug)]
+pub struct DynamicSampler {
+    target_events_per_sec: f64,
+    window: Duration,
+    window_start: Instant,
+    counts: HashMap<Option<String>, u64>,
+    ratios: HashMap<Option<String>, f64>,
+    values: HashMap<Option<String>, f64>,
+}
+
+impl DynamicSampler {
+    pub fn new(target_events_per_sec: f64, window: Duration) -> Self {
+        Self {
+            target_events_per_sec,
+            window,
+            window_start: Instant::now(),
+            counts: HashMap::default(),
+            ratios: HashMap::default(),
+            values: HashMap::default(),
+        }
+    }
+
+    /// The ratio of events kept for a key.
+    pub(super) fn ratio(&self, group_by_key: &Option<String>) -> f64 {
+        self.ratios.get(group_by_key).copied().unwrap_or(1.0)
+    }
+
+    pub(super) fn sample(&mut self, group_by_key: &Option<String>, value: Option<&[u8]>) -> bool {
+        self.roll_window(Instant::now());
+        *self.counts.entry(group_by_key.clone()).or_default() += 1;
+
+        let ratio = self.ratio(group_by_key);
+        match value {
+            Some(value) => seahash::hash(value) <= hash_ratio_threshold(ratio),
+            None => {
+                let value = self
+                    .values
+                    .entry(group_by_key.clone())
+                    .or_insert(1.0 - ratio);
+                let increment = *value + ratio;
+                *value = if increment >= 1.0 {
+                    increment - 1.0
+                } else {
+                    increment
+                };
+                increment >= 1.0
+            }
+        }
+    }
+
+    /// Shares the budget of the next window between the keys once the current window is over.
+    pub(super) fn roll_window(&mut self, now: Instant) {
+        if now.saturating_duration_since(self.window_start) < self.window {
+            return;
+        }
+
+        let mut counts = self.counts.drain().collect::<Vec<_>>();
+        counts.sort_unstable_by_key(|(_, count)| *count);
+
+        let mut budget = self.target_events_per_sec * self.window.as_secs_f64();
+        let mut keys_left = counts.len();
+        self.ratios.clear();
+        for (key, count) in counts {
+            let count = count as f64;
+            let kept = count.min(budget / keys_left as f64);
+            self.ratios.insert(key, kept / count);
+            budget -= kept;
+            keys_left -= 1;
+        }
+
+        self.values.retain(|key, _| self.ratios.contains_key(key));
+        self.window_start = now;
+    }
+
+    /// The number of events each sampled event of a key stands for, to re-weight counts.
+    pub(super) fn sample_rate(&self, group_by_key: &Option<String>) -> String {
+        let rate = 1.0 / self.ratio(group_by_key);
+        ((rate * 1000.0).round() / 1000.0).to_string()
+    }
 }
 
 impl fmt::Display for SampleMode {
@@ -96,6 +212,7 @@ impl fmt::Display for SampleMode {
         match self {
             Self::Rate { rate, .. } => write!(f, "{rate}"),
             Self::Ratio { ratio, .. } => write!(f, "{ratio}"),
+            Self::Dynamic(sampler) => write!(f, "{}", sampler.target_events_per_sec),
         }
     }
 }
@@ -137,6 +254,7 @@ impl Sample {
         match self.rate {
             SampleMode::Rate { rate, .. } => 1.0f64 / rate as f64,
             SampleMode::Ratio { ratio, .. } => ratio,
+            SampleMode::Dynamic(_) => panic!("dynamic sampling has no fixed ratio"),
         }
     }
 }
@@ -208,11 +326,14 @@ impl FunctionTransform for Sample {
                             event,
                             Some(LegacyKey::Overwrite(path)),
                             path,
-                            self.rate.to_string(),
+                            self.rate.sample_rate(&group_by_key),
                         );
                     }
                     Event::Trace(ref mut event) => {
-                        event.insert(&OwnedTargetPath::event(path.clone()), self.rate.to_string());
+                        event.insert(
+                            &OwnedTargetPath::event(path.clone()),
+                            self.rate.sample_rate(&group_by_key),
+                        );
                     }
                     Event::Metric(_) => panic!("component can never receive metric events"),
                 };
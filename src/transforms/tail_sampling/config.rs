@@ -0,0 +1,235 @@
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use serde_with::serde_as;
+use vector_lib::config::{clone_input_definitions, LogNamespace};
+use vector_lib::configurable::configurable_component;
+
+use crate::{
+    conditions::AnyCondition,
+    config::{
+        DataType, GenerateConfig, Input, OutputId, TransformConfig, TransformContext,
+        TransformOutput,
+    },
+    schema,
+    transforms::Transform,
+};
+
+use super::transform::TailSampling;
+
+/// Configuration for the `tail_sampling` transform.
+#[serde_as]
+#[configurable_component(transform(
+    "tail_sampling",
+    "Sample whole traces once their spans have been received, based on a set of policies."
+))]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct TailSamplingConfig {
+    /// The policies deciding which traces are kept.
+    ///
+    /// A trace is kept if any of the policies samples it, and dropped otherwise. Policies are
+    /// evaluated in order, and the first one sampling a trace is reported in the metrics.
+    pub policies: Vec<PolicyConfig>,
+
+    /// The maximum time to wait for the spans of a trace after receiving its first span, in seconds.
+    ///
+    /// The trace is decided on with the spans received so far once this time has elapsed.
+    #[serde(default = "default_decision_wait_secs")]
+    #[serde_as(as = "serde_with::DurationSecondsWithFrac<f64>")]
+    #[configurable(metadata(docs::human_name = "Decision Wait"))]
+    pub decision_wait_secs: Duration,
+
+    /// Whether to decide on a trace as soon as its root span is received.
+    ///
+    /// Root spans are the spans without a parent, and usually end last. When disabled, traces are
+    /// always buffered for `decision_wait_secs`.
+    #[serde(default = "crate::serde::default_true")]
+    pub decide_on_root_span: bool,
+
+    /// The maximum number of traces buffered while waiting for their spans.
+    ///
+    /// When this limit is reached, the oldest trace is decided on early with the spans received so far.
+    #[serde(default = "default_max_traces")]
+    pub max_traces: NonZeroUsize,
+
+    /// The maximum size of the buffered events, in bytes.
+    ///
+    /// When this limit is reached, the oldest traces are decided on early with the spans received so far.
+    #[configurable(metadata(docs::type_unit = "bytes"))]
+    pub max_bytes: Option<NonZeroUsize>,
+
+    /// The number of recent decisions remembered to handle spans received after their trace was
+    /// decided on.
+    ///
+    /// Late spans of a kept trace are forwarded, and late spans of a dropped trace are dropped. Late
+    /// spans of traces no longer remembered start a new trace.
+    #[serde(default = "default_decision_cache_size")]
+    pub decision_cache_size: NonZeroUsize,
+}
+
+/// A policy sampling traces.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[configurable(metadata(docs::enum_tag_description = "The type of policy."))]
+pub enum PolicyConfig {
+    /// Samples traces with at least one span in error.
+    ///
+    /// Datadog spans are in error when their `error` field is non-zero, and OpenTelemetry spans when
+    /// their status code is `2`.
+    Error,
+
+    /// Samples traces lasting longer than a threshold, from the start of their first span to the end
+    /// of their last span.
+    Latency(LatencyPolicyConfig),
+
+    /// Samples traces with at least one event matching a condition.
+    Condition(ConditionPolicyConfig),
+
+    /// Samples a ratio of traces, chosen by trace ID.
+    ///
+    /// The choice only depends on the trace ID, so that all instances of this transform make the
+    /// same decision for a trace.
+    Probabilistic(ProbabilisticPolicyConfig),
+}
+
+/// Configuration of the `latency` policy.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LatencyPolicyConfig {
+    /// The duration above which traces are sampled, in milliseconds.
+    #[configurable(metadata(docs::examples = 5000))]
+    pub threshold_ms: u64,
+}
+
+/// Configuration of the `condition` policy.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ConditionPolicyConfig {
+    /// The condition matched against the events of the trace.
+    ///
+    /// Datadog traces are received as events holding a chunk of spans, while OpenTelemetry traces
+    /// are received as an event per span.
+    pub condition: AnyCondition,
+}
+
+/// Configuration of the `probabilistic` policy.
+#[configurable_component]
+#[derive(Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ProbabilisticPolicyConfig {
+    /// The ratio of traces sampled.
+    #[configurable(metadata(docs::examples = 0.1))]
+    #[configurable(validation(range(min = 0.0, max = 1.0)))]
+    pub ratio: f64,
+}
+
+const fn default_decision_wait_secs() -> Duration {
+    Duration::from_secs(30)
+}
+
+const fn default_max_traces() -> NonZeroUsize {
+    unsafe { NonZeroUsize::new_unchecked(10_000) }
+}
+
+const fn default_decision_cache_size() -> NonZeroUsize {
+    unsafe { NonZeroUsize::new_unchecked(50_000) }
+}
+
+impl TailSamplingConfig {
+    fn validate_options(&self) -> crate::Result<()> {
+        if self.policies.is_empty() {
+            return Err("at least one policy must be provided".into());
+        }
+        if self.decision_wait_secs.is_zero() {
+            return Err("`decision_wait_secs` must be non-zero".into());
+        }
+        for policy in &self.policies {
+            if let PolicyConfig::Probabilistic(config) = policy {
+                if !(0.0..=1.0).contains(&config.ratio) {
+                    return Err(format!(
+                        "the ratio of the `probabilistic` policy must be between 0 and 1, value: {}",
+                        config.ratio
+                    )
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GenerateConfig for TailSamplingConfig {
+    fn generate_config() -> toml::Value {
+        toml::from_str(
+            r#"decision_wait_secs = 30.0
+
+            [[policies]]
+            type = "error"
+
+            [[policies]]
+            type = "latency"
+            threshold_ms = 5000
+
+            [[policies]]
+            type = "probabilistic"
+            ratio = 0.1
+        "#,
+        )
+        .unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+#[typetag::serde(name = "tail_sampling")]
+impl TransformConfig for TailSamplingConfig {
+    async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
+        self.validate_options()?;
+        TailSampling::new(self, &context.enrichment_tables).map(Transform::event_task)
+    }
+
+    fn input(&self) -> Input {
+        Input::trace()
+    }
+
+    fn validate(&self, _: &schema::Definition) -> Result<(), Vec<String>> {
+        self.validate_options()
+            .map_err(|error| vec![error.to_string()])
+    }
+
+    fn outputs(
+        &self,
+        _: vector_lib::enrichment::TableRegistry,
+        input_definitions: &[(OutputId, schema::Definition)],
+        _: LogNamespace,
+    ) -> Vec<TransformOutput> {
+        vec![TransformOutput::new(
+            DataType::Trace,
+            clone_input_definitions(input_definitions),
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_config() {
+        crate::test_util::test_generate_config::<TailSamplingConfig>();
+    }
+
+    #[test]
+    fn rejects_invalid_policies() {
+        let mut config = toml::from_str::<TailSamplingConfig>("policies = []").unwrap();
+        assert!(config.validate_options().is_err());
+
+        config.policies = vec![PolicyConfig::Probabilistic(ProbabilisticPolicyConfig {
+            ratio: 1.5,
+        })];
+        assert!(config.validate_options().is_err());
+    }
+}
@@ -0,0 +1,464 @@
+use std::num::NonZeroUsize;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use lru::LruCache;
+use vector_lib::stream::expiration_map::{map_with_expiration, Emitter};
+use vector_lib::ByteSizeOf;
+use vrl::event_path;
+use vrl::value::{ObjectMap, Value};
+
+use crate::{
+    conditions::Condition,
+    event::{Event, TraceEvent},
+    internal_events::{
+        TailSamplingLateEventsDropped, TailSamplingTraceDropped, TailSamplingTraceEvicted,
+        TailSamplingTraceSampled,
+    },
+    transforms::TaskTransform,
+};
+
+use super::config::{PolicyConfig, TailSamplingConfig};
+
+/// The OpenTelemetry status code of spans in error.
+const STATUS_CODE_ERROR: i64 = 2;
+
+/// The longest interval between checks for traces done waiting for their spans.
+const MAX_EXPIRATION_INTERVAL: Duration = Duration::from_secs(1);
+
+enum Policy {
+    Error,
+    Latency(chrono::Duration),
+    Condition(Condition),
+    Probabilistic(u64),
+}
+
+impl Policy {
+    fn new(
+        config: &PolicyConfig,
+        enrichment_tables: &vector_lib::enrichment::TableRegistry,
+    ) -> crate::Result<Self> {
+        Ok(match config {
+            PolicyConfig::Error => Self::Error,
+            PolicyConfig::Latency(config) => Self::Latency(chrono::Duration::milliseconds(
+                i64::try_from(config.threshold_ms).unwrap_or(i64::MAX),
+            )),
+            PolicyConfig::Condition(config) => {
+                Self::Condition(config.condition.build(enrichment_tables)?)
+            }
+            // Trace IDs are sampled when their hash falls below the ratio of the hash space.
+            PolicyConfig::Probabilistic(config) => {
+                Self::Probabilistic((config.ratio * u64::MAX as f64) as u64)
+            }
+        })
+    }
+
+    const fn name(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Latency(_) => "latency",
+            Self::Condition(_) => "condition",
+            Self::Probabilistic(_) => "probabilistic",
+        }
+    }
+
+    /// Evaluates the policy against the events of a trace, returning whether it samples the trace.
+    fn samples(&self, trace_id: &str, events: &mut Vec<TraceEvent>) -> bool {
+        match self {
+            Self::Error => events.iter().flat_map(spans).any(is_error),
+            Self::Latency(threshold) => {
+                duration(events).is_some_and(|duration| duration > *threshold)
+            }
+            Self::Condition(condition) => {
+                let mut matched = false;
+                *events = std::mem::take(events)
+                    .into_iter()
+                    .map(|trace| {
+                        if matched {
+                            return trace;
+                        }
+                        let (result, event) = condition.check(Event::Trace(trace));
+                        matched = result;
+                        event.into_trace()
+                    })
+                    .collect();
+                matched
+            }
+            Self::Probabilistic(threshold) => seahash::hash(trace_id.as_bytes()) <= *threshold,
+        }
+    }
+}
+
+/// The spans of a trace event.
+///
+/// Datadog trace events hold a chunk of spans in their `spans` field, while OpenTelemetry trace
+/// events are a single span.
+fn spans(trace: &TraceEvent) -> Vec<&ObjectMap> {
+    match trace.get(event_path!("spans")) {
+        Some(Value::Array(spans)) => spans.iter().filter_map(Value::as_object).collect(),
+        _ => vec![trace.as_map()],
+    }
+}
+
+fn trace_id(trace: &TraceEvent) -> Option<String> {
+    trace
+        .get(event_path!("trace_id"))
+        .or_else(|| {
+            spans(trace)
+                .into_iter()
+                .find_map(|span| span.get("trace_id"))
+        })
+        .filter(|id| !matches!(id, Value::Null))
+        .map(|id| id.to_string_lossy().into_owned())
+}
+
+fn is_root(span: &ObjectMap) -> bool {
+    match (span.get("parent_span_id"), span.get("parent_id")) {
+        (Some(Value::Bytes(parent)), _) => parent.is_empty(),
+        (_, Some(Value::Integer(parent))) => *parent == 0,
+        _ => false,
+    }
+}
+
+fn is_error(span: &ObjectMap) -> bool {
+    match span.get("error") {
+        Some(Value::Integer(error)) => *error != 0,
+        Some(Value::Boolean(error)) => *error,
+        _ => matches!(
+            span.get("status"),
+            Some(Value::Object(status))
+                if status.get("code") == Some(&Value::Integer(STATUS_CODE_ERROR))
+        ),
+    }
+}
+
+/// The start and end of a span.
+fn span_bounds(span: &ObjectMap) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    match (
+        span.get("start_time_unix_nano"),
+        span.get("end_time_unix_nano"),
+    ) {
+        (Some(Value::Timestamp(start)), Some(Value::Timestamp(end))) => Some((*start, *end)),
+        _ => match (span.get("start"), span.get("duration")) {
+            (Some(Value::Timestamp(start)), Some(Value::Integer(duration))) => {
+                Some((*start, *start + chrono::Duration::nanoseconds(*duration)))
+            }
+            _ => None,
+        },
+    }
+}
+
+/// The duration of a trace, from the start of its first span to the end of its last span.
+fn duration(events: &[TraceEvent]) -> Option<chrono::Duration> {
+    let (start, end) = events
+        .iter()
+        .flat_map(spans)
+        .filter_map(span_bounds)
+        .reduce(|(start, end), (span_start, span_end)| {
+            (start.min(span_start), end.max(span_end))
+        })?;
+    Some(end - start)
+}
+
+/// The events of a trace waiting for a decision.
+struct PendingTrace {
+    events: Vec<TraceEvent>,
+    first_seen: Instant,
+    byte_size: usize,
+}
+
+pub struct TailSampling {
+    policies: Vec<Policy>,
+    decision_wait: Duration,
+    decide_on_root_span: bool,
+    max_traces: usize,
+    max_bytes: Option<usize>,
+    /// The pending traces, least recently seen first as entries are never promoted.
+    pending: LruCache<String, PendingTrace>,
+    pending_bytes: usize,
+    /// Whether the recently decided traces were sampled.
+    decisions: LruCache<String, bool>,
+}
+
+impl TailSampling {
+    pub fn new(
+        config: &TailSamplingConfig,
+        enrichment_tables: &vector_lib::enrichment::TableRegistry,
+    ) -> crate::Result<Self> {
+        Ok(Self {
+            policies: config
+                .policies
+                .iter()
+                .map(|policy| Policy::new(policy, enrichment_tables))
+                .collect::<crate::Result<_>>()?,
+            decision_wait: config.decision_wait_secs,
+            decide_on_root_span: config.decide_on_root_span,
+            max_traces: config.max_traces.get(),
+            max_bytes: config.max_bytes.map(NonZeroUsize::get),
+            pending: LruCache::unbounded(),
+            pending_bytes: 0,
+            decisions: LruCache::new(config.decision_cache_size),
+        })
+    }
+
+    fn transform_one(&mut self, event: Event, now: Instant, emitter: &mut Emitter<Event>) {
+        let trace = match event {
+            Event::Trace(trace) => trace,
+            event => return emitter.emit(event),
+        };
+        let Some(trace_id) = trace_id(&trace) else {
+            return emitter.emit(Event::Trace(trace));
+        };
+
+        if let Some(&sampled) = self.decisions.get(&trace_id) {
+            if sampled {
+                emitter.emit(Event::Trace(trace));
+            } else {
+                emit!(TailSamplingLateEventsDropped { count: 1 });
+            }
+            return;
+        }
+
+        let complete = self.decide_on_root_span && spans(&trace).into_iter().any(is_root);
+        let byte_size = trace.size_of();
+        self.pending_bytes += byte_size;
+        match self.pending.peek_mut(&trace_id) {
+            Some(pending) => {
+                pending.events.push(trace);
+                pending.byte_size += byte_size;
+            }
+            None => {
+                self.pending.push(
+                    trace_id.clone(),
+                    PendingTrace {
+                        events: vec![trace],
+                        first_seen: now,
+                        byte_size,
+                    },
+                );
+            }
+        }
+
+        if complete {
+            if let Some(pending) = self.pending.pop(&trace_id) {
+                self.decide(trace_id, pending, emitter);
+            }
+        }
+
+        while self.pending.len() > self.max_traces
+            || self.max_bytes.is_some_and(|max| self.pending_bytes > max)
+        {
+            let Some((trace_id, pending)) = self.pending.pop_lru() else {
+                break;
+            };
+            emit!(TailSamplingTraceEvicted);
+            self.decide(trace_id, pending, emitter);
+        }
+    }
+
+    fn decide(&mut self, trace_id: String, pending: PendingTrace, emitter: &mut Emitter<Event>) {
+        self.pending_bytes -= pending.byte_size;
+
+        let mut events = pending.events;
+        let policy = self
+            .policies
+            .iter()
+            .find(|policy| policy.samples(&trace_id, &mut events));
+        match policy {
+            Some(policy) => {
+                emit!(TailSamplingTraceSampled {
+                    policy: policy.name()
+                });
+                events
+                    .into_iter()
+                    .for_each(|trace| emitter.emit(Event::Trace(trace)));
+            }
+            None => emit!(TailSamplingTraceDropped {
+                count: events.len()
+            }),
+        }
+        self.decisions.put(trace_id, policy.is_some());
+    }
+
+    /// Decides on the traces done waiting for their spans.
+    fn flush_expired(&mut self, now: Instant, emitter: &mut Emitter<Event>) {
+        while self.pending.peek_lru().is_some_and(|(_, pending)| {
+            now.saturating_duration_since(pending.first_seen) >= self.decision_wait
+        }) {
+            let (trace_id, pending) = self.pending.pop_lru().expect("trace was peeked");
+            self.decide(trace_id, pending, emitter);
+        }
+    }
+
+    fn flush_all(&mut self, emitter: &mut Emitter<Event>) {
+        while let Some((trace_id, pending)) = self.pending.pop_lru() {
+            self.decide(trace_id, pending, emitter);
+        }
+    }
+}
+
+impl TaskTransform<Event> for TailSampling {
+    fn transform(
+        self: Box<Self>,
+        input_rx: Pin<Box<dyn Stream<Item = Event> + Send>>,
+    ) -> Pin<Box<dyn Stream<Item = Event> + Send>>
+    where
+        Self: 'static,
+    {
+        let expiration_interval = self.decision_wait.min(MAX_EXPIRATION_INTERVAL);
+        Box::pin(map_with_expiration(
+            self,
+            input_rx,
+            expiration_interval,
+            |me, event, emitter| me.transform_one(event, Instant::now(), emitter),
+            |me, emitter| me.flush_expired(Instant::now(), emitter),
+            |me, emitter| me.flush_all(emitter),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use futures::StreamExt;
+
+    use super::*;
+
+    fn build(config: &str) -> TailSampling {
+        let config = toml::from_str::<TailSamplingConfig>(config).unwrap();
+        TailSampling::new(&config, &Default::default()).unwrap()
+    }
+
+    async fn run(transform: TailSampling, events: Vec<Event>) -> Vec<Event> {
+        Box::new(transform)
+            .transform(Box::pin(futures::stream::iter(events)))
+            .collect()
+            .await
+    }
+
+    fn otel_span(trace_id: &str, span_id: &str, parent: &str, status_code: i64) -> Event {
+        TraceEvent::from(ObjectMap::from([
+            ("trace_id".into(), Value::from(trace_id)),
+            ("span_id".into(), Value::from(span_id)),
+            ("parent_span_id".into(), Value::from(parent)),
+            (
+                "status".into(),
+                Value::from(ObjectMap::from([("code".into(), Value::from(status_code))])),
+            ),
+        ]))
+        .into()
+    }
+
+    fn datadog_chunk(trace_id: i64, duration_ms: i64) -> Event {
+        let span = |span_id: i64, parent_id: i64, start_ms: i64| {
+            Value::from(ObjectMap::from([
+                ("trace_id".into(), Value::from(trace_id)),
+                ("span_id".into(), Value::from(span_id)),
+                ("parent_id".into(), Value::from(parent_id)),
+                (
+                    "start".into(),
+                    Value::from(Utc.timestamp_millis_opt(start_ms).unwrap()),
+                ),
+                ("duration".into(), Value::from(1_000_000_i64)),
+                ("error".into(), Value::from(0)),
+            ]))
+        };
+        TraceEvent::from(ObjectMap::from([(
+            "spans".into(),
+            Value::Array(vec![span(1, 0, 0), span(2, 1, duration_ms - 1)]),
+        )]))
+        .into()
+    }
+
+    #[tokio::test]
+    async fn keeps_traces_with_errors() {
+        let transform = build(
+            r#"
+            [[policies]]
+            type = "error"
+            "#,
+        );
+        let failed = vec![otel_span("a", "2", "1", 2), otel_span("a", "1", "", 0)];
+        let succeeded = vec![otel_span("b", "2", "1", 0), otel_span("b", "1", "", 0)];
+        // Late spans follow the decision made on their trace.
+        let late = vec![otel_span("a", "3", "1", 0), otel_span("b", "3", "1", 2)];
+
+        let events = [failed.clone(), succeeded, late.clone()].concat();
+        assert_eq!(
+            run(transform, events).await,
+            [failed, vec![late[0].clone()]].concat()
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_slow_traces() {
+        let transform = build(
+            r#"
+            [[policies]]
+            type = "latency"
+            threshold_ms = 100
+            "#,
+        );
+        let slow = datadog_chunk(1, 500);
+        let events = vec![datadog_chunk(2, 50), slow.clone()];
+        assert_eq!(run(transform, events).await, vec![slow]);
+    }
+
+    #[tokio::test]
+    async fn samples_by_condition_and_ratio() {
+        let transform = build(
+            r#"
+            [[policies]]
+            type = "condition"
+            condition = '.span_id == "important"'
+
+            [[policies]]
+            type = "probabilistic"
+            ratio = 0.0
+            "#,
+        );
+        let important = otel_span("a", "important", "", 0);
+        let events = vec![important.clone(), otel_span("b", "other", "", 0)];
+        assert_eq!(run(transform, events).await, vec![important]);
+
+        let transform = build(
+            r#"
+            [[policies]]
+            type = "probabilistic"
+            ratio = 1.0
+            "#,
+        );
+        let events = vec![otel_span("a", "1", "", 0), otel_span("b", "1", "", 0)];
+        assert_eq!(run(transform, events.clone()).await, events);
+    }
+
+    #[tokio::test]
+    async fn decides_early_when_full_or_expired() {
+        let mut transform = build(
+            r#"
+            decide_on_root_span = false
+            max_traces = 1
+
+            [[policies]]
+            type = "error"
+            "#,
+        );
+        let now = Instant::now();
+        let mut emitter = Emitter::new();
+        // The first trace is decided on without its failed span when the second one is buffered.
+        transform.transform_one(otel_span("a", "1", "", 0), now, &mut emitter);
+        transform.transform_one(otel_span("b", "1", "", 2), now, &mut emitter);
+        transform.transform_one(otel_span("a", "2", "1", 2), now, &mut emitter);
+        assert_eq!(transform.decisions.peek("a"), Some(&false));
+        assert_eq!(transform.pending.len(), 1);
+
+        transform.flush_expired(now + Duration::from_secs(29), &mut emitter);
+        assert_eq!(transform.pending.len(), 1);
+        transform.flush_expired(now + Duration::from_secs(30), &mut emitter);
+        assert!(transform.pending.is_empty());
+        assert_eq!(transform.pending_bytes, 0);
+        assert_eq!(transform.decisions.peek("b"), Some(&true));
+    }
+}
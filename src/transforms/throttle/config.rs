@@ -1,13 +1,16 @@
 use governor::clock;
 use serde_with::serde_as;
-use std::time::Duration;
+use std::{collections::HashMap, num::NonZeroU32, time::Duration};
 use vector_lib::config::{clone_input_definitions, LogNamespace};
 use vector_lib::configurable::configurable_component;
 
 use super::transform::Throttle;
 use crate::{
     conditions::AnyCondition,
-    config::{DataType, Input, OutputId, TransformConfig, TransformContext, TransformOutput},
+    config::{
+        DataType, Input, OutputId, TransformConfig, TransformContext, TransformOutput,
+        DROPPED_OUTPUT,
+    },
     schema,
     template::Template,
     transforms::Transform,
@@ -31,6 +34,46 @@ pub struct ThrottleInternalMetricsConfig {
     pub emit_events_discarded_per_key: bool,
 }
 
+/// Thresholds of a quota, applied per configured `window_secs`.
+#[configurable_component]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdsConfig {
+    /// The number of events allowed per configured `window_secs`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[configurable(metadata(docs::examples = 1000))]
+    pub threshold: Option<NonZeroU32>,
+
+    /// The number of bytes allowed per configured `window_secs`.
+    ///
+    /// The size of an event is its estimated JSON-encoded size.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[configurable(metadata(docs::examples = 1048576))]
+    pub threshold_bytes: Option<NonZeroU32>,
+}
+
+/// An enrichment table holding the thresholds of keys.
+#[configurable_component]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ThresholdsTableConfig {
+    /// The name of the enrichment table.
+    ///
+    /// The `threshold` and `threshold_bytes` columns of the row found for a key replace the
+    /// thresholds of that key. Missing or empty columns leave the corresponding threshold unchanged.
+    #[configurable(metadata(docs::examples = "tenant_tiers"))]
+    pub table: String,
+
+    /// The column of the enrichment table holding the keys.
+    #[serde(default = "default_key_column")]
+    #[configurable(metadata(docs::examples = "tenant"))]
+    pub key_column: String,
+}
+
+fn default_key_column() -> String {
+    "key".to_string()
+}
+
 /// Configuration for the `throttle` transform.
 #[serde_as]
 #[configurable_component(transform("throttle", "Rate limit logs passing through a topology."))]
@@ -54,21 +97,71 @@ pub struct ThrottleConfig {
     #[configurable(metadata(docs::examples = "{{ message }}", docs::examples = "{{ hostname }}",))]
     pub key_field: Option<Template>,
 
+    /// The number of bytes allowed for a given bucket per configured `window_secs`.
+    ///
+    /// The size of an event is its estimated JSON-encoded size. Events are only allowed if they are
+    /// within both `threshold` and `threshold_bytes`.
+    #[serde(default)]
+    #[configurable(metadata(docs::examples = 1048576))]
+    pub threshold_bytes: Option<NonZeroU32>,
+
+    /// Thresholds of specific keys, replacing `threshold` and `threshold_bytes` for these keys.
+    ///
+    /// Thresholds left unset keep their default value. These take precedence over the thresholds
+    /// found in the `thresholds_table`.
+    #[serde(default)]
+    #[configurable(metadata(
+        docs::additional_props_description = "The thresholds of a key.",
+        docs::examples = "example_key_thresholds()"
+    ))]
+    pub key_thresholds: HashMap<String, ThresholdsConfig>,
+
+    #[configurable(derived)]
+    #[serde(default)]
+    pub thresholds_table: Option<ThresholdsTableConfig>,
+
+    /// A quota shared by all keys, applied on top of the quota of each key.
+    ///
+    /// Events within the quota of their key are still throttled once this quota is exceeded.
+    /// Events throttled by this quota count towards the quota of their key.
+    #[configurable(derived)]
+    #[serde(default)]
+    pub global: Option<ThresholdsConfig>,
+
     /// A logical condition used to exclude events from sampling.
     pub exclude: Option<AnyCondition>,
 
+    /// Whether to send throttled events to the `dropped` output instead of discarding them.
+    #[serde(default)]
+    pub reroute_dropped: bool,
+
     #[configurable(derived)]
     #[serde(default)]
     pub internal_metrics: ThrottleInternalMetricsConfig,
 }
 
+fn example_key_thresholds() -> HashMap<String, ThresholdsConfig> {
+    HashMap::from([(
+        "premium".to_string(),
+        ThresholdsConfig {
+            threshold: NonZeroU32::new(10000),
+            threshold_bytes: None,
+        },
+    )])
+}
+
 impl_generate_config_from_default!(ThrottleConfig);
 
 #[async_trait::async_trait]
 #[typetag::serde(name = "throttle")]
 impl TransformConfig for ThrottleConfig {
     async fn build(&self, context: &TransformContext) -> crate::Result<Transform> {
-        Throttle::new(self, context, clock::MonotonicClock).map(Transform::event_task)
+        let throttle = Throttle::new(self, context, clock::MonotonicClock)?;
+        Ok(if self.reroute_dropped {
+            Transform::synchronous(throttle)
+        } else {
+            Transform::event_task(throttle)
+        })
     }
 
     fn input(&self) -> Input {
@@ -82,10 +175,17 @@ impl TransformConfig for ThrottleConfig {
         _: LogNamespace,
     ) -> Vec<TransformOutput> {
         // The event is not modified, so the definition is passed through as-is
-        vec![TransformOutput::new(
+        let mut outputs = vec![TransformOutput::new(
             DataType::Log,
             clone_input_definitions(input_definitions),
-        )]
+        )];
+        if self.reroute_dropped {
+            outputs.push(
+                TransformOutput::new(DataType::Log, clone_input_definitions(input_definitions))
+                    .with_port(DROPPED_OUTPUT),
+            );
+        }
+        outputs
     }
 }
 
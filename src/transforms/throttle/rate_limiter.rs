@@ -3,6 +3,7 @@ use governor::middleware::NoOpMiddleware;
 use governor::state::keyed::DashMapStateStore;
 use governor::{Quota, RateLimiter};
 use std::hash::Hash;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio;
@@ -44,6 +45,11 @@ where
     pub fn check_key(&self, key: &K) -> bool {
         self.rate_limiter.check_key(key).is_ok()
     }
+
+    /// Checks whether `n` cells are available for the key at once, such as the bytes of an event.
+    pub fn check_key_n(&self, key: &K, n: NonZeroU32) -> bool {
+        matches!(self.rate_limiter.check_key_n(key, n), Ok(Ok(())))
+    }
 }
 
 impl<K, C> Drop for RateLimiterRunner<K, C>
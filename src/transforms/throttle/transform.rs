@@ -2,30 +2,182 @@ use async_stream::stream;
 use futures::{Stream, StreamExt};
 use governor::{clock, Quota};
 use snafu::Snafu;
+use std::collections::HashMap;
 use std::hash::Hash;
+use std::sync::Arc;
 use std::{num::NonZeroU32, pin::Pin, time::Duration};
+use vector_lib::enrichment::{Case, Condition as TableCondition, IndexHandle, TableSearch};
+use vector_lib::EstimatedJsonEncodedSizeOf;
+use vrl::value::Value;
 
 use super::{
-    config::{ThrottleConfig, ThrottleInternalMetricsConfig},
+    config::{
+        ThresholdsConfig, ThresholdsTableConfig, ThrottleConfig, ThrottleInternalMetricsConfig,
+    },
     rate_limiter::RateLimiterRunner,
 };
 use crate::{
     conditions::Condition,
-    config::TransformContext,
+    config::{TransformContext, DROPPED_OUTPUT},
     event::Event,
     internal_events::{TemplateRenderingError, ThrottleEventDiscarded},
     template::Template,
-    transforms::TaskTransform,
+    transforms::{SyncTransform, TaskTransform, TransformOutputsBuf},
 };
 
+/// The thresholds of a quota. Unset thresholds are not limited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Thresholds {
+    events: Option<NonZeroU32>,
+    bytes: Option<NonZeroU32>,
+}
+
+impl Thresholds {
+    /// Replaces these thresholds with the ones set in `config`.
+    fn with(self, config: &ThresholdsConfig) -> Self {
+        Self {
+            events: config.threshold.or(self.events),
+            bytes: config.threshold_bytes.or(self.bytes),
+        }
+    }
+}
+
+/// An enrichment table holding the thresholds of keys.
+#[derive(Clone)]
+struct ThresholdsTable {
+    search: TableSearch,
+    table: String,
+    key_column: String,
+    index: IndexHandle,
+}
+
+impl ThresholdsTable {
+    fn new(config: &ThresholdsTableConfig, context: &TransformContext) -> crate::Result<Self> {
+        let mut tables = context.enrichment_tables.clone();
+        let index = tables.add_index(&config.table, Case::Sensitive, &[&config.key_column])?;
+        Ok(Self {
+            search: tables.as_readonly(),
+            table: config.table.clone(),
+            key_column: config.key_column.clone(),
+            index,
+        })
+    }
+
+    /// Looks up the thresholds of a key, keeping the `defaults` for the columns missing a value.
+    fn find(&self, key: &str, defaults: Thresholds) -> Thresholds {
+        let condition = [TableCondition::Equals {
+            field: &self.key_column,
+            value: Value::from(key),
+        }];
+        let Ok(row) = self.search.find_table_row(
+            &self.table,
+            Case::Sensitive,
+            &condition,
+            None,
+            None,
+            Some(self.index),
+        ) else {
+            return defaults;
+        };
+        let threshold = |column: &str| match row.get(column) {
+            Some(Value::Integer(value)) => u32::try_from(*value).ok().and_then(NonZeroU32::new),
+            _ => None,
+        };
+        Thresholds {
+            events: threshold("threshold").or(defaults.events),
+            bytes: threshold("threshold_bytes").or(defaults.bytes),
+        }
+    }
+}
+
+/// Builds the quota allowing `threshold` cells per `window`, or `None` if it can't be enforced.
+fn quota(window: Duration, threshold: NonZeroU32) -> Option<Quota> {
+    Quota::with_period(Duration::from_secs_f64(
+        window.as_secs_f64() / f64::from(threshold.get()),
+    ))
+    .map(|quota| quota.allow_burst(threshold))
+}
+
+/// The rate limiters enforcing quotas, started on demand for each distinct threshold.
+#[derive(Clone)]
+struct QuotaLimiters<K, C>
+where
+    K: Hash + Eq + Clone,
+    C: clock::Clock,
+{
+    window: Duration,
+    clock: C,
+    events: HashMap<NonZeroU32, Arc<RateLimiterRunner<K, C>>>,
+    bytes: HashMap<NonZeroU32, Arc<RateLimiterRunner<K, C>>>,
+}
+
+impl<K, C> QuotaLimiters<K, C>
+where
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+    C: clock::Clock + Clone + Send + Sync + 'static,
+{
+    fn new(window: Duration, clock: C) -> Self {
+        Self {
+            window,
+            clock,
+            events: HashMap::new(),
+            bytes: HashMap::new(),
+        }
+    }
+
+    /// Checks an event of `byte_size` bytes against the thresholds of its key.
+    ///
+    /// The limiters can't be checked without consuming their quota, so the bytes are checked first,
+    /// and an event bigger than the bytes left doesn't consume the event quota of the key.
+    fn check(&mut self, key: &K, thresholds: Thresholds, byte_size: usize) -> bool {
+        if let Some(threshold) = thresholds.bytes {
+            let limiter = Self::limiter(&mut self.bytes, self.window, &self.clock, threshold);
+            let cells = u32::try_from(byte_size)
+                .ok()
+                .and_then(NonZeroU32::new)
+                .unwrap_or(NonZeroU32::MIN);
+            // Events bigger than the whole threshold are never allowed.
+            if limiter.is_some_and(|limiter| !limiter.check_key_n(key, cells)) {
+                return false;
+            }
+        }
+        if let Some(threshold) = thresholds.events {
+            let limiter = Self::limiter(&mut self.events, self.window, &self.clock, threshold);
+            if limiter.is_some_and(|limiter| !limiter.check_key(key)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn limiter<'a>(
+        limiters: &'a mut HashMap<NonZeroU32, Arc<RateLimiterRunner<K, C>>>,
+        window: Duration,
+        clock: &C,
+        threshold: NonZeroU32,
+    ) -> Option<&'a RateLimiterRunner<K, C>> {
+        if !limiters.contains_key(&threshold) {
+            let limiter =
+                RateLimiterRunner::start(quota(window, threshold)?, clock.clone(), window);
+            limiters.insert(threshold, Arc::new(limiter));
+        }
+        limiters.get(&threshold).map(Arc::as_ref)
+    }
+}
+
 #[derive(Clone)]
 pub struct Throttle<C: clock::Clock<Instant = I>, I: clock::Reference> {
-    pub quota: Quota,
     pub flush_keys_interval: Duration,
     key_field: Option<Template>,
     exclude: Option<Condition>,
     pub clock: C,
     internal_metrics: ThrottleInternalMetricsConfig,
+    thresholds: Thresholds,
+    key_thresholds: HashMap<String, Thresholds>,
+    thresholds_table: Option<ThresholdsTable>,
+    global: Option<Thresholds>,
+    key_limiters: QuotaLimiters<Option<String>, C>,
+    global_limiters: QuotaLimiters<(), C>,
 }
 
 impl<C, I> Throttle<C, I>
@@ -44,43 +196,113 @@ where
             Some(threshold) => threshold,
             None => return Err(Box::new(ConfigError::NonZero)),
         };
-
-        let quota = match Quota::with_period(Duration::from_secs_f64(
-            flush_keys_interval.as_secs_f64() / f64::from(threshold.get()),
-        )) {
-            Some(quota) => quota.allow_burst(threshold),
-            None => return Err(Box::new(ConfigError::NonZero)),
-        };
+        if quota(flush_keys_interval, threshold).is_none() {
+            return Err(Box::new(ConfigError::NonZero));
+        }
         let exclude = config
             .exclude
             .as_ref()
             .map(|condition| condition.build(&context.enrichment_tables))
             .transpose()?;
 
+        let thresholds = Thresholds {
+            events: Some(threshold),
+            bytes: config.threshold_bytes,
+        };
+        let thresholds_table = config
+            .thresholds_table
+            .as_ref()
+            .map(|table| ThresholdsTable::new(table, context))
+            .transpose()?;
+
         Ok(Self {
-            quota,
-            clock,
             flush_keys_interval,
             key_field: config.key_field.clone(),
             exclude,
             internal_metrics: config.internal_metrics.clone(),
+            thresholds,
+            key_thresholds: config
+                .key_thresholds
+                .iter()
+                .map(|(key, config)| (key.clone(), thresholds.with(config)))
+                .collect(),
+            thresholds_table,
+            global: config
+                .global
+                .as_ref()
+                .map(|config| Thresholds::default().with(config)),
+            key_limiters: QuotaLimiters::new(flush_keys_interval, clock.clone()),
+            global_limiters: QuotaLimiters::new(flush_keys_interval, clock.clone()),
+            clock,
         })
     }
 
-    #[must_use]
-    pub fn start_rate_limiter<K>(&self) -> RateLimiterRunner<K, C>
-    where
-        K: Hash + Eq + Clone + Send + Sync + 'static,
-    {
-        RateLimiterRunner::start(self.quota, self.clock.clone(), self.flush_keys_interval)
-    }
-
     pub fn emit_event_discarded(&self, key: String) {
         emit!(ThrottleEventDiscarded {
             key,
             emit_events_discarded_per_key: self.internal_metrics.emit_events_discarded_per_key
         });
     }
+
+    /// Returns the thresholds of a key, from `key_thresholds`, then the `thresholds_table`.
+    fn thresholds(&self, key: Option<&str>) -> Thresholds {
+        let Some(key) = key else {
+            return self.thresholds;
+        };
+        if let Some(thresholds) = self.key_thresholds.get(key) {
+            return *thresholds;
+        }
+        match &self.thresholds_table {
+            Some(table) => table.find(key, self.thresholds),
+            None => self.thresholds,
+        }
+    }
+
+    /// Checks the event against the quota of its key, then against the global quota.
+    ///
+    /// Throttled events are returned as errors, along with their key.
+    fn check(&mut self, event: Event) -> Result<Event, (Event, String)> {
+        let (throttle, event) = match self.exclude.as_ref() {
+            Some(condition) => {
+                let (result, event) = condition.check(event);
+                (!result, event)
+            }
+            _ => (true, event),
+        };
+        if !throttle {
+            return Ok(event);
+        }
+
+        let key = self.key_field.as_ref().and_then(|t| {
+            t.render_string(&event)
+                .map_err(|error| {
+                    emit!(TemplateRenderingError {
+                        error,
+                        field: Some("key_field"),
+                        drop_event: false,
+                    })
+                })
+                .ok()
+        });
+        let thresholds = self.thresholds(key.as_deref());
+        let byte_size = if thresholds.bytes.is_some()
+            || self.global.is_some_and(|global| global.bytes.is_some())
+        {
+            event.estimated_json_encoded_size_of().get()
+        } else {
+            0
+        };
+
+        let allowed = self.key_limiters.check(&key, thresholds, byte_size)
+            && self
+                .global
+                .is_none_or(|global| self.global_limiters.check(&(), global, byte_size));
+        if allowed {
+            Ok(event)
+        } else {
+            Err((event, key.unwrap_or_else(|| "None".to_string())))
+        }
+    }
 }
 
 impl<C, I> TaskTransform<Event> for Throttle<C, I>
@@ -95,47 +317,33 @@ where
     where
         Self: 'static,
     {
-        let limiter = self.start_rate_limiter();
+        let mut throttle = self;
 
         Box::pin(stream! {
             while let Some(event) = input_rx.next().await {
-                let (throttle, event) = match self.exclude.as_ref() {
-                    Some(condition) => {
-                        let (result, event) = condition.check(event);
-                        (!result, event)
-                    },
-                    _ => (true, event)
-                };
-                let output = if throttle {
-                    let key = self.key_field.as_ref().and_then(|t| {
-                        t.render_string(&event)
-                            .map_err(|error| {
-                                emit!(TemplateRenderingError {
-                                    error,
-                                    field: Some("key_field"),
-                                    drop_event: false,
-                                })
-                            })
-                            .ok()
-                    });
-
-                    if limiter.check_key(&key) {
-                        Some(event)
-                    } else {
-                        self.emit_event_discarded(key.unwrap_or_else(|| "None".to_string()));
-                        None
-                    }
-                } else {
-                    Some(event)
-                };
-                if let Some(event) = output {
-                    yield event;
+                match throttle.check(event) {
+                    Ok(event) => yield event,
+                    Err((_, key)) => throttle.emit_event_discarded(key),
                 }
             }
         })
     }
 }
 
+/// Used when `reroute_dropped` is set, to send throttled events to the `dropped` output.
+impl<C, I> SyncTransform for Throttle<C, I>
+where
+    C: clock::Clock<Instant = I> + Clone + Send + Sync + 'static,
+    I: clock::Reference + Send + Sync + 'static,
+{
+    fn transform(&mut self, event: Event, output: &mut TransformOutputsBuf) {
+        match self.check(event) {
+            Ok(event) => output.push(None, event),
+            Err((event, _)) => output.push(Some(DROPPED_OUTPUT), event),
+        }
+    }
+}
+
 #[derive(Debug, Snafu)]
 pub enum ConfigError {
     #[snafu(display("`threshold`, and `window_secs` must be non-zero"))]
@@ -149,6 +357,7 @@ mod tests {
     use futures::SinkExt;
 
     use super::*;
+    use crate::config::{schema::Definition, OutputId, TransformConfig};
     use crate::transforms::Transform;
     use crate::{
         event::LogEvent, test_util::components::assert_transform_compliance,
@@ -156,6 +365,7 @@ mod tests {
     };
     use tokio::sync::mpsc;
     use tokio_stream::wrappers::ReceiverStream;
+    use vector_lib::config::LogNamespace;
 
     #[tokio::test]
     async fn throttle_events() {
@@ -367,9 +577,7 @@ key_field = "{{ bucket }}"
             let config = ThrottleConfig {
                 threshold: 1,
                 window_secs: Duration::from_secs_f64(1.0),
-                key_field: None,
-                exclude: None,
-                internal_metrics: Default::default(),
+                ..Default::default()
             };
             let (tx, rx) = mpsc::channel(1);
             let (topology, mut out) = create_topology(ReceiverStream::new(rx), config).await;
@@ -385,4 +593,145 @@ key_field = "{{ bucket }}"
         })
         .await
     }
+
+    fn bucket_event(bucket: &str, message: &str) -> Event {
+        let mut log = LogEvent::from(message);
+        log.insert("bucket", bucket);
+        log.into()
+    }
+
+    #[tokio::test]
+    async fn throttle_bytes() {
+        let clock = clock::FakeRelativeClock::default();
+        let config = toml::from_str::<ThrottleConfig>(
+            r"
+threshold = 100
+threshold_bytes = 100
+window_secs = 10
+",
+        )
+        .unwrap();
+        let mut throttle =
+            Throttle::new(&config, &TransformContext::default(), clock.clone()).unwrap();
+
+        let event = Event::from(LogEvent::from("a".repeat(30)));
+        let size = event.estimated_json_encoded_size_of().get();
+        let allowed = (0..10)
+            .filter(|_| throttle.check(event.clone()).is_ok())
+            .count();
+        assert_eq!(100 / size, allowed);
+
+        // Events bigger than the whole threshold are never allowed.
+        clock.advance(Duration::from_secs(10));
+        assert!(throttle
+            .check(LogEvent::from("a".repeat(200)).into())
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn throttle_bytes_before_events() {
+        let clock = clock::FakeRelativeClock::default();
+        let config = toml::from_str::<ThrottleConfig>(
+            r"
+threshold = 2
+threshold_bytes = 100
+window_secs = 10
+",
+        )
+        .unwrap();
+        let mut throttle = Throttle::new(&config, &TransformContext::default(), clock).unwrap();
+
+        // The events rejected for their size don't consume the event quota.
+        for _ in 0..3 {
+            assert!(throttle
+                .check(LogEvent::from("a".repeat(200)).into())
+                .is_err());
+        }
+        assert!(throttle.check(LogEvent::from("a").into()).is_ok());
+        assert!(throttle.check(LogEvent::from("a").into()).is_ok());
+        assert!(throttle.check(LogEvent::from("a").into()).is_err());
+    }
+
+    #[tokio::test]
+    async fn throttle_key_thresholds() {
+        let clock = clock::FakeRelativeClock::default();
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold = 1
+window_secs = 5
+key_field = "{{ bucket }}"
+key_thresholds.premium.threshold = 3
+"#,
+        )
+        .unwrap();
+        let mut throttle = Throttle::new(&config, &TransformContext::default(), clock).unwrap();
+
+        let allowed = |throttle: &mut Throttle<_, _>, bucket: &str| {
+            (0..5)
+                .filter(|_| throttle.check(bucket_event(bucket, "message")).is_ok())
+                .count()
+        };
+        assert_eq!(1, allowed(&mut throttle, "basic"));
+        assert_eq!(3, allowed(&mut throttle, "premium"));
+    }
+
+    #[tokio::test]
+    async fn throttle_global_quota() {
+        let clock = clock::FakeRelativeClock::default();
+        let config = toml::from_str::<ThrottleConfig>(
+            r#"
+threshold = 2
+window_secs = 5
+key_field = "{{ bucket }}"
+global.threshold = 3
+"#,
+        )
+        .unwrap();
+        let mut throttle = Throttle::new(&config, &TransformContext::default(), clock).unwrap();
+
+        assert!(throttle.check(bucket_event("a", "message")).is_ok());
+        assert!(throttle.check(bucket_event("a", "message")).is_ok());
+        assert!(throttle.check(bucket_event("a", "message")).is_err());
+        assert!(throttle.check(bucket_event("b", "message")).is_ok());
+
+        // Within the quota of its key, but over the global quota.
+        let (_, key) = throttle.check(bucket_event("b", "message")).unwrap_err();
+        assert_eq!("b", key);
+    }
+
+    #[tokio::test]
+    async fn throttle_reroutes_dropped_events() {
+        let config = toml::from_str::<ThrottleConfig>(
+            r"
+threshold = 1
+window_secs = 5
+reroute_dropped = true
+",
+        )
+        .unwrap();
+        let outputs = config.outputs(
+            Default::default(),
+            &[(OutputId::dummy(), Definition::default_legacy_namespace())],
+            LogNamespace::Legacy,
+        );
+        assert_eq!(2, outputs.len());
+
+        let mut throttle = Throttle::new(
+            &config,
+            &TransformContext::default(),
+            clock::FakeRelativeClock::default(),
+        )
+        .unwrap();
+        let mut buf = TransformOutputsBuf::new_with_capacity(outputs, 2);
+        SyncTransform::transform(&mut throttle, LogEvent::from("first").into(), &mut buf);
+        SyncTransform::transform(&mut throttle, LogEvent::from("second").into(), &mut buf);
+
+        assert_eq!(1, buf.drain().count());
+        let dropped = buf.drain_named(DROPPED_OUTPUT).collect::<Vec<_>>();
+        assert_eq!(1, dropped.len());
+        assert_eq!(
+            Some(&Value::from("second")),
+            dropped[0].as_log().get("message")
+        );
+    }
 }
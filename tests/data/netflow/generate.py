@@ -0,0 +1,94 @@
+#!/usr/bin/env python3
+"""Generates the NetFlow/IPFIX fixtures used by the `netflow` source tests.
+
+Each fixture is the UDP payload of a single export datagram. Run from this directory to
+regenerate the `.bin` files.
+"""
+
+import ipaddress
+import struct
+
+
+def v5():
+    records = [
+        ("10.0.0.1", "10.0.0.2", 1, 2, 10, 1500, 40000, 443),
+        ("10.0.0.2", "10.0.0.1", 2, 1, 8, 900, 443, 40000),
+    ]
+    out = struct.pack(">HHIIIIBBH", 5, len(records), 360000, 1700000000, 0, 1000, 0, 0, (1 << 14) | 100)
+    for src, dst, iif, oif, pkts, octets, sport, dport in records:
+        out += ipaddress.IPv4Address(src).packed
+        out += ipaddress.IPv4Address(dst).packed
+        out += ipaddress.IPv4Address("10.0.0.254").packed
+        out += struct.pack(">HHIIIIHHBBBBHHBBH", iif, oif, pkts, octets, 350000, 359000, sport, dport, 0, 0x18, 6, 0, 64512, 64513, 24, 24, 0)
+    return out
+
+
+def pad(body):
+    return body + b"\0" * (-len(body) % 4)
+
+
+def v9_data():
+    body = b""
+    for src, dst, octets, pkts, iif, oif in [
+        ("10.1.0.1", "10.1.0.2", 1000, 10, 1, 2),
+        ("10.1.0.3", "10.1.0.4", 3000, 20, 3, 4),
+    ]:
+        body += ipaddress.IPv4Address(src).packed + ipaddress.IPv4Address(dst).packed
+        body += struct.pack(">IIHHHHB", octets, pkts, iif, oif, 5353, 53, 17)
+    body = pad(struct.pack(">HH", 256, 0) + body)
+    return body[:2] + struct.pack(">H", len(body)) + body[4:]
+
+
+def v9_template():
+    fields = [(8, 4), (12, 4), (1, 4), (2, 4), (10, 2), (14, 2), (7, 2), (11, 2), (4, 1)]
+    body = struct.pack(">HH", 256, len(fields)) + b"".join(struct.pack(">HH", *f) for f in fields)
+    return struct.pack(">HH", 0, len(body) + 4) + body
+
+
+def v9(with_template):
+    sets = (v9_template() if with_template else b"") + v9_data()
+    count = 3 if with_template else 2
+    return struct.pack(">HHIIII", 9, count, 360000, 1700000000, 1, 42) + sets
+
+
+def ipfix_message(sets):
+    return struct.pack(">HHIII", 10, 16 + len(sets), 1700000000, 1, 7) + sets
+
+
+def ipfix_set(set_id, body):
+    body = pad(body) if set_id >= 256 else body
+    return struct.pack(">HH", set_id, len(body) + 4) + body
+
+
+def ipfix():
+    fields = struct.pack(">HH", 256, 6)
+    fields += struct.pack(">HH", 27, 16) + struct.pack(">HH", 28, 16)
+    fields += struct.pack(">HH", 1, 8) + struct.pack(">HH", 152, 8)
+    fields += struct.pack(">HH", 82, 65535)
+    fields += struct.pack(">HHI", 0x8000 | 1, 2, 9)
+    templates = ipfix_set(2, fields)
+
+    options = struct.pack(">HHH", 257, 2, 1) + struct.pack(">HH", 48, 1) + struct.pack(">HH", 34, 4)
+    templates += ipfix_set(3, options)
+
+    flow = ipaddress.IPv6Address("2001:db8::1").packed + ipaddress.IPv6Address("2001:db8::2").packed
+    flow += struct.pack(">QQ", 123456, 1700000000123)
+    flow += struct.pack(">B", 4) + b"eth0" + b"\xbe\xef"
+    data = ipfix_set(256, flow) + ipfix_set(257, struct.pack(">BI", 1, 1000))
+    return ipfix_message(templates + data)
+
+
+def ipfix_withdrawal():
+    return ipfix_message(ipfix_set(2, struct.pack(">HH", 256, 0)))
+
+
+if __name__ == "__main__":
+    for name, data in [
+        ("v5.bin", v5()),
+        ("v9.bin", v9(True)),
+        ("v9-data-only.bin", v9(False)),
+        ("ipfix.bin", ipfix()),
+        ("ipfix-withdrawal.bin", ipfix_withdrawal()),
+    ]:
+        with open(name, "wb") as f:
+            f.write(data)
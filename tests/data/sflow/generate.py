@@ -0,0 +1,58 @@
+#!/usr/bin/env python3
+"""Generates the sFlow v5 fixtures used by the `sflow` source tests.
+
+Each fixture is the UDP payload of a single datagram. Run from this directory to
+regenerate the `.bin` files.
+"""
+
+import ipaddress
+import struct
+
+
+def structure(fmt, body):
+    body += b"\0" * (-len(body) % 4)
+    return struct.pack(">II", fmt, len(body)) + body
+
+
+def datagram(samples):
+    out = struct.pack(">II", 5, 1) + ipaddress.IPv4Address("192.0.2.10").packed
+    out += struct.pack(">IIII", 0, 17, 123456, len(samples))
+    return out + b"".join(samples)
+
+
+def sampled_header():
+    eth = bytes.fromhex("66778899aabb") + bytes.fromhex("001122334455")
+    eth += struct.pack(">HHH", 0x8100, 10, 0x0800)
+    ip = struct.pack(">BBHHHBBH", 0x45, 0, 1500, 0, 0, 64, 6, 0)
+    ip += ipaddress.IPv4Address("198.51.100.1").packed + ipaddress.IPv4Address("203.0.113.7").packed
+    tcp = struct.pack(">HHIIBBHHH", 51000, 443, 0, 0, 0x50, 0x18, 65535, 0, 0)
+    header = eth + ip + tcp
+    return structure(1, struct.pack(">IIII", 1, 1518, 4, len(header)) + header)
+
+
+def flow_sample():
+    records = [sampled_header(), structure(1001, struct.pack(">IIII", 10, 0, 20, 0))]
+    body = struct.pack(">IIIIIIII", 5, 3, 1024, 4096, 0, 3, 7, len(records))
+    return structure(1, body + b"".join(records))
+
+
+def counters_sample():
+    generic = struct.pack(
+        ">IIQIIQIIIIIIQIIIIII",
+        3, 6, 10_000_000_000, 1, 3,
+        5_000_000_000, 100, 5, 1, 0, 2, 0,
+        7_000_000_000, 200, 6, 2, 0, 1, 0,
+    )
+    ethernet = struct.pack(">13I", 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0)
+    records = [structure(1, generic), structure(2, ethernet)]
+    body = struct.pack(">III", 9, 3, len(records))
+    return structure(2, body + b"".join(records))
+
+
+if __name__ == "__main__":
+    for name, data in [
+        ("flow-sample.bin", datagram([flow_sample()])),
+        ("counters-sample.bin", datagram([counters_sample()])),
+    ]:
+        with open(name, "wb") as f:
+            f.write(data)
@@ -206,4 +206,21 @@ generated: components: sinks: configuration: {
 			}
 		}
 	}
+	reroute_dropped: {
+		description: """
+			Reroutes events that the sink failed to deliver to a named output.
+
+			Events whose delivery failed permanently, such as requests that were rejected by the
+			destination or exhausted their retries, are normally dropped. When `reroute_dropped` is set
+			to `true`, they are instead forwarded to a specially-named output of the sink, `dropped`,
+			which can be used as an input by other components. The events are annotated with
+			additional fields describing the failure, such as the status and the response returned by
+			the destination.
+
+			The output must be referenced by its full name, such as `my_sink.dropped`, as it is not
+			matched by wildcards in the inputs of other components.
+			"""
+		required: false
+		type: bool: default: false
+	}
 }
@@ -669,6 +669,50 @@ generated: components: sinks: aws_kinesis_streams: configuration: {
 					}
 				}
 			}
+			circuit_breaker: {
+				description: """
+					Configuration of the circuit breaker of outbound requests.
+
+					The circuit breaker opens after a number of consecutive failed requests, stopping the requests to
+					the endpoint so that they are not retried in vain. Once the reset timeout has elapsed, the
+					circuit breaker is half-open: a single request is sent to probe the endpoint, and the circuit
+					breaker closes if it succeeds, or opens again otherwise.
+
+					Requests are failed when they return an error that would be retried, or a response that would be
+					retried.
+					"""
+				required: false
+				type: object: options: {
+					failure_threshold: {
+						description: "The number of consecutive failed requests after which the circuit breaker opens."
+						required:    false
+						type: uint: {
+							default: 5
+							unit:    "requests"
+						}
+					}
+					reject_when_open: {
+						description: """
+																Whether requests are rejected while the circuit breaker is open, instead of waiting for it to
+																close.
+
+																The events of the rejected requests can be sent to a failover sink by enabling
+																`reroute_dropped` on this sink, and using its `dropped` output as an input of the failover
+																sink.
+																"""
+						required: false
+						type: bool: default: false
+					}
+					reset_timeout_secs: {
+						description: "The time the circuit breaker stays open before probing the endpoint."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+				}
+			}
 			concurrency: {
 				description: """
 					Configuration for outbound request concurrency.
@@ -776,8 +820,12 @@ generated: components: sinks: aws_kinesis_streams: configuration: {
 		}
 	}
 	request_retry_partial: {
-		description: "Whether or not to retry successful requests containing partial failures."
-		required:    false
+		description: """
+			Whether or not to retry the records that failed individually in successful requests.
+
+			Only the failed records are retried. When disabled, the failed records are rejected.
+			"""
+		required: false
 		type: bool: default: false
 	}
 	stream_name: {
@@ -279,8 +279,12 @@ generated: components: sinks: clickhouse: configuration: {
 		}
 	}
 	date_time_best_effort: {
-		description: "Sets `date_time_input_format` to `best_effort`, allowing ClickHouse to properly parse RFC3339/ISO 8601."
-		required:    false
+		description: """
+			Sets `date_time_input_format` to `best_effort`, allowing ClickHouse to properly parse RFC3339/ISO 8601.
+
+			The `row_binary` and `arrow_stream` formats always parse RFC 3339 timestamps.
+			"""
+		required: false
 		type: bool: default: false
 	}
 	encoding: {
@@ -326,9 +330,22 @@ generated: components: sinks: clickhouse: configuration: {
 		type: string: {
 			default: "json_each_row"
 			enum: {
+				arrow_stream: """
+					ArrowStream.
+
+					Events are converted to the types of the columns of the table like with `row_binary`, and
+					each batch is encoded as an Arrow IPC stream.
+					"""
 				json_as_object: "JSONAsObject."
 				json_as_string: "JSONAsString."
 				json_each_row:  "JSONEachRow."
+				row_binary: """
+					RowBinary.
+
+					Events are converted to the types of the columns of the table, whose schema is fetched with
+					`DESCRIBE TABLE`, and encoded in the native binary format of ClickHouse, which is much cheaper
+					for ClickHouse to parse than JSON. Events that can't be converted are rejected.
+					"""
 			}
 		}
 	}
@@ -481,6 +498,50 @@ generated: components: sinks: clickhouse: configuration: {
 					}
 				}
 			}
+			circuit_breaker: {
+				description: """
+					Configuration of the circuit breaker of outbound requests.
+
+					The circuit breaker opens after a number of consecutive failed requests, stopping the requests to
+					the endpoint so that they are not retried in vain. Once the reset timeout has elapsed, the
+					circuit breaker is half-open: a single request is sent to probe the endpoint, and the circuit
+					breaker closes if it succeeds, or opens again otherwise.
+
+					Requests are failed when they return an error that would be retried, or a response that would be
+					retried.
+					"""
+				required: false
+				type: object: options: {
+					failure_threshold: {
+						description: "The number of consecutive failed requests after which the circuit breaker opens."
+						required:    false
+						type: uint: {
+							default: 5
+							unit:    "requests"
+						}
+					}
+					reject_when_open: {
+						description: """
+																Whether requests are rejected while the circuit breaker is open, instead of waiting for it to
+																close.
+
+																The events of the rejected requests can be sent to a failover sink by enabling
+																`reroute_dropped` on this sink, and using its `dropped` output as an input of the failover
+																sink.
+																"""
+						required: false
+						type: bool: default: false
+					}
+					reset_timeout_secs: {
+						description: "The time the circuit breaker stays open before probing the endpoint."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+				}
+			}
 			concurrency: {
 				description: """
 					Configuration for outbound request concurrency.
@@ -591,7 +652,8 @@ generated: components: sinks: clickhouse: configuration: {
 		description: """
 			Sets `input_format_skip_unknown_fields`, allowing ClickHouse to discard fields not present in the table schema.
 
-			If left unspecified, use the default provided by the `ClickHouse` server.
+			If left unspecified, use the default provided by the `ClickHouse` server. Fields not present in
+			the table schema are always discarded by the `row_binary` and `arrow_stream` formats.
 			"""
 		required: false
 		type: bool: {}
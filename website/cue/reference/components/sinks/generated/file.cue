@@ -493,6 +493,72 @@ generated: components: sinks: file: configuration: {
 			syntax: "template"
 		}
 	}
+	rotation: {
+		description: """
+			File rotation and retention configuration.
+
+			When rotation is enabled, by setting a maximum size or age or by enabling `atomic`, each file is
+			written with a sequence number inserted before its extensions, such as `app.3.log.gz` for the
+			path `app.log.gz`. A new file, with the next sequence number, is started when the current file
+			is rotated, and when events are written again after the current file was closed for idleness.
+			"""
+		required: false
+		type: object: options: {
+			atomic: {
+				description: """
+					Whether files are written under a temporary name, and renamed once closed.
+
+					The temporary name is the final name prefixed with `.` and suffixed with `.tmp`, so that
+					readers of the final names never see partially written files. Temporary files left by an
+					unclean shutdown are renamed to their final names when the next file of the path is started.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			max_age_secs: {
+				description: """
+					The age after which a file is rotated.
+
+					The age of a file is checked when events are written to it.
+					"""
+				required: false
+				type: uint: {
+					examples: [3600]
+					unit: "seconds"
+				}
+			}
+			max_file_size: {
+				description: """
+					The size, in bytes, after which a file is rotated.
+
+					The size is measured before compression.
+					"""
+				required: false
+				type: uint: {
+					examples: [104857600]
+					unit: "bytes"
+				}
+			}
+			max_files: {
+				description: """
+					The maximum number of files to keep for each path.
+
+					The oldest files are deleted when a file is closed.
+					"""
+				required: false
+				type: uint: {}
+			}
+			max_total_bytes: {
+				description: """
+					The maximum total size, in bytes, of the files to keep for each path.
+
+					The oldest files are deleted when a file is closed, but the newest file is always kept.
+					"""
+				required: false
+				type: uint: unit: "bytes"
+			}
+		}
+	}
 	timezone: {
 		description: """
 			Timezone to use for any date specifiers in template strings.
@@ -36,6 +36,24 @@ generated: components: sinks: http: configuration: {
 			"""
 		required: false
 		type: object: options: {
+			algorithm: {
+				description:   "The hash function of an HMAC signature."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: {
+					default: "sha256"
+					enum: {
+						sha256: "HMAC-SHA256."
+						sha512: "HMAC-SHA512."
+					}
+				}
+			}
+			audience: {
+				description:   "The audience to request the access token for, for authorization servers requiring one."
+				relevant_when: "strategy = \"oauth2\""
+				required:      false
+				type: string: examples: ["https://api.example.com"]
+			}
 			auth: {
 				description:   "The AWS authentication configuration."
 				relevant_when: "strategy = \"aws\""
@@ -159,18 +177,60 @@ generated: components: sinks: http: configuration: {
 					}
 				}
 			}
+			client_id: {
+				description:   "The client identifier."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: examples: ["${CLIENT_ID}", "SECRET[vault.client_id]"]
+			}
+			client_secret: {
+				description:   "The client secret."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: examples: ["${CLIENT_SECRET}", "SECRET[vault.client_secret]"]
+			}
 			password: {
 				description:   "The basic authentication password."
 				relevant_when: "strategy = \"basic\""
 				required:      true
 				type: string: examples: ["${PASSWORD}", "password"]
 			}
+			refresh_margin_secs: {
+				description:   "How long before it expires the access token is refreshed."
+				relevant_when: "strategy = \"oauth2\""
+				required:      false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+			scopes: {
+				description:   "The scopes to request."
+				relevant_when: "strategy = \"oauth2\""
+				required:      false
+				type: array: {
+					default: []
+					items: type: string: examples: ["logs.write"]
+				}
+			}
+			secret: {
+				description:   "The secret used to sign requests."
+				relevant_when: "strategy = \"hmac\""
+				required:      true
+				type: string: examples: ["${SIGNING_SECRET}", "SECRET[vault.signing_secret]"]
+			}
 			service: {
 				description:   "The AWS service name to use for signing."
 				relevant_when: "strategy = \"aws\""
 				required:      true
 				type: string: {}
 			}
+			signature_header: {
+				description:   "The header holding the signature."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: default: "X-Signature"
+			}
 			strategy: {
 				description: "The authentication strategy to use."
 				required:    true
@@ -188,14 +248,39 @@ generated: components: sinks: http: configuration: {
 
 						The bearer token value (OAuth2, JWT, etc.) is passed as-is.
 						"""
+					hmac: """
+						HMAC request signing.
+
+						Each request is signed with the secret, over its timestamp, method, path with the query
+						string, if any, and body, each followed by a newline except for the body. The hex-encoded
+						signature and the timestamp, in Unix seconds, are passed in headers.
+						"""
+					oauth2: """
+						OAuth2 client credentials authentication.
+
+						An access token is requested from the token endpoint with the client credentials, and is
+						passed as a bearer token. The token is cached, and refreshed before it expires.
+						"""
 				}
 			}
+			timestamp_header: {
+				description:   "The header holding the timestamp the request was signed at."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: default: "X-Signature-Timestamp"
+			}
 			token: {
 				description:   "The bearer authentication token."
 				relevant_when: "strategy = \"bearer\""
 				required:      true
 				type: string: {}
 			}
+			token_endpoint: {
+				description:   "The URL of the token endpoint of the authorization server."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: examples: ["https://auth.example.com/oauth2/token"]
+			}
 			user: {
 				description:   "The basic authentication username."
 				relevant_when: "strategy = \"basic\""
@@ -686,6 +771,109 @@ generated: components: sinks: http: configuration: {
 			type: string: {}
 		}
 	}
+	load_balance: {
+		description: """
+			Client-side load balancing configuration.
+
+			Requests are distributed across the endpoints, of which only the scheme and authority are
+			used: the path, query, headers and credentials of the requests are left untouched. As such,
+			load balancing can't be used along with request signing, such as AWS authentication, which
+			covers the host the request is sent to.
+			"""
+		required: false
+		type: object: options: {
+			endpoint_health: {
+				description: "Options for determining the health of the endpoints."
+				required:    false
+				type: object: options: {
+					retry_initial_backoff_secs: {
+						description: "Initial delay between attempts to reactivate endpoints once they become unhealthy."
+						required:    false
+						type: uint: {
+							default: 1
+							unit:    "seconds"
+						}
+					}
+					retry_max_duration_secs: {
+						description: "Maximum delay between attempts to reactivate endpoints once they become unhealthy."
+						required:    false
+						type: uint: {
+							default: 3600
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+			endpoints: {
+				description: "The endpoints to distribute the requests across."
+				required:    false
+				type: array: {
+					default: []
+					items: type: string: examples: ["http://10.24.32.122:9000", "https://ingest-2.example.com"]
+				}
+			}
+			hash_header: {
+				description: "The header whose value is part of the partition key of the `consistent_hash` strategy."
+				required:    false
+				type: string: examples: ["X-Tenant-Id"]
+			}
+			srv: {
+				description: """
+					Discovery of additional endpoints through DNS SRV records.
+
+					Until endpoints are discovered, requests are sent to the static `endpoints`, or to the
+					endpoint of the sink if there are none.
+					"""
+				required: false
+				type: object: options: {
+					name: {
+						description: """
+																The name of the SRV records.
+
+																The targets with the lowest priority value are used as endpoints, and their weights are
+																ignored.
+																"""
+						required: true
+						type: string: examples: ["_http._tcp.ingest.example.com"]
+					}
+					refresh_interval_secs: {
+						description: "The interval between lookups of the SRV records."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+					scheme: {
+						description: "The scheme of the discovered endpoints."
+						required:    false
+						type: string: {
+							default: "http"
+							examples: ["https"]
+						}
+					}
+				}
+			}
+			strategy: {
+				description: "The strategy used to pick the endpoint of each request."
+				required:    false
+				type: string: {
+					default: "least_outstanding"
+					enum: {
+						consistent_hash: """
+															Requests with the same partition key are sent to the same endpoint.
+
+															The partition key is made of the path and query of the request, along with the value of the
+															`hash_header` header, if set. Only the requests of an endpoint are moved to other endpoints
+															when it becomes unhealthy.
+															"""
+						least_outstanding: "Requests are sent to the endpoint with the fewest requests in flight."
+						round_robin:       "Requests are sent to each endpoint in turn."
+					}
+				}
+			}
+		}
+	}
 	method: {
 		description: "The HTTP method to use when making the request."
 		required:    false
@@ -807,6 +995,50 @@ generated: components: sinks: http: configuration: {
 					}
 				}
 			}
+			circuit_breaker: {
+				description: """
+					Configuration of the circuit breaker of outbound requests.
+
+					The circuit breaker opens after a number of consecutive failed requests, stopping the requests to
+					the endpoint so that they are not retried in vain. Once the reset timeout has elapsed, the
+					circuit breaker is half-open: a single request is sent to probe the endpoint, and the circuit
+					breaker closes if it succeeds, or opens again otherwise.
+
+					Requests are failed when they return an error that would be retried, or a response that would be
+					retried.
+					"""
+				required: false
+				type: object: options: {
+					failure_threshold: {
+						description: "The number of consecutive failed requests after which the circuit breaker opens."
+						required:    false
+						type: uint: {
+							default: 5
+							unit:    "requests"
+						}
+					}
+					reject_when_open: {
+						description: """
+																Whether requests are rejected while the circuit breaker is open, instead of waiting for it to
+																close.
+
+																The events of the rejected requests can be sent to a failover sink by enabling
+																`reroute_dropped` on this sink, and using its `dropped` output as an input of the failover
+																sink.
+																"""
+						required: false
+						type: bool: default: false
+					}
+					reset_timeout_secs: {
+						description: "The time the circuit breaker stays open before probing the endpoint."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+				}
+			}
 			concurrency: {
 				description: """
 					Configuration for outbound request concurrency.
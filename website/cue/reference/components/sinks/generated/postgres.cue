@@ -31,14 +31,9 @@ generated: components: sinks: postgres: configuration: {
 		description: """
 			Event batching behavior.
 
-			Note that as PostgreSQL's `jsonb_populate_recordset` function is used to insert events,
-			a single event in the batch can make the whole batch to fail. For example, if a single event within the batch triggers
-			a unique constraint violation in the destination table, the whole event batch will fail.
-
-			As a workaround, [triggers](https://www.postgresql.org/docs/current/sql-createtrigger.html) on constraint violations
-			can be defined at a database level to change the behavior of the insert operation on specific tables.
-			Alternatively, setting `max_events` batch setting to `1` will make each event to be inserted independently,
-			so events that trigger a constraint violation will not affect the rest of the events.
+			Each batch is inserted with a single statement. If the statement fails because of invalid data,
+			such as a single event triggering a unique constraint violation, the events of the batch are
+			inserted one by one within a transaction, and only the events the database rejects are dropped.
 			"""
 		required: false
 		type: object: options: {
@@ -70,6 +65,45 @@ generated: components: sinks: postgres: configuration: {
 			}
 		}
 	}
+	columns: {
+		description: """
+			The columns that are populated from each event.
+
+			If not set, each event is converted to a row with `jsonb_populate_recordset`, matching the
+			top-level fields of the event to the columns of the table.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				name: {
+					description: "The name of the column."
+					required:    true
+					type: string: examples: ["created_at"]
+				}
+				path: {
+					description: """
+						The path of the event field holding the value of the column.
+
+						If not set, the top-level field with the same name as the column is used.
+						"""
+					required: false
+					type: string: examples: ["timestamp", "http.status_code"]
+				}
+				type: {
+					description: """
+						A type the value is explicitly cast to, such as `timestamptz` or `int8` in PostgreSQL.
+
+						If not set, the value is converted to the type of the column by the database. Casts are not
+						applied by the `copy` insert mode of the `postgres` sink, where values are always parsed by
+						the type of the column.
+						"""
+					required: false
+					type: string: examples: ["timestamptz"]
+				}
+			}
+		}
+	}
 	endpoint: {
 		description: """
 			The PostgreSQL server connection string. It can contain the username and password.
@@ -79,6 +113,56 @@ generated: components: sinks: postgres: configuration: {
 		required: true
 		type: string: {}
 	}
+	insert_mode: {
+		description: "How batches are written to the table."
+		required:    false
+		type: string: {
+			default: "insert"
+			enum: {
+				copy: """
+					Batches are streamed with `COPY ... FROM STDIN`, which is faster for large batches.
+
+					Requires `columns` to be set and does not support `on_conflict`.
+					"""
+				insert: "Batches are inserted with an `INSERT` statement."
+			}
+		}
+	}
+	on_conflict: {
+		description: "How rows conflicting with existing rows are handled."
+		required:    false
+		type: object: options: {
+			action: {
+				description: "The action taken for rows conflicting with existing rows."
+				required:    true
+				type: string: enum: {
+					error:  "Conflicts are errors, and the conflicting events are dropped."
+					ignore: "Conflicting rows are skipped."
+					update: "Existing rows are updated with the values of conflicting rows."
+				}
+			}
+			columns: {
+				description:   "The columns that are updated."
+				relevant_when: "action = \"update\""
+				required:      true
+				type: array: items: type: string: {}
+			}
+			target: {
+				description: """
+					The columns of the unique index conflicts are checked against.
+
+					If empty, conflicts with any unique index are ignored. MySQL always checks all unique
+					indexes, so this must be empty there.
+					"""
+				relevant_when: "action = \"ignore\" or action = \"update\""
+				required:      false
+				type: array: {
+					default: []
+					items: type: string: {}
+				}
+			}
+		}
+	}
 	pool_size: {
 		description: """
 			The postgres connection pool size. See [this](https://docs.rs/sqlx/latest/sqlx/struct.Pool.html#why-use-a-pool) for more
@@ -167,6 +251,50 @@ generated: components: sinks: postgres: configuration: {
 					}
 				}
 			}
+			circuit_breaker: {
+				description: """
+					Configuration of the circuit breaker of outbound requests.
+
+					The circuit breaker opens after a number of consecutive failed requests, stopping the requests to
+					the endpoint so that they are not retried in vain. Once the reset timeout has elapsed, the
+					circuit breaker is half-open: a single request is sent to probe the endpoint, and the circuit
+					breaker closes if it succeeds, or opens again otherwise.
+
+					Requests are failed when they return an error that would be retried, or a response that would be
+					retried.
+					"""
+				required: false
+				type: object: options: {
+					failure_threshold: {
+						description: "The number of consecutive failed requests after which the circuit breaker opens."
+						required:    false
+						type: uint: {
+							default: 5
+							unit:    "requests"
+						}
+					}
+					reject_when_open: {
+						description: """
+																Whether requests are rejected while the circuit breaker is open, instead of waiting for it to
+																close.
+
+																The events of the rejected requests can be sent to a failover sink by enabling
+																`reroute_dropped` on this sink, and using its `dropped` output as an input of the failover
+																sink.
+																"""
+						required: false
+						type: bool: default: false
+					}
+					reset_timeout_secs: {
+						description: "The time the circuit breaker stays open before probing the endpoint."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+				}
+			}
 			concurrency: {
 				description: """
 					Configuration for outbound request concurrency.
@@ -279,8 +407,15 @@ generated: components: sinks: postgres: configuration: {
 			to SQL injection attacks as Vector does not validate or sanitize it, you must not use untrusted input.
 			This parameter will be directly interpolated in the SQL query statement,
 			as table names as parameters in prepared statements are not allowed in PostgreSQL.
+
+			The table can be templated, for example to write events to daily partitions. Rendered table
+			names must be identifiers, optionally qualified by a schema, such as `logs_2024_01_01` or
+			`audit.logs`. Events rendering any other table name are dropped.
 			"""
 		required: true
-		type: string: {}
+		type: string: {
+			examples: ["logs", "logs_%Y_%m_%d"]
+			syntax: "template"
+		}
 	}
 }
@@ -566,6 +566,106 @@ generated: components: sinks: splunk_hec_logs: configuration: {
 			items: type: string: examples: ["field1", "field2"]
 		}
 	}
+	load_balance: {
+		description: """
+			Client-side load balancing of the requests across Splunk HEC endpoints.
+
+			Requires indexer acknowledgements to be disabled.
+			"""
+		required: false
+		type: object: options: {
+			endpoint_health: {
+				description: "Options for determining the health of the endpoints."
+				required:    false
+				type: object: options: {
+					retry_initial_backoff_secs: {
+						description: "Initial delay between attempts to reactivate endpoints once they become unhealthy."
+						required:    false
+						type: uint: {
+							default: 1
+							unit:    "seconds"
+						}
+					}
+					retry_max_duration_secs: {
+						description: "Maximum delay between attempts to reactivate endpoints once they become unhealthy."
+						required:    false
+						type: uint: {
+							default: 3600
+							unit:    "seconds"
+						}
+					}
+				}
+			}
+			endpoints: {
+				description: "The endpoints to distribute the requests across."
+				required:    false
+				type: array: {
+					default: []
+					items: type: string: examples: ["http://10.24.32.122:9000", "https://ingest-2.example.com"]
+				}
+			}
+			hash_header: {
+				description: "The header whose value is part of the partition key of the `consistent_hash` strategy."
+				required:    false
+				type: string: examples: ["X-Tenant-Id"]
+			}
+			srv: {
+				description: """
+					Discovery of additional endpoints through DNS SRV records.
+
+					Until endpoints are discovered, requests are sent to the static `endpoints`, or to the
+					endpoint of the sink if there are none.
+					"""
+				required: false
+				type: object: options: {
+					name: {
+						description: """
+																The name of the SRV records.
+
+																The targets with the lowest priority value are used as endpoints, and their weights are
+																ignored.
+																"""
+						required: true
+						type: string: examples: ["_http._tcp.ingest.example.com"]
+					}
+					refresh_interval_secs: {
+						description: "The interval between lookups of the SRV records."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+					scheme: {
+						description: "The scheme of the discovered endpoints."
+						required:    false
+						type: string: {
+							default: "http"
+							examples: ["https"]
+						}
+					}
+				}
+			}
+			strategy: {
+				description: "The strategy used to pick the endpoint of each request."
+				required:    false
+				type: string: {
+					default: "least_outstanding"
+					enum: {
+						consistent_hash: """
+															Requests with the same partition key are sent to the same endpoint.
+
+															The partition key is made of the path and query of the request, along with the value of the
+															`hash_header` header, if set. Only the requests of an endpoint are moved to other endpoints
+															when it becomes unhealthy.
+															"""
+						least_outstanding: "Requests are sent to the endpoint with the fewest requests in flight."
+						round_robin:       "Requests are sent to each endpoint in turn."
+					}
+				}
+			}
+		}
+	}
 	request: {
 		description: """
 			Middleware settings for outbound requests.
@@ -646,6 +746,50 @@ generated: components: sinks: splunk_hec_logs: configuration: {
 					}
 				}
 			}
+			circuit_breaker: {
+				description: """
+					Configuration of the circuit breaker of outbound requests.
+
+					The circuit breaker opens after a number of consecutive failed requests, stopping the requests to
+					the endpoint so that they are not retried in vain. Once the reset timeout has elapsed, the
+					circuit breaker is half-open: a single request is sent to probe the endpoint, and the circuit
+					breaker closes if it succeeds, or opens again otherwise.
+
+					Requests are failed when they return an error that would be retried, or a response that would be
+					retried.
+					"""
+				required: false
+				type: object: options: {
+					failure_threshold: {
+						description: "The number of consecutive failed requests after which the circuit breaker opens."
+						required:    false
+						type: uint: {
+							default: 5
+							unit:    "requests"
+						}
+					}
+					reject_when_open: {
+						description: """
+																Whether requests are rejected while the circuit breaker is open, instead of waiting for it to
+																close.
+
+																The events of the rejected requests can be sent to a failover sink by enabling
+																`reroute_dropped` on this sink, and using its `dropped` output as an input of the failover
+																sink.
+																"""
+						required: false
+						type: bool: default: false
+					}
+					reset_timeout_secs: {
+						description: "The time the circuit breaker stays open before probing the endpoint."
+						required:    false
+						type: uint: {
+							default: 30
+							unit:    "seconds"
+						}
+					}
+				}
+			}
 			concurrency: {
 				description: """
 					Configuration for outbound request concurrency.
@@ -36,12 +36,36 @@ generated: components: sources: heroku_logs: configuration: {
 			"""
 		required: false
 		type: object: options: {
+			algorithm: {
+				description:   "The hash function of an HMAC signature."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: {
+					default: "sha256"
+					enum: {
+						sha256: "HMAC-SHA256."
+						sha512: "HMAC-SHA512."
+					}
+				}
+			}
 			password: {
 				description:   "The basic authentication password."
 				relevant_when: "strategy = \"basic\""
 				required:      true
 				type: string: examples: ["${PASSWORD}", "password"]
 			}
+			secret: {
+				description:   "The secret requests are signed with."
+				relevant_when: "strategy = \"hmac\""
+				required:      true
+				type: string: examples: ["${SIGNING_SECRET}", "SECRET[vault.signing_secret]"]
+			}
+			signature_header: {
+				description:   "The header holding the signature."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: default: "X-Signature"
+			}
 			source: {
 				description:   "The VRL boolean expression."
 				relevant_when: "strategy = \"custom\""
@@ -64,8 +88,22 @@ generated: components: sources: heroku_logs: configuration: {
 
 						Takes in request and validates it using VRL code.
 						"""
+					hmac: """
+						HMAC request signature verification.
+
+						The signature of each request is computed with the secret, over its timestamp, method, path
+						with the query string, if any, and body, each followed by a newline except for the body, and
+						compared to the hex-encoded signature of the request. Requests signed more than five minutes
+						away from the current time are rejected.
+						"""
 				}
 			}
+			timestamp_header: {
+				description:   "The header holding the timestamp the request was signed at, in Unix seconds."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: default: "X-Signature-Timestamp"
+			}
 			username: {
 				description:   "The basic authentication username."
 				relevant_when: "strategy = \"basic\""
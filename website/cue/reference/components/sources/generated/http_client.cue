@@ -5,6 +5,24 @@ generated: components: sources: http_client: configuration: {
 		description: "HTTP Authentication."
 		required:    false
 		type: object: options: {
+			algorithm: {
+				description:   "The hash function of an HMAC signature."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: {
+					default: "sha256"
+					enum: {
+						sha256: "HMAC-SHA256."
+						sha512: "HMAC-SHA512."
+					}
+				}
+			}
+			audience: {
+				description:   "The audience to request the access token for, for authorization servers requiring one."
+				relevant_when: "strategy = \"oauth2\""
+				required:      false
+				type: string: examples: ["https://api.example.com"]
+			}
 			auth: {
 				description:   "The AWS authentication configuration."
 				relevant_when: "strategy = \"aws\""
@@ -128,18 +146,60 @@ generated: components: sources: http_client: configuration: {
 					}
 				}
 			}
+			client_id: {
+				description:   "The client identifier."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: examples: ["${CLIENT_ID}", "SECRET[vault.client_id]"]
+			}
+			client_secret: {
+				description:   "The client secret."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: examples: ["${CLIENT_SECRET}", "SECRET[vault.client_secret]"]
+			}
 			password: {
 				description:   "The basic authentication password."
 				relevant_when: "strategy = \"basic\""
 				required:      true
 				type: string: examples: ["${PASSWORD}", "password"]
 			}
+			refresh_margin_secs: {
+				description:   "How long before it expires the access token is refreshed."
+				relevant_when: "strategy = \"oauth2\""
+				required:      false
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+			}
+			scopes: {
+				description:   "The scopes to request."
+				relevant_when: "strategy = \"oauth2\""
+				required:      false
+				type: array: {
+					default: []
+					items: type: string: examples: ["logs.write"]
+				}
+			}
+			secret: {
+				description:   "The secret used to sign requests."
+				relevant_when: "strategy = \"hmac\""
+				required:      true
+				type: string: examples: ["${SIGNING_SECRET}", "SECRET[vault.signing_secret]"]
+			}
 			service: {
 				description:   "The AWS service name to use for signing."
 				relevant_when: "strategy = \"aws\""
 				required:      true
 				type: string: {}
 			}
+			signature_header: {
+				description:   "The header holding the signature."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: default: "X-Signature"
+			}
 			strategy: {
 				description: "The authentication strategy to use."
 				required:    true
@@ -157,14 +217,39 @@ generated: components: sources: http_client: configuration: {
 
 						The bearer token value (OAuth2, JWT, etc.) is passed as-is.
 						"""
+					hmac: """
+						HMAC request signing.
+
+						Each request is signed with the secret, over its timestamp, method, path with the query
+						string, if any, and body, each followed by a newline except for the body. The hex-encoded
+						signature and the timestamp, in Unix seconds, are passed in headers.
+						"""
+					oauth2: """
+						OAuth2 client credentials authentication.
+
+						An access token is requested from the token endpoint with the client credentials, and is
+						passed as a bearer token. The token is cached, and refreshed before it expires.
+						"""
 				}
 			}
+			timestamp_header: {
+				description:   "The header holding the timestamp the request was signed at."
+				relevant_when: "strategy = \"hmac\""
+				required:      false
+				type: string: default: "X-Signature-Timestamp"
+			}
 			token: {
 				description:   "The bearer authentication token."
 				relevant_when: "strategy = \"bearer\""
 				required:      true
 				type: string: {}
 			}
+			token_endpoint: {
+				description:   "The URL of the token endpoint of the authorization server."
+				relevant_when: "strategy = \"oauth2\""
+				required:      true
+				type: string: examples: ["https://auth.example.com/oauth2/token"]
+			}
 			user: {
 				description:   "The basic authentication username."
 				relevant_when: "strategy = \"basic\""
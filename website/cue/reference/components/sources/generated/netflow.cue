@@ -0,0 +1,68 @@
+package metadata
+
+generated: components: sources: netflow: configuration: {
+	address: {
+		description: """
+			The socket address to listen for connections on, or `systemd{#N}` to use the Nth socket passed by
+			systemd socket activation.
+
+			If a socket address is used, it _must_ include a port.
+			"""
+		required: true
+		type: string: examples: ["0.0.0.0:9000", "systemd", "systemd#3"]
+	}
+	host_key: {
+		description: """
+			Overrides the name of the log field used to add the exporter's address to each event.
+
+			By default, the [global `log_schema.host_key` option][global_host_key] is used.
+
+			Set to `""` to suppress this key.
+
+			[global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+			"""
+		required: false
+		type: string: {}
+	}
+	interface_metrics: {
+		description: """
+			Whether or not to additionally emit per-interface byte and packet counters.
+
+			When enabled, the `netflow_interface_bytes_total` and `netflow_interface_packets_total`
+			counters are emitted for every datagram, tagged with the exporter address, the interface
+			index and the direction (`ingress` or `egress`) of the flows.
+			"""
+		required: false
+		type: bool: default: false
+	}
+	max_length: {
+		description: """
+			The maximum size of an incoming datagram.
+
+			Datagrams larger than this are truncated, and are likely to fail decoding.
+			"""
+		required: false
+		type: uint: {
+			default: 65535
+			unit:    "bytes"
+		}
+	}
+	receive_buffer_bytes: {
+		description: "The size of the receive buffer used for the listening socket."
+		required:    false
+		type: uint: unit: "bytes"
+	}
+	template_timeout_secs: {
+		description: """
+			How long a NetFlow v9 or IPFIX template is kept after it was last announced by its exporter.
+
+			Data records referencing an expired (or never received) template cannot be decoded and are
+			discarded until the exporter announces the template again.
+			"""
+		required: false
+		type: uint: {
+			default: 1800
+			unit:    "seconds"
+		}
+	}
+}
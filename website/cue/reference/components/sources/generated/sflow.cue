@@ -0,0 +1,47 @@
+package metadata
+
+generated: components: sources: sflow: configuration: {
+	address: {
+		description: """
+			The socket address to listen for connections on, or `systemd{#N}` to use the Nth socket passed by
+			systemd socket activation.
+
+			If a socket address is used, it _must_ include a port.
+			"""
+		required: true
+		type: string: examples: ["0.0.0.0:9000", "systemd", "systemd#3"]
+	}
+	host_key: {
+		description: """
+			Overrides the name of the log field used to add the address of the peer that sent the
+			datagram to each flow sample.
+
+			The sFlow agent address reported inside the datagram is always added as `agent_address`.
+
+			By default, the [global `log_schema.host_key` option][global_host_key] is used.
+
+			Set to `""` to suppress this key.
+
+			[global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+			"""
+		required: false
+		type: string: {}
+	}
+	max_length: {
+		description: """
+			The maximum size of an incoming datagram.
+
+			Datagrams larger than this are discarded.
+			"""
+		required: false
+		type: uint: {
+			default: 65535
+			unit:    "bytes"
+		}
+	}
+	receive_buffer_bytes: {
+		description: "The size of the receive buffer used for the listening socket."
+		required:    false
+		type: uint: unit: "bytes"
+	}
+}
@@ -0,0 +1,253 @@
+package metadata
+
+generated: components: sources: snmp: configuration: {
+	max_repetitions: {
+		description: "The number of table rows requested at once when walking tables."
+		required:    false
+		type: uint: default: 25
+	}
+	mib_files: {
+		description: """
+			A list of files mapping OIDs to object names.
+
+			Each line contains an object name and its OID, as produced by `snmptranslate -Tz -On`
+			for a set of MIBs. Names from these files can be used in place of numeric OIDs. Common
+			objects from SNMPv2-MIB and IF-MIB are always known.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["/etc/vector/mibs/oids.txt"]
+		}
+	}
+	namespace: {
+		description: """
+			Overrides the default namespace for the metrics emitted by the source.
+
+			If set to an empty string, no namespace is added to the metrics.
+
+			By default, `snmp` is used.
+			"""
+		required: false
+		type: string: default: "snmp"
+	}
+	retries: {
+		description: "The number of times a request is retried after timing out."
+		required:    false
+		type: uint: default: 1
+	}
+	scrape_interval_secs: {
+		description: """
+			The interval between scrapes.
+
+			Can be overridden for each target.
+			"""
+		required: false
+		type: uint: {
+			default: 60
+			unit:    "seconds"
+		}
+	}
+	targets: {
+		description: "The agents to poll."
+		required:    true
+		type: array: items: type: object: options: {
+			address: {
+				description: """
+					The address of the agent.
+
+					If no port is given, the standard port 161 is used.
+					"""
+				required: true
+				type: string: examples: ["192.0.2.1", "switch01.example.com:161"]
+			}
+			community: {
+				description: "The community used for SNMPv2c requests."
+				required:    false
+				type: string: {
+					default: "public"
+					examples: ["SECRET[secret_backend.snmp_community]"]
+				}
+			}
+			context_name: {
+				description: "The context name used for SNMPv3 requests."
+				required:    false
+				type: string: {}
+			}
+			objects: {
+				description: "Scalar objects to fetch."
+				required:    false
+				type: array: {
+					default: []
+					items: type: object: options: {
+						name: {
+							description: "The name of the metric."
+							required:    true
+							type: string: examples: ["interface_in_octets_total"]
+						}
+						oid: {
+							description: """
+																						The OID of the object, either numeric or as an object name.
+
+																						For tables, this is the OID of the column.
+																						"""
+							required: true
+							type: string: examples: ["1.3.6.1.2.1.1.3.0", "ifHCInOctets"]
+						}
+						type: {
+							description: "The type of metric emitted for an object."
+							required:    false
+							type: string: {
+								default: "auto"
+								enum: {
+									auto:    "A counter for `Counter32` and `Counter64` values, and a gauge for all others."
+									counter: "A counter."
+									gauge:   "A gauge."
+								}
+							}
+						}
+					}
+				}
+			}
+			scrape_interval_secs: {
+				description: "Overrides the interval between scrapes of this agent."
+				required:    false
+				type: uint: unit: "seconds"
+			}
+			tables: {
+				description: "Tables to walk."
+				required:    false
+				type: array: {
+					default: []
+					items: type: object: options: {
+						index_tag: {
+							description: "The name of the tag holding the index of each row."
+							required:    false
+							type: string: default: "index"
+						}
+						metrics: {
+							description: "The columns whose values are emitted as metrics."
+							required:    true
+							type: array: items: type: object: options: {
+								name: {
+									description: "The name of the metric."
+									required:    true
+									type: string: examples: ["interface_in_octets_total"]
+								}
+								oid: {
+									description: """
+																													The OID of the object, either numeric or as an object name.
+
+																													For tables, this is the OID of the column.
+																													"""
+									required: true
+									type: string: examples: ["1.3.6.1.2.1.1.3.0", "ifHCInOctets"]
+								}
+								type: {
+									description: "The type of metric emitted for an object."
+									required:    false
+									type: string: {
+										default: "auto"
+										enum: {
+											auto:    "A counter for `Counter32` and `Counter64` values, and a gauge for all others."
+											counter: "A counter."
+											gauge:   "A gauge."
+										}
+									}
+								}
+							}
+						}
+						tags: {
+							description: """
+																						The columns whose values are added as tags to the metrics of the same row.
+
+																						Columns of other tables sharing the same index, such as `ifName` from `ifXTable` for
+																						rows of `ifTable`, can be used.
+																						"""
+							required: false
+							type: array: {
+								default: []
+								items: type: object: options: {
+									name: {
+										description: "The name of the tag."
+										required:    true
+										type: string: examples: ["interface"]
+									}
+									oid: {
+										description: "The OID of the column, either numeric or as an object name."
+										required:    true
+										type: string: examples: ["ifName"]
+									}
+								}
+							}
+						}
+					}
+				}
+			}
+			user: {
+				description: "The user used for SNMPv3 requests."
+				required:    false
+				type: object: options: {
+					auth_password: {
+						description: "The authentication password."
+						required:    false
+						type: string: examples: ["${SNMP_AUTH_PASSWORD}", "SECRET[secret_backend.snmp_auth]"]
+					}
+					auth_protocol: {
+						description: "The authentication protocol."
+						required:    false
+						type: string: enum: {
+							md5:    "HMAC-MD5-96."
+							sha:    "HMAC-SHA-96."
+							sha224: "HMAC-SHA-224 with a 128-bit digest."
+							sha256: "HMAC-SHA-256 with a 192-bit digest."
+							sha384: "HMAC-SHA-384 with a 256-bit digest."
+							sha512: "HMAC-SHA-512 with a 384-bit digest."
+						}
+					}
+					privacy_password: {
+						description: "The privacy password."
+						required:    false
+						type: string: examples: ["${SNMP_PRIVACY_PASSWORD}", "SECRET[secret_backend.snmp_privacy]"]
+					}
+					privacy_protocol: {
+						description: """
+																			The privacy protocol.
+
+																			Requires `auth_protocol` to be set.
+																			"""
+						required: false
+						type: string: enum: {
+							aes: "CFB128-AES-128."
+							des: "CBC-DES."
+						}
+					}
+					user_name: {
+						description: "The name of the user."
+						required:    true
+						type: string: examples: ["monitor"]
+					}
+				}
+			}
+			version: {
+				description: "The SNMP version used to poll an agent."
+				required:    false
+				type: string: {
+					default: "2c"
+					enum: {
+						"2c": "SNMPv2c, using a community for authentication."
+						"3":  "SNMPv3, using the user-based security model."
+					}
+				}
+			}
+		}
+	}
+	timeout_secs: {
+		description: "The timeout for each SNMP request."
+		required:    false
+		type: uint: {
+			default: 5
+			unit:    "seconds"
+		}
+	}
+}
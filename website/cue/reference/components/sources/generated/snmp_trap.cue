@@ -0,0 +1,134 @@
+package metadata
+
+generated: components: sources: snmp_trap: configuration: {
+	address: {
+		description: """
+			The socket address to listen for connections on, or `systemd{#N}` to use the Nth socket passed by
+			systemd socket activation.
+
+			If a socket address is used, it _must_ include a port.
+			"""
+		required: true
+		type: string: examples: ["0.0.0.0:9000", "systemd", "systemd#3"]
+	}
+	communities: {
+		description: """
+			The communities accepted from SNMPv1 and SNMPv2c agents.
+
+			If empty, messages with any community are accepted.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["public", "SECRET[secret_backend.snmp_community]"]
+		}
+	}
+	engine_id: {
+		description: """
+			The SNMP engine ID of this receiver, as a hex string.
+
+			SNMPv3 agents sending informs authenticate against this engine ID. If not set, a random
+			engine ID is generated on startup, which requires agents to rediscover it after each
+			restart.
+			"""
+		required: false
+		type: string: examples: ["80001f8880e9630000d61ff449"]
+	}
+	host_key: {
+		description: """
+			Overrides the name of the log field used to add the agent's address to each event.
+
+			By default, the [global `log_schema.host_key` option][global_host_key] is used.
+
+			Set to `""` to suppress this key.
+
+			[global_host_key]: https://vector.dev/docs/reference/configuration/global-options/#log_schema.host_key
+			"""
+		required: false
+		type: string: {}
+	}
+	max_length: {
+		description: """
+			The maximum size of an incoming datagram.
+
+			Datagrams larger than this are truncated, and are likely to fail decoding.
+			"""
+		required: false
+		type: uint: {
+			default: 65535
+			unit:    "bytes"
+		}
+	}
+	mib_files: {
+		description: """
+			A list of files mapping OIDs to object names.
+
+			Each line contains an object name and its OID, as produced by `snmptranslate -Tz -On`
+			for a set of MIBs. These names are used to populate the `name` of variable bindings
+			and the `trap_name` of notifications. Common objects from SNMPv2-MIB and IF-MIB are
+			always known.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["/etc/vector/mibs/oids.txt"]
+		}
+	}
+	receive_buffer_bytes: {
+		description: "The size of the receive buffer used for the listening socket."
+		required:    false
+		type: uint: unit: "bytes"
+	}
+	users: {
+		description: """
+			The users accepted from SNMPv3 agents.
+
+			SNMPv3 messages from users that are not listed here are discarded.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: object: options: {
+				auth_password: {
+					description: "The authentication password."
+					required:    false
+					type: string: examples: ["${SNMP_AUTH_PASSWORD}", "SECRET[secret_backend.snmp_auth]"]
+				}
+				auth_protocol: {
+					description: "The authentication protocol."
+					required:    false
+					type: string: enum: {
+						md5:    "HMAC-MD5-96."
+						sha:    "HMAC-SHA-96."
+						sha224: "HMAC-SHA-224 with a 128-bit digest."
+						sha256: "HMAC-SHA-256 with a 192-bit digest."
+						sha384: "HMAC-SHA-384 with a 256-bit digest."
+						sha512: "HMAC-SHA-512 with a 384-bit digest."
+					}
+				}
+				privacy_password: {
+					description: "The privacy password."
+					required:    false
+					type: string: examples: ["${SNMP_PRIVACY_PASSWORD}", "SECRET[secret_backend.snmp_privacy]"]
+				}
+				privacy_protocol: {
+					description: """
+						The privacy protocol.
+
+						Requires `auth_protocol` to be set.
+						"""
+					required: false
+					type: string: enum: {
+						aes: "CFB128-AES-128."
+						des: "CBC-DES."
+					}
+				}
+				user_name: {
+					description: "The name of the user."
+					required:    true
+					type: string: examples: ["monitor"]
+				}
+			}
+		}
+	}
+}
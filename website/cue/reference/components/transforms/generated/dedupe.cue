@@ -4,10 +4,79 @@ generated: components: transforms: dedupe: configuration: {
 	cache: {
 		description: "Caching configuration for deduplication."
 		required:    false
-		type: object: options: num_events: {
-			description: "Number of events to cache and use for comparing incoming events to previously seen events."
-			required:    false
-			type: uint: default: 5000
+		type: object: options: {
+			fingerprint: {
+				description: """
+					Whether to cache a 64-bit fingerprint of the matched fields instead of their values.
+
+					This keeps the cache small regardless of the size of the matched fields, at the cost of
+					a negligible chance of two different events being considered duplicates.
+					"""
+				required: false
+				type: bool: default: false
+			}
+			max_bytes: {
+				description: """
+					The maximum size of the cache, in bytes.
+
+					The size of the cache is estimated from the size of the matched field values. The least
+					recently seen events are evicted once the cache grows over this size.
+
+					By default, the cache is only bounded by `num_events`.
+					"""
+				required: false
+				type: uint: examples: [10485760]
+			}
+			num_events: {
+				description: "Number of events to cache and use for comparing incoming events to previously seen events."
+				required:    false
+				type: uint: default: 5000
+			}
+			persistence: {
+				description: """
+					Configuration for persisting the cache across restarts.
+
+					When set, snapshots of the cache are written to the data directory, and the cache is
+					restored from its latest snapshot on startup, so that events redelivered after a restart
+					are still deduplicated. Persisted caches always hold fingerprints of the matched fields.
+					"""
+				required: false
+				type: object: options: {
+					data_dir: {
+						description: """
+																The directory used to persist the snapshots.
+
+																By default, the [global `data_dir` option][global_data_dir] is used. Make sure the running
+																user has write permissions to this directory.
+
+																[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+																"""
+						required: false
+						type: string: examples: ["/var/lib/vector"]
+					}
+					snapshot_interval_secs: {
+						description: """
+																The interval between snapshots, in seconds.
+
+																A last snapshot is also written on shutdown.
+																"""
+						required: false
+						type: uint: default: 60
+					}
+				}
+			}
+			ttl_secs: {
+				description: """
+					The time-to-live of cached events, in seconds.
+
+					When set, events are only dropped if a duplicate was seen within this period. Seeing a
+					duplicate again restarts the period.
+
+					By default, events stay cached until they are evicted by newer events.
+					"""
+				required: false
+				type: uint: examples: [600]
+			}
 		}
 	}
 	fields: {
@@ -0,0 +1,104 @@
+package metadata
+
+generated: components: transforms: log_clustering: configuration: {
+	depth: {
+		description: """
+			The depth of the parse tree, which routes messages by their first `depth - 2` tokens.
+
+			Must be at least 3.
+			"""
+		required: false
+		type: uint: default: 4
+	}
+	field: {
+		description: """
+			The field holding the message to cluster.
+
+			By default, the message of the event is used. Events without a string in this field are
+			forwarded without a pattern.
+			"""
+		required: false
+		type: string: examples: ["message"]
+	}
+	max_children: {
+		description: """
+			The maximum number of children of a node of the parse tree.
+
+			Tokens of nodes with this many children are routed to the wildcard child.
+			"""
+		required: false
+		type: uint: default: 100
+	}
+	max_clusters: {
+		description: """
+			The maximum number of patterns kept.
+
+			When this limit is reached, the least recently matched pattern is forgotten.
+			"""
+		required: false
+		type: uint: default: 1000
+	}
+	metrics_interval_secs: {
+		description: """
+			The interval between the emission of pattern counts, in seconds.
+
+			When set, an incremental `log_pattern_events` counter is emitted for each pattern matched
+			during the interval, tagged with the `pattern_id` and `template` of the pattern, alongside the
+			log events.
+			"""
+		required: false
+		type: uint: {}
+	}
+	persistence: {
+		description: """
+			Configuration for persisting the learned patterns across restarts.
+
+			When set, snapshots of the patterns are written to the data directory, and the patterns are
+			restored from their latest snapshot on startup.
+			"""
+		required: false
+		type: object: options: {
+			data_dir: {
+				description: """
+					The directory used to persist the snapshots.
+
+					By default, the [global `data_dir` option][global_data_dir] is used. Make sure the running
+					user has write permissions to this directory.
+
+					[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+					"""
+				required: false
+				type: string: examples: ["/var/lib/vector"]
+			}
+			snapshot_interval_secs: {
+				description: """
+					The interval between snapshots, in seconds.
+
+					A last snapshot is also written on shutdown.
+					"""
+				required: false
+				type: uint: default: 60
+			}
+		}
+	}
+	similarity_threshold: {
+		description: "The share of tokens a message must have in common with a template to match it."
+		required:    false
+		type: float: default: 0.4
+	}
+	target_field: {
+		description: """
+			The field the pattern of the message is written to.
+
+			The pattern is an object with the `id` of the pattern, its `template`, in which the variable
+			tokens are replaced with `<*>`, and the `variables` of the message, the tokens matching the
+			`<*>` of the template. The ID of a pattern is derived from the first message of the pattern,
+			and stays the same as the template is generalized.
+			"""
+		required: false
+		type: string: {
+			default: "pattern"
+			examples: ["pattern"]
+		}
+	}
+}
@@ -0,0 +1,75 @@
+package metadata
+
+generated: components: transforms: pii_redaction: configuration: {
+	action: {
+		description: "What to do with the sensitive values found."
+		required:    false
+		type: string: {
+			default: "mask"
+			enum: {
+				drop: "Removes the fields holding sensitive values."
+				hash: """
+					Replaces the sensitive values with their keyed hash, so that values can still be joined
+					on without being revealed.
+
+					Values are replaced with `[<detector>:<hash>]`, where the hash is the first 16 bytes of the
+					HMAC-SHA256 of the value with `hash_key`, hex-encoded.
+					"""
+				mask: "Replaces the sensitive values with `mask`."
+			}
+		}
+	}
+	detectors: {
+		description: """
+			The detectors of sensitive values to run.
+
+			By default, all detectors are run.
+			"""
+		required: false
+		type: array: {
+			default: ["credit_card", "email", "ipv4", "ipv6", "iban", "jwt", "aws_key", "api_token"]
+			items: type: string: enum: {
+				api_token: "GitHub, GitLab, Slack, Stripe and Google API tokens."
+				aws_key:   "AWS access key IDs."
+				credit_card: """
+					Credit card numbers, with 13 to 19 digits optionally separated by spaces or dashes, passing
+					the Luhn check.
+					"""
+				email: "Email addresses."
+				iban:  "International bank account numbers, passing the MOD-97 check."
+				ipv4:  "IPv4 addresses."
+				ipv6:  "IPv6 addresses."
+				jwt:   "JSON Web Tokens."
+			}
+		}
+	}
+	fields: {
+		description: """
+			The fields to scan.
+
+			By default, all string fields of the event are scanned. The objects and arrays in these
+			fields are scanned recursively.
+			"""
+		required: false
+		type: array: {
+			default: []
+			items: type: string: examples: ["message", "user.address"]
+		}
+	}
+	hash_key: {
+		description: """
+			The key of the HMAC of sensitive values with the `hash` action.
+
+			Use a [secret][secrets] to keep the key out of the configuration.
+
+			[secrets]: https://vector.dev/docs/reference/configuration/global-options/#secret
+			"""
+		required: false
+		type: string: examples: ["SECRET[backend.pii_hash_key]"]
+	}
+	mask: {
+		description: "The text replacing sensitive values with the `mask` action."
+		required:    false
+		type: string: default: "[REDACTED]"
+	}
+}
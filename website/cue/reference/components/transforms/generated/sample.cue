@@ -71,4 +71,26 @@ generated: components: transforms: sample: configuration: {
 			examples: ["sample_rate"]
 		}
 	}
+	target_events_per_sec: {
+		description: """
+			The total number of events per second to forward, sampling events dynamically.
+
+			The events of each `group_by` bucket are counted over `target_window_secs`, and this target is
+			then shared between the buckets for the next window. Rare buckets are forwarded in full, while
+			the buckets with the most events are sampled the hardest. The effective rate of the bucket of
+			each forwarded event, expressed as `N` for `1/N`, is stored in `sample_rate_key` so that counts
+			can be re-weighted downstream. It is an error to provide a value for both this and `rate` or
+			`ratio`.
+			"""
+		required: false
+		type: float: examples: [100.0]
+	}
+	target_window_secs: {
+		description: "The window over which events are counted to adjust the rates of dynamic sampling, in seconds."
+		required:    false
+		type: float: {
+			default: 10.0
+			unit:    "seconds"
+		}
+	}
 }
@@ -0,0 +1,112 @@
+package metadata
+
+generated: components: transforms: tail_sampling: configuration: {
+	decide_on_root_span: {
+		description: """
+			Whether to decide on a trace as soon as its root span is received.
+
+			Root spans are the spans without a parent, and usually end last. When disabled, traces are
+			always buffered for `decision_wait_secs`.
+			"""
+		required: false
+		type: bool: default: true
+	}
+	decision_cache_size: {
+		description: """
+			The number of recent decisions remembered to handle spans received after their trace was
+			decided on.
+
+			Late spans of a kept trace are forwarded, and late spans of a dropped trace are dropped. Late
+			spans of traces no longer remembered start a new trace.
+			"""
+		required: false
+		type: uint: default: 50000
+	}
+	decision_wait_secs: {
+		description: """
+			The maximum time to wait for the spans of a trace after receiving its first span, in seconds.
+
+			The trace is decided on with the spans received so far once this time has elapsed.
+			"""
+		required: false
+		type: float: {
+			default: 30.0
+			unit:    "seconds"
+		}
+	}
+	max_bytes: {
+		description: """
+			The maximum size of the buffered events, in bytes.
+
+			When this limit is reached, the oldest traces are decided on early with the spans received so far.
+			"""
+		required: false
+		type: uint: unit: "bytes"
+	}
+	max_traces: {
+		description: """
+			The maximum number of traces buffered while waiting for their spans.
+
+			When this limit is reached, the oldest trace is decided on early with the spans received so far.
+			"""
+		required: false
+		type: uint: default: 10000
+	}
+	policies: {
+		description: """
+			The policies deciding which traces are kept.
+
+			A trace is kept if any of the policies samples it, and dropped otherwise. Policies are
+			evaluated in order, and the first one sampling a trace is reported in the metrics.
+			"""
+		required: true
+		type: array: items: type: object: options: {
+			condition: {
+				description: """
+					The condition matched against the events of the trace.
+
+					Datadog traces are received as events holding a chunk of spans, while OpenTelemetry traces
+					are received as an event per span.
+					"""
+				relevant_when: "type = \"condition\""
+				required:      true
+				type: condition: {}
+			}
+			ratio: {
+				description:   "The ratio of traces sampled."
+				relevant_when: "type = \"probabilistic\""
+				required:      true
+				type: float: examples: [0.1]
+			}
+			threshold_ms: {
+				description:   "The duration above which traces are sampled, in milliseconds."
+				relevant_when: "type = \"latency\""
+				required:      true
+				type: uint: examples: [5000]
+			}
+			type: {
+				description: "The type of policy."
+				required:    true
+				type: string: enum: {
+					condition: "Samples traces with at least one event matching a condition."
+					error: """
+						Samples traces with at least one span in error.
+
+						Datadog spans are in error when their `error` field is non-zero, and OpenTelemetry spans when
+						their status code is `2`.
+						"""
+					latency: """
+						Samples traces lasting longer than a threshold, from the start of their first span to the end
+						of their last span.
+						"""
+					probabilistic: """
+						Samples a ratio of traces, chosen by trace ID.
+
+						The choice only depends on the trace ID, so that all instances of this transform make the
+						same decision for a trace.
+						"""
+				}
+			}
+		}
+	}
+}
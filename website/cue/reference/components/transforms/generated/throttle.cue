@@ -6,6 +6,31 @@ generated: components: transforms: throttle: configuration: {
 		required:    false
 		type: condition: {}
 	}
+	global: {
+		description: """
+			A quota shared by all keys, applied on top of the quota of each key.
+
+			Events within the quota of their key are still throttled once this quota is exceeded.
+			Events throttled by this quota count towards the quota of their key.
+			"""
+		required: false
+		type: object: options: {
+			threshold: {
+				description: "The number of events allowed per configured `window_secs`."
+				required:    false
+				type: uint: examples: [1000]
+			}
+			threshold_bytes: {
+				description: """
+					The number of bytes allowed per configured `window_secs`.
+
+					The size of an event is its estimated JSON-encoded size.
+					"""
+				required: false
+				type: uint: examples: [1048576]
+			}
+		}
+	}
 	internal_metrics: {
 		description: "Configuration of internal metrics for the Throttle transform."
 		required:    false
@@ -38,6 +63,45 @@ generated: components: transforms: throttle: configuration: {
 			syntax: "template"
 		}
 	}
+	key_thresholds: {
+		description: """
+			Thresholds of specific keys, replacing `threshold` and `threshold_bytes` for these keys.
+
+			Thresholds left unset keep their default value. These take precedence over the thresholds
+			found in the `thresholds_table`.
+			"""
+		required: false
+		type: object: {
+			examples: [{
+				premium: threshold: 10000
+			}]
+			options: "*": {
+				description: "The thresholds of a key."
+				required:    true
+				type: object: options: {
+					threshold: {
+						description: "The number of events allowed per configured `window_secs`."
+						required:    false
+						type: uint: examples: [1000]
+					}
+					threshold_bytes: {
+						description: """
+																The number of bytes allowed per configured `window_secs`.
+
+																The size of an event is its estimated JSON-encoded size.
+																"""
+						required: false
+						type: uint: examples: [1048576]
+					}
+				}
+			}
+		}
+	}
+	reroute_dropped: {
+		description: "Whether to send throttled events to the `dropped` output instead of discarding them."
+		required:    false
+		type: bool: default: false
+	}
 	threshold: {
 		description: """
 			The number of events allowed for a given bucket per configured `window_secs`.
@@ -47,6 +111,40 @@ generated: components: transforms: throttle: configuration: {
 		required: true
 		type: uint: {}
 	}
+	threshold_bytes: {
+		description: """
+			The number of bytes allowed for a given bucket per configured `window_secs`.
+
+			The size of an event is its estimated JSON-encoded size. Events are only allowed if they are
+			within both `threshold` and `threshold_bytes`.
+			"""
+		required: false
+		type: uint: examples: [1048576]
+	}
+	thresholds_table: {
+		description: "An enrichment table holding the thresholds of keys."
+		required:    false
+		type: object: options: {
+			key_column: {
+				description: "The column of the enrichment table holding the keys."
+				required:    false
+				type: string: {
+					default: "key"
+					examples: ["tenant"]
+				}
+			}
+			table: {
+				description: """
+					The name of the enrichment table.
+
+					The `threshold` and `threshold_bytes` columns of the row found for a key replace the
+					thresholds of that key. Missing or empty columns leave the corresponding threshold unchanged.
+					"""
+				required: true
+				type: string: examples: ["tenant_tiers"]
+			}
+		}
+	}
 	window_secs: {
 		description: "The time window in which the configured `threshold` is applied, in seconds."
 		required:    true
@@ -9,8 +9,9 @@ generated: configuration: configuration: {
 						type: object: options: {
 							delimiter: {
 								type: string: default: ","
-								description: "The delimiter used to separate fields in each row of the CSV file."
-								required:    false
+								description:   "The delimiter used to separate fields in each row of the CSV file."
+								required:      false
+								relevant_when: "type = \"csv\""
 							}
 							include_headers: {
 								type: bool: default: true
@@ -22,15 +23,41 @@ generated: configuration: configuration: {
 
 																		When set to `false`, columns are referred to by their numerical index.
 																		"""
-								required: false
+								required:      false
+								relevant_when: "type = \"csv\""
 							}
 							type: {
 								required: true
-								type: string: enum: csv: """
+								type: string: enum: {
+									csv: """
 																					Decodes the file as a [CSV][csv] (comma-separated values) file.
 
 																					[csv]: https://wikipedia.org/wiki/Comma-separated_values
 																					"""
+									json: """
+																					Decodes the file as a [JSON][json] array of objects, each object being a row.
+
+																					The columns are the keys found in the objects. Rows missing a key have a `null` value in
+																					its column.
+
+																					[json]: https://www.json.org/
+																					"""
+									ndjson: """
+																					Decodes the file as [newline-delimited JSON][ndjson], each line holding the object of a row.
+
+																					The columns are the keys found in the objects. Rows missing a key have a `null` value in
+																					its column.
+
+																					[ndjson]: https://github.com/ndjson/ndjson-spec
+																					"""
+									parquet: """
+																					Decodes the file as an [Apache Parquet][parquet] file.
+
+																					The columns are the top-level fields of the file schema.
+
+																					[parquet]: https://parquet.apache.org/
+																					"""
+								}
 								description: "File encoding type."
 							}
 						}
@@ -38,15 +65,36 @@ generated: configuration: configuration: {
 						required:    true
 					}
 					path: {
-						type: string: {}
+						type: string: examples: ["/etc/vector/table.csv"]
 						description: """
 														The path of the enrichment table file.
 
-														Currently, only [CSV][csv] files are supported.
+														The file is reloaded when its modification time changes and the configuration is reloaded.
 
-														[csv]: https://en.wikipedia.org/wiki/Comma-separated_values
+														Exactly one of `path` and `url` must be set.
 														"""
-						required: true
+						required: false
+					}
+					refresh_interval_secs: {
+						type: uint: {
+							default: 300
+							unit:    "seconds"
+						}
+						description: "The interval between refreshes of a file fetched from a `url`, in seconds."
+						required:    false
+					}
+					url: {
+						type: string: examples: ["https://example.com/enrichment/table.csv"]
+						description: """
+														The HTTP(S) URL of the enrichment table file.
+
+														The file is fetched again every `refresh_interval_secs`. Its `ETag` is sent in the
+														`If-None-Match` header, so it is only downloaded again once it changed. Lookups keep using
+														the previous data until the new data is completely loaded.
+
+														Exactly one of `path` and `url` must be set.
+														"""
+						required: false
 					}
 				}
 				description:   "File-specific settings."
@@ -64,6 +112,8 @@ generated: configuration: configuration: {
 
 					This is used to coerce log fields from strings into their proper types. The available types are listed in the `Types` list below.
 
+					Values that are already typed, like the numbers and booleans of JSON or Parquet files, are coerced from their string representation.
+
 					Timestamp coercions need to be prefaced with `timestamp|`, for example `"timestamp|%F"`. Timestamp specifiers can use either of the following:
 
 					1. One of the built-in-formats listed in the `Timestamp Formats` table below.
@@ -147,6 +197,49 @@ generated: configuration: configuration: {
 				required:      false
 				relevant_when: "type = \"memory\""
 			}
+			persistence: {
+				type: object: options: {
+					data_dir: {
+						type: string: examples: ["/var/lib/vector"]
+						description: """
+														The directory used to persist the snapshots.
+
+														By default, the [global `data_dir` option][global_data_dir] is used. Make sure the running
+														user has write permissions to this directory.
+
+														[global_data_dir]: https://vector.dev/docs/reference/configuration/global-options/#data_dir
+														"""
+						required: false
+					}
+					name: {
+						type: string: examples: ["sessions"]
+						description: """
+														The name of the snapshot of the table.
+
+														Snapshots are written to the `memory_enrichment_table` subdirectory of the data directory,
+														so this name must be unique among the memory enrichment tables sharing a data directory.
+														"""
+						required: true
+					}
+					snapshot_interval_secs: {
+						type: uint: default: 60
+						description: """
+														The interval between snapshots, in seconds.
+
+														A last snapshot is also written on shutdown.
+														"""
+						required: false
+					}
+				}
+				description: """
+					Configuration for persisting the table across restarts.
+
+					When set, snapshots of the table are written to the data directory, and the table is
+					restored from its latest snapshot on startup.
+					"""
+				required:      false
+				relevant_when: "type = \"memory\""
+			}
 			scan_interval: {
 				type: uint: default: 30
 				description: """
@@ -157,6 +250,19 @@ generated: configuration: configuration: {
 				required:      false
 				relevant_when: "type = \"memory\""
 			}
+			seed_path: {
+				type: string: examples: ["/var/lib/vector/seed.ndjson"]
+				description: """
+					The path of a file seeding the table on startup.
+
+					The file holds a JSON object per line, with the `key` and `value` of an entry, and
+					optionally its remaining `ttl`. This is the shape of the events exported by the table when
+					used as a source, so the exported data of a table can be written to a file and used to
+					seed it. The seed file is ignored when the table is restored from a snapshot.
+					"""
+				required:      false
+				relevant_when: "type = \"memory\""
+			}
 			source_config: {
 				type: object: options: {
 					export_batch_size: {
@@ -239,6 +345,141 @@ generated: configuration: configuration: {
 				required:      true
 				relevant_when: "type = \"geoip\" or type = \"mmdb\""
 			}
+			cache_size: {
+				type: uint: default: 10000
+				description: """
+					The maximum number of lookup results kept in the local cache.
+
+					Set to `0` to disable the cache.
+					"""
+				required:      false
+				relevant_when: "type = \"redis\" or type = \"sql\""
+			}
+			cache_ttl_secs: {
+				type: uint: {
+					default: 60
+					unit:    "seconds"
+				}
+				description:   "How long the rows found by a lookup are cached, in seconds."
+				required:      false
+				relevant_when: "type = \"redis\" or type = \"sql\""
+			}
+			data_type: {
+				type: string: {
+					default: "hash"
+					enum: {
+						hash:   "Each row is a hash, whose fields are the fields of the row."
+						string: "Each row is a string value, found in the `value` field of the row."
+					}
+				}
+				description:   "How the rows are stored in Redis."
+				required:      false
+				relevant_when: "type = \"redis\""
+			}
+			endpoint: {
+				type: string: examples: ["redis://127.0.0.1:6379/0"]
+				description: """
+					The URL of the Redis endpoint to connect to.
+
+					The URL _must_ take the form of `protocol://server:port/db` where the protocol can either be
+					`redis` or `rediss` for connections secured via TLS.
+					"""
+				required:      true
+				relevant_when: "type = \"redis\" or type = \"sql\""
+			}
+			failure_backoff_secs: {
+				type: uint: {
+					default: 5
+					unit:    "seconds"
+				}
+				description: """
+					How long lookups are suspended, in seconds, once several lookups in a row time out or fail.
+
+					Suspended lookups find no rows without querying the database, so that an unresponsive
+					database doesn't delay every event by the lookup timeout. The next lookup once this
+					elapses queries the database again.
+					"""
+				required:      false
+				relevant_when: "type = \"redis\" or type = \"sql\""
+			}
+			key_field: {
+				type: string: {
+					default: "key"
+					examples: ["user_id"]
+				}
+				description: """
+					The field of the lookup conditions holding the key of the row.
+
+					Lookups need an equality condition on this field, and the key is added to the rows found
+					under this field.
+					"""
+				required:      false
+				relevant_when: "type = \"redis\""
+			}
+			key_prefix: {
+				type: string: {
+					default: ""
+					examples: ["users:"]
+				}
+				description:   "A prefix added to the keys looked up."
+				required:      false
+				relevant_when: "type = \"redis\""
+			}
+			negative_cache_ttl_secs: {
+				type: uint: {
+					default: 10
+					unit:    "seconds"
+				}
+				description: """
+					How long lookups finding no rows are cached, in seconds.
+
+					Lookups that time out or fail are not cached.
+					"""
+				required:      false
+				relevant_when: "type = \"redis\" or type = \"sql\""
+			}
+			timeout_ms: {
+				type: uint: {
+					default: 100
+					unit:    "milliseconds"
+				}
+				description: """
+					The maximum time to wait for the result of a lookup, in milliseconds.
+
+					Lookups timing out find no rows, so a slow database delays events by at most this long.
+					"""
+				required:      false
+				relevant_when: "type = \"redis\" or type = \"sql\""
+			}
+			parameters: {
+				type: array: items: type: string: examples: ["ip"]
+				description: """
+					The fields of the lookup conditions bound to the parameters of the query, in order.
+
+					Lookups need an equality condition on each of these fields.
+					"""
+				required:      true
+				relevant_when: "type = \"sql\""
+			}
+			pool_size: {
+				type: uint: default: 4
+				description:   "The maximum number of connections to the database."
+				required:      false
+				relevant_when: "type = \"sql\""
+			}
+			query: {
+				type: string: examples: ["SELECT hostname, owner, region FROM hosts WHERE ip = $1"]
+				description: """
+					The query returning the rows matching a lookup.
+
+					Its parameters, written as `$1`, `$2` and so on, are bound to the values of the lookup
+					conditions on the `parameters` fields. Every column of the query results is a field of the
+					rows found. Columns of types other than booleans, numbers, text, dates and timestamps must
+					be cast to text in the query.
+					"""
+				required:      true
+				relevant_when: "type = \"sql\""
+			}
 			type: {
 				required: true
 				type: string: enum: {
@@ -258,6 +499,12 @@ generated: configuration: configuration: {
 
 						[maxmind]: https://www.maxmind.com/
 						"""
+					redis: """
+						Looks up rows in [Redis][redis] hashes or string values by key.
+
+						[redis]: https://redis.io/
+						"""
+					sql: "Looks up rows with a query against a PostgreSQL or SQLite database."
 				}
 				description: "enrichment table type"
 			}
@@ -266,9 +513,10 @@ generated: configuration: configuration: {
 			Configuration options for an [enrichment table](https://vector.dev/docs/reference/glossary/#enrichment-tables) to be used in a
 			[`remap`](https://vector.dev/docs/reference/configuration/transforms/remap/) transform. Currently supported are:
 
-			* [CSV](https://en.wikipedia.org/wiki/Comma-separated_values) files
+			* [CSV](https://en.wikipedia.org/wiki/Comma-separated_values), JSON and Parquet files, read from disk or fetched over HTTP(S)
 			* [MaxMind](https://www.maxmind.com/en/home) databases
 			* In-memory storage
+			* Redis, PostgreSQL and SQLite databases, queried on each lookup with a local cache
 
 			For the lookup in the enrichment tables to be as performant as possible, the data is indexed according
 			to the fields that are used in the search. Note that indices can only be created for fields for which an
@@ -276,6 +524,9 @@ generated: configuration: configuration: {
 			drops back to a sequential scan of the data. A sequential scan shouldn't impact performance
 			significantly provided that there are only a few possible rows returned by the exact matches in the
 			condition. We don't recommend using a condition that uses only date range searches.
+
+			CIDR (`contains_ip`), numeric range (`to_field` and `contains`) and prefix (`prefix_of`) searches
+			use dedicated lookup structures instead, so they don't need a sequential scan of the data.
 			"""
 		common:   false
 		required: false
@@ -65,7 +65,7 @@ remap: {
 		This function returns the rows that match the provided condition(s). _All_ fields need to
 		match for rows to be returned; if any fields do not match, then no rows are returned.
 
-		There are currently three forms of search criteria:
+		There are currently six forms of search criteria:
 
 		1. **Exact match search**. The given field must match the value exactly. Case sensitivity
 		   can be specified using the `case_sensitive` argument. An exact match search can use an
@@ -83,6 +83,20 @@ remap: {
 		   match criteria. Therefore, use date ranges as the _only_ criteria when the enrichment
 		   data set is very small.
 
+		4. **CIDR search**. The given field must hold a CIDR block, such as `10.0.0.0/8`, that
+		   contains the IP address provided as `contains_ip`. Example: `{"subnet": {"contains_ip": .ip}}`.
+
+		5. **Numeric range search**. The number provided as `contains` must be greater than or equal
+		   to the given field and less than or equal to the field named by `to_field`. Example:
+		   `{"start": {"to_field": "end", "contains": .port}}`.
+
+		6. **Prefix search**. The given field must hold a prefix of the string provided as `prefix_of`.
+		   Example: `{"prefix": {"prefix_of": .phone}}`.
+
+		CIDR, numeric range, and prefix searches use lookup structures built when the table is loaded,
+		so they don't need to scan all of the rows. `memory` enrichment tables support CIDR and prefix
+		searches on their keys.
+
 		For `geoip` and `mmdb` enrichment tables, this condition needs to be a VRL object with a single key-value pair
 		whose value needs to be a valid IP address. Example: `{"ip": .ip }`. If a return field is expected
 		and without a value, `null` is used. This table can return the following fields:
@@ -89,5 +89,17 @@ remap: functions: find_enrichment_table_records: {
 				{"id": 2, "firstname": "Fred", "surname": "Smith"},
 			]
 		},
+		{
+			title: "CIDR search"
+			source: #"""
+				find_enrichment_table_records!("subnets",
+				  {
+				    "subnet": {"contains_ip": "10.1.2.3"}
+				  })
+				"""#
+			return: [{"subnet": "10.0.0.0/8", "site": "europe"},
+				{"subnet": "10.1.0.0/16", "site": "paris"},
+			]
+		},
 	]
 }
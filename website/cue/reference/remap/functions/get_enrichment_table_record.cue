@@ -90,5 +90,15 @@ remap: functions: get_enrichment_table_record: {
 				"""#
 			return: {"id": 1, "firstname": "Bob", "surname": "Smith"}
 		},
+		{
+			title: "CIDR search"
+			source: #"""
+				get_enrichment_table_record!("subnets",
+				  {
+				    "subnet": {"contains_ip": "10.1.2.3"}
+				  })
+				"""#
+			return: {"subnet": "10.1.0.0/16", "site": "paris"}
+		},
 	]
 }